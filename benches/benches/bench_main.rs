@@ -8,4 +8,7 @@ criterion_main! {
     benchmarks::secp_2in2out::process_block,
     benchmarks::overall::overall,
     benchmarks::resolve::resolve,
+    benchmarks::tx_pool_read::tx_pool_read,
+    benchmarks::tx_pool_chain::tx_pool_chain,
+    benchmarks::header_pow::header_pow,
 }
@@ -0,0 +1,56 @@
+use ckb_pow::{EaglesongPowEngine, PowEngine};
+use ckb_types::{
+    core::{HeaderBuilder, HeaderView},
+    prelude::*,
+    utilities::difficulty_to_compact,
+    U256,
+};
+use ckb_verification::BatchPowVerifier;
+use criterion::{criterion_group, BenchmarkId, Criterion};
+
+#[cfg(not(feature = "ci"))]
+const SIZES: &[usize] = &[2000];
+
+#[cfg(feature = "ci")]
+const SIZES: &[usize] = &[100];
+
+// the lowest difficulty, so every nonce is valid and all the headers are hashed
+fn gen_headers(count: usize) -> Vec<HeaderView> {
+    let compact_target = difficulty_to_compact(U256::one());
+    (0..count as u64)
+        .map(|number| {
+            HeaderBuilder::default()
+                .number(number.pack())
+                .compact_target(compact_target.pack())
+                .nonce((number as u128).pack())
+                .build()
+        })
+        .collect()
+}
+
+// Compares checking the PoW of a `SendHeaders` batch header by header with checking it by the
+// rayon workers.
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("header_pow");
+    let pow: &dyn PowEngine = &EaglesongPowEngine;
+
+    for size in SIZES.iter() {
+        let headers = gen_headers(*size);
+        group.bench_with_input(BenchmarkId::new("sequential", size), size, |b, _| {
+            b.iter(|| {
+                assert!(headers.iter().all(|header| pow.verify(&header.data())));
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), size, |b, _| {
+            b.iter(|| {
+                assert_eq!(BatchPowVerifier::new(&headers, pow).verify(), headers.len());
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = header_pow;
+    config = Criterion::default().sample_size(10);
+    targets = bench
+);
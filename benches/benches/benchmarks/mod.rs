@@ -1,5 +1,8 @@
 pub mod always_success;
+pub mod header_pow;
 pub mod overall;
 pub mod resolve;
 pub mod secp_2in2out;
+pub mod tx_pool_chain;
+pub mod tx_pool_read;
 pub mod util;
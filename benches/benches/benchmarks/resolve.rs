@@ -5,6 +5,7 @@ use ckb_chain_spec::{ChainSpec, IssuedCell};
 use ckb_jsonrpc_types::JsonBytes;
 use ckb_resource::Resource;
 use ckb_shared::{Shared, SharedBuilder, Snapshot};
+use ckb_store::ChainStore;
 use ckb_types::global::DATA_DIR;
 use ckb_types::{
     bytes::Bytes,
@@ -181,6 +182,29 @@ fn bench(c: &mut Criterion) {
             )
         },
     );
+
+    let (shared, _) = setup_chain(SIZE);
+    let out_points = shared
+        .consensus()
+        .genesis_block()
+        .transaction(0)
+        .unwrap()
+        .output_pts();
+
+    group.bench_with_input(BenchmarkId::new("get_cell", SIZE), &SIZE, |b, _| {
+        let snapshot: &Snapshot = &shared.snapshot();
+        b.iter(|| {
+            out_points
+                .iter()
+                .map(|out_point| snapshot.get_cell(out_point))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_with_input(BenchmarkId::new("get_cells", SIZE), &SIZE, |b, _| {
+        let snapshot: &Snapshot = &shared.snapshot();
+        b.iter(|| snapshot.get_cells(&out_points))
+    });
 }
 
 criterion_group!(
@@ -0,0 +1,89 @@
+use crate::benchmarks::overall::{gen_txs_from_block, setup_chain};
+use crate::benchmarks::util::{create_2out_transaction, create_secp_tx, secp_cell};
+use ckb_types::{
+    core::TransactionView,
+    packed::{CellDep, OutPoint},
+    prelude::*,
+};
+use criterion::{criterion_group, BenchmarkId, Criterion};
+
+#[cfg(not(feature = "ci"))]
+const DEPTHS: &[usize] = &[100];
+
+#[cfg(feature = "ci")]
+const DEPTHS: &[usize] = &[10usize];
+
+// Builds a chain of `depth` transactions, each one spends the outputs of the previous one.
+fn gen_chained_txs(first: TransactionView, depth: usize) -> Vec<TransactionView> {
+    let secp_tx = create_secp_tx();
+    let secp_cell_deps = vec![
+        CellDep::new_builder()
+            .out_point(OutPoint::new(secp_tx.hash(), 0))
+            .build(),
+        CellDep::new_builder()
+            .out_point(OutPoint::new(secp_tx.hash(), 1))
+            .build(),
+    ];
+    let (_, _, secp_script) = secp_cell();
+
+    let mut txs = vec![first];
+    while txs.len() < depth {
+        let parent = txs.last().expect("not empty");
+        let tx = create_2out_transaction(
+            parent.output_pts(),
+            secp_script.clone(),
+            secp_cell_deps.clone(),
+        );
+        txs.push(tx);
+    }
+    txs
+}
+
+// Measures the ancestors and descendants queries on a deep chain of in-pool transactions, which
+// are answered from the memoized sets unless the chain changes.
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tx_pool_chain");
+
+    for depth in DEPTHS.iter() {
+        let (shared, _chain) = setup_chain(1);
+        let tx_pool = shared.tx_pool_controller().clone();
+        let first = gen_txs_from_block(shared.consensus().genesis_block())
+            .pop()
+            .expect("genesis tx");
+        let mut txs = gen_chained_txs(first, *depth);
+        for tx in &txs {
+            tx_pool
+                .submit_local_tx(tx.clone())
+                .unwrap()
+                .expect("submit_tx");
+        }
+        let root = txs.first().expect("root").hash();
+        let tip = txs.pop().expect("tip");
+
+        group.bench_with_input(BenchmarkId::new("root_detail", depth), depth, |b, _| {
+            b.iter(|| tx_pool.get_tx_detail(root.clone()).expect("get_tx_detail"))
+        });
+
+        group.bench_with_input(BenchmarkId::new("tip_detail", depth), depth, |b, _| {
+            b.iter(|| tx_pool.get_tx_detail(tip.hash()).expect("get_tx_detail"))
+        });
+
+        // every round invalidates the sets of the whole chain
+        group.bench_with_input(BenchmarkId::new("churn_tip", depth), depth, |b, _| {
+            b.iter(|| {
+                tx_pool.remove_local_tx(tip.hash()).expect("remove_tx");
+                tx_pool
+                    .submit_local_tx(tip.clone())
+                    .unwrap()
+                    .expect("submit_tx");
+                tx_pool.get_tx_detail(root.clone()).expect("get_tx_detail")
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = tx_pool_chain;
+    config = Criterion::default().sample_size(10);
+    targets = bench
+);
@@ -0,0 +1,118 @@
+use crate::benchmarks::overall::{gen_txs_from_block, setup_chain};
+use ckb_app_config::TxPoolConfig;
+use ckb_tx_pool::{TxEntry, TxPool};
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, TransactionBuilder},
+    prelude::*,
+};
+use criterion::{criterion_group, BenchmarkId, Criterion};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(not(feature = "ci"))]
+const SIZES: &[usize] = &[500];
+
+#[cfg(feature = "ci")]
+const SIZES: &[usize] = &[10usize];
+
+#[cfg(not(feature = "ci"))]
+const POOL_SIZES: &[usize] = &[20_000];
+
+#[cfg(feature = "ci")]
+const POOL_SIZES: &[usize] = &[100usize];
+
+const PAGE_SIZE: usize = 1000;
+
+// Measures the latency of the `get_raw_tx_pool` reads, with and without a concurrent writer
+// which keeps submitting and removing transactions.
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tx_pool_read");
+
+    for txs_size in SIZES.iter() {
+        let (shared, _chain) = setup_chain(*txs_size);
+        let tx_pool = shared.tx_pool_controller().clone();
+        let mut txs = gen_txs_from_block(shared.consensus().genesis_block());
+        // half of the txs stay in the pool, the other half is churned by the writer
+        let churn_txs = txs.split_off(txs.len() / 2);
+        for tx in txs {
+            tx_pool.submit_local_tx(tx).unwrap().expect("submit_tx");
+        }
+
+        group.bench_with_input(BenchmarkId::new("idle", txs_size), txs_size, |b, _| {
+            b.iter(|| tx_pool.get_all_entry_info().expect("get_all_entry_info"))
+        });
+        group.bench_with_input(BenchmarkId::new("ids_idle", txs_size), txs_size, |b, _| {
+            b.iter(|| tx_pool.get_all_ids().expect("get_all_ids"))
+        });
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let writer = {
+            let stop = Arc::clone(&stop);
+            let tx_pool = tx_pool.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    for tx in churn_txs.iter() {
+                        let _ = tx_pool.submit_local_tx(tx.clone());
+                    }
+                    for tx in churn_txs.iter() {
+                        let _ = tx_pool.remove_local_tx(tx.hash());
+                    }
+                }
+            })
+        };
+
+        group.bench_with_input(
+            BenchmarkId::new("under_write_load", txs_size),
+            txs_size,
+            |b, _| b.iter(|| tx_pool.get_all_entry_info().expect("get_all_entry_info")),
+        );
+
+        stop.store(true, Ordering::Relaxed);
+        writer.join().expect("join writer");
+    }
+}
+
+// Compares walking through the pool page by page with collecting all the ids at once.
+fn bench_pages(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tx_pool_read_pages");
+
+    for pool_size in POOL_SIZES.iter() {
+        let (shared, _chain) = setup_chain(1);
+        let mut tx_pool = TxPool::new(TxPoolConfig::default(), Arc::clone(&shared.snapshot()));
+        for i in 0..*pool_size as u64 {
+            let tx = TransactionBuilder::default()
+                .output_data(Bytes::from(i.to_le_bytes().to_vec()).pack())
+                .build();
+            let entry = TxEntry::dummy_resolve(tx, 100, Capacity::shannons(i), 100);
+            tx_pool.plug_pending(entry).expect("plug_pending");
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("collect_all", pool_size),
+            pool_size,
+            |b, _| b.iter(|| tx_pool.ids_page(false, None, usize::MAX)),
+        );
+        group.bench_with_input(BenchmarkId::new("pages", pool_size), pool_size, |b, _| {
+            b.iter(|| {
+                let mut ids = Vec::with_capacity(*pool_size);
+                let mut after = None;
+                loop {
+                    let (page, next) = tx_pool.ids_page(false, after.as_ref(), PAGE_SIZE);
+                    ids.extend(page);
+                    match next {
+                        Some(next) => after = Some(next),
+                        None => break ids,
+                    }
+                }
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = tx_pool_read;
+    config = Criterion::default().sample_size(10);
+    targets = bench, bench_pages
+);
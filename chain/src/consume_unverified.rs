@@ -1,3 +1,4 @@
+use crate::observer::{BlockObservers, VerifiedBlock, VerifiedBlocksEvent};
 use crate::{delete_unverified_block, UnverifiedBlock};
 use crate::{utils::forkchanges::ForkChanges, GlobalIndex, TruncateRequest, VerifyResult};
 use ckb_channel::{select, Receiver};
@@ -9,7 +10,10 @@ use ckb_merkle_mountain_range::leaf_index_to_mmr_size;
 use ckb_proposal_table::ProposalTable;
 use ckb_shared::block_status::BlockStatus;
 use ckb_shared::Shared;
-use ckb_store::{attach_block_cell, detach_block_cell, ChainStore, StoreTransaction};
+use ckb_store::{
+    attach_block_cell, attach_block_epoch_stats, detach_block_cell, detach_block_epoch_stats,
+    ChainStore, PrefetchedCells, StoreTransaction,
+};
 use ckb_systemtime::unix_time_as_millis;
 use ckb_tx_pool::TxPoolController;
 use ckb_types::core::cell::{
@@ -27,11 +31,19 @@ use dashmap::DashSet;
 use std::cmp;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// A reorganization detaching at least this many blocks pauses the tx relay and the tx
+// verification until it settles
+const DEEP_REORG_DETACHED_BLOCKS: usize = 6;
+// The tx relay and the tx verification resume after this duration even if the blocks keep coming
+const MAX_REORG_SETTLE_DURATION: Duration = Duration::from_secs(60);
 
 pub(crate) struct ConsumeUnverifiedBlockProcessor {
     pub(crate) shared: Shared,
     pub(crate) is_pending_verify: Arc<DashSet<Byte32>>,
     pub(crate) proposal_table: ProposalTable,
+    pub(crate) block_observers: BlockObservers,
 }
 
 pub(crate) struct ConsumeUnverifiedBlocks {
@@ -42,6 +54,8 @@ pub(crate) struct ConsumeUnverifiedBlocks {
 
     stop_rx: Receiver<()>,
     processor: ConsumeUnverifiedBlockProcessor,
+    // when the verify workers were kept suspended for a deep reorganization
+    reorg_settling_since: Option<Instant>,
 }
 
 impl ConsumeUnverifiedBlocks {
@@ -52,6 +66,7 @@ impl ConsumeUnverifiedBlocks {
         proposal_table: ProposalTable,
         is_pending_verify: Arc<DashSet<Byte32>>,
         stop_rx: Receiver<()>,
+        block_observers: BlockObservers,
     ) -> Self {
         ConsumeUnverifiedBlocks {
             tx_pool_controller: shared.tx_pool_controller().to_owned(),
@@ -62,7 +77,9 @@ impl ConsumeUnverifiedBlocks {
                 shared,
                 is_pending_verify,
                 proposal_table,
+                block_observers,
             },
+            reorg_settling_since: None,
         }
     }
 
@@ -84,7 +101,7 @@ impl ConsumeUnverifiedBlocks {
                             handle.ckb_chain_consume_unverified_block_duration.observe(_trace_now.elapsed().as_secs_f64())
                         }
 
-                        let _ = self.tx_pool_controller.continue_chunk_process();
+                        self.continue_chunk_process();
                     },
                     Err(err) => {
                         error!("unverified_block_rx err: {}", err);
@@ -95,7 +112,7 @@ impl ConsumeUnverifiedBlocks {
                     Ok(Request { responder, arguments: target_tip_hash }) => {
                         let _ = self.tx_pool_controller.suspend_chunk_process();
                         let _ = responder.send(self.processor.truncate(&target_tip_hash));
-                        let _ = self.tx_pool_controller.continue_chunk_process();
+                        self.continue_chunk_process();
                     },
                     Err(err) => {
                         info!("truncate_block_tx has been closed, err: {}", err);
@@ -110,6 +127,25 @@ impl ConsumeUnverifiedBlocks {
             }
         }
     }
+
+    // The verify workers stay suspended while a deep reorganization settles, which is when the
+    // blocks queued during the reorganization are consumed, or the pause lasts too long.
+    fn continue_chunk_process(&mut self) {
+        let shared = &self.processor.shared;
+        if shared.is_reorg_settling() {
+            let since = *self.reorg_settling_since.get_or_insert_with(Instant::now);
+            if !self.unverified_block_rx.is_empty() && since.elapsed() < MAX_REORG_SETTLE_DURATION {
+                return;
+            }
+            self.reorg_settling_since = None;
+            shared.set_reorg_settling(false);
+            info!(
+                "Deep reorg settled in {:?}, resume tx relay and verification",
+                since.elapsed()
+            );
+        }
+        let _ = self.tx_pool_controller.continue_chunk_process();
+    }
 }
 
 impl ConsumeUnverifiedBlockProcessor {
@@ -207,6 +243,7 @@ impl ConsumeUnverifiedBlockProcessor {
                         == &ckb_types::prelude::Unpack::<H256>::unpack(&BlockView::hash(block))
                     {
                         assume_valid_target.take();
+                        self.shared.record_full_verification_resumed(block.number());
                         Switch::NONE
                     } else {
                         Switch::DISABLE_SCRIPT
@@ -357,6 +394,20 @@ impl ConsumeUnverifiedBlockProcessor {
                     .new_snapshot(tip_header, cannon_total_difficulty, epoch, new_proposals);
 
             self.shared.store_snapshot(Arc::clone(&new_snapshot));
+            self.shared.record_main_chain_switch(
+                fork.detached_blocks().iter(),
+                fork.attached_blocks().iter(),
+                block.header().number(),
+            );
+            if fork.detached_blocks().len() >= DEEP_REORG_DETACHED_BLOCKS
+                && !self.shared.is_reorg_settling()
+            {
+                info!(
+                    "Deep reorg detached {} blocks, pause tx relay and verification until it settles",
+                    fork.detached_blocks().len()
+                );
+                self.shared.set_reorg_settling(true);
+            }
 
             let tx_pool_controller = self.shared.tx_pool_controller();
             if tx_pool_controller.service_started() {
@@ -373,6 +424,7 @@ impl ConsumeUnverifiedBlockProcessor {
             self.shared
                 .notify_controller()
                 .notify_new_block(block.to_owned());
+            self.notify_block_observers(&fork);
             if log_enabled!(ckb_logger::Level::Trace) {
                 self.print_chain(10);
             }
@@ -381,6 +433,7 @@ impl ConsumeUnverifiedBlockProcessor {
             }
         } else {
             self.shared.refresh_snapshot();
+            self.shared.record_stale_block(&block.header());
             info!(
                 "[verify block] uncle: {}, hash: {:#x}, epoch: {:#}, total_diff: {:#x}, txs: {}",
                 block.header().number(),
@@ -400,6 +453,28 @@ impl ConsumeUnverifiedBlockProcessor {
         Ok(true)
     }
 
+    fn notify_block_observers(&self, fork: &ForkChanges) {
+        if self.block_observers.is_empty() {
+            return;
+        }
+        let store = self.shared.store();
+        let attached_blocks = fork
+            .attached_blocks()
+            .iter()
+            .map(|block| VerifiedBlock {
+                block: block.clone(),
+                ext: store
+                    .get_block_ext(&block.hash())
+                    .expect("attached block ext stored"),
+            })
+            .collect();
+        let event = VerifiedBlocksEvent {
+            detached_blocks: fork.detached_blocks().iter().cloned().collect(),
+            attached_blocks,
+        };
+        self.block_observers.notify(&event);
+    }
+
     pub(crate) fn update_proposal_table(&mut self, fork: &ForkChanges) {
         for blk in fork.detached_blocks() {
             self.proposal_table.remove(blk.header().number());
@@ -449,6 +524,7 @@ impl ConsumeUnverifiedBlockProcessor {
 
     pub(crate) fn rollback(&self, fork: &ForkChanges, txn: &StoreTransaction) -> Result<(), Error> {
         for block in fork.detached_blocks().iter().rev() {
+            detach_block_epoch_stats(txn, block)?;
             txn.detach_block(block)?;
             detach_block_cell(txn, block)?;
         }
@@ -610,6 +686,7 @@ impl ConsumeUnverifiedBlockProcessor {
         for b in fork.attached_blocks().iter().take(verified_len) {
             txn.attach_block(b)?;
             attach_block_cell(&txn, b)?;
+            attach_block_epoch_stats(&txn, b)?;
             mmr.push(b.digest())
                 .map_err(|e| InternalErrorKind::MMR.other(e))?;
         }
@@ -670,6 +747,7 @@ impl ConsumeUnverifiedBlockProcessor {
                                         Some(&cache_entries),
                                         Some(txs_sizes),
                                     )?;
+                                    attach_block_epoch_stats(&txn, b)?;
 
                                     if !switch.disable_script() && b.transactions().len() > 1 {
                                         self.monitor_block_txs_verified(
@@ -701,6 +779,7 @@ impl ConsumeUnverifiedBlockProcessor {
                 mmr.push(b.digest())
                     .map_err(|e| InternalErrorKind::MMR.other(e))?;
                 self.insert_ok_ext(&txn, &b.header().hash(), ext.clone(), None, None)?;
+                attach_block_epoch_stats(&txn, b)?;
             }
         }
 
@@ -723,7 +802,21 @@ impl ConsumeUnverifiedBlockProcessor {
         let mut seen_inputs = HashSet::new();
         let block_cp = BlockCellProvider::new(block)?;
         let transactions = block.transactions();
-        let cell_provider = OverlayCellProvider::new(&block_cp, txn);
+        // Read the inputs and the cell deps from the store in a batch, the ones created in this
+        // block are served by `block_cp` and never read.
+        let block_tx_hashes: HashSet<Byte32> = transactions.iter().map(|tx| tx.hash()).collect();
+        let out_points: HashSet<_> = transactions
+            .iter()
+            .skip(1)
+            .flat_map(|tx| {
+                tx.input_pts_iter()
+                    .chain(tx.cell_deps_iter().map(|cell_dep| cell_dep.out_point()))
+            })
+            .filter(|out_point| !block_tx_hashes.contains(&out_point.tx_hash()))
+            .collect();
+        let out_points: Vec<_> = out_points.into_iter().collect();
+        let prefetched = PrefetchedCells::new(txn, &out_points);
+        let cell_provider = OverlayCellProvider::new(&block_cp, &prefetched);
         let resolved = transactions
             .iter()
             .cloned()
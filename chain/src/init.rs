@@ -4,6 +4,7 @@
 use crate::chain_service::ChainService;
 use crate::consume_unverified::ConsumeUnverifiedBlocks;
 use crate::init_load_unverified::InitLoadUnverified;
+use crate::observer::BlockObservers;
 use crate::orphan_broker::OrphanBroker;
 use crate::preload_unverified_blocks_channel::PreloadUnverifiedBlocksChannel;
 use crate::utils::orphan_block_pool::OrphanBlockPool;
@@ -22,6 +23,14 @@ use std::thread;
 const ORPHAN_BLOCK_SIZE: usize = BLOCK_DOWNLOAD_WINDOW as usize;
 
 pub fn start_chain_services(builder: ChainServicesBuilder) -> ChainController {
+    start_chain_services_with_observers(builder, BlockObservers::default())
+}
+
+/// Start the chain services with the compiled-in observers of the verified blocks.
+pub fn start_chain_services_with_observers(
+    builder: ChainServicesBuilder,
+    block_observers: BlockObservers,
+) -> ChainController {
     let orphan_blocks_broker = Arc::new(OrphanBlockPool::with_capacity(ORPHAN_BLOCK_SIZE));
 
     let (truncate_block_tx, truncate_block_rx) = channel::bounded(1);
@@ -49,6 +58,7 @@ pub fn start_chain_services(builder: ChainServicesBuilder) -> ChainController {
                     builder.proposal_table,
                     is_pending_verify,
                     unverified_queue_stop_rx,
+                    block_observers,
                 );
 
                 consume_unverified.start();
@@ -19,6 +19,7 @@ mod chain_service;
 pub mod consume_unverified;
 mod init;
 mod init_load_unverified;
+mod observer;
 mod orphan_broker;
 mod preload_unverified_blocks_channel;
 #[cfg(test)]
@@ -30,7 +31,8 @@ use ckb_logger::{error, info};
 use ckb_store::{ChainDB, ChainStore};
 use ckb_types::prelude::{Pack, Unpack};
 use ckb_types::{BlockNumberAndHash, H256};
-pub use init::start_chain_services;
+pub use init::{start_chain_services, start_chain_services_with_observers};
+pub use observer::{BlockObserver, BlockObservers, VerifiedBlock, VerifiedBlocksEvent};
 
 type ProcessBlockRequest = Request<LonelyBlock, ()>;
 type TruncateRequest = Request<Byte32, Result<(), Error>>;
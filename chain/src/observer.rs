@@ -0,0 +1,100 @@
+//! Non-consensus observers of the verified blocks.
+//!
+//! Observers are compiled into the node and registered by [`start_chain_services_with_observers`].
+//! They receive every block attached to the main chain together with the metadata collected
+//! during verification, so analytics pipelines don't have to re-verify the blocks externally.
+//!
+//! [`start_chain_services_with_observers`]: crate::start_chain_services_with_observers
+use ckb_logger::error;
+use ckb_types::core::{BlockExt, BlockView, Capacity, Cycle};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// An observer of the verified blocks.
+///
+/// The callbacks are invoked on the block verification thread after the blocks have been
+/// committed to the database, implementations should return quickly and hand expensive work
+/// over to their own threads. An observer can't affect the verification result, a panic in the
+/// callback is logged and ignored.
+pub trait BlockObserver: Send + Sync {
+    /// The name of the observer, used in the logs.
+    fn name(&self) -> &str;
+
+    /// Called when the main chain switches to a new tip.
+    fn on_blocks_verified(&self, event: &VerifiedBlocksEvent);
+}
+
+/// A fully validated block attached to the main chain.
+#[derive(Debug, Clone)]
+pub struct VerifiedBlock {
+    /// The block.
+    pub block: BlockView,
+    /// The block extension stored after verification.
+    pub ext: BlockExt,
+}
+
+impl VerifiedBlock {
+    /// The fees of the transactions, cellbase excluded.
+    pub fn txs_fees(&self) -> &[Capacity] {
+        &self.ext.txs_fees
+    }
+
+    /// The cycles consumed by the transactions, cellbase excluded.
+    ///
+    /// It is `None` if the block was attached without verifying the scripts.
+    pub fn txs_cycles(&self) -> Option<&[Cycle]> {
+        self.ext.cycles.as_deref()
+    }
+
+    /// The total cycles consumed by the block.
+    pub fn total_cycles(&self) -> Option<Cycle> {
+        self.txs_cycles().map(|cycles| cycles.iter().sum())
+    }
+}
+
+/// The main chain changes caused by a new best block.
+#[derive(Debug, Clone, Default)]
+pub struct VerifiedBlocksEvent {
+    /// Blocks detached from the main chain, in ascending order of the block number.
+    ///
+    /// It is empty unless the new tip caused a reorganization.
+    pub detached_blocks: Vec<BlockView>,
+    /// Blocks attached to the main chain, in ascending order of the block number, the last one
+    /// is the new tip.
+    pub attached_blocks: Vec<VerifiedBlock>,
+}
+
+impl VerifiedBlocksEvent {
+    /// Whether the event is a chain reorganization.
+    pub fn is_reorg(&self) -> bool {
+        !self.detached_blocks.is_empty()
+    }
+}
+
+/// The registered block observers.
+#[derive(Clone, Default)]
+pub struct BlockObservers {
+    observers: Vec<Arc<dyn BlockObserver>>,
+}
+
+impl BlockObservers {
+    /// Registers an observer.
+    pub fn register(&mut self, observer: Arc<dyn BlockObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Whether no observer is registered.
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    pub(crate) fn notify(&self, event: &VerifiedBlocksEvent) {
+        for observer in &self.observers {
+            let result =
+                panic::catch_unwind(AssertUnwindSafe(|| observer.on_blocks_verified(event)));
+            if result.is_err() {
+                error!("block observer {} panicked", observer.name());
+            }
+        }
+    }
+}
@@ -73,6 +73,7 @@ fn test_find_fork_case1() {
         shared: shared.clone(),
         is_pending_verify,
         proposal_table,
+        block_observers: Default::default(),
     };
 
     // fork1 total_difficulty 400
@@ -157,6 +158,7 @@ fn test_find_fork_case2() {
         shared: shared.clone(),
         is_pending_verify: Arc::new(DashSet::new()),
         proposal_table,
+        block_observers: Default::default(),
     };
 
     // fork1 total_difficulty 400
@@ -240,6 +242,7 @@ fn test_find_fork_case3() {
         shared: shared.clone(),
         is_pending_verify: Arc::new(DashSet::new()),
         proposal_table,
+        block_observers: Default::default(),
     };
     // fork1 total_difficulty 240
     for blk in fork1.blocks() {
@@ -321,6 +324,7 @@ fn test_find_fork_case4() {
         shared: shared.clone(),
         is_pending_verify: Arc::new(DashSet::new()),
         proposal_table,
+        block_observers: Default::default(),
     };
 
     // fork1 total_difficulty 200
@@ -403,6 +407,7 @@ fn repeatedly_switch_fork() {
         shared: shared.clone(),
         is_pending_verify: Arc::new(DashSet::new()),
         proposal_table,
+        block_observers: Default::default(),
     };
 
     for blk in fork1.blocks() {
@@ -8,6 +8,7 @@ mod load_code_with_snapshot;
 mod load_input_cell_data;
 mod load_input_data_hash_cell;
 mod non_contextual_block_txs_verify;
+mod observer;
 mod orphan_block_pool;
 mod reward;
 mod truncate;
@@ -0,0 +1,119 @@
+use crate::{
+    start_chain_services_with_observers, BlockObserver, BlockObservers, VerifiedBlocksEvent,
+};
+use ckb_chain_spec::consensus::Consensus;
+use ckb_shared::SharedBuilder;
+use ckb_store::ChainStore;
+use ckb_test_chain_utils::{MockChain, MockStore};
+use ckb_types::core::BlockNumber;
+use ckb_util::Mutex;
+use ckb_verification_traits::Switch;
+use std::sync::Arc;
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<VerifiedBlocksEvent>>,
+}
+
+impl BlockObserver for RecordingObserver {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    fn on_blocks_verified(&self, event: &VerifiedBlocksEvent) {
+        self.events.lock().push(event.clone());
+    }
+}
+
+struct PanickingObserver;
+
+impl BlockObserver for PanickingObserver {
+    fn name(&self) -> &str {
+        "panicking"
+    }
+
+    fn on_blocks_verified(&self, _event: &VerifiedBlocksEvent) {
+        panic!("observer failure");
+    }
+}
+
+fn numbers(event: &VerifiedBlocksEvent) -> (Vec<BlockNumber>, Vec<BlockNumber>) {
+    (
+        event
+            .detached_blocks
+            .iter()
+            .map(|block| block.number())
+            .collect(),
+        event
+            .attached_blocks
+            .iter()
+            .map(|verified| verified.block.number())
+            .collect(),
+    )
+}
+
+// 0--1--2--3--4
+//  \
+//   1--2--3--4--5
+#[test]
+fn test_block_observers() {
+    let (shared, mut pack) = SharedBuilder::with_temp_db()
+        .consensus(Consensus::default())
+        .build()
+        .unwrap();
+
+    let recorder = Arc::new(RecordingObserver::default());
+    let mut observers = BlockObservers::default();
+    // a panicking observer must not stop the others or the chain service
+    observers.register(Arc::new(PanickingObserver));
+    observers.register(Arc::clone(&recorder) as Arc<dyn BlockObserver>);
+    let chain_controller =
+        start_chain_services_with_observers(pack.take_chain_services_builder(), observers);
+
+    let genesis = shared
+        .store()
+        .get_block_header(&shared.store().get_block_hash(0).unwrap())
+        .unwrap();
+    let mock_store = MockStore::new(&genesis, shared.store());
+    let mut fork1 = MockChain::new(genesis.clone(), shared.consensus());
+    let mut fork2 = MockChain::new(genesis, shared.consensus());
+    for _ in 0..4 {
+        fork1.gen_empty_block_with_diff(20u64, &mock_store);
+    }
+    for _ in 0..5 {
+        fork2.gen_empty_block_with_diff(30u64, &mock_store);
+    }
+
+    for blk in fork1.blocks().iter().chain(fork2.blocks()) {
+        chain_controller
+            .blocking_process_block_with_switch(Arc::new(blk.clone()), Switch::DISABLE_ALL)
+            .unwrap();
+    }
+
+    let events = recorder.events.lock();
+    let numbers: Vec<_> = events.iter().map(numbers).collect();
+    assert_eq!(
+        numbers,
+        vec![
+            (vec![], vec![1]),
+            (vec![], vec![2]),
+            (vec![], vec![3]),
+            (vec![], vec![4]),
+            // fork2 becomes the main chain at the 3rd block, total difficulty 90 > 80
+            (vec![1, 2, 3, 4], vec![1, 2, 3]),
+            (vec![], vec![4]),
+            (vec![], vec![5]),
+        ]
+    );
+    assert!(events[4].is_reorg());
+    assert_eq!(
+        events[4].detached_blocks.last().unwrap().hash(),
+        fork1.tip().hash()
+    );
+
+    let tip = events.last().unwrap().attached_blocks.last().unwrap();
+    assert_eq!(tip.block.hash(), fork2.tip().hash());
+    assert_eq!(tip.ext.verified, Some(true));
+    // scripts are not verified with `Switch::DISABLE_ALL`
+    assert_eq!(tip.total_cycles(), None);
+}
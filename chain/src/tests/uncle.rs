@@ -3,6 +3,7 @@ use ckb_chain_spec::consensus::Consensus;
 use ckb_shared::SharedBuilder;
 use ckb_store::ChainStore;
 use ckb_test_chain_utils::{MockChain, MockStore};
+use ckb_types::core::BlockView;
 use ckb_verification_traits::Switch;
 use std::sync::Arc;
 
@@ -45,3 +46,49 @@ fn test_get_block_body_after_inserting() {
         assert_eq!(len, 1, "[fork2] snapshot.get_block_body({})", blk.hash(),);
     }
 }
+
+#[test]
+fn test_stale_block_stats() {
+    let builder = SharedBuilder::with_temp_db();
+    let (shared, mut pack) = builder
+        .consensus(Consensus::default())
+        .chain_stats_windows(vec![2, 100])
+        .build()
+        .unwrap();
+    let chain_controller = start_chain_services(pack.take_chain_services_builder());
+
+    let parent = shared
+        .store()
+        .get_block_header(&shared.store().get_block_hash(0).unwrap())
+        .unwrap();
+    let mock_store = MockStore::new(&parent, shared.store());
+    let mut fork1 = MockChain::new(parent.clone(), shared.consensus());
+    let mut fork2 = MockChain::new(parent, shared.consensus());
+    for _ in 0..4 {
+        fork1.gen_empty_block_with_diff(100u64, &mock_store);
+    }
+    for _ in 0..6 {
+        fork2.gen_empty_block_with_diff(90u64, &mock_store);
+    }
+
+    let process = |blk: &BlockView| {
+        chain_controller
+            .blocking_process_block_with_switch(Arc::new(blk.clone()), Switch::DISABLE_ALL)
+            .unwrap();
+    };
+    fork1.blocks().iter().for_each(process);
+    fork2.blocks()[..4].iter().for_each(process);
+    let stats = shared.chain_stats();
+    assert_eq!(stats.len(), 2);
+    // the window of the last 2 blocks covers the stale blocks 3 and 4
+    assert_eq!((stats[0].window, stats[0].blocks), (2, 2));
+    assert_eq!(stats[0].stale_blocks, 2);
+    assert_eq!((stats[1].window, stats[1].blocks), (100, 4));
+    assert_eq!(stats[1].stale_blocks, 4);
+    assert_eq!(stats[1].uncles, 0);
+
+    // fork2 becomes the main chain, the blocks of fork1 are stale now
+    fork2.blocks()[4..].iter().for_each(process);
+    let stats = shared.chain_stats();
+    assert_eq!((stats[1].blocks, stats[1].stale_blocks), (6, 4));
+}
@@ -1,6 +1,13 @@
+use ckb_app_config::{DBConfig, ExitCode};
+use ckb_db::WalArchiver;
+use ckb_db_schema::{Col, COLUMN_INFOS};
 use ckb_logger::debug;
 
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
 use std::io::{stdin, stdout, Write};
+use std::path::{Path, PathBuf};
 
 #[cfg(not(feature = "deadlock_detection"))]
 pub fn deadlock_detection() {}
@@ -45,6 +52,42 @@ pub fn deadlock_detection() {
     register_thread("dead_lock_detect", dead_lock_jh);
 }
 
+/// Spawns a background thread that periodically copies the closed WAL files to
+/// `db.wal_archive_dir`, if it is set.
+pub fn wal_archive(db_config: &DBConfig) {
+    use ckb_channel::select;
+    use ckb_logger::{error, info};
+    use ckb_stop_handler::{new_crossbeam_exit_rx, register_thread};
+    use std::{thread, time::Duration};
+
+    let archive_dir = match db_config.wal_archive_dir {
+        Some(ref dir) => dir.clone(),
+        None => return,
+    };
+    info!("Archive the closed WAL files to {}", archive_dir.display());
+    let archiver = WalArchiver::new(&db_config.path, archive_dir);
+    let archive = move || match archiver.archive() {
+        Ok(numbers) if !numbers.is_empty() => debug!("Archived WAL files {:?}", numbers),
+        Ok(_) => {}
+        Err(e) => error!("Archive WAL files error {}", e),
+    };
+    let wal_archive_jh = thread::spawn({
+        let ticker = ckb_channel::tick(Duration::from_secs(60));
+        let stop_rx = new_crossbeam_exit_rx();
+        move || loop {
+            select! {
+                recv(ticker) -> _ => archive(),
+                recv(stop_rx) -> _ => {
+                    archive();
+                    info!("wal_archive received exit signal, stopped");
+                    return;
+                }
+            }
+        }
+    });
+    register_thread("wal_archive", wal_archive_jh);
+}
+
 pub fn prompt(msg: &str) -> String {
     let stdout = stdout();
     let mut stdout = stdout.lock();
@@ -59,6 +102,56 @@ pub fn prompt(msg: &str) -> String {
     input
 }
 
+pub fn is_empty_dir(path: &Path) -> Result<bool, ExitCode> {
+    if !path.exists() {
+        return Ok(true);
+    }
+    fs::read_dir(path)
+        .map(|mut entries| entries.next().is_none())
+        .map_err(|e| {
+            eprintln!("Read the directory {} error {e}", path.display());
+            ExitCode::IO
+        })
+}
+
+/// The directories of the columns placed in other directories, see `db.column_paths`, for a copy
+/// of the database. They are next to the copy and named by the columns, e.g.,
+/// `backups/db.COLUMN_CELL` for `backups/db`. The columns moved back to `db_path` are skipped.
+pub fn column_copies(
+    copy: &Path,
+    db_path: &Path,
+    column_paths: &HashMap<Col, PathBuf>,
+) -> Vec<(Col, &'static str, PathBuf)> {
+    COLUMN_INFOS
+        .iter()
+        .filter(|info| {
+            column_paths
+                .get(info.col)
+                .map_or(false, |dir| dir != db_path)
+        })
+        .map(|info| {
+            let mut dir = OsString::from(copy.as_os_str());
+            dir.push(".");
+            dir.push(info.name);
+            (info.col, info.name, PathBuf::from(dir))
+        })
+        .collect()
+}
+
+pub fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
 /// Raise the soft open file descriptor resource limit to the hard resource
 /// limit.
 ///
@@ -44,6 +44,17 @@ pub fn run_app(version: Version) -> Result<(), ExitCode> {
             cli::CMD_LIST_HASHES => {
                 return subcommand::list_hashes(Setup::root_dir_from_matches(matches)?, matches);
             }
+            cli::CMD_COMPLETIONS => {
+                return subcommand::completions(matches);
+            }
+            cli::CMD_VERIFY_CHECKPOINTS => {
+                return subcommand::verify_checkpoints(matches);
+            }
+            cli::CMD_CONFIG => {
+                if let Some((cli::CMD_CONFIG_SCHEMA, matches)) = matches.subcommand() {
+                    return subcommand::config_schema(matches);
+                }
+            }
             cli::CMD_PEERID => {
                 if let Some((cli, matches)) = matches.subcommand() {
                     match cli {
@@ -133,9 +144,22 @@ fn run_app_inner(
         cli::CMD_REPLAY => subcommand::replay(setup.replay(matches)?, handle.clone()),
         cli::CMD_EXPORT => subcommand::export(setup.export(matches)?, handle.clone()),
         cli::CMD_IMPORT => subcommand::import(setup.import(matches)?, handle.clone()),
+        cli::CMD_EXPORT_INDEXER => subcommand::export_indexer(setup.export_indexer(matches)?),
+        cli::CMD_IMPORT_INDEXER => {
+            subcommand::import_indexer(setup.import(matches)?, handle.clone())
+        }
         cli::CMD_STATS => subcommand::stats(setup.stats(matches)?, handle.clone()),
         cli::CMD_RESET_DATA => subcommand::reset_data(setup.reset_data(matches)?),
         cli::CMD_MIGRATE => subcommand::migrate(setup.migrate(matches)?),
+        cli::CMD_RESTORE_WAL => subcommand::restore_wal(setup.restore_wal(matches)?),
+        cli::CMD_DB => match matches.subcommand() {
+            Some((cli::CMD_VERIFY_FREEZER, matches)) => {
+                subcommand::verify_freezer(setup.verify_freezer(matches)?, handle.clone())
+            }
+            Some((cli::CMD_MOVE_COLUMNS, _)) => subcommand::move_columns(setup.move_columns()?),
+            _ => unreachable!(),
+        },
+        cli::CMD_BENCH => subcommand::bench(setup.bench(matches)?, version, handle.clone()),
         #[cfg(not(target_os = "windows"))]
         cli::CMD_DAEMON => subcommand::daemon(setup.daemon(matches)?),
         _ => unreachable!(),
@@ -169,9 +193,14 @@ fn is_silent_logging(cmd: &str) -> Silent {
         cmd,
         cli::CMD_EXPORT
             | cli::CMD_IMPORT
+            | cli::CMD_EXPORT_INDEXER
+            | cli::CMD_IMPORT_INDEXER
             | cli::CMD_STATS
             | cli::CMD_MIGRATE
             | cli::CMD_RESET_DATA
+            | cli::CMD_RESTORE_WAL
+            | cli::CMD_DB
+            | cli::CMD_BENCH
             | cli::CMD_DAEMON
     )
 }
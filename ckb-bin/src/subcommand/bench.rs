@@ -0,0 +1,451 @@
+use ckb_app_config::{BenchArgs, ExitCode};
+use ckb_async_runtime::Handle;
+use ckb_build_info::Version;
+use ckb_chain::ChainController;
+use ckb_db::RocksDB;
+use ckb_hash::blake2b_256;
+use ckb_instrument::{ProgressBar, ProgressStyle};
+use ckb_shared::{ChainServicesBuilder, Shared, SharedBuilder};
+use ckb_store::ChainStore;
+use ckb_tx_pool::{TxEntry, TxPool};
+use ckb_types::{
+    bytes::Bytes,
+    core::{BlockNumber, Capacity, TransactionBuilder},
+    packed::{CellInput, CellOutput, OutPoint},
+    prelude::*,
+};
+use ckb_verification_traits::Switch;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SUITE_BLOCK_REPLAY: &str = "block-replay";
+const SUITE_TX_POOL: &str = "tx-pool";
+const SUITE_DB: &str = "db";
+// The blocks replayed by default
+const DEFAULT_REPLAY_BLOCKS: u64 = 1000;
+// The txs in the tx-pool benchmark form chains of this length
+const POOL_CHAIN_LEN: usize = 10;
+// The witnesses size of the large txs in the tx-pool benchmark
+const POOL_WITNESS_SIZE: usize = 1024;
+// The key-value pairs written in a batch in the database benchmark
+const DB_WRITE_BATCH_SIZE: usize = 1000;
+const DB_VALUE_SIZE: usize = 128;
+const DB_COLUMN: &str = "0";
+
+// The result of a benchmark.
+struct Measurement {
+    name: &'static str,
+    ops: u64,
+    unit: &'static str,
+    duration: Duration,
+    // the bytes held in memory after the benchmark
+    memory: Option<usize>,
+}
+
+impl Measurement {
+    fn new(name: &'static str, ops: u64, unit: &'static str, duration: Duration) -> Self {
+        Measurement {
+            name,
+            ops,
+            unit,
+            duration,
+            memory: None,
+        }
+    }
+
+    fn with_memory(mut self, memory: usize) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        let secs = self.duration.as_secs_f64();
+        if secs > 0.0 {
+            self.ops as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn bench(args: BenchArgs, version: Version, async_handle: Handle) -> Result<(), ExitCode> {
+    if !args.tmp_target.is_dir() {
+        eprintln!("Bench error: the specified path does not exist or not directory");
+        return Err(ExitCode::Failure);
+    }
+    let suites: Vec<&str> = if args.suites.is_empty() {
+        vec![SUITE_BLOCK_REPLAY, SUITE_TX_POOL, SUITE_DB]
+    } else {
+        args.suites.iter().map(String::as_str).collect()
+    };
+
+    let shared_builder = SharedBuilder::new(
+        &args.config.bin_name,
+        args.config.root_dir.as_path(),
+        &args.config.db,
+        None,
+        async_handle.clone(),
+        args.consensus.clone(),
+    )?;
+    let (shared, _) = shared_builder.build()?;
+    let tmp_dir = tempfile::tempdir_in(&args.tmp_target).map_err(|err| {
+        eprintln!("Bench error: {err:?}");
+        ExitCode::Failure
+    })?;
+
+    let mut measurements = Vec::new();
+    for suite in &suites {
+        if !args.json {
+            println!("Running the {suite} benchmark");
+        }
+        match *suite {
+            SUITE_BLOCK_REPLAY => {
+                measurements.extend(bench_block_replay(
+                    &args,
+                    &shared,
+                    tmp_dir.path(),
+                    async_handle.clone(),
+                )?);
+            }
+            SUITE_TX_POOL => measurements.extend(bench_tx_pool(&args, &shared, tmp_dir.path())),
+            SUITE_DB => measurements.extend(bench_db(&args, &shared, tmp_dir.path())),
+            _ => unreachable!(),
+        }
+    }
+    tmp_dir.close().map_err(|err| {
+        eprintln!("Bench error: {err:?}");
+        ExitCode::Failure
+    })?;
+
+    print_report(&args, &version, &shared, &measurements);
+    Ok(())
+}
+
+// Replays the blocks in a temporary database, only the blocks in the range are timed and fully
+// verified.
+fn bench_block_replay(
+    args: &BenchArgs,
+    shared: &Shared,
+    tmp_dir: &Path,
+    async_handle: Handle,
+) -> Result<Vec<Measurement>, ExitCode> {
+    let tip_number = shared.snapshot().tip_number();
+    let from = args.from.unwrap_or(1).max(1);
+    let to = args
+        .to
+        .unwrap_or_else(|| from.saturating_add(DEFAULT_REPLAY_BLOCKS - 1))
+        .min(tip_number);
+    if from > to {
+        eprintln!("Bench error: no blocks to replay in {from}..={to}, the tip is {tip_number}");
+        return Err(ExitCode::Cli);
+    }
+
+    let mut tmp_db_config = args.config.db.clone();
+    tmp_db_config.path = tmp_dir.join("replay");
+    tmp_db_config.wal_archive_dir = None;
+    let shared_builder = SharedBuilder::new(
+        &args.config.bin_name,
+        args.config.root_dir.as_path(),
+        &tmp_db_config,
+        None,
+        async_handle,
+        args.consensus.clone(),
+    )?;
+    let (_tmp_shared, mut pack) = shared_builder
+        .tx_pool_config(args.config.tx_pool.clone())
+        .build()?;
+    let chain_service_builder: ChainServicesBuilder = pack.take_chain_services_builder();
+    let chain_controller = ckb_chain::start_chain_services(chain_service_builder);
+
+    if from > 1 {
+        replay_blocks(
+            shared,
+            &chain_controller,
+            1..=from - 1,
+            Switch::DISABLE_ALL,
+            !args.json,
+        )?;
+    }
+    let now = Instant::now();
+    let txs = replay_blocks(
+        shared,
+        &chain_controller,
+        from..=to,
+        Switch::NONE,
+        !args.json,
+    )?;
+    let duration = now.elapsed();
+
+    Ok(vec![
+        Measurement::new("block-replay blocks", to - from + 1, "blocks", duration),
+        Measurement::new("block-replay txs", txs, "txs", duration),
+    ])
+}
+
+// Returns the count of the non-cellbase txs in the blocks.
+fn replay_blocks(
+    shared: &Shared,
+    chain_controller: &ChainController,
+    range: RangeInclusive<BlockNumber>,
+    switch: Switch,
+    show_progress: bool,
+) -> Result<u64, ExitCode> {
+    let pb = if show_progress {
+        ProgressBar::new(range.end() - range.start() + 1)
+    } else {
+        ProgressBar::hidden()
+    };
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .progress_chars("#>-"),
+    );
+    let snapshot = shared.snapshot();
+    let mut txs = 0;
+    for number in range {
+        let block = snapshot
+            .get_block_hash(number)
+            .and_then(|hash| snapshot.get_block(&hash))
+            .ok_or_else(|| {
+                eprintln!("Bench error: block {number} is not found");
+                ExitCode::IO
+            })?;
+        txs += block.transactions().len().saturating_sub(1) as u64;
+        chain_controller
+            .blocking_process_block_with_switch(Arc::new(block), switch)
+            .map_err(|err| {
+                eprintln!("Bench error: process block {number} error {err}");
+                ExitCode::Failure
+            })?;
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+    Ok(txs)
+}
+
+// Adds and removes the txs in the tx-pool without verification, then the txs with large
+// witnesses, which are kept in memory and in the witness store.
+fn bench_tx_pool(args: &BenchArgs, shared: &Shared, tmp_dir: &Path) -> Vec<Measurement> {
+    let count = args.count as usize;
+    let mut measurements = Vec::new();
+    measurements.extend(bench_tx_pool_entries(
+        args,
+        shared,
+        None,
+        pool_entries(count, 0),
+        ["tx-pool add", "tx-pool remove"],
+    ));
+    measurements.extend(bench_tx_pool_entries(
+        args,
+        shared,
+        None,
+        pool_entries(count, POOL_WITNESS_SIZE),
+        ["tx-pool add witnesses", "tx-pool remove witnesses"],
+    ));
+    measurements.extend(bench_tx_pool_entries(
+        args,
+        shared,
+        Some(tmp_dir.join("witness_store")),
+        pool_entries(count, POOL_WITNESS_SIZE),
+        ["tx-pool add lazy", "tx-pool remove lazy"],
+    ));
+    measurements
+}
+
+fn bench_tx_pool_entries(
+    args: &BenchArgs,
+    shared: &Shared,
+    witness_store: Option<PathBuf>,
+    entries: Vec<TxEntry>,
+    names: [&'static str; 2],
+) -> Vec<Measurement> {
+    let mut config = args.config.tx_pool.clone();
+    // the benchmark must not touch the recent reject database in the data directory
+    config.recent_reject = PathBuf::new();
+    match witness_store {
+        Some(path) => {
+            config.witness_store = path;
+            config.lazy_witnesses_threshold = Some(POOL_WITNESS_SIZE);
+        }
+        None => config.lazy_witnesses_threshold = None,
+    }
+    let mut tx_pool = TxPool::new(config, shared.cloned_snapshot());
+    let ids: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.proposal_short_id())
+        .collect();
+
+    let now = Instant::now();
+    let mut added = 0;
+    for entry in entries {
+        if let Ok(true) = tx_pool.plug_pending(entry) {
+            added += 1;
+        }
+    }
+    let add_duration = now.elapsed();
+    let memory = tx_pool.txs_memory_size();
+
+    // the descendants are removed first, so every removal removes exactly one tx
+    let now = Instant::now();
+    let mut removed = 0;
+    for id in ids.iter().rev() {
+        if tx_pool.unplug(id) {
+            removed += 1;
+        }
+    }
+    let remove_duration = now.elapsed();
+
+    vec![
+        Measurement::new(names[0], added, "txs", add_duration).with_memory(memory),
+        Measurement::new(names[1], removed, "txs", remove_duration),
+    ]
+}
+
+fn pool_entries(count: usize, witness_size: usize) -> Vec<TxEntry> {
+    let mut entries: Vec<TxEntry> = Vec::with_capacity(count);
+    for i in 0..count {
+        let previous_output = match entries.last() {
+            Some(parent) if i % POOL_CHAIN_LEN != 0 => {
+                OutPoint::new(parent.transaction().hash(), 0)
+            }
+            _ => OutPoint::new(blake2b_256((i as u64).to_le_bytes()).pack(), 0),
+        };
+        let tx = TransactionBuilder::default()
+            .input(CellInput::new(previous_output, 0))
+            .output(
+                CellOutput::new_builder()
+                    .capacity(Capacity::bytes(100).expect("capacity").pack())
+                    .build(),
+            )
+            .output_data(Bytes::new().pack())
+            .witnesses((witness_size > 0).then(|| Bytes::from(vec![0u8; witness_size]).pack()))
+            .build();
+        let size = tx.data().serialized_size_in_block();
+        // the various fees make the txs sorted in the pool
+        let fee = Capacity::shannons(1000 + (i % 1000) as u64);
+        entries.push(TxEntry::dummy_resolve(tx, 0, fee, size));
+    }
+    entries
+}
+
+// Writes and reads a temporary database with the configured RocksDB options, and reads the
+// blocks from the database in the data directory.
+fn bench_db(args: &BenchArgs, shared: &Shared, tmp_dir: &Path) -> Vec<Measurement> {
+    let count = args.count as usize;
+    let mut db_config = args.config.db.clone();
+    db_config.path = tmp_dir.join("db");
+    db_config.wal_archive_dir = None;
+    let db = RocksDB::open(&db_config, 1);
+    let value = vec![0xa5u8; DB_VALUE_SIZE];
+
+    let now = Instant::now();
+    for start in (0..count).step_by(DB_WRITE_BATCH_SIZE) {
+        let mut batch = db.new_write_batch();
+        for i in start..(start + DB_WRITE_BATCH_SIZE).min(count) {
+            batch
+                .put(DB_COLUMN, &db_key(i), &value)
+                .expect("db put should be ok");
+        }
+        db.write(&batch).expect("db write should be ok");
+    }
+    let write_duration = now.elapsed();
+
+    let now = Instant::now();
+    for i in 0..count {
+        let key = db_key(spread(i, count));
+        db.get_pinned(DB_COLUMN, &key)
+            .expect("db get should be ok")
+            .expect("the key is written");
+    }
+    let read_duration = now.elapsed();
+
+    let snapshot = shared.snapshot();
+    let blocks = snapshot.tip_number() as usize + 1;
+    let now = Instant::now();
+    for i in 0..count {
+        let number = spread(i, blocks) as BlockNumber;
+        let _block = snapshot
+            .get_block_hash(number)
+            .and_then(|hash| snapshot.get_block(&hash));
+    }
+    let block_read_duration = now.elapsed();
+
+    vec![
+        Measurement::new("db write", count as u64, "keys", write_duration),
+        Measurement::new("db random read", count as u64, "keys", read_duration),
+        Measurement::new("db block read", count as u64, "blocks", block_read_duration),
+    ]
+}
+
+fn db_key(i: usize) -> [u8; 32] {
+    blake2b_256((i as u64).to_le_bytes())
+}
+
+// Maps the i-th operation to a scattered position in 0..n, so the benchmark is deterministic
+// but does not read sequentially.
+fn spread(i: usize, n: usize) -> usize {
+    ((i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) % n as u64) as usize
+}
+
+fn print_report(
+    args: &BenchArgs,
+    version: &Version,
+    shared: &Shared,
+    measurements: &[Measurement],
+) {
+    let tip_number = shared.snapshot().tip_number();
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if args.json {
+        let report = serde_json::json!({
+            "version": version.short(),
+            "chain": shared.consensus().id,
+            "tip_number": tip_number,
+            "cpus": cpus,
+            "count": args.count,
+            "results": measurements.iter().map(|m| serde_json::json!({
+                "name": m.name,
+                "ops": m.ops,
+                "unit": m.unit,
+                "duration_ms": m.duration.as_millis() as u64,
+                "ops_per_sec": m.ops_per_sec(),
+                "memory_bytes": m.memory,
+            })).collect::<Vec<_>>(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("serialization should be ok")
+        );
+    } else {
+        println!(
+            "\n----------------------------\nVersion: {}, chain: {}, tip: {}, cpus: {}, count: {}",
+            version.short(),
+            shared.consensus().id,
+            tip_number,
+            cpus,
+            args.count
+        );
+        println!(
+            "{:<24} {:>12} {:>12} {:>16} {:>12}",
+            "benchmark", "ops", "duration", "ops/s", "memory"
+        );
+        for m in measurements {
+            let memory = m
+                .memory
+                .map_or_else(String::new, |bytes| format!("{bytes}B"));
+            println!(
+                "{:<24} {:>12} {:>12} {:>12.1} {}/s {:>12}",
+                m.name,
+                m.ops,
+                format!("{:.3}s", m.duration.as_secs_f64()),
+                m.ops_per_sec(),
+                m.unit,
+                memory
+            );
+        }
+        println!("----------------------------");
+    }
+}
@@ -0,0 +1,12 @@
+use ckb_app_config::{cli, ExitCode};
+use clap::ArgMatches;
+use clap_complete::Shell;
+
+pub fn completions(matches: &ArgMatches) -> Result<(), ExitCode> {
+    let shell = *matches
+        .get_one::<Shell>(cli::ARG_SHELL)
+        .expect("required arg");
+    let mut command = cli::basic_app();
+    clap_complete::generate(shell, &mut command, cli::BIN_NAME, &mut std::io::stdout());
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use ckb_app_config::{cli, CKBAppConfig, ExitCode, MinerAppConfig};
+use clap::ArgMatches;
+
+pub fn config_schema(matches: &ArgMatches) -> Result<(), ExitCode> {
+    let schema = if matches.get_flag(cli::ARG_MINER) {
+        MinerAppConfig::json_schema()
+    } else {
+        CKBAppConfig::json_schema()
+    };
+    let json = serde_json::to_string_pretty(&schema).map_err(|err| {
+        eprintln!("Serialize the JSON schema error {err}");
+        ExitCode::Failure
+    })?;
+    println!("{json}");
+    Ok(())
+}
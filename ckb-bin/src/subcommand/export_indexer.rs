@@ -0,0 +1,18 @@
+use ckb_app_config::{ExitCode, ExportIndexerArgs};
+use ckb_indexer::snapshot::export_snapshot;
+
+pub fn export_indexer(args: ExportIndexerArgs) -> Result<(), ExitCode> {
+    let manifest =
+        export_snapshot(&args.config.indexer, &args.target, args.height).map_err(|err| {
+            eprintln!("Export indexer error: {err}");
+            ExitCode::Failure
+        })?;
+    println!(
+        "Exported {} entries of the indexer at block {} {:#x} to {}",
+        manifest.entries,
+        manifest.tip_number,
+        manifest.tip_hash,
+        args.target.display()
+    );
+    Ok(())
+}
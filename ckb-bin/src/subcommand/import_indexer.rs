@@ -0,0 +1,31 @@
+use ckb_app_config::{ExitCode, ImportArgs};
+use ckb_async_runtime::Handle;
+use ckb_indexer::snapshot::import_snapshot;
+use ckb_shared::SharedBuilder;
+use ckb_store::ChainStore;
+
+pub fn import_indexer(args: ImportArgs, async_handle: Handle) -> Result<(), ExitCode> {
+    let builder = SharedBuilder::new(
+        &args.config.bin_name,
+        args.config.root_dir.as_path(),
+        &args.config.db,
+        None,
+        async_handle,
+        args.consensus,
+    )?;
+    let (shared, _) = builder.build()?;
+    let snapshot = shared.snapshot();
+
+    let manifest = import_snapshot(&args.config.indexer, &args.source, |number| {
+        snapshot.get_block_hash(number)
+    })
+    .map_err(|err| {
+        eprintln!("Import indexer error: {err}");
+        ExitCode::Failure
+    })?;
+    println!(
+        "Imported {} entries of the indexer at block {} {:#x}",
+        manifest.entries, manifest.tip_number, manifest.tip_hash
+    );
+    Ok(())
+}
@@ -5,6 +5,7 @@ use crate::helper::prompt;
 use base64::Engine;
 use ckb_app_config::{cli, AppConfig, ExitCode, InitArgs};
 use ckb_chain_spec::ChainSpec;
+use ckb_hash::blake2b_256;
 use ckb_jsonrpc_types::ScriptHashType;
 use ckb_resource::{
     Resource, TemplateContext, AVAILABLE_SPECS, CKB_CONFIG_FILE_NAME, DB_OPTIONS_FILE_NAME,
@@ -14,6 +15,7 @@ use ckb_types::{prelude::*, H256};
 
 const DEFAULT_LOCK_SCRIPT_HASH_TYPE: &str = "type";
 const SECP256K1_BLAKE160_SIGHASH_ALL_ARG_LEN: usize = 20 * 2 + 2; // 42 = 20 x 2 + prefix 0x
+const REMOTE_SPEC_SCHEME: &str = "https://";
 
 pub fn init(args: InitArgs) -> Result<(), ExitCode> {
     let mut args = args;
@@ -25,6 +27,34 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
         return Ok(());
     }
 
+    let remote_spec = if args.chain.starts_with(REMOTE_SPEC_SCHEME) {
+        if args.import_spec.is_some() {
+            eprintln!("Cannot use --import-spec when --chain is a URL.");
+            return Err(ExitCode::Failure);
+        }
+        let spec_content = fetch_remote_spec(&args.chain, args.spec_hash.as_ref())?;
+        let spec: ChainSpec = toml::from_slice(&spec_content).map_err(|err| {
+            eprintln!(
+                "The chain spec fetched from {} is invalid: {}",
+                args.chain, err
+            );
+            ExitCode::Failure
+        })?;
+        // the name is used in the paths of the exported spec files
+        if !is_valid_spec_name(&spec.name) {
+            eprintln!(
+                "The name {:?} of the chain spec fetched from {} is invalid, \
+                 it should only contain ASCII letters, digits, '_' and '-'.",
+                spec.name, args.chain
+            );
+            return Err(ExitCode::Failure);
+        }
+        args.chain = spec.name;
+        Some(spec_content)
+    } else {
+        None
+    };
+
     if args.chain != "dev" && !args.customize_spec.is_unset() {
         eprintln!("Customizing consensus parameters for chain spec; only works for dev chains.");
         return Err(ExitCode::Failure);
@@ -167,7 +197,19 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
         ],
     );
 
-    if let Some(spec_file) = args.import_spec {
+    if let Some(spec_content) = remote_spec {
+        context.insert("spec_source", "file");
+
+        let specs_dir = args.root_dir.join("specs");
+        fs::create_dir_all(&specs_dir)?;
+        let target_file = specs_dir.join(format!("{}.toml", args.chain));
+
+        println!(
+            "Create specs/{}.toml from the fetched chain spec",
+            args.chain
+        );
+        fs::write(target_file, spec_content)?;
+    } else if let Some(spec_file) = args.import_spec {
         context.insert("spec_source", "file");
 
         let specs_dir = args.root_dir.join("specs");
@@ -218,3 +260,41 @@ pub fn init(args: InitArgs) -> Result<(), ExitCode> {
 
     Ok(())
 }
+
+fn is_valid_spec_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Fetch the chain spec over HTTPS and verify it against the pinned hash.
+fn fetch_remote_spec(url: &str, spec_hash: Option<&H256>) -> Result<Vec<u8>, ExitCode> {
+    let expected = match spec_hash {
+        Some(hash) => hash,
+        None => {
+            eprintln!("--spec-hash is required to verify the chain spec fetched from {url}.");
+            return Err(ExitCode::Failure);
+        }
+    };
+
+    println!("Fetch the chain spec from {url}");
+    let content = reqwest::blocking::get(url)
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.bytes())
+        .map_err(|err| {
+            eprintln!("Failed to fetch the chain spec from {url}: {err}");
+            ExitCode::Failure
+        })?;
+
+    let actual = H256(blake2b_256(&content));
+    if &actual != expected {
+        eprintln!(
+            "The hash of the chain spec fetched from {url} is unmatched:\n\
+             expect {expected:#x},\n\
+             actual {actual:#x}"
+        );
+        return Err(ExitCode::Failure);
+    }
+    Ok(content.to_vec())
+}
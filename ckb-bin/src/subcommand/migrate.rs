@@ -1,12 +1,28 @@
 use ckb_app_config::{ExitCode, MigrateArgs};
-use ckb_migrate::migrate::Migrate;
+use ckb_db::column_paths::move_column_files;
+use ckb_db::{resolve_column_paths, RocksDB};
+use ckb_db_schema::{Col, COLUMNS};
+use ckb_migrate::migrate::{Migrate, MigrationInfo, MigrationRecord};
 use is_terminal::IsTerminal;
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use crate::helper::prompt;
+use crate::helper::{column_copies, is_empty_dir, prompt};
 
 pub fn migrate(args: MigrateArgs) -> Result<(), ExitCode> {
-    let migrate = Migrate::new(&args.config.db.path, args.consensus.hardfork_switch);
+    if let Some(target) = args.target.clone() {
+        return migrate_copy(args, &target);
+    }
+
+    let column_paths = resolve_column_paths(&args.config.db).map_err(|err| {
+        eprintln!("Config error {err}");
+        ExitCode::Config
+    })?;
+    let migrate = Migrate::new(&args.config.db.path, args.consensus.hardfork_switch)
+        .column_paths(column_paths);
 
     {
         let read_only_db = migrate.open_read_only_db().map_err(|e| {
@@ -14,6 +30,22 @@ pub fn migrate(args: MigrateArgs) -> Result<(), ExitCode> {
             ExitCode::Failure
         })?;
 
+        if args.list {
+            match read_only_db {
+                Some(db) => list_migrations(migrate.history(&db), migrate.pending(&db)),
+                None => println!("The database does not exist, no migration is applied."),
+            }
+            return Ok(());
+        }
+
+        if args.dry_run {
+            match read_only_db {
+                Some(db) => report_pending_migrations(migrate.pending_migrations(&db)),
+                None => println!("The database does not exist, no migration will run."),
+            }
+            return Ok(());
+        }
+
         if let Some(db) = read_only_db {
             // if there are only pending background migrations, they will run automatically
             // so here we check with `include_background` as true
@@ -81,3 +113,213 @@ pub fn migrate(args: MigrateArgs) -> Result<(), ExitCode> {
     }
     Ok(())
 }
+
+// Migrates a standalone copy of the database without any node services, so the operator can
+// stop the node and swap the migrated copy in instead of waiting for the migrations.
+//
+// The copy is a checkpoint of the database, see `create_checkpoint`. The columns placed in other
+// directories are moved next to the copy, see `column_copies`, the migrations never write to the
+// directories of the node.
+fn migrate_copy(args: MigrateArgs, target: &Path) -> Result<(), ExitCode> {
+    let db_path = &args.config.db.path;
+    let column_paths = resolve_column_paths(&args.config.db).map_err(|err| {
+        eprintln!("Config error {err}");
+        ExitCode::Config
+    })?;
+    let column_copies = column_copies(target, db_path, &column_paths);
+    if is_empty_dir(target)? {
+        for (_, name, copy) in column_copies.iter() {
+            if !is_empty_dir(copy)? {
+                eprintln!(
+                    "The copy {} of the column {name} is not empty",
+                    copy.display()
+                );
+                return Err(ExitCode::Failure);
+            }
+        }
+        create_checkpoint(db_path, target, &column_paths, &column_copies)?;
+    }
+
+    let migrate = Migrate::with_threads(target, args.consensus.hardfork_switch, args.threads)
+        .column_paths(
+            column_copies
+                .iter()
+                .map(|(col, _, copy)| (*col, copy.clone()))
+                .collect(),
+        );
+    {
+        let read_only_db = migrate
+            .open_read_only_db()
+            .map_err(|e| {
+                eprintln!("Migration error {e}");
+                ExitCode::Failure
+            })?
+            .ok_or_else(|| {
+                eprintln!("The database {} does not exist", target.display());
+                ExitCode::Failure
+            })?;
+        if matches!(migrate.check(&read_only_db, true), Ordering::Greater) {
+            eprintln!(
+                "The database copy was created by a higher version CKB executable binary \n\
+                 and cannot be migrated by the current binary."
+            );
+            return Err(ExitCode::Failure);
+        }
+        let pending = migrate.pending(&read_only_db);
+        if pending.is_empty() {
+            println!("The database copy {} is up to date", target.display());
+            return Ok(());
+        }
+        println!(
+            "Running {} pending migrations on {}: {}",
+            pending.len(),
+            target.display(),
+            pending.join(", ")
+        );
+    }
+
+    let started_at = Instant::now();
+    migrate.migrate_offline().map_err(|err| {
+        eprintln!("Run error: {err:?}");
+        ExitCode::Failure
+    })?;
+    println!(
+        "The database copy {} is migrated in {:?}.\n\
+         Stop the node, replace {} with the copy, and start the node again.",
+        target.display(),
+        started_at.elapsed(),
+        db_path.display()
+    );
+    for (col, name, copy) in column_copies.iter() {
+        println!(
+            "Replace {} of the column {name} with {} as well.",
+            column_paths[col].display(),
+            copy.display()
+        );
+    }
+    Ok(())
+}
+
+// Creates a checkpoint of the database in `target`, and moves the files of the columns placed in
+// other directories to their copies.
+//
+// The database can be opened by only one process, so the node must be stopped while the
+// checkpoint is created, which is quick since the files are hard linked on the same filesystem.
+// Unlike copying the directories of a running node, the checkpoint is a consistent copy.
+fn create_checkpoint(
+    db_path: &Path,
+    target: &Path,
+    column_paths: &HashMap<Col, PathBuf>,
+    column_copies: &[(Col, &'static str, PathBuf)],
+) -> Result<(), ExitCode> {
+    // the bulk load mode disables the compactions, so the files stay in place while they are moved
+    let db = RocksDB::prepare_for_bulk_load_open(db_path, COLUMNS, column_paths)
+        .map_err(|e| {
+            eprintln!(
+                "Open the database error {e}\n\
+                 Stop the node while the checkpoint of the database is created."
+            );
+            ExitCode::Failure
+        })?
+        .ok_or_else(|| {
+            eprintln!("The database {} does not exist", db_path.display());
+            ExitCode::Failure
+        })?;
+    println!(
+        "Creating a checkpoint of the database {} in {}",
+        db_path.display(),
+        target.display()
+    );
+    // RocksDB creates the checkpoint directory itself
+    if target.exists() {
+        fs::remove_dir(target).map_err(|e| {
+            eprintln!("Remove the empty directory {} error {e}", target.display());
+            ExitCode::IO
+        })?;
+    }
+    db.create_checkpoint(target).map_err(|e| {
+        eprintln!("Create the checkpoint error {e}");
+        ExitCode::Failure
+    })?;
+    for (col, name, copy) in column_copies.iter() {
+        let dir = &column_paths[col];
+        if !dir.exists() {
+            continue;
+        }
+        println!(
+            "Moving the files of the column {name} in {} to {}",
+            dir.display(),
+            copy.display()
+        );
+        move_column_files(target, dir, copy).map_err(|e| {
+            eprintln!("Move the files of the column {name} error {e}");
+            ExitCode::IO
+        })?;
+    }
+    println!("The checkpoint is created, the node can be started again.");
+    Ok(())
+}
+
+fn list_migrations(history: Vec<MigrationRecord>, pending: Vec<String>) {
+    println!("Applied migrations:");
+    if history.is_empty() {
+        println!("  (no migration history recorded)");
+    }
+    for record in history {
+        println!(
+            "  {}  completed at {} (unix time in ms), took {:?}",
+            record.version,
+            record.completed_at,
+            Duration::from_millis(record.duration_ms)
+        );
+    }
+
+    println!("Pending migrations:");
+    if pending.is_empty() {
+        println!("  (none)");
+    }
+    for version in pending {
+        println!("  {version}");
+    }
+}
+
+fn report_pending_migrations(pending: Vec<MigrationInfo>) {
+    if pending.is_empty() {
+        println!("The database is up to date, no migration will run.");
+        return;
+    }
+    println!("{} pending migrations will run:", pending.len());
+    for info in pending.iter() {
+        let mut notes = Vec::new();
+        if info.expensive {
+            notes.push("expensive");
+        }
+        if info.run_in_background {
+            notes.push("background");
+        }
+        let description = if info.description.is_empty() {
+            "(no description)"
+        } else {
+            info.description.as_str()
+        };
+        if notes.is_empty() {
+            println!("  {}  {}", info.version, description);
+        } else {
+            println!("  {}  {} [{}]", info.version, description, notes.join(", "));
+        }
+    }
+    if pending
+        .iter()
+        .any(|info| info.expensive && !info.run_in_background)
+    {
+        println!(
+            "The expensive migrations will take quite a long time before CKB could work again."
+        );
+    }
+    if pending.iter().any(|info| info.run_in_background) {
+        println!(
+            "The background migrations run after the node starts, \
+             or in the foreground with --include-background."
+        );
+    }
+}
@@ -1,27 +1,45 @@
+mod bench;
+mod completions;
+mod config_schema;
 #[cfg(not(target_os = "windows"))]
 mod daemon;
 mod export;
+mod export_indexer;
 mod import;
+mod import_indexer;
 mod init;
 mod list_hashes;
 mod migrate;
 mod miner;
+mod move_columns;
 mod peer_id;
 mod replay;
 mod reset_data;
+mod restore_wal;
 mod run;
 mod stats;
+mod verify_checkpoints;
+mod verify_freezer;
 
+pub use self::bench::bench;
+pub use self::completions::completions;
+pub use self::config_schema::config_schema;
 #[cfg(not(target_os = "windows"))]
 pub use self::daemon::{check_process, daemon};
 pub use self::export::export;
+pub use self::export_indexer::export_indexer;
 pub use self::import::import;
+pub use self::import_indexer::import_indexer;
 pub use self::init::init;
 pub use self::list_hashes::list_hashes;
 pub use self::migrate::migrate;
 pub use self::miner::miner;
+pub use self::move_columns::move_columns;
 pub use self::peer_id::peer_id;
 pub use self::replay::replay;
 pub use self::reset_data::reset_data;
+pub use self::restore_wal::restore_wal;
 pub use self::run::run;
 pub use self::stats::stats;
+pub use self::verify_checkpoints::verify_checkpoints;
+pub use self::verify_freezer::verify_freezer;
@@ -0,0 +1,40 @@
+use ckb_app_config::{ExitCode, MoveColumnsArgs};
+use ckb_db::{resolve_column_paths, RocksDB};
+use ckb_db_schema::{COLUMNS, COLUMN_INFOS};
+use std::time::Instant;
+
+pub fn move_columns(args: MoveColumnsArgs) -> Result<(), ExitCode> {
+    let column_paths = resolve_column_paths(&args.db).map_err(|err| {
+        eprintln!("Config error {err}");
+        ExitCode::Config
+    })?;
+    if column_paths.is_empty() {
+        println!("No columns are placed in other directories, see `db.column_paths`");
+        return Ok(());
+    }
+    if !args.db.path.exists() {
+        eprintln!("The database {} does not exist", args.db.path.display());
+        return Err(ExitCode::Failure);
+    }
+
+    let db = RocksDB::open(&args.db, COLUMNS);
+    for info in COLUMN_INFOS.iter() {
+        let dir = match column_paths.get(info.col) {
+            Some(dir) => dir,
+            None => continue,
+        };
+        println!("Moving the column {} to {}", info.name, dir.display());
+        let started_at = Instant::now();
+        db.rewrite_column(info.col).map_err(|err| {
+            eprintln!("Move the column {} error {err}", info.name);
+            ExitCode::Failure
+        })?;
+        println!(
+            "The column {} is moved in {:.2?}",
+            info.name,
+            started_at.elapsed()
+        );
+    }
+    println!("The columns are moved");
+    Ok(())
+}
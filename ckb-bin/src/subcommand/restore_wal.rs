@@ -0,0 +1,87 @@
+use ckb_app_config::{ExitCode, RestoreWalArgs};
+use ckb_db::wal_archive::{check_wal_archive_dir, restore_archived_wal};
+use ckb_db::{resolve_column_paths, RocksDB};
+use ckb_db_schema::COLUMNS;
+
+use crate::helper::{column_copies, copy_dir, is_empty_dir};
+
+pub fn restore_wal(args: RestoreWalArgs) -> Result<(), ExitCode> {
+    let archive_dir = args.db.wal_archive_dir.clone().ok_or_else(|| {
+        eprintln!("db.wal_archive_dir is not set");
+        ExitCode::Config
+    })?;
+    let db_path = args.db.path.clone();
+    check_wal_archive_dir(&db_path, &archive_dir).map_err(|err| {
+        eprintln!("Config error {err}");
+        ExitCode::Config
+    })?;
+    if !is_empty_dir(&db_path)? {
+        eprintln!(
+            "The database directory {} is not empty, remove it by `ckb reset-data --database` first",
+            db_path.display()
+        );
+        return Err(ExitCode::Failure);
+    }
+    // the columns placed in other directories are restored from the directories next to the
+    // backup, e.g., `backups/db.COLUMN_CELL`
+    let column_paths = resolve_column_paths(&args.db).map_err(|err| {
+        eprintln!("Config error {err}");
+        ExitCode::Config
+    })?;
+    let column_backups = column_copies(&args.backup, &db_path, &column_paths);
+    for (col, name, _) in column_backups.iter() {
+        let dir = &column_paths[col];
+        if !is_empty_dir(dir)? {
+            eprintln!(
+                "The directory {} of the column {name} is not empty, remove the files in it first",
+                dir.display()
+            );
+            return Err(ExitCode::Failure);
+        }
+    }
+
+    println!(
+        "Copying the backup {} to {}",
+        args.backup.display(),
+        db_path.display()
+    );
+    copy_dir(&args.backup, &db_path).map_err(|e| {
+        eprintln!("Copy the backup error {e}");
+        ExitCode::IO
+    })?;
+    for (col, name, backup) in column_backups.iter() {
+        if !backup.exists() {
+            continue;
+        }
+        let dir = &column_paths[col];
+        println!(
+            "Copying the backup {} of the column {name} to {}",
+            backup.display(),
+            dir.display()
+        );
+        copy_dir(backup, dir).map_err(|e| {
+            eprintln!("Copy the backup of the column {name} error {e}");
+            ExitCode::IO
+        })?;
+    }
+
+    let restored = restore_archived_wal(&archive_dir, &db_path, args.until).map_err(|e| {
+        eprintln!("Restore the archived WAL files error {e}");
+        ExitCode::Failure
+    })?;
+    match (restored.first(), restored.last()) {
+        (Some(first), Some(last)) => {
+            println!("Replaying the archived WAL files from {first} to {last}")
+        }
+        _ => println!("No archived WAL files to replay"),
+    }
+
+    // RocksDB replays the WAL files when the database is opened
+    let db = RocksDB::try_open(&args.db, COLUMNS).map_err(|e| {
+        eprintln!("Replay the archived WAL files error {e}");
+        ExitCode::Failure
+    })?;
+    drop(db);
+    println!("The database is restored");
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::thread::available_parallelism;
 
-use crate::helper::deadlock_detection;
+use crate::helper::{deadlock_detection, wal_archive};
 use ckb_app_config::{ExitCode, RunArgs};
 use ckb_async_runtime::{new_global_runtime, Handle};
 use ckb_build_info::Version;
@@ -24,11 +24,22 @@ pub fn run(args: RunArgs, version: Version, async_handle: Handle) -> Result<(),
     let miner_enable = block_assembler_config.is_some();
 
     launcher.check_indexer_config()?;
+    launcher.check_tx_pool_config()?;
+    launcher.preflight_check()?;
+    let checkpoint_signer = launcher.load_checkpoint_signer()?;
 
     let (shared, mut pack) = launcher.build_shared(block_assembler_config)?;
 
     // spawn freezer background process
     let _freezer = shared.spawn_freeze();
+    shared.spawn_prune_uncles_and_proposals(
+        launcher
+            .args
+            .config
+            .store
+            .uncles_and_proposals_retention_epochs,
+    );
+    wal_archive(&launcher.args.config.db);
 
     setup_system_cell_cache(
         shared.consensus().genesis_block(),
@@ -56,10 +67,17 @@ pub fn run(args: RunArgs, version: Version, async_handle: Handle) -> Result<(),
         chain_controller,
         miner_enable,
         pack.take_relay_tx_receiver(),
+        checkpoint_signer,
     );
 
     let tx_pool_builder = pack.take_tx_pool_builder();
-    tx_pool_builder.start(network_controller);
+    if launcher.args.config.tx_pool.enable {
+        tx_pool_builder.start(network_controller);
+    } else {
+        // dropping the builder closes the channels, the requests to the tx-pool fail immediately
+        info!("tx-pool is disabled");
+        drop(tx_pool_builder);
+    }
 
     info!("CKB service started ...");
     ctrlc::set_handler(|| {
@@ -0,0 +1,57 @@
+use ckb_app_config::{cli, ExitCode};
+use ckb_jsonrpc_types::SignedHeaderCheckpoints;
+use ckb_shared::header_checkpoints::verify_header_checkpoints;
+use ckb_types::core;
+use clap::ArgMatches;
+
+pub fn verify_checkpoints(matches: &ArgMatches) -> Result<(), ExitCode> {
+    let path = matches
+        .get_one::<String>(cli::ARG_FILE)
+        .expect("required arg");
+    let content = std::fs::read_to_string(path).map_err(|err| {
+        eprintln!("Read the file {path} error {err}");
+        ExitCode::IO
+    })?;
+    let json: SignedHeaderCheckpoints = serde_json::from_str(&content).map_err(|err| {
+        eprintln!("Parse the checkpoints error {err}");
+        ExitCode::Failure
+    })?;
+    let signed: core::SignedHeaderCheckpoints = json.clone().into();
+
+    if let Err(err) = verify_header_checkpoints(&signed) {
+        eprintln!("Invalid checkpoints: {err}");
+        return Err(ExitCode::Failure);
+    }
+    let node_id = &signed.node_id;
+    if let Some(expected) = matches.get_one::<String>(cli::ARG_NODE_ID) {
+        if expected != node_id {
+            eprintln!("The checkpoints are signed by the node {node_id} instead of {expected}");
+            return Err(ExitCode::Failure);
+        }
+    }
+    if signed
+        .checkpoints
+        .windows(2)
+        .any(|pair| pair[0].number >= pair[1].number)
+    {
+        eprintln!("The checkpoints are not in ascending order of the block numbers");
+        return Err(ExitCode::Failure);
+    }
+
+    for checkpoint in &json.checkpoints {
+        println!(
+            "#{} {:#x} epoch {} total difficulty {:#x}",
+            checkpoint.number.value(),
+            checkpoint.hash,
+            core::EpochNumberWithFraction::from_full_value(checkpoint.epoch.value()),
+            checkpoint.total_difficulty,
+        );
+    }
+    println!(
+        "The {} checkpoints of the chain {:#x} are signed by the node {}",
+        signed.checkpoints.len(),
+        json.genesis_hash,
+        node_id
+    );
+    Ok(())
+}
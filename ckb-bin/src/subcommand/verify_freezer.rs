@@ -0,0 +1,210 @@
+use ckb_app_config::{ExitCode, VerifyFreezerArgs};
+use ckb_async_runtime::Handle;
+use ckb_freezer::{ChecksumStatus, FileId, Freezer};
+use ckb_instrument::{ProgressBar, ProgressStyle};
+use ckb_jsonrpc_types::JsonBytes;
+use ckb_shared::SharedBuilder;
+use ckb_store::ChainStore;
+use ckb_types::{core::BlockNumber, packed, prelude::*};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+pub fn verify_freezer(args: VerifyFreezerArgs, async_handle: Handle) -> Result<(), ExitCode> {
+    if !args.config.store.freezer_enable {
+        eprintln!("The freezer is not enabled, see the option `store.freezer_enable`");
+        return Err(ExitCode::Config);
+    }
+    let builder = SharedBuilder::new(
+        &args.config.bin_name,
+        args.config.root_dir.as_path(),
+        &args.config.db,
+        Some(args.config.ancient.clone()),
+        async_handle,
+        args.consensus,
+    )?;
+    let (shared, _) = builder.build()?;
+    let store = shared.store();
+    let freezer = store.freezer().ok_or_else(|| {
+        eprintln!("The freezer is not opened");
+        ExitCode::Failure
+    })?;
+
+    // the data files whose blocks are verified one by one
+    let mut files: Vec<(FileId, ChecksumStatus, Range<BlockNumber>)> = Vec::new();
+    let sealed_files = freezer.sealed_files();
+    println!(
+        "Verifying the checksums of {} sealed data files",
+        sealed_files.len()
+    );
+    for file_id in sealed_files.clone() {
+        let status = freezer.verify_checksum(file_id).map_err(|err| {
+            eprintln!("Verify the checksum of the data file {file_id} error {err}");
+            ExitCode::IO
+        })?;
+        match status {
+            ChecksumStatus::Matched if !args.full => continue,
+            ChecksumStatus::Mismatched => {
+                println!("The data file {file_id} does not match the checksum")
+            }
+            _ => {}
+        }
+        files.push((file_id, status, blocks_in_file(freezer, file_id)?));
+    }
+    // the head file is not sealed yet
+    files.push((
+        sealed_files.end,
+        ChecksumStatus::Missing,
+        blocks_in_file(freezer, sealed_files.end)?,
+    ));
+
+    let total: u64 = files
+        .iter()
+        .map(|(_, _, blocks)| blocks.end - blocks.start)
+        .sum();
+    println!("Verifying {total} frozen blocks");
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+            )
+            .progress_chars("#>-"),
+    );
+    let mut corrupted = BTreeMap::new();
+    for (_, _, blocks) in &files {
+        for number in blocks.clone() {
+            let result = match store.get_block_hash(number) {
+                Some(hash) => freezer
+                    .verify_block(number, &hash)
+                    .map_err(|err| err.to_string()),
+                None => Err(format!("block {number} is not on the main chain")),
+            };
+            if let Err(reason) = result {
+                corrupted.insert(number, reason);
+            }
+            pb.inc(1);
+        }
+    }
+    pb.finish_and_clear();
+
+    for (start, end, reason) in corrupted_ranges(&corrupted) {
+        println!("Corrupted blocks {start}..={end}: {reason}");
+    }
+
+    if let Some(url) = &args.refetch {
+        let repaired = refetch(freezer, store, url, &corrupted);
+        corrupted.retain(|number, _| !repaired.contains(number));
+        println!(
+            "Repaired {} blocks, {} blocks remain corrupted",
+            repaired.len(),
+            corrupted.len()
+        );
+    }
+
+    // the verified files sealed before the checksums were introduced, and the repaired files
+    for (file_id, status, blocks) in &files {
+        let intact = corrupted.range(blocks.clone()).next().is_none();
+        if sealed_files.contains(file_id) && status != &ChecksumStatus::Matched && intact {
+            freezer.update_checksum(*file_id).map_err(|err| {
+                eprintln!("Update the checksum of the data file {file_id} error {err}");
+                ExitCode::IO
+            })?;
+        }
+    }
+
+    println!("Checksums root: {:#x}", freezer.checksums_root());
+    if corrupted.is_empty() {
+        println!("The freezer is intact");
+        Ok(())
+    } else {
+        eprintln!(
+            "{} frozen blocks are corrupted, re-fetch them with `--refetch <url>`",
+            corrupted.len()
+        );
+        Err(ExitCode::Failure)
+    }
+}
+
+fn blocks_in_file(freezer: &Freezer, file_id: FileId) -> Result<Range<BlockNumber>, ExitCode> {
+    freezer.blocks_in_file(file_id).map_err(|err| {
+        eprintln!("Read the index of the data file {file_id} error {err}");
+        ExitCode::IO
+    })
+}
+
+// Merges the consecutive corrupted blocks, each range is reported with its first reason.
+fn corrupted_ranges(
+    corrupted: &BTreeMap<BlockNumber, String>,
+) -> Vec<(BlockNumber, BlockNumber, &str)> {
+    let mut ranges: Vec<(BlockNumber, BlockNumber, &str)> = Vec::new();
+    for (number, reason) in corrupted {
+        match ranges.last_mut() {
+            Some((_, end, _)) if *end + 1 == *number => *end = *number,
+            _ => ranges.push((*number, *number, reason)),
+        }
+    }
+    ranges
+}
+
+// Re-fetches the corrupted blocks from the RPC of a live node, returns the repaired ones.
+fn refetch<S: ChainStore>(
+    freezer: &Freezer,
+    store: &S,
+    url: &str,
+    corrupted: &BTreeMap<BlockNumber, String>,
+) -> BTreeSet<BlockNumber> {
+    let client = reqwest::blocking::Client::new();
+    let mut repaired = BTreeSet::new();
+    for number in corrupted.keys() {
+        let expected_hash = match store.get_block_hash(*number) {
+            Some(hash) => hash,
+            None => continue,
+        };
+        let result = fetch_block(&client, url, *number).and_then(|block| {
+            let hash = block.header().calc_header_hash();
+            if hash != expected_hash {
+                return Err(format!(
+                    "the fetched block hash {hash} is not {expected_hash}"
+                ));
+            }
+            freezer
+                .repair_block(*number, &block)
+                .and_then(|_| freezer.verify_block(*number, &expected_hash))
+                .map_err(|err| err.to_string())
+        });
+        match result {
+            Ok(()) => {
+                repaired.insert(*number);
+            }
+            Err(err) => eprintln!("Repair the block {number} error {err}"),
+        }
+    }
+    repaired
+}
+
+fn fetch_block(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    number: BlockNumber,
+) -> Result<packed::Block, String> {
+    let request = serde_json::json!({
+        "id": 1,
+        "jsonrpc": "2.0",
+        "method": "get_block_by_number",
+        "params": [format!("{number:#x}"), "0x0"],
+    });
+    let response: serde_json::Value = client
+        .post(url)
+        .header("content-type", "application/json")
+        .body(request.to_string())
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .map_err(|err| format!("fetch from {url} error {err}"))
+        .and_then(|text| serde_json::from_str(&text).map_err(|err| err.to_string()))?;
+    let bytes: JsonBytes = serde_json::from_value(response["result"].clone())
+        .map_err(|_| format!("unexpected response {response}"))?;
+    packed::BlockReader::from_compatible_slice(bytes.as_bytes())
+        .map(|reader| reader.to_entity())
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,181 @@
+//! A reusable helper for the migrations which backfill data by traversing a column.
+use crate::{internal_error, Migration, ProgressBar, ProgressStyle};
+use ckb_db::{iter::DBIterator, Direction, IteratorMode, RocksDB, RocksDBWriteBatch};
+use ckb_db_schema::Col;
+use ckb_error::{Error, InternalErrorKind};
+use std::iter;
+
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+// The checkpoint of a finished shard.
+const SHARD_DONE: u8 = 1;
+// The checkpoint of an unfinished shard, followed by the next key to process.
+const SHARD_NEXT: u8 = 0;
+
+/// Traverses a column shard by shard, and saves a checkpoint per shard after each batch.
+///
+/// The checkpoint is written in the same write batch as the backfilled data, so an interrupted
+/// backfill resumes from the first key which is not processed yet. The checkpoints are kept in
+/// the default column until `clear_checkpoints` is called.
+///
+/// ```ignore
+/// fn migrate(&self, db: RocksDB, pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>) -> Result<RocksDB, Error> {
+///     let backfill = ShardedBackfill::new(VERSION, COLUMN_CELL_DATA, 16);
+///     backfill.run(&db, self, &*pb, |key, value, wb| {
+///         wb.put(COLUMN_CELL_DATA_HASH, key, &data_hash(value))
+///     })?;
+///     backfill.clear_checkpoints(&db)?;
+///     Ok(db)
+/// }
+/// ```
+pub struct ShardedBackfill {
+    name: String,
+    col: Col,
+    // The start key of each shard, a shard ends before the start key of the next one.
+    starts: Vec<Vec<u8>>,
+    batch_size: usize,
+}
+
+enum Checkpoint {
+    Done,
+    Next(Vec<u8>),
+}
+
+impl ShardedBackfill {
+    /// Splits the key space of the column into `shards` shards evenly by the first byte of keys,
+    /// which suits the columns keyed by hashes.
+    ///
+    /// `name` identifies the checkpoints, the migration version is a good choice.
+    pub fn new(name: &str, col: Col, shards: usize) -> Self {
+        let shards = shards.clamp(1, 256);
+        let boundaries = (1..shards)
+            .map(|i| vec![(i * 256 / shards) as u8])
+            .collect();
+        Self::with_boundaries(name, col, boundaries)
+    }
+
+    /// Splits the key space of the column at the given keys, which suits the columns keyed by
+    /// block numbers or other skewed keys.
+    pub fn with_boundaries(name: &str, col: Col, mut boundaries: Vec<Vec<u8>>) -> Self {
+        boundaries.retain(|key| !key.is_empty());
+        boundaries.sort();
+        boundaries.dedup();
+        ShardedBackfill {
+            name: name.to_string(),
+            col,
+            starts: iter::once(Vec::new()).chain(boundaries).collect(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Sets how many keys are processed in a write batch, 10,000 by default.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Calls `process` for every key-value pair of the column which is not processed by the
+    /// previous runs, `process` puts the backfilled data into the write batch.
+    ///
+    /// Returns an error when `migration.stop_background()` is set, the next run resumes from the
+    /// saved checkpoints.
+    pub fn run<M, F>(
+        &self,
+        db: &RocksDB,
+        migration: &M,
+        pb: &dyn Fn(u64) -> ProgressBar,
+        mut process: F,
+    ) -> Result<(), Error>
+    where
+        M: Migration + ?Sized,
+        F: FnMut(&[u8], &[u8], &mut RocksDBWriteBatch) -> Result<(), Error>,
+    {
+        let estimated_keys = db
+            .property_int_value_cf(self.col, "rocksdb.estimate-num-keys")?
+            .unwrap_or(0);
+        let pbi = pb(estimated_keys);
+        pbi.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{prefix:.bold.dim} {spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}",
+                )
+                .progress_chars("#>-"),
+        );
+        pbi.set_position(0);
+        pbi.enable_steady_tick(5000);
+
+        for shard in 0..self.shards() {
+            let mut next_key = match self.checkpoint(db, shard)? {
+                Checkpoint::Done => continue,
+                Checkpoint::Next(key) => key,
+            };
+            let end = self.starts.get(shard + 1);
+            pbi.set_message(format!("shard {}/{}", shard + 1, self.shards()));
+            loop {
+                if migration.stop_background() {
+                    return Err(InternalErrorKind::Database.other("interrupted").into());
+                }
+                let mut wb = db.new_write_batch();
+                let mut processed = 0;
+                let mut resume_at = None;
+                let iter = db.iter(self.col, IteratorMode::From(&next_key, Direction::Forward))?;
+                for (key, value) in iter {
+                    if end.map_or(false, |end| key.as_ref() >= end.as_slice()) {
+                        break;
+                    }
+                    if processed == self.batch_size {
+                        resume_at = Some(key.to_vec());
+                        break;
+                    }
+                    process(&key[..], &value[..], &mut wb)?;
+                    processed += 1;
+                }
+
+                let checkpoint = match resume_at {
+                    Some(ref key) => iter::once(SHARD_NEXT).chain(key.iter().copied()).collect(),
+                    None => vec![SHARD_DONE],
+                };
+                wb.put_default(&self.checkpoint_key(shard), &checkpoint)?;
+                db.write(&wb)?;
+                pbi.inc(processed as u64);
+
+                match resume_at {
+                    Some(key) => next_key = key,
+                    None => break,
+                }
+            }
+        }
+        pbi.finish_with_message("done");
+        Ok(())
+    }
+
+    /// Removes the checkpoints, a later run will traverse the column from the beginning.
+    pub fn clear_checkpoints(&self, db: &RocksDB) -> Result<(), Error> {
+        let mut wb = db.new_write_batch();
+        for shard in 0..self.shards() {
+            wb.delete_default(&self.checkpoint_key(shard))?;
+        }
+        db.write(&wb)
+    }
+
+    fn checkpoint(&self, db: &RocksDB, shard: usize) -> Result<Checkpoint, Error> {
+        let raw = db.get_pinned_default(&self.checkpoint_key(shard))?;
+        match raw.as_deref() {
+            None => Ok(Checkpoint::Next(self.starts[shard].clone())),
+            Some([SHARD_DONE]) => Ok(Checkpoint::Done),
+            Some([SHARD_NEXT, key @ ..]) => Ok(Checkpoint::Next(key.to_vec())),
+            Some(_) => Err(internal_error(format!(
+                "invalid checkpoint of the backfill {} shard {shard}",
+                self.name
+            ))),
+        }
+    }
+
+    fn checkpoint_key(&self, shard: usize) -> Vec<u8> {
+        format!("backfill-checkpoint-{}-{shard}", self.name).into_bytes()
+    }
+}
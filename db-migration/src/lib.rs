@@ -3,24 +3,102 @@ use ckb_channel::select;
 use ckb_channel::unbounded;
 use ckb_channel::Receiver;
 use ckb_db::{ReadOnlyDB, RocksDB};
-use ckb_db_schema::{COLUMN_META, META_TIP_HEADER_KEY, MIGRATION_VERSION_KEY};
+use ckb_db_schema::{
+    Col, COLUMN_META, META_TIP_HEADER_KEY, MIGRATION_HISTORY_KEY, MIGRATION_PROGRESS_KEY_PREFIX,
+    MIGRATION_VERSION_KEY,
+};
 use ckb_error::{Error, InternalErrorKind};
 use ckb_logger::{debug, error, info};
 use ckb_stop_handler::register_thread;
+use ckb_types::core::service::MigrationOutcome;
 use console::Term;
 pub use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use once_cell::sync::OnceCell;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+mod backfill;
+
+pub use backfill::ShardedBackfill;
 
 /// Shutdown flag for background migration.
 pub static SHUTDOWN_BACKGROUND_MIGRATION: OnceCell<bool> = OnceCell::new();
 
+type OutcomeListener = Box<dyn FnOnce(&MigrationOutcome) + Send>;
+
+// The state of the background migrations, the outcome is kept for the listeners registered after
+// the migrations finish, because the node services are started after the migrations.
+#[derive(Default)]
+enum BackgroundMigrationState {
+    #[default]
+    Idle,
+    Running(Vec<OutcomeListener>),
+    Finished(MigrationOutcome),
+}
+
+/// The outcome of the background migrations started by a [`Migrations`], see
+/// [`Migrations::background_outcome`].
+#[derive(Clone, Default)]
+pub struct BackgroundMigrationOutcome {
+    state: Arc<Mutex<BackgroundMigrationState>>,
+}
+
+impl BackgroundMigrationOutcome {
+    /// Calls `listener` with the outcome once the background migrations finish or fail, or
+    /// immediately if they have finished.
+    ///
+    /// Returns false and drops the listener if no background migrations have been started, or
+    /// they have been interrupted by the shutdown.
+    pub fn on_finished<F>(&self, listener: F) -> bool
+    where
+        F: FnOnce(&MigrationOutcome) + Send + 'static,
+    {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            BackgroundMigrationState::Idle => false,
+            BackgroundMigrationState::Running(ref mut listeners) => {
+                listeners.push(Box::new(listener));
+                true
+            }
+            BackgroundMigrationState::Finished(ref outcome) => {
+                listener(outcome);
+                true
+            }
+        }
+    }
+
+    fn start(&self) {
+        *self.state.lock().unwrap() = BackgroundMigrationState::Running(Vec::new());
+    }
+
+    fn finish(&self, outcome: Option<MigrationOutcome>) {
+        let listeners = {
+            let mut state = self.state.lock().unwrap();
+            let next = match outcome {
+                Some(ref outcome) => BackgroundMigrationState::Finished(outcome.clone()),
+                None => BackgroundMigrationState::Idle,
+            };
+            match std::mem::replace(&mut *state, next) {
+                BackgroundMigrationState::Running(listeners) => listeners,
+                _ => Vec::new(),
+            }
+        };
+        // the listeners are called without the lock, so they can register other listeners
+        if let Some(outcome) = outcome {
+            for listener in listeners {
+                listener(&outcome);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests;
 
@@ -28,10 +106,96 @@ fn internal_error(reason: String) -> Error {
     InternalErrorKind::Database.other(reason).into()
 }
 
+/// A migration which has been applied to the database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationRecord {
+    /// The migration version.
+    pub version: String,
+    /// When the migration was completed, in milliseconds since the unix epoch.
+    pub completed_at: u64,
+    /// How long the migration took, in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl MigrationRecord {
+    // Records are stored one per line, as `<version> <completed_at> <duration_ms>`.
+    fn encode(&self) -> String {
+        format!(
+            "{} {} {}\n",
+            self.version, self.completed_at, self.duration_ms
+        )
+    }
+
+    fn decode_all(raw: &[u8]) -> Vec<MigrationRecord> {
+        String::from_utf8_lossy(raw)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let version = fields.next()?.to_string();
+                let completed_at = fields.next()?.parse().ok()?;
+                let duration_ms = fields.next()?.parse().ok()?;
+                Some(MigrationRecord {
+                    version,
+                    completed_at,
+                    duration_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A migration which is not applied to the database yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationInfo {
+    /// The migration version.
+    pub version: String,
+    /// What the migration does.
+    pub description: String,
+    /// Whether the migration will cost a lot of time.
+    pub expensive: bool,
+    /// Whether the migration is executed in background.
+    pub run_in_background: bool,
+}
+
+// Saves the migration as the current database version, and appends it to the history log.
+fn save_migration_version(db: &RocksDB, version: &str, duration: Duration) -> Result<(), Error> {
+    let record = MigrationRecord {
+        version: version.to_string(),
+        completed_at: ckb_systemtime::unix_time_as_millis(),
+        duration_ms: duration.as_millis() as u64,
+    };
+    // the history is appended in a transaction, which fails to commit if the history is
+    // changed since it's read
+    let txn = db.transaction();
+    let snapshot = txn.get_snapshot();
+    let mut history = txn
+        .get_for_update_default(MIGRATION_HISTORY_KEY, &snapshot)
+        .map_err(|err| internal_error(format!("failed to get the migration history: {err}")))?
+        .map(|raw| raw.to_vec())
+        .unwrap_or_default();
+    history.extend_from_slice(record.encode().as_bytes());
+    txn.put_default(MIGRATION_HISTORY_KEY, &history)?;
+    txn.put_default(MIGRATION_VERSION_KEY, version.as_bytes())?;
+    // the progress is useless once the migration is completed
+    txn.delete(COLUMN_META, &migration_progress_key(version))?;
+    txn.commit()
+        .map_err(|err| internal_error(format!("failed to migrate the database: {err}")))
+}
+
+/// Returns the key in `COLUMN_META` where the progress of the migration is saved.
+///
+/// A migration can write its progress in the same write batch or transaction as a batch of its
+/// data with this key, so the progress never runs ahead of the committed data.
+pub fn migration_progress_key(version: &str) -> Vec<u8> {
+    [MIGRATION_PROGRESS_KEY_PREFIX, version.as_bytes()].concat()
+}
+
 /// TODO(doc): @quake
 #[derive(Default)]
 pub struct Migrations {
     migrations: BTreeMap<String, Arc<dyn Migration>>,
+    threads: Option<usize>,
+    background_outcome: BackgroundMigrationOutcome,
 }
 
 /// Commands
@@ -46,11 +210,26 @@ struct MigrationWorker {
     tasks: Arc<Mutex<MigrationTasks>>,
     db: RocksDB,
     inbox: Receiver<Command>,
+    // the database version before the migrations
+    version: String,
+    outcome: BackgroundMigrationOutcome,
 }
 
 impl MigrationWorker {
-    pub fn new(tasks: Arc<Mutex<MigrationTasks>>, db: RocksDB, inbox: Receiver<Command>) -> Self {
-        Self { tasks, db, inbox }
+    pub fn new(
+        tasks: Arc<Mutex<MigrationTasks>>,
+        db: RocksDB,
+        inbox: Receiver<Command>,
+        version: String,
+        outcome: BackgroundMigrationOutcome,
+    ) -> Self {
+        Self {
+            tasks,
+            db,
+            inbox,
+            version,
+            outcome,
+        }
     }
 
     pub fn start(self) -> JoinHandle<()> {
@@ -59,12 +238,17 @@ impl MigrationWorker {
                 let mut idx = 0;
                 let migrations_count = self.tasks.lock().unwrap().len() as u64;
                 let mpb = Arc::new(MultiProgress::new());
+                let all_started_at = Instant::now();
+                let mut version = self.version.clone();
+                let mut failure = None;
+                let mut interrupted = false;
 
                 while let Some((name, task)) = self.tasks.lock().unwrap().pop_front() {
                     select! {
                         recv(self.inbox) -> msg => {
                             if let Ok(Command::Stop) = msg {
                                 eprintln!("stop to run migrate in background: {}", name);
+                                interrupted = true;
                                 break;
                             }
                         }
@@ -78,16 +262,68 @@ impl MigrationWorker {
                                 pb.set_prefix(format!("[{}/{}]", idx, migrations_count));
                                 pb
                             };
-                            if let Ok(db) = task.migrate(self.db.clone(), Arc::new(pb)) {
-                                db.put_default(MIGRATION_VERSION_KEY, task.version())
-                                .map_err(|err| {
-                                    internal_error(format!("failed to migrate the database: {err}"))
-                                })
-                                .unwrap();
+                            let started_at = Instant::now();
+                            let result = task
+                                .migrate(self.db.clone(), Arc::new(pb))
+                                .and_then(|db| {
+                                    save_migration_version(
+                                        &db,
+                                        task.version(),
+                                        started_at.elapsed(),
+                                    )
+                                });
+                            match result {
+                                Ok(()) => version = task.version().to_string(),
+                                // the migrations resumable after a restart return errors when
+                                // they are stopped
+                                Err(_) if SHUTDOWN_BACKGROUND_MIGRATION.get() == Some(&true) => {
+                                    interrupted = true;
+                                    break;
+                                }
+                                // the version is not saved, and the later migrations still run as
+                                // before, the first failure is reported in the outcome
+                                Err(err) => {
+                                    error!("Background migration {} failed: {}", name, err);
+                                    failure.get_or_insert((name, err.to_string()));
+                                }
                             }
                         }
                     }
                 }
+
+                if interrupted {
+                    self.outcome.finish(None);
+                    return;
+                }
+                let duration_ms = all_started_at.elapsed().as_millis() as u64;
+                let outcome = match failure {
+                    Some((failed_version, err)) => {
+                        error!(
+                            "Background migrations finished in {} ms, but the migration {} \
+                             failed, the database version is {}: {}",
+                            duration_ms, failed_version, version, err
+                        );
+                        MigrationOutcome {
+                            version,
+                            duration_ms,
+                            failed_version: Some(failed_version),
+                            error: Some(err),
+                        }
+                    }
+                    None => {
+                        info!(
+                            "Background migrations finished in {} ms, the database version is {}",
+                            duration_ms, version
+                        );
+                        MigrationOutcome {
+                            version,
+                            duration_ms,
+                            failed_version: None,
+                            error: None,
+                        }
+                    }
+                };
+                self.outcome.finish(Some(outcome));
             }
         })
     }
@@ -98,9 +334,23 @@ impl Migrations {
     pub fn new() -> Self {
         Migrations {
             migrations: BTreeMap::new(),
+            threads: None,
+            background_outcome: BackgroundMigrationOutcome::default(),
         }
     }
 
+    /// Returns the outcome of the background migrations started by this instance.
+    pub fn background_outcome(&self) -> BackgroundMigrationOutcome {
+        self.background_outcome.clone()
+    }
+
+    /// Caps how many independent migrations are run concurrently, see [`Migration::columns`].
+    ///
+    /// It defaults to the available parallelism.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = Some(threads.max(1));
+    }
+
     /// TODO(doc): @quake
     pub fn add_migration(&mut self, migration: Arc<dyn Migration>) {
         self.migrations
@@ -197,6 +447,55 @@ impl Migrations {
             .all(|m| m.run_in_background())
     }
 
+    /// Returns the applied migrations recorded in the history log, in the order of completion.
+    ///
+    /// Migrations applied by binaries which didn't keep the history log are not included.
+    pub fn history(&self, db: &ReadOnlyDB) -> Vec<MigrationRecord> {
+        db.get_pinned_default(MIGRATION_HISTORY_KEY)
+            .expect("get the migration history of database")
+            .map(|raw| MigrationRecord::decode_all(&raw))
+            .unwrap_or_default()
+    }
+
+    /// Returns the versions of the migrations which are newer than the database version.
+    pub fn pending(&self, db: &ReadOnlyDB) -> Vec<String> {
+        self.pending_migrations(db)
+            .into_iter()
+            .map(|info| info.version)
+            .collect()
+    }
+
+    /// Returns the migrations which are newer than the database version, in the order they
+    /// will run, without executing them.
+    pub fn pending_migrations(&self, db: &ReadOnlyDB) -> Vec<MigrationInfo> {
+        let db_version = db
+            .get_pinned_default(MIGRATION_VERSION_KEY)
+            .expect("get the version of database")
+            .map(|version_bytes| {
+                String::from_utf8(version_bytes.to_vec()).expect("version bytes to utf8")
+            });
+        let pending: Vec<&Arc<dyn Migration>> = match db_version {
+            Some(db_version) => self
+                .migrations
+                .values()
+                .filter(|m| m.version() > db_version.as_str())
+                .collect(),
+            // if version is none, but db is not empty
+            // patch 220464f
+            None if self.is_non_empty_rdb(db) => self.migrations.values().collect(),
+            None => Vec::new(),
+        };
+        pending
+            .into_iter()
+            .map(|m| MigrationInfo {
+                version: m.version().to_string(),
+                description: m.description().to_string(),
+                expensive: m.expensive(),
+                run_in_background: m.run_in_background(),
+            })
+            .collect()
+    }
+
     fn is_non_empty_rdb(&self, db: &ReadOnlyDB) -> bool {
         if let Ok(v) = db.get_pinned(COLUMN_META, META_TIP_HEADER_KEY) {
             if v.is_some() {
@@ -215,25 +514,75 @@ impl Migrations {
         false
     }
 
+    // The migrations declaring disjoint columns are run concurrently in batches, the versions of a
+    // batch are saved in order once the batch is done. If a migration of the batch fails, only the
+    // versions before it are saved, the later ones in the batch are run again next time.
     fn run_migrate(&self, mut db: RocksDB, v: &str) -> Result<RocksDB, Error> {
         let mpb = Arc::new(MultiProgress::new());
-        let migrations: BTreeMap<_, _> = self
+        let migrations: Vec<_> = self
             .migrations
             .iter()
             .filter(|(mv, _)| mv.as_str() > v)
+            .map(|(_, m)| Arc::clone(m))
             .collect();
         let migrations_count = migrations.len();
-        for (idx, (_, m)) in migrations.iter().enumerate() {
+        let progress_bar = |idx: usize| {
             let mpbc = Arc::clone(&mpb);
-            let pb = move |count: u64| -> ProgressBar {
+            move |count: u64| -> ProgressBar {
                 let pb = mpbc.add(ProgressBar::new(count));
                 pb.set_draw_target(ProgressDrawTarget::term(Term::stdout(), None));
                 pb.set_prefix(format!("[{}/{}]", idx + 1, migrations_count));
                 pb
-            };
-            db = m.migrate(db, Arc::new(pb))?;
-            db.put_default(MIGRATION_VERSION_KEY, m.version())
-                .map_err(|err| internal_error(format!("failed to migrate the database: {err}")))?;
+            }
+        };
+        let max_parallel = self
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()));
+        let mut idx = 0;
+        for batch in independent_batches(&migrations, max_parallel) {
+            if let [m] = batch.as_slice() {
+                let started_at = Instant::now();
+                db = m.migrate(db, Arc::new(progress_bar(idx)))?;
+                save_migration_version(&db, m.version(), started_at.elapsed())?;
+                idx += 1;
+                continue;
+            }
+            info!(
+                "run migrations {} in parallel",
+                batch
+                    .iter()
+                    .map(|m| m.version())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            let results: Vec<_> = thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .enumerate()
+                    .map(|(i, m)| {
+                        let db = db.clone();
+                        let pb = progress_bar(idx + i);
+                        thread::Builder::new()
+                            .name(format!("migration-{}", m.version()))
+                            .spawn_scoped(scope, move || {
+                                let started_at = Instant::now();
+                                m.migrate(db, Arc::new(pb))
+                                    .map(|db| (db, started_at.elapsed()))
+                            })
+                            .expect("spawn migration thread")
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("migration thread panicked"))
+                    .collect()
+            });
+            for (m, result) in batch.iter().zip(results) {
+                let (migrated, duration) = result?;
+                db = migrated;
+                save_migration_version(&db, m.version(), duration)?;
+            }
+            idx += batch.len();
         }
         mpb.join_and_clear().expect("MultiProgress join");
         Ok(db)
@@ -250,7 +599,14 @@ impl Migrations {
         let all_can_resume = migrations.iter().all(|(_, m)| m.can_resume());
         let tasks = Arc::new(Mutex::new(migrations));
         let (tx, rx) = unbounded();
-        let worker = MigrationWorker::new(tasks, db, rx);
+        self.background_outcome.start();
+        let worker = MigrationWorker::new(
+            tasks,
+            db,
+            rx,
+            v.to_string(),
+            self.background_outcome.clone(),
+        );
 
         let exit_signal = ckb_stop_handler::new_crossbeam_exit_rx();
         let clone = v.to_string();
@@ -358,6 +714,11 @@ pub trait Migration: Send + Sync {
     /// returns migration version, use `date +'%Y%m%d%H%M%S'` timestamp format
     fn version(&self) -> &str;
 
+    /// A short description of what the migration does, shown to the operators before migrating.
+    fn description(&self) -> &str {
+        ""
+    }
+
     /// Will cost a lot of time to perform this migration operation.
     ///
     /// Override this function for `Migrations` which could be executed very fast.
@@ -372,7 +733,8 @@ pub trait Migration: Send + Sync {
 
     /// Check if the background migration should be stopped.
     /// If a migration need to implement the recovery logic, it should check this flag periodically,
-    /// store the migration progress when exiting and recover from the current progress when restarting.
+    /// store the migration progress when exiting and recover from the current progress when restarting,
+    /// see `save_progress` and `load_progress`.
     fn stop_background(&self) -> bool {
         *SHUTDOWN_BACKGROUND_MIGRATION.get().unwrap_or(&false)
     }
@@ -388,6 +750,66 @@ pub trait Migration: Send + Sync {
     fn can_resume(&self) -> bool {
         false
     }
+
+    /// Saves the progress of the migration, which is opaque to the migration framework.
+    ///
+    /// The progress is kept in `COLUMN_META` keyed by the migration version until the migration
+    /// is completed, see also [`migration_progress_key`].
+    fn save_progress(&self, db: &RocksDB, progress: &[u8]) -> Result<(), Error> {
+        let mut wb = db.new_write_batch();
+        wb.put(
+            COLUMN_META,
+            &migration_progress_key(self.version()),
+            progress,
+        )?;
+        db.write(&wb)
+    }
+
+    /// Loads the progress saved by the previous run, which is interrupted before the migration
+    /// is completed.
+    fn load_progress(&self, db: &RocksDB) -> Result<Option<Vec<u8>>, Error> {
+        let progress = db.get_pinned(COLUMN_META, &migration_progress_key(self.version()))?;
+        Ok(progress.map(|progress| progress.to_vec()))
+    }
+
+    /// The columns the migration writes, and the columns it reads which other migrations may
+    /// write.
+    ///
+    /// The consecutive migrations declaring disjoint columns are run concurrently. The migration
+    /// declaring nothing is run alone, which is the default. A migration declaring its columns
+    /// must return the database it's given rather than reopening it, and may be run again after
+    /// it has completed if a migration run alongside fails.
+    fn columns(&self) -> &[Col] {
+        &[]
+    }
+}
+
+// Splits the migrations in order into the batches run concurrently. A batch has at most `max`
+// migrations and their declared columns are disjoint, a migration declaring no columns is a batch
+// by itself.
+fn independent_batches(
+    migrations: &[Arc<dyn Migration>],
+    max: usize,
+) -> Vec<Vec<Arc<dyn Migration>>> {
+    let mut batches: Vec<Vec<Arc<dyn Migration>>> = Vec::new();
+    let mut columns: HashSet<Col> = HashSet::new();
+    for m in migrations {
+        let joinable = !m.columns().is_empty()
+            && batches.last().map_or(false, |batch| {
+                batch.len() < max && !batch[0].columns().is_empty()
+            })
+            && m.columns().iter().all(|col| !columns.contains(col));
+        if !joinable {
+            batches.push(Vec::new());
+            columns.clear();
+        }
+        columns.extend(m.columns());
+        batches
+            .last_mut()
+            .expect("pushed above")
+            .push(Arc::clone(m));
+    }
+    batches
 }
 
 /// TODO(doc): @quake
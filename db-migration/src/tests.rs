@@ -1,12 +1,15 @@
 use ckb_app_config::DBConfig;
 use ckb_db::ReadOnlyDB;
 use ckb_db::RocksDB;
-use ckb_db_schema::MIGRATION_VERSION_KEY;
-use ckb_error::Error;
+use ckb_db_schema::{Col, COLUMNS, MIGRATION_VERSION_KEY};
+use ckb_error::{Error, InternalErrorKind};
 use indicatif::ProgressBar;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use crate::{DefaultMigration, Migration, Migrations};
+use crate::{
+    independent_batches, DefaultMigration, Migration, MigrationInfo, Migrations, ShardedBackfill,
+};
 
 #[test]
 fn test_default_migration() {
@@ -132,12 +135,21 @@ fn test_background_migration() {
 
     pub struct BackgroundMigration {
         version: String,
+        fail: bool,
     }
 
     impl BackgroundMigration {
         pub fn new(version: &str) -> Self {
             BackgroundMigration {
                 version: version.to_string(),
+                fail: false,
+            }
+        }
+
+        pub fn failing(version: &str) -> Self {
+            BackgroundMigration {
+                version: version.to_string(),
+                fail: true,
             }
         }
     }
@@ -152,6 +164,9 @@ fn test_background_migration() {
             db: RocksDB,
             _pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>,
         ) -> Result<RocksDB, Error> {
+            if self.fail {
+                return Err(InternalErrorKind::Database.other("failed").into());
+            }
             let db_tx = db.transaction();
             let v = self.version.as_bytes();
             db_tx.put("1", v, &[1])?;
@@ -233,6 +248,7 @@ fn test_background_migration() {
         let mut migrations = Migrations::default();
         migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
         migrations.add_migration(Arc::new(BackgroundMigration::new("20231127101121")));
+        migrations.add_migration(Arc::new(BackgroundMigration::failing("20231127101122")));
         migrations.add_migration(Arc::new(BackgroundMigration::new("20241127101122")));
 
         let db = ReadOnlyDB::open_cf(&config.path, vec!["4"])
@@ -240,6 +256,7 @@ fn test_background_migration() {
             .unwrap();
 
         assert!(migrations.can_run_in_background(&db));
+        let background_outcome = migrations.background_outcome();
         let db = migrations
             .migrate(RocksDB::open(&config, 12), true)
             .unwrap();
@@ -254,6 +271,22 @@ fn test_background_migration() {
                 .to_vec()
         );
 
+        // the listener registered after the migrations finish still gets the outcome
+        let outcome = Arc::new(std::sync::Mutex::new(None));
+        let outcome_clone = Arc::clone(&outcome);
+        assert!(background_outcome.on_finished(move |outcome| {
+            *outcome_clone.lock().unwrap() = Some(outcome.clone());
+        }));
+        // the failed migration doesn't stop the later ones
+        let outcome = outcome.lock().unwrap().take().unwrap();
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.failed_version.as_deref(), Some("20231127101122"));
+        assert_eq!(outcome.version, "20241127101122");
+        // the outcome is kept by the instance which started the migrations
+        assert!(!Migrations::default()
+            .background_outcome()
+            .on_finished(|_| unreachable!()));
+
         // confirm the background migration is executed
         let db_tx = db.transaction();
         let v = db_tx
@@ -300,3 +333,442 @@ fn test_background_migration() {
         assert_eq!(v, vec![2]);
     }
 }
+
+#[test]
+fn test_migration_history() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_migration_history")
+        .tempdir()
+        .unwrap();
+    let config = DBConfig {
+        path: tmp_dir.as_ref().to_path_buf(),
+        ..Default::default()
+    };
+    {
+        let mut migrations = Migrations::default();
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+        let db = RocksDB::open(&config, 1);
+        migrations.init_db_version(&db).unwrap();
+    }
+    {
+        let mut migrations = Migrations::default();
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191127101121")));
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191128101121")));
+
+        let db = ReadOnlyDB::open_cf(&config.path, vec!["0"])
+            .unwrap()
+            .unwrap();
+        // the initial version is not a migration run, so it is not recorded
+        assert!(migrations.history(&db).is_empty());
+        assert_eq!(
+            migrations.pending(&db),
+            vec!["20191127101121".to_string(), "20191128101121".to_string()]
+        );
+        drop(db);
+
+        migrations
+            .migrate(RocksDB::open(&config, 1), false)
+            .unwrap();
+
+        let db = ReadOnlyDB::open_cf(&config.path, vec!["0"])
+            .unwrap()
+            .unwrap();
+        let versions: Vec<_> = migrations
+            .history(&db)
+            .into_iter()
+            .map(|record| record.version)
+            .collect();
+        assert_eq!(
+            versions,
+            vec!["20191127101121".to_string(), "20191128101121".to_string()]
+        );
+        assert!(migrations.pending(&db).is_empty());
+    }
+}
+
+#[test]
+fn test_pending_migrations() {
+    struct BackgroundMigration;
+
+    impl Migration for BackgroundMigration {
+        fn migrate(
+            &self,
+            db: RocksDB,
+            _pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>,
+        ) -> Result<RocksDB, Error> {
+            Ok(db)
+        }
+
+        fn version(&self) -> &str {
+            "20191128101121"
+        }
+
+        fn description(&self) -> &str {
+            "Backfill something"
+        }
+
+        fn run_in_background(&self) -> bool {
+            true
+        }
+    }
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_pending_migrations")
+        .tempdir()
+        .unwrap();
+    let config = DBConfig {
+        path: tmp_dir.as_ref().to_path_buf(),
+        ..Default::default()
+    };
+    {
+        let mut migrations = Migrations::default();
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+        let db = RocksDB::open(&config, 1);
+        migrations.init_db_version(&db).unwrap();
+    }
+
+    let mut migrations = Migrations::default();
+    migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+    migrations.add_migration(Arc::new(DefaultMigration::new("20191127101121")));
+    migrations.add_migration(Arc::new(BackgroundMigration));
+    let db = ReadOnlyDB::open_cf(&config.path, vec!["0"])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        migrations.pending_migrations(&db),
+        vec![
+            MigrationInfo {
+                version: "20191127101121".to_string(),
+                description: String::new(),
+                expensive: false,
+                run_in_background: false,
+            },
+            MigrationInfo {
+                version: "20191128101121".to_string(),
+                description: "Backfill something".to_string(),
+                expensive: true,
+                run_in_background: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_sharded_backfill_resume() {
+    // stops the backfill after the given number of batches
+    struct StoppableMigration {
+        batches: AtomicUsize,
+    }
+
+    impl Migration for StoppableMigration {
+        fn migrate(
+            &self,
+            db: RocksDB,
+            _pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>,
+        ) -> Result<RocksDB, Error> {
+            Ok(db)
+        }
+
+        fn version(&self) -> &str {
+            "20191127101121"
+        }
+
+        fn stop_background(&self) -> bool {
+            self.batches
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_err()
+        }
+    }
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_sharded_backfill_resume")
+        .tempdir()
+        .unwrap();
+    let config = DBConfig {
+        path: tmp_dir.as_ref().to_path_buf(),
+        ..Default::default()
+    };
+    let db = RocksDB::open(&config, 2);
+    let mut wb = db.new_write_batch();
+    for i in 0..200usize {
+        let key = [(i * 256 / 200) as u8, i as u8];
+        wb.put("0", &key, &key).unwrap();
+    }
+    db.write(&wb).unwrap();
+
+    let backfill = ShardedBackfill::new("test", "0", 4).batch_size(10);
+    assert_eq!(backfill.shards(), 4);
+    let processed = AtomicUsize::new(0);
+    let copy = |key: &[u8], value: &[u8], wb: &mut ckb_db::RocksDBWriteBatch| {
+        processed.fetch_add(1, Ordering::SeqCst);
+        wb.put("1", key, value)
+    };
+    let pb = |_: u64| ProgressBar::hidden();
+
+    let interrupted = StoppableMigration {
+        batches: AtomicUsize::new(7),
+    };
+    assert!(backfill.run(&db, &interrupted, &pb, copy).is_err());
+    assert_eq!(processed.load(Ordering::SeqCst), 70);
+
+    // resumes from the checkpoints, no key is processed twice
+    let resumed = StoppableMigration {
+        batches: AtomicUsize::new(usize::MAX),
+    };
+    backfill.run(&db, &resumed, &pb, copy).unwrap();
+    assert_eq!(processed.load(Ordering::SeqCst), 200);
+    let mut copied = 0;
+    db.full_traverse("1", &mut |key: &[u8], value: &[u8]| {
+        assert_eq!(key, value);
+        copied += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(copied, 200);
+
+    // all the shards are done
+    backfill.run(&db, &resumed, &pb, copy).unwrap();
+    assert_eq!(processed.load(Ordering::SeqCst), 200);
+
+    backfill.clear_checkpoints(&db).unwrap();
+    assert!(db
+        .get_pinned_default(b"backfill-checkpoint-test-0")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_migration_progress() {
+    // the first run saves the progress and fails as interrupted, the second run resumes
+    struct ResumableMigration {
+        runs: AtomicUsize,
+    }
+
+    impl Migration for ResumableMigration {
+        fn migrate(
+            &self,
+            db: RocksDB,
+            _pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>,
+        ) -> Result<RocksDB, Error> {
+            if self.runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                assert_eq!(self.load_progress(&db)?, None);
+                self.save_progress(&db, b"batch-1")?;
+                return Err(InternalErrorKind::Database.other("interrupted").into());
+            }
+            assert_eq!(self.load_progress(&db)?, Some(b"batch-1".to_vec()));
+            Ok(db)
+        }
+
+        fn version(&self) -> &str {
+            "20191127101121"
+        }
+
+        fn can_resume(&self) -> bool {
+            true
+        }
+    }
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_migration_progress")
+        .tempdir()
+        .unwrap();
+    let config = DBConfig {
+        path: tmp_dir.as_ref().to_path_buf(),
+        ..Default::default()
+    };
+    {
+        let mut migrations = Migrations::default();
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+        let db = RocksDB::open(&config, COLUMNS);
+        migrations.init_db_version(&db).unwrap();
+    }
+
+    let resumable = Arc::new(ResumableMigration {
+        runs: AtomicUsize::new(0),
+    });
+    let mut migrations = Migrations::default();
+    migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+    migrations.add_migration(Arc::clone(&resumable) as Arc<dyn Migration>);
+
+    assert!(migrations
+        .migrate(RocksDB::open(&config, COLUMNS), false)
+        .is_err());
+    let db = migrations
+        .migrate(RocksDB::open(&config, COLUMNS), false)
+        .unwrap();
+    assert_eq!(resumable.runs.load(Ordering::SeqCst), 2);
+
+    // the progress is removed once the migration is completed
+    assert_eq!(resumable.load_progress(&db).unwrap(), None);
+}
+
+struct ColumnMigration {
+    version: &'static str,
+    columns: Vec<Col>,
+    fail: bool,
+    runs: AtomicUsize,
+}
+
+impl ColumnMigration {
+    fn new(version: &'static str, columns: Vec<Col>) -> Self {
+        ColumnMigration {
+            version,
+            columns,
+            fail: false,
+            runs: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Migration for ColumnMigration {
+    fn migrate(
+        &self,
+        db: RocksDB,
+        _pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>,
+    ) -> Result<RocksDB, Error> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        if self.fail {
+            return Err(InternalErrorKind::Database.other("failed").into());
+        }
+        if let Some(col) = self.columns.first() {
+            let mut wb = db.new_write_batch();
+            wb.put(col, self.version.as_bytes(), b"migrated")?;
+            db.write(&wb)?;
+        }
+        Ok(db)
+    }
+
+    fn version(&self) -> &str {
+        self.version
+    }
+
+    fn columns(&self) -> &[Col] {
+        &self.columns
+    }
+}
+
+#[test]
+fn test_independent_batches() {
+    let migrations: Vec<Arc<dyn Migration>> = vec![
+        Arc::new(ColumnMigration::new("1", vec!["1"])),
+        Arc::new(ColumnMigration::new("2", vec!["2", "3"])),
+        // overlaps with the previous one
+        Arc::new(ColumnMigration::new("3", vec!["3"])),
+        Arc::new(ColumnMigration::new("4", vec!["4"])),
+        // declares nothing
+        Arc::new(ColumnMigration::new("5", vec![])),
+        Arc::new(ColumnMigration::new("6", vec!["1"])),
+        Arc::new(ColumnMigration::new("7", vec!["2"])),
+        Arc::new(ColumnMigration::new("8", vec!["5"])),
+    ];
+    let versions = |max| {
+        independent_batches(&migrations, max)
+            .iter()
+            .map(|batch| batch.iter().map(|m| m.version()).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        versions(4),
+        vec![
+            vec!["1", "2"],
+            vec!["3", "4"],
+            vec!["5"],
+            vec!["6", "7", "8"]
+        ]
+    );
+    assert_eq!(
+        versions(2),
+        vec![
+            vec!["1", "2"],
+            vec!["3", "4"],
+            vec!["5"],
+            vec!["6", "7"],
+            vec!["8"]
+        ]
+    );
+    assert_eq!(versions(1).len(), migrations.len());
+}
+
+#[test]
+fn test_parallel_migrations() {
+    // the three migrations are run in a batch
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_parallel_migrations")
+        .tempdir()
+        .unwrap();
+    let config = DBConfig {
+        path: tmp_dir.as_ref().to_path_buf(),
+        ..Default::default()
+    };
+    {
+        let mut migrations = Migrations::default();
+        migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+        let db = RocksDB::open(&config, COLUMNS);
+        migrations.init_db_version(&db).unwrap();
+    }
+
+    let first = Arc::new(ColumnMigration::new("20191127101121", vec!["1"]));
+    let failing = Arc::new(ColumnMigration {
+        fail: true,
+        ..ColumnMigration::new("20191127101122", vec!["2"])
+    });
+    let last = Arc::new(ColumnMigration::new("20191127101123", vec!["3"]));
+    let mut migrations = Migrations::default();
+    migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+    migrations.add_migration(Arc::clone(&first) as Arc<dyn Migration>);
+    migrations.add_migration(Arc::clone(&failing) as Arc<dyn Migration>);
+    migrations.add_migration(Arc::clone(&last) as Arc<dyn Migration>);
+    migrations.set_threads(4);
+    assert!(migrations
+        .migrate(RocksDB::open(&config, COLUMNS), false)
+        .is_err());
+
+    // only the migrations before the failed one are saved
+    let db = ReadOnlyDB::open_cf(&config.path, vec!["0"])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        migrations.pending(&db),
+        vec!["20191127101122".to_string(), "20191127101123".to_string()]
+    );
+    drop(db);
+
+    let fixed = Arc::new(ColumnMigration::new("20191127101122", vec!["2"]));
+    let mut migrations = Migrations::default();
+    migrations.add_migration(Arc::new(DefaultMigration::new("20191116225943")));
+    migrations.add_migration(Arc::clone(&first) as Arc<dyn Migration>);
+    migrations.add_migration(Arc::clone(&fixed) as Arc<dyn Migration>);
+    migrations.add_migration(Arc::clone(&last) as Arc<dyn Migration>);
+    migrations.set_threads(4);
+    let db = migrations
+        .migrate(RocksDB::open(&config, COLUMNS), false)
+        .unwrap();
+    assert_eq!(first.runs.load(Ordering::SeqCst), 1);
+    assert_eq!(last.runs.load(Ordering::SeqCst), 2);
+    for (col, version) in [
+        ("1", "20191127101121"),
+        ("2", "20191127101122"),
+        ("3", "20191127101123"),
+    ] {
+        assert!(db.get_pinned(col, version.as_bytes()).unwrap().is_some());
+    }
+    drop(db);
+
+    let db = ReadOnlyDB::open_cf(&config.path, vec!["0"])
+        .unwrap()
+        .unwrap();
+    let versions: Vec<_> = migrations
+        .history(&db)
+        .into_iter()
+        .map(|record| record.version)
+        .collect();
+    assert_eq!(
+        versions,
+        vec![
+            "20191127101121".to_string(),
+            "20191127101122".to_string(),
+            "20191127101123".to_string()
+        ]
+    );
+}
@@ -3,7 +3,7 @@
 /// Column families alias type
 pub type Col = &'static str;
 /// Total column number
-pub const COLUMNS: u32 = 19;
+pub const COLUMNS: u32 = 20;
 /// Column store chain index
 pub const COLUMN_INDEX: Col = "0";
 /// Column store block's header
@@ -44,6 +44,8 @@ pub const COLUMN_CHAIN_ROOT_MMR: Col = "16";
 pub const COLUMN_BLOCK_FILTER: Col = "17";
 /// Column store filter data hash for client-side filtering
 pub const COLUMN_BLOCK_FILTER_HASH: Col = "18";
+/// Column store aggregated statistics of the main chain blocks per epoch
+pub const COLUMN_EPOCH_STATS: Col = "19";
 
 /// META_TIP_HEADER_KEY tracks the latest known best block header
 pub const META_TIP_HEADER_KEY: &[u8] = b"TIP_HEADER";
@@ -51,8 +53,190 @@ pub const META_TIP_HEADER_KEY: &[u8] = b"TIP_HEADER";
 pub const META_CURRENT_EPOCH_KEY: &[u8] = b"CURRENT_EPOCH";
 /// META_FILTER_DATA_KEY tracks the latest built filter data block hash
 pub const META_LATEST_BUILT_FILTER_DATA_KEY: &[u8] = b"LATEST_BUILT_FILTER_DATA";
+/// META_PRUNED_UNCLES_AND_PROPOSALS_KEY tracks the number of the latest main chain block whose
+/// uncle proposals and proposal ids are pruned
+pub const META_PRUNED_UNCLES_AND_PROPOSALS_KEY: &[u8] = b"PRUNED_UNCLES_AND_PROPOSALS";
 
 /// CHAIN_SPEC_HASH_KEY tracks the hash of chain spec which created current database
 pub const CHAIN_SPEC_HASH_KEY: &[u8] = b"chain-spec-hash";
 /// MIGRATION_VERSION_KEY tracks the current database version.
 pub const MIGRATION_VERSION_KEY: &[u8] = b"db-version";
+/// MIGRATION_HISTORY_KEY tracks the applied migrations with their completion time and duration.
+pub const MIGRATION_HISTORY_KEY: &[u8] = b"db-migration-history";
+/// MIGRATION_PROGRESS_KEY_PREFIX followed by the migration version tracks the progress of an
+/// unfinished migration in `COLUMN_META`.
+pub const MIGRATION_PROGRESS_KEY_PREFIX: &[u8] = b"db-migration-progress-";
+
+/// The documentation of a column family: its purpose and the formats of its keys and values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// The column family
+    pub col: Col,
+    /// The name of the column constant
+    pub name: &'static str,
+    /// What the column stores
+    pub description: &'static str,
+    /// The format of the keys
+    pub key: &'static str,
+    /// The format of the values
+    pub value: &'static str,
+}
+
+/// The documentation of all the columns, ordered by the column family.
+pub const COLUMN_INFOS: [ColumnInfo; COLUMNS as usize] = [
+    ColumnInfo {
+        col: COLUMN_INDEX,
+        name: "COLUMN_INDEX",
+        description: "chain index",
+        key: "block number (packed::Uint64) or block hash (packed::Byte32)",
+        value: "block hash (packed::Byte32) or block number (packed::Uint64)",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_HEADER,
+        name: "COLUMN_BLOCK_HEADER",
+        description: "block's header",
+        key: "block hash (packed::Byte32)",
+        value: "packed::HeaderView",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_BODY,
+        name: "COLUMN_BLOCK_BODY",
+        description: "block's body",
+        key: "block number and hash with transaction index (packed::TransactionKey)",
+        value: "packed::TransactionView",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_UNCLE,
+        name: "COLUMN_BLOCK_UNCLE",
+        description: "block's uncle and uncles' proposal zones",
+        key: "block hash (packed::Byte32)",
+        value: "packed::UncleBlockVecView",
+    },
+    ColumnInfo {
+        col: COLUMN_META,
+        name: "COLUMN_META",
+        description: "meta data",
+        key: "meta key, such as TIP_HEADER and CURRENT_EPOCH",
+        value: "block hash (packed::Byte32)",
+    },
+    ColumnInfo {
+        col: COLUMN_TRANSACTION_INFO,
+        name: "COLUMN_TRANSACTION_INFO",
+        description: "transaction extra information",
+        key: "transaction hash (packed::Byte32)",
+        value: "packed::TransactionInfo",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_EXT,
+        name: "COLUMN_BLOCK_EXT",
+        description: "block extra information",
+        key: "block hash (packed::Byte32)",
+        value: "packed::BlockExtV1",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_PROPOSAL_IDS,
+        name: "COLUMN_BLOCK_PROPOSAL_IDS",
+        description: "block's proposal ids",
+        key: "block hash (packed::Byte32)",
+        value: "packed::ProposalShortIdVec",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_EPOCH,
+        name: "COLUMN_BLOCK_EPOCH",
+        description: "the epoch which a block belongs to",
+        key: "block hash (packed::Byte32)",
+        value: "epoch index (packed::Byte32)",
+    },
+    ColumnInfo {
+        col: COLUMN_EPOCH,
+        name: "COLUMN_EPOCH",
+        description: "epoch",
+        key: "epoch number (packed::Uint64) or epoch index (packed::Byte32)",
+        value: "epoch index (packed::Byte32) or packed::EpochExt",
+    },
+    ColumnInfo {
+        col: COLUMN_CELL,
+        name: "COLUMN_CELL",
+        description: "live cell",
+        key: "out point (packed::OutPoint)",
+        value: "packed::CellEntry",
+    },
+    ColumnInfo {
+        col: COLUMN_UNCLES,
+        name: "COLUMN_UNCLES",
+        description: "main chain consensus include uncles",
+        key: "uncle hash (packed::Byte32)",
+        value: "packed::HeaderView",
+    },
+    ColumnInfo {
+        col: COLUMN_CELL_DATA,
+        name: "COLUMN_CELL_DATA",
+        description: "live cell data",
+        key: "out point (packed::OutPoint)",
+        value: "packed::CellDataEntry",
+    },
+    ColumnInfo {
+        col: COLUMN_NUMBER_HASH,
+        name: "COLUMN_NUMBER_HASH",
+        description: "block number-hash pair",
+        key: "packed::NumberHash",
+        value: "transactions count (packed::Uint32)",
+    },
+    ColumnInfo {
+        col: COLUMN_CELL_DATA_HASH,
+        name: "COLUMN_CELL_DATA_HASH",
+        description: "live cell data hash",
+        key: "out point (packed::OutPoint)",
+        value: "data hash (packed::Byte32)",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_EXTENSION,
+        name: "COLUMN_BLOCK_EXTENSION",
+        description: "block extension data",
+        key: "block hash (packed::Byte32)",
+        value: "packed::Bytes",
+    },
+    ColumnInfo {
+        col: COLUMN_CHAIN_ROOT_MMR,
+        name: "COLUMN_CHAIN_ROOT_MMR",
+        description: "chain root MMR data",
+        key: "MMR position (packed::Uint64)",
+        value: "packed::HeaderDigest",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_FILTER,
+        name: "COLUMN_BLOCK_FILTER",
+        description: "filter data for client-side filtering",
+        key: "block hash (packed::Byte32)",
+        value: "packed::Bytes",
+    },
+    ColumnInfo {
+        col: COLUMN_BLOCK_FILTER_HASH,
+        name: "COLUMN_BLOCK_FILTER_HASH",
+        description: "filter data hash for client-side filtering",
+        key: "block hash (packed::Byte32)",
+        value: "packed::Byte32",
+    },
+    ColumnInfo {
+        col: COLUMN_EPOCH_STATS,
+        name: "COLUMN_EPOCH_STATS",
+        description: "aggregated statistics of the main chain blocks per epoch",
+        key: "epoch number (packed::Uint64)",
+        value: "block count, tx count, total fees and total cycles (packed::Uint64Vec)",
+    },
+];
+
+/// Generates the markdown documentation of the database schema from [`COLUMN_INFOS`].
+pub fn generate_schema_doc() -> String {
+    let mut doc = String::from(
+        "| Column | Name | Description | Key | Value |\n\
+         | ------ | ---- | ----------- | --- | ----- |\n",
+    );
+    for info in COLUMN_INFOS.iter() {
+        doc.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            info.col, info.name, info.description, info.key, info.value
+        ));
+    }
+    doc
+}
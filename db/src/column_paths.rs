@@ -0,0 +1,127 @@
+//! Place the columns in the directories other than the database directory, e.g., the live cells
+//! and the headers on a fast disk, while the block bodies stay on a slow one.
+//!
+//! A column placed in a directory has two paths for its SST files, the database directory with the
+//! target size 0 and its own directory. RocksDB still finds the existing files of the column in the
+//! database directory, and the files written by the compactions go to its own directory, so the
+//! database opens once the directory is configured, and the data moves over as the column is
+//! compacted. The memtable flushes are always written to the first path, they stay in the database
+//! directory until they are compacted.
+use crate::{internal_error, Result};
+use ckb_app_config::DBConfig;
+use ckb_db_schema::{Col, COLUMN_INFOS, COLUMN_META};
+use rocksdb::{DBPath, Options};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The files RocksDB writes to the paths of a column
+const COLUMN_FILE_EXTENSIONS: [&str; 2] = ["sst", "blob"];
+
+/// Resolves `db.column_paths` by the column names.
+///
+/// The version of the database is read from `COLUMN_META` before the database is opened, so it
+/// can't be placed in another directory. A directory overlapping the database directory, other
+/// than the database directory itself, or holding files other than the column files, is refused,
+/// since the obsolete column files are deleted from it and copying the database would miss or
+/// duplicate them.
+pub fn resolve_column_paths(config: &DBConfig) -> Result<HashMap<Col, PathBuf>> {
+    config
+        .column_paths
+        .iter()
+        .map(|(name, dir)| {
+            let info = COLUMN_INFOS
+                .iter()
+                .find(|info| info.name == name.as_str())
+                .ok_or_else(|| {
+                    internal_error(format!("unknown column {name} in db.column_paths"))
+                })?;
+            if info.col == COLUMN_META {
+                return Err(internal_error(format!(
+                    "the column {name} can't be placed in another directory"
+                )));
+            }
+            if dir.exists() && !dir.is_dir() {
+                return Err(internal_error(format!(
+                    "the path {} of the column {name} is not a directory",
+                    dir.display()
+                )));
+            }
+            // the column is moved back by setting its directory to the database directory
+            let db_path = &config.path;
+            if dir == db_path {
+                return Ok((info.col, dir.clone()));
+            }
+            if !db_path.as_os_str().is_empty()
+                && (dir.starts_with(db_path) || db_path.starts_with(dir))
+            {
+                return Err(internal_error(format!(
+                    "the path {} of the column {name} overlaps the database directory {}",
+                    dir.display(),
+                    db_path.display()
+                )));
+            }
+            if let Some(other) = find_other_file(dir) {
+                return Err(internal_error(format!(
+                    "the path {} of the column {name} is shared with other data, e.g., {}",
+                    dir.display(),
+                    other.display()
+                )));
+            }
+            Ok((info.col, dir.clone()))
+        })
+        .collect()
+}
+
+// Returns a file or directory in `dir` which is not written by RocksDB for the columns.
+fn find_other_file(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            !path.is_file()
+                || !path.extension().map_or(false, |extension| {
+                    COLUMN_FILE_EXTENSIONS
+                        .iter()
+                        .any(|column_extension| extension == *column_extension)
+                })
+        })
+}
+
+/// Moves the files of the column placed in `dir` from the checkpoint `checkpoint` to `copy`, the
+/// copy of `dir`.
+///
+/// A checkpoint puts the files of all the paths in its own directory, but the database records
+/// which path of the column every file is in, so the files found in `dir` must be in the copy of
+/// `dir` to open the checkpoint with the column placed in `copy`. Returns how many files are moved.
+pub fn move_column_files(checkpoint: &Path, dir: &Path, copy: &Path) -> Result<usize> {
+    let io_error = |err: std::io::Error| internal_error(format!("move the column files: {err}"));
+    let mut moved = 0;
+    for entry in fs::read_dir(dir).map_err(io_error)?.flatten() {
+        let file = checkpoint.join(entry.file_name());
+        if !file.is_file() {
+            continue;
+        }
+        fs::create_dir_all(copy).map_err(io_error)?;
+        let to = copy.join(entry.file_name());
+        // the copy may be on another filesystem
+        if fs::rename(&file, &to).is_err() {
+            fs::copy(&file, &to).map_err(io_error)?;
+            fs::remove_file(&file).map_err(io_error)?;
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// Sets the paths of the column, the existing files are found in `db_path` and the new ones are
+/// written to `dir`.
+pub fn set_column_path(opts: &mut Options, db_path: &Path, dir: &Path) -> Result<()> {
+    let paths = [
+        DBPath::new(db_path, 0).map_err(internal_error)?,
+        DBPath::new(dir, u64::MAX).map_err(internal_error)?,
+    ];
+    opts.set_cf_paths(&paths);
+    Ok(())
+}
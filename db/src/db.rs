@@ -1,21 +1,29 @@
 //! RocksDB wrapper base on OptimisticTransactionDB
+use crate::column_paths::{resolve_column_paths, set_column_path};
+use crate::iter::{prefetch_read_options, DEFAULT_PREFETCH_SIZE};
 use crate::snapshot::RocksDBSnapshot;
 use crate::transaction::RocksDBTransaction;
+use crate::wal_archive::{check_wal_archive_dir, WAL_TTL_SECONDS};
 use crate::write_batch::RocksDBWriteBatch;
 use crate::{internal_error, Result};
 use ckb_app_config::DBConfig;
 use ckb_db_schema::Col;
 use ckb_logger::info;
+use libc::{c_char, size_t};
 use rocksdb::ops::{
-    CompactRangeCF, CreateCF, DropCF, GetColumnFamilys, GetPinned, GetPinnedCF, IterateCF, OpenCF,
-    Put, SetOptions, WriteOps,
+    CompactRangeCF, CreateCF, DropCF, GetColumnFamilys, GetPinned, GetPinnedCF, GetPropertyCF,
+    IterateCF, OpenCF, Put, SetOptions, WriteOps,
 };
 use rocksdb::{
-    ffi, BlockBasedIndexType, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor,
-    DBPinnableSlice, FullOptions, IteratorMode, OptimisticTransactionDB,
-    OptimisticTransactionOptions, Options, SliceTransform, WriteBatch, WriteOptions,
+    ffi, ffi_util, BlockBasedIndexType, BlockBasedOptions, Cache, ColumnFamily,
+    ColumnFamilyDescriptor, DBPinnableSlice, FullOptions, Handle, IteratorMode,
+    OptimisticTransactionDB, OptimisticTransactionOptions, Options, ReadOptions, SliceTransform,
+    WriteBatch, WriteOptions,
 };
-use std::path::Path;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::ptr;
 use std::sync::Arc;
 
 /// RocksDB wrapper base on OptimisticTransactionDB
@@ -32,6 +40,10 @@ const DEFAULT_CACHE_ENTRY_CHARGE_SIZE: usize = 4096;
 impl RocksDB {
     pub(crate) fn open_with_check(config: &DBConfig, columns: u32) -> Result<Self> {
         let cf_names: Vec<_> = (0..columns).map(|c| c.to_string()).collect();
+        let column_paths = resolve_column_paths(config)?;
+        if let Some(ref dir) = config.wal_archive_dir {
+            check_wal_archive_dir(&config.path, dir)?;
+        }
         let mut cache = None;
 
         let (mut opts, mut cf_descriptors) = if let Some(ref file) = config.options_file {
@@ -91,11 +103,18 @@ impl RocksDB {
                     .set_prefix_extractor(SliceTransform::create_fixed_prefix(32));
             }
             cf.options.set_block_based_table_factory(&block_opts);
+            if let Some(dir) = column_paths.get(cf.name()) {
+                set_column_path(&mut cf.options, &config.path, dir)?;
+            }
         }
 
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
         opts.enable_statistics();
+        if config.wal_archive_dir.is_some() {
+            // keep the closed WAL files in the `archive` subdirectory until they are copied
+            opts.set_wal_ttl_seconds(WAL_TTL_SECONDS);
+        }
 
         let db = OptimisticTransactionDB::open_cf_descriptors(&opts, &config.path, cf_descriptors)
             .map_err(|err| internal_error(format!("failed to open database: {err}")))?;
@@ -117,32 +136,53 @@ impl RocksDB {
 
     /// Open a database with the given configuration and columns count.
     pub fn open(config: &DBConfig, columns: u32) -> Self {
-        Self::open_with_check(config, columns).unwrap_or_else(|err| panic!("{err}"))
+        Self::try_open(config, columns).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`open`](Self::open), but returns the error instead of panicking.
+    pub fn try_open(config: &DBConfig, columns: u32) -> Result<Self> {
+        Self::open_with_check(config, columns)
     }
 
     /// Open a database in the given directory with the default configuration and columns count.
     pub fn open_in<P: AsRef<Path>>(path: P, columns: u32) -> Self {
+        Self::try_open_in(path, columns).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`open_in`](Self::open_in), but returns the error instead of panicking.
+    pub fn try_open_in<P: AsRef<Path>>(path: P, columns: u32) -> Result<Self> {
         let config = DBConfig {
             path: path.as_ref().to_path_buf(),
             ..Default::default()
         };
-        Self::open_with_check(&config, columns).unwrap_or_else(|err| panic!("{err}"))
+        Self::open_with_check(&config, columns)
     }
 
     /// Set appropriate parameters for bulk loading.
+    ///
+    /// The columns placed in other directories are given by `column_paths`, see
+    /// [`resolve_column_paths`](crate::column_paths::resolve_column_paths).
     pub fn prepare_for_bulk_load_open<P: AsRef<Path>>(
         path: P,
         columns: u32,
+        column_paths: &HashMap<Col, PathBuf>,
     ) -> Result<Option<Self>> {
         let mut opts = Options::default();
 
         opts.create_missing_column_families(true);
         opts.set_prepare_for_bulk_load();
 
-        let cfnames: Vec<_> = (0..columns).map(|c| c.to_string()).collect();
-        let cf_options: Vec<&str> = cfnames.iter().map(|n| n as &str).collect();
+        let mut cf_descriptors = Vec::with_capacity(columns as usize);
+        for c in 0..columns {
+            let name = c.to_string();
+            let mut cf_opts = Options::default();
+            if let Some(dir) = column_paths.get(name.as_str()) {
+                set_column_path(&mut cf_opts, path.as_ref(), dir)?;
+            }
+            cf_descriptors.push(ColumnFamilyDescriptor::new(name, cf_opts));
+        }
 
-        OptimisticTransactionDB::open_cf(&opts, path, cf_options).map_or_else(
+        OptimisticTransactionDB::open_cf_descriptors(&opts, path, cf_descriptors).map_or_else(
             |err| {
                 let err_str = err.as_ref();
                 if err_str.starts_with("Invalid argument:")
@@ -173,6 +213,18 @@ impl RocksDB {
         self.inner.get_pinned_cf(cf, key).map_err(internal_error)
     }
 
+    /// Return the values associated with the keys from the given column, in the order of the keys.
+    ///
+    /// It's cheaper than calling `get_pinned` for each key, see `multi_get_sorted`.
+    pub fn multi_get_pinned<K: AsRef<[u8]>>(
+        &self,
+        col: Col,
+        keys: &[K],
+    ) -> Result<Vec<Option<DBPinnableSlice>>> {
+        let cf = cf_handle(&self.inner, col)?;
+        multi_get_sorted(&self.inner, cf, keys, &ReadOptions::default())
+    }
+
     /// Return the value associated with a key using RocksDB's PinnableSlice from the default column
     /// so as to avoid unnecessary memory copy.
     pub fn get_pinned_default(&self, key: &[u8]) -> Result<Option<DBPinnableSlice>> {
@@ -194,9 +246,10 @@ impl RocksDB {
         F: FnMut(&[u8], &[u8]) -> Result<()>,
     {
         let cf = cf_handle(&self.inner, col)?;
+        let opts = full_scan_read_options();
         let iter = self
             .inner
-            .full_iterator_cf(cf, IteratorMode::Start)
+            .iterator_cf_opt(cf, IteratorMode::Start, &opts)
             .map_err(internal_error)?;
         for (key, val) in iter {
             callback(&key, &val)?;
@@ -218,9 +271,10 @@ impl RocksDB {
         let mut count: usize = 0;
         let mut next_key: Vec<u8> = vec![];
         let cf = cf_handle(&self.inner, col)?;
+        let opts = full_scan_read_options();
         let iter = self
             .inner
-            .full_iterator_cf(cf, mode)
+            .iterator_cf_opt(cf, mode, &opts)
             .map_err(internal_error)?;
         for (key, val) in iter {
             if count > limit {
@@ -234,6 +288,15 @@ impl RocksDB {
         Ok((count, next_key))
     }
 
+    /// Return the integer value of a RocksDB property of the given column, for example
+    /// "rocksdb.estimate-num-keys" or "rocksdb.estimate-live-data-size".
+    pub fn property_int_value_cf(&self, col: Col, name: &str) -> Result<Option<u64>> {
+        let cf = cf_handle(&self.inner, col)?;
+        self.inner
+            .property_int_value_cf(cf, name)
+            .map_err(internal_error)
+    }
+
     /// Set a snapshot at start of transaction by setting set_snapshot=true
     pub fn transaction(&self) -> RocksDBTransaction {
         let write_options = WriteOptions::default();
@@ -302,6 +365,59 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Compacts all the files of the column, including the ones in the bottommost level, so they
+    /// are rewritten to the directory of the column, see `db.column_paths`.
+    ///
+    /// It's a blocking call like [`compact_range`](Self::compact_range).
+    pub fn rewrite_column(&self, col: Col) -> Result<()> {
+        let cf = cf_handle(&self.inner, col)?;
+        unsafe {
+            let opts = ffi::rocksdb_compactoptions_create();
+            // BottommostLevelCompaction::kForce
+            ffi::rocksdb_compactoptions_set_bottommost_level_compaction(opts, 2);
+            ffi::rocksdb_compact_range_cf_opt(
+                self.inner.base_db_ptr(),
+                cf.handle(),
+                opts,
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+            );
+            ffi::rocksdb_compactoptions_destroy(opts);
+        }
+        Ok(())
+    }
+
+    /// Creates a checkpoint of the database in `path`, which must not exist.
+    ///
+    /// The memtables are flushed first, and the files are hard linked if `path` is on the same
+    /// filesystem, otherwise copied, so the checkpoint is a consistent copy of the database. The
+    /// files of all the paths are put in `path`, see
+    /// [`move_column_files`](crate::column_paths::move_column_files).
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let c_path = path
+            .to_str()
+            .and_then(|path| CString::new(path).ok())
+            .ok_or_else(|| internal_error(format!("invalid checkpoint path {}", path.display())))?;
+        unsafe {
+            let mut err: *mut c_char = ptr::null_mut();
+            let checkpoint =
+                ffi::rocksdb_checkpoint_object_create(self.inner.base_db_ptr(), &mut err);
+            if !err.is_null() {
+                return Err(internal_error(ffi_util::error_message(err)));
+            }
+            // always flush the memtables, so the WAL files are not needed by the checkpoint
+            ffi::rocksdb_checkpoint_create(checkpoint, c_path.as_ptr(), 0, &mut err);
+            ffi::rocksdb_checkpoint_object_destroy(checkpoint);
+            if !err.is_null() {
+                return Err(internal_error(ffi_util::error_message(err)));
+            }
+        }
+        Ok(())
+    }
+
     /// Return `RocksDBSnapshot`.
     pub fn get_snapshot(&self) -> RocksDBSnapshot {
         unsafe {
@@ -331,7 +447,72 @@ impl RocksDB {
     }
 }
 
-#[inline]
+/// Reads the keys of the column by one `rocksdb_batched_multi_get_cf` call, and returns the values
+/// in the order of the keys.
+///
+/// The keys are sorted first, so RocksDB looks them up level by level in a batch, which hits the
+/// same data blocks and index blocks one after another, and saves the per-key overhead of the
+/// separate reads. An error of any key fails the whole read.
+pub(crate) fn multi_get_sorted<'a, K: AsRef<[u8]>>(
+    db: &'a OptimisticTransactionDB,
+    cf: &ColumnFamily,
+    keys: &[K],
+    readopts: &ReadOptions,
+) -> Result<Vec<Option<DBPinnableSlice<'a>>>> {
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_unstable_by(|&a, &b| keys[a].as_ref().cmp(keys[b].as_ref()));
+    let keys_list: Vec<*const c_char> = order
+        .iter()
+        .map(|&index| keys[index].as_ref().as_ptr() as *const c_char)
+        .collect();
+    let keys_list_sizes: Vec<size_t> = order
+        .iter()
+        .map(|&index| keys[index].as_ref().len() as size_t)
+        .collect();
+    let mut pinned_values = vec![ptr::null_mut(); keys.len()];
+    let mut errs: Vec<*mut c_char> = vec![ptr::null_mut(); keys.len()];
+    unsafe {
+        ffi::rocksdb_batched_multi_get_cf(
+            db.base_db_ptr(),
+            readopts.handle(),
+            cf.handle(),
+            keys.len(),
+            keys_list.as_ptr(),
+            keys_list_sizes.as_ptr(),
+            pinned_values.as_mut_ptr(),
+            errs.as_mut_ptr(),
+            true,
+        );
+    }
+
+    let mut values: Vec<Option<DBPinnableSlice<'a>>> = keys.iter().map(|_| None).collect();
+    let mut error = None;
+    for ((index, pinned_value), err) in order.into_iter().zip(pinned_values).zip(errs) {
+        if !err.is_null() {
+            let message = unsafe { ffi_util::error_message(err) };
+            error.get_or_insert(message);
+        } else if !pinned_value.is_null() {
+            // wraps every pinned value, so all of them are released even if the read fails
+            values[index] = Some(unsafe { DBPinnableSlice::from_c(pinned_value) });
+        }
+    }
+    match error {
+        Some(message) => Err(internal_error(message)),
+        None => Ok(values),
+    }
+}
+
+// The traversals scan whole columns, so they read ahead and keep the hot blocks in the cache. The
+// total order seek is required by the column with the prefix extractor.
+fn full_scan_read_options() -> ReadOptions {
+    let mut opts = prefetch_read_options(DEFAULT_PREFETCH_SIZE);
+    opts.set_total_order_seek(true);
+    opts
+}
+
 pub(crate) fn cf_handle(db: &OptimisticTransactionDB, col: Col) -> Result<&ColumnFamily> {
     db.cf_handle(col)
         .ok_or_else(|| internal_error(format!("column {col} not found")))
@@ -6,23 +6,27 @@
 use ckb_error::{Error, InternalErrorKind};
 use std::{fmt, result};
 
+pub mod column_paths;
 pub mod db;
 pub mod db_with_ttl;
 pub mod iter;
 pub mod read_only_db;
 pub mod snapshot;
 pub mod transaction;
+pub mod wal_archive;
 pub mod write_batch;
 
 #[cfg(test)]
 mod tests;
 
+pub use crate::column_paths::resolve_column_paths;
 pub use crate::db::RocksDB;
 pub use crate::db_with_ttl::DBWithTTL;
-pub use crate::iter::DBIterator;
+pub use crate::iter::{DBIterator, DEFAULT_PREFETCH_SIZE};
 pub use crate::read_only_db::ReadOnlyDB;
 pub use crate::snapshot::RocksDBSnapshot;
 pub use crate::transaction::{RocksDBTransaction, RocksDBTransactionSnapshot};
+pub use crate::wal_archive::WalArchiver;
 pub use crate::write_batch::RocksDBWriteBatch;
 pub use rocksdb::{
     self as internal, DBPinnableSlice, DBVector, Direction, Error as DBError, IteratorMode,
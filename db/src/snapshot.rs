@@ -1,5 +1,5 @@
 //! RocksDB snapshot wrapper
-use crate::db::cf_handle;
+use crate::db::{cf_handle, multi_get_sorted};
 use crate::{internal_error, Result};
 use ckb_db_schema::Col;
 use libc::{self, c_char, size_t};
@@ -40,6 +40,19 @@ impl RocksDBSnapshot {
         self.get_pinned_cf_full(Some(cf), key, None)
             .map_err(internal_error)
     }
+
+    /// Return the values associated with the keys from the given column, in the order of the
+    /// keys.
+    pub fn multi_get_pinned<K: AsRef<[u8]>>(
+        &self,
+        col: Col,
+        keys: &[K],
+    ) -> Result<Vec<Option<DBPinnableSlice>>> {
+        let cf = cf_handle(&self.db, col)?;
+        let mut readopts = ReadOptions::default();
+        readopts.set_snapshot(self);
+        multi_get_sorted(&self.db, cf, keys, &readopts)
+    }
 }
 
 impl Read for RocksDBSnapshot {}
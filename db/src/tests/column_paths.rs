@@ -0,0 +1,179 @@
+use crate::column_paths::{move_column_files, resolve_column_paths};
+use crate::RocksDB;
+use ckb_app_config::DBConfig;
+use ckb_db_schema::{COLUMNS, COLUMN_BLOCK_BODY, COLUMN_CELL};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+fn has_sst_files(dir: &Path) -> bool {
+    fs::read_dir(dir).map_or(false, |entries| {
+        entries.flatten().any(|entry| {
+            entry
+                .path()
+                .extension()
+                .map_or(false, |extension| extension == "sst")
+        })
+    })
+}
+
+#[test]
+fn test_resolve_column_paths() {
+    let mut config = DBConfig::default();
+    config
+        .column_paths
+        .insert("COLUMN_CELL".to_owned(), "/mnt/nvme".into());
+    assert_eq!(
+        resolve_column_paths(&config).unwrap(),
+        HashMap::from([(COLUMN_CELL, "/mnt/nvme".into())])
+    );
+
+    config
+        .column_paths
+        .insert("COLUMN_UNKNOWN".to_owned(), "/mnt/nvme".into());
+    assert!(resolve_column_paths(&config).is_err());
+
+    config.column_paths.remove("COLUMN_UNKNOWN");
+    config
+        .column_paths
+        .insert("COLUMN_META".to_owned(), "/mnt/nvme".into());
+    assert!(resolve_column_paths(&config).is_err());
+}
+
+#[test]
+fn test_refuse_shared_column_paths() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_refuse_shared_column_paths")
+        .tempdir()
+        .unwrap();
+    let mut config = DBConfig {
+        path: tmp_dir.path().join("db"),
+        ..Default::default()
+    };
+
+    // the directories overlapping the database directory
+    for dir in [
+        tmp_dir.path().join("db").join("cell"),
+        tmp_dir.path().into(),
+    ] {
+        config.column_paths = HashMap::from([("COLUMN_CELL".to_owned(), dir)]);
+        assert!(resolve_column_paths(&config).is_err());
+    }
+
+    // the column is moved back to the database directory
+    config.column_paths = HashMap::from([("COLUMN_CELL".to_owned(), config.path.clone())]);
+    assert!(resolve_column_paths(&config).is_ok());
+
+    // the directory holding the other data
+    let cell_dir = tmp_dir.path().join("cell");
+    fs::create_dir_all(&cell_dir).unwrap();
+    fs::write(cell_dir.join("000001.sst"), b"").unwrap();
+    config.column_paths = HashMap::from([("COLUMN_CELL".to_owned(), cell_dir.clone())]);
+    assert!(resolve_column_paths(&config).is_ok());
+    fs::write(cell_dir.join("notes.txt"), b"").unwrap();
+    assert!(resolve_column_paths(&config).is_err());
+}
+
+#[test]
+fn test_move_column() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_move_column")
+        .tempdir()
+        .unwrap();
+    let cell_dir = tmp_dir.path().join("cell");
+    let mut config = DBConfig {
+        path: tmp_dir.path().join("db"),
+        ..Default::default()
+    };
+    {
+        let db = RocksDB::open(&config, COLUMNS);
+        let txn = db.transaction();
+        txn.put(COLUMN_CELL, &[1], &[1, 1]).unwrap();
+        txn.put(COLUMN_BLOCK_BODY, &[2], &[2, 2]).unwrap();
+        txn.commit().unwrap();
+        db.compact_range(COLUMN_CELL, None, None).unwrap();
+    }
+
+    config
+        .column_paths
+        .insert("COLUMN_CELL".to_owned(), cell_dir.clone());
+    // the existing files are still read from the database directory
+    let db = RocksDB::open(&config, COLUMNS);
+    assert_eq!(
+        db.get_pinned(COLUMN_CELL, &[1]).unwrap().unwrap().as_ref(),
+        &[1, 1]
+    );
+    assert!(!has_sst_files(&cell_dir));
+
+    db.rewrite_column(COLUMN_CELL).unwrap();
+    assert!(has_sst_files(&cell_dir));
+    drop(db);
+
+    let db = RocksDB::open(&config, COLUMNS);
+    assert_eq!(
+        db.get_pinned(COLUMN_CELL, &[1]).unwrap().unwrap().as_ref(),
+        &[1, 1]
+    );
+    assert_eq!(
+        db.get_pinned(COLUMN_BLOCK_BODY, &[2])
+            .unwrap()
+            .unwrap()
+            .as_ref(),
+        &[2, 2]
+    );
+}
+
+#[test]
+fn test_checkpoint_with_column_paths() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_checkpoint_with_column_paths")
+        .tempdir()
+        .unwrap();
+    let mut config = DBConfig {
+        path: tmp_dir.path().join("db"),
+        ..Default::default()
+    };
+    config
+        .column_paths
+        .insert("COLUMN_CELL".to_owned(), tmp_dir.path().join("cell"));
+    let db = RocksDB::open(&config, COLUMNS);
+    let txn = db.transaction();
+    txn.put(COLUMN_CELL, &[1], &[1, 1]).unwrap();
+    txn.put(COLUMN_BLOCK_BODY, &[2], &[2, 2]).unwrap();
+    txn.commit().unwrap();
+    db.rewrite_column(COLUMN_CELL).unwrap();
+
+    let copy_cell_dir = tmp_dir.path().join("copy-cell");
+    let copy_config = DBConfig {
+        path: tmp_dir.path().join("copy"),
+        column_paths: HashMap::from([("COLUMN_CELL".to_owned(), copy_cell_dir.clone())]),
+        ..Default::default()
+    };
+    db.create_checkpoint(&copy_config.path).unwrap();
+    drop(db);
+    // the files of the column are put in the checkpoint directory
+    let moved = move_column_files(
+        &copy_config.path,
+        &tmp_dir.path().join("cell"),
+        &copy_cell_dir,
+    )
+    .unwrap();
+    assert!(moved > 0);
+    assert!(has_sst_files(&copy_cell_dir));
+
+    let copy = RocksDB::open(&copy_config, COLUMNS);
+    assert_eq!(
+        copy.get_pinned(COLUMN_CELL, &[1])
+            .unwrap()
+            .unwrap()
+            .as_ref(),
+        &[1, 1]
+    );
+    assert_eq!(
+        copy.get_pinned(COLUMN_BLOCK_BODY, &[2])
+            .unwrap()
+            .unwrap()
+            .as_ref(),
+        &[2, 2]
+    );
+}
@@ -1,3 +1,5 @@
+mod column_paths;
 mod db;
 mod db_with_ttl;
 mod read_only_db;
+mod wal_archive;
@@ -0,0 +1,68 @@
+use crate::wal_archive::{check_wal_archive_dir, list_wal_files, restore_archived_wal};
+use crate::WalArchiver;
+use std::fs;
+
+#[test]
+fn test_archive_and_restore_wal() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_archive_and_restore_wal")
+        .tempdir()
+        .unwrap();
+    let db_path = tmp_dir.path().join("db");
+    let archive_dir = tmp_dir.path().join("wal-archive");
+    let backup_path = tmp_dir.path().join("backup");
+    fs::create_dir_all(db_path.join("archive")).unwrap();
+    fs::create_dir_all(&backup_path).unwrap();
+    fs::create_dir_all(&archive_dir).unwrap();
+
+    fs::write(db_path.join("archive").join("000005.log"), b"wal-5").unwrap();
+    fs::write(db_path.join("archive").join("000007.log"), b"wal-7").unwrap();
+    fs::write(db_path.join("archive").join("OPTIONS-000001"), b"options").unwrap();
+
+    let archiver = WalArchiver::new(&db_path, &archive_dir);
+    assert_eq!(archiver.archive().unwrap(), vec![5, 7]);
+    // the archived files are not copied again
+    assert!(archiver.archive().unwrap().is_empty());
+    fs::write(db_path.join("archive").join("000009.log"), b"wal-9").unwrap();
+    assert_eq!(archiver.archive().unwrap(), vec![9]);
+    let numbers: Vec<_> = list_wal_files(&archive_dir)
+        .unwrap()
+        .into_iter()
+        .map(|file| file.number)
+        .collect();
+    assert_eq!(numbers, vec![5, 7, 9]);
+
+    // the backup was taken while wal 7 was still written to
+    fs::write(backup_path.join("000007.log"), b"wal").unwrap();
+    assert_eq!(
+        restore_archived_wal(&archive_dir, &backup_path, Some(7)).unwrap(),
+        vec![5, 7]
+    );
+    assert_eq!(fs::read(backup_path.join("000007.log")).unwrap(), b"wal-7");
+    assert!(!backup_path.join("000009.log").exists());
+}
+
+#[test]
+fn test_check_wal_archive_dir() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_check_wal_archive_dir")
+        .tempdir()
+        .unwrap();
+    let db_path = tmp_dir.path().join("db");
+    let archive_dir = tmp_dir.path().join("wal-archive");
+    fs::create_dir_all(db_path.join("archive")).unwrap();
+    fs::write(db_path.join("archive").join("000005.log"), b"wal-5").unwrap();
+
+    // the missing directory is not created
+    assert!(check_wal_archive_dir(&db_path, &archive_dir).is_err());
+    assert!(WalArchiver::new(&db_path, &archive_dir).archive().is_err());
+    assert!(!archive_dir.exists());
+
+    fs::create_dir_all(&archive_dir).unwrap();
+    assert!(check_wal_archive_dir(&db_path, &archive_dir).is_ok());
+
+    // the directories overlap
+    assert!(check_wal_archive_dir(&db_path, db_path.join("archive")).is_err());
+    assert!(check_wal_archive_dir(&db_path, &db_path).is_err());
+    assert!(check_wal_archive_dir(&db_path, tmp_dir.path()).is_err());
+}
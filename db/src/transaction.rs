@@ -2,7 +2,7 @@
 use crate::db::cf_handle;
 use crate::{internal_error, Result};
 use ckb_db_schema::Col;
-use rocksdb::ops::{DeleteCF, GetPinnedCF, PutCF};
+use rocksdb::ops::{DeleteCF, GetPinnedCF, Put, PutCF};
 pub use rocksdb::{DBPinnableSlice, DBVector};
 use rocksdb::{
     OptimisticTransaction, OptimisticTransactionDB, OptimisticTransactionSnapshot, ReadOptions,
@@ -22,12 +22,40 @@ impl RocksDBTransaction {
         self.inner.get_pinned_cf(cf, key).map_err(internal_error)
     }
 
+    /// Return the bytes associated with the given keys and given column, in the order of the
+    /// keys.
+    ///
+    /// The keys are read one by one in the sorted order, since the batched read of RocksDB
+    /// doesn't see the writes of the transaction.
+    pub fn multi_get_pinned<K: AsRef<[u8]>>(
+        &self,
+        col: Col,
+        keys: &[K],
+    ) -> Result<Vec<Option<DBPinnableSlice>>> {
+        let cf = cf_handle(&self.db, col)?;
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_unstable_by(|&a, &b| keys[a].as_ref().cmp(keys[b].as_ref()));
+        let mut values: Vec<Option<DBPinnableSlice>> = keys.iter().map(|_| None).collect();
+        for index in order {
+            values[index] = self
+                .inner
+                .get_pinned_cf(cf, keys[index].as_ref())
+                .map_err(internal_error)?;
+        }
+        Ok(values)
+    }
+
     /// Write the bytes into the given column with associated key.
     pub fn put(&self, col: Col, key: &[u8], value: &[u8]) -> Result<()> {
         let cf = cf_handle(&self.db, col)?;
         self.inner.put_cf(cf, key, value).map_err(internal_error)
     }
 
+    /// Write the bytes into the default column with associated key.
+    pub fn put_default(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.inner.put(key, value).map_err(internal_error)
+    }
+
     /// Delete the data associated with the given key and given column.
     pub fn delete(&self, col: Col, key: &[u8]) -> Result<()> {
         let cf = cf_handle(&self.db, col)?;
@@ -49,6 +77,20 @@ impl RocksDBTransaction {
             .map_err(internal_error)
     }
 
+    /// Read a key of the default column and make the read value a precondition for transaction
+    /// commit.
+    pub fn get_for_update_default(
+        &self,
+        key: &[u8],
+        snapshot: &RocksDBTransactionSnapshot<'_>,
+    ) -> Result<Option<DBVector>> {
+        let mut opts = ReadOptions::default();
+        opts.set_snapshot(&snapshot.inner);
+        self.inner
+            .get_for_update_opt(key, &opts, true)
+            .map_err(internal_error)
+    }
+
     /// Commit the transaction.
     pub fn commit(&self) -> Result<()> {
         self.inner.commit().map_err(internal_error)
@@ -0,0 +1,168 @@
+//! Write-ahead log archiving for point-in-time recovery.
+//!
+//! When `db.wal_archive_dir` is set, RocksDB moves the closed WAL files into the `archive`
+//! subdirectory of the database instead of deleting them, and [`WalArchiver`] copies them to the
+//! archive directory.
+//!
+//! To recover, restore a backup of the database and call [`restore_archived_wal`], which copies
+//! the archived WAL files into it. RocksDB replays them the next time the database is opened,
+//! the files which are older than the backup are skipped by RocksDB.
+use crate::{internal_error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How long RocksDB keeps the closed WAL files in the `archive` subdirectory of the database.
+///
+/// The archiver must copy them within this period.
+pub(crate) const WAL_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+const WAL_FILE_EXTENSION: &str = "log";
+const DB_ARCHIVE_SUBDIR: &str = "archive";
+
+/// A WAL file, named by its number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalFile {
+    /// The WAL number, the WAL files are replayed in ascending number order.
+    pub number: u64,
+    /// The path of the file.
+    pub path: PathBuf,
+}
+
+/// Checks that the archive directory exists and does not overlap the database directory.
+///
+/// A missing directory, e.g., an unmounted volume, is an error instead of being created, and the
+/// archived files must not be mixed with the database files.
+pub fn check_wal_archive_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    db_path: P,
+    archive_dir: Q,
+) -> Result<()> {
+    let db_path = db_path.as_ref();
+    let archive_dir = archive_dir.as_ref();
+    if !archive_dir.is_dir() {
+        return Err(internal_error(format!(
+            "the WAL archive directory {} does not exist or is not a directory",
+            archive_dir.display()
+        )));
+    }
+    if archive_dir.starts_with(db_path) || db_path.starts_with(archive_dir) {
+        return Err(internal_error(format!(
+            "the WAL archive directory {} overlaps the database directory {}",
+            archive_dir.display(),
+            db_path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Lists the WAL files in `dir`, sorted by number.
+///
+/// Returns an empty list if `dir` does not exist.
+pub fn list_wal_files<P: AsRef<Path>>(dir: P) -> Result<Vec<WalFile>> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(internal_error)? {
+        let path = entry.map_err(internal_error)?.path();
+        if !path.is_file()
+            || path.extension().and_then(|ext| ext.to_str()) != Some(WAL_FILE_EXTENSION)
+        {
+            continue;
+        }
+        if let Some(number) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok())
+        {
+            files.push(WalFile { number, path });
+        }
+    }
+    files.sort_by_key(|file| file.number);
+    Ok(files)
+}
+
+/// Copies the closed WAL files of a database to the archive directory.
+pub struct WalArchiver {
+    db_path: PathBuf,
+    archive_dir: PathBuf,
+}
+
+impl WalArchiver {
+    /// Creates an archiver for the database at `db_path`.
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(db_path: P, archive_dir: Q) -> Self {
+        WalArchiver {
+            db_path: db_path.as_ref().to_path_buf(),
+            archive_dir: archive_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Copies the closed WAL files which are not in the archive directory yet.
+    ///
+    /// A file is written to a temporary name first and renamed after it is synced, so the
+    /// archive directory never contains partially copied files. Returns the numbers of the
+    /// copied files.
+    pub fn archive(&self) -> Result<Vec<u64>> {
+        let closed = list_wal_files(self.db_path.join(DB_ARCHIVE_SUBDIR))?;
+        if closed.is_empty() {
+            return Ok(Vec::new());
+        }
+        check_wal_archive_dir(&self.db_path, &self.archive_dir)?;
+
+        let mut archived = Vec::new();
+        for file in closed {
+            let file_name = file.path.file_name().expect("listed file has a name");
+            let target = self.archive_dir.join(file_name);
+            if target.exists() {
+                continue;
+            }
+            let tmp = target.with_extension(format!("{WAL_FILE_EXTENSION}.tmp"));
+            copy_synced(&file.path, &tmp)?;
+            fs::rename(&tmp, &target).map_err(internal_error)?;
+            archived.push(file.number);
+        }
+        Ok(archived)
+    }
+}
+
+/// Copies the archived WAL files into the database at `db_path`, which should be a restored
+/// backup that is not opened.
+///
+/// Only the files whose number is less than or equal to `until` are copied, which recovers the
+/// database to the end of that WAL file. A WAL file in the database is replaced only if the
+/// archived one is not shorter, since the file in the backup may still have been written to when
+/// the backup was taken. Returns the numbers of the copied files.
+pub fn restore_archived_wal<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_dir: P,
+    db_path: Q,
+    until: Option<u64>,
+) -> Result<Vec<u64>> {
+    let db_path = db_path.as_ref();
+    let mut restored = Vec::new();
+    for file in list_wal_files(archive_dir)? {
+        if until.map_or(false, |until| file.number > until) {
+            break;
+        }
+        let file_name = file.path.file_name().expect("listed file has a name");
+        let target = db_path.join(file_name);
+        if target.exists() && file_len(&target)? > file_len(&file.path)? {
+            continue;
+        }
+        copy_synced(&file.path, &target)?;
+        restored.push(file.number);
+    }
+    Ok(restored)
+}
+
+fn file_len(path: &Path) -> Result<u64> {
+    fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .map_err(internal_error)
+}
+
+fn copy_synced(from: &Path, to: &Path) -> Result<()> {
+    fs::copy(from, to).map_err(internal_error)?;
+    fs::File::open(to)
+        .and_then(|file| file.sync_all())
+        .map_err(internal_error)
+}
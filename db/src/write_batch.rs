@@ -35,6 +35,16 @@ impl RocksDBWriteBatch {
         self.inner.put_cf(cf, key, value).map_err(internal_error)
     }
 
+    /// Write the bytes into the default column with associated key.
+    pub fn put_default(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.inner.put(key, value).map_err(internal_error)
+    }
+
+    /// Delete the data associated with the given key in the default column.
+    pub fn delete_default(&mut self, key: &[u8]) -> Result<()> {
+        self.inner.delete(key).map_err(internal_error)
+    }
+
     /// Delete the data associated with the given key and given column.
     pub fn delete(&mut self, col: Col, key: &[u8]) -> Result<()> {
         let cf = cf_handle(&self.db, col)?;
@@ -30,6 +30,9 @@ case $GITHUB_WORKFLOW in
       export CKB_FEATURES="deadlock_detection,with_sentry,portable"
     fi
     make test
+    if [[ $github_workflow_os == 'ubuntu' ]]; then
+      make script-determinism
+    fi
     ;;
   ci_benchmarks*)
     echo "ci_benchmarks_test"
@@ -1,15 +1,17 @@
-use crate::freezer_files::FreezerFiles;
+use crate::freezer_files::{ChecksumStatus, FileId, FreezerFiles};
 use crate::internal_error;
 use ckb_error::Error;
 use ckb_types::{
     core::{BlockNumber, BlockView, HeaderView},
     packed,
     prelude::*,
+    H256,
 };
 use ckb_util::Mutex;
 use fs2::FileExt;
 use std::collections::BTreeMap;
 use std::fs::{File, OpenOptions};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -148,6 +150,103 @@ impl Freezer {
         self.number.load(Ordering::SeqCst)
     }
 
+    /// Returns the ids of the sealed data files, which are checksummed in the manifest.
+    pub fn sealed_files(&self) -> Range<FileId> {
+        self.inner.lock().files.sealed_files()
+    }
+
+    /// Returns the numbers of the blocks stored in the data file.
+    pub fn blocks_in_file(&self, file_id: FileId) -> Result<Range<BlockNumber>, Error> {
+        self.inner
+            .lock()
+            .files
+            .items_in_file(file_id)
+            .map_err(internal_error)
+    }
+
+    /// Compares the sealed data file with its checksum in the manifest.
+    pub fn verify_checksum(&self, file_id: FileId) -> Result<ChecksumStatus, Error> {
+        self.inner
+            .lock()
+            .files
+            .verify_checksum(file_id)
+            .map_err(internal_error)
+    }
+
+    /// Records the checksum of the sealed data file, whose blocks have been verified.
+    pub fn update_checksum(&self, file_id: FileId) -> Result<(), Error> {
+        self.inner
+            .lock()
+            .files
+            .update_checksum(file_id)
+            .map_err(internal_error)
+    }
+
+    /// The merkle root of the checksums of the sealed data files.
+    pub fn checksums_root(&self) -> H256 {
+        self.inner.lock().files.checksums_root()
+    }
+
+    /// Verifies the integrity of the frozen block, `expected_hash` is the hash of the block on
+    /// the main chain.
+    ///
+    /// Besides the block hash, the transactions root, the proposals hash and the extra hash are
+    /// checked against the block body.
+    pub fn verify_block(
+        &self,
+        number: BlockNumber,
+        expected_hash: &packed::Byte32,
+    ) -> Result<(), Error> {
+        let raw_block = self
+            .retrieve(number)?
+            .ok_or_else(|| internal_error(format!("block {number} is not frozen")))?;
+        let block = packed::BlockReader::from_compatible_slice(&raw_block)
+            .map_err(internal_error)?
+            .to_entity()
+            .into_view();
+        let header = block.header();
+        if &header.hash() != expected_hash {
+            return Err(internal_error(format!(
+                "block {number} hash mismatch, expected {expected_hash} have {}",
+                header.hash()
+            )));
+        }
+        if header.transactions_root() != block.calc_transactions_root() {
+            return Err(internal_error(format!(
+                "block {number} transactions root mismatch"
+            )));
+        }
+        if header.proposals_hash() != block.calc_proposals_hash() {
+            return Err(internal_error(format!(
+                "block {number} proposals hash mismatch"
+            )));
+        }
+        if header.extra_hash() != block.calc_extra_hash().extra_hash() {
+            return Err(internal_error(format!(
+                "block {number} extra hash mismatch"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Repairs the corrupted frozen block with the block fetched elsewhere, which must be
+    /// verified by the caller.
+    ///
+    /// The checksum of the data file should be updated after the repair.
+    pub fn repair_block(&self, number: BlockNumber, block: &packed::Block) -> Result<(), Error> {
+        let block_number: BlockNumber = block.header().raw().number().unpack();
+        if block_number != number {
+            return Err(internal_error(format!(
+                "repairing unexpected block expected {number} have {block_number}"
+            )));
+        }
+        self.inner
+            .lock()
+            .files
+            .overwrite(number, block.as_slice())
+            .map_err(internal_error)
+    }
+
     /// Truncate discards any recent data above the provided threshold number.
     pub fn truncate(&self, item: u64) -> Result<(), Error> {
         if item > 0 && ((item + 1) < self.number()) {
@@ -1,6 +1,9 @@
+use ckb_hash::{new_blake2b, Blake2b};
+use ckb_types::{prelude::*, utilities::merkle_root, H256};
 use fail::fail_point;
 use lru::LruCache;
 use snap::raw::{Decoder as SnappyDecoder, Encoder as SnappyEncoder};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{Error as IoError, ErrorKind as IoErrorKind};
 use std::io::{Read, Write};
@@ -12,7 +15,9 @@ use std::sync::Arc;
 const MAX_FILE_SIZE: u64 = 2 * 1_000 * 1_000 * 1_000; // 2G
 const OPEN_FILES_LIMIT: usize = 256;
 const INDEX_FILE_NAME: &str = "INDEX";
+const CHECKSUMS_FILE_NAME: &str = "CHECKSUMS";
 pub(crate) const INDEX_ENTRY_SIZE: u64 = 12;
+const HASH_BUFFER_SIZE: usize = 4 * 1024 * 1024;
 
 /// File id alias
 pub type FileId = u32;
@@ -21,21 +26,43 @@ pub(crate) struct Head {
     pub(crate) file: File,
     // number of bytes written to the head file
     pub(crate) bytes: u64,
+    // the checksum of the head file is computed incrementally, it is unknown when the head file
+    // is reopened with existing data, and is computed from the file when it is sealed
+    hasher: Option<Blake2b>,
 }
 
 impl Head {
     pub fn new(file: File, bytes: u64) -> Self {
-        Head { file, bytes }
+        let hasher = (bytes == 0).then(new_blake2b);
+        Head {
+            file,
+            bytes,
+            hasher,
+        }
     }
 
     pub fn write(&mut self, data: &[u8]) -> Result<(), IoError> {
         fail_point!("write-head");
         self.file.write_all(data)?;
         self.bytes += data.len() as u64;
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(data);
+        }
         Ok(())
     }
 }
 
+/// The checksum status of a sealed data file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    /// The file matches the checksum in the manifest.
+    Matched,
+    /// The file does not match the checksum in the manifest.
+    Mismatched,
+    /// The file was sealed before the checksums were introduced.
+    Missing,
+}
+
 /// FreezerFiles represents a single chained block data,
 /// it consists of a data file and an index file
 pub struct FreezerFiles {
@@ -57,6 +84,8 @@ pub struct FreezerFiles {
     pub(crate) index: File,
     // enable compression
     pub(crate) enable_compression: bool,
+    // checksums of the sealed data files, persisted in the manifest
+    pub(crate) checksums: BTreeMap<FileId, H256>,
 }
 
 /// An instance of IndexEntry represents an entry inside of a index files
@@ -142,8 +171,13 @@ impl FreezerFiles {
             self.release(head_id);
             self.open_read_only(head_id)?;
 
+            let checksum = match self.head.hasher.take() {
+                Some(hasher) => finalize(hasher),
+                None => self.compute_checksum(head_id)?,
+            };
             self.head_id = next_id;
             self.head = Head::new(new_head_file, 0);
+            self.record_checksum(head_id, checksum)?;
         }
 
         self.head.write(data)?;
@@ -280,10 +314,168 @@ impl FreezerFiles {
         }
         helper::truncate_file(&mut self.head.file, new_index.offset)?;
         self.head.bytes = new_index.offset;
+        self.head.hasher = (new_index.offset == 0).then(new_blake2b);
         self.number.store(item + 1, Ordering::SeqCst);
+
+        // the checksums of the reopened head file and the deleted files are stale
+        if self.checksums.split_off(&self.head_id).is_empty() {
+            return Ok(());
+        }
+        self.write_checksums()
+    }
+
+    /// Returns the ids of the sealed data files, which are immutable and checksummed.
+    pub fn sealed_files(&self) -> std::ops::Range<FileId> {
+        self.tail_id..self.head_id
+    }
+
+    /// The items stored in the data file.
+    pub fn items_in_file(&self, file_id: FileId) -> Result<std::ops::Range<u64>, IoError> {
+        Ok(self.first_item_in(file_id)?..self.first_item_in(file_id + 1)?)
+    }
+
+    // The index entries are sorted by the file id, the first item of the file is found by
+    // binary search.
+    fn first_item_in(&self, file_id: FileId) -> Result<u64, IoError> {
+        let (mut low, mut high) = (1, self.number());
+        let mut buffer = [0; INDEX_ENTRY_SIZE as usize];
+        let mut index = &self.index;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            index.seek(SeekFrom::Start(mid * INDEX_ENTRY_SIZE))?;
+            index.read_exact(&mut buffer)?;
+            if IndexEntry::decode(&buffer)?.file_id < file_id {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Compares the sealed data file with its checksum in the manifest.
+    pub fn verify_checksum(&self, file_id: FileId) -> Result<ChecksumStatus, IoError> {
+        match self.checksums.get(&file_id) {
+            Some(expected) => {
+                if &self.compute_checksum(file_id)? == expected {
+                    Ok(ChecksumStatus::Matched)
+                } else {
+                    Ok(ChecksumStatus::Mismatched)
+                }
+            }
+            None => Ok(ChecksumStatus::Missing),
+        }
+    }
+
+    /// Computes the checksum of the sealed data file from its current content and records it
+    /// in the manifest.
+    ///
+    /// It should only be used when the content is verified, such as the files sealed before the
+    /// checksums were introduced or the repaired files.
+    pub fn update_checksum(&mut self, file_id: FileId) -> Result<(), IoError> {
+        if !self.sealed_files().contains(&file_id) {
+            return Err(IoError::new(
+                IoErrorKind::Other,
+                format!("file {file_id} is not sealed"),
+            ));
+        }
+        let checksum = self.compute_checksum(file_id)?;
+        if self.checksums.contains_key(&file_id) {
+            self.checksums.insert(file_id, checksum);
+            self.write_checksums()
+        } else {
+            self.record_checksum(file_id, checksum)
+        }
+    }
+
+    /// The merkle root of the checksums of the sealed data files.
+    pub fn checksums_root(&self) -> H256 {
+        let leaves: Vec<_> = self
+            .checksums
+            .values()
+            .map(|checksum| checksum.pack())
+            .collect();
+        merkle_root(&leaves).unpack()
+    }
+
+    /// Overwrites the stored item with the data, which must have the same size as the stored one
+    /// after compression.
+    ///
+    /// Freezer files are append-only, this is only used to repair the corrupted items. The
+    /// checksum of the file should be updated after the repair.
+    pub fn overwrite(&mut self, item: u64, input: &[u8]) -> Result<(), IoError> {
+        let (start_offset, end_offset, file_id) = self.get_bounds(item)?.ok_or_else(|| {
+            IoError::new(IoErrorKind::Other, format!("item {item} is not frozen"))
+        })?;
+        let data = if self.enable_compression {
+            SnappyEncoder::new()
+                .compress_vec(input)
+                .map_err(|e| IoError::new(IoErrorKind::Other, format!("compress error {e}")))?
+        } else {
+            input.to_vec()
+        };
+        if data.len() as u64 != end_offset - start_offset {
+            return Err(IoError::new(
+                IoErrorKind::Other,
+                format!(
+                    "item {item} size mismatch, stored {} have {}",
+                    end_offset - start_offset,
+                    data.len()
+                ),
+            ));
+        }
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .open(self.file_path.join(helper::file_name(file_id)))?;
+        file.seek(SeekFrom::Start(start_offset))?;
+        file.write_all(&data)?;
+        file.sync_all()?;
+        if file_id == self.head_id {
+            // the incremental checksum of the head file is stale
+            self.head.hasher = None;
+        }
+        Ok(())
+    }
+
+    fn compute_checksum(&self, file_id: FileId) -> Result<H256, IoError> {
+        let mut file = File::open(self.file_path.join(helper::file_name(file_id)))?;
+        let mut hasher = new_blake2b();
+        let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+        loop {
+            let size = file.read(&mut buffer)?;
+            if size == 0 {
+                break;
+            }
+            hasher.update(&buffer[..size]);
+        }
+        Ok(finalize(hasher))
+    }
+
+    fn record_checksum(&mut self, file_id: FileId, checksum: H256) -> Result<(), IoError> {
+        let mut manifest = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.file_path.join(CHECKSUMS_FILE_NAME))?;
+        manifest.write_all(helper::checksum_line(file_id, &checksum).as_bytes())?;
+        manifest.sync_all()?;
+        self.checksums.insert(file_id, checksum);
         Ok(())
     }
 
+    // Rewrites the whole manifest atomically
+    fn write_checksums(&self) -> Result<(), IoError> {
+        let content: String = self
+            .checksums
+            .iter()
+            .map(|(file_id, checksum)| helper::checksum_line(*file_id, checksum))
+            .collect();
+        let tmp_path = self.file_path.join(format!("{CHECKSUMS_FILE_NAME}.tmp"));
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(content.as_bytes())?;
+        tmp.sync_all()?;
+        fs::rename(tmp_path, self.file_path.join(CHECKSUMS_FILE_NAME))
+    }
+
     /// Attempts to open files, initialize fd map
     pub fn preopen(&mut self) -> Result<(), IoError> {
         self.release_all();
@@ -474,6 +666,9 @@ impl FreezerFilesBuilder {
         index.sync_all()?;
 
         let number = index_size / INDEX_ENTRY_SIZE;
+        let mut checksums = self.load_checksums()?;
+        // the files after the tail are sealed, the others are left by a crash or truncation
+        checksums.retain(|file_id, _| (tail_id..head_index.file_id).contains(file_id));
 
         Ok(FreezerFiles {
             files: LruCache::new(self.open_files_limit),
@@ -485,9 +680,28 @@ impl FreezerFilesBuilder {
             file_path: self.file_path,
             index,
             enable_compression: self.enable_compression,
+            checksums,
         })
     }
 
+    fn load_checksums(&self) -> Result<BTreeMap<FileId, H256>, IoError> {
+        let path = self.file_path.join(CHECKSUMS_FILE_NAME);
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let mut checksums = BTreeMap::new();
+        for line in fs::read_to_string(path)?.lines() {
+            match helper::parse_checksum_line(line) {
+                Some((file_id, checksum)) => {
+                    checksums.insert(file_id, checksum);
+                }
+                // a partially written line is left by a crash, the file is rehashed by verify
+                None => ckb_logger::warn!("Freezer ignores invalid checksum line {:?}", line),
+            }
+        }
+        Ok(checksums)
+    }
+
     // Open the file without append mode
     // If a file is opened with both read and append access,
     // after opening, and after every write,
@@ -534,4 +748,21 @@ pub(crate) mod helper {
     pub(crate) fn file_name(file_id: FileId) -> String {
         format!("blk{file_id:06}")
     }
+
+    pub(crate) fn checksum_line(file_id: FileId, checksum: &H256) -> String {
+        format!("{} {:x}\n", file_name(file_id), checksum)
+    }
+
+    pub(crate) fn parse_checksum_line(line: &str) -> Option<(FileId, H256)> {
+        let (name, checksum) = line.split_once(' ')?;
+        let file_id = name.strip_prefix("blk")?.parse().ok()?;
+        let checksum = checksum.trim().parse().ok()?;
+        Some((file_id, checksum))
+    }
+}
+
+fn finalize(hasher: Blake2b) -> H256 {
+    let mut checksum = [0u8; 32];
+    hasher.finalize(&mut checksum);
+    checksum.into()
 }
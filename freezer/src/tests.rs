@@ -1,5 +1,6 @@
-use crate::freezer_files::helper::truncate_file;
-use crate::freezer_files::{FreezerFilesBuilder, INDEX_ENTRY_SIZE};
+use crate::freezer_files::helper::{file_name, truncate_file};
+use crate::freezer_files::{ChecksumStatus, FreezerFilesBuilder, INDEX_ENTRY_SIZE};
+use std::io::{Seek, SeekFrom, Write};
 
 fn make_bytes(size: usize, byte: u8) -> Vec<u8> {
     let mut ret = Vec::with_capacity(size);
@@ -285,3 +286,80 @@ fn open_files_limit_retrieve() {
         assert_eq!(Some(expect), actual);
     }
 }
+
+#[test]
+fn checksums() {
+    let tempdir = tempfile::Builder::new().tempdir().unwrap();
+    let root = {
+        let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+            .enable_compression(false)
+            .max_file_size(50)
+            .build()
+            .unwrap();
+        freezer.preopen().unwrap();
+        for i in 1..11 {
+            let data = make_bytes(15, i);
+            freezer.append(i.into(), &data).unwrap();
+        }
+
+        // 3 items per file, the head file is not sealed
+        assert_eq!(freezer.sealed_files(), 0..3);
+        assert_eq!(freezer.checksums.len(), 3);
+        assert_eq!(freezer.items_in_file(0).unwrap(), 1..4);
+        assert_eq!(freezer.items_in_file(1).unwrap(), 4..7);
+        assert_eq!(freezer.items_in_file(3).unwrap(), 10..11);
+        for file_id in freezer.sealed_files() {
+            assert_eq!(
+                freezer.verify_checksum(file_id).unwrap(),
+                ChecksumStatus::Matched
+            );
+        }
+
+        // flip a byte of the item 5
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(tempdir.path().join(file_name(1)))
+            .unwrap();
+        file.seek(SeekFrom::Start(20)).unwrap();
+        file.write_all(&[0xff]).unwrap();
+        assert_eq!(
+            freezer.verify_checksum(1).unwrap(),
+            ChecksumStatus::Mismatched
+        );
+
+        // the size of the item must be the same
+        assert!(freezer.overwrite(5, &make_bytes(16, 5)).is_err());
+        freezer.overwrite(5, &make_bytes(15, 5)).unwrap();
+        assert_eq!(freezer.retrieve(5).unwrap(), Some(make_bytes(15, 5)));
+        assert_eq!(freezer.verify_checksum(1).unwrap(), ChecksumStatus::Matched);
+        freezer.checksums_root()
+    };
+
+    {
+        let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+            .enable_compression(false)
+            .max_file_size(50)
+            .build()
+            .unwrap();
+        freezer.preopen().unwrap();
+        assert_eq!(freezer.checksums_root(), root);
+
+        // the checksums of the truncated files are removed
+        freezer.truncate(4).unwrap();
+        assert_eq!(freezer.sealed_files(), 0..1);
+        assert_eq!(freezer.checksums.len(), 1);
+    }
+
+    // the files sealed before the checksums were introduced
+    std::fs::remove_file(tempdir.path().join("CHECKSUMS")).unwrap();
+    let mut freezer = FreezerFilesBuilder::new(tempdir.path().to_path_buf())
+        .enable_compression(false)
+        .max_file_size(50)
+        .build()
+        .unwrap();
+    freezer.preopen().unwrap();
+    assert_eq!(freezer.verify_checksum(0).unwrap(), ChecksumStatus::Missing);
+    freezer.update_checksum(0).unwrap();
+    assert_eq!(freezer.verify_checksum(0).unwrap(), ChecksumStatus::Matched);
+    assert!(freezer.update_checksum(1).is_err());
+}
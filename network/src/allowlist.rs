@@ -0,0 +1,59 @@
+//! The allowlist of the peers in a private network.
+//!
+//! The peer ID of a session is derived from the public key the peer proves to own in the secio
+//! handshake, so checking the peer IDs of the sessions against the allowlist authenticates the
+//! peers in both directions.
+use crate::errors::Error;
+use p2p::secio::{PeerId, PublicKey};
+use std::collections::HashSet;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+
+// The length of a compressed secp256k1 public key
+const PUBLIC_KEY_LEN: usize = 33;
+
+/// Loads the allowed peers from the file.
+pub(crate) fn load_allowed_peers(path: &Path) -> Result<HashSet<PeerId>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    parse_allowed_peers(&content).map_err(|err| {
+        IoError::new(
+            ErrorKind::InvalidData,
+            format!("invalid allowed peers file {}: {}", path.display(), err),
+        )
+        .into()
+    })
+}
+
+/// Parses the allowed peers, one peer ID or hex encoded public key per line.
+pub(crate) fn parse_allowed_peers(content: &str) -> Result<HashSet<PeerId>, String> {
+    let mut peers = HashSet::new();
+    for (index, line) in content.lines().enumerate() {
+        let entry = line.split('#').next().unwrap_or_default().trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let peer_id = parse_peer(entry).ok_or_else(|| {
+            format!(
+                "line {}: {} is neither a peer ID nor a public key",
+                index + 1,
+                entry
+            )
+        })?;
+        peers.insert(peer_id);
+    }
+    Ok(peers)
+}
+
+fn parse_peer(entry: &str) -> Option<PeerId> {
+    let hex = entry.strip_prefix("0x").unwrap_or(entry);
+    if hex.len() == PUBLIC_KEY_LEN * 2 {
+        let mut key = vec![0u8; PUBLIC_KEY_LEN];
+        if faster_hex::hex_decode(hex.as_bytes(), &mut key).is_ok()
+            && (key[0] == 0x02 || key[0] == 0x03)
+        {
+            return Some(PublicKey::from_raw_key(key).peer_id());
+        }
+    }
+    PeerId::from_str(entry).ok()
+}
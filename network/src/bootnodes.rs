@@ -0,0 +1,167 @@
+//! Weighted bootnode sets, rotated away from the unreachable bootnodes.
+use ckb_app_config::NetworkConfig;
+use ckb_logger::{debug, info};
+use ckb_util::RwLock;
+use p2p::{multiaddr::Multiaddr, secio::PeerId, utils::extract_peer_id};
+use rand::Rng;
+use std::cmp::Ordering;
+
+// A bootnode is rotated away after failing this many consecutive dials
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// The health of a bootnode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BootnodeHealth {
+    /// The bootnode address.
+    pub address: Multiaddr,
+    /// The name of the set the bootnode belongs to.
+    pub set: String,
+    /// The weight of the set.
+    pub weight: u32,
+    /// The count of the dials failed since the last successful one.
+    pub consecutive_failures: u32,
+    /// The unix timestamp in milliseconds of the last successful connection.
+    pub last_success: Option<u64>,
+    /// The unix timestamp in milliseconds of the last failed dial.
+    pub last_failure: Option<u64>,
+    /// The unix timestamp in milliseconds of the last health check.
+    pub last_checked: Option<u64>,
+}
+
+impl BootnodeHealth {
+    /// Whether the bootnode is rotated away, it is picked again after a successful health check.
+    pub fn is_unhealthy(&self) -> bool {
+        self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES
+    }
+
+    fn matches(&self, peer_id: Option<&PeerId>, addr: &Multiaddr) -> bool {
+        match peer_id {
+            Some(peer_id) => extract_peer_id(&self.address).as_ref() == Some(peer_id),
+            None => &self.address == addr,
+        }
+    }
+}
+
+/// The bootnodes of all the sets with their health.
+///
+/// The bootnodes are picked by the weights of their sets, skipping the unhealthy ones unless all
+/// of them are unhealthy. Every bootnode is dialed once per health check interval, so the
+/// unhealthy ones are picked again once they are reachable.
+pub(crate) struct Bootnodes {
+    check_interval_ms: u64,
+    nodes: RwLock<Vec<BootnodeHealth>>,
+}
+
+impl Bootnodes {
+    pub(crate) fn new(config: &NetworkConfig) -> Self {
+        let mut nodes: Vec<BootnodeHealth> = Vec::new();
+        for set in config.bootnode_sets() {
+            for address in set.bootnodes {
+                // a bootnode listed in several sets keeps the first one
+                if nodes.iter().any(|node| node.address == address) {
+                    continue;
+                }
+                nodes.push(BootnodeHealth {
+                    address,
+                    set: set.name.clone(),
+                    weight: set.weight,
+                    consecutive_failures: 0,
+                    last_success: None,
+                    last_failure: None,
+                    last_checked: None,
+                });
+            }
+        }
+        Bootnodes {
+            check_interval_ms: config.bootnode_health_check_interval().as_millis() as u64,
+            nodes: RwLock::new(nodes),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.read().len()
+    }
+
+    /// Picks at most `count` bootnodes randomly by the weights of their sets.
+    pub(crate) fn choose<R: Rng>(&self, rng: &mut R, count: usize) -> Vec<Multiaddr> {
+        let nodes = self.nodes.read();
+        let healthy: Vec<_> = nodes
+            .iter()
+            .filter(|node| node.weight > 0 && !node.is_unhealthy())
+            .collect();
+        let candidates = if healthy.is_empty() {
+            // still try the unhealthy bootnodes, the node may have been offline for a while
+            nodes.iter().filter(|node| node.weight > 0).collect()
+        } else {
+            healthy
+        };
+
+        // weighted sampling without replacement, the nodes with the largest `u^(1/weight)` win
+        let mut keyed: Vec<_> = candidates
+            .into_iter()
+            .map(|node| {
+                let key = rng.gen::<f64>().powf(1.0 / f64::from(node.weight));
+                (key, &node.address)
+            })
+            .collect();
+        keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+        keyed
+            .into_iter()
+            .take(count)
+            .map(|(_, addr)| addr.clone())
+            .collect()
+    }
+
+    /// Returns the bootnodes due for a health check, and marks them checked.
+    pub(crate) fn due_for_check(&self, now_ms: u64) -> Vec<Multiaddr> {
+        let mut nodes = self.nodes.write();
+        nodes
+            .iter_mut()
+            .filter(|node| {
+                node.last_checked
+                    .map_or(true, |last| last + self.check_interval_ms <= now_ms)
+            })
+            .map(|node| {
+                node.last_checked = Some(now_ms);
+                node.address.clone()
+            })
+            .collect()
+    }
+
+    pub(crate) fn record_success(&self, addr: &Multiaddr, now_ms: u64) {
+        let peer_id = extract_peer_id(addr);
+        let mut nodes = self.nodes.write();
+        for node in nodes
+            .iter_mut()
+            .filter(|node| node.matches(peer_id.as_ref(), addr))
+        {
+            if node.is_unhealthy() {
+                info!("Bootnode {} is reachable again", node.address);
+            }
+            node.consecutive_failures = 0;
+            node.last_success = Some(now_ms);
+        }
+    }
+
+    pub(crate) fn record_failure(&self, addr: &Multiaddr, now_ms: u64) {
+        let peer_id = extract_peer_id(addr);
+        let mut nodes = self.nodes.write();
+        for node in nodes
+            .iter_mut()
+            .filter(|node| node.matches(peer_id.as_ref(), addr))
+        {
+            node.consecutive_failures = node.consecutive_failures.saturating_add(1);
+            node.last_failure = Some(now_ms);
+            if node.consecutive_failures == MAX_CONSECUTIVE_FAILURES {
+                debug!(
+                    "Bootnode {} failed {} dials in a row, rotate away from it",
+                    node.address, MAX_CONSECUTIVE_FAILURES
+                );
+            }
+        }
+    }
+
+    pub(crate) fn health(&self) -> Vec<BootnodeHealth> {
+        self.nodes.read().clone()
+    }
+}
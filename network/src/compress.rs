@@ -112,6 +112,11 @@ pub fn compress(src: Bytes) -> Bytes {
     Message::from_raw(src).compress()
 }
 
+/// Frame data without compressing it, the peers decompress it as usual
+pub fn uncompressed(src: Bytes) -> Bytes {
+    Message::from_raw(src).inner.freeze()
+}
+
 /// Decompress data
 pub fn decompress(src: BytesMut) -> Result<Bytes, io::Error> {
     Message::from_compressed(src).decompress()
@@ -0,0 +1,53 @@
+use ckb_util::Mutex;
+use p2p::secio::PeerId;
+use std::collections::HashMap;
+use tokio::sync::oneshot;
+
+/// The outcome of a dial requested by `NetworkController::dial_peer`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DialOutcome {
+    /// The session with the peer is opened.
+    Connected,
+    /// The address is banned, it's not dialed.
+    Banned,
+    /// The connection is established, but the handshake failed.
+    HandshakeFailed(String),
+    /// The dial failed before the handshake, or it's refused, e.g., the address is the node itself,
+    /// or the session is rejected by the peer registry.
+    Failed(String),
+}
+
+/// The waiters of the dials by the peer ids, they are notified once the session is opened or the
+/// dial fails.
+#[derive(Default)]
+pub(crate) struct DialWaiters {
+    waiters: Mutex<HashMap<PeerId, Vec<oneshot::Sender<DialOutcome>>>>,
+}
+
+impl DialWaiters {
+    pub(crate) fn wait(&self, peer_id: PeerId) -> oneshot::Receiver<DialOutcome> {
+        let (sender, receiver) = oneshot::channel();
+        let mut waiters = self.waiters.lock();
+        // drop the waiters which have given up, e.g., timed out
+        waiters.retain(|_, senders| {
+            senders.retain(|sender| !sender.is_closed());
+            !senders.is_empty()
+        });
+        waiters.entry(peer_id).or_default().push(sender);
+        receiver
+    }
+
+    pub(crate) fn notify(&self, peer_id: &PeerId, outcome: DialOutcome) {
+        let senders = self.waiters.lock().remove(peer_id);
+        for sender in senders.into_iter().flatten() {
+            let _ = sender.send(outcome.clone());
+        }
+    }
+
+    /// Returns a receiver which has got the outcome, for the dial decided without dialing.
+    pub(crate) fn ready(outcome: DialOutcome) -> oneshot::Receiver<DialOutcome> {
+        let (sender, receiver) = oneshot::channel();
+        let _ = sender.send(outcome);
+        receiver
+    }
+}
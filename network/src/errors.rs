@@ -66,8 +66,12 @@ pub enum PeerError {
     Banned,
     /// Reach max inbound limit
     ReachMaxInboundLimit,
+    /// Reach the inbound limit of the listeners of the same kind
+    ReachListenerInboundLimit,
     /// Reach max outbound limit
     ReachMaxOutboundLimit,
+    /// Not in the allowlist of the private network
+    NotAllowed,
 }
 
 impl From<PeerStoreError> for Error {
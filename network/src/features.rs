@@ -0,0 +1,150 @@
+//! P2P features negotiated with the peers.
+//!
+//! A feature is negotiated with a peer when the protocols which provide it are opened on the
+//! session, at a version which supports it, and the peer announces the required identify flags.
+//! Since the protocol versions are negotiated by tentacle, both sides support the feature.
+//!
+//! A new P2P behaviour should be registered in [`FEATURES`] with the protocol version which
+//! introduces it, and only used with the peers for which [`Peer::supports`] returns true, so it
+//! can be rolled out while the older nodes are still on the network. The features disabled by the
+//! local config, see [`PeerFeatures::local`], are never negotiated.
+use crate::{protocols::identify::Flags, Peer, SupportProtocols};
+use ckb_app_config::NetworkConfig;
+
+bitflags::bitflags! {
+    /// P2P features which can be negotiated with a peer
+    pub struct PeerFeatures: u64 {
+        /// Snappy compression of the large messages
+        const COMPRESSION = 0b1;
+        /// Compact block relay
+        const COMPACT_RELAY = 0b10;
+        /// Light client data and existence-proof services
+        const LIGHT_CLIENT = 0b100;
+        /// Client-side block filter download service
+        const BLOCK_FILTER = 0b1000;
+        /// The peer accepts the full small transactions pushed without announcements
+        const TX_PUSH = 0b10000;
+    }
+}
+
+/// What a peer must support for a feature to be negotiated.
+struct FeatureRequirement {
+    feature: PeerFeatures,
+    name: &'static str,
+    /// One of the protocols must be opened
+    protocols: &'static [SupportProtocols],
+    /// The minimum major version of the opened protocol
+    min_version: u32,
+    /// The identify flags the peer must announce
+    flags: Flags,
+}
+
+const FEATURES: &[FeatureRequirement] = &[
+    // every CKB protocol compresses its large messages unless `network.compression` is
+    // disabled, see `compress`
+    FeatureRequirement {
+        feature: PeerFeatures::COMPRESSION,
+        name: "compression",
+        protocols: &[SupportProtocols::Identify],
+        min_version: 0,
+        flags: Flags::empty(),
+    },
+    FeatureRequirement {
+        feature: PeerFeatures::COMPACT_RELAY,
+        name: "compact_relay",
+        protocols: &[SupportProtocols::RelayV2, SupportProtocols::RelayV3],
+        min_version: 0,
+        flags: Flags::empty(),
+    },
+    FeatureRequirement {
+        feature: PeerFeatures::LIGHT_CLIENT,
+        name: "light_client",
+        protocols: &[SupportProtocols::LightClient],
+        min_version: 0,
+        flags: Flags::LIGHT_CLIENT,
+    },
+    FeatureRequirement {
+        feature: PeerFeatures::BLOCK_FILTER,
+        name: "block_filter",
+        protocols: &[SupportProtocols::Filter],
+        min_version: 0,
+        flags: Flags::BLOCK_FILTER,
+    },
+    FeatureRequirement {
+        feature: PeerFeatures::TX_PUSH,
+        name: "tx_push",
+        protocols: &[SupportProtocols::RelayV2, SupportProtocols::RelayV3],
+        min_version: 0,
+        flags: Flags::TX_PUSH,
+    },
+];
+
+const FLAG_NAMES: &[(Flags, &str)] = &[
+    (Flags::COMPATIBILITY, "compatibility"),
+    (Flags::DISCOVERY, "discovery"),
+    (Flags::SYNC, "sync"),
+    (Flags::RELAY, "relay"),
+    (Flags::LIGHT_CLIENT, "light_client"),
+    (Flags::BLOCK_FILTER, "block_filter"),
+    (Flags::TX_PUSH, "tx_push"),
+];
+
+impl PeerFeatures {
+    /// Returns the features enabled by the local config
+    pub fn local(config: &NetworkConfig) -> PeerFeatures {
+        let mut features = PeerFeatures::all();
+        features.set(PeerFeatures::COMPRESSION, config.compression);
+        features
+    }
+
+    /// Returns the features negotiated with the peer
+    pub fn negotiated(peer: &Peer) -> PeerFeatures {
+        let flags = peer
+            .identify_info
+            .as_ref()
+            .map(|info| info.flags)
+            .unwrap_or_else(Flags::empty);
+        FEATURES
+            .iter()
+            .filter(|requirement| {
+                flags.contains(requirement.flags)
+                    && requirement.protocols.iter().any(|protocol| {
+                        peer.protocol_version(protocol.protocol_id())
+                            .map_or(false, |version| {
+                                major_version(&version) >= requirement.min_version
+                            })
+                    })
+            })
+            .fold(PeerFeatures::empty(), |features, requirement| {
+                features | requirement.feature
+            })
+            & peer.local_features
+    }
+
+    /// Returns the names of the features, used by the RPC
+    pub fn names(&self) -> Vec<&'static str> {
+        FEATURES
+            .iter()
+            .filter(|requirement| self.contains(requirement.feature))
+            .map(|requirement| requirement.name)
+            .collect()
+    }
+}
+
+/// Returns the names of the identify flags, used by the RPC
+pub fn flag_names(flags: Flags) -> Vec<&'static str> {
+    FLAG_NAMES
+        .iter()
+        .filter(|(flag, _)| flags.contains(*flag))
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+// The versions are "1", "2", "3", while the earliest protocols use "0.0.1"
+fn major_version(version: &str) -> u32 {
+    version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok())
+        .unwrap_or(0)
+}
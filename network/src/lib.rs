@@ -6,28 +6,44 @@
 //! And implemented several basic protocols: identify, discovery, ping, feeler, disconnect_message
 //!
 
+mod allowlist;
 mod behaviour;
+mod bootnodes;
 /// compress module
 pub mod compress;
+mod dial_waiters;
 pub mod errors;
+pub mod features;
+mod listeners;
+mod misbehavior;
 pub mod network;
 mod network_group;
+mod outbound_targets;
 mod peer;
 pub mod peer_registry;
 pub mod peer_store;
 mod protocols;
+mod reachability;
+mod send_priority;
 mod services;
+mod traffic;
 
 #[cfg(test)]
 mod tests;
 
 pub use crate::{
     behaviour::Behaviour,
+    bootnodes::BootnodeHealth,
+    dial_waiters::DialOutcome,
     errors::Error,
+    features::PeerFeatures,
+    listeners::ListenerInfo,
+    misbehavior::{MisbehaviorComponent, PeerMisbehavior},
     network::{
         DefaultExitHandler, EventHandler, ExitHandler, NetworkController, NetworkService,
         NetworkState,
     },
+    outbound_targets::OutboundTargets,
     peer::{Peer, PeerIdentifyInfo},
     peer_registry::PeerRegistry,
     peer_store::Score,
@@ -35,6 +51,8 @@ pub use crate::{
         identify::Flags, support_protocols::SupportProtocols, CKBProtocol, CKBProtocolContext,
         CKBProtocolHandler, PeerIndex,
     },
+    reachability::{Reachability, ReachabilityStatus},
+    traffic::ProtocolTraffic,
 };
 pub use p2p::{
     async_trait,
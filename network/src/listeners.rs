@@ -0,0 +1,147 @@
+//! The listeners with their own inbound caps and advertisement.
+use ckb_app_config::NetworkConfig;
+use ckb_util::RwLock;
+use p2p::multiaddr::{Multiaddr, Protocol};
+use std::collections::HashMap;
+
+/// The IP version and the transport of an address.
+///
+/// Tentacle does not tell which listener accepts a session, so the inbound peers are attributed
+/// to the listeners by the kind of their addresses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ListenerKind {
+    ipv6: bool,
+    ws: bool,
+}
+
+impl ListenerKind {
+    pub(crate) fn of(addr: &Multiaddr) -> Option<ListenerKind> {
+        let mut ipv6 = None;
+        let mut ws = false;
+        for proto in addr.iter() {
+            match proto {
+                Protocol::Ip4(_) => ipv6 = Some(false),
+                Protocol::Ip6(_) => ipv6 = Some(true),
+                Protocol::Ws => ws = true,
+                _ => (),
+            }
+        }
+        ipv6.map(|ipv6| ListenerKind { ipv6, ws })
+    }
+}
+
+/// The state of a listener.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListenerInfo {
+    /// The configured address.
+    pub address: Multiaddr,
+    /// The address listened on, e.g., with the port assigned by the system.
+    ///
+    /// It is `None` before the listener is started.
+    pub listened_address: Option<Multiaddr>,
+    /// The maximum number of inbound peers, `None` if only limited by `max_peers`.
+    pub max_inbound_peers: Option<u32>,
+    /// Whether the address is advertised to the peers.
+    pub advertise: bool,
+    /// The count of the inbound peers, excluding the whitelist peers.
+    ///
+    /// The listeners with the same IP version and transport share the count.
+    pub inbound_peers: u32,
+}
+
+pub(crate) struct Listeners {
+    listeners: RwLock<Vec<ListenerInfo>>,
+}
+
+impl Listeners {
+    pub(crate) fn new(config: &NetworkConfig) -> Self {
+        let listeners = config
+            .listeners()
+            .into_iter()
+            .map(|listener| ListenerInfo {
+                address: listener.address,
+                listened_address: None,
+                max_inbound_peers: listener.max_inbound_peers,
+                advertise: listener.advertise,
+                inbound_peers: 0,
+            })
+            .collect();
+        Listeners {
+            listeners: RwLock::new(listeners),
+        }
+    }
+
+    pub(crate) fn addresses(&self) -> Vec<Multiaddr> {
+        self.listeners
+            .read()
+            .iter()
+            .map(|listener| listener.address.clone())
+            .collect()
+    }
+
+    pub(crate) fn set_listened(&self, address: &Multiaddr, listened_address: Multiaddr) {
+        if let Some(listener) = self
+            .listeners
+            .write()
+            .iter_mut()
+            .find(|listener| &listener.address == address)
+        {
+            listener.listened_address = Some(listened_address);
+        }
+    }
+
+    /// The listened addresses advertised to the peers.
+    pub(crate) fn advertised(&self) -> Vec<Multiaddr> {
+        self.listeners
+            .read()
+            .iter()
+            .filter(|listener| listener.advertise)
+            .filter_map(|listener| listener.listened_address.clone())
+            .collect()
+    }
+
+    /// The inbound caps by listener kind, the listeners of the same kind share the sum of their
+    /// caps, and the kind is not capped if any of its listeners is not.
+    pub(crate) fn inbound_caps(&self) -> HashMap<ListenerKind, u32> {
+        let mut caps: HashMap<ListenerKind, Option<u32>> = HashMap::new();
+        for listener in self.listeners.read().iter() {
+            if let Some(kind) = ListenerKind::of(&listener.address) {
+                let cap = caps.entry(kind).or_insert(Some(0));
+                *cap = cap
+                    .zip(listener.max_inbound_peers)
+                    .map(|(a, b)| a.saturating_add(b));
+            }
+        }
+        caps.into_iter()
+            .filter_map(|(kind, cap)| cap.map(|cap| (kind, cap)))
+            .collect()
+    }
+
+    /// The sum of the inbound caps of all the listeners, `None` if any of them is not capped.
+    ///
+    /// It caps the inbound peers in total, including the ones whose addresses are not of any
+    /// listener kind.
+    pub(crate) fn total_inbound_cap(&self) -> Option<u32> {
+        self.listeners
+            .read()
+            .iter()
+            .try_fold(0u32, |total, listener| {
+                listener
+                    .max_inbound_peers
+                    .map(|cap| total.saturating_add(cap))
+            })
+    }
+
+    pub(crate) fn infos(&self, inbound_peers: &HashMap<ListenerKind, u32>) -> Vec<ListenerInfo> {
+        self.listeners
+            .read()
+            .iter()
+            .map(|listener| ListenerInfo {
+                inbound_peers: ListenerKind::of(&listener.address)
+                    .and_then(|kind| inbound_peers.get(&kind).copied())
+                    .unwrap_or(0),
+                ..listener.clone()
+            })
+            .collect()
+    }
+}
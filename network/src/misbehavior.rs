@@ -0,0 +1,177 @@
+//! Misbehavior scores of the peers, reported by all the protocols.
+use crate::PeerId;
+use ckb_util::Mutex;
+use std::collections::HashMap;
+
+// The max number of the peers scored, the lowest scores are dropped beyond it
+pub(crate) const MAX_SCORED_PEERS: usize = 4096;
+// The scores decayed below it are dropped
+const MIN_KEPT_SCORE: f64 = 1.0;
+
+/// The score of a peer from one kind of misbehavior.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MisbehaviorComponent {
+    /// The misbehavior kind, e.g., `BlockIsInvalid`.
+    pub kind: String,
+    /// The score decayed to now.
+    pub score: f64,
+    /// The count of the reports of this kind.
+    pub count: u64,
+    /// The reason of the last report.
+    pub last_reason: String,
+    /// The unix timestamp in milliseconds of the last report.
+    pub last_reported_at: u64,
+}
+
+/// The misbehavior score of a peer with its components.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerMisbehavior {
+    /// The total score decayed to now.
+    pub score: f64,
+    /// The scores by misbehavior kind, the highest first.
+    pub components: Vec<MisbehaviorComponent>,
+}
+
+impl PeerMisbehavior {
+    /// The score breakdown attached to the ban reason, e.g., `BlockIsInvalid=100.0(1)`.
+    pub fn breakdown(&self) -> String {
+        self.components
+            .iter()
+            .map(|c| format!("{}={:.1}({})", c.kind, c.score, c.count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The misbehavior scores of the peers, keyed by the peer ID.
+///
+/// Every score halves once per half life, so a peer which misbehaves now and then is not banned
+/// for the mistakes long ago. The scores are kept after the peer disconnects, so a peer can't
+/// reset its score by reconnecting, and are dropped once decayed below 1 or when the peer is
+/// banned. At most `MAX_SCORED_PEERS` peers are scored.
+pub(crate) struct MisbehaviorScores {
+    // 0 disables the decay
+    half_life_ms: u64,
+    peers: Mutex<HashMap<PeerId, HashMap<String, MisbehaviorComponent>>>,
+}
+
+impl MisbehaviorScores {
+    pub(crate) fn new(half_life_ms: u64) -> Self {
+        MisbehaviorScores {
+            half_life_ms,
+            peers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds the weight to the score of the kind, returns the total score of the peer.
+    pub(crate) fn report(
+        &self,
+        peer_id: &PeerId,
+        kind: &str,
+        weight: u32,
+        reason: &str,
+        now_ms: u64,
+    ) -> f64 {
+        let mut peers = self.peers.lock();
+        if !peers.contains_key(peer_id) && peers.len() >= MAX_SCORED_PEERS {
+            self.evict(&mut peers, now_ms);
+        }
+        let components = peers.entry(peer_id.clone()).or_default();
+        let component = components
+            .entry(kind.to_owned())
+            .or_insert_with(|| MisbehaviorComponent {
+                kind: kind.to_owned(),
+                score: 0.0,
+                count: 0,
+                last_reason: String::new(),
+                last_reported_at: now_ms,
+            });
+        component.score =
+            self.decay(component.score, component.last_reported_at, now_ms) + f64::from(weight);
+        component.count += 1;
+        component.last_reason = reason.to_owned();
+        component.last_reported_at = now_ms;
+
+        components
+            .values()
+            .map(|c| self.decay(c.score, c.last_reported_at, now_ms))
+            .sum()
+    }
+
+    /// Returns the scores of the peer decayed to now.
+    pub(crate) fn peer(&self, peer_id: &PeerId, now_ms: u64) -> Option<PeerMisbehavior> {
+        self.peers
+            .lock()
+            .get(peer_id)
+            .map(|components| self.snapshot(components, now_ms))
+    }
+
+    pub(crate) fn peers(&self, now_ms: u64) -> Vec<(PeerId, PeerMisbehavior)> {
+        self.peers
+            .lock()
+            .iter()
+            .map(|(peer_id, components)| (peer_id.clone(), self.snapshot(components, now_ms)))
+            .collect()
+    }
+
+    pub(crate) fn remove(&self, peer_id: &PeerId) {
+        self.peers.lock().remove(peer_id);
+    }
+
+    // Drops the scores decayed out, or the lowest one when none has
+    fn evict(
+        &self,
+        peers: &mut HashMap<PeerId, HashMap<String, MisbehaviorComponent>>,
+        now_ms: u64,
+    ) {
+        let total = |components: &HashMap<String, MisbehaviorComponent>| -> f64 {
+            components
+                .values()
+                .map(|c| self.decay(c.score, c.last_reported_at, now_ms))
+                .sum()
+        };
+        peers.retain(|_, components| total(components) >= MIN_KEPT_SCORE);
+        if peers.len() >= MAX_SCORED_PEERS {
+            let lowest = peers
+                .iter()
+                .map(|(peer_id, components)| (peer_id, total(components)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(peer_id, _)| peer_id.clone());
+            if let Some(peer_id) = lowest {
+                peers.remove(&peer_id);
+            }
+        }
+    }
+
+    fn snapshot(
+        &self,
+        components: &HashMap<String, MisbehaviorComponent>,
+        now_ms: u64,
+    ) -> PeerMisbehavior {
+        let mut components: Vec<_> = components
+            .values()
+            .map(|c| MisbehaviorComponent {
+                score: self.decay(c.score, c.last_reported_at, now_ms),
+                ..c.clone()
+            })
+            .collect();
+        components.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.kind.cmp(&b.kind))
+        });
+        PeerMisbehavior {
+            score: components.iter().map(|c| c.score).sum(),
+            components,
+        }
+    }
+
+    fn decay(&self, score: f64, since_ms: u64, now_ms: u64) -> f64 {
+        if self.half_life_ms == 0 {
+            return score;
+        }
+        let elapsed = now_ms.saturating_sub(since_ms) as f64;
+        score * 0.5f64.powf(elapsed / self.half_life_ms as f64)
+    }
+}
@@ -1,5 +1,11 @@
 //! Global state struct and start function
+use crate::allowlist::load_allowed_peers;
+use crate::bootnodes::{BootnodeHealth, Bootnodes};
+use crate::dial_waiters::{DialOutcome, DialWaiters};
 use crate::errors::{Error, P2PError};
+use crate::listeners::{ListenerInfo, Listeners};
+use crate::misbehavior::{MisbehaviorScores, PeerMisbehavior};
+use crate::outbound_targets::{OutboundTargets, OutboundTargetsAdapter};
 use crate::peer_registry::{ConnectionStatus, PeerRegistry};
 use crate::peer_store::{
     types::{AddrInfo, BannedAddr},
@@ -13,11 +19,14 @@ use crate::protocols::{
     ping::PingHandler,
     support_protocols::SupportProtocols,
 };
+use crate::reachability::{Reachability, ReachabilityProbe, ReachabilityStatus};
+use crate::send_priority::SendScheduler;
 use crate::services::{
     dump_peer_store::DumpPeerStoreService, outbound_peer::OutboundPeerService,
     protocol_type_checker::ProtocolTypeCheckerService,
 };
-use crate::{Behaviour, CKBProtocol, Peer, PeerIndex, ProtocolId, ServiceControl};
+use crate::traffic::{ProtocolTraffic, TrafficStats};
+use crate::{Behaviour, CKBProtocol, Peer, PeerFeatures, PeerIndex, ProtocolId, ServiceControl};
 use ckb_app_config::{default_support_all_protocols, NetworkConfig, SupportProtocol};
 use ckb_logger::{debug, error, info, trace, warn};
 use ckb_spawn::Spawn;
@@ -70,16 +79,17 @@ const DIAL_HANG_TIMEOUT: Duration = Duration::from_secs(300);
 pub struct NetworkState {
     pub(crate) peer_registry: RwLock<PeerRegistry>,
     pub(crate) peer_store: Mutex<PeerStore>,
-    /// Node listened addresses
-    pub(crate) listened_addrs: RwLock<Vec<Multiaddr>>,
+    /// Node listeners with their listened addresses
+    pub(crate) listeners: Listeners,
     dialing_addrs: RwLock<HashMap<PeerId, Instant>>,
+    dial_waiters: DialWaiters,
     /// Node public addresses,
     /// includes manually public addrs and remote peer observed addrs
     public_addrs: RwLock<HashSet<Multiaddr>>,
     pending_observed_addrs: RwLock<HashSet<Multiaddr>>,
     local_private_key: secio::SecioKeyPair,
     local_peer_id: PeerId,
-    pub(crate) bootnodes: Vec<Multiaddr>,
+    pub(crate) bootnodes: Bootnodes,
     pub(crate) config: NetworkConfig,
     pub(crate) active: AtomicBool,
     /// Node supported protocols
@@ -88,6 +98,16 @@ pub struct NetworkState {
     pub(crate) required_flags: Flags,
 
     pub(crate) ckb2023: AtomicBool,
+    /// Message bytes sent and received by session and protocol
+    pub(crate) traffic: TrafficStats,
+    /// Outbound peer targets by role
+    pub(crate) outbound_targets: OutboundTargetsAdapter,
+    /// Misbehavior scores reported by the protocols
+    pub(crate) misbehaviors: MisbehaviorScores,
+    /// Send queue selection by message priority
+    pub(crate) send_scheduler: SendScheduler,
+    /// The connectivity self-test
+    pub(crate) reachability: ReachabilityProbe,
 }
 
 impl NetworkState {
@@ -98,10 +118,11 @@ impl NetworkState {
         let local_peer_id = local_private_key.peer_id();
         // set max score to public addresses
         let public_addrs: HashSet<Multiaddr> = config
-            .listen_addresses
-            .iter()
-            .chain(config.public_addresses.iter())
-            .cloned()
+            .listeners()
+            .into_iter()
+            .filter(|listener| listener.advertise)
+            .map(|listener| listener.address)
+            .chain(config.public_addresses.iter().cloned())
             .filter_map(|mut addr| {
                 multiaddr_to_socketaddr(&addr)
                     .filter(|addr| is_reachable(addr.ip()))
@@ -117,14 +138,31 @@ impl NetworkState {
         let peer_store = Mutex::new(PeerStore::load_from_dir_or_default(
             config.peer_store_path(),
         ));
-        let bootnodes = config.bootnodes();
+        let bootnodes = Bootnodes::new(&config);
+        let listeners = Listeners::new(&config);
 
-        let peer_registry = PeerRegistry::new(
+        let mut peer_registry = PeerRegistry::new(
             config.max_inbound_peers(),
             config.max_outbound_peers(),
             config.whitelist_only,
             config.whitelist_peers(),
         );
+        peer_registry.set_listener_inbound_caps(&listeners);
+        peer_registry.set_local_features(PeerFeatures::local(&config));
+        if let Some(path) = config.allowed_peers_file.as_ref() {
+            let allowed_peers = load_allowed_peers(path)?;
+            info!(
+                "Private network, {} peers allowed by {}",
+                allowed_peers.len(),
+                path.display()
+            );
+            peer_registry.set_allowed_peers(Some(allowed_peers));
+        }
+
+        let outbound_targets = OutboundTargetsAdapter::new(&config);
+        let misbehaviors =
+            MisbehaviorScores::new(config.misbehavior_decay_half_life().as_millis() as u64);
+        let reachability = ReachabilityProbe::new(&config);
 
         Ok(NetworkState {
             peer_store,
@@ -132,8 +170,9 @@ impl NetworkState {
             bootnodes,
             peer_registry: RwLock::new(peer_registry),
             dialing_addrs: RwLock::new(HashMap::default()),
+            dial_waiters: DialWaiters::default(),
             public_addrs: RwLock::new(public_addrs),
-            listened_addrs: RwLock::new(Vec::new()),
+            listeners,
             pending_observed_addrs: RwLock::new(HashSet::default()),
             local_private_key,
             local_peer_id,
@@ -141,6 +180,11 @@ impl NetworkState {
             protocols: RwLock::new(Vec::new()),
             required_flags: Flags::SYNC | Flags::DISCOVERY | Flags::RELAY,
             ckb2023: AtomicBool::new(false),
+            traffic: TrafficStats::default(),
+            outbound_targets,
+            misbehaviors,
+            send_scheduler: SendScheduler::default(),
+            reachability,
         })
     }
 
@@ -225,6 +269,61 @@ impl NetworkState {
         }
     }
 
+    /// Adds the weight of the misbehavior kind to the score of the peer, and bans the peer for
+    /// `duration` once the score reaches the threshold.
+    pub(crate) fn misbehave(
+        &self,
+        p2p_control: &ServiceControl,
+        session_id: SessionId,
+        kind: &str,
+        duration: Duration,
+        reason: String,
+    ) {
+        let peer_id = match self.with_peer_registry(|reg| {
+            reg.get_peer(session_id)
+                .and_then(|peer| extract_peer_id(&peer.connected_addr))
+        }) {
+            Some(peer_id) => peer_id,
+            None => {
+                debug!(
+                    "Report misbehavior of session({}) failed: not found in peer registry",
+                    session_id
+                );
+                return;
+            }
+        };
+        let weight = self.config.misbehavior_weight(kind);
+        let threshold = self.config.misbehavior_ban_threshold();
+        let now = ckb_systemtime::unix_time_as_millis();
+        let score = self
+            .misbehaviors
+            .report(&peer_id, kind, weight, &reason, now);
+        if let Some(metrics) = ckb_metrics::handle() {
+            metrics
+                .ckb_network_misbehavior
+                .with_label_values(&[kind])
+                .inc();
+        }
+        debug!(
+            "Session({}) {:?} misbehaves: {} (+{}), score {:.1}/{}, reason: {}",
+            session_id, peer_id, kind, weight, score, threshold, reason
+        );
+        if score >= f64::from(threshold) {
+            let breakdown = self
+                .misbehaviors
+                .peer(&peer_id, now)
+                .map(|misbehavior| misbehavior.breakdown())
+                .unwrap_or_default();
+            self.misbehaviors.remove(&peer_id);
+            self.ban_session(
+                p2p_control,
+                session_id,
+                duration,
+                format!("{reason}, misbehavior score {score:.1} >= {threshold}: {breakdown}"),
+            );
+        }
+    }
+
     pub(crate) fn accept_peer(
         &self,
         session_context: &SessionContext,
@@ -251,6 +350,41 @@ impl NetworkState {
         callback(&self.peer_registry.read())
     }
 
+    /// Sessions of the connected peers which have opened the protocol
+    pub(crate) fn protocol_sessions(&self, proto_id: ProtocolId) -> Vec<SessionId> {
+        self.with_peer_registry(|reg| {
+            reg.peers()
+                .iter()
+                .filter(|(_, peer)| peer.protocols.contains_key(&proto_id))
+                .map(|(session_id, _)| *session_id)
+                .collect()
+        })
+    }
+
+    /// Resolves a broadcast target to the explicit sessions, which are recorded by
+    /// `record_broadcast` after the message is sent
+    pub(crate) fn resolve_broadcast(
+        &self,
+        target: TargetSession,
+        proto_id: ProtocolId,
+    ) -> Vec<SessionId> {
+        let sessions = match target {
+            TargetSession::All | TargetSession::Filter(_) => self.protocol_sessions(proto_id),
+            _ => Vec::new(),
+        };
+        TrafficStats::resolve_target(target, sessions)
+    }
+
+    /// Records a message sent to the sessions resolved by `resolve_broadcast`
+    pub(crate) fn record_broadcast(
+        &self,
+        sessions: &[SessionId],
+        proto_id: ProtocolId,
+        len: usize,
+    ) {
+        self.traffic.record_broadcast(sessions, proto_id, len)
+    }
+
     // For restrict lock in inner scope
     pub(crate) fn with_peer_registry_mut<F, T>(&self, callback: F) -> T
     where
@@ -297,7 +431,7 @@ impl NetworkState {
 
     /// Get local node's listen address list
     pub fn public_urls(&self, max_urls: usize) -> Vec<(String, u8)> {
-        let listened_addrs = self.listened_addrs.read();
+        let listened_addrs = self.listeners.advertised();
         self.public_addrs(max_urls.saturating_sub(listened_addrs.len()))
             .into_iter()
             .filter_map(|addr| {
@@ -316,6 +450,40 @@ impl NetworkState {
         self.dial_identify(p2p_control, address);
     }
 
+    pub(crate) fn dial_peer(
+        &self,
+        p2p_control: &ServiceControl,
+        address: Multiaddr,
+    ) -> oneshot::Receiver<DialOutcome> {
+        let peer_id = match extract_peer_id(&address) {
+            Some(peer_id) => peer_id,
+            None => {
+                return DialWaiters::ready(DialOutcome::Failed(format!(
+                    "no peer id in the address {address}"
+                )))
+            }
+        };
+        if self.peer_store.lock().is_addr_banned(&address) {
+            return DialWaiters::ready(DialOutcome::Banned);
+        }
+        if self.with_peer_registry(|reg| reg.get_key_by_peer_id(&peer_id).is_some()) {
+            return DialWaiters::ready(DialOutcome::Connected);
+        }
+        let receiver = self.dial_waiters.wait(peer_id.clone());
+        if let Err(err) = self.dial_inner(
+            p2p_control,
+            address,
+            TargetProtocol::Single(SupportProtocols::Identify.protocol_id()),
+        ) {
+            // the outcome of the dial in flight is waited for as well
+            if !self.dialing_addrs.read().contains_key(&peer_id) {
+                self.dial_waiters
+                    .notify(&peer_id, DialOutcome::Failed(err.to_string()));
+            }
+        }
+        receiver
+    }
+
     /// use a filter to get protocol id list
     pub fn get_protocol_ids<F: Fn(ProtocolId) -> bool>(&self, filter: F) -> Vec<ProtocolId> {
         self.protocols
@@ -346,6 +514,11 @@ impl NetworkState {
             return false;
         }
 
+        if !self.with_peer_registry(|reg| reg.is_allowed(peer_id)) {
+            trace!("Do not dial peer not allowed: {:?}, {}", peer_id, addr);
+            return false;
+        }
+
         let peer_in_registry = self.with_peer_registry(|reg| {
             reg.get_key_by_peer_id(peer_id).is_some() || reg.is_feeler(addr)
         });
@@ -388,15 +561,27 @@ impl NetworkState {
         if let Some(peer_id) = extract_peer_id(addr) {
             self.dialing_addrs.write().remove(&peer_id);
         }
+        self.bootnodes
+            .record_success(addr, ckb_systemtime::unix_time_as_millis());
+    }
+
+    /// Reports the outcome to the waiters of the dial, once the session is accepted or rejected.
+    pub(crate) fn dial_accepted(&self, addr: &Multiaddr, outcome: DialOutcome) {
+        if let Some(peer_id) = extract_peer_id(addr) {
+            self.dial_waiters.notify(&peer_id, outcome);
+        }
     }
 
-    pub(crate) fn dial_failed(&self, addr: &Multiaddr) {
+    pub(crate) fn dial_failed(&self, addr: &Multiaddr, outcome: DialOutcome) {
         self.with_peer_registry_mut(|reg| {
             reg.remove_feeler(addr);
         });
+        self.bootnodes
+            .record_failure(addr, ckb_systemtime::unix_time_as_millis());
 
         if let Some(peer_id) = extract_peer_id(addr) {
             self.dialing_addrs.write().remove(&peer_id);
+            self.dial_waiters.notify(&peer_id, outcome);
         }
     }
 
@@ -449,14 +634,35 @@ impl NetworkState {
 
     /// this method is intent to check observed addr by dial to self
     pub(crate) fn try_dial_observed_addrs(&self, p2p_control: &ServiceControl) {
+        let now_ms = ckb_systemtime::unix_time_as_millis();
         let mut pending_observed_addrs = self.pending_observed_addrs.write();
-        if pending_observed_addrs.is_empty() {
+        if self.reachability.is_due(now_ms) {
+            // the connectivity self-test dials all the public addresses
+            self.reachability.start(now_ms);
+            let addrs: HashSet<Multiaddr> = self
+                .public_addrs
+                .read()
+                .iter()
+                .cloned()
+                .chain(pending_observed_addrs.drain())
+                .collect();
+            for addr in addrs {
+                self.reachability.dialing(addr.clone(), now_ms);
+                if let Err(err) = p2p_control.dial(
+                    addr,
+                    TargetProtocol::Single(SupportProtocols::Identify.protocol_id()),
+                ) {
+                    trace!("try_dial_observed_addrs {err} failed in reachability self-test")
+                }
+            }
+        } else if pending_observed_addrs.is_empty() {
             let addrs = self.public_addrs.read();
             if addrs.is_empty() {
                 return;
             }
             // random get addr
             if let Some(addr) = addrs.iter().choose(&mut rand::thread_rng()) {
+                self.reachability.dialing(addr.clone(), now_ms);
                 if let Err(err) = p2p_control.dial(
                     addr.clone(),
                     TargetProtocol::Single(SupportProtocols::Identify.protocol_id()),
@@ -467,6 +673,7 @@ impl NetworkState {
         } else {
             for addr in pending_observed_addrs.drain() {
                 trace!("try dial observed addr: {:?}", addr);
+                self.reachability.dialing(addr.clone(), now_ms);
                 if let Err(err) = p2p_control.dial(
                     addr,
                     TargetProtocol::Single(SupportProtocols::Identify.protocol_id()),
@@ -477,6 +684,26 @@ impl NetworkState {
         }
     }
 
+    /// Reports the connectivity self-test, and updates the metrics
+    pub(crate) fn report_reachability(&self) -> Reachability {
+        let reachability = self
+            .reachability
+            .report(ckb_systemtime::unix_time_as_millis());
+        if let Some(metrics) = ckb_metrics::handle() {
+            for status in [
+                ReachabilityStatus::Unknown,
+                ReachabilityStatus::Reachable,
+                ReachabilityStatus::Unreachable,
+            ] {
+                metrics
+                    .ckb_network_reachability
+                    .with_label_values(&[status.as_str()])
+                    .set((status == reachability.status) as i64);
+            }
+        }
+        reachability
+    }
+
     /// add observed address for identify protocol
     pub(crate) fn add_observed_addrs(&self, iter: impl Iterator<Item = Multiaddr>) {
         let mut pending_observed_addrs = self.pending_observed_addrs.write();
@@ -561,12 +788,23 @@ impl ServiceHandle for EventHandler {
         match error {
             ServiceError::DialerError { address, error } => {
                 let mut public_addrs = self.network_state.public_addrs.write();
+                let outcome = match error {
+                    DialerErrorKind::HandshakeError(_) => {
+                        DialOutcome::HandshakeFailed(error.to_string())
+                    }
+                    _ => DialOutcome::Failed(error.to_string()),
+                };
 
                 match error {
                     DialerErrorKind::HandshakeError(HandshakeErrorKind::SecioError(
                         SecioError::ConnectSelf,
                     )) => {
                         debug!("dial observed address success: {:?}", address);
+                        self.network_state.reachability.dialed(
+                            &address,
+                            true,
+                            ckb_systemtime::unix_time_as_millis(),
+                        );
                         if let Some(ip) = multiaddr_to_socketaddr(&address) {
                             if is_reachable(ip.ip()) {
                                 public_addrs.insert(address);
@@ -584,7 +822,12 @@ impl ServiceHandle for EventHandler {
                     }
                 }
                 public_addrs.remove(&address);
-                self.network_state.dial_failed(&address);
+                self.network_state.reachability.dialed(
+                    &address,
+                    false,
+                    ckb_systemtime::unix_time_as_millis(),
+                );
+                self.network_state.dial_failed(&address, outcome);
             }
             ServiceError::ProtocolError {
                 id,
@@ -594,9 +837,10 @@ impl ServiceHandle for EventHandler {
                 debug!("ProtocolError({}, {}) {}", id, proto_id, error);
                 let message = format!("ProtocolError id={proto_id}");
                 // Ban because misbehave of remote peer
-                self.network_state.ban_session(
+                self.network_state.misbehave(
                     &context.control().clone().into(),
                     id,
+                    "ProtocolError",
                     Duration::from_secs(300),
                     message,
                 );
@@ -671,6 +915,19 @@ impl ServiceHandle for EventHandler {
                     session_context.id, session_context.address,
                 );
                 self.network_state.dial_success(&session_context.address);
+                if session_context.ty.is_outbound() {
+                    self.network_state
+                        .reachability
+                        .session_opened(&session_context.address);
+                }
+                if session_context.ty.is_inbound()
+                    && multiaddr_to_socketaddr(&session_context.address)
+                        .map_or(false, |addr| is_reachable(addr.ip()))
+                {
+                    self.network_state
+                        .reachability
+                        .public_inbound(ckb_systemtime::unix_time_as_millis());
+                }
 
                 let iter = self.inbound_eviction();
 
@@ -692,8 +949,17 @@ impl ServiceHandle for EventHandler {
                         "Feeler connected {} => {}",
                         session_context.id, session_context.address,
                     );
+                    self.network_state
+                        .dial_accepted(&session_context.address, DialOutcome::Connected);
                 } else {
-                    match self.network_state.accept_peer(&session_context) {
+                    let accept_peer_result = self.network_state.accept_peer(&session_context);
+                    let outcome = match &accept_peer_result {
+                        Ok(_) => DialOutcome::Connected,
+                        Err(err) => DialOutcome::Failed(format!("the peer is rejected: {err}")),
+                    };
+                    self.network_state
+                        .dial_accepted(&session_context.address, outcome);
+                    match accept_peer_result {
                         Ok(Some(evicted_peer)) => {
                             debug!(
                                 "Disconnect peer, {} => {}",
@@ -743,6 +1009,12 @@ impl ServiceHandle for EventHandler {
                     reg.remove_feeler(&session_context.address);
                     reg.remove_peer(session_context.id).is_some()
                 });
+                self.network_state
+                    .traffic
+                    .remove_session(session_context.id);
+                self.network_state
+                    .send_scheduler
+                    .remove_session(session_context.id);
                 if peer_exists {
                     debug!(
                         "{} closed. Remove {} from peer_registry",
@@ -902,7 +1174,8 @@ impl NetworkService {
         #[cfg(target_os = "linux")]
         let p2p_service = {
             if config.reuse_port_on_linux {
-                let iter = config.listen_addresses.iter();
+                let listen_addresses = network_state.listeners.addresses();
+                let iter = listen_addresses.iter();
 
                 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
                 enum TransportType {
@@ -1059,14 +1332,11 @@ impl NetworkService {
                 .into_iter()
                 .map(|paddr| paddr.addr)
                 .collect();
-            // Get bootnodes randomly
+            // Get bootnodes randomly by the weights of their sets
             let bootnodes = self
                 .network_state
                 .bootnodes
-                .iter()
-                .choose_multiple(&mut rand::thread_rng(), count.saturating_sub(addrs.len()))
-                .into_iter()
-                .cloned();
+                .choose(&mut rand::thread_rng(), count.saturating_sub(addrs.len()));
             addrs.extend(bootnodes);
             addrs
         });
@@ -1100,14 +1370,11 @@ impl NetworkService {
             let network_state = Arc::clone(&network_state);
             let p2p_control: ServiceAsyncControl = p2p_control.clone().into();
             handle.spawn_task(async move {
-                for addr in &config.listen_addresses {
+                for addr in &network_state.listeners.addresses() {
                     match p2p_service.listen(addr.to_owned()).await {
                         Ok(listen_address) => {
                             info!("Listen on address: {}", listen_address);
-                            network_state
-                                .listened_addrs
-                                .write()
-                                .push(listen_address.clone());
+                            network_state.listeners.set_listened(addr, listen_address);
                         }
                         Err(err) => {
                             warn!(
@@ -1210,6 +1477,15 @@ impl NetworkController {
         self.network_state.add_node(&self.p2p_control, address)
     }
 
+    /// Dial remote node now, the returned receiver gets the outcome once the session is opened or
+    /// the dial fails
+    ///
+    /// The address must contain the peer id. The peer already connected is reported as connected
+    /// without dialing.
+    pub fn dial_peer(&self, address: Multiaddr) -> oneshot::Receiver<DialOutcome> {
+        self.network_state.dial_peer(&self.p2p_control, address)
+    }
+
     /// Disconnect session with peer id
     pub fn remove_node(&self, peer_id: &PeerId) {
         if let Some(session_id) = self
@@ -1228,6 +1504,32 @@ impl NetworkController {
         }
     }
 
+    /// Reloads the allowlist of the private network from `allowed_peers_file` and disconnects
+    /// the peers removed from it.
+    ///
+    /// Returns the number of the allowed peers, or `None` if the network is not private.
+    pub fn reload_allowed_peers(&self) -> Result<Option<usize>, Error> {
+        let path = match self.network_state.config.allowed_peers_file.as_ref() {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let allowed_peers = load_allowed_peers(path)?;
+        let count = allowed_peers.len();
+        let disallowed = {
+            let mut peer_registry = self.network_state.peer_registry.write();
+            peer_registry.set_allowed_peers(Some(allowed_peers));
+            peer_registry.disallowed_sessions()
+        };
+        for session_id in disallowed {
+            if let Err(err) =
+                disconnect_with_message(&self.p2p_control, session_id, "not allowed any more")
+            {
+                debug!("Disconnect failed {:?}, error: {:?}", session_id, err);
+            }
+        }
+        Ok(Some(count))
+    }
+
     /// Get banned peer list
     pub fn get_banned_addrs(&self) -> Vec<BannedAddr> {
         self.network_state
@@ -1280,6 +1582,59 @@ impl NetworkController {
         })
     }
 
+    /// Return the message bytes sent to and received from the peer, by protocol
+    pub fn peer_traffic(&self, peer_index: PeerIndex) -> HashMap<ProtocolId, ProtocolTraffic> {
+        self.network_state.traffic.peer_traffic(peer_index)
+    }
+
+    /// Return the current outbound peer targets by role
+    pub fn outbound_targets(&self) -> OutboundTargets {
+        self.network_state.outbound_targets.targets()
+    }
+
+    /// Return the report of the connectivity self-test, which tells whether the node accepts
+    /// inbound connections
+    pub fn reachability(&self) -> Reachability {
+        self.network_state.report_reachability()
+    }
+
+    /// Return the listeners with their inbound peers
+    pub fn listeners(&self) -> Vec<ListenerInfo> {
+        let inbound_peers = self
+            .network_state
+            .with_peer_registry(|reg| reg.non_whitelist_inbound_by_listener_kind());
+        self.network_state.listeners.infos(&inbound_peers)
+    }
+
+    /// Return the health of the bootnodes
+    pub fn bootnodes_health(&self) -> Vec<BootnodeHealth> {
+        self.network_state.bootnodes.health()
+    }
+
+    /// Return the misbehavior scores of the peers, including the disconnected ones
+    pub fn peer_misbehaviors(&self) -> Vec<(PeerId, PeerMisbehavior)> {
+        self.network_state
+            .misbehaviors
+            .peers(ckb_systemtime::unix_time_as_millis())
+    }
+
+    /// Return the misbehavior score to ban a peer
+    pub fn misbehavior_ban_threshold(&self) -> u32 {
+        self.network_state.config.misbehavior_ban_threshold()
+    }
+
+    /// Report a misbehavior of the peer, which is banned once its score reaches the threshold
+    pub fn report_misbehavior(
+        &self,
+        peer_index: PeerIndex,
+        kind: &str,
+        duration: Duration,
+        reason: String,
+    ) {
+        self.network_state
+            .misbehave(&self.p2p_control, peer_index, kind, duration, reason);
+    }
+
     /// Ban an peer through peer index
     pub fn ban_peer(&self, peer_index: PeerIndex, duration: Duration, reason: String) {
         self.network_state
@@ -1311,10 +1666,12 @@ impl NetworkController {
         data: Bytes,
     ) -> Result<(), SendErrorKind> {
         let now = Instant::now();
+        let sessions = match target {
+            Some(session_id) => vec![session_id],
+            None => self.network_state.protocol_sessions(proto_id),
+        };
         loop {
-            let target = target
-                .map(TargetSession::Single)
-                .unwrap_or(TargetSession::All);
+            let target = TargetSession::Multi(Box::new(sessions.clone().into_iter()));
             let result = if quick {
                 self.p2p_control
                     .quick_filter_broadcast(target, proto_id, data.clone())
@@ -1322,8 +1679,16 @@ impl NetworkController {
                 self.p2p_control
                     .filter_broadcast(target, proto_id, data.clone())
             };
+            self.network_state
+                .send_scheduler
+                .observe(sessions.iter().copied(), &result);
             match result {
                 Ok(()) => {
+                    for session_id in sessions {
+                        self.network_state
+                            .traffic
+                            .record_sent(session_id, proto_id, data.len());
+                    }
                     return Ok(());
                 }
                 Err(SendErrorKind::WouldBlock) => {
@@ -0,0 +1,115 @@
+//! Outbound peer targets by role, adapted to the bandwidth headroom.
+use ckb_app_config::NetworkConfig;
+use ckb_logger::debug;
+use ckb_util::Mutex;
+use std::time::Instant;
+
+// The targets are lowered while the traffic exceeds 80% of the bandwidth limit
+const HIGH_WATERMARK_PERCENT: u64 = 80;
+// The targets are restored while the traffic is below 50% of the bandwidth limit
+const LOW_WATERMARK_PERCENT: u64 = 50;
+
+/// The current outbound peer targets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutboundTargets {
+    /// The target number of outbound peers which open the sync protocol.
+    pub sync: u32,
+    /// The target number of outbound peers which open the relay protocol.
+    pub relay: u32,
+    /// The measured p2p traffic in bytes per second.
+    ///
+    /// It is `None` if `network.outbound_targets.bandwidth_limit` is not set.
+    pub bandwidth_usage: Option<u64>,
+}
+
+/// Adapts the outbound targets to the measured traffic.
+///
+/// Relay peers are dropped from the targets first and restored last, because a node can still
+/// follow the chain with fewer relay peers, but falls behind without enough sync peers.
+pub(crate) struct OutboundTargetsAdapter {
+    max_sync: u32,
+    max_relay: u32,
+    min: u32,
+    // bytes per second
+    bandwidth_limit: Option<u64>,
+    state: Mutex<AdapterState>,
+}
+
+struct AdapterState {
+    targets: OutboundTargets,
+    // the time and the total traffic bytes of the last sample
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl OutboundTargetsAdapter {
+    pub(crate) fn new(config: &NetworkConfig) -> Self {
+        let max_sync = config.outbound_sync_target();
+        let max_relay = config.outbound_relay_target();
+        let bandwidth_limit = config
+            .outbound_targets
+            .bandwidth_limit
+            .map(|limit| limit.as_u64())
+            .filter(|limit| *limit > 0);
+        OutboundTargetsAdapter {
+            max_sync,
+            max_relay,
+            min: config.outbound_min_target(),
+            bandwidth_limit,
+            state: Mutex::new(AdapterState {
+                targets: OutboundTargets {
+                    sync: max_sync,
+                    relay: max_relay,
+                    bandwidth_usage: None,
+                },
+                last_sample: None,
+            }),
+        }
+    }
+
+    pub(crate) fn targets(&self) -> OutboundTargets {
+        self.state.lock().targets
+    }
+
+    /// Samples the total traffic bytes and adjusts the targets by one step, returns the new
+    /// targets.
+    pub(crate) fn adapt(&self, total_bytes: u64, now: Instant) -> OutboundTargets {
+        let limit = match self.bandwidth_limit {
+            Some(limit) => limit,
+            None => return self.targets(),
+        };
+        let mut state = self.state.lock();
+        if let Some((last_at, last_bytes)) = state.last_sample {
+            let elapsed_ms = now.saturating_duration_since(last_at).as_millis() as u64;
+            if elapsed_ms == 0 {
+                return state.targets;
+            }
+            let usage = total_bytes.saturating_sub(last_bytes).saturating_mul(1000) / elapsed_ms;
+            let targets = &mut state.targets;
+            let previous = *targets;
+            targets.bandwidth_usage = Some(usage);
+            if usage.saturating_mul(100) > limit.saturating_mul(HIGH_WATERMARK_PERCENT) {
+                let (min_sync, min_relay) =
+                    (self.min.min(self.max_sync), self.min.min(self.max_relay));
+                if targets.relay > min_relay {
+                    targets.relay -= 1;
+                } else if targets.sync > min_sync {
+                    targets.sync -= 1;
+                }
+            } else if usage.saturating_mul(100) < limit.saturating_mul(LOW_WATERMARK_PERCENT) {
+                if targets.sync < self.max_sync {
+                    targets.sync += 1;
+                } else if targets.relay < self.max_relay {
+                    targets.relay += 1;
+                }
+            }
+            if (targets.sync, targets.relay) != (previous.sync, previous.relay) {
+                debug!(
+                    "Outbound targets are adapted to sync {} relay {}, traffic {} bytes/s, limit {} bytes/s",
+                    targets.sync, targets.relay, usage, limit
+                );
+            }
+        }
+        state.last_sample = Some((now, total_bytes));
+        state.targets
+    }
+}
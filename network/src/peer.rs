@@ -1,3 +1,4 @@
+use crate::features::PeerFeatures;
 use crate::network_group::Group;
 use crate::{
     multiaddr::Multiaddr, protocols::identify::Flags, ProtocolId, ProtocolVersion, SessionType,
@@ -42,6 +43,8 @@ pub struct Peer {
     pub is_whitelist: bool,
     /// Whether the remote peer is a light client, and it subscribes the chain state.
     pub if_lightclient_subscribed: bool,
+    /// The features enabled by the local config, the others are never negotiated
+    pub local_features: PeerFeatures,
 }
 
 impl Peer {
@@ -65,6 +68,7 @@ impl Peer {
             protocols: HashMap::with_capacity_and_hasher(1, Default::default()),
             is_whitelist,
             if_lightclient_subscribed: false,
+            local_features: PeerFeatures::all(),
         }
     }
 
@@ -87,4 +91,14 @@ impl Peer {
     pub fn protocol_version(&self, protocol_id: ProtocolId) -> Option<ProtocolVersion> {
         self.protocols.get(&protocol_id).cloned()
     }
+
+    /// P2P features negotiated on this session
+    pub fn features(&self) -> PeerFeatures {
+        PeerFeatures::negotiated(self)
+    }
+
+    /// Whether all the features are negotiated on this session
+    pub fn supports(&self, features: PeerFeatures) -> bool {
+        self.features().contains(features)
+    }
 }
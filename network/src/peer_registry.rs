@@ -1,12 +1,13 @@
 //! Peer registry
+use crate::listeners::{ListenerKind, Listeners};
 use crate::network_group::Group;
 use crate::peer_store::PeerStore;
 use crate::{
     errors::{Error, PeerError},
-    extract_peer_id, Peer, PeerId, SessionType,
+    extract_peer_id, Peer, PeerFeatures, PeerId, SessionType, SupportProtocols,
 };
 use ckb_logger::debug;
-use p2p::{multiaddr::Multiaddr, SessionId};
+use p2p::{multiaddr::Multiaddr, ProtocolId, SessionId};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use std::collections::{HashMap, HashSet};
@@ -24,6 +25,14 @@ pub struct PeerRegistry {
     whitelist_only: bool,
     whitelist_peers: HashSet<PeerId>,
     feeler_peers: HashSet<PeerId>,
+    // the inbound caps of the listeners
+    listener_inbound_caps: HashMap<ListenerKind, u32>,
+    // the sum of the inbound caps if all the listeners are capped
+    listener_total_inbound_cap: Option<u32>,
+    // the only peers allowed in a private network
+    allowed_peers: Option<HashSet<PeerId>>,
+    // the features enabled by the local config
+    local_features: PeerFeatures,
 }
 
 /// Global network connection status
@@ -63,12 +72,50 @@ impl PeerRegistry {
             peers: HashMap::with_capacity_and_hasher(20, Default::default()),
             whitelist_peers: whitelist_peers.iter().filter_map(extract_peer_id).collect(),
             feeler_peers: HashSet::default(),
+            listener_inbound_caps: HashMap::default(),
+            listener_total_inbound_cap: None,
+            allowed_peers: None,
+            local_features: PeerFeatures::all(),
             max_inbound,
             max_outbound,
             whitelist_only,
         }
     }
 
+    pub(crate) fn set_listener_inbound_caps(&mut self, listeners: &Listeners) {
+        self.listener_inbound_caps = listeners.inbound_caps();
+        self.listener_total_inbound_cap = listeners.total_inbound_cap();
+    }
+
+    /// Sets the features enabled by the local config, the peers never negotiate the others.
+    pub(crate) fn set_local_features(&mut self, local_features: PeerFeatures) {
+        self.local_features = local_features;
+    }
+
+    /// Sets the allowlist of the private network, `None` allows all the peers.
+    pub(crate) fn set_allowed_peers(&mut self, allowed_peers: Option<HashSet<PeerId>>) {
+        self.allowed_peers = allowed_peers;
+    }
+
+    /// Whether the peer is allowed by the allowlist of the private network.
+    pub(crate) fn is_allowed(&self, peer_id: &PeerId) -> bool {
+        self.allowed_peers
+            .as_ref()
+            .map_or(true, |allowed_peers| allowed_peers.contains(peer_id))
+    }
+
+    /// The sessions of the connected peers not allowed by the allowlist.
+    pub(crate) fn disallowed_sessions(&self) -> Vec<SessionId> {
+        self.peers
+            .iter()
+            .filter(|(_, peer)| {
+                extract_peer_id(&peer.connected_addr)
+                    .map_or(false, |peer_id| !self.is_allowed(&peer_id))
+            })
+            .map(|(session_id, _)| *session_id)
+            .collect()
+    }
+
     pub(crate) fn accept_peer(
         &mut self,
         remote_addr: Multiaddr,
@@ -83,6 +130,9 @@ impl PeerRegistry {
         if self.get_key_by_peer_id(&peer_id).is_some() {
             return Err(PeerError::PeerIdExists(peer_id).into());
         }
+        if !self.is_allowed(&peer_id) {
+            return Err(PeerError::NotAllowed.into());
+        }
 
         let is_whitelist = self.whitelist_peers.contains(&peer_id);
         let mut evicted_peer: Option<Peer> = None;
@@ -98,6 +148,20 @@ impl PeerRegistry {
             let connection_status = self.connection_status();
             // check peers connection limitation
             if session_type.is_inbound() {
+                // no eviction for the listener caps, an evicted peer may be of another listener
+                if let Some(cap) = self.listener_total_inbound_cap {
+                    if connection_status.non_whitelist_inbound >= cap {
+                        return Err(PeerError::ReachListenerInboundLimit.into());
+                    }
+                }
+                if let Some(kind) = ListenerKind::of(&remote_addr) {
+                    if let Some(cap) = self.listener_inbound_caps.get(&kind) {
+                        let inbound = self.non_whitelist_inbound_by_listener_kind();
+                        if inbound.get(&kind).unwrap_or(&0) >= cap {
+                            return Err(PeerError::ReachListenerInboundLimit.into());
+                        }
+                    }
+                }
                 if connection_status.non_whitelist_inbound >= self.max_inbound {
                     if let Some(evicted_session) = self.try_evict_inbound_peer(peer_store) {
                         evicted_peer = self.remove_peer(evicted_session);
@@ -110,7 +174,8 @@ impl PeerRegistry {
             }
         }
         peer_store.add_connected_peer(remote_addr.clone(), session_type);
-        let peer = Peer::new(session_id, session_type, remote_addr, is_whitelist);
+        let mut peer = Peer::new(session_id, session_type, remote_addr, is_whitelist);
+        peer.local_features = self.local_features;
         self.peers.insert(session_id, peer);
         Ok(evicted_peer)
     }
@@ -245,6 +310,90 @@ impl PeerRegistry {
         self.peers.keys().cloned().collect()
     }
 
+    /// Returns the numbers of the non-whitelist outbound peers which open the sync protocol and
+    /// the relay protocol.
+    pub(crate) fn outbound_role_counts(&self) -> (u32, u32) {
+        let sync = SupportProtocols::Sync.protocol_id();
+        let relays = [
+            SupportProtocols::RelayV2.protocol_id(),
+            SupportProtocols::RelayV3.protocol_id(),
+        ];
+        self.peers
+            .values()
+            .filter(|peer| !peer.is_whitelist && peer.is_outbound())
+            .fold((0, 0), |(sync_peers, relay_peers), peer| {
+                (
+                    sync_peers + u32::from(peer.protocols.contains_key(&sync)),
+                    relay_peers
+                        + u32::from(relays.iter().any(|id| peer.protocols.contains_key(id))),
+                )
+            })
+    }
+
+    /// Returns the non-whitelist outbound peers beyond the role targets, the newest are trimmed
+    /// first.
+    ///
+    /// The first list is the relay protocols to close on the peers beyond the relay target. The
+    /// second list is the peers to disconnect beyond the sync target, which only contains the peers
+    /// not opening the relay protocol, so trimming the sync peers never lowers the relay peers.
+    pub(crate) fn outbound_peers_over_targets(
+        &self,
+        sync_target: u32,
+        relay_target: u32,
+    ) -> (Vec<(SessionId, ProtocolId)>, Vec<SessionId>) {
+        let sync = SupportProtocols::Sync.protocol_id();
+        let relays = [
+            SupportProtocols::RelayV2.protocol_id(),
+            SupportProtocols::RelayV3.protocol_id(),
+        ];
+        let opens_relay = |peer: &Peer| relays.iter().any(|id| peer.protocols.contains_key(id));
+        let mut peers: Vec<&Peer> = self
+            .peers
+            .values()
+            .filter(|peer| !peer.is_whitelist && peer.is_outbound())
+            .collect();
+        peers.sort_by_key(|peer| std::cmp::Reverse(peer.connected_time));
+
+        let relay_peers = peers.iter().filter(|peer| opens_relay(peer)).count();
+        let close_relays = peers
+            .iter()
+            .filter(|peer| opens_relay(peer))
+            .take(relay_peers.saturating_sub(relay_target as usize))
+            .flat_map(|peer| {
+                relays
+                    .iter()
+                    .filter(|id| peer.protocols.contains_key(id))
+                    .map(|id| (peer.session_id, *id))
+            })
+            .collect();
+        let sync_peers = peers
+            .iter()
+            .filter(|peer| peer.protocols.contains_key(&sync))
+            .count();
+        let disconnects = peers
+            .iter()
+            .filter(|peer| peer.protocols.contains_key(&sync) && !opens_relay(peer))
+            .take(sync_peers.saturating_sub(sync_target as usize))
+            .map(|peer| peer.session_id)
+            .collect();
+        (close_relays, disconnects)
+    }
+
+    /// The count of the inbound peers by the listener kind of their addresses.
+    pub(crate) fn non_whitelist_inbound_by_listener_kind(&self) -> HashMap<ListenerKind, u32> {
+        let mut counts = HashMap::new();
+        for peer in self
+            .peers
+            .values()
+            .filter(|peer| peer.is_inbound() && !peer.is_whitelist)
+        {
+            if let Some(kind) = ListenerKind::of(&peer.connected_addr) {
+                *counts.entry(kind).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
     pub(crate) fn connection_status(&self) -> ConnectionStatus {
         let total = self.peers.len() as u32;
         let mut non_whitelist_inbound: u32 = 0;
@@ -24,6 +24,9 @@ impl ServiceProtocol for DisconnectMessageProtocol {
 
     async fn received(&mut self, context: ProtocolContextMutRef<'_>, data: Bytes) {
         let session_id = context.session.id;
+        self.0
+            .traffic
+            .record_received(session_id, context.proto_id, data.len());
         if let Ok(message) = String::from_utf8(data.to_vec()) {
             info!(
                 "Received disconnect message from peer={}: {}",
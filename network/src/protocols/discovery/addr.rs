@@ -47,6 +47,10 @@ pub trait AddressManager {
     fn get_random(&mut self, n: usize, target: Flags) -> Vec<(Multiaddr, Flags)>;
     fn required_flags(&self) -> Flags;
     fn node_flags(&self, id: SessionId) -> Option<Flags>;
+    /// Records the bytes of a message sent to the session, for bandwidth accounting
+    fn record_sent(&self, _id: SessionId, _pid: ProtocolId, _len: usize) {}
+    /// Records the bytes of a message received from the session, for bandwidth accounting
+    fn record_received(&self, _id: SessionId, _pid: ProtocolId, _len: usize) {}
 }
 
 // bitcoin: bloom.h, bloom.cpp => CRollingBloomFilter
@@ -94,6 +94,8 @@ impl<M: AddressManager + Send + Sync> ServiceProtocol for DiscoveryProtocol<M> {
     async fn received(&mut self, context: ProtocolContextMutRef<'_>, data: bytes::Bytes) {
         let session = context.session;
         trace!("[received message]: length={}", data.len());
+        self.addr_mgr
+            .record_received(session.id, context.proto_id, data.len());
 
         let mgr = &mut self.addr_mgr;
         let mut check =
@@ -164,8 +166,11 @@ impl<M: AddressManager + Send + Sync> ServiceProtocol for DiscoveryProtocol<M> {
                             };
 
                             let msg = encode(DiscoveryMessage::Nodes(nodes));
+                            let len = msg.len();
                             if context.send_message(msg).await.is_err() {
                                 debug!("{:?} send discovery msg Nodes fail", session.id)
+                            } else {
+                                self.addr_mgr.record_sent(session.id, context.proto_id, len);
                             }
                         }
                     }
@@ -228,7 +233,7 @@ impl<M: AddressManager + Send + Sync> ServiceProtocol for DiscoveryProtocol<M> {
         // get announce list
         let mut announce_list = Vec::new();
         for (id, state) in self.sessions.iter_mut() {
-            state.send_messages(context, *id).await;
+            state.send_messages(context, *id, &self.addr_mgr).await;
 
             if let Some(addr) = state
                 .check_timer(now, ANNOUNCE_INTERVAL)
@@ -391,6 +396,14 @@ impl AddressManager for DiscoveryAddressManager {
         self.network_state.required_flags
     }
 
+    fn record_sent(&self, id: SessionId, pid: ProtocolId, len: usize) {
+        self.network_state.traffic.record_sent(id, pid, len);
+    }
+
+    fn record_received(&self, id: SessionId, pid: ProtocolId, len: usize) {
+        self.network_state.traffic.record_received(id, pid, len);
+    }
+
     fn node_flags(&self, id: SessionId) -> Option<Flags> {
         self.network_state.with_peer_registry(|reg| {
             reg.get_peer(id)
@@ -67,11 +67,14 @@ impl SessionState {
                 required_flags: addr_manager.required_flags(),
             });
 
+            let len = msg.len();
             if context.send_message(msg).await.is_err() {
                 debug!(
                     "{:?} sending discovery msg GetNode failed",
                     context.session.id
                 )
+            } else {
+                addr_manager.record_sent(context.session.id, context.proto_id, len);
             }
 
             addr_known.insert(&context.session.address);
@@ -108,7 +111,12 @@ impl SessionState {
         }
     }
 
-    pub(crate) async fn send_messages(&mut self, cx: &mut ProtocolContext, id: SessionId) {
+    pub(crate) async fn send_messages<M: AddressManager + Sync>(
+        &mut self,
+        cx: &mut ProtocolContext,
+        id: SessionId,
+        addr_manager: &M,
+    ) {
         if !self.announce_multiaddrs.is_empty() {
             let items = self
                 .announce_multiaddrs
@@ -122,8 +130,11 @@ impl SessionState {
                 announce: true,
                 items,
             }));
+            let len = msg.len();
             if cx.send_message_to(id, cx.proto_id, msg).await.is_err() {
                 debug!("{:?} sending discovery msg Nodes failed", id)
+            } else {
+                addr_manager.record_sent(id, cx.proto_id, len);
             }
         }
     }
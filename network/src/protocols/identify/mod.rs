@@ -12,7 +12,7 @@ use p2p::{
     service::{SessionType, TargetProtocol},
     traits::ServiceProtocol,
     utils::{extract_peer_id, is_reachable, multiaddr_to_socketaddr},
-    SessionId,
+    ProtocolId, SessionId,
 };
 
 mod protocol;
@@ -80,6 +80,10 @@ pub trait Callback: Clone + Send {
     fn add_observed_addr(&mut self, addr: Multiaddr, ty: SessionType) -> MisbehaveResult;
     /// Report misbehavior
     fn misbehave(&mut self, session: &SessionContext, kind: Misbehavior) -> MisbehaveResult;
+    /// Record the bytes of a message sent to the session
+    fn record_sent(&self, _id: SessionId, _pid: ProtocolId, _len: usize) {}
+    /// Record the bytes of a message received from the session
+    fn record_received(&self, _id: SessionId, _pid: ProtocolId, _len: usize) {}
 }
 
 /// Identify protocol
@@ -236,10 +240,11 @@ impl<T: Callback> ServiceProtocol for IdentifyProtocol<T> {
 
         let identify = self.callback.identify();
         let data = IdentifyMessage::new(listen_addrs, session.address.clone(), identify).encode();
-        let _ = context
-            .quick_send_message(data)
-            .await
-            .map_err(|err| error!("IdentifyProtocol quick_send_message, error: {:?}", err));
+        let len = data.len();
+        match context.quick_send_message(data).await {
+            Ok(()) => self.callback.record_sent(session.id, context.proto_id, len),
+            Err(err) => error!("IdentifyProtocol quick_send_message, error: {:?}", err),
+        }
     }
 
     async fn disconnected(&mut self, context: ProtocolContextMutRef<'_>) {
@@ -255,6 +260,8 @@ impl<T: Callback> ServiceProtocol for IdentifyProtocol<T> {
 
     async fn received(&mut self, mut context: ProtocolContextMutRef<'_>, data: Bytes) {
         let session = context.session;
+        self.callback
+            .record_received(session.id, context.proto_id, data.len());
         match IdentifyMessage::decode(&data) {
             Some(message) => {
                 trace!(
@@ -576,6 +583,14 @@ impl Callback for IdentifyCallback {
         );
         MisbehaveResult::Disconnect
     }
+
+    fn record_sent(&self, id: SessionId, pid: ProtocolId, len: usize) {
+        self.network_state.traffic.record_sent(id, pid, len);
+    }
+
+    fn record_received(&self, id: SessionId, pid: ProtocolId, len: usize) {
+        self.network_state.traffic.record_received(id, pid, len);
+    }
 }
 
 #[derive(Clone)]
@@ -639,5 +654,7 @@ bitflags::bitflags! {
         const LIGHT_CLIENT = 0b10000;
         /// Client-side block filter protocol can provide BlockFilter download service
         const BLOCK_FILTER = 0b100000;
+        /// Asks the peers to push the full small transactions instead of announcing the hashes
+        const TX_PUSH = 0b1000000;
     }
 }
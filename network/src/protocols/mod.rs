@@ -33,9 +33,10 @@ pub type PeerIndex = SessionId;
 pub type BoxedFutureTask = Pin<Box<dyn Future<Output = ()> + 'static + Send>>;
 
 use crate::{
-    compress::{compress, decompress},
+    compress::{compress, decompress, uncompressed},
     network::{async_disconnect_with_message, disconnect_with_message},
-    Behaviour, Error, NetworkState, Peer, ProtocolVersion, SupportProtocols,
+    send_priority::single_session,
+    Behaviour, Error, NetworkState, Peer, PeerFeatures, ProtocolVersion, SupportProtocols,
 };
 
 /// Abstract protocol context
@@ -109,9 +110,19 @@ pub trait CKBProtocolContext: Send {
     fn filter_broadcast(&self, target: TargetSession, data: Bytes) -> Result<(), Error>;
     /// Disconnect session
     fn disconnect(&self, peer_index: PeerIndex, message: &str) -> Result<(), Error>;
+    /// Whether the sends to the peer have found the send buffer full recently, the transaction
+    /// relay messages to the peer are deferred meanwhile
+    fn send_congested(&self, _peer_index: PeerIndex) -> bool {
+        false
+    }
     // Interact with NetworkState
     /// Get peer info
     fn get_peer(&self, peer_index: PeerIndex) -> Option<Peer>;
+    /// Whether all the features are negotiated with the peer
+    fn peer_supports(&self, peer_index: PeerIndex, features: PeerFeatures) -> bool {
+        self.get_peer(peer_index)
+            .map_or(false, |peer| peer.supports(features))
+    }
     /// Modify peer info
     fn with_peer_mut(&self, peer_index: PeerIndex, f: Box<dyn FnOnce(&mut Peer)>);
     /// Get all session id
@@ -120,6 +131,17 @@ pub trait CKBProtocolContext: Send {
     fn report_peer(&self, peer_index: PeerIndex, behaviour: Behaviour);
     /// Ban peer
     fn ban_peer(&self, peer_index: PeerIndex, duration: Duration, reason: String);
+    /// Report peer misbehavior, the peer is banned for `duration` once its score reaches the
+    /// threshold. The score of the kind is configured by `network.misbehavior.weights`.
+    fn report_misbehavior(
+        &self,
+        peer_index: PeerIndex,
+        _kind: &str,
+        duration: Duration,
+        reason: String,
+    ) {
+        self.ban_peer(peer_index, duration, reason);
+    }
     /// current protocol id
     fn protocol_id(&self) -> ProtocolId;
     /// Raw tentacle controller
@@ -236,6 +258,11 @@ impl CKBProtocol {
     pub fn build(self) -> ProtocolMeta {
         let protocol_name = self.protocol_name();
         let max_frame_length = self.max_frame_length;
+        let before_send: fn(Bytes) -> Bytes = if self.network_state.config.compression {
+            compress
+        } else {
+            uncompressed
+        };
         let supported_versions = self
             .supported_versions
             .iter()
@@ -259,7 +286,7 @@ impl CKBProtocol {
                     handler: self.handler,
                 }))
             })
-            .before_send(compress)
+            .before_send(before_send)
             .before_receive(|| Some(Box::new(decompress)))
             .build()
     }
@@ -363,6 +390,9 @@ impl ServiceProtocol for CKBHandler {
             async_p2p_control: context.control().to_owned(),
         };
         let peer_index = context.session.id;
+        self.network_state
+            .traffic
+            .record_received(peer_index, self.proto_id, data.len());
         self.handler.received(Arc::new(nc), peer_index, data).await;
     }
 
@@ -428,9 +458,13 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let len = data.len();
         self.async_p2p_control
             .quick_send_message_to(peer_index, proto_id, data)
             .await?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, proto_id, len);
         Ok(())
     }
     async fn async_quick_send_message_to(
@@ -444,9 +478,13 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let len = data.len();
         self.async_p2p_control
             .quick_send_message_to(peer_index, self.proto_id, data)
             .await?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, self.proto_id, len);
         Ok(())
     }
     async fn async_quick_filter_broadcast(
@@ -454,9 +492,14 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
         target: TargetSession,
         data: Bytes,
     ) -> Result<(), Error> {
+        let len = data.len();
+        let sessions = self.network_state.resolve_broadcast(target, self.proto_id);
+        let target = TargetSession::Multi(Box::new(sessions.clone().into_iter()));
         self.async_p2p_control
             .quick_filter_broadcast(target, self.proto_id, data)
             .await?;
+        self.network_state
+            .record_broadcast(&sessions, self.proto_id, len);
         Ok(())
     }
     async fn async_future_task(&self, task: BoxedFutureTask, blocking: bool) -> Result<(), Error> {
@@ -480,9 +523,22 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
-        self.async_p2p_control
-            .send_message_to(peer_index, proto_id, data)
-            .await?;
+        let len = data.len();
+        let scheduler = &self.network_state.send_scheduler;
+        let result = if scheduler.quick(peer_index, proto_id, &data)? {
+            self.async_p2p_control
+                .quick_send_message_to(peer_index, proto_id, data)
+                .await
+        } else {
+            self.async_p2p_control
+                .send_message_to(peer_index, proto_id, data)
+                .await
+        };
+        scheduler.observe(Some(peer_index), &result);
+        result?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, proto_id, len);
         Ok(())
     }
     async fn async_send_message_to(&self, peer_index: PeerIndex, data: Bytes) -> Result<(), Error> {
@@ -492,9 +548,22 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
-        self.async_p2p_control
-            .send_message_to(peer_index, self.proto_id, data)
-            .await?;
+        let len = data.len();
+        let scheduler = &self.network_state.send_scheduler;
+        let result = if scheduler.quick(peer_index, self.proto_id, &data)? {
+            self.async_p2p_control
+                .quick_send_message_to(peer_index, self.proto_id, data)
+                .await
+        } else {
+            self.async_p2p_control
+                .send_message_to(peer_index, self.proto_id, data)
+                .await
+        };
+        scheduler.observe(Some(peer_index), &result);
+        result?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, self.proto_id, len);
         Ok(())
     }
     async fn async_filter_broadcast(
@@ -502,9 +571,25 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
         target: TargetSession,
         data: Bytes,
     ) -> Result<(), Error> {
-        self.async_p2p_control
-            .filter_broadcast(target, self.proto_id, data)
-            .await?;
+        let scheduler = &self.network_state.send_scheduler;
+        let single = single_session(&target);
+        let (quick, target) = scheduler.broadcast(target, self.proto_id, &data)?;
+        let len = data.len();
+        let sessions = self.network_state.resolve_broadcast(target, self.proto_id);
+        let target = TargetSession::Multi(Box::new(sessions.clone().into_iter()));
+        let result = if quick {
+            self.async_p2p_control
+                .quick_filter_broadcast(target, self.proto_id, data)
+                .await
+        } else {
+            self.async_p2p_control
+                .filter_broadcast(target, self.proto_id, data)
+                .await
+        };
+        scheduler.observe(single, &result);
+        result?;
+        self.network_state
+            .record_broadcast(&sessions, self.proto_id, len);
         Ok(())
     }
     async fn async_disconnect(&self, peer_index: PeerIndex, message: &str) -> Result<(), Error> {
@@ -524,8 +609,12 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let len = data.len();
         self.p2p_control
             .quick_send_message_to(peer_index, proto_id, data)?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, proto_id, len);
         Ok(())
     }
     fn quick_send_message_to(&self, peer_index: PeerIndex, data: Bytes) -> Result<(), Error> {
@@ -535,13 +624,22 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
+        let len = data.len();
         self.p2p_control
             .quick_send_message_to(peer_index, self.proto_id, data)?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, self.proto_id, len);
         Ok(())
     }
     fn quick_filter_broadcast(&self, target: TargetSession, data: Bytes) -> Result<(), Error> {
+        let len = data.len();
+        let sessions = self.network_state.resolve_broadcast(target, self.proto_id);
+        let target = TargetSession::Multi(Box::new(sessions.clone().into_iter()));
         self.p2p_control
             .quick_filter_broadcast(target, self.proto_id, data)?;
+        self.network_state
+            .record_broadcast(&sessions, self.proto_id, len);
         Ok(())
     }
     fn future_task(&self, task: BoxedFutureTask, blocking: bool) -> Result<(), Error> {
@@ -565,8 +663,19 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
-        self.p2p_control
-            .send_message_to(peer_index, proto_id, data)?;
+        let len = data.len();
+        let scheduler = &self.network_state.send_scheduler;
+        let result = if scheduler.quick(peer_index, proto_id, &data)? {
+            self.p2p_control
+                .quick_send_message_to(peer_index, proto_id, data)
+        } else {
+            self.p2p_control.send_message_to(peer_index, proto_id, data)
+        };
+        scheduler.observe(Some(peer_index), &result);
+        result?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, proto_id, len);
         Ok(())
     }
     fn send_message_to(&self, peer_index: PeerIndex, data: Bytes) -> Result<(), Error> {
@@ -576,13 +685,40 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
             peer_index,
             data.len()
         );
-        self.p2p_control
-            .send_message_to(peer_index, self.proto_id, data)?;
+        let len = data.len();
+        let scheduler = &self.network_state.send_scheduler;
+        let result = if scheduler.quick(peer_index, self.proto_id, &data)? {
+            self.p2p_control
+                .quick_send_message_to(peer_index, self.proto_id, data)
+        } else {
+            self.p2p_control
+                .send_message_to(peer_index, self.proto_id, data)
+        };
+        scheduler.observe(Some(peer_index), &result);
+        result?;
+        self.network_state
+            .traffic
+            .record_sent(peer_index, self.proto_id, len);
         Ok(())
     }
     fn filter_broadcast(&self, target: TargetSession, data: Bytes) -> Result<(), Error> {
-        self.p2p_control
-            .filter_broadcast(target, self.proto_id, data)?;
+        let scheduler = &self.network_state.send_scheduler;
+        let single = single_session(&target);
+        let (quick, target) = scheduler.broadcast(target, self.proto_id, &data)?;
+        let len = data.len();
+        let sessions = self.network_state.resolve_broadcast(target, self.proto_id);
+        let target = TargetSession::Multi(Box::new(sessions.clone().into_iter()));
+        let result = if quick {
+            self.p2p_control
+                .quick_filter_broadcast(target, self.proto_id, data)
+        } else {
+            self.p2p_control
+                .filter_broadcast(target, self.proto_id, data)
+        };
+        scheduler.observe(single, &result);
+        result?;
+        self.network_state
+            .record_broadcast(&sessions, self.proto_id, len);
         Ok(())
     }
     fn disconnect(&self, peer_index: PeerIndex, message: &str) -> Result<(), Error> {
@@ -590,6 +726,9 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
         disconnect_with_message(&self.p2p_control, peer_index, message)?;
         Ok(())
     }
+    fn send_congested(&self, peer_index: PeerIndex) -> bool {
+        self.network_state.send_scheduler.is_congested(peer_index)
+    }
 
     fn get_peer(&self, peer_index: PeerIndex) -> Option<Peer> {
         self.network_state
@@ -624,6 +763,16 @@ impl CKBProtocolContext for DefaultCKBProtocolContext {
         self.network_state
             .ban_session(&self.p2p_control, peer_index, duration, reason);
     }
+    fn report_misbehavior(
+        &self,
+        peer_index: PeerIndex,
+        kind: &str,
+        duration: Duration,
+        reason: String,
+    ) {
+        self.network_state
+            .misbehave(&self.p2p_control, peer_index, kind, duration, reason);
+    }
 
     fn protocol_id(&self) -> ProtocolId {
         self.proto_id
@@ -15,7 +15,7 @@ use p2p::{
     SessionId,
 };
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     str,
     sync::Arc,
     time::{Duration, Instant},
@@ -81,7 +81,7 @@ impl PingHandler {
     async fn ping_peers(&mut self, context: &ProtocolContext) {
         let now = Instant::now();
         let send_nonce = nonce(&now, self.start_time);
-        let peers: HashSet<SessionId> = self
+        let peers: Vec<SessionId> = self
             .connected_session_ids
             .iter_mut()
             .filter_map(|(session_id, ps)| {
@@ -99,9 +99,10 @@ impl PingHandler {
             debug!("start ping peers: {:?}", peers);
             let ping_msg = PingMessage::build_ping(send_nonce);
             let proto_id = context.proto_id;
+            let len = ping_msg.len();
             if context
                 .filter_broadcast(
-                    TargetSession::Multi(Box::new(peers.into_iter())),
+                    TargetSession::Multi(Box::new(peers.clone().into_iter())),
                     proto_id,
                     ping_msg,
                 )
@@ -109,6 +110,8 @@ impl PingHandler {
                 .is_err()
             {
                 debug!("Failed to send message");
+            } else {
+                self.network_state.record_broadcast(&peers, proto_id, len);
             }
         }
     }
@@ -200,6 +203,9 @@ impl ServiceProtocol for PingHandler {
 
     async fn received(&mut self, context: ProtocolContextMutRef<'_>, data: Bytes) {
         let session = context.session;
+        self.network_state
+            .traffic
+            .record_received(session.id, context.proto_id, data.len());
         match PingMessage::decode(data.as_ref()) {
             None => {
                 error!("Message decode error");
@@ -214,12 +220,16 @@ impl ServiceProtocol for PingHandler {
                 match msg {
                     PingPayload::Ping(nonce) => {
                         self.ping_received(session.id);
-                        if context
-                            .send_message(PingMessage::build_pong(nonce))
-                            .await
-                            .is_err()
-                        {
+                        let pong_msg = PingMessage::build_pong(nonce);
+                        let len = pong_msg.len();
+                        if context.send_message(pong_msg).await.is_err() {
                             debug!("Failed to send message");
+                        } else {
+                            self.network_state.traffic.record_sent(
+                                session.id,
+                                context.proto_id,
+                                len,
+                            );
                         }
                     }
                     PingPayload::Pong(nonce) => {
@@ -0,0 +1,171 @@
+//! The connectivity self-test, which tells whether the node accepts inbound connections.
+//!
+//! The public addresses of the node come from the config and the addresses observed by the
+//! peers, see the identify protocol. They are all dialed once per check interval, an address is
+//! reachable if the dial comes back to the node itself. An inbound session from a public IP
+//! proves the node reachable as well, since the dials to its own addresses fail behind the NATs
+//! which don't support hairpinning.
+use ckb_app_config::NetworkConfig;
+use ckb_logger::info;
+use ckb_util::Mutex;
+use p2p::multiaddr::Multiaddr;
+use std::collections::HashMap;
+
+// The result of a dial or an inbound session expires after this many check intervals
+const RESULT_EXPIRY_INTERVALS: u64 = 3;
+// The addresses being dialed at most, the observed addresses come from the peers
+const MAX_DIALING_ADDRS: usize = 256;
+
+/// Whether the node accepts inbound connections.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReachabilityStatus {
+    /// The node has no public address to test, or none of the tests is finished.
+    #[default]
+    Unknown,
+    /// A public address is dialed back to the node, or a peer connects from a public IP.
+    Reachable,
+    /// All the tested public addresses fail, and no peer connects from a public IP.
+    Unreachable,
+}
+
+impl ReachabilityStatus {
+    /// The name of the status, used as the metrics label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReachabilityStatus::Unknown => "unknown",
+            ReachabilityStatus::Reachable => "reachable",
+            ReachabilityStatus::Unreachable => "unreachable",
+        }
+    }
+}
+
+/// The report of the connectivity self-test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reachability {
+    /// The status concluded from the unexpired results.
+    pub status: ReachabilityStatus,
+    /// The public addresses dialed back to the node.
+    pub reachable_addresses: Vec<Multiaddr>,
+    /// The public addresses failed to be dialed.
+    pub unreachable_addresses: Vec<Multiaddr>,
+    /// The unix timestamp in milliseconds of the last self-test.
+    pub last_checked: Option<u64>,
+    /// The unix timestamp in milliseconds of the last inbound session from a public IP.
+    pub last_public_inbound: Option<u64>,
+}
+
+/// Tracks the dials to the public addresses of the node and the inbound sessions.
+pub(crate) struct ReachabilityProbe {
+    check_interval_ms: u64,
+    state: Mutex<ProbeState>,
+}
+
+#[derive(Default)]
+struct ProbeState {
+    // the addresses being dialed, and the time of the dial
+    dialing: HashMap<Multiaddr, u64>,
+    // whether the address is reachable, and the time of the result
+    results: HashMap<Multiaddr, (bool, u64)>,
+    last_checked: Option<u64>,
+    last_public_inbound: Option<u64>,
+    last_status: ReachabilityStatus,
+}
+
+impl ReachabilityProbe {
+    pub(crate) fn new(config: &NetworkConfig) -> Self {
+        ReachabilityProbe {
+            check_interval_ms: config.reachability_check_interval().as_millis() as u64,
+            state: Mutex::new(ProbeState::default()),
+        }
+    }
+
+    /// Whether the public addresses are due for another self-test.
+    pub(crate) fn is_due(&self, now_ms: u64) -> bool {
+        self.state.lock().last_checked.map_or(true, |last| {
+            now_ms.saturating_sub(last) >= self.check_interval_ms
+        })
+    }
+
+    /// Starts a self-test, which dials all the public addresses.
+    pub(crate) fn start(&self, now_ms: u64) {
+        self.state.lock().last_checked = Some(now_ms);
+    }
+
+    /// Records a dial to a public address of the node.
+    ///
+    /// The dials without an outcome for a check interval are dropped, and the address is not
+    /// tracked if there are still `MAX_DIALING_ADDRS` addresses being dialed.
+    pub(crate) fn dialing(&self, addr: Multiaddr, now_ms: u64) {
+        let mut state = self.state.lock();
+        if state.dialing.len() >= MAX_DIALING_ADDRS {
+            let check_interval_ms = self.check_interval_ms;
+            state
+                .dialing
+                .retain(|_, at| now_ms.saturating_sub(*at) < check_interval_ms);
+        }
+        if state.dialing.len() < MAX_DIALING_ADDRS || state.dialing.contains_key(&addr) {
+            state.dialing.insert(addr, now_ms);
+        }
+    }
+
+    /// Records the outcome of a dial, the dials not to the public addresses are ignored.
+    pub(crate) fn dialed(&self, addr: &Multiaddr, reachable: bool, now_ms: u64) {
+        let mut state = self.state.lock();
+        if state.dialing.remove(addr).is_some() {
+            state.results.insert(addr.clone(), (reachable, now_ms));
+        }
+    }
+
+    /// Records a session opened to an address, the dial to it reaches another node instead of
+    /// coming back to the node itself, which tells nothing about the reachability.
+    pub(crate) fn session_opened(&self, addr: &Multiaddr) {
+        self.state.lock().dialing.remove(addr);
+    }
+
+    /// Records an inbound session from a public IP.
+    pub(crate) fn public_inbound(&self, now_ms: u64) {
+        self.state.lock().last_public_inbound = Some(now_ms);
+    }
+
+    pub(crate) fn report(&self, now_ms: u64) -> Reachability {
+        let mut state = self.state.lock();
+        let expiry_ms = self
+            .check_interval_ms
+            .saturating_mul(RESULT_EXPIRY_INTERVALS);
+        let is_fresh = |at: u64| now_ms.saturating_sub(at) < expiry_ms;
+        state.results.retain(|_, (_, at)| is_fresh(*at));
+
+        let (mut reachable_addresses, mut unreachable_addresses) = (Vec::new(), Vec::new());
+        for (addr, (reachable, _)) in state.results.iter() {
+            if *reachable {
+                reachable_addresses.push(addr.clone());
+            } else {
+                unreachable_addresses.push(addr.clone());
+            }
+        }
+        reachable_addresses.sort_by_key(|addr| addr.to_string());
+        unreachable_addresses.sort_by_key(|addr| addr.to_string());
+
+        let status = if !reachable_addresses.is_empty()
+            || state.last_public_inbound.map_or(false, is_fresh)
+        {
+            ReachabilityStatus::Reachable
+        } else if !unreachable_addresses.is_empty() {
+            ReachabilityStatus::Unreachable
+        } else {
+            ReachabilityStatus::Unknown
+        };
+        if state.last_status != status {
+            state.last_status = status;
+            info!("The node reachability is {}", status.as_str());
+        }
+
+        Reachability {
+            status,
+            reachable_addresses,
+            unreachable_addresses,
+            last_checked: state.last_checked,
+            last_public_inbound: state.last_public_inbound,
+        }
+    }
+}
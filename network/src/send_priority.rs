@@ -0,0 +1,188 @@
+//! Send queue selection by the priority of the messages.
+//!
+//! Tentacle drains the quick queue of a session before its normal queue, so the block messages
+//! are sent through the quick queue to preempt the bulk transaction relay. When a send to a peer
+//! finds the send buffer full, the transaction relay messages to that peer are deferred for a
+//! while, and the relayer keeps the deferred hashes to announce them later.
+use crate::{ProtocolId, SessionId, SupportProtocols};
+use ckb_logger::trace;
+use ckb_types::molecule;
+use ckb_util::Mutex;
+use p2p::{error::SendErrorKind, service::TargetSession};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// How long the transaction relay to a peer is deferred after the send buffer is found full.
+pub(crate) const CONGESTION_BACKOFF: Duration = Duration::from_millis(500);
+
+// The item ids of `SyncMessage` and `RelayMessage` in `extensions.mol`.
+const SYNC_SEND_HEADERS: u32 = 1;
+const SYNC_SEND_BLOCK: u32 = 3;
+const RELAY_COMPACT_BLOCK: u32 = 0;
+const RELAY_TRANSACTIONS: u32 = 1;
+const RELAY_TRANSACTION_HASHES: u32 = 2;
+const RELAY_GET_TRANSACTIONS: u32 = 3;
+const RELAY_BLOCK_TRANSACTIONS: u32 = 5;
+
+/// The priority of an outgoing message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SendPriority {
+    /// Block announcements and block responses.
+    Block,
+    /// The messages neither carrying blocks nor relaying transactions.
+    Normal,
+    /// Transaction relay, deferred under congestion.
+    TxRelay,
+}
+
+impl SendPriority {
+    /// Classifies a message by its protocol and its union item id.
+    pub(crate) fn of(proto_id: ProtocolId, data: &[u8]) -> SendPriority {
+        let item_id = match data.get(..molecule::NUMBER_SIZE) {
+            Some(header) => {
+                let mut bytes = [0u8; molecule::NUMBER_SIZE];
+                bytes.copy_from_slice(header);
+                u32::from_le_bytes(bytes)
+            }
+            None => return SendPriority::Normal,
+        };
+        if proto_id == SupportProtocols::Sync.protocol_id() {
+            match item_id {
+                SYNC_SEND_HEADERS | SYNC_SEND_BLOCK => SendPriority::Block,
+                _ => SendPriority::Normal,
+            }
+        } else if proto_id == SupportProtocols::RelayV2.protocol_id()
+            || proto_id == SupportProtocols::RelayV3.protocol_id()
+        {
+            match item_id {
+                RELAY_COMPACT_BLOCK | RELAY_BLOCK_TRANSACTIONS => SendPriority::Block,
+                RELAY_TRANSACTIONS | RELAY_TRANSACTION_HASHES | RELAY_GET_TRANSACTIONS => {
+                    SendPriority::TxRelay
+                }
+                _ => SendPriority::Normal,
+            }
+        } else {
+            SendPriority::Normal
+        }
+    }
+}
+
+/// Returns the session of a single target, whose congestion is observed by a broadcast.
+pub(crate) fn single_session(target: &TargetSession) -> Option<SessionId> {
+    match target {
+        TargetSession::Single(session_id) => Some(*session_id),
+        _ => None,
+    }
+}
+
+/// Picks the send queue for the messages and tracks the congestion of the peers.
+#[derive(Default)]
+pub(crate) struct SendScheduler {
+    congested_at: Mutex<HashMap<SessionId, Instant>>,
+}
+
+impl SendScheduler {
+    /// Returns whether the message to the session goes through the quick queue.
+    ///
+    /// Returns `SendErrorKind::WouldBlock` if the message is transaction relay and the session
+    /// is congested.
+    pub(crate) fn quick(
+        &self,
+        session_id: SessionId,
+        proto_id: ProtocolId,
+        data: &[u8],
+    ) -> Result<bool, SendErrorKind> {
+        match SendPriority::of(proto_id, data) {
+            SendPriority::Block => Ok(true),
+            SendPriority::Normal => Ok(false),
+            SendPriority::TxRelay => {
+                if self.is_congested(session_id) {
+                    trace!(
+                        "defer tx relay message to {} on {}, length={}",
+                        session_id,
+                        proto_id,
+                        data.len()
+                    );
+                    Err(SendErrorKind::WouldBlock)
+                } else {
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    /// Returns whether the broadcast goes through the quick queue, and the target without the
+    /// congested sessions if the message is transaction relay.
+    ///
+    /// Returns `SendErrorKind::WouldBlock` if the target is a single congested session.
+    pub(crate) fn broadcast(
+        &self,
+        target: TargetSession,
+        proto_id: ProtocolId,
+        data: &[u8],
+    ) -> Result<(bool, TargetSession), SendErrorKind> {
+        match SendPriority::of(proto_id, data) {
+            SendPriority::Block => Ok((true, target)),
+            SendPriority::Normal => Ok((false, target)),
+            SendPriority::TxRelay => {
+                let congested = self.congested_sessions();
+                if congested.is_empty() {
+                    return Ok((false, target));
+                }
+                let target = match target {
+                    TargetSession::Single(session_id) => {
+                        if congested.contains(&session_id) {
+                            return Err(SendErrorKind::WouldBlock);
+                        }
+                        TargetSession::Single(session_id)
+                    }
+                    TargetSession::Multi(iter) => TargetSession::Multi(Box::new(
+                        iter.filter(move |session_id| !congested.contains(session_id)),
+                    )),
+                    TargetSession::All => TargetSession::Filter(Box::new(move |session_id| {
+                        !congested.contains(session_id)
+                    })),
+                    TargetSession::Filter(mut filter) => {
+                        TargetSession::Filter(Box::new(move |session_id| {
+                            !congested.contains(session_id) && filter(session_id)
+                        }))
+                    }
+                };
+                Ok((false, target))
+            }
+        }
+    }
+
+    /// Records the congestion of the sessions if the send buffer is full.
+    pub(crate) fn observe<T>(
+        &self,
+        sessions: impl IntoIterator<Item = SessionId>,
+        result: &Result<T, SendErrorKind>,
+    ) {
+        if let Err(SendErrorKind::WouldBlock) = result {
+            let now = Instant::now();
+            let mut congested_at = self.congested_at.lock();
+            for session_id in sessions {
+                congested_at.insert(session_id, now);
+            }
+        }
+    }
+
+    pub(crate) fn is_congested(&self, session_id: SessionId) -> bool {
+        self.congested_at
+            .lock()
+            .get(&session_id)
+            .map_or(false, |at| at.elapsed() < CONGESTION_BACKOFF)
+    }
+
+    pub(crate) fn remove_session(&self, session_id: SessionId) {
+        self.congested_at.lock().remove(&session_id);
+    }
+
+    // Drops the expired congestion, and returns the congested sessions
+    fn congested_sessions(&self) -> HashSet<SessionId> {
+        let mut congested_at = self.congested_at.lock();
+        congested_at.retain(|_, at| at.elapsed() < CONGESTION_BACKOFF);
+        congested_at.keys().copied().collect()
+    }
+}
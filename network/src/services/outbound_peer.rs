@@ -1,17 +1,17 @@
 use crate::{
+    network::disconnect_with_message,
     peer_store::{types::AddrInfo, PeerStore},
     NetworkState,
 };
-use ckb_logger::trace;
+use ckb_logger::{debug, trace};
 use ckb_systemtime::unix_time_as_millis;
 use futures::Future;
-use p2p::{multiaddr::MultiAddr, service::ServiceControl};
-use rand::prelude::IteratorRandom;
+use p2p::{multiaddr::MultiAddr, service::ServiceControl, utils::extract_peer_id};
 use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::time::{Interval, MissedTickBehavior};
 
@@ -68,11 +68,59 @@ impl OutboundPeerService {
         }
     }
 
+    fn adapt_outbound_targets(&self) {
+        let total_bytes = self.network_state.traffic.total_bytes();
+        self.network_state
+            .outbound_targets
+            .adapt(total_bytes, Instant::now());
+    }
+
+    // Trims the outbound peers beyond the role targets, the relay protocols are closed first, then
+    // the peers only serving the sync role are disconnected
+    fn trim_outbound_peers(&self) {
+        let targets = self.network_state.outbound_targets.targets();
+        let (close_relays, disconnects) = self.network_state.with_peer_registry(|registry| {
+            registry.outbound_peers_over_targets(targets.sync, targets.relay)
+        });
+        for (session_id, proto_id) in close_relays {
+            debug!(
+                "Close the relay protocol {} of the session {} beyond the relay target {}",
+                proto_id, session_id, targets.relay
+            );
+            if let Err(err) = self.p2p_control.close_protocol(session_id, proto_id) {
+                debug!("Close the relay protocol error: {:?}", err);
+            }
+        }
+        for session_id in disconnects {
+            debug!(
+                "Disconnect the session {} beyond the sync target {}",
+                session_id, targets.sync
+            );
+            if let Err(err) = disconnect_with_message(
+                &self.p2p_control,
+                session_id,
+                "outbound peers beyond the sync target",
+            ) {
+                debug!("Disconnect the session error: {:?}", err);
+            }
+        }
+    }
+
     fn try_dial_peers(&mut self) {
         let status = self.network_state.connection_status();
+        let targets = self.network_state.outbound_targets.targets();
+        let (sync_peers, relay_peers) = self
+            .network_state
+            .with_peer_registry(|registry| registry.outbound_role_counts());
+        // dial until both role targets are met
+        let wanted = targets
+            .sync
+            .saturating_sub(sync_peers)
+            .max(targets.relay.saturating_sub(relay_peers));
         let count = status
             .max_outbound
-            .saturating_sub(status.non_whitelist_outbound) as usize;
+            .saturating_sub(status.non_whitelist_outbound)
+            .min(wanted) as usize;
         if count == 0 {
             self.try_identify_count = 0;
             return;
@@ -102,19 +150,18 @@ impl OutboundPeerService {
                     .with_peer_store_mut(|peer_store| f(peer_store, count - len, now_ms));
 
                 Box::new(
-                    attempt_peers
-                        .into_iter()
-                        .map(|info| info.addr)
-                        .chain(self.network_state.bootnodes.iter().cloned()),
+                    attempt_peers.into_iter().map(|info| info.addr).chain(
+                        self.network_state
+                            .bootnodes
+                            .choose(&mut rand::thread_rng(), len),
+                    ),
                 )
             } else {
                 Box::new(
                     self.network_state
                         .bootnodes
-                        .iter()
-                        .choose_multiple(&mut rand::thread_rng(), count)
-                        .into_iter()
-                        .cloned(),
+                        .choose(&mut rand::thread_rng(), count)
+                        .into_iter(),
                 )
             }
         } else {
@@ -143,9 +190,27 @@ impl OutboundPeerService {
         }
     }
 
+    // Dials the bootnodes due for a health check, the connected ones are healthy.
+    fn check_bootnodes(&self) {
+        let now_ms = unix_time_as_millis();
+        for addr in self.network_state.bootnodes.due_for_check(now_ms) {
+            let connected = extract_peer_id(&addr).map_or(false, |peer_id| {
+                self.network_state
+                    .with_peer_registry(|reg| reg.get_key_by_peer_id(&peer_id).is_some())
+            });
+            if connected {
+                self.network_state.bootnodes.record_success(&addr, now_ms);
+            } else {
+                self.network_state.dial_feeler(&self.p2p_control, addr);
+            }
+        }
+    }
+
     fn try_dial_observed(&self) {
         self.network_state
             .try_dial_observed_addrs(&self.p2p_control);
+        // the outcomes of the previous dials are reported to the metrics
+        self.network_state.report_reachability();
     }
 }
 
@@ -167,10 +232,15 @@ impl Future for OutboundPeerService {
             self.try_dial_whitelist();
             // ensure feeler work at any time
             self.dial_feeler();
+            // lower or restore the outbound targets by the bandwidth headroom
+            self.adapt_outbound_targets();
+            self.trim_outbound_peers();
             // keep outbound peer is enough
             self.try_dial_peers();
             // try dial observed addrs
             self.try_dial_observed();
+            // check whether the bootnodes are reachable
+            self.check_bootnodes();
         }
         Poll::Pending
     }
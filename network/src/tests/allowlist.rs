@@ -0,0 +1,77 @@
+use super::random_addr;
+use crate::{
+    allowlist::parse_allowed_peers,
+    errors::{Error, PeerError},
+    extract_peer_id,
+    multiaddr::Multiaddr,
+    peer_registry::PeerRegistry,
+    peer_store::PeerStore,
+    PeerId, SessionId, SessionType,
+};
+use p2p::secio::SecioKeyPair;
+
+#[test]
+fn test_parse_allowed_peers() {
+    let key_pair = SecioKeyPair::secp256k1_generated();
+    let public_key = key_pair.public_key();
+    let mut hex_key = vec![0u8; public_key.inner_ref().len() * 2];
+    faster_hex::hex_encode(public_key.inner_ref(), &mut hex_key).unwrap();
+    let peer_id = PeerId::random();
+
+    let content = format!(
+        "# the peers of the test network\n\n{}\n0x{}  # by public key\n",
+        peer_id.to_base58(),
+        String::from_utf8(hex_key).unwrap()
+    );
+    let peers = parse_allowed_peers(&content).unwrap();
+    assert_eq!(peers.len(), 2);
+    assert!(peers.contains(&peer_id));
+    assert!(peers.contains(&key_pair.peer_id()));
+
+    let err = parse_allowed_peers("not-a-peer").unwrap_err();
+    assert!(err.starts_with("line 1:"), "{}", err);
+}
+
+#[test]
+fn test_accept_allowed_peers_only() {
+    let mut peer_store = PeerStore::default();
+    let allowed_addr = random_addr();
+    let whitelist_addr = format!("/ip4/127.0.0.1/tcp/43/p2p/{}", PeerId::random().to_base58())
+        .parse::<Multiaddr>()
+        .unwrap();
+    let mut peers = PeerRegistry::new(3, 3, false, vec![whitelist_addr.clone()]);
+    peers.set_allowed_peers(Some(
+        vec![extract_peer_id(&allowed_addr).unwrap()]
+            .into_iter()
+            .collect(),
+    ));
+
+    // the whitelist peers are not exempted
+    for (session_id, addr, session_type) in [
+        (1, random_addr(), SessionType::Inbound),
+        (2, random_addr(), SessionType::Outbound),
+        (3, whitelist_addr, SessionType::Outbound),
+    ] {
+        let err = peers
+            .accept_peer(addr, session_id.into(), session_type, &mut peer_store)
+            .unwrap_err();
+        assert_eq!(
+            format!("{err}"),
+            format!("{}", Error::Peer(PeerError::NotAllowed))
+        );
+    }
+    let session_id: SessionId = 4.into();
+    peers
+        .accept_peer(
+            allowed_addr,
+            session_id,
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+    assert!(peers.disallowed_sessions().is_empty());
+
+    // the peers removed from the allowlist are to be disconnected
+    peers.set_allowed_peers(Some(Default::default()));
+    assert_eq!(peers.disallowed_sessions(), vec![session_id]);
+}
@@ -0,0 +1,119 @@
+use super::random_addr;
+use crate::bootnodes::Bootnodes;
+use ckb_app_config::{BootnodeSetConfig, NetworkConfig};
+
+fn config(sets: Vec<(u32, Vec<crate::multiaddr::Multiaddr>)>) -> NetworkConfig {
+    NetworkConfig {
+        bootnode_sets: sets
+            .into_iter()
+            .enumerate()
+            .map(|(i, (weight, bootnodes))| BootnodeSetConfig {
+                name: format!("set{i}"),
+                weight,
+                bootnodes,
+            })
+            .collect(),
+        bootnode_health_check_interval_secs: Some(10),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_default_set_from_bootnodes() {
+    let addr = random_addr();
+    let config = NetworkConfig {
+        bootnodes: vec![addr.clone()],
+        bootnode_sets: vec![BootnodeSetConfig {
+            name: "asia".to_owned(),
+            weight: 2,
+            bootnodes: vec![addr.clone(), random_addr()],
+        }],
+        ..Default::default()
+    };
+    let bootnodes = Bootnodes::new(&config);
+    assert_eq!(bootnodes.len(), 2);
+
+    let health = bootnodes.health();
+    assert_eq!(health[0].address, addr);
+    assert_eq!(health[0].set, "default");
+    assert_eq!(health[0].weight, 1);
+    assert_eq!(health[1].set, "asia");
+}
+
+#[test]
+fn test_rotate_away_from_unhealthy_bootnode() {
+    let dead = random_addr();
+    let alive = random_addr();
+    let bootnodes = Bootnodes::new(&config(vec![(1, vec![dead.clone(), alive.clone()])]));
+    let mut rng = rand::thread_rng();
+
+    for now in 0..2 {
+        bootnodes.record_failure(&dead, now);
+    }
+    assert_eq!(bootnodes.choose(&mut rng, 2).len(), 2);
+
+    bootnodes.record_failure(&dead, 2);
+    assert!(bootnodes.health()[0].is_unhealthy());
+    for _ in 0..10 {
+        assert_eq!(bootnodes.choose(&mut rng, 2), vec![alive.clone()]);
+    }
+
+    // reachable again
+    bootnodes.record_success(&dead, 3);
+    let health = &bootnodes.health()[0];
+    assert!(!health.is_unhealthy());
+    assert_eq!(health.consecutive_failures, 0);
+    assert_eq!(health.last_success, Some(3));
+    assert_eq!(health.last_failure, Some(2));
+    assert_eq!(bootnodes.choose(&mut rng, 2).len(), 2);
+}
+
+#[test]
+fn test_fallback_when_all_unhealthy() {
+    let addr = random_addr();
+    let bootnodes = Bootnodes::new(&config(vec![(1, vec![addr.clone()])]));
+    for now in 0..3 {
+        bootnodes.record_failure(&addr, now);
+    }
+    assert_eq!(bootnodes.choose(&mut rand::thread_rng(), 1), vec![addr]);
+}
+
+#[test]
+fn test_choose_by_weight() {
+    let disabled = random_addr();
+    let light = random_addr();
+    let heavy = random_addr();
+    let bootnodes = Bootnodes::new(&config(vec![
+        (0, vec![disabled.clone()]),
+        (1, vec![light.clone()]),
+        (9, vec![heavy.clone()]),
+    ]));
+    let mut rng = rand::thread_rng();
+
+    let mut heavy_count = 0;
+    for _ in 0..1000 {
+        let chosen = bootnodes.choose(&mut rng, 1);
+        assert_eq!(chosen.len(), 1);
+        assert_ne!(chosen[0], disabled);
+        if chosen[0] == heavy {
+            heavy_count += 1;
+        }
+    }
+    // the expected count is 900
+    assert!(heavy_count > 800, "heavy picked {heavy_count} times");
+
+    let all = bootnodes.choose(&mut rng, 3);
+    assert_eq!(all.len(), 2);
+    assert!(all.contains(&light) && all.contains(&heavy));
+}
+
+#[test]
+fn test_due_for_check() {
+    let addr = random_addr();
+    let bootnodes = Bootnodes::new(&config(vec![(1, vec![addr.clone()])]));
+
+    assert_eq!(bootnodes.due_for_check(1_000), vec![addr.clone()]);
+    assert!(bootnodes.due_for_check(5_000).is_empty());
+    assert_eq!(bootnodes.health()[0].last_checked, Some(1_000));
+    assert_eq!(bootnodes.due_for_check(11_000), vec![addr]);
+}
@@ -1,6 +1,6 @@
 use p2p::bytes::{Bytes, BytesMut};
 
-use crate::compress::{compress, decompress, Message, COMPRESSION_SIZE_THRESHOLD};
+use crate::compress::{compress, decompress, uncompressed, Message, COMPRESSION_SIZE_THRESHOLD};
 
 #[test]
 fn test_no_need_compress() {
@@ -46,3 +46,14 @@ fn test_invalid_data() {
     assert!(decompress(BytesMut::from(&cmp_data.as_ref()[1..])).is_err());
     assert!(decompress(BytesMut::new()).is_err());
 }
+
+#[test]
+fn test_uncompressed() {
+    let raw_data = Bytes::from(vec![1; COMPRESSION_SIZE_THRESHOLD + 1]);
+    let data = uncompressed(raw_data.clone());
+    assert_eq!(data.len(), raw_data.len() + 1);
+
+    let msg = Message::from_compressed(BytesMut::from(data.as_ref()));
+    assert!(!msg.compress_flag());
+    assert_eq!(decompress(BytesMut::from(data.as_ref())).unwrap(), raw_data);
+}
@@ -0,0 +1,33 @@
+use crate::dial_waiters::{DialOutcome, DialWaiters};
+use crate::PeerId;
+use tokio::sync::oneshot::error::TryRecvError;
+
+#[test]
+fn test_dial_waiters() {
+    let waiters = DialWaiters::default();
+    let (peer, other) = (PeerId::random(), PeerId::random());
+    let mut first = waiters.wait(peer.clone());
+    let mut second = waiters.wait(peer.clone());
+    let mut third = waiters.wait(other.clone());
+
+    waiters.notify(&peer, DialOutcome::HandshakeFailed("secio".to_owned()));
+    for receiver in [&mut first, &mut second] {
+        assert_eq!(
+            receiver.try_recv(),
+            Ok(DialOutcome::HandshakeFailed("secio".to_owned()))
+        );
+    }
+    assert_eq!(third.try_recv(), Err(TryRecvError::Empty));
+
+    // the waiter notified is removed
+    let mut fourth = waiters.wait(peer.clone());
+    assert_eq!(fourth.try_recv(), Err(TryRecvError::Empty));
+    waiters.notify(&other, DialOutcome::Connected);
+    assert_eq!(third.try_recv(), Ok(DialOutcome::Connected));
+    assert_eq!(fourth.try_recv(), Err(TryRecvError::Empty));
+
+    assert_eq!(
+        DialWaiters::ready(DialOutcome::Banned).try_recv(),
+        Ok(DialOutcome::Banned)
+    );
+}
@@ -0,0 +1,88 @@
+use super::random_addr;
+use crate::{
+    features::{flag_names, PeerFeatures},
+    Flags, Peer, PeerIdentifyInfo, SessionType, SupportProtocols,
+};
+use ckb_app_config::NetworkConfig;
+
+fn new_peer(protocols: &[(SupportProtocols, &str)], flags: Option<Flags>) -> Peer {
+    let mut peer = Peer::new(0.into(), SessionType::Outbound, random_addr(), false);
+    for (protocol, version) in protocols {
+        peer.protocols
+            .insert(protocol.protocol_id(), version.to_string());
+    }
+    peer.identify_info = flags.map(|flags| PeerIdentifyInfo {
+        client_version: "test".to_string(),
+        flags,
+    });
+    peer
+}
+
+#[test]
+fn test_negotiated_features() {
+    let peer = new_peer(&[], None);
+    assert_eq!(peer.features(), PeerFeatures::empty());
+
+    let peer = new_peer(
+        &[
+            (SupportProtocols::Identify, "3"),
+            (SupportProtocols::RelayV3, "3"),
+            (SupportProtocols::Filter, "3"),
+        ],
+        Some(Flags::SYNC | Flags::RELAY),
+    );
+    assert_eq!(
+        peer.features(),
+        PeerFeatures::COMPRESSION | PeerFeatures::COMPACT_RELAY
+    );
+    assert!(peer.supports(PeerFeatures::COMPACT_RELAY));
+    // the filter protocol is opened, but the peer does not announce the service
+    assert!(!peer.supports(PeerFeatures::BLOCK_FILTER));
+    assert_eq!(
+        peer.features().names(),
+        vec!["compression", "compact_relay"]
+    );
+
+    let peer = new_peer(
+        &[(SupportProtocols::Filter, "0.0.1")],
+        Some(Flags::BLOCK_FILTER),
+    );
+    assert_eq!(peer.features(), PeerFeatures::BLOCK_FILTER);
+
+    // the push is negotiated only with the peers opting in
+    let peer = new_peer(
+        &[(SupportProtocols::RelayV3, "3")],
+        Some(Flags::RELAY | Flags::TX_PUSH),
+    );
+    assert!(peer.supports(PeerFeatures::COMPACT_RELAY | PeerFeatures::TX_PUSH));
+    assert_eq!(peer.features().names(), vec!["compact_relay", "tx_push"]);
+}
+
+#[test]
+fn test_local_features() {
+    let mut config = NetworkConfig::default();
+    config.compression = true;
+    assert_eq!(PeerFeatures::local(&config), PeerFeatures::all());
+
+    // the compression disabled by the config is not negotiated
+    config.compression = false;
+    let mut peer = new_peer(
+        &[
+            (SupportProtocols::Identify, "3"),
+            (SupportProtocols::RelayV3, "3"),
+        ],
+        Some(Flags::SYNC | Flags::RELAY),
+    );
+    peer.local_features = PeerFeatures::local(&config);
+    assert_eq!(peer.features(), PeerFeatures::COMPACT_RELAY);
+    assert!(!peer.supports(PeerFeatures::COMPRESSION));
+}
+
+#[test]
+fn test_flag_names() {
+    assert!(flag_names(Flags::empty()).is_empty());
+    assert_eq!(
+        flag_names(Flags::COMPATIBILITY | Flags::SYNC | Flags::BLOCK_FILTER),
+        vec!["compatibility", "sync", "block_filter"]
+    );
+}
@@ -0,0 +1,144 @@
+use super::{random_addr, random_addr_v6};
+use crate::{
+    errors::{Error, PeerError},
+    listeners::{ListenerKind, Listeners},
+    multiaddr::Multiaddr,
+    peer_registry::PeerRegistry,
+    peer_store::PeerStore,
+    SessionType,
+};
+use ckb_app_config::{ListenerConfig, NetworkConfig};
+
+fn listener(address: &str, max_inbound_peers: Option<u32>, advertise: bool) -> ListenerConfig {
+    ListenerConfig {
+        address: address.parse().unwrap(),
+        max_inbound_peers,
+        advertise,
+    }
+}
+
+fn config(listeners: Vec<ListenerConfig>) -> NetworkConfig {
+    NetworkConfig {
+        listen_addresses: vec!["/ip4/0.0.0.0/tcp/8115".parse().unwrap()],
+        listeners,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_listeners_from_config() {
+    let config = config(vec![
+        listener("/ip6/::/tcp/8115", Some(2), true),
+        listener("/ip4/10.0.0.2/tcp/8116", None, false),
+    ]);
+    let listeners = Listeners::new(&config);
+    assert_eq!(listeners.addresses().len(), 3);
+
+    // only the started and advertised listeners are told to the peers
+    let addresses = listeners.addresses();
+    for address in &addresses {
+        listeners.set_listened(address, address.clone());
+    }
+    assert_eq!(
+        listeners.advertised(),
+        vec![addresses[0].clone(), addresses[1].clone()]
+    );
+
+    // the ipv4 listeners are not capped since `listen_addresses` are not
+    let caps = listeners.inbound_caps();
+    assert_eq!(caps.len(), 1);
+    let ipv6 = ListenerKind::of(&random_addr_v6()).unwrap();
+    assert_eq!(caps.get(&ipv6), Some(&2));
+    assert_eq!(listeners.total_inbound_cap(), None);
+}
+
+#[test]
+fn test_listener_settings_override_listen_addresses() {
+    let config = config(vec![listener("/ip4/0.0.0.0/tcp/8115", Some(3), false)]);
+    let listeners = config.listeners();
+    assert_eq!(listeners.len(), 1);
+    assert_eq!(listeners[0].max_inbound_peers, Some(3));
+    assert!(!listeners[0].advertise);
+}
+
+#[test]
+fn test_accept_inbound_peer_until_listener_full() {
+    let mut peer_store = PeerStore::default();
+    let config = config(vec![
+        listener("/ip6/::/tcp/8115", Some(1), true),
+        listener("/ip6/::/tcp/8116", Some(1), true),
+    ]);
+    let listeners = Listeners::new(&config);
+    let mut peers = PeerRegistry::new(10, 3, false, Vec::<Multiaddr>::new());
+    peers.set_listener_inbound_caps(&listeners);
+
+    // the ipv6 listeners share the sum of their caps
+    for session_id in 1..=2 {
+        peers
+            .accept_peer(
+                random_addr_v6(),
+                session_id.into(),
+                SessionType::Inbound,
+                &mut peer_store,
+            )
+            .expect("accept");
+    }
+    let err = peers
+        .accept_peer(
+            random_addr_v6(),
+            3.into(),
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("{err}"),
+        format!("{}", Error::Peer(PeerError::ReachListenerInboundLimit))
+    );
+
+    // the ipv4 listener is still open
+    peers
+        .accept_peer(
+            random_addr(),
+            4.into(),
+            SessionType::Inbound,
+            &mut peer_store,
+        )
+        .expect("accept");
+    let inbound = peers.non_whitelist_inbound_by_listener_kind();
+    let ipv6 = ListenerKind::of(&random_addr_v6()).unwrap();
+    assert_eq!(inbound.get(&ipv6), Some(&2));
+}
+
+#[test]
+fn test_accept_inbound_peer_until_listeners_full_in_total() {
+    let mut peer_store = PeerStore::default();
+    let config = config(vec![
+        listener("/ip4/0.0.0.0/tcp/8115", Some(1), true),
+        listener("/ip6/::/tcp/8115", Some(1), true),
+    ]);
+    let listeners = Listeners::new(&config);
+    assert_eq!(listeners.total_inbound_cap(), Some(2));
+    let mut peers = PeerRegistry::new(10, 3, false, Vec::<Multiaddr>::new());
+    peers.set_listener_inbound_caps(&listeners);
+
+    for (session_id, addr) in [(1, random_addr()), (2, random_addr_v6())] {
+        peers
+            .accept_peer(
+                addr,
+                session_id.into(),
+                SessionType::Inbound,
+                &mut peer_store,
+            )
+            .expect("accept");
+    }
+    // no listener of its kind caps the websocket peer, but all the listeners are full
+    let ws_addr: Multiaddr = "/ip4/127.0.0.1/tcp/8080/ws".parse().unwrap();
+    let err = peers
+        .accept_peer(ws_addr, 3.into(), SessionType::Inbound, &mut peer_store)
+        .unwrap_err();
+    assert_eq!(
+        format!("{err}"),
+        format!("{}", Error::Peer(PeerError::ReachListenerInboundLimit))
+    );
+}
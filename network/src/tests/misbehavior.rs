@@ -0,0 +1,111 @@
+use crate::misbehavior::{MisbehaviorScores, MAX_SCORED_PEERS};
+use crate::PeerId;
+use ckb_app_config::{MisbehaviorConfig, NetworkConfig};
+
+const HOUR_MS: u64 = 3_600_000;
+
+#[test]
+fn test_misbehavior_config() {
+    let config = NetworkConfig::default();
+    assert_eq!(config.misbehavior_ban_threshold(), 100);
+    assert_eq!(config.misbehavior_decay_half_life().as_secs(), 3600);
+    assert_eq!(config.misbehavior_weight("BlockIsInvalid"), 100);
+
+    let config = NetworkConfig {
+        misbehavior: MisbehaviorConfig {
+            ban_threshold: Some(200),
+            decay_half_life_secs: Some(60),
+            weights: vec![("TooManyUnknownTransactions".to_owned(), 20)]
+                .into_iter()
+                .collect(),
+        },
+        ..Default::default()
+    };
+    assert_eq!(config.misbehavior_ban_threshold(), 200);
+    assert_eq!(config.misbehavior_decay_half_life().as_secs(), 60);
+    assert_eq!(config.misbehavior_weight("TooManyUnknownTransactions"), 20);
+    assert_eq!(config.misbehavior_weight("BlockIsInvalid"), 100);
+}
+
+#[test]
+fn test_scores_add_up_by_kind() {
+    let scores = MisbehaviorScores::new(HOUR_MS);
+    let peer_id = PeerId::random();
+
+    assert_eq!(scores.report(&peer_id, "A", 30, "a1", 0), 30.0);
+    assert_eq!(scores.report(&peer_id, "B", 20, "b1", 0), 50.0);
+    assert_eq!(scores.report(&peer_id, "A", 30, "a2", 0), 80.0);
+
+    let peer = scores.peer(&peer_id, 0).unwrap();
+    assert_eq!(peer.score, 80.0);
+    assert_eq!(peer.components.len(), 2);
+    assert_eq!(peer.components[0].kind, "A");
+    assert_eq!(peer.components[0].score, 60.0);
+    assert_eq!(peer.components[0].count, 2);
+    assert_eq!(peer.components[0].last_reason, "a2");
+    assert_eq!(peer.components[1].kind, "B");
+    assert_eq!(peer.breakdown(), "A=60.0(2), B=20.0(1)");
+
+    // the peers are scored independently
+    assert_eq!(scores.report(&PeerId::random(), "A", 30, "a1", 0), 30.0);
+    assert_eq!(scores.peers(0).len(), 2);
+
+    scores.remove(&peer_id);
+    assert!(scores.peer(&peer_id, 0).is_none());
+    assert_eq!(scores.peers(0).len(), 1);
+}
+
+#[test]
+fn test_scores_decay() {
+    let scores = MisbehaviorScores::new(HOUR_MS);
+    let peer_id = PeerId::random();
+
+    scores.report(&peer_id, "A", 80, "a", 0);
+    let peer = scores.peer(&peer_id, HOUR_MS).unwrap();
+    assert!((peer.score - 40.0).abs() < 1e-6);
+    assert_eq!(peer.components[0].last_reported_at, 0);
+
+    // the decayed score is kept and the new weight is added to it
+    let score = scores.report(&peer_id, "A", 80, "a", 2 * HOUR_MS);
+    assert!((score - 100.0).abs() < 1e-6);
+    let score = scores.report(&peer_id, "B", 10, "b", 3 * HOUR_MS);
+    assert!((score - 60.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_scores_never_decay_with_zero_half_life() {
+    let scores = MisbehaviorScores::new(0);
+    let peer_id = PeerId::random();
+
+    scores.report(&peer_id, "A", 80, "a", 0);
+    assert_eq!(scores.peer(&peer_id, 100 * HOUR_MS).unwrap().score, 80.0);
+}
+
+#[test]
+fn test_scores_evict_the_lowest() {
+    let scores = MisbehaviorScores::new(0);
+    let lowest = PeerId::random();
+
+    scores.report(&lowest, "A", 10, "a", 0);
+    for _ in 1..MAX_SCORED_PEERS {
+        scores.report(&PeerId::random(), "A", 50, "a", 0);
+    }
+    let peer_id = PeerId::random();
+    scores.report(&peer_id, "A", 50, "a", 0);
+
+    assert_eq!(scores.peers(0).len(), MAX_SCORED_PEERS);
+    assert!(scores.peer(&lowest, 0).is_none());
+    assert!(scores.peer(&peer_id, 0).is_some());
+}
+
+#[test]
+fn test_scores_evict_the_decayed() {
+    let scores = MisbehaviorScores::new(HOUR_MS);
+    for _ in 0..MAX_SCORED_PEERS {
+        scores.report(&PeerId::random(), "A", 50, "a", 0);
+    }
+    // all the scores decay below 1 after 6 half lives
+    scores.report(&PeerId::random(), "A", 50, "a", 6 * HOUR_MS);
+
+    assert_eq!(scores.peers(6 * HOUR_MS).len(), 1);
+}
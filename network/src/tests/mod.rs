@@ -1,8 +1,17 @@
 mod addr_manager;
+mod allowlist;
+mod bootnodes;
 mod compress;
+mod dial_waiters;
+mod features;
+mod listeners;
+mod misbehavior;
+mod outbound_targets;
 mod peer_registry;
 mod peer_store;
 mod peer_store_db;
+mod reachability;
+mod send_priority;
 
 fn random_addr() -> crate::multiaddr::Multiaddr {
     format!(
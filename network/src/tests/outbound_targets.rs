@@ -0,0 +1,68 @@
+use crate::outbound_targets::{OutboundTargets, OutboundTargetsAdapter};
+use ckb_app_config::NetworkConfig;
+use std::time::{Duration, Instant};
+
+fn config(bandwidth_limit: Option<u64>) -> NetworkConfig {
+    let mut config = NetworkConfig {
+        max_outbound_peers: 8,
+        ..Default::default()
+    };
+    config.outbound_targets.relay = Some(4);
+    config.outbound_targets.min_peers = Some(3);
+    config.outbound_targets.bandwidth_limit = bandwidth_limit.map(Into::into);
+    config
+}
+
+#[test]
+fn test_fixed_targets_without_bandwidth_limit() {
+    let adapter = OutboundTargetsAdapter::new(&config(None));
+    let now = Instant::now();
+    adapter.adapt(0, now);
+    let targets = adapter.adapt(u64::MAX, now + Duration::from_secs(1));
+    assert_eq!(
+        targets,
+        OutboundTargets {
+            sync: 8,
+            relay: 4,
+            bandwidth_usage: None,
+        }
+    );
+}
+
+#[test]
+fn test_adapt_targets_to_bandwidth() {
+    let adapter = OutboundTargetsAdapter::new(&config(Some(1000)));
+    let start = Instant::now();
+    let mut total_bytes = 0;
+    let mut sample = |secs: u64, bytes_per_sec: u64| {
+        total_bytes += bytes_per_sec;
+        adapter.adapt(total_bytes, start + Duration::from_secs(secs))
+    };
+    // the first sample only records the traffic
+    assert_eq!(sample(0, 0).bandwidth_usage, None);
+
+    // relay peers are dropped first, but never below min_peers
+    let targets = sample(1, 900);
+    assert_eq!((targets.sync, targets.relay), (8, 3));
+    assert_eq!(targets.bandwidth_usage, Some(900));
+    let targets = sample(2, 900);
+    assert_eq!((targets.sync, targets.relay), (7, 3));
+    for secs in 3..10 {
+        sample(secs, 900);
+    }
+    let targets = sample(10, 900);
+    assert_eq!((targets.sync, targets.relay), (3, 3));
+
+    // the targets are kept between the watermarks
+    let targets = sample(11, 600);
+    assert_eq!((targets.sync, targets.relay), (3, 3));
+
+    // sync peers are restored first
+    let targets = sample(12, 100);
+    assert_eq!((targets.sync, targets.relay), (4, 3));
+    for secs in 13..20 {
+        sample(secs, 100);
+    }
+    let targets = sample(20, 100);
+    assert_eq!((targets.sync, targets.relay), (8, 4));
+}
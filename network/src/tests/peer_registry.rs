@@ -7,7 +7,7 @@ use crate::{
     multiaddr::Multiaddr,
     peer_registry::{PeerRegistry, EVICTION_PROTECT_PEERS},
     peer_store::PeerStore,
-    PeerId, SessionType,
+    PeerId, SessionType, SupportProtocols,
 };
 use std::time::{Duration, Instant};
 
@@ -221,3 +221,43 @@ fn test_accept_inbound_peer_eviction() {
     // should evict from one of evict_targets
     assert_eq!(len_after_eviction, evict_targets.len() - 1);
 }
+
+#[test]
+fn test_outbound_peers_over_targets() {
+    let mut peer_store = PeerStore::default();
+    let mut peers_registry = PeerRegistry::new(0, 4, false, Vec::new());
+    let now = Instant::now() - Duration::from_secs(10);
+    for id in 1..=4 {
+        let session_id = id.into();
+        peers_registry
+            .accept_peer(
+                random_addr(),
+                session_id,
+                SessionType::Outbound,
+                &mut peer_store,
+            )
+            .expect("accept");
+        let peer = peers_registry.get_peer_mut(session_id).unwrap();
+        // the larger session id is newer
+        peer.connected_time = now + Duration::from_secs(id as u64);
+        peer.protocols
+            .insert(SupportProtocols::Sync.protocol_id(), "3".to_owned());
+        if id < 4 {
+            peer.protocols
+                .insert(SupportProtocols::RelayV3.protocol_id(), "3".to_owned());
+        }
+    }
+
+    let (close_relays, disconnects) = peers_registry.outbound_peers_over_targets(4, 4);
+    assert!(close_relays.is_empty());
+    assert!(disconnects.is_empty());
+
+    // the newest relay peer closes the relay protocol, and only the peer not serving the relay
+    // role is disconnected
+    let (close_relays, disconnects) = peers_registry.outbound_peers_over_targets(2, 2);
+    assert_eq!(
+        close_relays,
+        vec![(3.into(), SupportProtocols::RelayV3.protocol_id())]
+    );
+    assert_eq!(disconnects, vec![4.into()]);
+}
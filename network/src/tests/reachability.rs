@@ -0,0 +1,122 @@
+use crate::multiaddr::Multiaddr;
+use crate::reachability::{ReachabilityProbe, ReachabilityStatus};
+use ckb_app_config::NetworkConfig;
+
+const INTERVAL_MS: u64 = 60_000;
+
+fn probe() -> ReachabilityProbe {
+    let config = NetworkConfig {
+        reachability_check_interval_secs: Some(INTERVAL_MS / 1000),
+        ..Default::default()
+    };
+    ReachabilityProbe::new(&config)
+}
+
+fn addr(ip: &str) -> Multiaddr {
+    format!("/ip4/{ip}/tcp/8115").parse().unwrap()
+}
+
+#[test]
+fn test_reachability_config() {
+    let config = NetworkConfig::default();
+    assert_eq!(config.reachability_check_interval().as_secs(), 600);
+}
+
+#[test]
+fn test_self_test_is_due_by_interval() {
+    let probe = probe();
+    assert!(probe.is_due(0));
+    probe.start(1_000);
+    assert!(!probe.is_due(1_000 + INTERVAL_MS - 1));
+    assert!(probe.is_due(1_000 + INTERVAL_MS));
+}
+
+#[test]
+fn test_status_by_dial_results() {
+    let probe = probe();
+    let report = probe.report(0);
+    assert_eq!(report.status, ReachabilityStatus::Unknown);
+    assert_eq!(report.last_checked, None);
+
+    probe.start(0);
+    probe.dialing(addr("1.1.1.1"), 0);
+    probe.dialing(addr("2.2.2.2"), 0);
+    // the dials not to the public addresses are ignored
+    probe.dialed(&addr("3.3.3.3"), true, 10);
+    assert_eq!(probe.report(10).status, ReachabilityStatus::Unknown);
+
+    probe.dialed(&addr("1.1.1.1"), false, 10);
+    let report = probe.report(10);
+    assert_eq!(report.status, ReachabilityStatus::Unreachable);
+    assert_eq!(report.unreachable_addresses, vec![addr("1.1.1.1")]);
+    assert_eq!(report.last_checked, Some(0));
+
+    probe.dialed(&addr("2.2.2.2"), true, 20);
+    let report = probe.report(20);
+    assert_eq!(report.status, ReachabilityStatus::Reachable);
+    assert_eq!(report.reachable_addresses, vec![addr("2.2.2.2")]);
+    assert_eq!(report.unreachable_addresses, vec![addr("1.1.1.1")]);
+}
+
+#[test]
+fn test_public_inbound_proves_reachable() {
+    let probe = probe();
+    probe.dialing(addr("1.1.1.1"), 0);
+    probe.dialed(&addr("1.1.1.1"), false, 0);
+    probe.public_inbound(10);
+
+    let report = probe.report(10);
+    assert_eq!(report.status, ReachabilityStatus::Reachable);
+    assert_eq!(report.last_public_inbound, Some(10));
+}
+
+#[test]
+fn test_results_expire() {
+    let probe = probe();
+    probe.dialing(addr("1.1.1.1"), 0);
+    probe.dialed(&addr("1.1.1.1"), true, 0);
+    probe.public_inbound(0);
+    assert_eq!(
+        probe.report(3 * INTERVAL_MS - 1).status,
+        ReachabilityStatus::Reachable
+    );
+
+    let report = probe.report(3 * INTERVAL_MS);
+    assert_eq!(report.status, ReachabilityStatus::Unknown);
+    assert!(report.reachable_addresses.is_empty());
+    // the last inbound is still reported, though it no longer proves the node reachable
+    assert_eq!(report.last_public_inbound, Some(0));
+}
+
+#[test]
+fn test_dial_to_another_node_is_dropped() {
+    let probe = probe();
+    probe.dialing(addr("1.1.1.1"), 0);
+    // the address belongs to another node, the dial opens a session
+    probe.session_opened(&addr("1.1.1.1"));
+    probe.dialed(&addr("1.1.1.1"), false, 10);
+    assert_eq!(probe.report(10).status, ReachabilityStatus::Unknown);
+}
+
+#[test]
+fn test_dialing_addresses_are_bounded() {
+    let probe = probe();
+    let addrs: Vec<_> = (0..=255u8).map(|i| addr(&format!("1.1.1.{i}"))).collect();
+    for addr in &addrs {
+        probe.dialing(addr.clone(), 0);
+    }
+    // the set is full of the unfinished dials
+    probe.dialing(addr("2.2.2.2"), 10);
+    probe.dialed(&addr("2.2.2.2"), true, 10);
+    assert_eq!(probe.report(10).status, ReachabilityStatus::Unknown);
+
+    // the dials without an outcome for a check interval are dropped
+    probe.dialing(addr("2.2.2.2"), INTERVAL_MS);
+    probe.dialed(&addr("2.2.2.2"), true, INTERVAL_MS);
+    assert_eq!(
+        probe.report(INTERVAL_MS).reachable_addresses,
+        vec![addr("2.2.2.2")]
+    );
+    probe.dialed(&addrs[0], true, INTERVAL_MS);
+    assert_eq!(probe.report(INTERVAL_MS).reachable_addresses.len(), 1);
+}
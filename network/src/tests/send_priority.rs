@@ -0,0 +1,141 @@
+use crate::{
+    send_priority::{SendPriority, SendScheduler},
+    SessionId, SupportProtocols,
+};
+use ckb_types::{packed, prelude::*};
+use p2p::{error::SendErrorKind, service::TargetSession};
+
+#[test]
+fn test_classify_messages() {
+    let sync = SupportProtocols::Sync.protocol_id();
+    let relay = SupportProtocols::RelayV3.protocol_id();
+
+    let send_block = packed::SyncMessage::new_builder()
+        .set(packed::SendBlock::default())
+        .build();
+    let get_blocks = packed::SyncMessage::new_builder()
+        .set(packed::GetBlocks::default())
+        .build();
+    assert_eq!(
+        SendPriority::of(sync, send_block.as_slice()),
+        SendPriority::Block
+    );
+    assert_eq!(
+        SendPriority::of(sync, get_blocks.as_slice()),
+        SendPriority::Normal
+    );
+
+    let compact_block = packed::RelayMessage::new_builder()
+        .set(packed::CompactBlock::default())
+        .build();
+    let block_txs = packed::RelayMessage::new_builder()
+        .set(packed::BlockTransactions::default())
+        .build();
+    let tx_hashes = packed::RelayMessage::new_builder()
+        .set(packed::RelayTransactionHashes::default())
+        .build();
+    let txs = packed::RelayMessage::new_builder()
+        .set(packed::RelayTransactions::default())
+        .build();
+    let proposal = packed::RelayMessage::new_builder()
+        .set(packed::BlockProposal::default())
+        .build();
+    assert_eq!(
+        SendPriority::of(relay, compact_block.as_slice()),
+        SendPriority::Block
+    );
+    assert_eq!(
+        SendPriority::of(relay, block_txs.as_slice()),
+        SendPriority::Block
+    );
+    assert_eq!(
+        SendPriority::of(relay, tx_hashes.as_slice()),
+        SendPriority::TxRelay
+    );
+    assert_eq!(
+        SendPriority::of(relay, txs.as_slice()),
+        SendPriority::TxRelay
+    );
+    assert_eq!(
+        SendPriority::of(relay, proposal.as_slice()),
+        SendPriority::Normal
+    );
+
+    // the other protocols are not inspected
+    let ping = SupportProtocols::Ping.protocol_id();
+    assert_eq!(
+        SendPriority::of(ping, compact_block.as_slice()),
+        SendPriority::Normal
+    );
+    assert_eq!(SendPriority::of(relay, &[0u8; 2]), SendPriority::Normal);
+}
+
+#[test]
+fn test_defer_tx_relay_under_congestion() {
+    let relay = SupportProtocols::RelayV3.protocol_id();
+    let compact_block = packed::RelayMessage::new_builder()
+        .set(packed::CompactBlock::default())
+        .build();
+    let tx_hashes = packed::RelayMessage::new_builder()
+        .set(packed::RelayTransactionHashes::default())
+        .build();
+
+    let (congested, other) = (SessionId::new(1), SessionId::new(2));
+
+    let scheduler = SendScheduler::default();
+    assert!(matches!(
+        scheduler.quick(congested, relay, compact_block.as_slice()),
+        Ok(true)
+    ));
+    assert!(matches!(
+        scheduler.quick(congested, relay, tx_hashes.as_slice()),
+        Ok(false)
+    ));
+
+    scheduler.observe::<()>(Some(congested), &Err(SendErrorKind::WouldBlock));
+    assert!(scheduler.is_congested(congested));
+    assert!(!scheduler.is_congested(other));
+    assert!(matches!(
+        scheduler.quick(congested, relay, compact_block.as_slice()),
+        Ok(true)
+    ));
+    assert!(matches!(
+        scheduler.quick(congested, relay, tx_hashes.as_slice()),
+        Err(SendErrorKind::WouldBlock)
+    ));
+    // the other peers are not affected
+    assert!(matches!(
+        scheduler.quick(other, relay, tx_hashes.as_slice()),
+        Ok(false)
+    ));
+
+    // the broadcasts skip the congested peer
+    assert!(matches!(
+        scheduler.broadcast(
+            TargetSession::Single(congested),
+            relay,
+            tx_hashes.as_slice()
+        ),
+        Err(SendErrorKind::WouldBlock)
+    ));
+    match scheduler.broadcast(TargetSession::All, relay, tx_hashes.as_slice()) {
+        Ok((false, TargetSession::Filter(mut filter))) => {
+            assert!(!filter(&congested));
+            assert!(filter(&other));
+        }
+        _ => panic!("the congested peer should be filtered"),
+    }
+    match scheduler.broadcast(
+        TargetSession::Multi(Box::new(vec![congested, other].into_iter())),
+        relay,
+        tx_hashes.as_slice(),
+    ) {
+        Ok((false, TargetSession::Multi(iter))) => {
+            assert_eq!(iter.collect::<Vec<_>>(), vec![other]);
+        }
+        _ => panic!("the congested peer should be filtered"),
+    }
+
+    scheduler.remove_session(congested);
+    assert!(!scheduler.is_congested(congested));
+}
@@ -0,0 +1,109 @@
+//! Bandwidth accounting per peer and per protocol.
+use crate::{ProtocolId, SessionId};
+use ckb_util::Mutex;
+use p2p::service::TargetSession;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bytes of the messages exchanged with a peer on a protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProtocolTraffic {
+    /// Total bytes of the messages sent to the peer.
+    pub bytes_sent: u64,
+    /// Total bytes of the messages received from the peer.
+    pub bytes_received: u64,
+}
+
+/// Message bytes sent and received, by session and protocol.
+///
+/// Only the message payloads are counted, the framing and encryption overhead is not included.
+#[derive(Default)]
+pub(crate) struct TrafficStats {
+    sessions: Mutex<HashMap<SessionId, HashMap<ProtocolId, ProtocolTraffic>>>,
+    // bytes sent and received since the node started, including the closed sessions
+    total_bytes: AtomicU64,
+}
+
+impl TrafficStats {
+    pub(crate) fn record_sent(&self, session_id: SessionId, proto_id: ProtocolId, len: usize) {
+        self.sessions
+            .lock()
+            .entry(session_id)
+            .or_default()
+            .entry(proto_id)
+            .or_default()
+            .bytes_sent += len as u64;
+        self.total_bytes.fetch_add(len as u64, Ordering::Relaxed);
+        metric_protocol_bytes("out", proto_id, len);
+    }
+
+    pub(crate) fn record_received(&self, session_id: SessionId, proto_id: ProtocolId, len: usize) {
+        self.sessions
+            .lock()
+            .entry(session_id)
+            .or_default()
+            .entry(proto_id)
+            .or_default()
+            .bytes_received += len as u64;
+        self.total_bytes.fetch_add(len as u64, Ordering::Relaxed);
+        metric_protocol_bytes("in", proto_id, len);
+    }
+
+    /// Resolves a broadcast target to the explicit sessions, so the message can be recorded
+    /// for each of them once it's sent.
+    ///
+    /// `sessions` are the connected sessions which have opened the protocol.
+    pub(crate) fn resolve_target(
+        target: TargetSession,
+        sessions: Vec<SessionId>,
+    ) -> Vec<SessionId> {
+        match target {
+            TargetSession::All => sessions,
+            TargetSession::Single(session_id) => vec![session_id],
+            TargetSession::Multi(iter) => iter.collect(),
+            TargetSession::Filter(mut filter) => {
+                sessions.into_iter().filter(|id| filter(id)).collect()
+            }
+        }
+    }
+
+    /// Records a message sent to multiple sessions.
+    pub(crate) fn record_broadcast(
+        &self,
+        sessions: &[SessionId],
+        proto_id: ProtocolId,
+        len: usize,
+    ) {
+        for session_id in sessions {
+            self.record_sent(*session_id, proto_id, len);
+        }
+    }
+
+    pub(crate) fn peer_traffic(
+        &self,
+        session_id: SessionId,
+    ) -> HashMap<ProtocolId, ProtocolTraffic> {
+        self.sessions
+            .lock()
+            .get(&session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn total_bytes(&self) -> u64 {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn remove_session(&self, session_id: SessionId) {
+        self.sessions.lock().remove(&session_id);
+    }
+}
+
+fn metric_protocol_bytes(direction: &str, proto_id: ProtocolId, len: usize) {
+    if let Some(metrics) = ckb_metrics::handle() {
+        metrics
+            .ckb_network_protocol_bytes
+            .with_label_values(&[direction, &proto_id.value().to_string()])
+            .inc_by(len as u64);
+    }
+}
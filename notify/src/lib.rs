@@ -17,7 +17,7 @@ use tokio::sync::{
 };
 use tokio::time::timeout;
 
-pub use ckb_types::core::service::PoolTransactionEntry;
+pub use ckb_types::core::service::{MigrationOutcome, PoolTransactionEntry};
 
 /// Asynchronous request sent to the service.
 pub struct Request<A, R> {
@@ -93,12 +93,18 @@ pub struct NotifyController {
     new_block_notifier: Sender<BlockView>,
     new_transaction_register: NotifyRegister<PoolTransactionEntry>,
     new_transaction_notifier: Sender<PoolTransactionEntry>,
+    gap_transaction_register: NotifyRegister<PoolTransactionEntry>,
+    gap_transaction_notifier: Sender<PoolTransactionEntry>,
     proposed_transaction_register: NotifyRegister<PoolTransactionEntry>,
     proposed_transaction_notifier: Sender<PoolTransactionEntry>,
+    committed_transaction_register: NotifyRegister<PoolTransactionEntry>,
+    committed_transaction_notifier: Sender<PoolTransactionEntry>,
     reject_transaction_register: NotifyRegister<(PoolTransactionEntry, Reject)>,
     reject_transaction_notifier: Sender<(PoolTransactionEntry, Reject)>,
     network_alert_register: NotifyRegister<Alert>,
     network_alert_notifier: Sender<Alert>,
+    migration_finished_register: NotifyRegister<MigrationOutcome>,
+    migration_finished_notifier: Sender<MigrationOutcome>,
     handle: Handle,
 }
 
@@ -108,9 +114,14 @@ pub struct NotifyService {
     new_block_subscribers: HashMap<String, Sender<BlockView>>,
     new_block_watchers: HashMap<String, watch::Sender<Byte32>>,
     new_transaction_subscribers: HashMap<String, Sender<PoolTransactionEntry>>,
+    gap_transaction_subscribers: HashMap<String, Sender<PoolTransactionEntry>>,
     proposed_transaction_subscribers: HashMap<String, Sender<PoolTransactionEntry>>,
+    committed_transaction_subscribers: HashMap<String, Sender<PoolTransactionEntry>>,
     reject_transaction_subscribers: HashMap<String, Sender<(PoolTransactionEntry, Reject)>>,
     network_alert_subscribers: HashMap<String, Sender<Alert>>,
+    migration_finished_subscribers: HashMap<String, Sender<MigrationOutcome>>,
+    // the background migrations finish only once, the late subscribers get the kept outcome
+    migration_outcome: Option<MigrationOutcome>,
     timeout: NotifyTimeout,
     handle: Handle,
 }
@@ -125,9 +136,13 @@ impl NotifyService {
             new_block_subscribers: HashMap::default(),
             new_block_watchers: HashMap::default(),
             new_transaction_subscribers: HashMap::default(),
+            gap_transaction_subscribers: HashMap::default(),
             proposed_transaction_subscribers: HashMap::default(),
+            committed_transaction_subscribers: HashMap::default(),
             reject_transaction_subscribers: HashMap::default(),
             network_alert_subscribers: HashMap::default(),
+            migration_finished_subscribers: HashMap::default(),
+            migration_outcome: None,
             timeout,
             handle,
         }
@@ -149,11 +164,21 @@ impl NotifyService {
         let (new_transaction_sender, mut new_transaction_receiver) =
             mpsc::channel(NOTIFY_CHANNEL_SIZE);
 
+        let (gap_transaction_register, mut gap_transaction_register_receiver) =
+            mpsc::channel(REGISTER_CHANNEL_SIZE);
+        let (gap_transaction_sender, mut gap_transaction_receiver) =
+            mpsc::channel(NOTIFY_CHANNEL_SIZE);
+
         let (proposed_transaction_register, mut proposed_transaction_register_receiver) =
             mpsc::channel(REGISTER_CHANNEL_SIZE);
         let (proposed_transaction_sender, mut proposed_transaction_receiver) =
             mpsc::channel(NOTIFY_CHANNEL_SIZE);
 
+        let (committed_transaction_register, mut committed_transaction_register_receiver) =
+            mpsc::channel(REGISTER_CHANNEL_SIZE);
+        let (committed_transaction_sender, mut committed_transaction_receiver) =
+            mpsc::channel(NOTIFY_CHANNEL_SIZE);
+
         let (reject_transaction_register, mut reject_transaction_register_receiver) =
             mpsc::channel(REGISTER_CHANNEL_SIZE);
         let (reject_transaction_sender, mut reject_transaction_receiver) =
@@ -163,6 +188,11 @@ impl NotifyService {
             mpsc::channel(REGISTER_CHANNEL_SIZE);
         let (network_alert_sender, mut network_alert_receiver) = mpsc::channel(NOTIFY_CHANNEL_SIZE);
 
+        let (migration_finished_register, mut migration_finished_register_receiver) =
+            mpsc::channel(REGISTER_CHANNEL_SIZE);
+        let (migration_finished_sender, mut migration_finished_receiver) =
+            mpsc::channel(SIGNAL_CHANNEL_SIZE);
+
         handle.spawn(async move {
             loop {
                 tokio::select! {
@@ -171,12 +201,18 @@ impl NotifyService {
                     Some(msg) = new_block_receiver.recv() => { self.handle_notify_new_block(msg) },
                     Some(msg) = new_transaction_register_receiver.recv() => { self.handle_register_new_transaction(msg) },
                     Some(msg) = new_transaction_receiver.recv() => { self.handle_notify_new_transaction(msg) },
+                    Some(msg) = gap_transaction_register_receiver.recv() => { self.handle_register_gap_transaction(msg) },
+                    Some(msg) = gap_transaction_receiver.recv() => { self.handle_notify_gap_transaction(msg) },
                     Some(msg) = proposed_transaction_register_receiver.recv() => { self.handle_register_proposed_transaction(msg) },
                     Some(msg) = proposed_transaction_receiver.recv() => { self.handle_notify_proposed_transaction(msg) },
+                    Some(msg) = committed_transaction_register_receiver.recv() => { self.handle_register_committed_transaction(msg) },
+                    Some(msg) = committed_transaction_receiver.recv() => { self.handle_notify_committed_transaction(msg) },
                     Some(msg) = reject_transaction_register_receiver.recv() => { self.handle_register_reject_transaction(msg) },
                     Some(msg) = reject_transaction_receiver.recv() => { self.handle_notify_reject_transaction(msg) },
                     Some(msg) = network_alert_register_receiver.recv() => { self.handle_register_network_alert(msg) },
                     Some(msg) = network_alert_receiver.recv() => { self.handle_notify_network_alert(msg) },
+                    Some(msg) = migration_finished_register_receiver.recv() => { self.handle_register_migration_finished(msg) },
+                    Some(msg) = migration_finished_receiver.recv() => { self.handle_notify_migration_finished(msg) },
                     _ = signal_receiver.cancelled() => {
                         info!("NotifyService received exit signal, exit now");
                         break;
@@ -192,12 +228,18 @@ impl NotifyService {
             new_block_notifier: new_block_sender,
             new_transaction_register,
             new_transaction_notifier: new_transaction_sender,
+            gap_transaction_register,
+            gap_transaction_notifier: gap_transaction_sender,
             proposed_transaction_register,
             proposed_transaction_notifier: proposed_transaction_sender,
+            committed_transaction_register,
+            committed_transaction_notifier: committed_transaction_sender,
             reject_transaction_register,
             reject_transaction_notifier: reject_transaction_sender,
             network_alert_register,
             network_alert_notifier: network_alert_sender,
+            migration_finished_register,
+            migration_finished_notifier: migration_finished_sender,
             handle,
         }
     }
@@ -294,6 +336,35 @@ impl NotifyService {
         }
     }
 
+    fn handle_register_gap_transaction(
+        &mut self,
+        msg: Request<String, Receiver<PoolTransactionEntry>>,
+    ) {
+        let Request {
+            responder,
+            arguments: name,
+        } = msg;
+        debug!("Register gap_transaction {:?}", name);
+        let (sender, receiver) = mpsc::channel(NOTIFY_CHANNEL_SIZE);
+        self.gap_transaction_subscribers.insert(name, sender);
+        let _ = responder.send(receiver);
+    }
+
+    fn handle_notify_gap_transaction(&self, tx_entry: PoolTransactionEntry) {
+        trace!("Gap tx event {:?}", tx_entry);
+        let tx_timeout = self.timeout.tx;
+        // notify all subscribers
+        for subscriber in self.gap_transaction_subscribers.values() {
+            let tx_entry = tx_entry.clone();
+            let subscriber = subscriber.clone();
+            self.handle.spawn(async move {
+                if let Err(e) = subscriber.send_timeout(tx_entry, tx_timeout).await {
+                    error!("Failed to notify gap transaction, error {}", e);
+                }
+            });
+        }
+    }
+
     fn handle_register_proposed_transaction(
         &mut self,
         msg: Request<String, Receiver<PoolTransactionEntry>>,
@@ -324,6 +395,35 @@ impl NotifyService {
         }
     }
 
+    fn handle_register_committed_transaction(
+        &mut self,
+        msg: Request<String, Receiver<PoolTransactionEntry>>,
+    ) {
+        let Request {
+            responder,
+            arguments: name,
+        } = msg;
+        debug!("Register committed_transaction {:?}", name);
+        let (sender, receiver) = mpsc::channel(NOTIFY_CHANNEL_SIZE);
+        self.committed_transaction_subscribers.insert(name, sender);
+        let _ = responder.send(receiver);
+    }
+
+    fn handle_notify_committed_transaction(&self, tx_entry: PoolTransactionEntry) {
+        trace!("Committed tx event {:?}", tx_entry);
+        let tx_timeout = self.timeout.tx;
+        // notify all subscribers
+        for subscriber in self.committed_transaction_subscribers.values() {
+            let tx_entry = tx_entry.clone();
+            let subscriber = subscriber.clone();
+            self.handle.spawn(async move {
+                if let Err(e) = subscriber.send_timeout(tx_entry, tx_timeout).await {
+                    error!("Failed to notify committed transaction, error {}", e);
+                }
+            });
+        }
+    }
+
     fn handle_register_reject_transaction(
         &mut self,
         msg: Request<String, Receiver<(PoolTransactionEntry, Reject)>>,
@@ -406,6 +506,39 @@ impl NotifyService {
             });
         }
     }
+
+    fn handle_register_migration_finished(
+        &mut self,
+        msg: Request<String, Receiver<MigrationOutcome>>,
+    ) {
+        let Request {
+            responder,
+            arguments: name,
+        } = msg;
+        debug!("Register migration_finished {:?}", name);
+        let (sender, receiver) = mpsc::channel(NOTIFY_CHANNEL_SIZE);
+        if let Some(outcome) = self.migration_outcome.clone() {
+            // the channel is empty, so it never fails for the capacity
+            let _ = sender.try_send(outcome);
+        }
+        self.migration_finished_subscribers.insert(name, sender);
+        let _ = responder.send(receiver);
+    }
+
+    fn handle_notify_migration_finished(&mut self, outcome: MigrationOutcome) {
+        info!("Migration finished event {:?}", outcome);
+        self.migration_outcome = Some(outcome.clone());
+        // notify all subscribers
+        for subscriber in self.migration_finished_subscribers.values() {
+            let outcome = outcome.clone();
+            let subscriber = subscriber.clone();
+            self.handle.spawn(async move {
+                if let Err(e) = subscriber.send(outcome).await {
+                    error!("Failed to notify migration finished, error: {}", e);
+                }
+            });
+        }
+    }
 }
 
 impl NotifyController {
@@ -453,6 +586,26 @@ impl NotifyController {
         });
     }
 
+    /// Subscribes the transactions which enter the gap set of the pool.
+    pub async fn subscribe_gap_transaction<S: ToString>(
+        &self,
+        name: S,
+    ) -> Receiver<PoolTransactionEntry> {
+        Request::call(&self.gap_transaction_register, name.to_string())
+            .await
+            .expect("Subscribe gap transaction should be OK")
+    }
+
+    /// Notifies that a transaction enters the gap set of the pool.
+    pub fn notify_gap_transaction(&self, tx_entry: PoolTransactionEntry) {
+        let gap_transaction_notifier = self.gap_transaction_notifier.clone();
+        self.handle.spawn(async move {
+            if let Err(e) = gap_transaction_notifier.send(tx_entry).await {
+                error!("notify_gap_transaction channel is closed: {}", e);
+            }
+        });
+    }
+
     /// TODO(doc): @quake
     pub async fn subscribe_proposed_transaction<S: ToString>(
         &self,
@@ -473,6 +626,26 @@ impl NotifyController {
         });
     }
 
+    /// Subscribes the in-pool transactions which are committed in a block.
+    pub async fn subscribe_committed_transaction<S: ToString>(
+        &self,
+        name: S,
+    ) -> Receiver<PoolTransactionEntry> {
+        Request::call(&self.committed_transaction_register, name.to_string())
+            .await
+            .expect("Subscribe committed transaction should be OK")
+    }
+
+    /// Notifies that an in-pool transaction is committed in a block.
+    pub fn notify_committed_transaction(&self, tx_entry: PoolTransactionEntry) {
+        let committed_transaction_notifier = self.committed_transaction_notifier.clone();
+        self.handle.spawn(async move {
+            if let Err(e) = committed_transaction_notifier.send(tx_entry).await {
+                error!("notify_committed_transaction channel is closed: {}", e);
+            }
+        });
+    }
+
     /// TODO(doc): @quake
     pub async fn subscribe_reject_transaction<S: ToString>(
         &self,
@@ -509,4 +682,26 @@ impl NotifyController {
             }
         });
     }
+
+    /// Subscribes the outcome of the background database migrations.
+    ///
+    /// The subscribers registered after the migrations finish get the outcome immediately.
+    pub async fn subscribe_migration_finished<S: ToString>(
+        &self,
+        name: S,
+    ) -> Receiver<MigrationOutcome> {
+        Request::call(&self.migration_finished_register, name.to_string())
+            .await
+            .expect("Subscribe migration finished should be OK")
+    }
+
+    /// Notifies that the background database migrations finish or fail.
+    pub fn notify_migration_finished(&self, outcome: MigrationOutcome) {
+        let migration_finished_notifier = self.migration_finished_notifier.clone();
+        self.handle.spawn(async move {
+            if let Err(e) = migration_finished_notifier.send(outcome).await {
+                error!("notify_migration_finished channel is closed: {}", e);
+            }
+        });
+    }
 }
@@ -29,6 +29,7 @@ pub use std::io::{Error, Result};
 
 use ckb_types::H256;
 use includedir::Files;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::fmt;
@@ -58,7 +59,7 @@ pub const DB_OPTIONS_FILE_NAME: &str = "default.db-options";
 
 /// Represents a resource, which is either bundled in the CKB binary or resident in the local file
 /// system.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Resource {
     /// A resource that bundled in the CKB binary.
@@ -1,5 +1,7 @@
 use ckb_error::{AnyError, Error as CKBError, ErrorKind, InternalError, InternalErrorKind};
+use ckb_jsonrpc_types::{PackageTransactionReject, PoolTransactionReject};
 use ckb_tx_pool::error::Reject;
+use ckb_types::{core::tx_pool::RejectCode, packed::Byte32};
 use jsonrpc_core::{Error, ErrorCode, Value};
 use schemars::JsonSchema;
 use std::fmt::{Debug, Display};
@@ -57,6 +59,12 @@ pub enum RPCError {
     ///
     /// Users have to edit the config file to fix the error.
     ConfigError = -7,
+    /// (-8): The method call is not completed within the timeout of its class, see the config
+    /// options `rpc.timeouts`.
+    ///
+    /// The error `data` is an object with the fields `method`, `class` and `timeout_ms`. The call
+    /// may still complete on the node, e.g., a transaction may still be submitted.
+    Timeout = -8,
     /// (-101): The CKB local node failed to broadcast a message to its peers.
     P2PFailedToBroadcast = -101,
     /// (-200): Internal database error.
@@ -77,6 +85,12 @@ pub enum RPCError {
     /// This is a fatal error usually caused by the underlying database used by CKB. Please back up
     /// the data directory and re-sync the chain from scratch.
     DatabaseIsCorrupt = -202,
+    /// (-203): The requested block data has been pruned.
+    ///
+    /// The node prunes the uncle proposals and the proposal ids of the old blocks when the config
+    /// option `store.uncles_and_proposals_retention_epochs` is set. The block headers are still
+    /// available.
+    BlockDataPruned = -203,
     /// (-301): Failed to resolve the referenced cells and headers used in the transaction, as inputs or
     /// dependencies.
     TransactionFailedToResolve = -301,
@@ -96,12 +110,19 @@ pub enum RPCError {
     /// ```text
     /// fee / (1000 * tx_serialization_size_in_block_in_bytes)
     /// ```
+    ///
+    /// The error `data` is the JSON string of a [`PoolTransactionReject`](#type-pooltransactionreject)
+    /// object, the fields `min_fee` and `min_fee_rate` are the thresholds.
     PoolRejectedTransactionByMinFeeRate = -1104,
     /// (-1105): The in-pool ancestors count must be less than or equal to the config option `tx_pool.max_ancestors_count`
     ///
     /// Pool rejects a large package of chained transactions to avoid certain kinds of DoS attacks.
     PoolRejectedTransactionByMaxAncestorsCountLimit = -1105,
     /// (-1106): The transaction is rejected because the pool has reached its limit.
+    ///
+    /// When the transaction is evicted for its fee rate, the error `data` is the JSON string of a
+    /// [`PoolTransactionReject`](#type-pooltransactionreject) object, the field `min_fee_rate` is
+    /// the fee rate floor of the pool which the transaction must exceed.
     PoolIsFull = -1106,
     /// (-1107): The transaction is already in the pool.
     PoolRejectedDuplicatedTransaction = -1107,
@@ -114,11 +135,38 @@ pub enum RPCError {
     /// (-1110): The transaction exceeded maximum size limit.
     PoolRejectedTransactionBySizeLimit = -1110,
     /// (-1111): The transaction is rejected for RBF checking.
+    ///
+    /// When the fee is too low to replace the conflicting transactions, the error `data` is the
+    /// JSON string of a [`PoolTransactionReject`](#type-pooltransactionreject) object, the field
+    /// `min_replace_fee` is the threshold.
     PoolRejectedRBF = -1111,
     /// (-1112): The transaction is rejected for ref cell consuming.
     PoolRejectedInvalidated = -1112,
+    /// (-1113): The fee of the transaction exceeds the config option `tx_pool.max_absolute_fee` or
+    /// `tx_pool.max_fee_rate`.
+    ///
+    /// Set the `send_transaction` parameter `allow_high_fees` to `true` to submit it anyway.
+    PoolRejectedAbsurdFee = -1113,
+    /// (-1114): A script group of the transaction consumes more cycles than the config option
+    /// `tx_pool.max_script_group_cycles`.
+    PoolRejectedScriptGroupCycles = -1114,
+    /// (-1115): A header dep of the transaction is less than the config option
+    /// `tx_pool.min_header_dep_depth` blocks below the tip.
+    ///
+    /// Such header deps are likely to be detached by a reorg, resubmit the transaction after
+    /// more blocks are mined.
+    PoolRejectedRecentHeaderDep = -1115,
+    /// (-1116): The transaction package is rejected, none of its transactions is accepted.
+    ///
+    /// The error data is an array of
+    /// [`PackageTransactionReject`](#type-packagetransactionreject) objects, one for each
+    /// invalid transaction in the package.
+    PoolRejectedPackage = -1116,
     /// (-1200): The indexer error.
     Indexer = -1200,
+    /// (-1201): The indexer hasn't processed the block in the parameter `wait_for` before the
+    /// timeout.
+    IndexerNotReady = -1201,
 }
 
 impl RPCError {
@@ -165,27 +213,70 @@ impl RPCError {
         }
     }
 
-    /// Creates an RPC error from the reason that a transaction is rejected to be submitted.
-    pub fn from_submit_transaction_reject(reject: &Reject) -> Error {
-        let code = match reject {
-            Reject::LowFeeRate(_, _, _) => RPCError::PoolRejectedTransactionByMinFeeRate,
-            Reject::ExceededMaximumAncestorsCount => {
+    /// Returns the RPC error of the reject code, whose code is `RejectCode::rpc_error_code`.
+    pub fn from_reject_code(code: RejectCode) -> RPCError {
+        match code {
+            RejectCode::LowFeeRate => RPCError::PoolRejectedTransactionByMinFeeRate,
+            RejectCode::ExceededMaximumAncestorsCount => {
                 RPCError::PoolRejectedTransactionByMaxAncestorsCountLimit
             }
-            Reject::Full(_) => RPCError::PoolIsFull,
-            Reject::Duplicated(_) => RPCError::PoolRejectedDuplicatedTransaction,
-            Reject::Malformed(_, _) => RPCError::PoolRejectedMalformedTransaction,
-            Reject::DeclaredWrongCycles(..) => RPCError::PoolRejectedMalformedTransaction,
-            Reject::Resolve(_) => RPCError::TransactionFailedToResolve,
-            Reject::Verification(_) => RPCError::TransactionFailedToVerify,
-            Reject::RBFRejected(_) => RPCError::PoolRejectedRBF,
-            Reject::Invalidated(_) => RPCError::PoolRejectedInvalidated,
-            Reject::ExceededTransactionSizeLimit(_, _) => {
+            RejectCode::ExceededTransactionSizeLimit => {
                 RPCError::PoolRejectedTransactionBySizeLimit
             }
-            Reject::Expiry(_) => RPCError::TransactionExpired,
+            RejectCode::Full => RPCError::PoolIsFull,
+            RejectCode::Duplicated => RPCError::PoolRejectedDuplicatedTransaction,
+            RejectCode::Malformed | RejectCode::DeclaredWrongCycles => {
+                RPCError::PoolRejectedMalformedTransaction
+            }
+            RejectCode::Resolve => RPCError::TransactionFailedToResolve,
+            RejectCode::Verification => RPCError::TransactionFailedToVerify,
+            RejectCode::Expiry => RPCError::TransactionExpired,
+            RejectCode::RBFRejected => RPCError::PoolRejectedRBF,
+            RejectCode::Invalidated => RPCError::PoolRejectedInvalidated,
+            RejectCode::AbsurdFee => RPCError::PoolRejectedAbsurdFee,
+            RejectCode::ExceededMaximumScriptGroupCycles => RPCError::PoolRejectedScriptGroupCycles,
+            RejectCode::HeaderDepTooRecent => RPCError::PoolRejectedRecentHeaderDep,
+        }
+    }
+
+    /// Creates an RPC error from the reason that a transaction is rejected to be submitted.
+    pub fn from_submit_transaction_reject(reject: &Reject) -> Error {
+        let code = RPCError::from_reject_code(reject.code());
+        match reject {
+            // the fee thresholds are in the structured data, so the wallets can bump the fee
+            // without parsing the message, the data is still a string as the other errors
+            Reject::LowFeeRate(..)
+            | Reject::FullFeeRateFloor(..)
+            | Reject::RBFRejectedByFee(..) => {
+                let data = serde_json::to_string(&PoolTransactionReject::from(reject.clone()))
+                    .expect("serialize pool transaction reject");
+                Error {
+                    code: ErrorCode::ServerError(code as i64),
+                    message: format!("{code:?}: {reject}"),
+                    data: Some(Value::String(data)),
+                }
+            }
+            _ => RPCError::custom_with_error(code, reject),
+        }
+    }
+
+    /// Creates an RPC error from the rejected transactions of a package.
+    pub fn from_submit_package_rejects(rejects: Vec<(Byte32, Reject)>) -> Error {
+        let code = RPCError::PoolRejectedPackage;
+        let message = match rejects.first() {
+            Some((tx_hash, reject)) => format!(
+                "{code:?}: {} of the transactions are rejected, the first is {tx_hash}: {reject}",
+                rejects.len()
+            ),
+            None => format!("{code:?}: the package is rejected"),
         };
-        RPCError::custom_with_error(code, reject)
+        let rejects: Vec<PackageTransactionReject> = rejects.into_iter().map(Into::into).collect();
+        let data = serde_json::to_value(rejects).expect("serialize package transaction rejects");
+        Error {
+            code: ErrorCode::ServerError(code as i64),
+            message,
+            data: Some(data),
+        }
     }
 
     /// Creates an CKB error from `CKBError`.
@@ -249,6 +340,17 @@ impl RPCError {
         )
     }
 
+    /// RPC error which indicates that the tx-pool is disabled.
+    ///
+    /// The tx-pool does not run when `tx_pool.enable` is `false` in the config.
+    pub fn tx_pool_is_disabled() -> Error {
+        Self::custom(
+            RPCError::ConfigError,
+            "The tx-pool is disabled on this node. \
+            Please set `enable = true` in the `[tx_pool]` section of ckb.toml and restart the ckb node to enable it.",
+        )
+    }
+
     /// RPC error which indicates that the method is deprecated.
     ///
     /// Deprecated methods are disabled by default unless they are enabled via the config options
@@ -3,14 +3,19 @@ use crate::util::FeeRateCollector;
 use async_trait::async_trait;
 use ckb_jsonrpc_types::{
     BlockEconomicState, BlockFilter, BlockNumber, BlockResponse, BlockView, CellWithStatus,
-    Consensus, EpochNumber, EpochView, EstimateCycles, FeeRateStatistics, HeaderView, OutPoint,
-    ResponseFormat, ResponseFormatInnerType, Timestamp, Transaction, TransactionAndWitnessProof,
-    TransactionProof, TransactionWithStatusResponse, Uint32, Uint64,
+    Consensus, EconomicStateSummary, Either, EpochNumber, EpochStats, EpochView, EstimateCycles,
+    FeeRateStatistics, HeaderView, OutPoint, Projected, ResponseFormat, ResponseFormatInnerType,
+    ScriptGroupCycles, ScriptGroupDryRun, ScriptGroupType as JsonScriptGroupType, Timestamp,
+    Transaction, TransactionAndWitnessProof, TransactionProof, TransactionWithStatusResponse,
+    Uint32, Uint64, VmCycles,
 };
 use ckb_logger::error;
 use ckb_reward_calculator::RewardCalculator;
 use ckb_shared::{shared::Shared, Snapshot};
-use ckb_store::{data_loader_wrapper::AsDataLoader, ChainStore};
+use ckb_store::{
+    data_loader_wrapper::{AsDataLoader, DataLoaderWrapper},
+    ChainStore,
+};
 use ckb_traits::HeaderFieldsProvider;
 use ckb_types::core::tx_pool::TransactionWithStatus;
 use ckb_types::{
@@ -24,8 +29,8 @@ use ckb_types::{
     utilities::{merkle_root, MerkleProof, CBMT},
     H256,
 };
-use ckb_verification::ScriptVerifier;
 use ckb_verification::TxVerifyEnv;
+use ckb_verification::{ScriptGroupType, ScriptVerifier};
 use jsonrpc_core::Result;
 use jsonrpc_utils::rpc;
 use std::collections::HashSet;
@@ -53,6 +58,17 @@ use std::sync::Arc;
 /// * it is found as an output in any transaction in the [canonical chain](#canonical-chain),
 /// and
 /// * it is not found as an input in any transaction in the canonical chain.
+///
+/// ## Bulk Export
+///
+/// The HTTP listener also serves `GET /export/blocks?from=<number>&to=<number>&format=json` when
+/// this module is enabled. It streams the blocks in the canonical chain in the range as
+/// newline-delimited JSON, each line is the same as the result of `get_block` with the verbosity 2,
+/// or the verbosity 0 when `format` is `hex`. Both bounds are inclusive and the range is capped by
+/// the tip. The node reads ahead only a few blocks, so a slow client slows down the export instead
+/// of growing the memory. At most 4 exports run at the same time, the others are refused with
+/// the HTTP status 429. If the chain is reorganized in the range during the export, the response
+/// is aborted before it completes, and the client should export the range again.
 #[rpc(openrpc)]
 #[async_trait]
 pub trait ChainRpc {
@@ -63,6 +79,7 @@ pub trait ChainRpc {
     /// * `block_hash` - the block hash.
     /// * `verbosity` - result format which allows 0 and 2. (**Optional**, the default is 2.)
     /// * `with_cycles` - whether the return cycles of block transactions. (**Optional**, default false.)
+    /// * `fields` - the fields to return, each is a top-level field or a dotted path like `header.number`. (**Optional**, the default is all the fields.)
     ///
     /// ## Returns
     ///
@@ -81,6 +98,15 @@ pub trait ChainRpc {
     /// When `verbosity` is 0, it returns a 0x-prefixed hex string as the `result`. The string
     /// encodes the block serialized by molecule using schema `table Block`.
     ///
+    /// When `fields` is specified, the `result` only has the listed fields, e.g., `["header"]`
+    /// returns only the header, and `["header.number", "transactions.hash"]` returns the block
+    /// number and the transaction hashes. The fields can't be specified when `verbosity` is 0
+    /// and `with_cycles` is false, since the result is not a JSON object.
+    ///
+    /// ## Errors
+    ///
+    /// * [`BlockDataPruned (-203)`](../enum.RPCError.html#variant.BlockDataPruned) - The uncle proposals and the proposal ids of the block are pruned.
+    ///
     /// ## Examples
     ///
     /// Request
@@ -185,7 +211,8 @@ pub trait ChainRpc {
         block_hash: H256,
         verbosity: Option<Uint32>,
         with_cycles: Option<bool>,
-    ) -> Result<Option<BlockResponse>>;
+        fields: Option<Vec<String>>,
+    ) -> Result<Option<Projected<BlockResponse>>>;
 
     /// Returns the block in the [canonical chain](#canonical-chain) with the specific block number.
     ///
@@ -194,6 +221,7 @@ pub trait ChainRpc {
     /// * `block_number` - the block number.
     /// * `verbosity` - result format which allows 0 and 2. (**Optional**, the default is 2.)
     /// * `with_cycles` - whether the return cycles of block transactions. (**Optional**, default false.)
+    /// * `fields` - the fields to return, each is a top-level field or a dotted path like `header.number`. (**Optional**, the default is all the fields.)
     ///
     /// ## Returns
     ///
@@ -210,10 +238,16 @@ pub trait ChainRpc {
     /// When `verbosity` is 0, it returns a 0x-prefixed hex string as the `result`. The string
     /// encodes the block serialized by molecule using schema `table Block`.
     ///
+    /// When `fields` is specified, the `result` only has the listed fields, e.g., `["header"]`
+    /// returns only the header, and `["header.number", "transactions.hash"]` returns the block
+    /// number and the transaction hashes. The fields can't be specified when `verbosity` is 0
+    /// and `with_cycles` is false, since the result is not a JSON object.
+    ///
     /// ## Errors
     ///
     /// * [`ChainIndexIsInconsistent (-201)`](../enum.RPCError.html#variant.ChainIndexIsInconsistent) - The index is inconsistent. It says a block hash is in the main chain, but cannot read it from the database.
     /// * [`DatabaseIsCorrupt (-202)`](../enum.RPCError.html#variant.DatabaseIsCorrupt) - The data read from database is dirty. Please report it as a bug.
+    /// * [`BlockDataPruned (-203)`](../enum.RPCError.html#variant.BlockDataPruned) - The uncle proposals and the proposal ids of the block are pruned.
     ///
     /// ## Examples
     ///
@@ -319,7 +353,8 @@ pub trait ChainRpc {
         block_number: BlockNumber,
         verbosity: Option<Uint32>,
         with_cycles: Option<bool>,
-    ) -> Result<Option<BlockResponse>>;
+        fields: Option<Vec<String>>,
+    ) -> Result<Option<Projected<BlockResponse>>>;
 
     /// Returns the information about a block header by hash.
     ///
@@ -544,6 +579,8 @@ pub trait ChainRpc {
     /// * `tx_hash` - Hash of a transaction
     /// * `verbosity` - result format which allows 0, 1 and 2. (**Optional**, the defaults to 2.)
     /// * `only_committed` - whether to query committed transaction only. (**Optional**, if not set, it will query all status of transactions.)
+    /// The node with the tx-pool disabled only queries the committed transactions.
+    /// * `fields` - the fields to return, each is a top-level field or a dotted path like `header.number`. (**Optional**, the default is all the fields.)
     ///
     /// ## Returns
     ///
@@ -555,6 +592,10 @@ pub trait ChainRpc {
     /// When verbosity is 2: if tx_status.status is pending, proposed, or committed,
     /// the RPC returns the transaction content as field transaction, otherwise the field is null.
     ///
+    /// When `fields` is specified, the result only has the listed fields, e.g., `["tx_status"]`
+    /// returns only the status, and `["transaction.outputs", "tx_status.status"]` returns the
+    /// outputs of the transaction and its status.
+    ///
     /// ## Examples
     ///
     /// Request
@@ -626,7 +667,8 @@ pub trait ChainRpc {
     ///       "block_number": null,
     ///       "status": "pending",
     ///       "tx_index": null,
-    ///       "reason": null
+    ///       "reason": null,
+    ///       "reason_code": null
     ///     }
     ///   }
     /// }
@@ -647,7 +689,8 @@ pub trait ChainRpc {
     ///       "block_number": null,
     ///       "status": "pending",
     ///       "tx_index": null,
-    ///       "reason": null
+    ///       "reason": null,
+    ///       "reason_code": null
     ///     }
     ///   }
     /// }
@@ -659,7 +702,77 @@ pub trait ChainRpc {
         tx_hash: H256,
         verbosity: Option<Uint32>,
         only_committed: Option<bool>,
-    ) -> Result<TransactionWithStatusResponse>;
+        fields: Option<Vec<String>>,
+    ) -> Result<Projected<TransactionWithStatusResponse>>;
+
+    /// Returns the information about a batch of transactions requested by transaction hashes.
+    ///
+    /// It is the batched version of [`get_transaction`](#tymethod.get_transaction), all the
+    /// transactions are queried against the same chain snapshot.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hashes` - Hashes of the transactions, at most 100 hashes are allowed in one call.
+    /// * `verbosity` - result format which allows 0, 1 and 2. (**Optional**, the defaults to 2.)
+    /// * `only_committed` - whether to query committed transactions only. (**Optional**, if not set, it will query all status of transactions.)
+    ///
+    /// ## Returns
+    ///
+    /// An array of results in the same order as `tx_hashes`. Each element is the same as the
+    /// result of [`get_transaction`](#tymethod.get_transaction) with the same `verbosity` and
+    /// `only_committed`. The status of an unknown transaction is `unknown`.
+    ///
+    /// For committed transactions, the field `fee` is the fee paid by the transaction, and it is null
+    /// for the cellbase transaction.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_transactions_by_hashes",
+    ///   "params": [
+    ///     ["0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"],
+    ///     "0x1"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "transaction": null,
+    ///       "cycles": "0x219",
+    ///       "time_added_to_pool": null,
+    ///       "fee": null,
+    ///       "min_replace_fee": null,
+    ///       "tx_status": {
+    ///         "block_hash": null,
+    ///         "block_number": null,
+    ///         "status": "pending",
+    ///         "tx_index": null,
+    ///         "reason": null,
+    ///         "reason_code": null
+    ///       }
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_transactions_by_hashes")]
+    fn get_transactions_by_hashes(
+        &self,
+        tx_hashes: Vec<H256>,
+        verbosity: Option<Uint32>,
+        only_committed: Option<bool>,
+    ) -> Result<Vec<TransactionWithStatusResponse>>;
 
     /// Returns the hash of a block in the [canonical chain](#canonical-chain) with the specified
     /// `block_number`.
@@ -788,6 +901,13 @@ pub trait ChainRpc {
     /// * `with_data` - Whether the RPC should return cell data. Cell data can be huge, if the client
     /// does not need the data, it should set this to `false` to save bandwidth.
     /// * `include_tx_pool` - Whether the RPC check live cell in TxPool, default is false.
+    /// * `include_pool_status` - Whether the RPC also returns `pool_status`, the hashes of the
+    /// pool transactions which create and spend the cell, default is false. The cell status and
+    /// the pool status are read from the same pool state, which helps wallets to select the
+    /// cells not being spent yet.
+    ///
+    /// The RPC returns the error `ConfigError` if `include_tx_pool` or `include_pool_status` is
+    /// set and the tx-pool is disabled by `tx_pool.enable` on the node.
     ///
     /// ## Examples
     ///
@@ -840,6 +960,7 @@ pub trait ChainRpc {
         out_point: OutPoint,
         with_data: bool,
         include_tx_pool: Option<bool>,
+        include_pool_status: Option<bool>,
     ) -> Result<CellWithStatus>;
 
     /// Returns the highest block number in the [canonical chain](#canonical-chain).
@@ -954,6 +1075,55 @@ pub trait ChainRpc {
     #[rpc(name = "get_epoch_by_number")]
     fn get_epoch_by_number(&self, epoch_number: EpochNumber) -> Result<Option<EpochView>>;
 
+    /// Returns the aggregated statistics of the epoch in the [canonical chain](#canonical-chain)
+    /// with the specific epoch number.
+    ///
+    /// The statistics are maintained when blocks are attached to and detached from the canonical
+    /// chain, so they don't need to be recomputed from the blocks. For the current epoch, they
+    /// cover the blocks committed so far.
+    ///
+    /// ## Params
+    ///
+    /// * `epoch_number` - Epoch number
+    ///
+    /// ## Returns
+    ///
+    /// The RPC returns null when `epoch_number` is greater than the current epoch number.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_epoch_stats",
+    ///   "params": [
+    ///     "0x0"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "average_block_interval": "0x1d4c",
+    ///     "block_count": "0x3e8",
+    ///     "number": "0x0",
+    ///     "total_cycles": "0x2540be400",
+    ///     "total_fees": "0x174876e800",
+    ///     "tx_count": "0x1f4"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_epoch_stats")]
+    fn get_epoch_stats(&self, epoch_number: EpochNumber) -> Result<Option<EpochStats>>;
+
     /// Returns increased issuance, miner reward, and the total transaction fee of a block.
     ///
     /// This RPC returns null if the block is not in the [canonical chain](#canonical-chain).
@@ -978,6 +1148,10 @@ pub trait ChainRpc {
     /// its rewards have been finalized, return the block rewards analysis for this block. A special
     /// case is that the return value for genesis block is null.
     ///
+    /// ## Errors
+    ///
+    /// * [`BlockDataPruned (-203)`](../enum.RPCError.html#variant.BlockDataPruned) - The proposals needed to calculate the rewards are pruned.
+    ///
     /// ## Examples
     ///
     /// Request
@@ -1018,6 +1192,81 @@ pub trait ChainRpc {
     #[rpc(name = "get_block_economic_state")]
     fn get_block_economic_state(&self, block_hash: H256) -> Result<Option<BlockEconomicState>>;
 
+    /// Returns the total issuance, miner rewards, and transaction fees of the blocks in a range.
+    ///
+    /// It sums up what [`get_block_economic_state`](#tymethod.get_block_economic_state) returns
+    /// for each block in the range, so the dashboards don't have to query the blocks one by one.
+    ///
+    /// The genesis block and the blocks whose rewards are not finalized yet are skipped.
+    ///
+    /// ## Params
+    ///
+    /// * `start_number` - The number of the first block in the range.
+    /// * `end_number` - The number of the last block in the range, inclusive.
+    /// * `aggregate_by_epoch` - Whether to sum up the blocks by epoch, defaults to false.
+    ///
+    /// ## Returns
+    ///
+    /// A summary of all the blocks in the range, or a summary for each epoch in ascending order
+    /// if `aggregate_by_epoch` is true. It is empty when no block in the range is finalized.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - `start_number` is greater than `end_number`, or the range contains more than 10000 blocks.
+    /// * [`BlockDataPruned (-203)`](../enum.RPCError.html#variant.BlockDataPruned) - The proposals needed to calculate the rewards are pruned.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_block_economic_state_range",
+    ///   "params": [
+    ///     "0x1",
+    ///     "0x2",
+    ///     true
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "block_count": "0x2",
+    ///       "end_number": "0x2",
+    ///       "epoch": "0x0",
+    ///       "issuance": {
+    ///         "primary": "0x319d245794",
+    ///         "secondary": "0xfe05d8caa"
+    ///       },
+    ///       "miner_reward": {
+    ///         "committed": "0x0",
+    ///         "primary": "0x319d245794",
+    ///         "proposal": "0x0",
+    ///         "secondary": "0x2f726c0a"
+    ///       },
+    ///       "start_number": "0x1",
+    ///       "txs_fee": "0x0"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_block_economic_state_range")]
+    fn get_block_economic_state_range(
+        &self,
+        start_number: BlockNumber,
+        end_number: BlockNumber,
+        aggregate_by_epoch: Option<bool>,
+    ) -> Result<Vec<EconomicStateSummary>>;
+
     /// Returns a Merkle proof that transactions are included in a block.
     ///
     /// ## Params
@@ -1455,7 +1704,9 @@ pub trait ChainRpc {
     /// This method will not check the transaction validity, but only run the lock script
     /// and type script and then return the execution cycles.
     ///
-    /// It is used to estimate how many cycles the scripts consume.
+    /// It is used to estimate how many cycles the scripts consume. The cycles are also broken down
+    /// by the script groups, and by the VMs spawned in each group, which form a tree by the parent
+    /// ids, so the authors of the scripts using spawn can tell which VM consumes the cycles.
     ///
     /// ## Errors
     ///
@@ -1522,7 +1773,21 @@ pub trait ChainRpc {
     ///   "id": 42,
     ///   "jsonrpc": "2.0",
     ///   "result": {
-    ///     "cycles": "0x219"
+    ///     "cycles": "0x219",
+    ///     "script_groups": [
+    ///       {
+    ///         "cycles": "0x219",
+    ///         "group_type": "lock",
+    ///         "script_hash": "0x4ceaa32f692948413e213ce6f3a83337145bde6e11fd8cb94377ce2637dcc412",
+    ///         "vms": [
+    ///           {
+    ///             "cycles": "0x219",
+    ///             "id": "0x0",
+    ///             "parent_id": null
+    ///           }
+    ///         ]
+    ///       }
+    ///     ]
     ///   }
     /// }
     /// ```
@@ -1626,6 +1891,27 @@ pub(crate) struct ChainRpcImpl {
 const DEFAULT_BLOCK_VERBOSITY_LEVEL: u32 = 2;
 const DEFAULT_HEADER_VERBOSITY_LEVEL: u32 = 1;
 const DEFAULT_GET_TRANSACTION_VERBOSITY_LEVEL: u32 = 2;
+const MAX_GET_TRANSACTIONS_BATCH_SIZE: usize = 100;
+const MAX_ECONOMIC_STATE_RANGE: u64 = 10_000;
+
+// The hex block is not a JSON object to pick the fields from.
+fn project_block(
+    block: BlockResponse,
+    fields: Option<Vec<String>>,
+) -> Result<Projected<BlockResponse>> {
+    let is_hex = matches!(
+        &block,
+        BlockResponse::Regular(ResponseFormat {
+            inner: Either::Right(_)
+        })
+    );
+    if fields.is_some() && is_hex {
+        return Err(RPCError::invalid_params(
+            "fields only apply to the responses in the JSON object format",
+        ));
+    }
+    Projected::project(block, fields).map_err(RPCError::invalid_params)
+}
 
 #[async_trait]
 impl ChainRpc for ChainRpcImpl {
@@ -1634,11 +1920,14 @@ impl ChainRpc for ChainRpcImpl {
         block_hash: H256,
         verbosity: Option<Uint32>,
         with_cycles: Option<bool>,
-    ) -> Result<Option<BlockResponse>> {
+        fields: Option<Vec<String>>,
+    ) -> Result<Option<Projected<BlockResponse>>> {
         let snapshot = self.shared.snapshot();
         let block_hash = block_hash.pack();
 
-        self.get_block_by_hash(&snapshot, &block_hash, verbosity, with_cycles)
+        self.get_block_by_hash(&snapshot, &block_hash, verbosity, with_cycles)?
+            .map(|block| project_block(block, fields))
+            .transpose()
     }
 
     fn get_block_by_number(
@@ -1646,7 +1935,8 @@ impl ChainRpc for ChainRpcImpl {
         block_number: BlockNumber,
         verbosity: Option<Uint32>,
         with_cycles: Option<bool>,
-    ) -> Result<Option<BlockResponse>> {
+        fields: Option<Vec<String>>,
+    ) -> Result<Option<Projected<BlockResponse>>> {
         let snapshot = self.shared.snapshot();
         let block_hash = match snapshot.get_block_hash(block_number.into()) {
             Some(block_hash) => block_hash,
@@ -1664,7 +1954,7 @@ impl ChainRpc for ChainRpcImpl {
                 message,
             ));
         }
-        ret
+        ret?.map(|block| project_block(block, fields)).transpose()
     }
 
     fn get_header(
@@ -1751,32 +2041,56 @@ impl ChainRpc for ChainRpcImpl {
         tx_hash: H256,
         verbosity: Option<Uint32>,
         only_committed: Option<bool>,
-    ) -> Result<TransactionWithStatusResponse> {
-        let tx_hash = tx_hash.pack();
+        fields: Option<Vec<String>>,
+    ) -> Result<Projected<TransactionWithStatusResponse>> {
         let verbosity = verbosity
             .map(|v| v.value())
             .unwrap_or(DEFAULT_GET_TRANSACTION_VERBOSITY_LEVEL);
-
         let only_committed: bool = only_committed.unwrap_or(false);
 
-        if verbosity == 0 {
-            // when verbosity=0, it's response value is as same as verbosity=2, but it
-            // return a 0x-prefixed hex encoded molecule packed::Transaction` on `transaction` field
-            self.get_transaction_verbosity2(tx_hash, only_committed)
-                .map(|tws| TransactionWithStatusResponse::from(tws, ResponseFormatInnerType::Hex))
-        } else if verbosity == 1 {
-            // The RPC does not return the transaction content and the field transaction must be null.
-            self.get_transaction_verbosity1(tx_hash, only_committed)
-                .map(|tws| TransactionWithStatusResponse::from(tws, ResponseFormatInnerType::Json))
-        } else if verbosity == 2 {
-            // if tx_status.status is pending, proposed, or committed,
-            // the RPC returns the transaction content as field transaction,
-            // otherwise the field is null.
-            self.get_transaction_verbosity2(tx_hash, only_committed)
-                .map(|tws| TransactionWithStatusResponse::from(tws, ResponseFormatInnerType::Json))
-        } else {
-            Err(RPCError::invalid_params("invalid verbosity level"))
+        let snapshot = self.shared.snapshot();
+        let tx = self.get_transaction_with_verbosity(
+            &snapshot,
+            tx_hash.pack(),
+            verbosity,
+            only_committed,
+            false,
+        )?;
+        Projected::project(tx, fields).map_err(RPCError::invalid_params)
+    }
+
+    fn get_transactions_by_hashes(
+        &self,
+        tx_hashes: Vec<H256>,
+        verbosity: Option<Uint32>,
+        only_committed: Option<bool>,
+    ) -> Result<Vec<TransactionWithStatusResponse>> {
+        if tx_hashes.len() > MAX_GET_TRANSACTIONS_BATCH_SIZE {
+            return Err(RPCError::invalid_params(format!(
+                "the number of tx_hashes {} exceeds the limit {}",
+                tx_hashes.len(),
+                MAX_GET_TRANSACTIONS_BATCH_SIZE
+            )));
         }
+        let verbosity = verbosity
+            .map(|v| v.value())
+            .unwrap_or(DEFAULT_GET_TRANSACTION_VERBOSITY_LEVEL);
+        let only_committed: bool = only_committed.unwrap_or(false);
+
+        // use the same snapshot for all the transactions, so the results are consistent
+        let snapshot = self.shared.snapshot();
+        tx_hashes
+            .into_iter()
+            .map(|tx_hash| {
+                self.get_transaction_with_verbosity(
+                    &snapshot,
+                    tx_hash.pack(),
+                    verbosity,
+                    only_committed,
+                    true,
+                )
+            })
+            .collect()
     }
 
     fn get_block_hash(&self, block_number: BlockNumber) -> Result<Option<H256>> {
@@ -1821,13 +2135,84 @@ impl ChainRpc for ChainRpcImpl {
             }))
     }
 
+    fn get_epoch_stats(&self, epoch_number: EpochNumber) -> Result<Option<EpochStats>> {
+        let snapshot = self.shared.snapshot();
+        let epoch_number: core::EpochNumber = epoch_number.into();
+        let stats = match snapshot.get_epoch_stats(epoch_number) {
+            Some(stats) => stats,
+            None => return Ok(None),
+        };
+        let epoch_ext = match snapshot
+            .get_epoch_index(epoch_number)
+            .and_then(|hash| snapshot.get_epoch_ext(&hash))
+        {
+            Some(epoch_ext) => epoch_ext,
+            None => return Ok(None),
+        };
+
+        // The interval of the first block is measured from its parent, which is in the previous
+        // epoch. The genesis block has no parent, so it's excluded.
+        let start_number = epoch_ext.start_number();
+        let last_number = start_number + stats.block_count - 1;
+        let (from_number, intervals) = if start_number == 0 {
+            (0, stats.block_count - 1)
+        } else {
+            (start_number - 1, stats.block_count)
+        };
+        let timestamp = |number| {
+            snapshot
+                .get_block_hash(number)
+                .and_then(|hash| snapshot.get_block_header(&hash))
+                .map(|header| header.timestamp())
+                .ok_or_else(|| {
+                    RPCError::custom(
+                        RPCError::ChainIndexIsInconsistent,
+                        format!("the header of block {number} is not found"),
+                    )
+                })
+        };
+        let average_block_interval = if intervals == 0 {
+            0
+        } else {
+            timestamp(last_number)?.saturating_sub(timestamp(from_number)?) / intervals
+        };
+
+        Ok(Some(EpochStats {
+            number: epoch_number.into(),
+            block_count: stats.block_count.into(),
+            tx_count: stats.tx_count.map(Into::into),
+            total_fees: stats.total_fees.into(),
+            total_cycles: stats.total_cycles.into(),
+            average_block_interval: average_block_interval.into(),
+        }))
+    }
+
     fn get_live_cell(
         &self,
         out_point: OutPoint,
         with_data: bool,
         include_tx_pool: Option<bool>,
+        include_pool_status: Option<bool>,
     ) -> Result<CellWithStatus> {
-        let cell_status: CellStatus = if include_tx_pool.unwrap_or_default() {
+        let include_pool_status = include_pool_status.unwrap_or_default();
+        let include_tx_pool = include_tx_pool.unwrap_or_default();
+        if (include_pool_status || include_tx_pool)
+            && !self.shared.tx_pool_controller().is_enabled()
+        {
+            return Err(RPCError::tx_pool_is_disabled());
+        }
+        if include_pool_status {
+            let (cell_status, pool_status) = self
+                .shared
+                .tx_pool_controller()
+                .get_live_cell_with_pool_status(out_point.into(), with_data, include_tx_pool)
+                .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+            let mut cell = CellWithStatus::from(cell_status);
+            cell.pool_status = Some(pool_status.into());
+            return Ok(cell);
+        }
+
+        let cell_status: CellStatus = if include_tx_pool {
             self.shared
                 .tx_pool_controller()
                 .get_live_cell(out_point.into(), with_data)
@@ -1847,70 +2232,66 @@ impl ChainRpc for ChainRpcImpl {
 
     fn get_block_economic_state(&self, block_hash: H256) -> Result<Option<BlockEconomicState>> {
         let snapshot = self.shared.snapshot();
+        Ok(self
+            .block_economic_state(&snapshot, block_hash.pack())?
+            .map(Into::into))
+    }
 
-        let block_number = if let Some(block_number) = snapshot.get_block_number(&block_hash.pack())
-        {
-            block_number
-        } else {
-            return Ok(None);
-        };
-
-        let delay_length = snapshot.consensus().finalization_delay_length();
-        let finalized_at_number = block_number + delay_length;
-        if block_number == 0 || snapshot.tip_number() < finalized_at_number {
-            return Ok(None);
+    fn get_block_economic_state_range(
+        &self,
+        start_number: BlockNumber,
+        end_number: BlockNumber,
+        aggregate_by_epoch: Option<bool>,
+    ) -> Result<Vec<EconomicStateSummary>> {
+        let start_number: core::BlockNumber = start_number.into();
+        let end_number: core::BlockNumber = end_number.into();
+        if start_number > end_number {
+            return Err(RPCError::invalid_params(format!(
+                "start_number {start_number} is greater than end_number {end_number}"
+            )));
         }
+        if end_number - start_number >= MAX_ECONOMIC_STATE_RANGE {
+            return Err(RPCError::invalid_params(format!(
+                "the range [{start_number}, {end_number}] exceeds the limit {MAX_ECONOMIC_STATE_RANGE} blocks"
+            )));
+        }
+        let aggregate_by_epoch = aggregate_by_epoch.unwrap_or(false);
 
-        let block_hash = block_hash.pack();
-        let finalized_at = if let Some(block_hash) = snapshot.get_block_hash(finalized_at_number) {
-            block_hash
-        } else {
-            return Ok(None);
-        };
-
-        let issuance = if let Some(issuance) = snapshot
-            .get_block_epoch_index(&block_hash)
-            .and_then(|index| snapshot.get_epoch_ext(&index))
-            .and_then(|epoch_ext| {
-                let primary = epoch_ext.block_reward(block_number).ok()?;
-                let secondary = epoch_ext
-                    .secondary_block_issuance(
-                        block_number,
-                        snapshot.consensus().secondary_epoch_reward(),
+        let snapshot = self.shared.snapshot();
+        // the genesis block has no rewards, and the rewards of the recent blocks are not
+        // finalized yet
+        let finalized_number = snapshot
+            .tip_number()
+            .saturating_sub(snapshot.consensus().finalization_delay_length());
+        let start_number = start_number.max(1);
+        let end_number = end_number.min(finalized_number);
+
+        let mut totals: Vec<EconomicStateTotal> = Vec::new();
+        for number in start_number..=end_number {
+            let header = snapshot
+                .get_block_hash(number)
+                .and_then(|hash| snapshot.get_block_header(&hash))
+                .ok_or_else(|| {
+                    RPCError::custom(
+                        RPCError::ChainIndexIsInconsistent,
+                        format!("the header of block {number} is not found"),
                     )
-                    .ok()?;
-                Some(core::BlockIssuance { primary, secondary })
-            }) {
-            issuance
-        } else {
-            return Ok(None);
-        };
-
-        let txs_fee = if let Some(txs_fee) =
-            snapshot.get_block_ext(&block_hash).and_then(|block_ext| {
-                block_ext
-                    .txs_fees
-                    .iter()
-                    .try_fold(core::Capacity::zero(), |acc, tx_fee| acc.safe_add(*tx_fee))
-                    .ok()
-            }) {
-            txs_fee
-        } else {
-            return Ok(None);
-        };
-
-        Ok(snapshot.get_block_header(&block_hash).and_then(|header| {
-            RewardCalculator::new(snapshot.consensus(), snapshot.as_ref())
-                .block_reward_for_target(&header)
-                .ok()
-                .map(|(_, block_reward)| core::BlockEconomicState {
-                    issuance,
-                    miner_reward: block_reward.into(),
-                    txs_fee,
-                    finalized_at,
-                })
-                .map(Into::into)
-        }))
+                })?;
+            let state = self
+                .block_economic_state(&snapshot, header.hash())?
+                .ok_or_else(|| {
+                    RPCError::custom(
+                        RPCError::ChainIndexIsInconsistent,
+                        format!("the economic state of block {number} is not found"),
+                    )
+                })?;
+            let epoch = aggregate_by_epoch.then(|| header.epoch().number());
+            match totals.last_mut() {
+                Some(total) if total.epoch == epoch => total.add(number, &state),
+                _ => totals.push(EconomicStateTotal::new(epoch, number, &state)),
+            }
+        }
+        Ok(totals.into_iter().map(Into::into).collect())
     }
 
     fn get_transaction_proof(
@@ -2138,36 +2519,159 @@ impl ChainRpc for ChainRpcImpl {
 }
 
 impl ChainRpcImpl {
+    // Returns the economic state of the canonical block, or `None` if the rewards of the block
+    // are not finalized yet.
+    fn block_economic_state(
+        &self,
+        snapshot: &Snapshot,
+        block_hash: packed::Byte32,
+    ) -> Result<Option<core::BlockEconomicState>> {
+        let block_number = if let Some(block_number) = snapshot.get_block_number(&block_hash) {
+            block_number
+        } else {
+            return Ok(None);
+        };
+
+        let delay_length = snapshot.consensus().finalization_delay_length();
+        let finalized_at_number = block_number + delay_length;
+        if block_number == 0 || snapshot.tip_number() < finalized_at_number {
+            return Ok(None);
+        }
+
+        // the rewards are calculated from the proposals of the blocks up to about two proposal
+        // windows before the block
+        let proposal_lookback = 2 * snapshot.consensus().tx_proposal_window().farthest();
+        if snapshot
+            .get_pruned_uncles_and_proposals_number()
+            .map_or(false, |pruned| {
+                pruned >= block_number.saturating_sub(proposal_lookback)
+            })
+        {
+            return Err(RPCError::custom(
+                RPCError::BlockDataPruned,
+                format!("the proposals before block #{block_number} are pruned"),
+            ));
+        }
+
+        let finalized_at = if let Some(block_hash) = snapshot.get_block_hash(finalized_at_number) {
+            block_hash
+        } else {
+            return Ok(None);
+        };
+
+        let issuance = if let Some(issuance) = snapshot
+            .get_block_epoch_index(&block_hash)
+            .and_then(|index| snapshot.get_epoch_ext(&index))
+            .and_then(|epoch_ext| {
+                let primary = epoch_ext.block_reward(block_number).ok()?;
+                let secondary = epoch_ext
+                    .secondary_block_issuance(
+                        block_number,
+                        snapshot.consensus().secondary_epoch_reward(),
+                    )
+                    .ok()?;
+                Some(core::BlockIssuance { primary, secondary })
+            }) {
+            issuance
+        } else {
+            return Ok(None);
+        };
+
+        let txs_fee = if let Some(txs_fee) =
+            snapshot.get_block_ext(&block_hash).and_then(|block_ext| {
+                block_ext
+                    .txs_fees
+                    .iter()
+                    .try_fold(core::Capacity::zero(), |acc, tx_fee| acc.safe_add(*tx_fee))
+                    .ok()
+            }) {
+            txs_fee
+        } else {
+            return Ok(None);
+        };
+
+        Ok(snapshot.get_block_header(&block_hash).and_then(|header| {
+            RewardCalculator::new(snapshot.consensus(), snapshot)
+                .block_reward_for_target(&header)
+                .ok()
+                .map(|(_, block_reward)| core::BlockEconomicState {
+                    issuance,
+                    miner_reward: block_reward.into(),
+                    txs_fee,
+                    finalized_at,
+                })
+        }))
+    }
+
+    // The fee of a committed transaction is only returned if `committed_fee`
+    fn get_transaction_with_verbosity(
+        &self,
+        snapshot: &Snapshot,
+        tx_hash: packed::Byte32,
+        verbosity: u32,
+        only_committed: bool,
+        committed_fee: bool,
+    ) -> Result<TransactionWithStatusResponse> {
+        if verbosity == 0 {
+            // when verbosity=0, it's response value is as same as verbosity=2, but it
+            // return a 0x-prefixed hex encoded molecule packed::Transaction` on `transaction` field
+            self.get_transaction_verbosity2(snapshot, tx_hash, only_committed, committed_fee)
+                .map(|tws| TransactionWithStatusResponse::from(tws, ResponseFormatInnerType::Hex))
+        } else if verbosity == 1 {
+            // The RPC does not return the transaction content and the field transaction must be null.
+            self.get_transaction_verbosity1(snapshot, tx_hash, only_committed, committed_fee)
+                .map(|tws| TransactionWithStatusResponse::from(tws, ResponseFormatInnerType::Json))
+        } else if verbosity == 2 {
+            // if tx_status.status is pending, proposed, or committed,
+            // the RPC returns the transaction content as field transaction,
+            // otherwise the field is null.
+            self.get_transaction_verbosity2(snapshot, tx_hash, only_committed, committed_fee)
+                .map(|tws| TransactionWithStatusResponse::from(tws, ResponseFormatInnerType::Json))
+        } else {
+            Err(RPCError::invalid_params("invalid verbosity level"))
+        }
+    }
+
+    // Returns the cycles and fee of a committed transaction, both of them are `None` for cellbase.
+    fn committed_cycles_and_fee(
+        snapshot: &Snapshot,
+        tx_info: &core::TransactionInfo,
+    ) -> (Option<core::Cycle>, Option<core::Capacity>) {
+        if tx_info.is_cellbase() {
+            return (None, None);
+        }
+        let index = tx_info.index.saturating_sub(1);
+        match snapshot.get_block_ext(&tx_info.block_hash) {
+            Some(block_ext) => (
+                block_ext.cycles.and_then(|v| v.get(index).copied()),
+                block_ext.txs_fees.get(index).copied(),
+            ),
+            None => (None, None),
+        }
+    }
+
     fn get_transaction_verbosity1(
         &self,
+        snapshot: &Snapshot,
         tx_hash: packed::Byte32,
         only_committed: bool,
+        committed_fee: bool,
     ) -> Result<TransactionWithStatus> {
-        let snapshot = self.shared.snapshot();
-
         if let Some(tx_info) = snapshot.get_transaction_info(&tx_hash) {
-            let cycles = if tx_info.is_cellbase() {
-                None
-            } else {
-                snapshot
-                    .get_block_ext(&tx_info.block_hash)
-                    .and_then(|block_ext| {
-                        block_ext
-                            .cycles
-                            .and_then(|v| v.get(tx_info.index.saturating_sub(1)).copied())
-                    })
-            };
+            let (cycles, fee) = Self::committed_cycles_and_fee(snapshot, &tx_info);
+            let fee = fee.filter(|_| committed_fee);
             return Ok(TransactionWithStatus::with_committed(
                 None,
                 tx_info.block_number,
                 tx_info.block_hash.unpack(),
                 tx_info.index as u32,
                 cycles,
-                None,
+                fee,
             ));
         }
 
-        if only_committed {
+        // the node without the tx-pool only knows the committed transactions
+        if only_committed || !self.shared.tx_pool_controller().is_enabled() {
             return Ok(TransactionWithStatus::with_unknown());
         }
 
@@ -2189,34 +2693,26 @@ impl ChainRpcImpl {
 
     fn get_transaction_verbosity2(
         &self,
+        snapshot: &Snapshot,
         tx_hash: packed::Byte32,
         only_committed: bool,
+        committed_fee: bool,
     ) -> Result<TransactionWithStatus> {
-        let snapshot = self.shared.snapshot();
         if let Some((tx, tx_info)) = snapshot.get_transaction_with_info(&tx_hash) {
-            let cycles = if tx_info.is_cellbase() {
-                None
-            } else {
-                snapshot
-                    .get_block_ext(&tx_info.block_hash)
-                    .and_then(|block_ext| {
-                        block_ext
-                            .cycles
-                            .and_then(|v| v.get(tx_info.index.saturating_sub(1)).copied())
-                    })
-            };
-
+            let (cycles, fee) = Self::committed_cycles_and_fee(snapshot, &tx_info);
+            let fee = fee.filter(|_| committed_fee);
             return Ok(TransactionWithStatus::with_committed(
                 Some(tx),
                 tx_info.block_number,
                 tx_info.block_hash.unpack(),
                 tx_info.index as u32,
                 cycles,
-                None,
+                fee,
             ));
         }
 
-        if only_committed {
+        // the node without the tx-pool only knows the committed transactions
+        if only_committed || !self.shared.tx_pool_controller().is_enabled() {
             return Ok(TransactionWithStatus::with_unknown());
         }
 
@@ -2246,6 +2742,12 @@ impl ChainRpcImpl {
         if !snapshot.is_main_chain(block_hash) {
             return Ok(None);
         }
+        if snapshot.is_uncles_and_proposals_pruned(block_hash) {
+            return Err(RPCError::custom(
+                RPCError::BlockDataPruned,
+                format!("the uncle proposals and proposal ids of block {block_hash:#x} are pruned"),
+            ));
+        }
 
         let verbosity = verbosity
             .map(|v| v.value())
@@ -2344,6 +2846,73 @@ impl ChainRpcImpl {
     }
 }
 
+// Sums up the economic states of the consecutive blocks.
+struct EconomicStateTotal {
+    epoch: Option<core::EpochNumber>,
+    start_number: core::BlockNumber,
+    end_number: core::BlockNumber,
+    block_count: u64,
+    issuance: core::BlockIssuance,
+    miner_reward: core::MinerReward,
+    txs_fee: core::Capacity,
+}
+
+impl EconomicStateTotal {
+    fn new(
+        epoch: Option<core::EpochNumber>,
+        number: core::BlockNumber,
+        state: &core::BlockEconomicState,
+    ) -> Self {
+        let mut total = EconomicStateTotal {
+            epoch,
+            start_number: number,
+            end_number: number,
+            block_count: 0,
+            issuance: Default::default(),
+            miner_reward: Default::default(),
+            txs_fee: core::Capacity::zero(),
+        };
+        total.add(number, state);
+        total
+    }
+
+    fn add(&mut self, number: core::BlockNumber, state: &core::BlockEconomicState) {
+        // the total issuance never overflows
+        fn sum(total: &mut core::Capacity, value: core::Capacity) {
+            *total = core::Capacity::shannons(total.as_u64().saturating_add(value.as_u64()));
+        }
+        self.end_number = number;
+        self.block_count += 1;
+        sum(&mut self.issuance.primary, state.issuance.primary);
+        sum(&mut self.issuance.secondary, state.issuance.secondary);
+        sum(&mut self.miner_reward.primary, state.miner_reward.primary);
+        sum(
+            &mut self.miner_reward.secondary,
+            state.miner_reward.secondary,
+        );
+        sum(
+            &mut self.miner_reward.committed,
+            state.miner_reward.committed,
+        );
+        sum(&mut self.miner_reward.proposal, state.miner_reward.proposal);
+        sum(&mut self.txs_fee, state.txs_fee);
+    }
+}
+
+impl From<EconomicStateTotal> for EconomicStateSummary {
+    fn from(total: EconomicStateTotal) -> Self {
+        EconomicStateSummary {
+            epoch: total.epoch.map(Into::into),
+            start_number: total.start_number.into(),
+            end_number: total.end_number.into(),
+            block_count: total.block_count.into(),
+            issuance: total.issuance.into(),
+            miner_reward: total.miner_reward.into(),
+            txs_fee: total.txs_fee.into(),
+        }
+    }
+}
+
 // CyclesEstimator run given transaction, and return the result, including execution cycles.
 pub(crate) struct CyclesEstimator<'a> {
     shared: &'a Shared,
@@ -2379,34 +2948,74 @@ impl<'a> CyclesEstimator<'a> {
     }
 
     pub(crate) fn run(&self, tx: packed::Transaction) -> Result<EstimateCycles> {
-        let snapshot = self.shared.cloned_snapshot();
-        let consensus = snapshot.cloned_consensus();
-        match resolve_transaction(tx.into_view(), &mut HashSet::new(), self, self) {
-            Ok(resolved) => {
-                let max_cycles = consensus.max_block_cycles;
-                let tip_header = snapshot.tip_header();
-                let tx_env = TxVerifyEnv::new_submit(tip_header);
-                match ScriptVerifier::new(
-                    Arc::new(resolved),
-                    snapshot.as_data_loader(),
-                    consensus,
-                    Arc::new(tx_env),
-                )
-                .verify(max_cycles)
-                {
-                    Ok(cycles) => Ok(EstimateCycles {
-                        cycles: cycles.into(),
-                    }),
-                    Err(err) => Err(RPCError::custom_with_error(
-                        RPCError::TransactionFailedToVerify,
-                        err,
-                    )),
-                }
-            }
+        let (verifier, max_cycles) = self.script_verifier(tx)?;
+        match verifier.verify_with_vm_cycles(max_cycles) {
+            Ok((cycles, groups)) => Ok(EstimateCycles {
+                cycles: cycles.into(),
+                script_groups: groups
+                    .into_iter()
+                    .map(|group| ScriptGroupCycles {
+                        group_type: match group.group_type {
+                            ScriptGroupType::Lock => JsonScriptGroupType::Lock,
+                            ScriptGroupType::Type => JsonScriptGroupType::Type,
+                        },
+                        script_hash: group.script_hash.unpack(),
+                        cycles: group.cycles.into(),
+                        vms: group
+                            .vms
+                            .into_iter()
+                            .map(|vm| VmCycles {
+                                id: vm.id.into(),
+                                parent_id: vm.parent_id.map(Into::into),
+                                cycles: vm.cycles.into(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            }),
             Err(err) => Err(RPCError::custom_with_error(
-                RPCError::TransactionFailedToResolve,
+                RPCError::TransactionFailedToVerify,
                 err,
             )),
         }
     }
+
+    /// Runs all the script groups even after some of them fail.
+    pub(crate) fn run_all_groups(&self, tx: packed::Transaction) -> Result<Vec<ScriptGroupDryRun>> {
+        let (verifier, max_cycles) = self.script_verifier(tx)?;
+        Ok(verifier
+            .verify_exhaustive(max_cycles)
+            .into_iter()
+            .map(|result| ScriptGroupDryRun {
+                group_type: match result.group_type {
+                    ScriptGroupType::Lock => JsonScriptGroupType::Lock,
+                    ScriptGroupType::Type => JsonScriptGroupType::Type,
+                },
+                script_hash: result.script_hash.unpack(),
+                cycles: result.cycles.map(Into::into),
+                error: result.error.map(|err| err.to_string()),
+            })
+            .collect())
+    }
+
+    fn script_verifier(
+        &self,
+        tx: packed::Transaction,
+    ) -> Result<(ScriptVerifier<DataLoaderWrapper<Snapshot>>, core::Cycle)> {
+        let snapshot = self.shared.cloned_snapshot();
+        let consensus = snapshot.cloned_consensus();
+        let resolved = resolve_transaction(tx.into_view(), &mut HashSet::new(), self, self)
+            .map_err(|err| {
+                RPCError::custom_with_error(RPCError::TransactionFailedToResolve, err)
+            })?;
+        let max_cycles = consensus.max_block_cycles;
+        let tx_env = TxVerifyEnv::new_submit(snapshot.tip_header());
+        let verifier = ScriptVerifier::new(
+            Arc::new(resolved),
+            snapshot.as_data_loader(),
+            consensus,
+            Arc::new(tx_env),
+        );
+        Ok((verifier, max_cycles))
+    }
 }
@@ -1,8 +1,20 @@
+use crate::error::RPCError;
+use crate::module::chain::CyclesEstimator;
 use async_trait::async_trait;
-use ckb_jsonrpc_types::{ExtraLoggerConfig, MainLoggerConfig};
+use ckb_db_schema::COLUMN_INFOS;
+use ckb_jsonrpc_types::{
+    ChainIntegrityIssue, ChainIntegrityIssueKind, ChainIntegrityReport, DBColumnInfo,
+    ExtraLoggerConfig, MainLoggerConfig, ScriptDeterminismReport, ScriptGroupDryRun, Transaction,
+    TxLifecycle, Uint64,
+};
 use ckb_logger_service::Logger;
+use ckb_shared::shared::Shared;
+use ckb_store::integrity::IntegrityIssueKind;
+use ckb_types::{packed, prelude::*, H256};
+use ckb_verification::VM_BACKEND;
 use jsonrpc_core::{Error, ErrorCode::InternalError, Result};
 use jsonrpc_utils::rpc;
+use std::collections::{BTreeSet, HashSet};
 use std::time;
 /// RPC Module Debug for internal RPC methods.
 ///
@@ -33,10 +45,134 @@ pub trait DebugRpc {
     /// Removes the logger when this is null.
     #[rpc(name = "set_extra_logger")]
     fn set_extra_logger(&self, name: String, config_opt: Option<ExtraLoggerConfig>) -> Result<()>;
+    /// Lists all the columns of the database.
+    ///
+    /// Each column contains its purpose, the formats of its keys and values, and the estimated
+    /// number of entries and size reported by RocksDB.
+    #[rpc(name = "get_db_columns_info")]
+    fn get_db_columns_info(&self) -> Result<Vec<DBColumnInfo>>;
+    /// Checks the latest main chain blocks for the data and indexes left inconsistent by an
+    /// unclean shutdown.
+    ///
+    /// ## Params
+    ///
+    /// * `depth` - How many blocks below the tip are checked, defaults to 1000.
+    /// * `repair` - Whether to repair the issues found, defaults to false. The indexes and block
+    /// exts are repaired from the local data, and the blocks whose bodies are missing are
+    /// downloaded again from the peers.
+    #[rpc(name = "check_chain_integrity")]
+    fn check_chain_integrity(
+        &self,
+        depth: Option<Uint64>,
+        repair: Option<bool>,
+    ) -> Result<ChainIntegrityReport>;
+    /// Returns the traced lifecycle of a transaction through the pool, the block templates and
+    /// the chain.
+    ///
+    /// A trace id is assigned when the transaction is submitted or relayed, and a timestamped
+    /// event is recorded when it is verified, proposed, included in a block template, committed
+    /// or rejected. The pool keeps the traces of the most recent transactions, see
+    /// `tx_pool.max_traced_txs` in the config.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hash` - Hash of a transaction.
+    ///
+    /// ## Returns
+    ///
+    /// Returns null if the transaction is not traced, e.g., it has been evicted from the traces
+    /// or the tracing is disabled. If a transaction comes back after it is committed or rejected,
+    /// e.g., re-added to the pool after a reorg, only the latest trace is returned.
+    #[rpc(name = "get_tx_lifecycle")]
+    fn get_tx_lifecycle(&self, tx_hash: H256) -> Result<Option<TxLifecycle>>;
+    /// Sets a virtual fee delta for a transaction in the pool.
+    ///
+    /// The delta is added to the transaction fee when the pool sorts transactions and selects
+    /// them for the block template, so the transaction and its descendants are packaged as if
+    /// they paid a higher fee. It does not change the fee paid on chain, the fee rate checks or
+    /// the RBF rules.
+    ///
+    /// The delta is kept in memory until the transaction leaves the pool, e.g., it's committed,
+    /// expired or evicted, or the node restarts. Setting it again replaces the previous delta,
+    /// and setting it to 0 removes it.
+    ///
+    /// This method is meant for the node operator, so it's only available in the module `Debug`.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hash` - Hash of a transaction.
+    /// * `fee_delta` - The virtual fee delta in shannons.
+    ///
+    /// ## Returns
+    ///
+    /// If the transaction exists in the pool, return true; otherwise, return false.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "prioritise_transaction",
+    ///   "params": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3",
+    ///     "0x64"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": true
+    /// }
+    /// ```
+    #[rpc(name = "prioritise_transaction")]
+    fn prioritise_transaction(&self, tx_hash: H256, fee_delta: Uint64) -> Result<bool>;
+    /// Runs all the script groups of a transaction twice on the CKB-VM backend of this node, and
+    /// compares the results and the consumed cycles.
+    ///
+    /// The backend, the ASM machine or the Rust interpreter, is selected at build time. To compare
+    /// the two backends, call this method on a node of each build, and pass the report of one as
+    /// the `baseline` of the other. Any difference is a consensus bug of a backend.
+    ///
+    /// This method is only available on dev chains.
+    ///
+    /// ## Params
+    ///
+    /// * `tx` - The transaction.
+    /// * `baseline` - The report of the same transaction from a node with another backend,
+    /// optional.
+    ///
+    /// ## Returns
+    ///
+    /// The backend of this node, the results of the script groups, and the hashes of the scripts
+    /// which diverge. A script group missing from either side diverges.
+    ///
+    /// ## Errors
+    ///
+    /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to resolve the referenced cells and headers used in the transaction, as inputs or dependencies.
+    #[rpc(name = "check_script_determinism")]
+    fn check_script_determinism(
+        &self,
+        tx: Transaction,
+        baseline: Option<ScriptDeterminismReport>,
+    ) -> Result<ScriptDeterminismReport>;
 }
 
 #[derive(Clone)]
-pub(crate) struct DebugRpcImpl {}
+pub(crate) struct DebugRpcImpl {
+    pub shared: Shared,
+}
+
+const DEFAULT_CHAIN_INTEGRITY_DEPTH: u64 = 1000;
+const PROPERTY_NUM_KEYS: &str = "rocksdb.estimate-num-keys";
+const PROPERTY_LIVE_DATA_SIZE: &str = "rocksdb.estimate-live-data-size";
 
 #[async_trait]
 impl DebugRpc for DebugRpcImpl {
@@ -92,4 +228,125 @@ impl DebugRpc for DebugRpcImpl {
             data: None,
         })
     }
+
+    fn get_db_columns_info(&self) -> Result<Vec<DBColumnInfo>> {
+        let db = self.shared.store().db();
+        COLUMN_INFOS
+            .iter()
+            .map(|info| {
+                let estimated_entries = db
+                    .property_int_value_cf(info.col, PROPERTY_NUM_KEYS)
+                    .map_err(RPCError::from_ckb_error)?;
+                let estimated_size = db
+                    .property_int_value_cf(info.col, PROPERTY_LIVE_DATA_SIZE)
+                    .map_err(RPCError::from_ckb_error)?;
+                Ok(DBColumnInfo {
+                    col: info.col.to_owned(),
+                    name: info.name.to_owned(),
+                    description: info.description.to_owned(),
+                    key: info.key.to_owned(),
+                    value: info.value.to_owned(),
+                    estimated_entries: estimated_entries.map(Into::into),
+                    estimated_size: estimated_size.map(Into::into),
+                })
+            })
+            .collect()
+    }
+
+    fn check_chain_integrity(
+        &self,
+        depth: Option<Uint64>,
+        repair: Option<bool>,
+    ) -> Result<ChainIntegrityReport> {
+        let depth: u64 = depth
+            .map(Unpack::unpack)
+            .unwrap_or(DEFAULT_CHAIN_INTEGRITY_DEPTH);
+        let tip_number = self.shared.snapshot().tip_number();
+        let from = tip_number.saturating_sub(depth);
+        let issues = self
+            .shared
+            .check_chain_integrity(from, repair.unwrap_or(false))
+            .map_err(RPCError::from_ckb_error)?;
+        Ok(ChainIntegrityReport {
+            from: from.into(),
+            to: tip_number.into(),
+            issues: issues
+                .into_iter()
+                .map(|issue| ChainIntegrityIssue {
+                    block_number: issue.number.into(),
+                    block_hash: issue.hash.unpack(),
+                    kind: match issue.kind {
+                        IntegrityIssueKind::MissingBody => ChainIntegrityIssueKind::MissingBody,
+                        IntegrityIssueKind::MissingBlockExt => {
+                            ChainIntegrityIssueKind::MissingBlockExt
+                        }
+                        IntegrityIssueKind::IndexMismatch => ChainIntegrityIssueKind::IndexMismatch,
+                        IntegrityIssueKind::MissingTransactionInfo => {
+                            ChainIntegrityIssueKind::MissingTransactionInfo
+                        }
+                    },
+                })
+                .collect(),
+            missing_block_bodies: self
+                .shared
+                .missing_block_bodies()
+                .into_iter()
+                .map(|hash| hash.unpack())
+                .collect(),
+        })
+    }
+
+    fn get_tx_lifecycle(&self, tx_hash: H256) -> Result<Option<TxLifecycle>> {
+        Ok(self
+            .shared
+            .tx_pool_controller()
+            .get_tx_lifecycle(&tx_hash.pack())
+            .map(Into::into))
+    }
+
+    fn prioritise_transaction(&self, tx_hash: H256, fee_delta: Uint64) -> Result<bool> {
+        self.shared
+            .tx_pool_controller()
+            .prioritise_tx(tx_hash.pack(), fee_delta.value())
+            .map_err(RPCError::ckb_internal_error)
+    }
+
+    fn check_script_determinism(
+        &self,
+        tx: Transaction,
+        baseline: Option<ScriptDeterminismReport>,
+    ) -> Result<ScriptDeterminismReport> {
+        if self.shared.consensus().is_public_chain() {
+            return Err(RPCError::invalid_params(
+                "check_script_determinism is only available on dev chains",
+            ));
+        }
+        let tx: packed::Transaction = tx.into();
+        let estimator = CyclesEstimator::new(&self.shared);
+        let script_groups = estimator.run_all_groups(tx.clone())?;
+        // the outcomes must not depend on the state left by the previous run either
+        let mut diverged = diverged_scripts(&script_groups, &estimator.run_all_groups(tx)?);
+        if let Some(baseline) = baseline {
+            diverged.extend(diverged_scripts(&script_groups, &baseline.script_groups));
+        }
+        Ok(ScriptDeterminismReport {
+            backend: VM_BACKEND.to_string(),
+            script_groups,
+            diverged: diverged.into_iter().collect(),
+        })
+    }
+}
+
+/// Returns the hashes of the scripts whose results differ, including the groups missing from
+/// either side.
+pub(crate) fn diverged_scripts(
+    groups: &[ScriptGroupDryRun],
+    others: &[ScriptGroupDryRun],
+) -> BTreeSet<H256> {
+    let groups: HashSet<_> = groups.iter().collect();
+    let others: HashSet<_> = others.iter().collect();
+    groups
+        .symmetric_difference(&others)
+        .map(|group| group.script_hash.clone())
+        .collect()
 }
@@ -3,7 +3,8 @@ use crate::module::chain::CyclesEstimator;
 use async_trait::async_trait;
 use ckb_dao::DaoCalculator;
 use ckb_jsonrpc_types::{
-    Capacity, DaoWithdrawingCalculationKind, EstimateCycles, OutPoint, Transaction,
+    Capacity, DaoWithdrawingCalculationKind, EstimateCycles, OutPoint, ScriptGroupDryRun,
+    Transaction,
 };
 use ckb_shared::{shared::Shared, Snapshot};
 use ckb_store::ChainStore;
@@ -91,7 +92,21 @@ pub trait ExperimentRpc {
     ///   "id": 42,
     ///   "jsonrpc": "2.0",
     ///   "result": {
-    ///     "cycles": "0x219"
+    ///     "cycles": "0x219",
+    ///     "script_groups": [
+    ///       {
+    ///         "cycles": "0x219",
+    ///         "group_type": "lock",
+    ///         "script_hash": "0x4ceaa32f692948413e213ce6f3a83337145bde6e11fd8cb94377ce2637dcc412",
+    ///         "vms": [
+    ///           {
+    ///             "cycles": "0x219",
+    ///             "id": "0x0",
+    ///             "parent_id": null
+    ///           }
+    ///         ]
+    ///       }
+    ///     ]
     ///   }
     /// }
     /// ```
@@ -102,6 +117,92 @@ pub trait ExperimentRpc {
     #[rpc(name = "dry_run_transaction")]
     fn dry_run_transaction(&self, tx: Transaction) -> Result<EstimateCycles>;
 
+    /// Dry run a transaction and return the result of every script group.
+    ///
+    /// Unlike [`estimate_cycles`](#chain-estimate_cycles), which stops at the first failing
+    /// script, this method runs all the lock and type script groups, so the developers can fix
+    /// all the failing scripts in one iteration. The groups share the max block cycles, the
+    /// groups after the limit is reached fail with the error of exceeding the maximum cycles.
+    ///
+    /// This method will not check the transaction validity, and the transaction is valid only if
+    /// all the groups pass.
+    ///
+    /// ## Errors
+    ///
+    /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to resolve the referenced cells and headers used in the transaction, as inputs or dependencies.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "dry_run_script_groups",
+    ///   "params": [
+    ///     {
+    ///       "cell_deps": [
+    ///         {
+    ///           "dep_type": "code",
+    ///           "out_point": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///           }
+    ///         }
+    ///       ],
+    ///       "header_deps": [
+    ///         "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed"
+    ///       ],
+    ///       "inputs": [
+    ///         {
+    ///           "previous_output": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0x365698b50ca0da75dca2c87f9e7b563811d3b5813736b8cc62cc3b106faceb17"
+    ///           },
+    ///           "since": "0x0"
+    ///         }
+    ///       ],
+    ///       "outputs": [
+    ///         {
+    ///           "capacity": "0x2540be400",
+    ///           "lock": {
+    ///             "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///             "hash_type": "data",
+    ///             "args": "0x"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       ],
+    ///       "outputs_data": [
+    ///         "0x"
+    ///       ],
+    ///       "version": "0x0",
+    ///       "witnesses": []
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "cycles": "0x219",
+    ///       "error": null,
+    ///       "group_type": "lock",
+    ///       "script_hash": "0x4ceaa32f692948413e213ce6f3a83337145bde6e11fd8cb94377ce2637dcc412"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "dry_run_script_groups")]
+    fn dry_run_script_groups(&self, tx: Transaction) -> Result<Vec<ScriptGroupDryRun>>;
+
     /// Calculates the maximum withdrawal one can get, given a referenced DAO cell, and
     /// a withdrawing block hash.
     ///
@@ -176,6 +277,11 @@ impl ExperimentRpc for ExperimentRpcImpl {
         CyclesEstimator::new(&self.shared).run(tx)
     }
 
+    fn dry_run_script_groups(&self, tx: Transaction) -> Result<Vec<ScriptGroupDryRun>> {
+        let tx: packed::Transaction = tx.into();
+        CyclesEstimator::new(&self.shared).run_all_groups(tx)
+    }
+
     fn calculate_dao_maximum_withdraw(
         &self,
         out_point: OutPoint,
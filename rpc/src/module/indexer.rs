@@ -1,9 +1,10 @@
 use crate::error::RPCError;
+use crate::util::wait_for_indexer;
 use async_trait::async_trait;
 use ckb_indexer::IndexerHandle;
 use ckb_jsonrpc_types::{
     IndexerCell, IndexerCellsCapacity, IndexerOrder, IndexerPagination, IndexerSearchKey,
-    IndexerTip, IndexerTx, JsonBytes, Uint32,
+    IndexerTip, IndexerTx, IndexerTxWithWitness, IndexerWaitFor, JsonBytes, Uint32,
 };
 use jsonrpc_core::Result;
 use jsonrpc_utils::rpc;
@@ -14,6 +15,10 @@ use jsonrpc_utils::rpc;
 pub trait IndexerRpc {
     /// Returns the indexed tip
     ///
+    /// The indexer processes the blocks asynchronously, so the indexed tip may lag behind the
+    /// chain tip. Pass the parameter `wait_for` to the queries to get results consistent with a
+    /// block the client has already seen.
+    ///
     /// ## Returns
     ///   * block_hash - indexed tip block hash
     ///   * block_number - indexed tip block number
@@ -65,6 +70,10 @@ pub trait IndexerRpc {
     /// * order: enum, asc | desc
     /// * limit: result size limit
     /// * after: pagination parameter, optional
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
     ///
     /// ## Returns
     ///
@@ -394,12 +403,13 @@ pub trait IndexerRpc {
     /// }
     /// ```
     #[rpc(name = "get_cells")]
-    fn get_cells(
+    async fn get_cells(
         &self,
         search_key: IndexerSearchKey,
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerCell>>;
 
     /// Returns the transactions collection by the lock or type script.
@@ -415,6 +425,10 @@ pub trait IndexerRpc {
     /// * order: enum, asc | desc
     /// * limit: result size limit
     /// * after: pagination parameter, optional
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
     ///
     /// ## Returns
     ///
@@ -810,14 +824,93 @@ pub trait IndexerRpc {
     /// }
     /// ```
     #[rpc(name = "get_transactions")]
-    fn get_transactions(
+    async fn get_transactions(
         &self,
         search_key: IndexerSearchKey,
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerTx>>;
 
+    /// Returns the transactions with the witnesses starting with the witness prefix.
+    ///
+    /// The witness prefixes are opt-in, only the prefixes in the `witness_prefixes` of the indexer config are indexed, so the protocols encoding identifiers in the witnesses can look up their messages without scanning the chain. Only the blocks indexed after a prefix is added are indexed by it.
+    ///
+    /// ## Parameters
+    ///
+    /// * witness_prefix: one of the indexed witness prefixes
+    /// * order: enum, asc | desc
+    /// * limit: result size limit
+    /// * after: pagination parameter, optional
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
+    ///
+    /// ## Returns
+    ///
+    /// If the number of objects is less than the requested `limit`, it indicates that these are the last page of get_transactions_by_witness_prefix.
+    ///
+    ///  * objects - the transactions ordered by the block number, the tx index and the witness index
+    ///     - tx_hash: transaction hash
+    ///     - block_number: the number of the transaction committed in the block
+    ///     - tx_index: the position index of the transaction committed in the block
+    ///     - witness_index: the position index of the witness starting with the prefix in the transaction witnesses
+    ///  * last_cursor - pagination parameter
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///     "id": 2,
+    ///     "jsonrpc": "2.0",
+    ///     "method": "get_transactions_by_witness_prefix",
+    ///     "params": [
+    ///         "0x636b62",
+    ///         "asc",
+    ///         "0x2"
+    ///     ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///     "jsonrpc": "2.0",
+    ///     "result": {
+    ///       "last_cursor": "0xf003636b6200000000005b59df0000000100000000",
+    ///       "objects": [
+    ///         {
+    ///           "block_number": "0x5b59cc",
+    ///           "tx_hash": "0x57ca2822c28e02b199424a731b2efd2c9bf752f07b7309f555f2e71abe83ba26",
+    ///           "tx_index": "0x1",
+    ///           "witness_index": "0x1"
+    ///         },
+    ///         {
+    ///           "block_number": "0x5b59df",
+    ///           "tx_hash": "0x21c4632a41140b828e9347ff80480b3e07be4e0a0b8d577565e7421fd5473194",
+    ///           "tx_index": "0x1",
+    ///           "witness_index": "0x0"
+    ///         }
+    ///       ]
+    ///     },
+    ///     "id": 2
+    /// }
+    /// ```
+    #[rpc(name = "get_transactions_by_witness_prefix")]
+    async fn get_transactions_by_witness_prefix(
+        &self,
+        witness_prefix: JsonBytes,
+        order: IndexerOrder,
+        limit: Uint32,
+        after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
+    ) -> Result<IndexerPagination<IndexerTxWithWitness>>;
+
     /// Returns the live cells capacity by the lock or type script.
     ///
     /// ## Parameters
@@ -834,6 +927,10 @@ pub trait IndexerRpc {
     ///         - output_data_len_range: [u64; 2], filter cells by output data len range, [inclusive, exclusive]
     ///         - output_capacity_range: [u64; 2], filter cells by output capacity range, [inclusive, exclusive]
     ///         - block_range: [u64; 2], filter cells by block number range, [inclusive, exclusive]
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
     ///
     /// ## Returns
     ///
@@ -877,9 +974,10 @@ pub trait IndexerRpc {
     /// }
     /// ```
     #[rpc(name = "get_cells_capacity")]
-    fn get_cells_capacity(
+    async fn get_cells_capacity(
         &self,
         search_key: IndexerSearchKey,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<Option<IndexerCellsCapacity>>;
 }
 
@@ -892,6 +990,19 @@ impl IndexerRpcImpl {
     pub fn new(handle: IndexerHandle) -> Self {
         IndexerRpcImpl { handle }
     }
+
+    async fn wait_for_indexer(&self, wait_for: Option<IndexerWaitFor>) -> Result<()> {
+        wait_for_indexer(
+            wait_for,
+            || async { self.get_indexer_tip() },
+            |block_number| async move {
+                self.handle
+                    .get_block_hash(block_number)
+                    .map_err(|e| RPCError::custom(RPCError::Indexer, e))
+            },
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -902,34 +1013,54 @@ impl IndexerRpc for IndexerRpcImpl {
             .map_err(|e| RPCError::custom(RPCError::Indexer, e))
     }
 
-    fn get_cells(
+    async fn get_cells(
         &self,
         search_key: IndexerSearchKey,
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerCell>> {
+        self.wait_for_indexer(wait_for).await?;
         self.handle
             .get_cells(search_key, order, limit, after)
             .map_err(|e| RPCError::custom(RPCError::Indexer, e))
     }
 
-    fn get_transactions(
+    async fn get_transactions(
         &self,
         search_key: IndexerSearchKey,
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerTx>> {
+        self.wait_for_indexer(wait_for).await?;
         self.handle
             .get_transactions(search_key, order, limit, after)
             .map_err(|e| RPCError::custom(RPCError::Indexer, e))
     }
 
-    fn get_cells_capacity(
+    async fn get_transactions_by_witness_prefix(
+        &self,
+        witness_prefix: JsonBytes,
+        order: IndexerOrder,
+        limit: Uint32,
+        after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
+    ) -> Result<IndexerPagination<IndexerTxWithWitness>> {
+        self.wait_for_indexer(wait_for).await?;
+        self.handle
+            .get_transactions_by_witness_prefix(witness_prefix, order, limit, after)
+            .map_err(|e| RPCError::custom(RPCError::Indexer, e))
+    }
+
+    async fn get_cells_capacity(
         &self,
         search_key: IndexerSearchKey,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<Option<IndexerCellsCapacity>> {
+        self.wait_for_indexer(wait_for).await?;
         self.handle
             .get_cells_capacity(search_key)
             .map_err(|e| RPCError::custom(RPCError::Indexer, e))
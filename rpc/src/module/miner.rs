@@ -6,6 +6,7 @@ use ckb_logger::{debug, error, info, warn};
 use ckb_network::{NetworkController, PeerIndex, SupportProtocols, TargetSession};
 use ckb_shared::{shared::Shared, Snapshot};
 use ckb_store::ChainStore;
+use ckb_sync::build_compact_block;
 use ckb_systemtime::unix_time_as_millis;
 use ckb_types::{core, packed, prelude::*, H256};
 use ckb_verification::HeaderVerifier;
@@ -312,7 +313,7 @@ impl MinerRpc for MinerRpcImpl {
                 header.hash(),
                 unix_time_as_millis()
             );
-            let content = packed::CompactBlock::build_from_block(&block, &HashSet::new());
+            let content = build_compact_block(&self.shared, &block);
             let message = packed::RelayMessage::new_builder().set(content).build();
             let pid = if self.network_controller.load_ckb2023() {
                 SupportProtocols::RelayV3.protocol_id()
@@ -58,6 +58,14 @@
 //! removed in 0.37.0. The minor versions are released monthly, so there's at least a two-month
 //! buffer for a deprecated RPC method.
 //!
+//! The deprecated methods are listed by the RPC method `list_deprecated_methods`. When a
+//! deprecated method is called via HTTP, the response has an extra `warning` field, and the HTTP
+//! response has the header `Deprecation: true` and also the header `Sunset` if the removal date
+//! is scheduled. The node logs a warning at most once per method in 10 minutes.
+//!
+//! A method is marked as deprecated by the `#[deprecated]` attribute on the RPC trait method and
+//! an entry in `DEPRECATED_METHODS` in `src/util/deprecation.rs`.
+//!
 //! ## JSON Cheatsheet
 //!
 //! CKB uses a framework to serialize into and deserialize from JSON. Some Rust std-lib
@@ -125,6 +133,8 @@ mod test;
 
 pub(crate) use self::alert::AlertRpcImpl;
 pub(crate) use self::chain::ChainRpcImpl;
+#[cfg(test)]
+pub(crate) use self::debug::diverged_scripts;
 pub(crate) use self::debug::DebugRpcImpl;
 pub(crate) use self::experiment::ExperimentRpcImpl;
 pub(crate) use self::indexer::IndexerRpcImpl;
@@ -134,6 +144,10 @@ pub(crate) use self::pool::PoolRpcImpl;
 pub(crate) use self::rich_indexer::RichIndexerRpcImpl;
 pub(crate) use self::stats::StatsRpcImpl;
 pub(crate) use self::subscription::SubscriptionRpcImpl;
+#[cfg(test)]
+pub(crate) use self::subscription::{
+    EventBatch, EventFilter, EventTx, ResumeToken, TopicHistory, TxStatusTracker,
+};
 pub(crate) use self::test::IntegrationTestRpcImpl;
 
 pub use self::alert::{add_alert_rpc_methods, alert_rpc_doc, AlertRpc};
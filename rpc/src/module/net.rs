@@ -1,20 +1,38 @@
 use crate::error::RPCError;
+use crate::util::MAX_PAGINATION_LIMIT;
 use async_trait::async_trait;
+use ckb_app_config::{DEFAULT_CHECKPOINT_COUNT, DEFAULT_CHECKPOINT_INTERVAL};
 use ckb_chain::ChainController;
+use ckb_jsonrpc_types::pagination::{check_limit, paginate_by_key};
 use ckb_jsonrpc_types::{
-    BannedAddr, LocalNode, LocalNodeProtocol, NodeAddress, PeerSyncState, RemoteNode,
-    RemoteNodeProtocol, SyncState, Timestamp,
+    AssumeValidState, BannedAddr, BlockNumber, BootnodeHealth, BootnodeStatus, DialPeerResult,
+    DialStatus, IndexerOrder, IndexerPagination, JsonBytes, LocalNode, LocalNodeListener,
+    LocalNodeProtocol, MisbehaviorComponent, NodeAddress, NodeReachability, OutboundTargets,
+    PeerMisbehavior, PeerSyncState, ReachabilityStatus, RemoteNode, RemoteNodeProtocol,
+    SignedHeaderCheckpoints, SyncState, Timestamp, Uint32,
 };
-use ckb_network::{extract_peer_id, multiaddr::Multiaddr, NetworkController};
+use ckb_network::{
+    extract_peer_id, features::flag_names, multiaddr::Multiaddr, DialOutcome, NetworkController,
+};
+use ckb_shared::header_checkpoints::{
+    collect_header_checkpoints, HeaderCheckpointSigner, MAX_HEADER_CHECKPOINTS,
+};
+use ckb_store::ChainStore;
 use ckb_sync::SyncShared;
 use ckb_systemtime::unix_time_as_millis;
-use ckb_types::prelude::{Pack, Unpack};
+use ckb_types::{
+    packed,
+    prelude::{Pack, Unpack},
+};
 use jsonrpc_core::Result;
 use jsonrpc_utils::rpc;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const MAX_ADDRS: usize = 50;
 const DEFAULT_BAN_DURATION: u64 = 24 * 60 * 60 * 1000; // 1 day
+const DIAL_PEER_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// RPC Module Net for P2P network.
 #[rpc(openrpc)]
@@ -56,7 +74,21 @@ pub trait NetRpc {
     ///       }
     ///     ],
     ///     "connections": "0xb",
+    ///     "listeners": [
+    ///       {
+    ///         "address": "/ip4/0.0.0.0/tcp/8112",
+    ///         "advertise": true,
+    ///         "inbound_peers": "0x3",
+    ///         "listened_address": "/ip4/0.0.0.0/tcp/8112",
+    ///         "max_inbound_peers": null
+    ///       }
+    ///     ],
     ///     "node_id": "QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+    ///     "outbound_targets": {
+    ///       "bandwidth_usage": null,
+    ///       "relay": "0x8",
+    ///       "sync": "0x8"
+    ///     },
     ///     "protocols": [
     ///       {
     ///         "id": "0x0",
@@ -73,6 +105,15 @@ pub trait NetRpc {
     ///         ]
     ///       }
     ///     ],
+    ///     "reachability": {
+    ///       "last_checked": "0x174876e8000",
+    ///       "last_public_inbound": null,
+    ///       "reachable_addresses": [
+    ///         "/ip4/192.168.0.2/tcp/8112/p2p/QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS"
+    ///       ],
+    ///       "status": "reachable",
+    ///       "unreachable_addresses": []
+    ///     },
     ///     "version": "0.34.0 (f37f598 2020-07-17)"
     ///   }
     /// }
@@ -114,39 +155,64 @@ pub trait NetRpc {
     ///         }
     ///       ],
     ///       "connected_duration": "0x2f",
+    ///       "features": [
+    ///         "compression",
+    ///         "compact_relay"
+    ///       ],
+    ///       "flags": [
+    ///         "discovery",
+    ///         "sync",
+    ///         "relay"
+    ///       ],
     ///       "is_outbound": true,
     ///       "last_ping_duration": "0x1a",
     ///       "node_id": "QmXwUgF48ULy6hkgfqrEwEfuHW7WyWyWauueRDAYQHNDfN",
     ///       "protocols": [
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x4",
     ///           "version": "0.0.1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x2",
     ///           "version": "0.0.1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x1",
     ///           "version": "0.0.1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x64",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x6e",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x66",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x65",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x0",
     ///           "version": "0.0.1"
     ///         }
@@ -170,39 +236,64 @@ pub trait NetRpc {
     ///         }
     ///       ],
     ///       "connected_duration": "0x95",
+    ///       "features": [
+    ///         "compression",
+    ///         "compact_relay"
+    ///       ],
+    ///       "flags": [
+    ///         "discovery",
+    ///         "sync",
+    ///         "relay"
+    ///       ],
     ///       "is_outbound": true,
     ///       "last_ping_duration": "0x41",
     ///       "node_id": "QmSrkzhdBMmfCGx8tQGwgXxzBg8kLtX8qMcqECMuKWsxDV",
     ///       "protocols": [
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x0",
     ///           "version": "0.0.1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x2",
     ///           "version": "0.0.1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x6e",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x66",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x1",
     ///           "version": "0.0.1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x65",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x64",
     ///           "version": "1"
     ///         },
     ///         {
+    ///           "bytes_received": "0x1a0",
+    ///           "bytes_sent": "0x1f4",
     ///           "id": "0x4",
     ///           "version": "0.0.1"
     ///         }
@@ -224,6 +315,51 @@ pub trait NetRpc {
     #[rpc(name = "get_peers")]
     fn get_peers(&self) -> Result<Vec<RemoteNode>>;
 
+    /// Returns the connected peers page by page.
+    ///
+    /// The peers are sorted by `node_id` in ascending order and the cursor is the `node_id` of
+    /// the last returned peer, so iterating is stable while peers connect and disconnect.
+    /// See [`get_peers`](#tymethod.get_peers) for the peer fields.
+    ///
+    /// ## Params
+    ///
+    /// * `limit` - The max number of returned peers, at most 1000.
+    /// * `after` - Pagination parameter, the `last_cursor` returned by the previous page.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "list_peers",
+    ///   "params": [
+    ///     "0x64"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "last_cursor": "0x",
+    ///     "objects": []
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "list_peers")]
+    fn list_peers(
+        &self,
+        limit: Uint32,
+        after: Option<JsonBytes>,
+    ) -> Result<IndexerPagination<RemoteNode>>;
+
     /// Returns all banned IPs/Subnets.
     ///
     /// ## Examples
@@ -363,6 +499,7 @@ pub trait NetRpc {
     ///   "id": 42,
     ///   "jsonrpc": "2.0",
     ///   "result": {
+    ///     "assume_valid": null,
     ///     "assume_valid_target": "0x0000000000000000000000000000000000000000000000000000000000000000",
     ///     "assume_valid_target_reached": true,
     ///     "best_known_block_number": "0x400",
@@ -515,6 +652,54 @@ pub trait NetRpc {
     #[rpc(name = "remove_node")]
     fn remove_node(&self, peer_id: String) -> Result<()>;
 
+    /// Dials a node now and waits for the outcome, at most 10 seconds.
+    ///
+    /// Unlike `add_node`, which dials in the background, it reports whether the node is
+    /// connected, so the operators can debug the connectivity without searching the logs.
+    ///
+    /// ## Params
+    ///
+    /// * `address` - The full P2P address of the node, including the peer id, e.g.,
+    /// "/ip4/192.168.2.100/tcp/8114/p2p/QmUsZHPbjjzU627UZFt4k8j6ycEcNvXRnVGxCPKqwbAfQS".
+    ///
+    /// ## Returns
+    ///
+    /// The node already connected is reported as `connected` without dialing, and the banned
+    /// address is reported as `banned` without dialing. The dial is not canceled on `timeout`,
+    /// the node may be connected later.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "dial_peer",
+    ///   "params": [
+    ///     "/ip4/127.0.0.1/tcp/8115/p2p/QmUsZHPbjjzU627UZFt4k8j6ycEcNvXRnVGxCPKqwbAfQS"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "address": "/ip4/127.0.0.1/tcp/8115/p2p/QmUsZHPbjjzU627UZFt4k8j6ycEcNvXRnVGxCPKqwbAfQS",
+    ///     "duration": "0x3",
+    ///     "message": "IoError: Connection refused (os error 111)",
+    ///     "status": "failed"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "dial_peer")]
+    async fn dial_peer(&self, address: String) -> Result<DialPeerResult>;
+
     /// Requests that a ping is sent to all connected peers, to measure ping time.
     ///
     /// ## Examples
@@ -541,6 +726,168 @@ pub trait NetRpc {
     /// ```
     #[rpc(name = "ping_peers")]
     fn ping_peers(&self) -> Result<()>;
+
+    /// Returns the health of the bootnodes.
+    ///
+    /// The bootnodes are picked by the weights of their sets, see `network.bootnode_sets` in
+    /// ckb.toml. A bootnode is unhealthy after 3 failed dials in a row, and it is not picked until
+    /// a periodic health check connects to it again.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_bootnodes_health",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "address": "/ip4/192.168.0.2/tcp/8112/p2p/QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+    ///       "consecutive_failures": "0x0",
+    ///       "last_checked": "0x16bde533338",
+    ///       "last_failure": null,
+    ///       "last_success": "0x16bde533338",
+    ///       "set": "default",
+    ///       "status": "healthy",
+    ///       "weight": "0x1"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_bootnodes_health")]
+    fn get_bootnodes_health(&self) -> Result<Vec<BootnodeHealth>>;
+
+    /// Returns the misbehavior scores of the connected peers.
+    ///
+    /// The sync, relay, filter and time protocols report the misbehaviors of the peers to the
+    /// network. Every report adds the weight of its kind to the score of the peer, see
+    /// `network.misbehavior.weights` in ckb.toml, and the peer is banned once the score reaches
+    /// the threshold. The scores decay exponentially over time. Only the peers which have
+    /// misbehaved are returned.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_peer_misbehaviors",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "address": "/ip4/192.168.0.3/tcp/8115/p2p/QmaaaLB4uPyDpZwTQGhV63zuYrKm4reyN2tF1j2ain4oE7",
+    ///       "ban_threshold": "0x64",
+    ///       "components": [
+    ///         {
+    ///           "count": "0x1",
+    ///           "kind": "TooManyUnknownTransactions",
+    ///           "last_reason": "TooManyUnknownTransactions(416): 1200 > 1000",
+    ///           "last_reported_at": "0x16bde533338",
+    ///           "score": "0x32"
+    ///         }
+    ///       ],
+    ///       "node_id": "QmaaaLB4uPyDpZwTQGhV63zuYrKm4reyN2tF1j2ain4oE7",
+    ///       "score": "0x32"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_peer_misbehaviors")]
+    fn get_peer_misbehaviors(&self) -> Result<Vec<PeerMisbehavior>>;
+
+    /// Returns the recent canonical chain header checkpoints signed by the network secret key of
+    /// the node.
+    ///
+    /// The light client infrastructure can serve the checkpoints to the light clients, which
+    /// only sync the headers after a trusted checkpoint. The signature proves that the node with
+    /// the `node_id` vouches for the checkpoints, and it can be verified offline with
+    /// `ckb verify-checkpoints`. The node can also export the checkpoints to a file periodically,
+    /// see `header_checkpoints` in ckb.toml.
+    ///
+    /// ## Params
+    ///
+    /// * `interval` - The number of blocks between two checkpoints, the checkpoints are at the
+    /// block numbers which are multiples of it. Default is 1000.
+    /// * `count` - The number of the most recent checkpoints, at most 100. Default is 10.
+    ///
+    /// ## Returns
+    ///
+    /// The checkpoints are in ascending order of the block numbers. There are fewer checkpoints
+    /// than `count` when the chain is not long enough.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_header_checkpoints",
+    ///   "params": [
+    ///     "0x3e8",
+    ///     "0x2"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "checkpoints": [
+    ///       {
+    ///         "epoch": "0x7080014000001",
+    ///         "hash": "0xd0ab04c43f2d0dcb3a3b4bb0cd5d1e7eb0bd7f8f48c8e4a3e5d1b05c6ad5a7a3",
+    ///         "number": "0x3e8",
+    ///         "total_difficulty": "0x3e8f4240"
+    ///       },
+    ///       {
+    ///         "epoch": "0x70800a8000002",
+    ///         "hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
+    ///         "number": "0x7d0",
+    ///         "total_difficulty": "0x7d1e8480"
+    ///       }
+    ///     ],
+    ///     "genesis_hash": "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed",
+    ///     "node_id": "QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+    ///     "public_key": "0x02f21e0cdbab9e5b6e7eb8a8f4e8b0cad4e6b5d12a5eb2da3ef6b2ac8a2f64dbb6",
+    ///     "signature": "0x4d2e1e8b1a1e38a2c07e83c1c7a7e8b1e5d53b1c2bfa7c0e43b5d1e0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d501"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_header_checkpoints")]
+    fn get_header_checkpoints(
+        &self,
+        interval: Option<BlockNumber>,
+        count: Option<Uint32>,
+    ) -> Result<SignedHeaderCheckpoints>;
 }
 
 #[derive(Clone)]
@@ -548,41 +895,12 @@ pub(crate) struct NetRpcImpl {
     pub network_controller: NetworkController,
     pub sync_shared: Arc<SyncShared>,
     pub chain_controller: Arc<ChainController>,
+    pub checkpoint_signer: Arc<HeaderCheckpointSigner>,
 }
 
-#[async_trait]
-impl NetRpc for NetRpcImpl {
-    fn local_node_info(&self) -> Result<LocalNode> {
-        Ok(LocalNode {
-            version: self.network_controller.version().to_owned(),
-            node_id: self.network_controller.node_id(),
-            active: self.network_controller.is_active(),
-            addresses: self
-                .network_controller
-                .public_urls(MAX_ADDRS)
-                .into_iter()
-                .map(|(address, score)| NodeAddress {
-                    address,
-                    score: u64::from(score).into(),
-                })
-                .collect(),
-            protocols: self
-                .network_controller
-                .protocols()
-                .into_iter()
-                .map(|(protocol_id, name, support_versions)| LocalNodeProtocol {
-                    id: (protocol_id.value() as u64).into(),
-                    name,
-                    support_versions,
-                })
-                .collect::<Vec<_>>(),
-            connections: (self.network_controller.connected_peers().len() as u64).into(),
-        })
-    }
-
-    fn get_peers(&self) -> Result<Vec<RemoteNode>> {
-        let peers: Vec<RemoteNode> = self
-            .network_controller
+impl NetRpcImpl {
+    fn remote_nodes(&self) -> Vec<RemoteNode> {
+        self.network_controller
             .connected_peers()
             .iter()
             .map(|(peer_index, peer)| {
@@ -606,6 +924,12 @@ impl NetRpc for NetRpcImpl {
                     .collect();
 
                 let inflight_blocks = self.sync_shared.state().read_inflight_blocks();
+                let traffic = self.network_controller.peer_traffic(*peer_index);
+                let flags = peer
+                    .identify_info
+                    .as_ref()
+                    .map(|info| flag_names(info.flags))
+                    .unwrap_or_default();
                 RemoteNode {
                     is_outbound: peer.is_outbound(),
                     version: peer
@@ -655,16 +979,120 @@ impl NetRpc for NetRpcImpl {
                     protocols: peer
                         .protocols
                         .iter()
-                        .map(|(protocol_id, protocol_version)| RemoteNodeProtocol {
-                            id: (protocol_id.value() as u64).into(),
-                            version: protocol_version.clone(),
+                        .map(|(protocol_id, protocol_version)| {
+                            let traffic = traffic.get(protocol_id).copied().unwrap_or_default();
+                            RemoteNodeProtocol {
+                                id: (protocol_id.value() as u64).into(),
+                                version: protocol_version.clone(),
+                                bytes_sent: traffic.bytes_sent.into(),
+                                bytes_received: traffic.bytes_received.into(),
+                            }
                         })
                         .collect(),
+                    flags: flags.into_iter().map(ToString::to_string).collect(),
+                    features: peer
+                        .features()
+                        .names()
+                        .into_iter()
+                        .map(ToString::to_string)
+                        .collect(),
                 }
             })
-            .collect();
+            .collect()
+    }
+}
 
-        Ok(peers)
+#[async_trait]
+impl NetRpc for NetRpcImpl {
+    fn local_node_info(&self) -> Result<LocalNode> {
+        Ok(LocalNode {
+            version: self.network_controller.version().to_owned(),
+            node_id: self.network_controller.node_id(),
+            active: self.network_controller.is_active(),
+            addresses: self
+                .network_controller
+                .public_urls(MAX_ADDRS)
+                .into_iter()
+                .map(|(address, score)| NodeAddress {
+                    address,
+                    score: u64::from(score).into(),
+                })
+                .collect(),
+            protocols: self
+                .network_controller
+                .protocols()
+                .into_iter()
+                .map(|(protocol_id, name, support_versions)| LocalNodeProtocol {
+                    id: (protocol_id.value() as u64).into(),
+                    name,
+                    support_versions,
+                })
+                .collect::<Vec<_>>(),
+            connections: (self.network_controller.connected_peers().len() as u64).into(),
+            outbound_targets: {
+                let targets = self.network_controller.outbound_targets();
+                OutboundTargets {
+                    sync: targets.sync.into(),
+                    relay: targets.relay.into(),
+                    bandwidth_usage: targets.bandwidth_usage.map(Into::into),
+                }
+            },
+            listeners: self
+                .network_controller
+                .listeners()
+                .into_iter()
+                .map(|listener| LocalNodeListener {
+                    address: listener.address.to_string(),
+                    listened_address: listener.listened_address.map(|addr| addr.to_string()),
+                    max_inbound_peers: listener.max_inbound_peers.map(Into::into),
+                    advertise: listener.advertise,
+                    inbound_peers: listener.inbound_peers.into(),
+                })
+                .collect(),
+            reachability: {
+                let reachability = self.network_controller.reachability();
+                NodeReachability {
+                    status: match reachability.status {
+                        ckb_network::ReachabilityStatus::Unknown => ReachabilityStatus::Unknown,
+                        ckb_network::ReachabilityStatus::Reachable => ReachabilityStatus::Reachable,
+                        ckb_network::ReachabilityStatus::Unreachable => {
+                            ReachabilityStatus::Unreachable
+                        }
+                    },
+                    reachable_addresses: reachability
+                        .reachable_addresses
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                    unreachable_addresses: reachability
+                        .unreachable_addresses
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                    last_checked: reachability.last_checked.map(Into::into),
+                    last_public_inbound: reachability.last_public_inbound.map(Into::into),
+                }
+            },
+        })
+    }
+
+    fn get_peers(&self) -> Result<Vec<RemoteNode>> {
+        Ok(self.remote_nodes())
+    }
+
+    fn list_peers(
+        &self,
+        limit: Uint32,
+        after: Option<JsonBytes>,
+    ) -> Result<IndexerPagination<RemoteNode>> {
+        let limit = check_limit(limit, MAX_PAGINATION_LIMIT).map_err(RPCError::invalid_params)?;
+        Ok(paginate_by_key(
+            self.remote_nodes(),
+            |peer| peer.node_id.as_bytes().to_vec(),
+            IndexerOrder::Asc,
+            limit,
+            after,
+        ))
     }
 
     fn get_banned_addresses(&self) -> Result<Vec<BannedAddr>> {
@@ -737,6 +1165,32 @@ impl NetRpc for NetRpcImpl {
             u128::from_le_bytes(min_chain_work_500k_u128)
         };
         let unverified_tip = shared.get_unverified_tip();
+        let assume_valid = shared
+            .assume_valid_target_specified()
+            .as_ref()
+            .as_ref()
+            .map(|target| {
+                let target_hash: packed::Byte32 = target.pack();
+                let target_number = shared
+                    .header_map()
+                    .get(&target_hash)
+                    .map(|header| header.number())
+                    .or_else(|| {
+                        shared
+                            .store()
+                            .get_block_header(&target_hash)
+                            .map(|header| header.number())
+                    });
+                let resumed = shared.full_verification_resumed();
+                AssumeValidState {
+                    target: target.clone(),
+                    target_number: target_number.map(Into::into),
+                    active: shared.assume_valid_target().is_some(),
+                    verified_number: chain.tip_number().into(),
+                    full_verification_resumed_number: resumed.map(|(number, _)| number.into()),
+                    full_verification_resumed_at: resumed.map(|(_, at)| at.into()),
+                }
+            });
         let sync_state = SyncState {
             ibd: chain.is_initial_block_download(),
             assume_valid_target_reached: shared.assume_valid_target().is_none(),
@@ -747,6 +1201,7 @@ impl NetRpc for NetRpcImpl {
                 .unwrap_or_default()
                 .pack()
                 .into(),
+            assume_valid,
             min_chain_work: min_chain_work.into(),
             min_chain_work_reached: state.min_chain_work_ready(),
             best_known_block_number: best_known.number().into(),
@@ -789,8 +1244,122 @@ impl NetRpc for NetRpcImpl {
         Ok(())
     }
 
+    async fn dial_peer(&self, address: String) -> Result<DialPeerResult> {
+        let multiaddr: Multiaddr = address
+            .parse()
+            .map_err(|err| RPCError::invalid_params(format!("invalid address {address}: {err}")))?;
+        let started_at = Instant::now();
+        let receiver = self.network_controller.dial_peer(multiaddr);
+        let (status, message) = match tokio::time::timeout(DIAL_PEER_TIMEOUT, receiver).await {
+            Ok(Ok(DialOutcome::Connected)) => (DialStatus::Connected, None),
+            Ok(Ok(DialOutcome::Banned)) => (DialStatus::Banned, None),
+            Ok(Ok(DialOutcome::HandshakeFailed(message))) => {
+                (DialStatus::HandshakeFailed, Some(message))
+            }
+            Ok(Ok(DialOutcome::Failed(message))) => (DialStatus::Failed, Some(message)),
+            Ok(Err(_)) => (
+                DialStatus::Failed,
+                Some("the network service is stopped".to_owned()),
+            ),
+            Err(_) => (DialStatus::Timeout, None),
+        };
+        Ok(DialPeerResult {
+            address,
+            status,
+            message,
+            duration: (started_at.elapsed().as_millis() as u64).into(),
+        })
+    }
+
     fn ping_peers(&self) -> Result<()> {
         self.network_controller.ping_peers();
         Ok(())
     }
+
+    fn get_bootnodes_health(&self) -> Result<Vec<BootnodeHealth>> {
+        Ok(self
+            .network_controller
+            .bootnodes_health()
+            .into_iter()
+            .map(|health| {
+                let status = if health.is_unhealthy() {
+                    BootnodeStatus::Unhealthy
+                } else if health.last_success.is_some() {
+                    BootnodeStatus::Healthy
+                } else {
+                    BootnodeStatus::Unknown
+                };
+                BootnodeHealth {
+                    address: health.address.to_string(),
+                    set: health.set,
+                    weight: health.weight.into(),
+                    status,
+                    consecutive_failures: health.consecutive_failures.into(),
+                    last_success: health.last_success.map(Into::into),
+                    last_failure: health.last_failure.map(Into::into),
+                    last_checked: health.last_checked.map(Into::into),
+                }
+            })
+            .collect())
+    }
+
+    fn get_peer_misbehaviors(&self) -> Result<Vec<PeerMisbehavior>> {
+        let ban_threshold = self.network_controller.misbehavior_ban_threshold();
+        // the scores are keyed by the peer ID, and kept after the peer disconnects
+        let peers: HashMap<_, _> = self
+            .network_controller
+            .connected_peers()
+            .into_iter()
+            .filter_map(|(_, peer)| Some((extract_peer_id(&peer.connected_addr)?, peer)))
+            .collect();
+        Ok(self
+            .network_controller
+            .peer_misbehaviors()
+            .into_iter()
+            .filter_map(|(peer_id, misbehavior)| {
+                let peer = peers.get(&peer_id)?;
+                Some(PeerMisbehavior {
+                    node_id: peer_id.to_base58(),
+                    address: peer.connected_addr.to_string(),
+                    score: (misbehavior.score.round() as u64).into(),
+                    ban_threshold: ban_threshold.into(),
+                    components: misbehavior
+                        .components
+                        .into_iter()
+                        .map(|component| MisbehaviorComponent {
+                            kind: component.kind,
+                            score: (component.score.round() as u64).into(),
+                            count: component.count.into(),
+                            last_reason: component.last_reason,
+                            last_reported_at: component.last_reported_at.into(),
+                        })
+                        .collect(),
+                })
+            })
+            .collect())
+    }
+
+    fn get_header_checkpoints(
+        &self,
+        interval: Option<BlockNumber>,
+        count: Option<Uint32>,
+    ) -> Result<SignedHeaderCheckpoints> {
+        let interval = interval.map_or(DEFAULT_CHECKPOINT_INTERVAL, Into::into);
+        if interval == 0 {
+            return Err(RPCError::invalid_params("interval must be greater than 0"));
+        }
+        let count = count.map_or(DEFAULT_CHECKPOINT_COUNT, |count| count.value() as usize);
+        if count > MAX_HEADER_CHECKPOINTS {
+            return Err(RPCError::invalid_params(format!(
+                "count must not be greater than {MAX_HEADER_CHECKPOINTS}"
+            )));
+        }
+        let snapshot = self.sync_shared.shared().snapshot();
+        let checkpoints = collect_header_checkpoints(&snapshot, interval, count);
+        let genesis_hash = self.sync_shared.shared().genesis_hash();
+        Ok(self
+            .checkpoint_signer
+            .sign(genesis_hash, checkpoints)
+            .into())
+    }
 }
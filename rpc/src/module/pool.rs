@@ -1,19 +1,32 @@
 use crate::error::RPCError;
+use crate::util::{IdempotencyCache, MAX_PAGINATION_LIMIT};
 use async_trait::async_trait;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_constant::hardfork::{mainnet, testnet};
+use ckb_jsonrpc_types::pagination::{check_limit, paginate_by_key};
 use ckb_jsonrpc_types::{
-    EntryCompleted, OutputsValidator, PoolTxDetailInfo, RawTxPool, Script, Transaction, TxPoolInfo,
+    EntryCompleted, IndexerOrder, IndexerPagination, JsonBytes, OutputsValidator, PoolPolicy,
+    PoolTxDetailInfo, PoolTxGraph, ProposalWindowSimulation, RawTxPool, ReplacementSimulation,
+    Script, TemplateBacklog, Transaction, TransactionOrHash, TxDiagnosis, TxFeeBreakdown,
+    TxPoolInfo, Uint32, Uint64,
 };
 use ckb_logger::error;
 use ckb_shared::shared::Shared;
+use ckb_store::ChainStore;
+use ckb_systemtime::unix_time_as_millis;
+use ckb_tx_pool::MAX_FEE_ESTIMATE_TARGET_BLOCKS;
 use ckb_types::core::TransactionView;
 use ckb_types::{core, packed, prelude::*, H256};
 use ckb_verification::{Since, SinceMetric};
 use jsonrpc_core::Result;
 use jsonrpc_utils::rpc;
+use std::collections::HashSet;
 use std::sync::Arc;
 
+const DEFAULT_POOL_TX_GRAPH_DEPTH: u32 = 32;
+const MAX_POOL_TX_GRAPH_DEPTH: u32 = 256;
+const MAX_TRANSACTION_PACKAGE_SIZE: usize = 25;
+
 /// RPC Module Pool for transaction memory pool.
 #[rpc(openrpc)]
 #[async_trait]
@@ -29,12 +42,24 @@ pub trait PoolRpc {
     ///
     /// * `transaction` - The transaction.
     /// * `outputs_validator` - Validates the transaction outputs before entering the tx-pool. (**Optional**, default is "passthrough").
+    /// * `idempotency_key` - A client-supplied key of 1 to 128 bytes to retry the request safely. (**Optional**)
+    /// * `allow_high_fees` - Submits the transaction even if its fee exceeds the config option `tx_pool.max_absolute_fee` or `tx_pool.max_fee_rate`. (**Optional**, default is false)
+    ///
+    /// When `idempotency_key` is set, the node caches the result of the first request with the key,
+    /// no matter the transaction is accepted or rejected, and returns it to the retries with the
+    /// same key, transaction and `allow_high_fees` within
+    /// `rpc.send_transaction_idempotency_window_secs` seconds. The transaction is submitted again
+    /// if the first request failed with an internal error.
     ///
     /// ## Errors
     ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - The idempotency key is too long, or it is used by another transaction within the window.
+    /// * [`PoolRejectedAbsurdFee (-1113)`](../enum.RPCError.html#variant.PoolRejectedAbsurdFee) - The transaction fee exceeds the config option `tx_pool.max_absolute_fee` or `tx_pool.max_fee_rate`, and `allow_high_fees` is not set.
     /// * [`PoolRejectedTransactionByOutputsValidator (-1102)`](../enum.RPCError.html#variant.PoolRejectedTransactionByOutputsValidator) - The transaction is rejected by the validator specified by `outputs_validator`. If you really want to send transactions with advanced scripts, please set `outputs_validator` to "passthrough".
     /// * [`PoolRejectedTransactionByMinFeeRate (-1104)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMinFeeRate) - The transaction fee rate must be greater than or equal to the config option `tx_pool.min_fee_rate`.
     /// * [`PoolRejectedTransactionByMaxAncestorsCountLimit (-1105)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMaxAncestorsCountLimit) - The ancestors count must be greater than or equal to the config option `tx_pool.max_ancestors_count`.
+    /// * [`PoolRejectedScriptGroupCycles (-1114)`](../enum.RPCError.html#variant.PoolRejectedScriptGroupCycles) - A script group consumes more cycles than the config option `tx_pool.max_script_group_cycles`.
+    /// * [`PoolRejectedRecentHeaderDep (-1115)`](../enum.RPCError.html#variant.PoolRejectedRecentHeaderDep) - A header dep is less than the config option `tx_pool.min_header_dep_depth` blocks below the tip.
     /// * [`PoolIsFull (-1106)`](../enum.RPCError.html#variant.PoolIsFull) - Pool is full.
     /// * [`PoolRejectedDuplicatedTransaction (-1107)`](../enum.RPCError.html#variant.PoolRejectedDuplicatedTransaction) - The transaction is already in the pool.
     /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to resolve the referenced cells and headers used in the transaction, as inputs or dependencies.
@@ -108,6 +133,8 @@ pub trait PoolRpc {
         &self,
         tx: Transaction,
         outputs_validator: Option<OutputsValidator>,
+        idempotency_key: Option<String>,
+        allow_high_fees: Option<bool>,
     ) -> Result<H256>;
 
     /// Test if a transaction can be accepted by the transaction pool without inserting it into the pool or rebroadcasting it to peers.
@@ -123,6 +150,8 @@ pub trait PoolRpc {
     /// * [`PoolRejectedTransactionByOutputsValidator (-1102)`](../enum.RPCError.html#variant.PoolRejectedTransactionByOutputsValidator) - The transaction is rejected by the validator specified by `outputs_validator`. If you really want to send transactions with advanced scripts, please set `outputs_validator` to "passthrough".
     /// * [`PoolRejectedTransactionByMinFeeRate (-1104)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMinFeeRate) - The transaction fee rate must be greater than or equal to the config option `tx_pool.min_fee_rate`.
     /// * [`PoolRejectedTransactionByMaxAncestorsCountLimit (-1105)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMaxAncestorsCountLimit) - The ancestors count must be greater than or equal to the config option `tx_pool.max_ancestors_count`.
+    /// * [`PoolRejectedScriptGroupCycles (-1114)`](../enum.RPCError.html#variant.PoolRejectedScriptGroupCycles) - A script group consumes more cycles than the config option `tx_pool.max_script_group_cycles`.
+    /// * [`PoolRejectedRecentHeaderDep (-1115)`](../enum.RPCError.html#variant.PoolRejectedRecentHeaderDep) - A header dep is less than the config option `tx_pool.min_header_dep_depth` blocks below the tip.
     /// * [`PoolIsFull (-1106)`](../enum.RPCError.html#variant.PoolIsFull) - Pool is full.
     /// * [`PoolRejectedDuplicatedTransaction (-1107)`](../enum.RPCError.html#variant.PoolRejectedDuplicatedTransaction) - The transaction is already in the pool.
     /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to resolve the referenced cells and headers used in the transaction, as inputs or dependencies.
@@ -217,6 +246,110 @@ pub trait PoolRpc {
         outputs_validator: Option<OutputsValidator>,
     ) -> Result<EntryCompleted>;
 
+    /// Submits a package of dependent transactions into the transaction pool, either all of them
+    /// are accepted or none is.
+    ///
+    /// The transactions are ordered, a transaction can spend or depend on the outputs of the
+    /// transactions before it in the package, so a wallet can submit a chain of transactions at
+    /// once without waiting for the parents to enter the pool. Each transaction is checked the
+    /// same way as `send_transaction`, and the package is rejected if any of them is invalid.
+    /// The transactions must not conflict with the pool, they can't replace the pool
+    /// transactions by RBF.
+    ///
+    /// Unlike `send_transaction`, the transactions are verified before this method returns, and
+    /// they are relayed to peers one by one after they are accepted.
+    ///
+    /// ## Params
+    ///
+    /// * `transactions` - The transactions in the package, at most 25 of them.
+    /// * `outputs_validator` - Validates the outputs of every transaction before entering the tx-pool. (**Optional**, default is "passthrough").
+    /// * `allow_high_fees` - Submits the transactions even if their fees exceed the config option `tx_pool.max_absolute_fee` or `tx_pool.max_fee_rate`. (**Optional**, default is false)
+    ///
+    /// ## Returns
+    ///
+    /// The hashes of the transactions in the package.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - The package is empty or has more than 25 transactions.
+    /// * [`PoolRejectedTransactionByOutputsValidator (-1102)`](../enum.RPCError.html#variant.PoolRejectedTransactionByOutputsValidator) - A transaction is rejected by the validator specified by `outputs_validator`.
+    /// * [`PoolRejectedPackage (-1116)`](../enum.RPCError.html#variant.PoolRejectedPackage) - The package is rejected. The error data lists the reasons of the invalid transactions, a transaction which is valid by itself is still rejected with the package.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "send_transaction_package",
+    ///   "params": [
+    ///     [
+    ///       {
+    ///         "cell_deps": [
+    ///           {
+    ///             "dep_type": "code",
+    ///             "out_point": {
+    ///               "index": "0x0",
+    ///               "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///             }
+    ///           }
+    ///         ],
+    ///         "header_deps": [
+    ///           "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed"
+    ///         ],
+    ///         "inputs": [
+    ///           {
+    ///             "previous_output": {
+    ///               "index": "0x0",
+    ///               "tx_hash": "0x365698b50ca0da75dca2c87f9e7b563811d3b5813736b8cc62cc3b106faceb17"
+    ///             },
+    ///             "since": "0x0"
+    ///           }
+    ///         ],
+    ///         "outputs": [
+    ///           {
+    ///             "capacity": "0x2540be400",
+    ///             "lock": {
+    ///               "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///               "hash_type": "data",
+    ///               "args": "0x"
+    ///             },
+    ///             "type": null
+    ///           }
+    ///         ],
+    ///         "outputs_data": [
+    ///           "0x"
+    ///         ],
+    ///         "version": "0x0",
+    ///         "witnesses": []
+    ///       }
+    ///     ],
+    ///     "passthrough"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "send_transaction_package")]
+    fn send_transaction_package(
+        &self,
+        transactions: Vec<Transaction>,
+        outputs_validator: Option<OutputsValidator>,
+        allow_high_fees: Option<bool>,
+    ) -> Result<Vec<H256>>;
+
     /// Removes a transaction and all transactions which depends on it from tx pool if it exists.
     ///
     /// ## Params
@@ -283,18 +416,81 @@ pub trait PoolRpc {
     ///     "orphan": "0x0",
     ///     "pending": "0x1",
     ///     "proposed": "0x0",
+    ///     "rbf_replaced_fees": "0x0",
     ///     "tip_hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
     ///     "tip_number": "0x400",
+    ///     "top_lock_scripts": [
+    ///       {
+    ///         "fees": "0x1dcd6500",
+    ///         "lock_hash": "0x4f3a4a3a5e57b8e5d3a5c2bd1b3c4a9c8b0f6e1e57f2e1b8d3a2c0b4e9d8f7a6",
+    ///         "tx_count": "0x1",
+    ///         "tx_size": "0x112"
+    ///       }
+    ///     ],
     ///     "total_tx_cycles": "0x219",
     ///     "total_tx_size": "0x112",
     ///     "tx_size_limit": "0x7d000",
-    ///     "verify_queue_size": "0x0"
+    ///     "verify_queue_cycles": "0x0",
+    ///     "verify_queue_size": "0x0",
+    ///     "verify_queue_tx_size": "0x0"
     ///   }
     /// }
     /// ```
     #[rpc(name = "tx_pool_info")]
     fn tx_pool_info(&self) -> Result<TxPoolInfo>;
 
+    /// Returns the effective relay and admission policy of the transaction pool.
+    ///
+    /// The fee rates, the ancestors count limit, the pool size limit and the RBF replacement
+    /// limits are reloaded from the config file when the node receives `SIGHUP`. The `version`
+    /// increases every time the reload changes the policy, so clients can cache the policy and
+    /// refresh it when the version changes.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_pool_policy",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "max_absolute_fee": null,
+    ///     "max_ancestors_count": "0x7d0",
+    ///     "max_fee_rate": null,
+    ///     "max_rbf_candidates": "0x64",
+    ///     "max_rbf_conflict_descendants": "0x64",
+    ///     "max_rbf_replacements": "0x1e",
+    ///     "max_script_group_cycles": null,
+    ///     "max_tx_pool_size": "0xaba9500",
+    ///     "max_tx_verify_cycles": "0x42c1d80",
+    ///     "min_fee_rate": "0x3e8",
+    ///     "min_header_dep_depth": null,
+    ///     "min_rbf_rate": "0x5dc",
+    ///     "rbf_free_replacements": "0xa",
+    ///     "rbf_replace_gap": true,
+    ///     "rbf_replace_proposed": true,
+    ///     "rbf_replaced_fees_cap": null,
+    ///     "rbf_replacement_window_secs": "0x258",
+    ///     "tx_size_limit": "0x7d000",
+    ///     "version": "0x0"
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "get_pool_policy")]
+    fn get_pool_policy(&self) -> Result<PoolPolicy>;
+
     /// Removes all transactions from the transaction pool.
     ///
     /// ## Examples
@@ -413,6 +609,575 @@ pub trait PoolRpc {
     #[rpc(name = "get_pool_tx_detail_info")]
     fn get_pool_tx_detail_info(&self, tx_hash: H256) -> Result<PoolTxDetailInfo>;
 
+    /// Returns the in-pool ancestors and descendants of a transaction, with the links between
+    /// them, which is useful to debug the packaging of chained transactions.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hash` - Hash of a transaction in the pool.
+    /// * `max_depth` - The maximum distance in links from the transaction to the returned
+    ///   ancestors and descendants, default is 32 and the maximum is 256.
+    ///
+    /// ## Returns
+    ///
+    /// Returns null when the transaction is not in the pool. The field `truncated` is true when
+    /// some ancestors or descendants are beyond `max_depth`.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_pool_tx_graph",
+    ///   "params": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3",
+    ///     "0x20"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "edges": [],
+    ///     "nodes": [
+    ///       {
+    ///         "cycles": "0x219",
+    ///         "depth": "0x0",
+    ///         "fee": "0x16923f7dcf",
+    ///         "relation": "root",
+    ///         "size": "0x112",
+    ///         "status": "pending",
+    ///         "timestamp": "0x18aa1baa54c",
+    ///         "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///       }
+    ///     ],
+    ///     "truncated": false,
+    ///     "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   },
+    ///   "id": 42
+    /// }
+    /// ```
+    #[rpc(name = "get_pool_tx_graph")]
+    fn get_pool_tx_graph(
+        &self,
+        tx_hash: H256,
+        max_depth: Option<Uint32>,
+    ) -> Result<Option<PoolTxGraph>>;
+
+    /// Explains why a transaction is not progressing.
+    ///
+    /// The diagnosis is computed from the pool, the verify queue and the recent rejects. An
+    /// issue is reported when:
+    ///
+    /// * `below_fee_floor` - The fee rate is below `min_fee_rate`.
+    /// * `low_rank` - The transaction is pending but ranked beyond the proposals limit of a block.
+    /// * `too_many_ancestors` - The in-pool ancestors reach `max_ancestors_count`.
+    /// * `conflicts` - Other transactions in the verify queue or the conflicts pool spend the same
+    ///   inputs.
+    /// * `verifying` - The transaction is waiting in the verify queue.
+    /// * `expires_soon` - The transaction will be removed for expiry within an hour.
+    /// * `rejected` - The transaction was rejected recently.
+    ///
+    /// ## Params
+    ///
+    /// * `tx_hash` - Hash of a transaction.
+    ///
+    /// ## Returns
+    ///
+    /// The status is `unknown` and there is no issue if the transaction is neither in the pool
+    /// nor rejected recently, e.g., it is committed.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "diagnose_transaction",
+    ///   "params": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "ancestors_count": "0x0",
+    ///     "expires_at": "0x18aa1e9c54c",
+    ///     "fee_rate": "0x52607f1408",
+    ///     "issues": [],
+    ///     "rank_in_pending": "0x0",
+    ///     "status": "pending",
+    ///     "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   },
+    ///   "id": 42
+    /// }
+    /// ```
+    #[rpc(name = "diagnose_transaction")]
+    fn diagnose_transaction(&self, tx_hash: H256) -> Result<TxDiagnosis>;
+
+    /// Breaks down the capacities and the fee of a transaction, with the fee thresholds of this
+    /// node.
+    ///
+    /// The fee is computed the same way as the pool, including the compensation of the withdrawn
+    /// DAO cells, so wallets don't need to re-implement it.
+    ///
+    /// ## Params
+    ///
+    /// * `tx` - The transaction, or the hash of a transaction in the pool or the chain.
+    ///
+    /// ## Returns
+    ///
+    /// The input cells are looked up in the pool and the chain, no matter whether they are live,
+    /// so a committed transaction can be broken down as well. The field `fee_rate` is computed
+    /// by the serialized size, which is what the pool checks against `min_fee_rate`.
+    ///
+    /// ## Errors
+    ///
+    /// * [`InvalidParams (-32602)`](../enum.RPCError.html#variant.InvalidParams) - The transaction hash is neither in the pool nor in the chain.
+    /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to find an input cell.
+    /// * [`PoolRejectedMalformedTransaction (-1108)`](../enum.RPCError.html#variant.PoolRejectedMalformedTransaction) - The outputs capacity exceeds the inputs capacity.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_transaction_fee_breakdown",
+    ///   "params": [
+    ///     "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "fee": "0x16923f7dcf",
+    ///     "fee_rate": "0x52607f1408",
+    ///     "inputs": [
+    ///       {
+    ///         "capacity": "0x18e64b61cf",
+    ///         "occupied_capacity": "0xf4610900"
+    ///       }
+    ///     ],
+    ///     "inputs_capacity": "0x18e64b61cf",
+    ///     "min_fee": "0x112",
+    ///     "min_fee_rate": "0x3e8",
+    ///     "min_rbf_rate": "0x5dc",
+    ///     "min_replace_fee": null,
+    ///     "outputs": [
+    ///       {
+    ///         "capacity": "0x2540be400",
+    ///         "occupied_capacity": "0xf4610900"
+    ///       }
+    ///     ],
+    ///     "outputs_capacity": "0x2540be400",
+    ///     "size": "0x112",
+    ///     "tx_hash": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///   },
+    ///   "id": 42
+    /// }
+    /// ```
+    #[rpc(name = "get_transaction_fee_breakdown")]
+    fn get_transaction_fee_breakdown(&self, tx: TransactionOrHash) -> Result<TxFeeBreakdown>;
+
+    /// Simulates the replacement of the conflicting transactions in the pool by a transaction,
+    /// without submitting it.
+    ///
+    /// It tells wallets which transactions would be evicted by the replacement and whether each
+    /// RBF rule passes, before they sign and send a fee bump.
+    ///
+    /// ## Params
+    ///
+    /// * `tx` - The replacement transaction.
+    ///
+    /// ## Returns
+    ///
+    /// The RBF rules are checked against the pool as it is now, the same way as `send_transaction`,
+    /// and a failed rule doesn't stop checking the rest. The scripts are not verified. The
+    /// `conflicts`, `evicted` and `rules` are empty if the transaction conflicts with nothing in
+    /// the pool.
+    ///
+    /// ## Errors
+    ///
+    /// * [`PoolRejectedRBF (-1111)`](../enum.RPCError.html#variant.PoolRejectedRBF) - RBF is disabled.
+    /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to find an input cell.
+    /// * [`PoolRejectedMalformedTransaction (-1108)`](../enum.RPCError.html#variant.PoolRejectedMalformedTransaction) - The outputs capacity exceeds the inputs capacity.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "simulate_replacement",
+    ///   "params": [
+    ///     {
+    ///       "cell_deps": [
+    ///         {
+    ///           "dep_type": "code",
+    ///           "out_point": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///           }
+    ///         }
+    ///       ],
+    ///       "header_deps": [
+    ///         "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed"
+    ///       ],
+    ///       "inputs": [
+    ///         {
+    ///           "previous_output": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0x075fe030c1f4725713c5aacf41c2f59b29b284008fdb786e5efd8a058be51d0c"
+    ///           },
+    ///           "since": "0x0"
+    ///         }
+    ///       ],
+    ///       "outputs": [
+    ///         {
+    ///           "capacity": "0x2431ac129",
+    ///           "lock": {
+    ///             "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///             "hash_type": "data",
+    ///             "args": "0x"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       ],
+    ///       "outputs_data": [
+    ///         "0x"
+    ///       ],
+    ///       "version": "0x0",
+    ///       "witnesses": []
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "conflicts": [
+    ///       "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///     ],
+    ///     "evicted": [
+    ///       "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///     ],
+    ///     "evicted_fee": "0x16923f7dcf",
+    ///     "fee": "0x2a66f36e90",
+    ///     "min_replace_fee": "0x16923f7f6a",
+    ///     "replaceable": true,
+    ///     "rules": [
+    ///       {
+    ///         "passed": true,
+    ///         "reason": null,
+    ///         "rule": "no_new_unconfirmed_inputs"
+    ///       },
+    ///       {
+    ///         "passed": true,
+    ///         "reason": null,
+    ///         "rule": "replaced_txs"
+    ///       },
+    ///       {
+    ///         "passed": true,
+    ///         "reason": null,
+    ///         "rule": "replacement_churn"
+    ///       },
+    ///       {
+    ///         "passed": true,
+    ///         "reason": null,
+    ///         "rule": "min_replace_fee"
+    ///       }
+    ///     ],
+    ///     "size": "0x112",
+    ///     "tx_hash": "0x5f8f4cd4b1ad8dc3c1a7eb2c5b5db7a2ba6bd8e5d5c4e1c1c7b3eb7e0ea6b20f"
+    ///   },
+    ///   "id": 42
+    /// }
+    /// ```
+    #[rpc(name = "simulate_replacement")]
+    fn simulate_replacement(&self, tx: Transaction) -> Result<ReplacementSimulation>;
+
+    /// Simulates when a transaction could be proposed and committed if it were submitted now,
+    /// without submitting it.
+    ///
+    /// CKB commits a transaction in two phases, it's proposed in a block first and committed in a
+    /// block of the proposal window later. It helps wallets tell the users how long the
+    /// transaction is expected to wait, by its rank among the pending transactions.
+    ///
+    /// ## Params
+    ///
+    /// * `tx` - The candidate transaction.
+    ///
+    /// ## Returns
+    ///
+    /// The transaction is ranked by the fee rate with its in-pool ancestors, against the pending
+    /// transactions in the pool as it is now, and it can't be proposed before its pending
+    /// ancestors. The scripts are not verified, and the new transactions paying more push it back.
+    ///
+    /// ## Errors
+    ///
+    /// * [`PoolRejectedDuplicatedTransaction (-1107)`](../enum.RPCError.html#variant.PoolRejectedDuplicatedTransaction) - The transaction is already in the pool.
+    /// * [`PoolRejectedTransactionByMinFeeRate (-1104)`](../enum.RPCError.html#variant.PoolRejectedTransactionByMinFeeRate) - The transaction fee rate is lower than `min_fee_rate`.
+    /// * [`TransactionFailedToResolve (-301)`](../enum.RPCError.html#variant.TransactionFailedToResolve) - Failed to find an input cell.
+    /// * [`PoolRejectedMalformedTransaction (-1108)`](../enum.RPCError.html#variant.PoolRejectedMalformedTransaction) - The outputs capacity exceeds the inputs capacity.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "simulate_proposal_window",
+    ///   "params": [
+    ///     {
+    ///       "cell_deps": [
+    ///         {
+    ///           "dep_type": "code",
+    ///           "out_point": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0xa4037a893eb48e18ed4ef61034ce26eba9c585f15c9cee102ae58505565eccc3"
+    ///           }
+    ///         }
+    ///       ],
+    ///       "header_deps": [
+    ///         "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed"
+    ///       ],
+    ///       "inputs": [
+    ///         {
+    ///           "previous_output": {
+    ///             "index": "0x0",
+    ///             "tx_hash": "0x075fe030c1f4725713c5aacf41c2f59b29b284008fdb786e5efd8a058be51d0c"
+    ///           },
+    ///           "since": "0x0"
+    ///         }
+    ///       ],
+    ///       "outputs": [
+    ///         {
+    ///           "capacity": "0x2431ac129",
+    ///           "lock": {
+    ///             "code_hash": "0x28e83a1277d48add8e72fadaa9248559e1b632bab2bd60b27955ebc4c03800a5",
+    ///             "hash_type": "data",
+    ///             "args": "0x"
+    ///           },
+    ///           "type": null
+    ///         }
+    ///       ],
+    ///       "outputs_data": [
+    ///         "0x"
+    ///       ],
+    ///       "version": "0x0",
+    ///       "witnesses": []
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "earliest_commit_block": "0x403",
+    ///     "earliest_proposal_block": "0x401",
+    ///     "fee_rate": "0x9ac09bf0f1",
+    ///     "latest_commit_block": "0x40b",
+    ///     "proposals_limit": "0x5dc",
+    ///     "rank": "0x0",
+    ///     "tip_number": "0x400",
+    ///     "tx_hash": "0x5f8f4cd4b1ad8dc3c1a7eb2c5b5db7a2ba6bd8e5d5c4e1c1c7b3eb7e0ea6b20f"
+    ///   },
+    ///   "id": 42
+    /// }
+    /// ```
+    #[rpc(name = "simulate_proposal_window")]
+    fn simulate_proposal_window(&self, tx: Transaction) -> Result<ProposalWindowSimulation>;
+
+    /// Estimates the fee rate for a transaction to be committed within the target blocks.
+    ///
+    /// The pool tracks how many blocks the transactions take to be committed since they enter the
+    /// pool, by their fee rates. The estimate is the lowest fee rate, with which at least 85% of
+    /// the recent transactions are committed within the target blocks. The recent blocks weigh
+    /// more, and the transactions staying in the pool longer than the target count as failures.
+    /// It's never lower than the `min_fee_rate` of the pool.
+    ///
+    /// A transaction is proposed first and committed in the proposal window, at least 2 blocks
+    /// later on the mainnet, so a target below 3 blocks is hardly met.
+    ///
+    /// ## Params
+    ///
+    /// * `target_blocks` - The number of blocks, 1 to 128.
+    ///
+    /// ## Returns
+    ///
+    /// The fee rate in shannons per kilo-weight, or null if the pool has not seen enough
+    /// committed transactions to tell, e.g., right after the node starts.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "estimate_fee_rate",
+    ///   "params": [
+    ///     "0x3"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": "0x3e8"
+    /// }
+    /// ```
+    #[rpc(name = "estimate_fee_rate")]
+    fn estimate_fee_rate(&self, target_blocks: Uint64) -> Result<Option<Uint64>>;
+
+    /// Returns the transactions left out of the recent block templates, the latest first.
+    ///
+    /// The block assembler records the totals of the pool transactions which did not fit in the
+    /// block every time it packages the transactions of a template, the latest 32 generations are
+    /// kept. The backlog depth beyond the block limits, in weight and cycles, tells the congestion
+    /// better than the pool size, e.g., a backlog weighing several blocks needs a higher fee rate
+    /// to be committed soon, while a large pool fitting in the next block is not congested at
+    /// all. The latest backlog is also exported as the `ckb_tx_pool_template_backlog` metrics.
+    ///
+    /// The block assembler must be enabled, see `[block_assembler]` in the config.
+    ///
+    /// ## Returns
+    ///
+    /// The backlogs of the recent templates, empty if no template has packaged the transactions
+    /// yet.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_block_template_backlog",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "generated_at": "0x18f3a2b5d88",
+    ///       "number": "0x401",
+    ///       "pending": {
+    ///         "cycles": "0x1c9c380",
+    ///         "fees": "0xf4240",
+    ///         "tx_count": "0x7",
+    ///         "tx_size": "0x9c4",
+    ///         "weight": "0x13fd"
+    ///       },
+    ///       "proposed": {
+    ///         "cycles": "0x2faf0800",
+    ///         "fees": "0x3d0900",
+    ///         "tx_count": "0x2a",
+    ///         "tx_size": "0x3f4a0",
+    ///         "weight": "0x3f4a0"
+    ///       },
+    ///       "work_id": "0x2b"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_block_template_backlog")]
+    fn get_block_template_backlog(&self) -> Result<Vec<TemplateBacklog>>;
+
+    /// Returns the hashes of the pending and proposed transactions in the pool page by page.
+    ///
+    /// The hashes are sorted in ascending order and the cursor is the last returned hash, so a
+    /// client can iterate the whole pool while transactions are being added and removed. A
+    /// transaction is never returned twice, and transactions staying in the pool during the
+    /// iteration are never skipped.
+    ///
+    /// ## Params
+    ///
+    /// * `limit` - The max number of returned transaction hashes, at most 1000.
+    /// * `after` - Pagination parameter, the `last_cursor` returned by the previous page.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "list_pool_tx_hashes",
+    ///   "params": [
+    ///     "0x64"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "last_cursor": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3",
+    ///     "objects": [
+    ///       "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+    ///     ]
+    ///   }
+    /// }
+    /// ```
+    #[rpc(name = "list_pool_tx_hashes")]
+    fn list_pool_tx_hashes(
+        &self,
+        limit: Uint32,
+        after: Option<JsonBytes>,
+    ) -> Result<IndexerPagination<H256>>;
+
     /// Returns whether tx-pool service is started, ready for request.
     ///
     /// ## Examples
@@ -446,6 +1211,7 @@ pub(crate) struct PoolRpcImpl {
     shared: Shared,
     well_known_lock_scripts: Vec<packed::Script>,
     well_known_type_scripts: Vec<packed::Script>,
+    idempotency_cache: IdempotencyCache,
 }
 
 impl PoolRpcImpl {
@@ -453,6 +1219,7 @@ impl PoolRpcImpl {
         shared: Shared,
         mut extra_well_known_lock_scripts: Vec<packed::Script>,
         mut extra_well_known_type_scripts: Vec<packed::Script>,
+        idempotency_window_secs: u64,
     ) -> PoolRpcImpl {
         let mut well_known_lock_scripts =
             build_well_known_lock_scripts(shared.consensus().id.as_str());
@@ -466,6 +1233,7 @@ impl PoolRpcImpl {
             shared,
             well_known_lock_scripts,
             well_known_type_scripts,
+            idempotency_cache: IdempotencyCache::new(idempotency_window_secs),
         }
     }
 
@@ -495,6 +1263,33 @@ impl PoolRpcImpl {
         }
         Ok(())
     }
+
+    fn submit_transaction(
+        &self,
+        outputs_validator: Option<OutputsValidator>,
+        tx: TransactionView,
+        allow_high_fees: bool,
+    ) -> Result<H256> {
+        self.check_output_validator(outputs_validator, &tx)?;
+
+        let tx_pool = self.shared.tx_pool_controller();
+        let submit_tx = if allow_high_fees {
+            tx_pool.submit_local_tx(tx.clone())
+        } else {
+            tx_pool.submit_local_tx_with_fee_check(tx.clone())
+        };
+
+        if let Err(e) = submit_tx {
+            error!("Send submit_tx request error {}", e);
+            return Err(RPCError::ckb_internal_error(e));
+        }
+
+        let tx_hash = tx.hash();
+        match submit_tx.unwrap() {
+            Ok(_) => Ok(tx_hash.unpack()),
+            Err(reject) => Err(RPCError::from_submit_transaction_reject(&reject)),
+        }
+    }
 }
 
 /// Build well known lock scripts
@@ -584,24 +1379,22 @@ impl PoolRpc for PoolRpcImpl {
         &self,
         tx: Transaction,
         outputs_validator: Option<OutputsValidator>,
+        idempotency_key: Option<String>,
+        allow_high_fees: Option<bool>,
     ) -> Result<H256> {
         let tx: packed::Transaction = tx.into();
         let tx: core::TransactionView = tx.into_view();
+        let allow_high_fees = allow_high_fees.unwrap_or(false);
 
-        self.check_output_validator(outputs_validator, &tx)?;
-
-        let tx_pool = self.shared.tx_pool_controller();
-        let submit_tx = tx_pool.submit_local_tx(tx.clone());
-
-        if let Err(e) = submit_tx {
-            error!("Send submit_tx request error {}", e);
-            return Err(RPCError::ckb_internal_error(e));
-        }
-
-        let tx_hash = tx.hash();
-        match submit_tx.unwrap() {
-            Ok(_) => Ok(tx_hash.unpack()),
-            Err(reject) => Err(RPCError::from_submit_transaction_reject(&reject)),
+        match idempotency_key {
+            Some(key) => self.idempotency_cache.send(
+                key,
+                &tx.hash().unpack(),
+                allow_high_fees,
+                unix_time_as_millis(),
+                || self.submit_transaction(outputs_validator, tx, allow_high_fees),
+            ),
+            None => self.submit_transaction(outputs_validator, tx, allow_high_fees),
         }
     }
 
@@ -630,6 +1423,39 @@ impl PoolRpc for PoolRpcImpl {
             })
     }
 
+    fn send_transaction_package(
+        &self,
+        transactions: Vec<Transaction>,
+        outputs_validator: Option<OutputsValidator>,
+        allow_high_fees: Option<bool>,
+    ) -> Result<Vec<H256>> {
+        if transactions.is_empty() || transactions.len() > MAX_TRANSACTION_PACKAGE_SIZE {
+            return Err(RPCError::invalid_params(format!(
+                "a package should have 1 to {MAX_TRANSACTION_PACKAGE_SIZE} transactions"
+            )));
+        }
+        let txs: Vec<core::TransactionView> = transactions
+            .into_iter()
+            .map(|tx| packed::Transaction::from(tx).into_view())
+            .collect();
+        for tx in txs.iter() {
+            self.check_output_validator(outputs_validator.clone(), tx)?;
+        }
+        let tx_hashes: Vec<H256> = txs.iter().map(|tx| tx.hash().unpack()).collect();
+
+        let tx_pool = self.shared.tx_pool_controller();
+        let submit_package = tx_pool
+            .submit_local_package(txs, !allow_high_fees.unwrap_or(false))
+            .map_err(|e| {
+                error!("Send submit_package request error {}", e);
+                RPCError::ckb_internal_error(e)
+            })?;
+
+        submit_package
+            .map(|_| tx_hashes)
+            .map_err(RPCError::from_submit_package_rejects)
+    }
+
     fn remove_transaction(&self, tx_hash: H256) -> Result<bool> {
         let tx_pool = self.shared.tx_pool_controller();
 
@@ -652,6 +1478,14 @@ impl PoolRpc for PoolRpcImpl {
         Ok(tx_pool_info.into())
     }
 
+    fn get_pool_policy(&self) -> Result<PoolPolicy> {
+        let tx_pool = self.shared.tx_pool_controller();
+        tx_pool.get_pool_policy().map(Into::into).map_err(|e| {
+            error!("Send get_pool_policy request error {}", e);
+            RPCError::ckb_internal_error(e)
+        })
+    }
+
     fn clear_tx_pool(&self) -> Result<()> {
         let snapshot = Arc::clone(&self.shared.snapshot());
         let tx_pool = self.shared.tx_pool_controller();
@@ -669,12 +1503,12 @@ impl PoolRpc for PoolRpcImpl {
             let info = tx_pool
                 .get_all_entry_info()
                 .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
-            RawTxPool::Verbose(info.into())
+            RawTxPool::Verbose(info.as_ref().into())
         } else {
             let ids = tx_pool
                 .get_all_ids()
                 .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
-            RawTxPool::Ids(ids.into())
+            RawTxPool::Ids(ids.as_ref().into())
         };
         Ok(raw)
     }
@@ -686,6 +1520,138 @@ impl PoolRpc for PoolRpcImpl {
             .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
         Ok(tx_detail.into())
     }
+
+    fn get_pool_tx_graph(
+        &self,
+        tx_hash: H256,
+        max_depth: Option<Uint32>,
+    ) -> Result<Option<PoolTxGraph>> {
+        let max_depth = max_depth
+            .map(|depth| depth.value())
+            .unwrap_or(DEFAULT_POOL_TX_GRAPH_DEPTH);
+        if max_depth > MAX_POOL_TX_GRAPH_DEPTH {
+            return Err(RPCError::invalid_params(format!(
+                "max_depth must be less than or equal to {MAX_POOL_TX_GRAPH_DEPTH}"
+            )));
+        }
+        let tx_pool = self.shared.tx_pool_controller();
+        let graph = tx_pool
+            .get_tx_graph(tx_hash.pack(), max_depth as usize)
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+        Ok(graph.map(Into::into))
+    }
+
+    fn diagnose_transaction(&self, tx_hash: H256) -> Result<TxDiagnosis> {
+        let tx_pool = self.shared.tx_pool_controller();
+        let diagnosis = tx_pool
+            .diagnose_tx(tx_hash.pack())
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+        Ok(diagnosis.into())
+    }
+
+    fn get_transaction_fee_breakdown(&self, tx: TransactionOrHash) -> Result<TxFeeBreakdown> {
+        let tx_pool = self.shared.tx_pool_controller();
+        let tx = match tx {
+            TransactionOrHash::Transaction(tx) => {
+                let tx: packed::Transaction = tx.into();
+                tx.into_view()
+            }
+            TransactionOrHash::Hash(tx_hash) => {
+                let tx_hash = tx_hash.pack();
+                match self.shared.snapshot().get_transaction(&tx_hash) {
+                    Some((tx, _)) => tx,
+                    None => tx_pool
+                        .fetch_txs(HashSet::from([packed::ProposalShortId::from_tx_hash(
+                            &tx_hash,
+                        )]))
+                        .map_err(RPCError::ckb_internal_error)?
+                        .into_values()
+                        .next()
+                        .ok_or_else(|| {
+                            RPCError::invalid_params(format!("transaction {tx_hash} is not found"))
+                        })?,
+                }
+            }
+        };
+        tx_pool
+            .get_tx_fee_breakdown(tx)
+            .map_err(RPCError::ckb_internal_error)?
+            .map(Into::into)
+            .map_err(|reject| RPCError::from_submit_transaction_reject(&reject))
+    }
+
+    fn simulate_replacement(&self, tx: Transaction) -> Result<ReplacementSimulation> {
+        let tx: packed::Transaction = tx.into();
+        self.shared
+            .tx_pool_controller()
+            .simulate_replacement(tx.into_view())
+            .map_err(RPCError::ckb_internal_error)?
+            .map(Into::into)
+            .map_err(|reject| RPCError::from_submit_transaction_reject(&reject))
+    }
+
+    fn simulate_proposal_window(&self, tx: Transaction) -> Result<ProposalWindowSimulation> {
+        let tx: packed::Transaction = tx.into();
+        self.shared
+            .tx_pool_controller()
+            .simulate_proposal_window(tx.into_view())
+            .map_err(RPCError::ckb_internal_error)?
+            .map(Into::into)
+            .map_err(|reject| RPCError::from_submit_transaction_reject(&reject))
+    }
+
+    fn estimate_fee_rate(&self, target_blocks: Uint64) -> Result<Option<Uint64>> {
+        let target_blocks = target_blocks.value();
+        if target_blocks == 0 || target_blocks > MAX_FEE_ESTIMATE_TARGET_BLOCKS {
+            return Err(RPCError::invalid_params(format!(
+                "target_blocks should be 1 to {MAX_FEE_ESTIMATE_TARGET_BLOCKS}"
+            )));
+        }
+        self.shared
+            .tx_pool_controller()
+            .estimate_fee_rate(target_blocks)
+            .map(|fee_rate| fee_rate.map(|fee_rate| fee_rate.as_u64().into()))
+            .map_err(RPCError::ckb_internal_error)
+    }
+
+    fn get_block_template_backlog(&self) -> Result<Vec<TemplateBacklog>> {
+        let backlogs = self
+            .shared
+            .tx_pool_controller()
+            .get_block_template_backlog()
+            .map_err(|err| {
+                error!("Send get_block_template_backlog request error {}", err);
+                RPCError::ckb_internal_error(err)
+            })?
+            .map_err(RPCError::from_any_error)?;
+        Ok(backlogs.into_iter().map(Into::into).collect())
+    }
+
+    fn list_pool_tx_hashes(
+        &self,
+        limit: Uint32,
+        after: Option<JsonBytes>,
+    ) -> Result<IndexerPagination<H256>> {
+        let limit = check_limit(limit, MAX_PAGINATION_LIMIT).map_err(RPCError::invalid_params)?;
+        let ids = self
+            .shared
+            .tx_pool_controller()
+            .get_all_ids()
+            .map_err(|err| RPCError::custom(RPCError::CKBInternalError, err.to_string()))?;
+        let hashes: Vec<H256> = ids
+            .pending
+            .iter()
+            .chain(ids.proposed.iter())
+            .map(|hash| hash.unpack())
+            .collect();
+        Ok(paginate_by_key(
+            hashes,
+            |hash| hash.as_bytes().to_vec(),
+            IndexerOrder::Asc,
+            limit,
+            after,
+        ))
+    }
 }
 
 pub(crate) struct WellKnownScriptsOnlyValidator<'a> {
@@ -1,8 +1,9 @@
 use crate::error::RPCError;
+use crate::util::wait_for_indexer;
 use async_trait::async_trait;
 use ckb_jsonrpc_types::{
     IndexerCell, IndexerCellsCapacity, IndexerOrder, IndexerPagination, IndexerSearchKey,
-    IndexerTip, IndexerTx, JsonBytes, Uint32,
+    IndexerTip, IndexerTx, IndexerWaitFor, JsonBytes, Uint32,
 };
 use ckb_rich_indexer::AsyncRichIndexerHandle;
 use jsonrpc_core::Result;
@@ -46,6 +47,10 @@ pub trait RichIndexerRpc {
     /// * order: enum, asc | desc
     /// * limit: result size limit
     /// * after: pagination parameter, optional
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
     ///
     /// ## Returns
     ///
@@ -69,6 +74,7 @@ pub trait RichIndexerRpc {
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerCell>>;
 
     /// Returns the transactions collection by the lock or type script.
@@ -91,6 +97,10 @@ pub trait RichIndexerRpc {
     /// * order: enum, asc | desc
     /// * limit: result size limit
     /// * after: pagination parameter, optional
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
     ///
     /// ## Returns
     ///
@@ -120,6 +130,7 @@ pub trait RichIndexerRpc {
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerTx>>;
 
     /// Returns the live cells capacity by the lock or type script.
@@ -140,6 +151,10 @@ pub trait RichIndexerRpc {
     ///         - output_data_len_range: [u64; 2], filter cells by output data len range, [inclusive, exclusive]
     ///         - output_capacity_range: [u64; 2], filter cells by output capacity range, [inclusive, exclusive]
     ///         - block_range: [u64; 2], filter cells by block number range, [inclusive, exclusive]
+    /// * wait_for: wait until the indexer has processed the block before answering, optional
+    ///     - block_number - the block number
+    ///     - block_hash - the block hash, optional
+    ///     - timeout - the max waiting time in milliseconds, optional default is `3000`, capped at `10000`
     ///
     /// ## Returns
     ///
@@ -154,6 +169,7 @@ pub trait RichIndexerRpc {
     async fn get_cells_capacity(
         &self,
         search_key: IndexerSearchKey,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<Option<IndexerCellsCapacity>>;
 }
 
@@ -166,6 +182,20 @@ impl RichIndexerRpcImpl {
     pub fn new(handle: AsyncRichIndexerHandle) -> Self {
         RichIndexerRpcImpl { handle }
     }
+
+    async fn wait_for_indexer(&self, wait_for: Option<IndexerWaitFor>) -> Result<()> {
+        wait_for_indexer(
+            wait_for,
+            || self.get_indexer_tip(),
+            |block_number| async move {
+                self.handle
+                    .get_block_hash(block_number)
+                    .await
+                    .map_err(|e| RPCError::custom(RPCError::Indexer, e))
+            },
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -183,7 +213,9 @@ impl RichIndexerRpc for RichIndexerRpcImpl {
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerCell>> {
+        self.wait_for_indexer(wait_for).await?;
         self.handle
             .get_cells(search_key, order, limit, after)
             .await
@@ -196,7 +228,9 @@ impl RichIndexerRpc for RichIndexerRpcImpl {
         order: IndexerOrder,
         limit: Uint32,
         after: Option<JsonBytes>,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<IndexerPagination<IndexerTx>> {
+        self.wait_for_indexer(wait_for).await?;
         self.handle
             .get_transactions(search_key, order, limit, after)
             .await
@@ -206,7 +240,9 @@ impl RichIndexerRpc for RichIndexerRpcImpl {
     async fn get_cells_capacity(
         &self,
         search_key: IndexerSearchKey,
+        wait_for: Option<IndexerWaitFor>,
     ) -> Result<Option<IndexerCellsCapacity>> {
+        self.wait_for_indexer(wait_for).await?;
         self.handle
             .get_cells_capacity(search_key)
             .await
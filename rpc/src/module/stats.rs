@@ -1,5 +1,9 @@
+use crate::util::DEPRECATED_METHODS;
 use async_trait::async_trait;
-use ckb_jsonrpc_types::{AlertMessage, ChainInfo, DeploymentInfo, DeploymentPos, DeploymentsInfo};
+use ckb_jsonrpc_types::{
+    AlertMessage, ChainInfo, DeploymentInfo, DeploymentPos, DeploymentsInfo, DeprecatedMethod,
+    UncleStats,
+};
 use ckb_network_alert::notifier::Notifier as AlertNotifier;
 use ckb_shared::shared::Shared;
 use ckb_traits::HeaderFieldsProvider;
@@ -99,12 +103,104 @@ pub trait StatsRpc {
     /// ```
     #[rpc(name = "get_deployments_info")]
     fn get_deployments_info(&self) -> Result<DeploymentsInfo>;
+
+    /// Returns the stale block and uncle statistics of the recent canonical chain blocks.
+    ///
+    /// The statistics are returned for every window configured by `chain.stats_windows` in
+    /// `ckb.toml`, in ascending window order. They are also exported as the metrics
+    /// `ckb_chain_stale_block_rate` and `ckb_chain_uncle_rate`.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "get_uncle_stats",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "blocks": "0x64",
+    ///       "stale_blocks": "0x2",
+    ///       "uncles": "0x3",
+    ///       "window": "0x64"
+    ///     },
+    ///     {
+    ///       "blocks": "0x708",
+    ///       "stale_blocks": "0x1a",
+    ///       "uncles": "0x2c",
+    ///       "window": "0x708"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "get_uncle_stats")]
+    fn get_uncle_stats(&self) -> Result<Vec<UncleStats>>;
+
+    /// Returns the deprecated RPC methods of this node.
+    ///
+    /// The deprecated methods can be called only when `rpc.enable_deprecated_rpc` is enabled in
+    /// `ckb.toml`. When a deprecated method is called via HTTP, the response has a `warning` field
+    /// and the `Deprecation` header, and also the `Sunset` header if the removal is scheduled. The
+    /// node logs a warning at most once per method in 10 minutes.
+    ///
+    /// ## Examples
+    ///
+    /// Request
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "method": "list_deprecated_methods",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// Response
+    ///
+    /// ```json
+    /// {
+    ///   "id": 42,
+    ///   "jsonrpc": "2.0",
+    ///   "result": [
+    ///     {
+    ///       "enabled": true,
+    ///       "name": "dry_run_transaction",
+    ///       "replacement": "estimate_cycles",
+    ///       "since": "0.105.1",
+    ///       "sunset": null
+    ///     },
+    ///     {
+    ///       "enabled": true,
+    ///       "name": "get_fee_rate_statics",
+    ///       "replacement": "get_fee_rate_statistics",
+    ///       "since": "0.109.0",
+    ///       "sunset": null
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    #[rpc(name = "list_deprecated_methods")]
+    fn list_deprecated_methods(&self) -> Result<Vec<DeprecatedMethod>>;
 }
 
 #[derive(Clone)]
 pub(crate) struct StatsRpcImpl {
     pub shared: Shared,
     pub alert_notifier: Arc<Mutex<AlertNotifier>>,
+    pub enable_deprecated_rpc: bool,
 }
 
 #[async_trait]
@@ -184,4 +280,25 @@ impl StatsRpc for StatsRpcImpl {
             deployments,
         })
     }
+
+    fn get_uncle_stats(&self) -> Result<Vec<UncleStats>> {
+        Ok(self
+            .shared
+            .chain_stats()
+            .into_iter()
+            .map(|stats| UncleStats {
+                window: stats.window.into(),
+                blocks: stats.blocks.into(),
+                uncles: stats.uncles.into(),
+                stale_blocks: stats.stale_blocks.into(),
+            })
+            .collect())
+    }
+
+    fn list_deprecated_methods(&self) -> Result<Vec<DeprecatedMethod>> {
+        Ok(DEPRECATED_METHODS
+            .iter()
+            .map(|method| method.to_json(self.enable_deprecated_rpc))
+            .collect())
+    }
 }
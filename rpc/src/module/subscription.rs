@@ -1,14 +1,26 @@
+use crate::error::RPCError;
 use async_trait::async_trait;
 use broadcast::error::RecvError;
+use ckb_app_config::SubscriptionResumeConfig;
 use ckb_async_runtime::Handle;
-use ckb_jsonrpc_types::Topic;
+use ckb_jsonrpc_types::{
+    JsonBytes, MigrationFinishedEvent, PoolTransactionEntry, PoolTransactionReject,
+    ProposalWindowEvent, ProposalWindowStatus, Status, SubscribeOptions, SubscriptionBatch, Topic,
+    TxStatusChangedEvent,
+};
 use ckb_logger::error;
 use ckb_notify::NotifyController;
 use ckb_notify::NOTIFY_CHANNEL_SIZE;
 use ckb_stop_handler::new_tokio_exit_rx;
+use ckb_systemtime::unix_time_as_millis;
+use ckb_types::{prelude::*, H256};
+use ckb_util::{LinkedHashMap, Mutex};
 use futures_util::{stream::BoxStream, Stream};
 use jsonrpc_core::Result;
 use jsonrpc_utils::{pub_sub::PublishMsg, rpc};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
 
 /// RPC Module Subscription that CKB node will push new messages to subscribers, support with WebSocket or TCP.
@@ -60,7 +72,25 @@ pub trait SubscriptionRpc {
     ///
     /// ###### Params
     ///
-    /// * `topic` - Subscription topic (enum: new_tip_header | new_tip_block | new_transaction | proposed_transaction | rejected_transaction)
+    /// * `topic` - Subscription topic (enum: new_tip_header | new_tip_block | new_transaction | proposed_transaction | rejected_transaction | proposal_window | migration_finished | tx_status_changed)
+    /// * `options` - Subscription options (optional)
+    ///     * `with_resume_token` - Whether to wrap every push message with a resume token, default false.
+    ///     * `resume_token` - Resumes the subscription after the event of this token, which implies
+    ///       `with_resume_token`.
+    ///     * `tx_hashes` - Only pushes the events of these transactions, which only applies to the
+    ///       topics `proposal_window` and `tx_status_changed`. All the events are pushed by default.
+    ///     * `filter` - Only pushes the transactions whose input or output cells use the scripts,
+    ///       which applies to the topics of the transactions, `new_transaction`,
+    ///       `proposed_transaction`, `rejected_transaction`, `proposal_window` and
+    ///       `tx_status_changed`. All the transactions are pushed by default.
+    ///         * `lock_hashes` - The lock script hashes, default empty.
+    ///         * `type_hashes` - The type script hashes, default empty.
+    ///     * `batch` - Pushes the events in arrays, which only applies to the topics
+    ///       `new_transaction` and `rejected_transaction`. Every event is pushed in its own
+    ///       message by default.
+    ///         * `max_batch_size` - Max number of events in a push message, default 100, at most 1000.
+    ///         * `max_batch_latency_ms` - Max milliseconds an event is held before the batch
+    ///           containing it is pushed, default 100, at most 10000.
     ///
     /// ###### Returns
     ///
@@ -81,6 +111,22 @@ pub trait SubscriptionRpc {
     /// }
     /// ```
     ///
+    /// ##### Resume Tokens
+    ///
+    /// When `rpc.subscription_resume` is configured, the node keeps the recent events of every topic
+    /// for a short retention window. A subscription with `with_resume_token` receives the push
+    /// messages whose `params.result` is an object with two fields:
+    ///
+    /// -   `event` - the event of the topic, the same as the `params.result` without the resume token, and
+    /// -   `resume_token` - an opaque token identifying the event.
+    ///
+    /// A client reconnecting after a network blip subscribes again with the `resume_token` of the
+    /// last received event, and the kept events after it are pushed before the new events, so no
+    /// events are missed. The subscription fails if the events after the token are no longer kept
+    /// or the node has restarted since, and the client has to resynchronize by other RPC methods.
+    ///
+    /// The resume token of a batch identifies its last event.
+    ///
     /// ##### Topics
     ///
     /// ###### `new_tip_header`
@@ -103,6 +149,9 @@ pub trait SubscriptionRpc {
     ///
     /// The type of the `params.result` in the push message is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html).
     ///
+    /// When the subscription sets the option `batch`, the events are pushed in batches, and the
+    /// type of the `params.result` is an array of [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html).
+    ///
     /// ###### `proposed_transaction`
     ///
     /// Subscribers will get notified when an in-pool transaction is proposed by chain.
@@ -120,6 +169,49 @@ pub trait SubscriptionRpc {
     /// -   the first item type is [`PoolTransactionEntry`](../../ckb_jsonrpc_types/struct.PoolTransactionEntry.html), and
     /// -   the second item type is [`PoolTransactionReject`](../../ckb_jsonrpc_types/struct.PoolTransactionReject.html).
     ///
+    /// When the subscription sets the option `batch`, the events are pushed in batches, and the
+    /// type of the `params.result` is an array of such two-elements arrays.
+    ///
+    /// ###### `proposal_window`
+    ///
+    /// Subscribers will get notified when an in-pool transaction enters the gap set, that is it is
+    /// proposed in a block which is not in the proposal window yet, and when it enters the proposed
+    /// set and can be committed. The services relying on the two-step commit flow can act at the
+    /// proposal time instead of polling `get_transaction`.
+    ///
+    /// The type of the `params.result` in the push message is [`ProposalWindowEvent`](../../ckb_jsonrpc_types/struct.ProposalWindowEvent.html).
+    ///
+    /// Subscribe with the option `tx_hashes` to get notified only for the specific transactions.
+    ///
+    /// ###### `migration_finished`
+    ///
+    /// Subscribers will get notified when the database migrations running in the background
+    /// finish or fail, so the operators of the daemonized nodes learn the outcome without scanning
+    /// the logs. The node pushes at most one event after it starts, and the subscriptions made
+    /// after the event get it once they subscribe. A failed migration doesn't stop the later ones,
+    /// `failed_version` tells the first failed one.
+    ///
+    /// The type of the `params.result` in the push message is [`MigrationFinishedEvent`](../../ckb_jsonrpc_types/struct.MigrationFinishedEvent.html).
+    ///
+    /// ###### `tx_status_changed`
+    ///
+    /// Subscribers will get notified when the status of a transaction changes, that is when it is
+    /// submitted to the pool, proposed, committed in a block, or rejected by tx-pool. The statuses
+    /// are the same as the ones returned by `get_transaction`, so the light wallets can track their
+    /// transactions without polling it. A transaction in the gap set is still `pending`.
+    ///
+    /// The old status is `unknown` if the node hasn't pushed any status of the transaction since it
+    /// started, or the transaction is no longer among the recent ones it keeps. Only the
+    /// transactions committed from the pool are notified.
+    ///
+    /// The status of a transaction only moves forward, from `pending` to `proposed` and then to
+    /// `committed` or `rejected`, until the transaction re-enters the pool, e.g. after a reorg. A
+    /// proposed transaction which goes back to the gap set stays `proposed`.
+    ///
+    /// The type of the `params.result` in the push message is [`TxStatusChangedEvent`](../../ckb_jsonrpc_types/struct.TxStatusChangedEvent.html).
+    ///
+    /// Subscribe with the option `tx_hashes` to get notified only for the specific transactions.
+    ///
     /// ###### Examples
     ///
     /// Subscribe Request
@@ -181,50 +273,632 @@ pub trait SubscriptionRpc {
     /// ```
     ///
     #[rpc(pub_sub(notify = "subscribe", unsubscribe = "unsubscribe"))]
-    fn subscribe(&self, topic: Topic) -> Result<Self::S>;
+    fn subscribe(&self, topic: Topic, options: Option<SubscribeOptions>) -> Result<Self::S>;
 }
 
 #[derive(Clone)]
 pub struct SubscriptionRpcImpl {
-    pub new_tip_header_sender: broadcast::Sender<PublishMsg<String>>,
-    pub new_tip_block_sender: broadcast::Sender<PublishMsg<String>>,
-    pub new_transaction_sender: broadcast::Sender<PublishMsg<String>>,
-    pub proposed_transaction_sender: broadcast::Sender<PublishMsg<String>>,
-    pub new_reject_transaction_sender: broadcast::Sender<PublishMsg<String>>,
+    pub new_tip_header_sender: TopicChannel,
+    pub new_tip_block_sender: TopicChannel,
+    pub new_transaction_sender: TopicChannel,
+    pub proposed_transaction_sender: TopicChannel,
+    pub new_reject_transaction_sender: TopicChannel,
+    pub proposal_window_sender: TopicChannel,
+    pub migration_finished_sender: TopicChannel,
+    pub tx_status_changed_sender: TopicChannel,
+    // identifies this process in the resume tokens, the tokens issued before a restart are invalid
+    instance_id: u64,
 }
 
 macro_rules! publiser_send {
     ($ty:ty, $info:expr, $sender:ident) => {{
         let msg: $ty = $info.into();
         let json_string = serde_json::to_string(&msg).expect("serialization should be ok");
-        drop($sender.send(PublishMsg::result(&json_string)));
+        $sender.publish(json_string);
     }};
 }
 
+/// An event of a topic, numbered by its order in the topic.
+#[derive(Clone)]
+pub struct TopicEvent {
+    pub(crate) seq: u64,
+    pub(crate) json: Arc<String>,
+    // the transactions of the event, for the subscriptions filtering by `tx_hashes` or `filter`
+    txs: Arc<Vec<EventTx>>,
+}
+
+/// A transaction in an event, with the scripts it uses.
+pub(crate) struct EventTx {
+    tx_hash: H256,
+    // the sorted script hashes of the input and output cells
+    lock_hashes: Vec<H256>,
+    type_hashes: Vec<H256>,
+}
+
+impl EventTx {
+    pub(crate) fn new(entry: &ckb_notify::PoolTransactionEntry) -> Self {
+        let (mut lock_hashes, mut type_hashes) = (Vec::new(), Vec::new());
+        let cells = entry
+            .transaction
+            .outputs()
+            .into_iter()
+            .chain(entry.input_cells.iter().cloned());
+        for cell in cells {
+            lock_hashes.push(cell.lock().calc_script_hash().unpack());
+            if let Some(script) = cell.type_().to_opt() {
+                type_hashes.push(script.calc_script_hash().unpack());
+            }
+        }
+        lock_hashes.sort_unstable();
+        lock_hashes.dedup();
+        type_hashes.sort_unstable();
+        type_hashes.dedup();
+        EventTx {
+            tx_hash: entry.transaction.hash().unpack(),
+            lock_hashes,
+            type_hashes,
+        }
+    }
+}
+
+/// Filters the events of a subscription by the options `tx_hashes` and `filter`.
+pub(crate) struct EventFilter {
+    tx_hashes: Option<HashSet<H256>>,
+    // the lock and type script hashes, `None` if the option `filter` is not set
+    scripts: Option<(HashSet<H256>, HashSet<H256>)>,
+}
+
+impl EventFilter {
+    pub(crate) fn new(options: &SubscribeOptions) -> Self {
+        EventFilter {
+            tx_hashes: options
+                .tx_hashes
+                .as_ref()
+                .map(|tx_hashes| tx_hashes.iter().cloned().collect()),
+            scripts: options.filter.as_ref().map(|filter| {
+                (
+                    filter.lock_hashes.iter().cloned().collect(),
+                    filter.type_hashes.iter().cloned().collect(),
+                )
+            }),
+        }
+    }
+
+    fn matches(&self, tx: &EventTx) -> bool {
+        let tx_hash_matches = self
+            .tx_hashes
+            .as_ref()
+            .map_or(true, |tx_hashes| tx_hashes.contains(&tx.tx_hash));
+        let scripts_match = self.scripts.as_ref().map_or(true, |(locks, types)| {
+            tx.lock_hashes.iter().any(|hash| locks.contains(hash))
+                || tx.type_hashes.iter().any(|hash| types.contains(hash))
+        });
+        tx_hash_matches && scripts_match
+    }
+
+    /// Returns the json to push for the event, or `None` if none of its transactions matches.
+    pub(crate) fn apply(&self, event: &TopicEvent) -> Option<Arc<String>> {
+        let unfiltered = self.tx_hashes.is_none() && self.scripts.is_none();
+        (unfiltered || event.txs.is_empty() || event.txs.iter().any(|tx| self.matches(tx)))
+            .then(|| Arc::clone(&event.json))
+    }
+}
+
+/// Broadcasts the events of a topic, and keeps the recent ones for resuming the subscriptions.
+#[derive(Clone)]
+pub struct TopicChannel {
+    pub(crate) sender: broadcast::Sender<TopicEvent>,
+    history: Arc<Mutex<TopicHistory>>,
+}
+
+impl TopicChannel {
+    fn new(resume_config: Option<SubscriptionResumeConfig>) -> Self {
+        let (sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
+        TopicChannel {
+            sender,
+            history: Arc::new(Mutex::new(TopicHistory::new(resume_config))),
+        }
+    }
+
+    fn keeping_last_event(resume_config: Option<SubscriptionResumeConfig>) -> Self {
+        let (sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
+        TopicChannel {
+            sender,
+            history: Arc::new(Mutex::new(TopicHistory::keeping_last_event(resume_config))),
+        }
+    }
+
+    fn publish(&self, json: String) {
+        self.publish_event(json, Vec::new())
+    }
+
+    fn publish_for_tx(&self, json: String, tx: EventTx) {
+        self.publish_event(json, vec![tx])
+    }
+
+    fn publish_event(&self, json: String, txs: Vec<EventTx>) {
+        // the event is sent with the lock held, so the subscribers see the events in order
+        let mut history = self.history.lock();
+        let event = history.push_event(json, txs, unix_time_as_millis());
+        drop(self.sender.send(event));
+    }
+}
+
+fn publish_proposal_window(
+    sender: &TopicChannel,
+    status: ProposalWindowStatus,
+    entry: ckb_notify::PoolTransactionEntry,
+) {
+    let tx = EventTx::new(&entry);
+    let event = ProposalWindowEvent {
+        status,
+        entry: entry.into(),
+    };
+    let json = serde_json::to_string(&event).expect("serialization should be ok");
+    sender.publish_for_tx(json, tx);
+}
+
+// The max number of the txs whose last statuses are kept for the topic `tx_status_changed`
+const MAX_TRACKED_TX_STATUSES: usize = 100_000;
+
+// The last pushed statuses of the recent txs, to tell the old status of a transition.
+//
+// The statuses come from separate notify channels, so they may arrive out of order. A status
+// which goes backwards is only taken when the tx re-enters the pool, i.e. its entry is newer.
+pub(crate) struct TxStatusTracker {
+    // the last status and the timestamp of the pool entry it comes from
+    statuses: LinkedHashMap<H256, (u64, Status)>,
+    capacity: usize,
+}
+
+// The order of the statuses of a pool entry.
+fn status_rank(status: &Status) -> u8 {
+    match status {
+        Status::Unknown => 0,
+        Status::Pending => 1,
+        Status::Proposed => 2,
+        Status::Committed | Status::Rejected => 3,
+    }
+}
+
+impl TxStatusTracker {
+    pub(crate) fn new(capacity: usize) -> Self {
+        TxStatusTracker {
+            statuses: LinkedHashMap::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    // Records the new status of the tx from the pool entry entered at `timestamp`, returns the
+    // event if the status changes. The stale statuses are ignored.
+    pub(crate) fn transit(
+        &mut self,
+        tx_hash: H256,
+        timestamp: u64,
+        new_status: Status,
+    ) -> Option<TxStatusChangedEvent> {
+        let (old_timestamp, old_status) = self
+            .statuses
+            .get(&tx_hash)
+            .cloned()
+            .unwrap_or((0, Status::Unknown));
+        if timestamp < old_timestamp
+            || (timestamp == old_timestamp && status_rank(&new_status) < status_rank(&old_status))
+        {
+            return None;
+        }
+        self.statuses.remove(&tx_hash);
+        self.statuses
+            .insert(tx_hash.clone(), (timestamp, new_status.clone()));
+        if self.statuses.len() > self.capacity {
+            self.statuses.pop_front();
+        }
+        (old_status != new_status).then_some(TxStatusChangedEvent {
+            tx_hash,
+            old_status,
+            new_status,
+        })
+    }
+}
+
+fn publish_tx_status(
+    sender: &TopicChannel,
+    tracker: &mut TxStatusTracker,
+    entry: &ckb_notify::PoolTransactionEntry,
+    status: Status,
+) {
+    let tx = EventTx::new(entry);
+    if let Some(event) = tracker.transit(tx.tx_hash.clone(), entry.timestamp, status) {
+        let json = serde_json::to_string(&event).expect("serialization should be ok");
+        sender.publish_for_tx(json, tx);
+    }
+}
+
+fn publish_tx_entry(sender: &TopicChannel, entry: ckb_notify::PoolTransactionEntry) {
+    let tx = EventTx::new(&entry);
+    sender.publish_for_tx(to_json(&PoolTransactionEntry::from(entry)), tx);
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("serialization should be ok")
+}
+
+// The recent events of a topic.
+pub(crate) struct TopicHistory {
+    // the sequence number of the next event
+    next_seq: u64,
+    // the kept events and when they were pushed, in milliseconds
+    events: VecDeque<(u64, TopicEvent)>,
+    config: Option<SubscriptionResumeConfig>,
+    // whether to push the last event to the new subscriptions, for the topics which have at most
+    // one event and whose subscribers may come after it
+    keep_last_event: bool,
+    last_event: Option<TopicEvent>,
+}
+
+impl TopicHistory {
+    pub(crate) fn new(config: Option<SubscriptionResumeConfig>) -> Self {
+        TopicHistory {
+            next_seq: 0,
+            events: VecDeque::new(),
+            config,
+            keep_last_event: false,
+            last_event: None,
+        }
+    }
+
+    /// The new subscriptions get the last event first, see `TopicHistory::last_event`.
+    pub(crate) fn keeping_last_event(config: Option<SubscriptionResumeConfig>) -> Self {
+        TopicHistory {
+            keep_last_event: true,
+            ..Self::new(config)
+        }
+    }
+
+    /// The last event, if the history is created by `keeping_last_event`.
+    pub(crate) fn last_event(&self) -> Option<&TopicEvent> {
+        self.last_event.as_ref()
+    }
+
+    fn resumable(&self) -> bool {
+        self.config.is_some()
+    }
+
+    pub(crate) fn push(&mut self, json: String, now: u64) -> TopicEvent {
+        self.push_event(json, Vec::new(), now)
+    }
+
+    pub(crate) fn push_event(&mut self, json: String, txs: Vec<EventTx>, now: u64) -> TopicEvent {
+        let event = TopicEvent {
+            seq: self.next_seq,
+            json: Arc::new(json),
+            txs: Arc::new(txs),
+        };
+        self.next_seq += 1;
+        if self.keep_last_event {
+            self.last_event = Some(event.clone());
+        }
+        if let Some(config) = &self.config {
+            self.events.push_back((now, event.clone()));
+            if self.events.len() > config.max_events {
+                self.events.pop_front();
+            }
+            self.prune(now);
+        }
+        event
+    }
+
+    fn prune(&mut self, now: u64) {
+        if let Some(config) = &self.config {
+            let retention = config.retention_secs.saturating_mul(1000);
+            while let Some((pushed_at, _)) = self.events.front() {
+                if pushed_at.saturating_add(retention) >= now {
+                    break;
+                }
+                self.events.pop_front();
+            }
+        }
+    }
+
+    // Returns the events after `seq`, or `None` if some of them are no longer kept.
+    pub(crate) fn events_after(&mut self, seq: u64, now: u64) -> Option<Vec<TopicEvent>> {
+        if seq >= self.next_seq {
+            return None;
+        }
+        self.prune(now);
+        let first_kept = self
+            .events
+            .front()
+            .map_or(self.next_seq, |(_, event)| event.seq);
+        if seq + 1 < first_kept {
+            return None;
+        }
+        Some(
+            self.events
+                .iter()
+                .filter(|(_, event)| event.seq > seq)
+                .map(|(_, event)| event.clone())
+                .collect(),
+        )
+    }
+}
+
+// The position of an event, encoded as an opaque token for the clients.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ResumeToken {
+    pub(crate) instance_id: u64,
+    pub(crate) topic: u8,
+    pub(crate) seq: u64,
+}
+
+impl ResumeToken {
+    pub(crate) fn encode(&self) -> JsonBytes {
+        let mut bytes = Vec::with_capacity(17);
+        bytes.extend_from_slice(&self.instance_id.to_be_bytes());
+        bytes.push(self.topic);
+        bytes.extend_from_slice(&self.seq.to_be_bytes());
+        JsonBytes::from_vec(bytes)
+    }
+
+    pub(crate) fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 17 {
+            return None;
+        }
+        let mut instance_id = [0u8; 8];
+        let mut seq = [0u8; 8];
+        instance_id.copy_from_slice(&bytes[..8]);
+        seq.copy_from_slice(&bytes[9..]);
+        Some(ResumeToken {
+            instance_id: u64::from_be_bytes(instance_id),
+            topic: bytes[8],
+            seq: u64::from_be_bytes(seq),
+        })
+    }
+}
+
+const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
+const MAX_BATCH_SIZE: u32 = 1000;
+const DEFAULT_MAX_BATCH_LATENCY_MS: u64 = 100;
+const MAX_BATCH_LATENCY_MS: u64 = 10_000;
+
+// The events of a subscription which are waiting to be pushed together, see the option `batch`.
+pub(crate) struct EventBatch {
+    // the sequence numbers and the json of the events
+    events: Vec<(u64, Arc<String>)>,
+    max_size: usize,
+}
+
+impl EventBatch {
+    pub(crate) fn new(max_size: usize) -> Self {
+        EventBatch {
+            events: Vec::new(),
+            max_size: max_size.max(1),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    // Adds an event, returns the batch when it is full.
+    pub(crate) fn push(&mut self, seq: u64, json: Arc<String>) -> Option<(u64, String)> {
+        self.events.push((seq, json));
+        if self.events.len() >= self.max_size {
+            self.take()
+        } else {
+            None
+        }
+    }
+
+    // Returns the sequence number of the last event and the json array of the events.
+    pub(crate) fn take(&mut self) -> Option<(u64, String)> {
+        let (last_seq, _) = self.events.last()?;
+        let last_seq = *last_seq;
+        let jsons: Vec<&str> = self.events.iter().map(|(_, json)| json.as_str()).collect();
+        let json = format!("[{}]", jsons.join(","));
+        self.events.clear();
+        Some((last_seq, json))
+    }
+}
+
+// Returns the max size and the max latency of the batches.
+fn batch_limits(batch: &SubscriptionBatch) -> Result<(usize, Duration)> {
+    let max_size = batch
+        .max_batch_size
+        .map_or(DEFAULT_MAX_BATCH_SIZE, |size| size.value());
+    let max_latency_ms = batch
+        .max_batch_latency_ms
+        .map_or(DEFAULT_MAX_BATCH_LATENCY_MS, |latency| latency.value());
+    if max_size == 0 || max_size > MAX_BATCH_SIZE {
+        return Err(RPCError::invalid_params(format!(
+            "The option `batch.max_batch_size` must be between 1 and {MAX_BATCH_SIZE}"
+        )));
+    }
+    if max_latency_ms == 0 || max_latency_ms > MAX_BATCH_LATENCY_MS {
+        return Err(RPCError::invalid_params(format!(
+            "The option `batch.max_batch_latency_ms` must be between 1 and {MAX_BATCH_LATENCY_MS}"
+        )));
+    }
+    Ok((max_size as usize, Duration::from_millis(max_latency_ms)))
+}
+
 #[async_trait]
 impl SubscriptionRpc for SubscriptionRpcImpl {
     type S = BoxStream<'static, PublishMsg<String>>;
-    fn subscribe(&self, topic: Topic) -> Result<Self::S> {
-        let tx = match topic {
-            Topic::NewTipHeader => self.new_tip_header_sender.clone(),
-            Topic::NewTipBlock => self.new_tip_block_sender.clone(),
-            Topic::NewTransaction => self.new_transaction_sender.clone(),
-            Topic::ProposedTransaction => self.proposed_transaction_sender.clone(),
-            Topic::RejectedTransaction => self.new_reject_transaction_sender.clone(),
+    fn subscribe(&self, topic: Topic, options: Option<SubscribeOptions>) -> Result<Self::S> {
+        let channel = match topic {
+            Topic::NewTipHeader => &self.new_tip_header_sender,
+            Topic::NewTipBlock => &self.new_tip_block_sender,
+            Topic::NewTransaction => &self.new_transaction_sender,
+            Topic::ProposedTransaction => &self.proposed_transaction_sender,
+            Topic::RejectedTransaction => &self.new_reject_transaction_sender,
+            Topic::ProposalWindow => &self.proposal_window_sender,
+            Topic::MigrationFinished => &self.migration_finished_sender,
+            Topic::TxStatusChanged => &self.tx_status_changed_sender,
+        };
+        let options = options.unwrap_or_default();
+        let with_resume_token = options.with_resume_token || options.resume_token.is_some();
+        if options.tx_hashes.is_some()
+            && !matches!(topic, Topic::ProposalWindow | Topic::TxStatusChanged)
+        {
+            return Err(RPCError::invalid_params(
+                "The option `tx_hashes` only applies to the topics `proposal_window` and `tx_status_changed`",
+            ));
+        }
+        if options.filter.is_some()
+            && !matches!(
+                topic,
+                Topic::NewTransaction
+                    | Topic::ProposedTransaction
+                    | Topic::RejectedTransaction
+                    | Topic::ProposalWindow
+                    | Topic::TxStatusChanged
+            )
+        {
+            return Err(RPCError::invalid_params(
+                "The option `filter` only applies to the topics of the transactions",
+            ));
+        }
+        if options.filter.as_ref().map_or(false, |filter| {
+            filter.lock_hashes.is_empty() && filter.type_hashes.is_empty()
+        }) {
+            return Err(RPCError::invalid_params(
+                "The option `filter` requires at least one lock or type script hash",
+            ));
+        }
+        if options.batch.is_some()
+            && !matches!(topic, Topic::NewTransaction | Topic::RejectedTransaction)
+        {
+            return Err(RPCError::invalid_params(
+                "The option `batch` only applies to the topics `new_transaction` and `rejected_transaction`",
+            ));
+        }
+        let batch = options.batch.as_ref().map(batch_limits).transpose()?;
+        let filter = EventFilter::new(&options);
+
+        let (mut rx, replay) = {
+            let mut history = channel.history.lock();
+            if with_resume_token && !history.resumable() {
+                return Err(RPCError::custom(
+                    RPCError::ConfigError,
+                    "The subscriptions can't be resumed, see the config option `rpc.subscription_resume`",
+                ));
+            }
+            // subscribe with the lock held, so the replayed events and the new events are
+            // consecutive
+            let rx = channel.sender.subscribe();
+            let replay = match &options.resume_token {
+                Some(token) => {
+                    let token = ResumeToken::decode(token.as_bytes())
+                        .filter(|token| {
+                            token.instance_id == self.instance_id && token.topic == topic as u8
+                        })
+                        .ok_or_else(|| {
+                            RPCError::invalid_params(
+                                "The resume token is not issued by the current node process for the topic",
+                            )
+                        })?;
+                    history
+                        .events_after(token.seq, unix_time_as_millis())
+                        .ok_or_else(|| {
+                            RPCError::invalid_params(
+                                "The events after the resume token are no longer kept",
+                            )
+                        })?
+                }
+                None => history.last_event().cloned().into_iter().collect(),
+            };
+            (rx, replay)
+        };
+
+        let instance_id = self.instance_id;
+        let to_msg = move |seq: u64, json: &str| {
+            if with_resume_token {
+                let token = ResumeToken {
+                    instance_id,
+                    topic: topic as u8,
+                    seq,
+                }
+                .encode();
+                let token = serde_json::to_string(&token).expect("serialization should be ok");
+                PublishMsg::result(&format!(
+                    "{{\"event\":{},\"resume_token\":{}}}",
+                    json, token
+                ))
+            } else {
+                PublishMsg::result(json)
+            }
+        };
+        let Some((max_batch_size, max_batch_latency)) = batch else {
+            return Ok(Box::pin(async_stream::stream! {
+                    for event in replay.iter() {
+                        if let Some(json) = filter.apply(event) {
+                            yield to_msg(event.seq, &json);
+                        }
+                    }
+                    loop {
+                        match rx.recv().await {
+                            Ok(event) => {
+                                if let Some(json) = filter.apply(&event) {
+                                    yield to_msg(event.seq, &json);
+                                }
+                            }
+                            Err(RecvError::Lagged(cnt)) => {
+                                error!("subscription lagged error: {:?}", cnt);
+                            }
+                            Err(RecvError::Closed) => {
+                                break;
+                            }
+                        }
+                    }
+            }));
         };
-        let mut rx = tx.subscribe();
+        let mut batch = EventBatch::new(max_batch_size);
         Ok(Box::pin(async_stream::stream! {
+                for event in replay.iter() {
+                    if let Some(json) = filter.apply(event) {
+                        if let Some((seq, json)) = batch.push(event.seq, json) {
+                            yield to_msg(seq, &json);
+                        }
+                    }
+                }
+                if let Some((seq, json)) = batch.take() {
+                    yield to_msg(seq, &json);
+                }
+                // when the pending batch must be pushed
+                let mut deadline = None;
                 loop {
-                    match rx.recv().await {
-                        Ok(msg) => {
-                            yield msg;
+                    let received = match deadline {
+                        Some(deadline) => tokio::time::timeout_at(deadline, rx.recv()).await.ok(),
+                        None => Some(rx.recv().await),
+                    };
+                    match received {
+                        Some(Ok(event)) => {
+                            if let Some(json) = filter.apply(&event) {
+                                if batch.is_empty() {
+                                    deadline = Some(tokio::time::Instant::now() + max_batch_latency);
+                                }
+                                if let Some((seq, json)) = batch.push(event.seq, json) {
+                                    deadline = None;
+                                    yield to_msg(seq, &json);
+                                }
+                            }
                         }
-                        Err(RecvError::Lagged(cnt)) => {
+                        Some(Err(RecvError::Lagged(cnt))) => {
                             error!("subscription lagged error: {:?}", cnt);
                         }
-                        Err(RecvError::Closed) => {
+                        Some(Err(RecvError::Closed)) => {
+                            if let Some((seq, json)) = batch.take() {
+                                yield to_msg(seq, &json);
+                            }
                             break;
                         }
+                        None => {
+                            deadline = None;
+                            if let Some((seq, json)) = batch.take() {
+                                yield to_msg(seq, &json);
+                            }
+                        }
                     }
                 }
         }))
@@ -232,7 +906,11 @@ impl SubscriptionRpc for SubscriptionRpcImpl {
 }
 
 impl SubscriptionRpcImpl {
-    pub fn new(notify_controller: NotifyController, handle: Handle) -> Self {
+    pub fn new(
+        notify_controller: NotifyController,
+        resume_config: Option<SubscriptionResumeConfig>,
+        handle: Handle,
+    ) -> Self {
         const SUBSCRIBER_NAME: &str = "TcpSubscription";
 
         let mut new_block_receiver =
@@ -244,12 +922,23 @@ impl SubscriptionRpcImpl {
         );
         let mut reject_transaction_receiver = handle
             .block_on(notify_controller.subscribe_reject_transaction(SUBSCRIBER_NAME.to_string()));
+        let mut gap_transaction_receiver = handle
+            .block_on(notify_controller.subscribe_gap_transaction(SUBSCRIBER_NAME.to_string()));
+        let mut committed_transaction_receiver = handle.block_on(
+            notify_controller.subscribe_committed_transaction(SUBSCRIBER_NAME.to_string()),
+        );
+        let mut migration_finished_receiver = handle
+            .block_on(notify_controller.subscribe_migration_finished(SUBSCRIBER_NAME.to_string()));
 
-        let (new_tip_header_sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
-        let (new_tip_block_sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
-        let (proposed_transaction_sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
-        let (new_transaction_sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
-        let (new_reject_transaction_sender, _) = broadcast::channel(NOTIFY_CHANNEL_SIZE);
+        let new_tip_header_sender = TopicChannel::new(resume_config.clone());
+        let new_tip_block_sender = TopicChannel::new(resume_config.clone());
+        let proposed_transaction_sender = TopicChannel::new(resume_config.clone());
+        let new_transaction_sender = TopicChannel::new(resume_config.clone());
+        let new_reject_transaction_sender = TopicChannel::new(resume_config.clone());
+        let proposal_window_sender = TopicChannel::new(resume_config.clone());
+        let migration_finished_sender = TopicChannel::keeping_last_event(resume_config.clone());
+        let tx_status_changed_sender = TopicChannel::new(resume_config);
+        let mut tx_status_tracker = TxStatusTracker::new(MAX_TRACKED_TX_STATUSES);
 
         let stop_rx = new_tokio_exit_rx();
         handle.spawn({
@@ -258,6 +947,9 @@ impl SubscriptionRpcImpl {
             let new_transaction_sender = new_transaction_sender.clone();
             let proposed_transaction_sender = proposed_transaction_sender.clone();
             let new_reject_transaction_sender = new_reject_transaction_sender.clone();
+            let proposal_window_sender = proposal_window_sender.clone();
+            let migration_finished_sender = migration_finished_sender.clone();
+            let tx_status_changed_sender = tx_status_changed_sender.clone();
             async move {
                 loop {
                     tokio::select! {
@@ -266,15 +958,29 @@ impl SubscriptionRpcImpl {
                             publiser_send!(ckb_jsonrpc_types::BlockView, block, new_tip_block_sender);
                         },
                         Some(tx_entry) = new_transaction_receiver.recv() => {
-                            publiser_send!(ckb_jsonrpc_types::PoolTransactionEntry, tx_entry, new_transaction_sender);
+                            publish_tx_status(&tx_status_changed_sender, &mut tx_status_tracker, &tx_entry, Status::Pending);
+                            publish_tx_entry(&new_transaction_sender, tx_entry);
                         },
                         Some(tx_entry) = proposed_transaction_receiver.recv() => {
-                            publiser_send!(ckb_jsonrpc_types::PoolTransactionEntry, tx_entry, proposed_transaction_sender);
+                            publish_tx_status(&tx_status_changed_sender, &mut tx_status_tracker, &tx_entry, Status::Proposed);
+                            publish_proposal_window(&proposal_window_sender, ProposalWindowStatus::Proposed, tx_entry.clone());
+                            publish_tx_entry(&proposed_transaction_sender, tx_entry);
+                        },
+                        Some(tx_entry) = gap_transaction_receiver.recv() => {
+                            publish_tx_status(&tx_status_changed_sender, &mut tx_status_tracker, &tx_entry, Status::Pending);
+                            publish_proposal_window(&proposal_window_sender, ProposalWindowStatus::Gap, tx_entry);
+                        },
+                        Some(tx_entry) = committed_transaction_receiver.recv() => {
+                            publish_tx_status(&tx_status_changed_sender, &mut tx_status_tracker, &tx_entry, Status::Committed);
                         },
                         Some((tx_entry, reject)) = reject_transaction_receiver.recv() => {
-                            publiser_send!((ckb_jsonrpc_types::PoolTransactionEntry, ckb_jsonrpc_types::PoolTransactionReject),
-                                            (tx_entry.into(), reject.into()),
-                                            new_reject_transaction_sender);
+                            publish_tx_status(&tx_status_changed_sender, &mut tx_status_tracker, &tx_entry, Status::Rejected);
+                            let tx = EventTx::new(&tx_entry);
+                            let json = to_json(&(PoolTransactionEntry::from(tx_entry), PoolTransactionReject::from(reject)));
+                            new_reject_transaction_sender.publish_for_tx(json, tx);
+                        }
+                        Some(outcome) = migration_finished_receiver.recv() => {
+                            publiser_send!(MigrationFinishedEvent, outcome, migration_finished_sender);
                         }
                         _ = stop_rx.cancelled() => {
                             break;
@@ -294,6 +1000,10 @@ impl SubscriptionRpcImpl {
             new_transaction_sender,
             proposed_transaction_sender,
             new_reject_transaction_sender,
+            proposal_window_sender,
+            migration_finished_sender,
+            tx_status_changed_sender,
+            instance_id: unix_time_as_millis(),
         }
     }
 }
@@ -1,15 +1,26 @@
+use crate::util::block_export::{export_blocks_handler, BlockExport};
+use crate::util::deprecation::DeprecatedRpcMethod;
+use crate::util::find_deprecated_method;
+use crate::util::timeout::is_timeout_error;
 use crate::IoHandler;
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Extension, Router};
+use axum_server::tls_rustls::RustlsConfig;
 use ckb_app_config::RpcConfig;
 use ckb_async_runtime::Handle;
 use ckb_error::AnyError;
-use ckb_logger::info;
+use ckb_logger::{error, info};
+use ckb_shared::Shared;
 
-use axum::{body::Bytes, http::StatusCode, response::Response, Json};
+use axum::{
+    body::Bytes,
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::Response,
+    Json,
+};
 
-use jsonrpc_core::{MetaIoHandler, Metadata, Request};
+use jsonrpc_core::{Call, Id, MetaIoHandler, Metadata, Output, Request};
 
 use ckb_stop_handler::{new_tokio_exit_rx, CancellationToken};
 use futures_util::future;
@@ -25,14 +36,16 @@ use jsonrpc_utils::stream::{serve_stream_sink, StreamMsg, StreamServerConfig};
 use std::net::{SocketAddr, ToSocketAddrs};
 use std::sync::Arc;
 use std::sync::OnceLock;
-use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec, LinesCodecError};
 use tower_http::cors::CorsLayer;
-use tower_http::timeout::TimeoutLayer;
 
 static JSONRPC_BATCH_LIMIT: OnceLock<usize> = OnceLock::new();
 
+// The headers of the responses to the deprecated method calls, see RFC 8594
+const DEPRECATION_HEADER: HeaderName = HeaderName::from_static("deprecation");
+const SUNSET_HEADER: HeaderName = HeaderName::from_static("sunset");
+
 #[doc(hidden)]
 #[derive(Debug)]
 pub struct RpcServer {
@@ -48,31 +61,60 @@ impl RpcServer {
     ///
     /// * `config` - RPC config options.
     /// * `io_handler` - RPC methods handler. See [ServiceBuilder](../service_builder/struct.ServiceBuilder.html).
+    /// * `shared` - Serves the blocks export endpoint if the `Chain` module is enabled.
     /// * `handler` - Tokio runtime handle.
-    pub fn new(config: RpcConfig, io_handler: IoHandler, handler: Handle) -> Self {
+    pub fn new(config: RpcConfig, io_handler: IoHandler, shared: &Shared, handler: Handle) -> Self {
         if let Some(jsonrpc_batch_limit) = config.rpc_batch_limit {
             let _ = JSONRPC_BATCH_LIMIT.get_or_init(|| jsonrpc_batch_limit);
         }
 
         let rpc = Arc::new(io_handler);
 
+        let tls = config.tls.as_ref().map(|tls_config| {
+            let tls = handler
+                .block_on(RustlsConfig::from_pem_file(
+                    &tls_config.cert_path,
+                    &tls_config.key_path,
+                ))
+                .expect("config rpc tls certificate and key loaded");
+            #[cfg(unix)]
+            Self::reload_tls_on_hangup(tls.clone(), tls_config.clone(), &handler);
+            tls
+        });
+        let (http_scheme, ws_scheme) = if tls.is_some() {
+            ("HTTPS", "WSS")
+        } else {
+            ("HTTP", "WebSocket")
+        };
+
+        let block_export = config
+            .chain_enable()
+            .then(|| BlockExport::new(shared.clone()));
         let http_address = Self::start_server(
             &rpc,
+            block_export.clone(),
             config.listen_address.to_owned(),
             handler.clone(),
             false,
+            tls.clone(),
         )
         .map(|local_addr| {
-            info!("Listen HTTP RPCServer on address: {}", local_addr);
+            info!(
+                "Listen {} RPCServer on address: {}",
+                http_scheme, local_addr
+            );
             local_addr
         })
         .unwrap();
 
         let ws_address = if let Some(addr) = config.ws_listen_address {
-            let local_addr = Self::start_server(&rpc, addr, handler.clone(), true).map(|addr| {
-                info!("Listen WebSocket RPCServer on address: {}", addr);
-                addr
-            });
+            let local_addr =
+                Self::start_server(&rpc, block_export, addr, handler.clone(), true, tls).map(
+                    |addr| {
+                        info!("Listen {} RPCServer on address: {}", ws_scheme, addr);
+                        addr
+                    },
+                );
             local_addr.ok()
         } else {
             None
@@ -97,9 +139,11 @@ impl RpcServer {
 
     fn start_server(
         rpc: &Arc<MetaIoHandler<Option<Session>>>,
+        block_export: Option<BlockExport>,
         address: String,
         handler: Handle,
         enable_websocket: bool,
+        tls: Option<RustlsConfig>,
     ) -> Result<SocketAddr, AnyError> {
         let stream_config = StreamServerConfig::default()
             .with_keep_alive(true)
@@ -117,26 +161,34 @@ impl RpcServer {
         };
         let method_router = post_router.merge(get_router);
 
-        let app = Router::new()
+        let mut app = Router::new()
             .route("/", method_router.clone())
             .route("/*path", method_router)
-            .route("/ping", get(ping_handler))
+            .route("/ping", get(ping_handler));
+        if let Some(block_export) = block_export {
+            app = app
+                .route("/export/blocks", get(export_blocks_handler))
+                .layer(Extension(block_export));
+        }
+        let app = app
             .layer(Extension(Arc::clone(rpc)))
             .layer(CorsLayer::permissive())
-            .layer(TimeoutLayer::new(Duration::from_secs(30)))
             .layer(Extension(stream_config));
 
+        let address = address
+            .to_socket_addrs()
+            .expect("config listen_address parsed")
+            .next()
+            .expect("config listen_address parsed");
+
+        if let Some(tls) = tls {
+            return Self::start_tls_server(app, address, handler, tls);
+        }
+
         let (tx_addr, rx_addr) = tokio::sync::oneshot::channel::<SocketAddr>();
 
         handler.spawn(async move {
-            let server = axum::Server::bind(
-                &address
-                    .to_socket_addrs()
-                    .expect("config listen_address parsed")
-                    .next()
-                    .expect("config listen_address parsed"),
-            )
-            .serve(app.clone().into_make_service());
+            let server = axum::Server::bind(&address).serve(app.clone().into_make_service());
 
             let _ = tx_addr.send(server.local_addr());
             let graceful = server.with_graceful_shutdown(async move {
@@ -149,6 +201,81 @@ impl RpcServer {
         Ok(rx_addr)
     }
 
+    fn start_tls_server(
+        app: Router,
+        address: SocketAddr,
+        handler: Handle,
+        tls: RustlsConfig,
+    ) -> Result<SocketAddr, AnyError> {
+        let server_handle = axum_server::Handle::new();
+
+        let shutdown_handle = server_handle.clone();
+        handler.spawn(async move {
+            new_tokio_exit_rx().cancelled().await;
+            shutdown_handle.graceful_shutdown(None);
+        });
+
+        let listening_handle = server_handle.clone();
+        handler.spawn(async move {
+            if let Err(err) = axum_server::bind_rustls(address, tls)
+                .handle(server_handle)
+                .serve(app.into_make_service())
+                .await
+            {
+                error!("TLS RPCServer error: {}", err);
+            }
+        });
+
+        handler
+            .block_on(listening_handle.listening())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("TLS RPCServer failed to listen on {address}"),
+                )
+                .into()
+            })
+    }
+
+    // Reloads the certificate and key from the files on SIGHUP, so they can be renewed without
+    // restarting the node. The new ones are used by the connections accepted afterwards.
+    #[cfg(unix)]
+    fn reload_tls_on_hangup(
+        tls: RustlsConfig,
+        tls_config: ckb_app_config::RpcTlsConfig,
+        handler: &Handle,
+    ) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        handler.spawn(async move {
+            let mut hangup = match signal(SignalKind::hangup()) {
+                Ok(hangup) => hangup,
+                Err(err) => {
+                    error!(
+                        "Failed to listen SIGHUP to reload RPC TLS certificate: {}",
+                        err
+                    );
+                    return;
+                }
+            };
+            let exit_signal: CancellationToken = new_tokio_exit_rx();
+            loop {
+                tokio::select! {
+                    _ = hangup.recv() => {
+                        match tls
+                            .reload_from_pem_file(&tls_config.cert_path, &tls_config.key_path)
+                            .await
+                        {
+                            Ok(()) => info!("Reloaded RPC TLS certificate"),
+                            Err(err) => error!("Failed to reload RPC TLS certificate: {}", err),
+                        }
+                    }
+                    _ = exit_signal.cancelled() => break,
+                }
+            }
+        });
+    }
+
     async fn start_tcp_server(
         rpc: Arc<MetaIoHandler<Option<Session>>>,
         tcp_listen_address: String,
@@ -231,6 +358,7 @@ async fn handle_jsonrpc<T: Default + Metadata>(
         }
     };
 
+    let mut deprecated = Vec::new();
     let req = serde_json::from_str::<Request>(req);
     let result = match req {
         Err(_error) => Left(future::ready(Some(RpcResponse::from(
@@ -248,21 +376,83 @@ async fn handle_jsonrpc<T: Default + Metadata>(
                     }
                 }
             }
+            deprecated = deprecated_calls(&request);
             Right(io.handle_rpc_request(request, T::default()))
         }
     };
 
     if let Some(response) = result.await {
-        serde_json::to_string(&response)
-            .map(|json| {
-                (
-                    [(axum::http::header::CONTENT_TYPE, "application/json")],
-                    json,
-                )
-                    .into_response()
+        let timed_out = matches!(
+            &response,
+            RpcResponse::Single(Output::Failure(failure)) if is_timeout_error(&failure.error)
+        );
+        let json = if deprecated.is_empty() {
+            serde_json::to_string(&response)
+        } else {
+            serde_json::to_value(&response).and_then(|mut value| {
+                add_deprecation_warnings(&mut value, &deprecated);
+                serde_json::to_string(&value)
             })
-            .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+        };
+        json.map(|json| {
+            let mut response = (
+                [(axum::http::header::CONTENT_TYPE, "application/json")],
+                json,
+            )
+                .into_response();
+            if timed_out {
+                *response.status_mut() = StatusCode::GATEWAY_TIMEOUT;
+            }
+            if !deprecated.is_empty() {
+                let headers = response.headers_mut();
+                headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("true"));
+                if let Some(sunset) = deprecated.iter().find_map(|(_, method)| method.sunset) {
+                    headers.insert(SUNSET_HEADER, HeaderValue::from_static(sunset));
+                }
+            }
+            response
+        })
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
     } else {
         StatusCode::NO_CONTENT.into_response()
     }
 }
+
+// The deprecated method calls in the request, with the ids to find their responses.
+fn deprecated_calls(request: &Request) -> Vec<(Id, &'static DeprecatedRpcMethod)> {
+    let calls = match request {
+        Request::Single(call) => std::slice::from_ref(call),
+        Request::Batch(calls) => calls.as_slice(),
+    };
+    calls
+        .iter()
+        .filter_map(|call| match call {
+            Call::MethodCall(method_call) => find_deprecated_method(&method_call.method)
+                .map(|method| (method_call.id.clone(), method)),
+            _ => None,
+        })
+        .collect()
+}
+
+// Adds the `warning` field to the responses of the deprecated method calls.
+fn add_deprecation_warnings(
+    response: &mut serde_json::Value,
+    deprecated: &[(Id, &'static DeprecatedRpcMethod)],
+) {
+    let outputs = match response {
+        serde_json::Value::Array(outputs) => outputs.iter_mut().collect(),
+        output => vec![output],
+    };
+    for output in outputs {
+        if let serde_json::Value::Object(fields) = output {
+            let method = fields.get("id").and_then(|id| {
+                deprecated
+                    .iter()
+                    .find(|(call_id, _)| serde_json::to_value(call_id).ok().as_ref() == Some(id))
+            });
+            if let Some((_, method)) = method {
+                fields.insert("warning".to_owned(), method.warning().into());
+            }
+        }
+    }
+}
@@ -7,20 +7,24 @@ use crate::module::{
     ExperimentRpcImpl, IndexerRpcImpl, IntegrationTestRpcImpl, MinerRpcImpl, NetRpcImpl,
     PoolRpcImpl, RichIndexerRpcImpl, StatsRpcImpl, SubscriptionRpcImpl,
 };
+use crate::util::timeout::with_timeout;
+use crate::util::{find_deprecated_method, warn_deprecated_call, MethodTimeouts};
 use crate::{IoHandler, RPCError};
 use ckb_app_config::{DBConfig, IndexerConfig, RpcConfig};
 use ckb_chain::ChainController;
 use ckb_indexer::IndexerService;
 use ckb_indexer_sync::{new_secondary_db, PoolService};
+use ckb_logger::error;
 use ckb_network::NetworkController;
 use ckb_network_alert::{notifier::Notifier as AlertNotifier, verifier::Verifier as AlertVerifier};
 use ckb_pow::Pow;
 use ckb_rich_indexer::RichIndexerService;
+use ckb_shared::header_checkpoints::HeaderCheckpointSigner;
 use ckb_shared::shared::Shared;
 use ckb_sync::SyncShared;
 use ckb_types::packed::Script;
 use ckb_util::Mutex;
-use jsonrpc_core::{MetaIoHandler, RemoteProcedure};
+use jsonrpc_core::{MetaIoHandler, Params, RemoteProcedure, RpcMethod};
 use jsonrpc_utils::pub_sub::Session;
 use std::sync::Arc;
 
@@ -30,6 +34,7 @@ const DEPRECATED_RPC_PREFIX: &str = "deprecated.";
 pub struct ServiceBuilder<'a> {
     config: &'a RpcConfig,
     io_handler: IoHandler,
+    timeouts: MethodTimeouts,
 }
 
 macro_rules! set_rpc_module_methods {
@@ -51,6 +56,7 @@ impl<'a> ServiceBuilder<'a> {
         Self {
             config,
             io_handler: IoHandler::with_compatibility(jsonrpc_core::Compatibility::V2),
+            timeouts: MethodTimeouts::new(&config.timeouts),
         }
     }
 
@@ -67,11 +73,19 @@ impl<'a> ServiceBuilder<'a> {
         extra_well_known_lock_scripts: Vec<Script>,
         extra_well_known_type_scripts: Vec<Script>,
     ) -> Self {
+        let tx_pool_enabled = shared.tx_pool_controller().is_enabled();
         let methods = PoolRpcImpl::new(
             shared,
             extra_well_known_lock_scripts,
             extra_well_known_type_scripts,
+            self.config.send_transaction_idempotency_window_secs,
         );
+        if self.config.pool_enable() && !tx_pool_enabled {
+            let mut meta_io = MetaIoHandler::default();
+            add_pool_rpc_methods(&mut meta_io, methods);
+            self.update_unavailable_methods(meta_io, RPCError::tx_pool_is_disabled());
+            return self;
+        }
         set_rpc_module_methods!(self, "Pool", pool_enable, add_pool_rpc_methods, methods)
     }
 
@@ -104,11 +118,13 @@ impl<'a> ServiceBuilder<'a> {
         network_controller: NetworkController,
         sync_shared: Arc<SyncShared>,
         chain_controller: Arc<ChainController>,
+        checkpoint_signer: Arc<HeaderCheckpointSigner>,
     ) -> Self {
         let methods = NetRpcImpl {
             network_controller,
             sync_shared,
             chain_controller,
+            checkpoint_signer,
         };
         set_rpc_module_methods!(self, "Net", net_enable, add_net_rpc_methods, methods)
     }
@@ -122,6 +138,7 @@ impl<'a> ServiceBuilder<'a> {
         let methods = StatsRpcImpl {
             shared,
             alert_notifier,
+            enable_deprecated_rpc: self.config.enable_deprecated_rpc,
         };
         set_rpc_module_methods!(self, "Stats", stats_enable, add_stats_rpc_methods, methods)
     }
@@ -183,8 +200,8 @@ impl<'a> ServiceBuilder<'a> {
     }
 
     /// Mounts methods from module Debug if it is enabled in the config.
-    pub fn enable_debug(mut self) -> Self {
-        let methods = DebugRpcImpl {};
+    pub fn enable_debug(mut self, shared: Shared) -> Self {
+        let methods = DebugRpcImpl { shared };
         set_rpc_module_methods!(self, "Debug", debug_enable, add_debug_rpc_methods, methods)
     }
 
@@ -196,7 +213,17 @@ impl<'a> ServiceBuilder<'a> {
         indexer_config: &IndexerConfig,
     ) -> Self {
         // Initialize instances of data sources that will be shared for use by indexer and rich-indexer.
-        let ckb_secondary_db = new_secondary_db(db_config, &indexer_config.into());
+        // The config of the database has been checked when it is opened.
+        let ckb_secondary_db = match new_secondary_db(db_config, &indexer_config.into()) {
+            Ok(db) => db,
+            Err(err) => {
+                error!(
+                    "Failed to open the secondary database for the indexer: {}",
+                    err
+                );
+                return self;
+            }
+        };
         let pool_service =
             PoolService::new(indexer_config.index_tx_pool, shared.async_handle().clone());
 
@@ -254,6 +281,7 @@ impl<'a> ServiceBuilder<'a> {
         if self.config.subscription_enable() {
             let methods = SubscriptionRpcImpl::new(
                 shared.notify_controller().clone(),
+                self.config.subscription_resume.clone(),
                 shared.async_handle().clone(),
             );
             let mut meta_io = MetaIoHandler::default();
@@ -267,13 +295,18 @@ impl<'a> ServiceBuilder<'a> {
         I: IntoIterator<Item = (String, RemoteProcedure<Option<Session>>)>,
     {
         let enable_deprecated_rpc = self.config.enable_deprecated_rpc;
+        let timeouts = &self.timeouts;
         self.io_handler
             .extend_with(rpc_methods.into_iter().map(|(name, method)| {
                 if let Some(striped_method_name) = name.strip_prefix(DEPRECATED_RPC_PREFIX) {
                     (
                         striped_method_name.to_owned(),
                         if enable_deprecated_rpc {
-                            method
+                            with_timeout(
+                                timeouts,
+                                striped_method_name,
+                                warn_on_call(striped_method_name, method),
+                            )
                         } else {
                             RemoteProcedure::Method(Arc::new(|_param, _meta| async {
                                 Err(RPCError::rpc_method_is_deprecated())
@@ -281,17 +314,25 @@ impl<'a> ServiceBuilder<'a> {
                         },
                     )
                 } else {
+                    let method = with_timeout(timeouts, &name, method);
                     (name, method)
                 }
             }));
     }
 
     fn update_disabled_methods<I, M>(&mut self, module: &str, rpc_methods: I)
+    where
+        I: IntoIterator<Item = (String, M)>,
+    {
+        self.update_unavailable_methods(rpc_methods, RPCError::rpc_module_is_disabled(module));
+    }
+
+    fn update_unavailable_methods<I, M>(&mut self, rpc_methods: I, error: jsonrpc_core::Error)
     where
         I: IntoIterator<Item = (String, M)>,
     {
         rpc_methods.into_iter().for_each(|(name, _method)| {
-            let error = Err(RPCError::rpc_module_is_disabled(module));
+            let error = Err(error.clone());
             self.io_handler.add_sync_method(
                 name.split(DEPRECATED_RPC_PREFIX)
                     .collect::<Vec<&str>>()
@@ -309,3 +350,19 @@ impl<'a> ServiceBuilder<'a> {
         io_handler
     }
 }
+
+// Logs the throttled warnings when the deprecated method is called.
+fn warn_on_call(
+    name: &str,
+    method: RemoteProcedure<Option<Session>>,
+) -> RemoteProcedure<Option<Session>> {
+    match (find_deprecated_method(name), method) {
+        (Some(deprecated), RemoteProcedure::Method(inner)) => {
+            RemoteProcedure::Method(Arc::new(move |params: Params, meta: Option<Session>| {
+                warn_deprecated_call(deprecated);
+                inner.call(params, meta)
+            }))
+        }
+        (_, other) => other,
+    }
+}
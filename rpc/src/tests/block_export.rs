@@ -0,0 +1,61 @@
+use crate::tests::setup_rpc_test_suite;
+use ckb_jsonrpc_types::{BlockView, JsonBytes};
+use ckb_store::ChainStore;
+use ckb_types::prelude::*;
+use reqwest::StatusCode;
+
+#[test]
+fn test_export_blocks() {
+    let suite = setup_rpc_test_suite(20, None);
+    let export = |query: &str| {
+        suite
+            .rpc_client
+            .get(format!("{}export/blocks?{}", suite.rpc_uri, query))
+            .send()
+            .unwrap()
+    };
+    let store = suite.shared.store();
+    let block_of = |number| {
+        store
+            .get_block(&store.get_block_hash(number).unwrap())
+            .unwrap()
+    };
+
+    let response = export("from=1&to=0x3");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    let blocks: Vec<BlockView> = response
+        .text()
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        blocks,
+        (1..=3)
+            .map(|number| BlockView::from(block_of(number)))
+            .collect::<Vec<_>>()
+    );
+
+    // the range is capped by the tip
+    let lines: Vec<JsonBytes> = export("from=19&to=100&format=hex")
+        .text()
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            JsonBytes::from_bytes(block_of(19).data().as_bytes()),
+            JsonBytes::from_bytes(block_of(20).data().as_bytes()),
+        ]
+    );
+
+    assert_eq!(export("from=3&to=1").status(), StatusCode::BAD_REQUEST);
+    assert_eq!(export("from=a&to=1").status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        export("from=1&to=3&format=csv").status(),
+        StatusCode::BAD_REQUEST
+    );
+}
@@ -0,0 +1,95 @@
+use crate::tests::{setup, RpcTestRequest};
+use crate::util::{find_deprecated_method, DEPRECATED_METHODS};
+use ckb_test_chain_utils::always_success_consensus;
+use serde_json::Value;
+use std::fs::{read_dir, read_to_string};
+use std::path::PathBuf;
+
+// Collects the names of the methods marked `#[deprecated]` in the RPC modules.
+fn collect_deprecated_rpc_names() -> Vec<String> {
+    let mut modules_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    modules_dir.push("src");
+    modules_dir.push("module");
+
+    let mut names = Vec::new();
+    for module_file in read_dir(modules_dir).unwrap() {
+        let path = module_file.unwrap().path();
+        if path.extension().unwrap_or_default() != "rs" {
+            continue;
+        }
+        let source = read_to_string(&path).unwrap();
+        let mut deprecated = false;
+        for line in source.lines().map(str::trim) {
+            if line.starts_with("#[deprecated(") {
+                deprecated = true;
+            } else if let Some(rest) = line.strip_prefix("#[rpc(name = \"") {
+                if deprecated {
+                    names.push(rest.split('"').next().unwrap().to_owned());
+                }
+                deprecated = false;
+            } else if line.starts_with("fn ") {
+                deprecated = false;
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+#[test]
+fn test_deprecated_methods_are_registered() {
+    let names = collect_deprecated_rpc_names();
+    let registered: Vec<String> = DEPRECATED_METHODS
+        .iter()
+        .map(|method| method.name.to_owned())
+        .collect();
+    assert_eq!(names, registered, "update DEPRECATED_METHODS");
+}
+
+#[test]
+fn test_deprecated_method_warning() {
+    let method = find_deprecated_method("get_fee_rate_statics").unwrap();
+    assert_eq!(
+        method.warning(),
+        "get_fee_rate_statics is deprecated since 0.109.0, please use get_fee_rate_statistics instead"
+    );
+    assert!(find_deprecated_method("get_fee_rate_statistics").is_none());
+}
+
+#[test]
+fn test_deprecated_method_response() {
+    let suite = setup(always_success_consensus());
+    let request = |id, method: &str| RpcTestRequest {
+        id,
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: vec![],
+    };
+
+    let response = suite.send_request(&request(42, "get_fee_rate_statics"));
+    assert_eq!(
+        response.headers().get("deprecation").unwrap(),
+        "true",
+        "Deprecation header"
+    );
+    assert!(response.headers().get("sunset").is_none());
+    let response: Value = response.json().unwrap();
+    assert!(response["warning"]
+        .as_str()
+        .unwrap()
+        .contains("get_fee_rate_statistics"));
+
+    let response = suite.send_request(&[
+        request(1, "get_tip_block_number"),
+        request(2, "get_fee_rate_statics"),
+    ]);
+    assert!(response.headers().get("deprecation").is_some());
+    let response: Value = response.json().unwrap();
+    assert!(response[0].get("warning").is_none());
+    assert!(response[1].get("warning").is_some());
+
+    let response = suite.send_request(&request(42, "get_tip_block_number"));
+    assert!(response.headers().get("deprecation").is_none());
+    let response: Value = response.json().unwrap();
+    assert!(response.get("warning").is_none());
+}
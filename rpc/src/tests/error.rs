@@ -2,8 +2,9 @@ use ckb_dao_utils::DaoError;
 use ckb_error::Error as CKBError;
 use ckb_tx_pool::error::Reject;
 use ckb_types::{
-    core::{error::OutPointError, FeeRate},
+    core::{error::OutPointError, tx_pool::REJECT_CODES, Capacity, FeeRate},
     packed::Byte32,
+    H256,
 };
 
 use crate::error::RPCError;
@@ -68,3 +69,122 @@ fn test_out_point_error_from_ckb_error() {
             RPCError::from_ckb_error(err).message
         );
 }
+
+#[test]
+fn test_pool_transaction_reject_code() {
+    let rejects = vec![
+        Reject::LowFeeRate(FeeRate::from_u64(500), 100, 50),
+        Reject::ExceededMaximumAncestorsCount,
+        Reject::ExceededTransactionSizeLimit(10, 9),
+        Reject::Full(Default::default()),
+        Reject::FullFeeRateFloor(FeeRate::from_u64(500), FeeRate::from_u64(1000)),
+        Reject::Duplicated(Byte32::new([0; 32])),
+        Reject::Malformed(Default::default(), Default::default()),
+        Reject::DeclaredWrongCycles(1, 2),
+        Reject::Resolve(OutPointError::Dead(Default::default())),
+        Reject::Expiry(0),
+        Reject::RBFRejected(Default::default()),
+        Reject::RBFRejectedByFee(Capacity::shannons(100), Capacity::shannons(200)),
+        Reject::RBFReplaced(Byte32::new([1; 32])),
+        Reject::Invalidated(Default::default()),
+        Reject::AbsurdFee(1, Default::default()),
+        Reject::ExceededMaximumScriptGroupCycles(Byte32::new([1; 32]), 1),
+        Reject::HeaderDepTooRecent(Byte32::new([1; 32]), 1, 2),
+    ];
+    for reject in rejects {
+        let expected = RPCError::from_submit_transaction_reject(&reject)
+            .code
+            .code();
+        let json_reject: ckb_jsonrpc_types::PoolTransactionReject = reject.into();
+        assert_eq!(expected, json_reject.code);
+    }
+
+    let json_reject: ckb_jsonrpc_types::PoolTransactionReject =
+        Reject::LowFeeRate(FeeRate::from_u64(500), 100, 50).into();
+    assert_eq!(Some(100u64.into()), json_reject.min_fee);
+
+    let json_reject: ckb_jsonrpc_types::PoolTransactionReject =
+        Reject::RBFReplaced(Byte32::new([1; 32])).into();
+    assert_eq!(
+        ckb_jsonrpc_types::PoolTransactionRejectType::RBFRejected,
+        json_reject.reject_type
+    );
+    assert_eq!(Some(H256([1u8; 32])), json_reject.replacing_tx_hash);
+}
+
+#[test]
+fn test_reject_code_table() {
+    for (code, name, rpc_error_code) in REJECT_CODES {
+        assert_eq!(RPCError::from_reject_code(code) as i64, rpc_error_code);
+        let reject_type = ckb_jsonrpc_types::PoolTransactionRejectType::from(code);
+        assert_eq!(serde_json::to_value(reject_type).unwrap(), name);
+        assert_eq!(reject_type.code(), rpc_error_code);
+    }
+
+    // the rejected status carries the code of the recorded reject
+    let record: ckb_jsonrpc_types::PoolTransactionReject = Reject::Expiry(0).into();
+    let status = ckb_jsonrpc_types::TxStatus::rejected(serde_json::to_string(&record).unwrap());
+    assert_eq!(
+        status.reason_code,
+        Some(ckb_jsonrpc_types::PoolTransactionRejectType::Expiry)
+    );
+    let status = ckb_jsonrpc_types::TxStatus::rejected("Expiry transaction".to_owned());
+    assert_eq!(status.reason_code, None);
+}
+
+// The data of the fee rejections is the JSON string of the structured reject
+fn reject_data(err: &jsonrpc_core::Error) -> serde_json::Value {
+    let data = err.data.as_ref().and_then(|data| data.as_str()).unwrap();
+    serde_json::from_str(data).unwrap()
+}
+
+#[test]
+fn test_submit_transaction_fee_thresholds() {
+    let err = RPCError::from_submit_transaction_reject(&Reject::LowFeeRate(
+        FeeRate::from_u64(500),
+        100,
+        50,
+    ));
+    let data = reject_data(&err);
+    assert_eq!(data["type"], "LowFeeRate");
+    assert_eq!(data["min_fee"], "0x64");
+    assert_eq!(data["min_fee_rate"], "0x1f4");
+
+    let err = RPCError::from_submit_transaction_reject(&Reject::FullFeeRateFloor(
+        FeeRate::from_u64(500),
+        FeeRate::from_u64(1000),
+    ));
+    assert_eq!(err.code.code(), RPCError::PoolIsFull as i64);
+    assert_eq!(
+        "PoolIsFull: Transaction is replaced because the pool is full, the fee_rate for this transaction is: 500 shannons/KW, expect it to > 1000 shannons/KW to stay in the pool",
+        err.message
+    );
+    let data = reject_data(&err);
+    assert_eq!(data["type"], "Full");
+    assert_eq!(data["min_fee_rate"], "0x3e8");
+    assert!(data.get("min_replace_fee").is_none());
+
+    let err = RPCError::from_submit_transaction_reject(&Reject::RBFRejectedByFee(
+        Capacity::shannons(100),
+        Capacity::shannons(200),
+    ));
+    assert_eq!(err.code.code(), RPCError::PoolRejectedRBF as i64);
+    assert_eq!(
+        "PoolRejectedRBF: RBF rejected: Tx's current fee is 100, expect it to >= 200 to replace old txs",
+        err.message
+    );
+    let data = reject_data(&err);
+    assert_eq!(data["type"], "RBFRejected");
+    assert_eq!(data["min_replace_fee"], "0xc8");
+
+    // the other rejects keep the debug string as the data
+    let err = RPCError::from_submit_transaction_reject(&Reject::Full("total_tx_size".to_owned()));
+    assert!(err.data.unwrap().is_string());
+}
+
+#[test]
+fn test_tx_pool_is_disabled_error() {
+    let err = RPCError::tx_pool_is_disabled();
+    assert_eq!(err.code.code(), RPCError::ConfigError as i64);
+    assert!(err.message.contains("[tx_pool]"));
+}
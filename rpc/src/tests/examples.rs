@@ -350,7 +350,11 @@ where
 // * Use replace_rpc_response to skip the response matching assertions.
 // * Fix timestamp related fields.
 fn mock_rpc_response(example: &RpcTestExample, response: &mut RpcTestResponse) {
-    use ckb_jsonrpc_types::{BannedAddr, Capacity, LocalNode, RemoteNode, Uint64};
+    use ckb_jsonrpc_types::{
+        BannedAddr, BootnodeHealth, Capacity, DialPeerResult, EconomicStateSummary, EpochStats,
+        LocalNode, PeerMisbehavior, ProposalWindowSimulation, RemoteNode, ReplacementSimulation,
+        SignedHeaderCheckpoints, TemplateBacklog, Uint64, UncleStats,
+    };
 
     let example_tx_hash = format!("{EXAMPLE_TX_HASH:#x}");
 
@@ -358,10 +362,22 @@ fn mock_rpc_response(example: &RpcTestExample, response: &mut RpcTestResponse) {
         "local_node_info" => replace_rpc_response::<LocalNode>(example, response),
         "get_peers" => replace_rpc_response::<Vec<RemoteNode>>(example, response),
         "get_banned_addresses" => replace_rpc_response::<Vec<BannedAddr>>(example, response),
+        "get_bootnodes_health" => replace_rpc_response::<Vec<BootnodeHealth>>(example, response),
+        "get_peer_misbehaviors" => replace_rpc_response::<Vec<PeerMisbehavior>>(example, response),
+        "dial_peer" => replace_rpc_response::<DialPeerResult>(example, response),
+        "get_header_checkpoints" => {
+            replace_rpc_response::<SignedHeaderCheckpoints>(example, response)
+        }
         "calculate_dao_maximum_withdraw" => replace_rpc_response::<Capacity>(example, response),
+        "get_epoch_stats" => replace_rpc_response::<Option<EpochStats>>(example, response),
+        "get_block_economic_state_range" => {
+            replace_rpc_response::<Vec<EconomicStateSummary>>(example, response)
+        }
+        "get_uncle_stats" => replace_rpc_response::<Vec<UncleStats>>(example, response),
         "subscribe" => replace_rpc_response::<Uint64>(example, response),
         "unsubscribe" => replace_rpc_response::<bool>(example, response),
         "send_transaction" => replace_rpc_response::<H256>(example, response),
+        "send_transaction_package" => replace_rpc_response::<Vec<H256>>(example, response),
         "get_block_template" => {
             response.result["current_time"] = example.response.result["current_time"].clone();
             response.result["work_id"] = example.response.result["work_id"].clone();
@@ -372,7 +388,9 @@ fn mock_rpc_response(example: &RpcTestExample, response: &mut RpcTestResponse) {
         }
         "tx_pool_info" => {
             response.result["last_txs_updated_at"] =
-                example.response.result["last_txs_updated_at"].clone()
+                example.response.result["last_txs_updated_at"].clone();
+            response.result["top_lock_scripts"] =
+                example.response.result["top_lock_scripts"].clone();
         }
         "get_blockchain_info" => {
             response.result["chain"] = example.response.result["chain"].clone()
@@ -386,9 +404,31 @@ fn mock_rpc_response(example: &RpcTestExample, response: &mut RpcTestResponse) {
         "generate_block" => replace_rpc_response::<H256>(example, response),
         "process_block_without_verify" => replace_rpc_response::<H256>(example, response),
         "notify_transaction" => replace_rpc_response::<H256>(example, response),
+        "simulate_replacement" => replace_rpc_response::<ReplacementSimulation>(example, response),
+        "simulate_proposal_window" => {
+            replace_rpc_response::<ProposalWindowSimulation>(example, response)
+        }
+        "estimate_fee_rate" => replace_rpc_response::<Uint64>(example, response),
+        "get_block_template_backlog" => {
+            replace_rpc_response::<Vec<TemplateBacklog>>(example, response)
+        }
         "get_pool_tx_detail_info" => {
             response.result["timestamp"] = example.response.result["timestamp"].clone()
         }
+        "dry_run_script_groups" => {
+            response.result[0]["script_hash"] = example.response.result[0]["script_hash"].clone()
+        }
+        "estimate_cycles" | "dry_run_transaction" => {
+            response.result["script_groups"][0]["script_hash"] =
+                example.response.result["script_groups"][0]["script_hash"].clone()
+        }
+        "diagnose_transaction" => {
+            response.result["expires_at"] = example.response.result["expires_at"].clone()
+        }
+        "get_pool_tx_graph" => {
+            response.result["nodes"][0]["timestamp"] =
+                example.response.result["nodes"][0]["timestamp"].clone()
+        }
         _ => {}
     }
 }
@@ -424,7 +464,7 @@ fn before_rpc_example(suite: &RpcTestSuite, example: &mut RpcTestExample) -> boo
 fn after_rpc_example(suite: &RpcTestSuite, example: &RpcTestExample) {
     match example.request.method.as_str() {
         "clear_tx_pool" => suite.send_example_transaction(),
-        "send_transaction" => {
+        "send_transaction" | "send_transaction_package" => {
             suite.rpc(&RpcTestRequest {
                 id: 42,
                 jsonrpc: "2.0".to_string(),
@@ -434,6 +474,16 @@ fn after_rpc_example(suite: &RpcTestSuite, example: &RpcTestExample) {
             suite.send_example_transaction()
         }
         "remove_transaction" => suite.send_example_transaction(),
+        "prioritise_transaction" => {
+            let mut params = example.request.params.clone();
+            params[1] = "0x0".into();
+            suite.rpc(&RpcTestRequest {
+                id: 42,
+                jsonrpc: "2.0".to_string(),
+                method: "prioritise_transaction".to_string(),
+                params,
+            });
+        }
         _ => {}
     }
 }
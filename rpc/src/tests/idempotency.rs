@@ -0,0 +1,90 @@
+use crate::error::RPCError;
+use crate::util::IdempotencyCache;
+use ckb_types::{h256, H256};
+use jsonrpc_core::ErrorCode;
+use std::cell::Cell;
+
+#[test]
+fn test_retries_get_the_first_result() {
+    let cache = IdempotencyCache::new(10);
+    let tx1 = h256!("0x1");
+    let tx2 = h256!("0x2");
+    let sent = Cell::new(0);
+    let send = |result: Result<H256, jsonrpc_core::Error>| {
+        sent.set(sent.get() + 1);
+        result
+    };
+
+    let rejected = RPCError::custom(RPCError::PoolIsFull, "full");
+    let result = cache.send("a".to_string(), &tx1, false, 1_000, || {
+        send(Err(rejected.clone()))
+    });
+    assert_eq!(result, Err(rejected.clone()));
+    // the retry is not submitted again
+    let result = cache.send("a".to_string(), &tx1, false, 2_000, || {
+        send(Ok(tx1.clone()))
+    });
+    assert_eq!(result, Err(rejected));
+    assert_eq!(sent.get(), 1);
+
+    // the key is used by another transaction
+    let result = cache.send("a".to_string(), &tx2, false, 2_000, || {
+        send(Ok(tx2.clone()))
+    });
+    assert_eq!(result.unwrap_err().code, ErrorCode::InvalidParams);
+    assert_eq!(sent.get(), 1);
+
+    // the key expires
+    let result = cache.send("a".to_string(), &tx2, false, 11_000, || {
+        send(Ok(tx2.clone()))
+    });
+    assert_eq!(result, Ok(tx2));
+    assert_eq!(sent.get(), 2);
+}
+
+#[test]
+fn test_internal_errors_are_not_cached() {
+    let cache = IdempotencyCache::new(10);
+    let tx = h256!("0x1");
+
+    let result = cache.send("a".to_string(), &tx, false, 1_000, || {
+        Err(RPCError::ckb_internal_error("channel closed"))
+    });
+    assert!(result.is_err());
+    let result = cache.send("a".to_string(), &tx, false, 1_000, || Ok(tx.clone()));
+    assert_eq!(result, Ok(tx));
+}
+
+#[test]
+fn test_invalid_keys() {
+    let cache = IdempotencyCache::new(10);
+    let tx = h256!("0x1");
+
+    let result = cache.send(String::new(), &tx, false, 1_000, || Ok(tx.clone()));
+    assert_eq!(result.unwrap_err().code, ErrorCode::InvalidParams);
+    let result = cache.send("k".repeat(129), &tx, false, 1_000, || Ok(tx.clone()));
+    assert_eq!(result.unwrap_err().code, ErrorCode::InvalidParams);
+
+    // the keys are ignored when the cache is disabled
+    let cache = IdempotencyCache::new(0);
+    let result = cache.send(String::new(), &tx, false, 1_000, || Ok(tx.clone()));
+    assert_eq!(result, Ok(tx));
+}
+
+#[test]
+fn test_retries_allowing_high_fees() {
+    let cache = IdempotencyCache::new(10);
+    let tx = h256!("0x1");
+
+    let rejected = RPCError::custom(RPCError::PoolRejectedAbsurdFee, "absurd fee");
+    let result = cache.send("a".to_string(), &tx, false, 1_000, || Err(rejected.clone()));
+    assert_eq!(result, Err(rejected.clone()));
+    // the retry allowing the high fees is submitted again
+    let result = cache.send("a".to_string(), &tx, true, 2_000, || Ok(tx.clone()));
+    assert_eq!(result, Ok(tx.clone()));
+    // both results are kept
+    let result = cache.send("a".to_string(), &tx, false, 3_000, || Ok(tx.clone()));
+    assert_eq!(result, Err(rejected));
+    let result = cache.send("a".to_string(), &tx, true, 3_000, || unreachable!());
+    assert_eq!(result, Ok(tx));
+}
@@ -0,0 +1,137 @@
+use crate::error::RPCError;
+use crate::util::indexer_wait::has_indexed;
+use crate::util::wait_for_indexer;
+use ckb_async_runtime::new_background_runtime;
+use ckb_jsonrpc_types::{IndexerTip, IndexerWaitFor};
+use ckb_types::{h256, H256};
+use jsonrpc_core::ErrorCode;
+use std::cell::Cell;
+
+fn tip(block_number: u64, block_hash: H256) -> IndexerTip {
+    IndexerTip {
+        block_hash,
+        block_number: block_number.into(),
+    }
+}
+
+fn wait_for(block_number: u64, block_hash: Option<H256>, timeout: u64) -> IndexerWaitFor {
+    IndexerWaitFor {
+        block_number: block_number.into(),
+        block_hash,
+        timeout: Some(timeout.into()),
+    }
+}
+
+async fn no_block_hash(_block_number: u64) -> Result<Option<H256>, jsonrpc_core::Error> {
+    unreachable!("the block hash is not needed")
+}
+
+#[test]
+fn test_has_indexed() {
+    let hash = h256!("0x1");
+    let other = h256!("0x2");
+
+    assert!(has_indexed(
+        &tip(10, hash.clone()),
+        &wait_for(10, None, 0),
+        None
+    ));
+    assert!(has_indexed(
+        &tip(11, other.clone()),
+        &wait_for(10, None, 0),
+        None
+    ));
+    assert!(!has_indexed(
+        &tip(9, hash.clone()),
+        &wait_for(10, None, 0),
+        None
+    ));
+
+    let target = wait_for(10, Some(hash.clone()), 0);
+    assert!(has_indexed(&tip(10, hash.clone()), &target, Some(&hash)));
+    // the indexed block is on another fork
+    assert!(!has_indexed(&tip(10, other.clone()), &target, Some(&other)));
+    assert!(!has_indexed(&tip(11, other.clone()), &target, Some(&other)));
+    assert!(has_indexed(&tip(11, other.clone()), &target, Some(&hash)));
+    // the indexed block is pruned
+    assert!(has_indexed(&tip(200, other), &target, None));
+}
+
+#[test]
+fn test_wait_for_indexer() {
+    let handle = new_background_runtime();
+    let hash = h256!("0x1");
+
+    // the indexer catches up after a few polls
+    let polls = Cell::new(0u64);
+    let result = handle.block_on(wait_for_indexer(
+        Some(wait_for(10, Some(hash.clone()), 3_000)),
+        || {
+            polls.set(polls.get() + 1);
+            let hash = hash.clone();
+            let number = 7 + polls.get();
+            async move { Ok::<_, jsonrpc_core::Error>(Some(tip(number, hash))) }
+        },
+        no_block_hash,
+    ));
+    assert!(result.is_ok());
+    assert_eq!(polls.get(), 3);
+
+    // the indexer is stuck
+    let result = handle.block_on(wait_for_indexer(
+        Some(wait_for(10, None, 100)),
+        || {
+            let hash = hash.clone();
+            async move { Ok::<_, jsonrpc_core::Error>(Some(tip(9, hash))) }
+        },
+        no_block_hash,
+    ));
+    assert_eq!(
+        result.unwrap_err().code,
+        ErrorCode::ServerError(RPCError::IndexerNotReady as i64)
+    );
+
+    // no waiting without the parameter
+    let result = handle.block_on(wait_for_indexer(
+        None,
+        || async { Ok::<Option<IndexerTip>, jsonrpc_core::Error>(None) },
+        no_block_hash,
+    ));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_wait_for_indexer_on_fork() {
+    let handle = new_background_runtime();
+    let hash = h256!("0x1");
+    let other = h256!("0x2");
+    let get_tip = || {
+        let other = other.clone();
+        async move { Ok::<_, jsonrpc_core::Error>(Some(tip(11, other))) }
+    };
+
+    // the indexed block 10 is on another fork
+    let result = handle.block_on(wait_for_indexer(
+        Some(wait_for(10, Some(hash.clone()), 100)),
+        get_tip,
+        |number| {
+            assert_eq!(number, 10);
+            let other = other.clone();
+            async move { Ok::<_, jsonrpc_core::Error>(Some(other)) }
+        },
+    ));
+    assert_eq!(
+        result.unwrap_err().code,
+        ErrorCode::ServerError(RPCError::IndexerNotReady as i64)
+    );
+
+    let result = handle.block_on(wait_for_indexer(
+        Some(wait_for(10, Some(hash.clone()), 100)),
+        get_tip,
+        |_| {
+            let hash = hash.clone();
+            async move { Ok::<_, jsonrpc_core::Error>(Some(hash)) }
+        },
+    ));
+    assert!(result.is_ok());
+}
@@ -23,11 +23,22 @@ use tokio::net::TcpStream;
 
 use self::setup::setup_rpc_test_suite;
 
+mod block_export;
+mod deprecation;
 mod error;
 mod examples;
 mod fee_rate;
+mod idempotency;
+mod indexer_wait;
 mod module;
+mod script_determinism;
 mod setup;
+mod subscription_batch;
+mod subscription_filter;
+mod subscription_proposal_window;
+mod subscription_resume;
+mod subscription_tx_status;
+mod timeout;
 
 #[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, Default)]
 struct RpcTestRequest {
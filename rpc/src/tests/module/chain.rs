@@ -0,0 +1,145 @@
+use ckb_jsonrpc_types::{BlockEconomicState, EconomicStateSummary};
+use ckb_store::ChainStore;
+use ckb_test_chain_utils::always_success_consensus;
+use serde_json::json;
+
+use crate::tests::{setup, RpcTestRequest, RpcTestSuite};
+
+fn economic_state_range(
+    suite: &RpcTestSuite,
+    start_number: u64,
+    end_number: u64,
+    aggregate_by_epoch: bool,
+) -> Vec<EconomicStateSummary> {
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_block_economic_state_range".to_string(),
+        params: vec![
+            json!(format!("{start_number:#x}")),
+            json!(format!("{end_number:#x}")),
+            json!(aggregate_by_epoch),
+        ],
+    });
+    assert!(response.error.is_null(), "{}", response.error);
+    serde_json::from_value(response.result).expect("deserialize summaries")
+}
+
+fn economic_state(suite: &RpcTestSuite, number: u64) -> BlockEconomicState {
+    let hash = suite
+        .shared
+        .store()
+        .get_block_hash(number)
+        .expect("canonical block");
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_block_economic_state".to_string(),
+        params: vec![json!(format!("{hash:#x}"))],
+    });
+    serde_json::from_value(response.result).expect("finalized block")
+}
+
+#[test]
+fn test_get_block_economic_state_range() {
+    let suite = setup(always_success_consensus());
+    let tip_number = suite.shared.snapshot().tip_number();
+    let finalized_number = tip_number - suite.shared.consensus().finalization_delay_length();
+
+    // the genesis block and the blocks not finalized are skipped
+    let summaries = economic_state_range(&suite, 0, tip_number, false);
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    assert_eq!(summary.epoch, None);
+    assert_eq!(summary.start_number.value(), 1);
+    assert_eq!(summary.end_number.value(), finalized_number);
+    assert_eq!(summary.block_count.value(), finalized_number);
+
+    let (mut primary_issuance, mut miner_secondary, mut txs_fee) = (0, 0, 0);
+    for number in 1..=finalized_number {
+        let state = economic_state(&suite, number);
+        primary_issuance += state.issuance.primary.value();
+        miner_secondary += state.miner_reward.secondary.value();
+        txs_fee += state.txs_fee.value();
+    }
+    assert_eq!(summary.issuance.primary.value(), primary_issuance);
+    assert_eq!(summary.miner_reward.secondary.value(), miner_secondary);
+    assert_eq!(summary.txs_fee.value(), txs_fee);
+
+    // the epochs cover the same blocks
+    let summaries = economic_state_range(&suite, 0, tip_number, true);
+    assert!(summaries.iter().all(|summary| summary.epoch.is_some()));
+    assert_eq!(
+        summaries
+            .iter()
+            .map(|summary| summary.block_count.value())
+            .sum::<u64>(),
+        finalized_number
+    );
+
+    // no block is finalized in the range
+    assert!(economic_state_range(&suite, finalized_number + 1, tip_number, false).is_empty());
+
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_block_economic_state_range".to_string(),
+        params: vec![json!("0x2"), json!("0x1")],
+    });
+    assert_eq!(response.error["code"], json!(-32602));
+}
+
+#[test]
+fn test_get_block_with_fields() {
+    let suite = setup(always_success_consensus());
+    let store = suite.shared.store();
+    let block_hash = store.get_block_hash(1).expect("canonical block");
+    let block = store.get_block(&block_hash).expect("block");
+    let tx_hash = block.transactions()[0].hash();
+
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_block_by_number".to_string(),
+        params: vec![
+            json!("0x1"),
+            json!(null),
+            json!(null),
+            json!(["header.number", "transactions.hash"]),
+        ],
+    });
+    assert!(response.error.is_null(), "{}", response.error);
+    assert_eq!(
+        response.result,
+        json!({
+            "header": { "number": "0x1" },
+            "transactions": [{ "hash": format!("{tx_hash:#x}") }],
+        })
+    );
+
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_transaction".to_string(),
+        params: vec![
+            json!(format!("{tx_hash:#x}")),
+            json!(null),
+            json!(null),
+            json!(["tx_status.status"]),
+        ],
+    });
+    assert!(response.error.is_null(), "{}", response.error);
+    assert_eq!(
+        response.result,
+        json!({ "tx_status": { "status": "committed" } })
+    );
+
+    // the hex format can't be projected
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_block_by_number".to_string(),
+        params: vec![json!("0x1"), json!("0x0"), json!(null), json!(["header"])],
+    });
+    assert_eq!(response.error["code"], json!(-32602));
+}
@@ -1,3 +1,5 @@
+mod chain;
 mod miner;
+mod net;
 mod pool;
 mod test;
@@ -0,0 +1,76 @@
+use ckb_jsonrpc_types::SignedHeaderCheckpoints;
+use ckb_shared::header_checkpoints::verify_header_checkpoints;
+use ckb_store::ChainStore;
+use ckb_test_chain_utils::always_success_consensus;
+use ckb_types::{core, h256, prelude::*, H256};
+use serde_json::json;
+
+use crate::tests::{setup, RpcTestRequest, RpcTestSuite};
+
+fn header_checkpoints(suite: &RpcTestSuite, interval: u64, count: u32) -> SignedHeaderCheckpoints {
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_header_checkpoints".to_string(),
+        params: vec![
+            json!(format!("{interval:#x}")),
+            json!(format!("{count:#x}")),
+        ],
+    });
+    assert!(response.error.is_null(), "{}", response.error);
+    serde_json::from_value(response.result).expect("deserialize checkpoints")
+}
+
+#[test]
+fn test_get_header_checkpoints() {
+    let suite = setup(always_success_consensus());
+    let tip_number = suite.shared.snapshot().tip_number();
+    assert_eq!(tip_number, 20);
+
+    let signed = header_checkpoints(&suite, 6, 3);
+    let numbers: Vec<u64> = signed
+        .checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.number.value())
+        .collect();
+    assert_eq!(numbers, vec![6, 12, 18]);
+    for checkpoint in &signed.checkpoints {
+        let number = checkpoint.number.value();
+        let hash = suite.shared.store().get_block_hash(number).unwrap();
+        let ext = suite.shared.store().get_block_ext(&hash).unwrap();
+        let hash: H256 = hash.unpack();
+        assert_eq!(checkpoint.hash, hash);
+        assert_eq!(checkpoint.total_difficulty, ext.total_difficulty);
+    }
+
+    // the signature is verified against the public key of the node, and binds the chain
+    let signed: core::SignedHeaderCheckpoints = signed.into();
+    assert_eq!(signed.genesis_hash, suite.shared.genesis_hash());
+    verify_header_checkpoints(&signed).expect("valid signature");
+    let mut truncated = signed.clone();
+    truncated.checkpoints.pop();
+    assert!(verify_header_checkpoints(&truncated).is_err());
+    let mut other_chain = signed.clone();
+    other_chain.genesis_hash = h256!("0x1").pack();
+    assert!(verify_header_checkpoints(&other_chain).is_err());
+    let mut other_node = signed;
+    other_node.node_id = "QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS".to_string();
+    assert!(verify_header_checkpoints(&other_node).is_err());
+
+    // the genesis block is included when the chain is not long enough
+    let signed = header_checkpoints(&suite, 10, 5);
+    let numbers: Vec<u64> = signed
+        .checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.number.value())
+        .collect();
+    assert_eq!(numbers, vec![0, 10, 20]);
+
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "get_header_checkpoints".to_string(),
+        params: vec![json!("0x0")],
+    });
+    assert!(!response.error.is_null());
+}
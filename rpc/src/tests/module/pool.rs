@@ -4,6 +4,7 @@ use ckb_types::{
     core::{self, Capacity, TransactionBuilder},
     packed::{self, CellDep, CellInput, CellOutputBuilder, OutPoint},
     prelude::*,
+    H256,
 };
 use serde_json::json;
 
@@ -184,6 +185,64 @@ fn test_send_transaction_exceeded_maximum_ancestors_count() {
     }
 }
 
+#[test]
+fn test_diagnose_transaction() {
+    let suite = setup(always_success_consensus());
+
+    let store = suite.shared.store();
+    let tip = store.get_tip_header().unwrap();
+    let tip_block = store.get_block(&tip.hash()).unwrap();
+    let cellbase_hash = tip_block.transactions().first().unwrap().hash();
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new(OutPoint::new(cellbase_hash, 0), 0))
+        .output(
+            CellOutputBuilder::default()
+                .capacity(Capacity::bytes(500).unwrap().pack())
+                .lock(always_success_cell().2.clone())
+                .build(),
+        )
+        .output_data(Default::default())
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(OutPoint::new(always_success_transaction().hash(), 0))
+                .build(),
+        )
+        .build();
+    let new_tx: ckb_jsonrpc_types::Transaction = tx.data().into();
+    let response = suite.rpc(&RpcTestRequest {
+        id: 42,
+        jsonrpc: "2.0".to_string(),
+        method: "send_transaction".to_string(),
+        params: vec![json!(new_tx), json!("passthrough")],
+    });
+    assert_eq!(response.error.to_string(), "null".to_string());
+
+    let diagnose = |tx_hash: H256| {
+        suite
+            .rpc(&RpcTestRequest {
+                id: 42,
+                jsonrpc: "2.0".to_string(),
+                method: "diagnose_transaction".to_string(),
+                params: vec![json!(tx_hash)],
+            })
+            .result
+    };
+
+    let diagnosis = diagnose(tx.hash().unpack());
+    assert_eq!(diagnosis["status"], json!("pending"));
+    assert_eq!(diagnosis["rank_in_pending"], json!("0x0"));
+    assert_eq!(diagnosis["ancestors_count"], json!("0x0"));
+    assert!(!diagnosis["fee_rate"].is_null());
+    assert!(!diagnosis["expires_at"].is_null());
+    assert_eq!(diagnosis["issues"], json!([]));
+
+    let diagnosis = diagnose(H256::default());
+    assert_eq!(diagnosis["status"], json!("unknown"));
+    assert!(diagnosis["fee_rate"].is_null());
+    assert_eq!(diagnosis["issues"], json!([]));
+}
+
 fn build_tx(
     code_hash: &packed::Byte32,
     hash_type: core::ScriptHashType,
@@ -0,0 +1,39 @@
+use crate::module::diverged_scripts;
+use ckb_jsonrpc_types::{ScriptGroupDryRun, ScriptGroupType};
+use ckb_types::{h256, H256};
+use std::collections::BTreeSet;
+
+fn group(script_hash: H256, cycles: Option<u64>, error: Option<&str>) -> ScriptGroupDryRun {
+    ScriptGroupDryRun {
+        group_type: ScriptGroupType::Lock,
+        script_hash,
+        cycles: cycles.map(Into::into),
+        error: error.map(ToString::to_string),
+    }
+}
+
+#[test]
+fn test_diverged_scripts() {
+    let groups = vec![
+        group(h256!("0x1"), Some(100), None),
+        group(h256!("0x2"), None, Some("error")),
+    ];
+    assert!(diverged_scripts(&groups, &groups.clone()).is_empty());
+
+    // the cycles differ
+    let others = vec![
+        group(h256!("0x1"), Some(101), None),
+        group(h256!("0x2"), None, Some("error")),
+    ];
+    assert_eq!(
+        diverged_scripts(&groups, &others),
+        BTreeSet::from([h256!("0x1")])
+    );
+
+    // the results differ, and a group is missing
+    let others = vec![group(h256!("0x2"), Some(100), None)];
+    assert_eq!(
+        diverged_scripts(&groups, &others),
+        BTreeSet::from([h256!("0x1"), h256!("0x2")])
+    );
+}
@@ -12,7 +12,7 @@ use ckb_dao_utils::genesis_dao_data;
 use ckb_network::{Flags, NetworkService, NetworkState};
 use ckb_network_alert::alert_relayer::AlertRelayer;
 use ckb_notify::NotifyService;
-use ckb_shared::SharedBuilder;
+use ckb_shared::{header_checkpoints::HeaderCheckpointSigner, SharedBuilder};
 use ckb_sync::SyncShared;
 use ckb_verification_traits::Switch;
 use serde_json::json;
@@ -93,14 +93,18 @@ pub(crate) fn setup_rpc_test_suite(height: u64, consensus: Option<Consensus>) ->
     // Start network services
     let temp_dir = tempfile::tempdir().expect("create tmp_dir failed");
 
+    let network_config = NetworkConfig {
+        path: temp_dir.path().join("network"),
+        ping_interval_secs: 1,
+        ping_timeout_secs: 1,
+        connect_outbound_interval_secs: 1,
+        ..Default::default()
+    };
+    let checkpoint_signer = Arc::new(
+        HeaderCheckpointSigner::from_network_config(&network_config)
+            .expect("Load network secret key failed"),
+    );
     let network_controller = {
-        let network_config = NetworkConfig {
-            path: temp_dir.path().join("network"),
-            ping_interval_secs: 1,
-            ping_timeout_secs: 1,
-            connect_outbound_interval_secs: 1,
-            ..Default::default()
-        };
         let network_state =
             Arc::new(NetworkState::from_config(network_config).expect("Init network state failed"));
         NetworkService::new(
@@ -175,6 +179,7 @@ pub(crate) fn setup_rpc_test_suite(height: u64, consensus: Option<Consensus>) ->
         listen_address: "127.0.0.1:0".to_owned(),
         tcp_listen_address: Some("127.0.0.1:0".to_owned()),
         ws_listen_address: None,
+        tls: None,
         max_request_body_size: 20_000_000,
         threads: None,
         rpc_batch_limit: Some(1000),
@@ -196,6 +201,9 @@ pub(crate) fn setup_rpc_test_suite(height: u64, consensus: Option<Consensus>) ->
         enable_deprecated_rpc: true,
         extra_well_known_lock_scripts: vec![],
         extra_well_known_type_scripts: vec![],
+        subscription_resume: None,
+        send_transaction_idempotency_window_secs: 600,
+        timeouts: Default::default(),
     };
 
     let builder = ServiceBuilder::new(&rpc_config)
@@ -211,6 +219,7 @@ pub(crate) fn setup_rpc_test_suite(height: u64, consensus: Option<Consensus>) ->
             network_controller.clone(),
             sync_shared,
             Arc::new(chain_controller.clone()),
+            checkpoint_signer,
         )
         .enable_stats(shared.clone(), Arc::clone(&alert_notifier))
         .enable_experiment(shared.clone())
@@ -221,13 +230,13 @@ pub(crate) fn setup_rpc_test_suite(height: u64, consensus: Option<Consensus>) ->
             vec![],
             vec![],
         )
-        .enable_debug()
+        .enable_debug(shared.clone())
         .enable_alert(alert_verifier, alert_notifier, network_controller);
 
     let io_handler = builder.build();
     let shared_clone = shared.clone();
     let handler = shared_clone.async_handle().clone();
-    let rpc_server = RpcServer::new(rpc_config, io_handler, handler);
+    let rpc_server = RpcServer::new(rpc_config, io_handler, &shared, handler);
 
     let rpc_client = reqwest::blocking::Client::new();
     let rpc_uri = format!(
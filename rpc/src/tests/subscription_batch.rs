@@ -0,0 +1,71 @@
+use crate::module::{EventBatch, SubscriptionRpc, SubscriptionRpcImpl};
+use ckb_async_runtime::new_background_runtime;
+use ckb_jsonrpc_types::{SubscribeOptions, SubscriptionBatch, Topic};
+use ckb_notify::NotifyService;
+use std::sync::Arc;
+
+#[test]
+fn test_event_batch() {
+    let mut batch = EventBatch::new(2);
+    assert!(batch.take().is_none());
+
+    assert!(batch.push(0, Arc::new("0".to_string())).is_none());
+    assert_eq!(
+        batch.push(1, Arc::new("1".to_string())),
+        Some((1, "[0,1]".to_string()))
+    );
+    assert!(batch.is_empty());
+
+    // the pending events are taken when the batch latency is reached
+    assert!(batch.push(2, Arc::new("2".to_string())).is_none());
+    assert_eq!(batch.take(), Some((2, "[2]".to_string())));
+    assert!(batch.take().is_none());
+}
+
+#[test]
+fn test_batch_option() {
+    let handle = new_background_runtime();
+    let notify_controller = NotifyService::new(Default::default(), handle.clone()).start();
+    let rpc = SubscriptionRpcImpl::new(notify_controller, None, handle);
+    let subscribe = |topic, batch| {
+        rpc.subscribe(
+            topic,
+            Some(SubscribeOptions {
+                batch: Some(batch),
+                ..Default::default()
+            }),
+        )
+        .is_ok()
+    };
+
+    assert!(subscribe(
+        Topic::NewTransaction,
+        SubscriptionBatch::default()
+    ));
+    assert!(subscribe(
+        Topic::RejectedTransaction,
+        SubscriptionBatch {
+            max_batch_size: Some(1000.into()),
+            max_batch_latency_ms: Some(1.into()),
+        }
+    ));
+    // the batches only apply to the bursty topics
+    assert!(!subscribe(
+        Topic::NewTipHeader,
+        SubscriptionBatch::default()
+    ));
+    assert!(!subscribe(
+        Topic::NewTransaction,
+        SubscriptionBatch {
+            max_batch_size: Some(0.into()),
+            ..Default::default()
+        }
+    ));
+    assert!(!subscribe(
+        Topic::NewTransaction,
+        SubscriptionBatch {
+            max_batch_latency_ms: Some(10_001.into()),
+            ..Default::default()
+        }
+    ));
+}
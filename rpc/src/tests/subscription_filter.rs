@@ -0,0 +1,87 @@
+use crate::module::{EventFilter, EventTx, TopicHistory};
+use ckb_jsonrpc_types::{SubscribeOptions, SubscriptionFilter};
+use ckb_notify::PoolTransactionEntry;
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, TransactionBuilder},
+    packed::{CellOutput, Script},
+    prelude::*,
+    H256,
+};
+
+fn script(args: u8) -> Script {
+    Script::new_builder()
+        .args(Bytes::from(vec![args]).pack())
+        .build()
+}
+
+fn cell(lock: u8, type_: Option<u8>) -> CellOutput {
+    CellOutput::new_builder()
+        .lock(script(lock))
+        .type_(type_.map(script).pack())
+        .build()
+}
+
+fn entry(outputs: Vec<CellOutput>, input_cells: Vec<CellOutput>) -> PoolTransactionEntry {
+    let outputs_data = vec![Bytes::new().pack(); outputs.len()];
+    PoolTransactionEntry {
+        transaction: TransactionBuilder::default()
+            .outputs(outputs)
+            .outputs_data(outputs_data)
+            .build(),
+        input_cells,
+        cycles: 0,
+        size: 0,
+        fee: Capacity::zero(),
+        timestamp: 0,
+    }
+}
+
+fn hash(script: Script) -> H256 {
+    script.calc_script_hash().unpack()
+}
+
+fn filter(lock_hashes: Vec<H256>, type_hashes: Vec<H256>) -> EventFilter {
+    EventFilter::new(&SubscribeOptions {
+        filter: Some(SubscriptionFilter {
+            lock_hashes,
+            type_hashes,
+        }),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_filter_by_scripts() {
+    let mut history = TopicHistory::new(None);
+    let by_output = history.push_event(
+        "0".to_string(),
+        vec![EventTx::new(&entry(vec![cell(1, Some(2))], vec![]))],
+        0,
+    );
+    let by_input = history.push_event(
+        "1".to_string(),
+        vec![EventTx::new(&entry(
+            vec![cell(3, None)],
+            vec![cell(1, None)],
+        ))],
+        0,
+    );
+    let no_txs = history.push("2".to_string(), 0);
+
+    let by_lock = filter(vec![hash(script(1))], vec![]);
+    assert!(by_lock.apply(&by_output).is_some());
+    assert!(by_lock.apply(&by_input).is_some());
+    assert!(by_lock.apply(&no_txs).is_some());
+
+    let by_type = filter(vec![], vec![hash(script(2))]);
+    assert!(by_type.apply(&by_output).is_some());
+    assert!(by_type.apply(&by_input).is_none());
+
+    // the lock script 2 is only used as a type script
+    let unmatched = filter(vec![hash(script(2))], vec![]);
+    assert!(unmatched.apply(&by_output).is_none());
+
+    let unfiltered = EventFilter::new(&SubscribeOptions::default());
+    assert!(unfiltered.apply(&by_input).is_some());
+}
@@ -0,0 +1,79 @@
+use crate::module::{SubscriptionRpc, SubscriptionRpcImpl};
+use ckb_async_runtime::new_background_runtime;
+use ckb_jsonrpc_types::{ProposalWindowEvent, ProposalWindowStatus, SubscribeOptions, Topic};
+use ckb_notify::{NotifyService, PoolTransactionEntry};
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, TransactionBuilder},
+    packed::CellOutput,
+    prelude::*,
+    H256,
+};
+use std::time::Duration;
+
+fn entry() -> PoolTransactionEntry {
+    PoolTransactionEntry {
+        transaction: TransactionBuilder::default()
+            .output(CellOutput::new_builder().build())
+            .output_data(Bytes::new().pack())
+            .build(),
+        input_cells: Vec::new(),
+        cycles: 0,
+        size: 0,
+        fee: Capacity::zero(),
+        timestamp: 0,
+    }
+}
+
+#[test]
+fn test_proposal_window_through_notify() {
+    let handle = new_background_runtime();
+    let notify_controller = NotifyService::new(Default::default(), handle.clone()).start();
+    let rpc = SubscriptionRpcImpl::new(notify_controller.clone(), None, handle.clone());
+    let mut rx = rpc.proposal_window_sender.sender.subscribe();
+    let mut next_event = || -> Option<(ProposalWindowStatus, H256)> {
+        let event = handle
+            .block_on(tokio::time::timeout(Duration::from_secs(5), rx.recv()))
+            .ok()?
+            .expect("the topic is open");
+        let event: ProposalWindowEvent =
+            serde_json::from_str(&event.json).expect("deserialization should be ok");
+        Some((event.status, event.entry.transaction.hash))
+    };
+    let tx_hash: H256 = entry().transaction.hash().unpack();
+
+    // entering pending is not an event of the topic
+    notify_controller.notify_new_transaction(entry());
+    notify_controller.notify_gap_transaction(entry());
+    assert_eq!(
+        next_event(),
+        Some((ProposalWindowStatus::Gap, tx_hash.clone()))
+    );
+
+    notify_controller.notify_proposed_transaction(entry());
+    assert_eq!(
+        next_event(),
+        Some((ProposalWindowStatus::Proposed, tx_hash))
+    );
+}
+
+#[test]
+fn test_tx_hashes_option() {
+    let handle = new_background_runtime();
+    let notify_controller = NotifyService::new(Default::default(), handle.clone()).start();
+    let rpc = SubscriptionRpcImpl::new(notify_controller, None, handle);
+    let subscribe = |topic| {
+        rpc.subscribe(
+            topic,
+            Some(SubscribeOptions {
+                tx_hashes: Some(vec![H256::default()]),
+                ..Default::default()
+            }),
+        )
+        .is_ok()
+    };
+
+    assert!(subscribe(Topic::ProposalWindow));
+    // the other txs topics push the events of all the txs
+    assert!(!subscribe(Topic::ProposedTransaction));
+}
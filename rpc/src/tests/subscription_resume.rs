@@ -0,0 +1,69 @@
+use crate::module::{ResumeToken, TopicHistory};
+use ckb_app_config::SubscriptionResumeConfig;
+
+fn seqs(history: &mut TopicHistory, seq: u64, now: u64) -> Option<Vec<u64>> {
+    history
+        .events_after(seq, now)
+        .map(|events| events.iter().map(|event| event.seq).collect())
+}
+
+#[test]
+fn test_resume_after_kept_events() {
+    let mut history = TopicHistory::new(Some(SubscriptionResumeConfig {
+        retention_secs: 10,
+        max_events: 3,
+    }));
+    for i in 0..5 {
+        history.push(format!("{i}"), 1_000);
+    }
+
+    // only the last 3 events are kept
+    assert_eq!(seqs(&mut history, 1, 1_000), Some(vec![2, 3, 4]));
+    assert_eq!(seqs(&mut history, 3, 1_000), Some(vec![4]));
+    assert_eq!(seqs(&mut history, 4, 1_000), Some(vec![]));
+    assert_eq!(seqs(&mut history, 0, 1_000), None);
+    // the token is from the future
+    assert_eq!(seqs(&mut history, 5, 1_000), None);
+
+    // the events expire after the retention window
+    assert_eq!(seqs(&mut history, 3, 12_000), None);
+    // nothing is missed after the last event
+    assert_eq!(seqs(&mut history, 4, 12_000), Some(vec![]));
+}
+
+#[test]
+fn test_resume_disabled() {
+    let mut history = TopicHistory::new(None);
+    history.push("0".to_string(), 1_000);
+    history.push("1".to_string(), 1_000);
+    assert_eq!(seqs(&mut history, 0, 1_000), None);
+    assert_eq!(seqs(&mut history, 1, 1_000), Some(vec![]));
+}
+
+#[test]
+fn test_resume_token_encoding() {
+    let token = ResumeToken {
+        instance_id: 1_700_000_000_000,
+        topic: 2,
+        seq: 42,
+    };
+    let bytes = token.encode();
+    assert_eq!(ResumeToken::decode(bytes.as_bytes()), Some(token));
+    assert_eq!(ResumeToken::decode(&bytes.as_bytes()[1..]), None);
+}
+
+#[test]
+fn test_keep_last_event() {
+    let mut history = TopicHistory::new(None);
+    history.push("0".to_string(), 1_000);
+    assert!(history.last_event().is_none());
+
+    // the subscriptions made after the event still get it
+    let mut history = TopicHistory::keeping_last_event(None);
+    assert!(history.last_event().is_none());
+    history.push("0".to_string(), 1_000);
+    history.push("1".to_string(), 1_000);
+    let event = history.last_event().unwrap();
+    assert_eq!(event.seq, 1);
+    assert_eq!(event.json.as_str(), "1");
+}
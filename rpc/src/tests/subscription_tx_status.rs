@@ -0,0 +1,148 @@
+use crate::module::{SubscriptionRpcImpl, TxStatusTracker};
+use ckb_async_runtime::new_background_runtime;
+use ckb_jsonrpc_types::{Status, TxStatusChangedEvent};
+use ckb_notify::{NotifyService, PoolTransactionEntry};
+use ckb_types::{
+    bytes::Bytes,
+    core::{Capacity, TransactionBuilder},
+    h256,
+    packed::CellOutput,
+    prelude::*,
+    H256,
+};
+use std::time::Duration;
+
+fn transit(
+    tracker: &mut TxStatusTracker,
+    tx_hash: H256,
+    timestamp: u64,
+    status: Status,
+) -> Option<(Status, Status)> {
+    tracker
+        .transit(tx_hash, timestamp, status)
+        .map(|event| (event.old_status, event.new_status))
+}
+
+#[test]
+fn test_tx_status_transitions() {
+    let tx_hash = h256!("0x1");
+    let mut tracker = TxStatusTracker::new(10);
+
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 1, Status::Pending),
+        Some((Status::Unknown, Status::Pending))
+    );
+    // entering the gap set keeps the tx pending
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 1, Status::Pending),
+        None
+    );
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 1, Status::Proposed),
+        Some((Status::Pending, Status::Proposed))
+    );
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 1, Status::Committed),
+        Some((Status::Proposed, Status::Committed))
+    );
+    // the tx re-enters the pool after a reorg
+    assert_eq!(
+        transit(&mut tracker, tx_hash, 2, Status::Pending),
+        Some((Status::Committed, Status::Pending))
+    );
+}
+
+#[test]
+fn test_tx_status_ignore_regressions() {
+    let tx_hash = h256!("0x1");
+    let mut tracker = TxStatusTracker::new(10);
+
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 2, Status::Proposed),
+        Some((Status::Unknown, Status::Proposed))
+    );
+    // the statuses of the same entry arrive late
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 2, Status::Pending),
+        None
+    );
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 2, Status::Committed),
+        Some((Status::Proposed, Status::Committed))
+    );
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 2, Status::Proposed),
+        None
+    );
+    // the status of an older entry arrives late
+    assert_eq!(
+        transit(&mut tracker, tx_hash.clone(), 1, Status::Rejected),
+        None
+    );
+    assert_eq!(
+        transit(&mut tracker, tx_hash, 3, Status::Pending),
+        Some((Status::Committed, Status::Pending))
+    );
+}
+
+#[test]
+fn test_tx_status_tracker_capacity() {
+    let mut tracker = TxStatusTracker::new(2);
+    transit(&mut tracker, h256!("0x1"), 0, Status::Pending);
+    transit(&mut tracker, h256!("0x2"), 0, Status::Pending);
+    // the tx 0x1 is refreshed, and the tx 0x2 is evicted
+    transit(&mut tracker, h256!("0x1"), 0, Status::Proposed);
+    transit(&mut tracker, h256!("0x3"), 0, Status::Pending);
+
+    assert_eq!(
+        transit(&mut tracker, h256!("0x1"), 0, Status::Committed),
+        Some((Status::Proposed, Status::Committed))
+    );
+    assert_eq!(
+        transit(&mut tracker, h256!("0x2"), 0, Status::Rejected),
+        Some((Status::Unknown, Status::Rejected))
+    );
+}
+
+fn entry(timestamp: u64) -> PoolTransactionEntry {
+    PoolTransactionEntry {
+        transaction: TransactionBuilder::default()
+            .output(CellOutput::new_builder().build())
+            .output_data(Bytes::new().pack())
+            .build(),
+        input_cells: Vec::new(),
+        cycles: 0,
+        size: 0,
+        fee: Capacity::zero(),
+        timestamp,
+    }
+}
+
+#[test]
+fn test_tx_status_changed_through_notify() {
+    let handle = new_background_runtime();
+    let notify_controller = NotifyService::new(Default::default(), handle.clone()).start();
+    let rpc = SubscriptionRpcImpl::new(notify_controller.clone(), None, handle.clone());
+    let mut rx = rpc.tx_status_changed_sender.sender.subscribe();
+    let mut next_event = || -> Option<(Status, Status)> {
+        let event = handle
+            .block_on(tokio::time::timeout(Duration::from_secs(5), rx.recv()))
+            .ok()?
+            .expect("the topic is open");
+        let event: TxStatusChangedEvent =
+            serde_json::from_str(&event.json).expect("deserialization should be ok");
+        Some((event.old_status, event.new_status))
+    };
+
+    notify_controller.notify_proposed_transaction(entry(1));
+    assert_eq!(next_event(), Some((Status::Unknown, Status::Proposed)));
+
+    // the pending status arrives after the proposed one, then the tx is committed
+    notify_controller.notify_new_transaction(entry(1));
+    notify_controller.notify_committed_transaction(entry(1));
+    assert_eq!(next_event(), Some((Status::Proposed, Status::Committed)));
+
+    // the tx re-enters the pool after a reorg
+    notify_controller.notify_new_transaction(entry(2));
+    assert_eq!(next_event(), Some((Status::Committed, Status::Pending)));
+}
@@ -0,0 +1,129 @@
+use crate::error::RPCError;
+use crate::util::timeout::with_timeout;
+use crate::util::MethodTimeouts;
+use crate::IoHandler;
+use ckb_app_config::{RpcMethodClass, RpcTimeoutsConfig};
+use ckb_async_runtime::new_background_runtime;
+use jsonrpc_core::{ErrorCode, Output, Response, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn timeouts() -> MethodTimeouts {
+    let mut config = RpcTimeoutsConfig {
+        fast_ms: 100,
+        ..Default::default()
+    };
+    config
+        .methods
+        .insert("get_block".to_owned(), RpcMethodClass::Heavy);
+    config
+        .methods
+        .insert("sleep".to_owned(), RpcMethodClass::Fast);
+    config
+        .methods
+        .insert("sleep_async".to_owned(), RpcMethodClass::Fast);
+    MethodTimeouts::new(&config)
+}
+
+#[test]
+fn test_method_classes() {
+    let timeouts = timeouts();
+    assert_eq!(
+        timeouts.class_of("get_tip_block_number"),
+        RpcMethodClass::Fast
+    );
+    assert_eq!(timeouts.class_of("get_cells"), RpcMethodClass::Heavy);
+    assert_eq!(timeouts.class_of("get_header"), RpcMethodClass::Standard);
+    // reclassified by the config
+    assert_eq!(timeouts.class_of("get_block"), RpcMethodClass::Heavy);
+
+    assert_eq!(
+        timeouts.timeout_of(RpcMethodClass::Fast),
+        Duration::from_millis(100)
+    );
+    assert_eq!(
+        timeouts.timeout_of(RpcMethodClass::Standard),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        timeouts.timeout_of(RpcMethodClass::Heavy),
+        Duration::from_secs(120)
+    );
+}
+
+#[test]
+fn test_call_timeout() {
+    let completed = Arc::new(AtomicBool::new(false));
+    let mut methods = IoHandler::default();
+    methods.add_sync_method("sleep", |_| {
+        std::thread::sleep(Duration::from_millis(500));
+        Ok(Value::Null)
+    });
+    methods.add_method("sleep_async", {
+        let completed = Arc::clone(&completed);
+        move |_| {
+            let completed = Arc::clone(&completed);
+            async move {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                completed.store(true, Ordering::SeqCst);
+                Ok(Value::Null)
+            }
+        }
+    });
+    methods.add_sync_method("get_header", |_| Ok(Value::Null));
+    let timeouts = timeouts();
+    let mut io = IoHandler::default();
+    io.extend_with(
+        methods
+            .into_iter()
+            .map(|(name, method)| (name.clone(), with_timeout(&timeouts, &name, method))),
+    );
+    let handle = new_background_runtime();
+    let call = |request: &str| {
+        handle
+            .block_on(io.handle_request(request, None))
+            .map(|response| serde_json::from_str::<Response>(&response).unwrap())
+    };
+
+    let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"sleep","params":[]}"#);
+    match response {
+        Some(Response::Single(Output::Failure(failure))) => {
+            assert_eq!(
+                failure.error.code,
+                ErrorCode::ServerError(RPCError::Timeout as i64)
+            );
+            let data = failure.error.data.unwrap();
+            assert_eq!(data["method"], "sleep");
+            assert_eq!(data["class"], "fast");
+            assert_eq!(data["timeout_ms"], 100);
+        }
+        response => panic!("unexpected response {response:?}"),
+    }
+
+    // only the slow call in the batch times out
+    let response = call(
+        r#"[{"jsonrpc":"2.0","id":1,"method":"sleep","params":[]},
+            {"jsonrpc":"2.0","id":2,"method":"get_header","params":[]}]"#,
+    );
+    match response {
+        Some(Response::Batch(outputs)) => {
+            assert_eq!(outputs.len(), 2);
+            assert!(matches!(outputs[0], Output::Failure(_)));
+            assert!(matches!(outputs[1], Output::Success(_)));
+        }
+        response => panic!("unexpected response {response:?}"),
+    }
+
+    // no response to a notification
+    assert!(call(r#"{"jsonrpc":"2.0","method":"get_header","params":[]}"#).is_none());
+
+    // the asynchronous part of a call is dropped after the timeout
+    let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"sleep_async","params":[]}"#);
+    assert!(matches!(
+        response,
+        Some(Response::Single(Output::Failure(_)))
+    ));
+    std::thread::sleep(Duration::from_millis(800));
+    assert!(!completed.load(Ordering::SeqCst));
+}
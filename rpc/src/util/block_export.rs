@@ -0,0 +1,155 @@
+//! The HTTP endpoint streaming the blocks in a range, so the ETL jobs can bulk-load the chain
+//! without issuing a `get_block` call per block.
+//!
+//! `GET /export/blocks?from=<number>&to=<number>[&format=json|hex]` responds with one block per
+//! line in the newline-delimited JSON format, the same as the result of `get_block` with the
+//! verbosity 2 (`json`) or 0 (`hex`). Both bounds are inclusive, in decimal or in `0x` prefixed
+//! hex, and the range is capped by the tip when the request arrives.
+//!
+//! The blocks are read ahead by at most `EXPORT_BUFFER_BLOCKS`, the reading pauses while the
+//! client is slower, and stops when the client disconnects. At most `MAX_CONCURRENT_EXPORTS`
+//! exports run at the same time, the others are refused with `429 Too Many Requests`.
+//!
+//! If the exported blocks are replaced by a reorg during the export, the response is aborted
+//! with an error instead of ending normally, so the client can tell it is incomplete.
+use axum::body::{Bytes, StreamBody};
+use axum::extract::Query;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use ckb_jsonrpc_types::{BlockView, JsonBytes};
+use ckb_logger::debug;
+use ckb_shared::Shared;
+use ckb_store::ChainStore;
+use ckb_types::{core::BlockNumber, packed::Byte32, prelude::*};
+use serde::Deserialize;
+use std::io::{Error as IoError, ErrorKind};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+
+const EXPORT_BUFFER_BLOCKS: usize = 16;
+const MAX_CONCURRENT_EXPORTS: usize = 4;
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// The state shared by the exports of the HTTP and WebSocket listeners.
+#[derive(Clone)]
+pub(crate) struct BlockExport {
+    shared: Shared,
+    permits: Arc<Semaphore>,
+}
+
+impl BlockExport {
+    pub(crate) fn new(shared: Shared) -> Self {
+        BlockExport {
+            shared,
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_EXPORTS)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ExportBlocksParams {
+    from: String,
+    to: String,
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExportFormat {
+    #[default]
+    Json,
+    Hex,
+}
+
+pub(crate) async fn export_blocks_handler(
+    Extension(export): Extension<BlockExport>,
+    Query(params): Query<ExportBlocksParams>,
+) -> Response {
+    let BlockExport { shared, permits } = export;
+    let (from, to) = match (parse_number(&params.from), parse_number(&params.to)) {
+        (Some(from), Some(to)) if from <= to => (from, to),
+        (Some(_), Some(_)) => {
+            return (StatusCode::BAD_REQUEST, "from must not be greater than to").into_response()
+        }
+        _ => return (StatusCode::BAD_REQUEST, "invalid block number").into_response(),
+    };
+    let to = to.min(shared.snapshot().tip_number());
+    let format = params.format;
+    let permit = match permits.try_acquire_owned() {
+        Ok(permit) => permit,
+        Err(_) => {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                "too many block exports in progress",
+            )
+                .into_response()
+        }
+    };
+
+    let (sender, mut receiver) = mpsc::channel::<Result<Bytes, IoError>>(EXPORT_BUFFER_BLOCKS);
+    let handle = shared.async_handle().clone();
+    handle.spawn_blocking(move || {
+        // released when the export completes or the client disconnects
+        let _permit = permit;
+        let store = shared.store();
+        let mut parent_hash: Option<Byte32> = None;
+        for number in from..=to {
+            // the blocks near the tip may be replaced by a reorg during the export
+            let block = store
+                .get_block_hash(number)
+                .and_then(|hash| store.get_block(&hash))
+                .filter(|block| {
+                    parent_hash
+                        .as_ref()
+                        .map_or(true, |parent_hash| &block.parent_hash() == parent_hash)
+                });
+            let block = match block {
+                Some(block) => block,
+                None => {
+                    debug!("block export aborted by a reorg at {}", number);
+                    let error = IoError::new(
+                        ErrorKind::Other,
+                        format!("the chain is reorganized at block {number} during the export"),
+                    );
+                    drop(sender.blocking_send(Err(error)));
+                    break;
+                }
+            };
+            parent_hash = Some(block.hash());
+            let line = match format {
+                ExportFormat::Json => serde_json::to_vec(&BlockView::from(block)),
+                ExportFormat::Hex => {
+                    serde_json::to_vec(&JsonBytes::from_bytes(block.data().as_bytes()))
+                }
+            };
+            let item = line
+                .map(|mut line| {
+                    line.push(b'\n');
+                    Bytes::from(line)
+                })
+                .map_err(IoError::from);
+            let failed = item.is_err();
+            // waits while the client is slower, and the send fails once it disconnects
+            if sender.blocking_send(item).is_err() || failed {
+                debug!("block export stopped at {}", number);
+                break;
+            }
+        }
+    });
+
+    let stream = futures_util::stream::poll_fn(move |cx| receiver.poll_recv(cx));
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        StreamBody::new(stream),
+    )
+        .into_response()
+}
+
+fn parse_number(number: &str) -> Option<BlockNumber> {
+    match number.strip_prefix("0x") {
+        Some(hex) => BlockNumber::from_str_radix(hex, 16).ok(),
+        None => number.parse().ok(),
+    }
+}
@@ -0,0 +1,84 @@
+use ckb_jsonrpc_types::DeprecatedMethod;
+use ckb_logger::warn;
+use ckb_util::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+// A deprecated method is logged at most once in the interval
+const WARNING_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The metadata of a deprecated RPC method.
+///
+/// Every method marked `#[deprecated]` in the RPC modules must have an entry in
+/// [`DEPRECATED_METHODS`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct DeprecatedRpcMethod {
+    pub name: &'static str,
+    pub since: &'static str,
+    pub replacement: Option<&'static str>,
+    // HTTP-date, returned in the `Sunset` header
+    pub sunset: Option<&'static str>,
+}
+
+pub(crate) const DEPRECATED_METHODS: &[DeprecatedRpcMethod] = &[
+    DeprecatedRpcMethod {
+        name: "dry_run_transaction",
+        since: "0.105.1",
+        replacement: Some("estimate_cycles"),
+        sunset: None,
+    },
+    DeprecatedRpcMethod {
+        name: "get_fee_rate_statics",
+        since: "0.109.0",
+        replacement: Some("get_fee_rate_statistics"),
+        sunset: None,
+    },
+];
+
+impl DeprecatedRpcMethod {
+    /// The message returned in the `warning` field of the responses.
+    pub(crate) fn warning(&self) -> String {
+        let mut warning = format!("{} is deprecated since {}", self.name, self.since);
+        if let Some(sunset) = self.sunset {
+            warning.push_str(&format!(" and will be removed after {sunset}"));
+        }
+        if let Some(replacement) = self.replacement {
+            warning.push_str(&format!(", please use {replacement} instead"));
+        }
+        warning
+    }
+
+    pub(crate) fn to_json(&self, enabled: bool) -> DeprecatedMethod {
+        DeprecatedMethod {
+            name: self.name.to_owned(),
+            since: self.since.to_owned(),
+            replacement: self.replacement.map(ToOwned::to_owned),
+            sunset: self.sunset.map(ToOwned::to_owned),
+            enabled,
+        }
+    }
+}
+
+pub(crate) fn find_deprecated_method(name: &str) -> Option<&'static DeprecatedRpcMethod> {
+    DEPRECATED_METHODS.iter().find(|method| method.name == name)
+}
+
+/// Logs a warning when a deprecated method is called, at most once per method in 10 minutes.
+pub(crate) fn warn_deprecated_call(method: &DeprecatedRpcMethod) {
+    static LAST_WARNINGS: OnceLock<Mutex<HashMap<&'static str, Instant>>> = OnceLock::new();
+    let now = Instant::now();
+    let should_warn = {
+        let mut last_warnings = LAST_WARNINGS.get_or_init(Default::default).lock();
+        match last_warnings.get(method.name) {
+            Some(last) if now.duration_since(*last) < WARNING_INTERVAL => false,
+            _ => {
+                last_warnings.insert(method.name, now);
+                true
+            }
+        }
+    };
+    if should_warn {
+        warn!("RPC {}", method.warning());
+    }
+}
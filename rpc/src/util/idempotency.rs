@@ -0,0 +1,122 @@
+use crate::error::RPCError;
+use ckb_types::H256;
+use ckb_util::Mutex;
+use jsonrpc_core::{ErrorCode, Result};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+// The max length of an idempotency key in bytes
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 128;
+// The cached keys are limited, the oldest ones are forgotten first
+const MAX_IDEMPOTENCY_KEYS: usize = 100_000;
+
+type SendSlot = Arc<Mutex<Option<Result<H256>>>>;
+
+struct Entry {
+    tx_hash: H256,
+    // the results with `allow_high_fees` unset and set, the flag changes the result of a
+    // transaction with an absurd fee
+    slots: [SendSlot; 2],
+}
+
+#[derive(Default)]
+struct Entries {
+    by_key: HashMap<String, Entry>,
+    // keys in the insertion order, which is also the expiration order
+    order: VecDeque<(u64, String)>,
+}
+
+/// Caches the results of `send_transaction` by the client-supplied idempotency keys.
+///
+/// The first request of a key submits the transaction, the retries with the same key, transaction
+/// and `allow_high_fees` within the window get the same result, even when they race with the
+/// first one.
+pub(crate) struct IdempotencyCache {
+    // window in milliseconds, 0 disables the cache
+    window: u64,
+    entries: Mutex<Entries>,
+}
+
+impl IdempotencyCache {
+    pub(crate) fn new(window_secs: u64) -> Self {
+        IdempotencyCache {
+            window: window_secs.saturating_mul(1000),
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// Returns the cached result of the key, otherwise calls `send` and caches its result.
+    ///
+    /// The internal errors are not cached, so the retries submit the transaction again.
+    pub(crate) fn send<F>(
+        &self,
+        key: String,
+        tx_hash: &H256,
+        allow_high_fees: bool,
+        now_ms: u64,
+        send: F,
+    ) -> Result<H256>
+    where
+        F: FnOnce() -> Result<H256>,
+    {
+        if self.window == 0 {
+            return send();
+        }
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            return Err(RPCError::invalid_params(format!(
+                "the idempotency key must be 1 to {MAX_IDEMPOTENCY_KEY_LEN} bytes"
+            )));
+        }
+
+        let slot = self.slot(key, tx_hash, allow_high_fees, now_ms)?;
+        // the requests of the same key are serialized here
+        let mut cached = slot.lock();
+        if let Some(result) = cached.as_ref() {
+            return result.clone();
+        }
+        let result = send();
+        let internal_error = ErrorCode::ServerError(RPCError::CKBInternalError as i64);
+        if !matches!(&result, Err(err) if err.code == internal_error) {
+            *cached = Some(result.clone());
+        }
+        result
+    }
+
+    fn slot(
+        &self,
+        key: String,
+        tx_hash: &H256,
+        allow_high_fees: bool,
+        now_ms: u64,
+    ) -> Result<SendSlot> {
+        let mut entries = self.entries.lock();
+        let expired_before = now_ms.saturating_sub(self.window);
+        while let Some((created_at, _)) = entries.order.front() {
+            if *created_at > expired_before && entries.by_key.len() < MAX_IDEMPOTENCY_KEYS {
+                break;
+            }
+            if let Some((_, key)) = entries.order.pop_front() {
+                entries.by_key.remove(&key);
+            }
+        }
+
+        if let Some(entry) = entries.by_key.get(&key) {
+            if entry.tx_hash != *tx_hash {
+                return Err(RPCError::invalid_params(format!(
+                    "the idempotency key {key} is already used by the transaction {:#x}",
+                    entry.tx_hash
+                )));
+            }
+            return Ok(Arc::clone(&entry.slots[usize::from(allow_high_fees)]));
+        }
+
+        let entry = Entry {
+            tx_hash: tx_hash.clone(),
+            slots: Default::default(),
+        };
+        let slot = Arc::clone(&entry.slots[usize::from(allow_high_fees)]);
+        entries.order.push_back((now_ms, key.clone()));
+        entries.by_key.insert(key, entry);
+        Ok(slot)
+    }
+}
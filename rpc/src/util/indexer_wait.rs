@@ -0,0 +1,93 @@
+use crate::error::RPCError;
+use ckb_jsonrpc_types::{IndexerTip, IndexerWaitFor};
+use ckb_types::H256;
+use jsonrpc_core::Result;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_millis(3_000);
+const MAX_WAIT_TIMEOUT: Duration = Duration::from_millis(10_000);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Whether the indexed tip has reached the block in `wait_for`.
+///
+/// `indexed_hash` is the hash of the indexed block at the height of `wait_for`, which is `None`
+/// if the indexer has pruned it. An indexed block with another hash is on a fork which the
+/// indexer is going to roll back, so it doesn't count.
+pub(crate) fn has_indexed(
+    tip: &IndexerTip,
+    wait_for: &IndexerWaitFor,
+    indexed_hash: Option<&H256>,
+) -> bool {
+    let tip_number: u64 = tip.block_number.into();
+    let target_number: u64 = wait_for.block_number.into();
+    tip_number >= target_number
+        && match (wait_for.block_hash.as_ref(), indexed_hash) {
+            (Some(hash), Some(indexed_hash)) => hash == indexed_hash,
+            _ => true,
+        }
+}
+
+/// Polls the indexed tip until it reaches the block in `wait_for`, or returns the error
+/// `IndexerNotReady` after the timeout.
+///
+/// `get_block_hash` returns the hash of the indexed block by number, it's only called when
+/// `wait_for` has the block hash and the indexed tip is above it.
+pub(crate) async fn wait_for_indexer<F, Fut, G, GFut>(
+    wait_for: Option<IndexerWaitFor>,
+    get_indexer_tip: F,
+    get_block_hash: G,
+) -> Result<()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<Option<IndexerTip>>>,
+    G: Fn(u64) -> GFut,
+    GFut: Future<Output = Result<Option<H256>>>,
+{
+    let wait_for = match wait_for {
+        Some(wait_for) => wait_for,
+        None => return Ok(()),
+    };
+    let timeout = wait_for
+        .timeout
+        .map(|timeout| Duration::from_millis(timeout.into()))
+        .unwrap_or(DEFAULT_WAIT_TIMEOUT)
+        .min(MAX_WAIT_TIMEOUT);
+    let deadline = Instant::now() + timeout;
+    loop {
+        let tip = get_indexer_tip().await?;
+        let reached = match tip.as_ref() {
+            Some(tip) if tip.block_number >= wait_for.block_number => {
+                let indexed_hash = if wait_for.block_hash.is_none() {
+                    None
+                } else if tip.block_number == wait_for.block_number {
+                    Some(tip.block_hash.clone())
+                } else {
+                    get_block_hash(wait_for.block_number.into()).await?
+                };
+                has_indexed(tip, &wait_for, indexed_hash.as_ref())
+            }
+            _ => false,
+        };
+        if reached {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            let tip = tip.map_or_else(
+                || "none".to_owned(),
+                |tip| format!("{}-{:#x}", u64::from(tip.block_number), tip.block_hash),
+            );
+            return Err(RPCError::custom(
+                RPCError::IndexerNotReady,
+                format!(
+                    "the indexer hasn't processed block {} in {}ms, the indexed tip is {}",
+                    u64::from(wait_for.block_number),
+                    timeout.as_millis(),
+                    tip
+                ),
+            ));
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+}
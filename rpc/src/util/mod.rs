@@ -1,6 +1,19 @@
+pub(crate) mod block_export;
+pub(crate) mod deprecation;
 pub(crate) mod fee_rate;
+pub(crate) mod idempotency;
+pub(crate) mod indexer_wait;
+pub(crate) mod timeout;
 
+pub(crate) use deprecation::{find_deprecated_method, warn_deprecated_call, DEPRECATED_METHODS};
 pub(crate) use fee_rate::FeeRateCollector;
+pub(crate) use idempotency::IdempotencyCache;
+pub(crate) use indexer_wait::wait_for_indexer;
+pub(crate) use timeout::MethodTimeouts;
 
 #[cfg(test)]
 pub(crate) use fee_rate::FeeRateProvider;
+
+/// The max `limit` accepted by the paginated RPC methods which enumerate in-memory collections,
+/// such as `list_peers` and `list_pool_tx_hashes`.
+pub(crate) const MAX_PAGINATION_LIMIT: usize = 1000;
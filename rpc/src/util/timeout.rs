@@ -0,0 +1,164 @@
+use crate::error::RPCError;
+use ckb_app_config::{RpcMethodClass, RpcTimeoutsConfig};
+use jsonrpc_core::{Error, ErrorCode, Metadata, RemoteProcedure};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The built-in fast methods, which only read a few values kept in memory.
+pub(crate) const FAST_METHODS: &[&str] = &[
+    "get_tip_block_number",
+    "get_tip_header",
+    "get_block_hash",
+    "get_current_epoch",
+    "get_consensus",
+    "get_indexer_tip",
+    "local_node_info",
+    "tx_pool_info",
+    "tx_pool_ready",
+    "get_pool_policy",
+    "list_deprecated_methods",
+];
+
+/// The built-in heavy methods, which scan the database or the pool, or run the scripts.
+pub(crate) const HEAVY_METHODS: &[&str] = &[
+    "get_transactions_by_hashes",
+    "get_cells",
+    "get_transactions",
+    "get_cells_capacity",
+    "estimate_cycles",
+    "dry_run_transaction",
+    "test_tx_pool_accept",
+    "get_fee_rate_statics",
+    "get_fee_rate_statistics",
+    "get_epoch_stats",
+    "get_uncle_stats",
+    "get_raw_tx_pool",
+    "get_db_columns_info",
+    "check_chain_integrity",
+    "jemalloc_profiling_dump",
+    "generate_epochs",
+];
+
+/// The timeouts of the method calls by their classes.
+#[derive(Debug)]
+pub(crate) struct MethodTimeouts {
+    fast: Duration,
+    standard: Duration,
+    heavy: Duration,
+    overrides: HashMap<String, RpcMethodClass>,
+}
+
+impl MethodTimeouts {
+    pub(crate) fn new(config: &RpcTimeoutsConfig) -> Self {
+        MethodTimeouts {
+            fast: Duration::from_millis(config.fast_ms),
+            standard: Duration::from_millis(config.standard_ms),
+            heavy: Duration::from_millis(config.heavy_ms),
+            overrides: config
+                .methods
+                .iter()
+                .map(|(method, class)| (method.to_owned(), *class))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn class_of(&self, method: &str) -> RpcMethodClass {
+        if let Some(class) = self.overrides.get(method) {
+            *class
+        } else if FAST_METHODS.contains(&method) {
+            RpcMethodClass::Fast
+        } else if HEAVY_METHODS.contains(&method) {
+            RpcMethodClass::Heavy
+        } else {
+            RpcMethodClass::Standard
+        }
+    }
+
+    pub(crate) fn timeout_of(&self, class: RpcMethodClass) -> Duration {
+        match class {
+            RpcMethodClass::Fast => self.fast,
+            RpcMethodClass::Standard => self.standard,
+            RpcMethodClass::Heavy => self.heavy,
+        }
+    }
+}
+
+/// Wraps the method so its calls return the error `Timeout` after the timeout of its class. It is
+/// applied to the methods themselves, so the calls are aborted on all the transports, HTTP, TCP
+/// and WebSocket.
+///
+/// The synchronous methods run when they are called rather than when their futures are polled,
+/// so the call is made in the blocking pool to be able to return before it completes. A call
+/// which has started in the blocking pool can't be interrupted, it keeps running after the
+/// timeout and its result is discarded. A call which is still waiting for a blocking thread is
+/// skipped, and the asynchronous part of a call is dropped.
+pub(crate) fn with_timeout<T: Metadata>(
+    timeouts: &MethodTimeouts,
+    name: &str,
+    method: RemoteProcedure<T>,
+) -> RemoteProcedure<T> {
+    let inner = match method {
+        RemoteProcedure::Method(inner) => inner,
+        // notifications have no responses to abort
+        other => return other,
+    };
+    let name: Arc<str> = name.into();
+    let class = timeouts.class_of(&name);
+    let timeout = timeouts.timeout_of(class);
+    RemoteProcedure::Method(Arc::new(move |params, meta| {
+        let inner = Arc::clone(&inner);
+        let name = Arc::clone(&name);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let call = {
+            let cancelled = Arc::clone(&cancelled);
+            async move {
+                let output = tokio::task::spawn_blocking(move || {
+                    (!cancelled.load(Ordering::Acquire)).then(|| inner.call(params, meta))
+                })
+                .await;
+                match output {
+                    Ok(Some(output)) => output.await,
+                    _ => Err(Error::internal_error()),
+                }
+            }
+        };
+        async move {
+            match tokio::time::timeout(timeout, call).await {
+                Ok(output) => output,
+                Err(_) => {
+                    cancelled.store(true, Ordering::Release);
+                    Err(timeout_error(&name, class, timeout))
+                }
+            }
+        }
+    }))
+}
+
+/// The error returned when a call is not completed in time.
+///
+/// Unlike the other errors, the data is an object with the method, its class and the timeout in
+/// milliseconds, so the clients can tell which call to retry or split.
+pub(crate) fn timeout_error(method: &str, class: RpcMethodClass, timeout: Duration) -> Error {
+    let class = serde_json::to_value(class).expect("serialize method class");
+    Error {
+        code: ErrorCode::ServerError(RPCError::Timeout as i64),
+        message: format!(
+            "{:?}: {} is not completed within {} ms",
+            RPCError::Timeout,
+            method,
+            timeout.as_millis()
+        ),
+        data: Some(json!({
+            "method": method,
+            "class": class,
+            "timeout_ms": timeout.as_millis() as u64,
+        })),
+    }
+}
+
+pub(crate) fn is_timeout_error(error: &Error) -> bool {
+    error.code == ErrorCode::ServerError(RPCError::Timeout as i64)
+}
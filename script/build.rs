@@ -16,7 +16,14 @@ fn main() {
         panic!("ASM feature is not available for target {target_arch} on {target_family}!");
     }
 
-    if cfg!(any(feature = "asm", feature = "detect-asm")) && can_enable_asm {
+    if cfg!(all(feature = "asm", feature = "portable")) {
+        panic!("ASM feature can not be enabled together with the portable feature!");
+    }
+
+    if cfg!(any(feature = "asm", feature = "detect-asm"))
+        && !cfg!(feature = "portable")
+        && can_enable_asm
+    {
         println!("cargo:rustc-cfg=has_asm");
     }
     println!("cargo:rerun-if-changed=src");
@@ -16,6 +16,10 @@ pub enum ScriptError {
     #[error("ExceededMaximumCycles: expect cycles <= {0}")]
     ExceededMaximumCycles(Cycle),
 
+    /// The script group consumes more cycles than the local policy allows
+    #[error("ExceededMaximumGroupCycles: script group {0} expects cycles <= {1}")]
+    ExceededMaximumGroupCycles(Byte32, Cycle),
+
     /// Internal error cycles overflow
     #[error("CyclesOverflow: lhs {0} rhs {1}")]
     CyclesOverflow(Cycle, Cycle),
@@ -1,4 +1,19 @@
 //! CKB component to run the type/lock scripts.
+//!
+//! ## Features
+//!
+//! - `asm` / `detect-asm`: run scripts with the ASM machine when the target supports it.
+//! - `async-verify`: enables the signal-driven
+//!   [`TransactionScriptsVerifier::resumable_verify_with_signal`], which depends on tokio.
+//! - `portable`: always use the interpreter machine. Together with `default-features = false`,
+//!   the verifier builds for targets without an OS, such as `wasm32-unknown-unknown`:
+//!
+//! ```toml
+//! ckb-script = { version = "...", default-features = false, features = ["portable"] }
+//! ```
+//!
+//! - `custom-syscalls`: enables [`TransactionScriptsVerifier::register_custom_syscall`] to add
+//!   extra syscalls with their own cycle prices on dev chains. [`Blake2bSyscall`] is an example.
 pub mod cost_model;
 mod error;
 mod scheduler;
@@ -11,8 +26,12 @@ mod verify_env;
 pub use crate::error::{ScriptError, TransactionScriptError};
 pub use crate::scheduler::{Scheduler, ROOT_VM_ID};
 pub use crate::types::{
-    ChunkCommand, CoreMachine, DataPieceId, RunMode, ScriptGroup, ScriptGroupType, ScriptVersion,
-    TransactionSnapshot, TransactionState, TxData, VerifyResult, VmIsa, VmState, VmVersion,
+    ChunkCommand, CoreMachine, DataPieceId, RunMode, ScriptGroup, ScriptGroupCycles,
+    ScriptGroupResult, ScriptGroupType, ScriptVersion, TransactionSnapshot, TransactionState,
+    TxData, VerifyResult, VmCycles, VmId, VmIsa, VmState, VmVersion, VM_BACKEND,
 };
 pub use crate::verify::{TransactionScriptsSyscallsGenerator, TransactionScriptsVerifier};
 pub use crate::verify_env::TxVerifyEnv;
+
+#[cfg(feature = "custom-syscalls")]
+pub use crate::syscalls::{Blake2bSyscall, CustomSyscall, MIN_CUSTOM_SYSCALL_NUMBER};
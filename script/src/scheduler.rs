@@ -9,7 +9,7 @@ use crate::ScriptVersion;
 
 use crate::types::{
     CoreMachineType, DataPieceId, Fd, FdArgs, FullSuspendedState, Machine, Message, ReadState,
-    RunMode, TxData, VmId, VmState, WriteState, FIRST_FD_SLOT, FIRST_VM_ID,
+    RunMode, TxData, VmCycles, VmId, VmState, WriteState, FIRST_FD_SLOT, FIRST_VM_ID,
 };
 use ckb_traits::{CellDataProvider, ExtensionProvider, HeaderProvider};
 use ckb_types::core::Cycle;
@@ -77,6 +77,8 @@ where
     pub suspended: BTreeMap<VmId, Snapshot2<DataPieceId>>,
     /// Terminated vms.
     pub terminated_vms: BTreeMap<VmId, i8>,
+    /// The cycles consumed by each vm, including the terminated ones.
+    pub vm_cycles: BTreeMap<VmId, VmCycles>,
 
     /// MessageBox is expected to be empty before returning from `run`
     /// function, there is no need to persist messages.
@@ -109,6 +111,7 @@ where
             suspended: BTreeMap::default(),
             message_box,
             terminated_vms: BTreeMap::default(),
+            vm_cycles: BTreeMap::default(),
         }
     }
 
@@ -117,6 +120,11 @@ where
         self.total_cycles
     }
 
+    /// Return the cycles consumed by each vm, ordered by the vm ids.
+    pub fn vm_cycles(&self) -> Vec<VmCycles> {
+        self.vm_cycles.values().copied().collect()
+    }
+
     /// Add cycles to total cycles.
     pub fn consumed_cycles_add(&mut self, cycles: Cycle) -> Result<(), Error> {
         self.total_cycles = self
@@ -157,6 +165,11 @@ where
                 .collect(),
             message_box,
             terminated_vms: full.terminated_vms.into_iter().collect(),
+            vm_cycles: full
+                .vm_cycles
+                .into_iter()
+                .map(|vm_cycles| (vm_cycles.id, vm_cycles))
+                .collect(),
         };
         scheduler
             .ensure_vms_instantiated(&full.instantiated_ids)
@@ -188,6 +201,7 @@ where
             inherited_fd: self.inherited_fd.into_iter().collect(),
             terminated_vms: self.terminated_vms.into_iter().collect(),
             instantiated_ids,
+            vm_cycles: self.vm_cycles.into_values().collect(),
         })
     }
 
@@ -211,6 +225,14 @@ where
                 self.boot_vm(&DataPieceId::Program, 0, u64::max_value(), &[])?,
                 ROOT_VM_ID
             );
+            self.vm_cycles.insert(
+                ROOT_VM_ID,
+                VmCycles {
+                    id: ROOT_VM_ID,
+                    parent_id: None,
+                    cycles: 0,
+                },
+            );
         }
         assert!(self.states.contains_key(&ROOT_VM_ID));
 
@@ -333,7 +355,14 @@ where
         let result = vm.run();
         let cycles = vm.machine.cycles();
         vm.machine.set_cycles(0);
-        self.iterate_process_results(id, result, cycles)
+        let result = self.iterate_process_results(id, result, cycles);
+        // the cycles to suspend and resume the vms in this iteration are charged to the vm as well
+        if let Some(vm_cycles) = self.vm_cycles.get_mut(&id) {
+            vm_cycles.cycles = vm_cycles
+                .cycles
+                .saturating_add(self.current_iteration_cycles);
+        }
+        result
     }
 
     fn process_message_box(&mut self) -> Result<(), Error> {
@@ -361,6 +390,14 @@ where
                     // Here we keep the original version of file descriptors.
                     // If one fd is moved afterward, this inherited file descriptors doesn't change.
                     self.inherited_fd.insert(spawned_vm_id, args.fds.clone());
+                    self.vm_cycles.insert(
+                        spawned_vm_id,
+                        VmCycles {
+                            id: spawned_vm_id,
+                            parent_id: Some(vm_id),
+                            cycles: 0,
+                        },
+                    );
 
                     let (_, machine) = self.ensure_get_instantiated(&vm_id)?;
                     machine
@@ -0,0 +1,120 @@
+//! Custom syscalls for experiments on dev chains.
+//!
+//! A dev chain operator can register extra syscalls to the verifier with
+//! [`TransactionScriptsVerifier::register_custom_syscall`]. Each custom syscall prices itself via
+//! [`CustomSyscall::cycles`], the cycles are charged before the syscall runs, so the scripts
+//! calling them are still bounded by the cycles limits.
+//!
+//! The custom syscalls change the behavior of the scripts, so they are refused on the public
+//! chains, and their numbers must not be less than [`MIN_CUSTOM_SYSCALL_NUMBER`] to never shadow
+//! the builtin syscalls.
+//!
+//! [`TransactionScriptsVerifier::register_custom_syscall`]: crate::TransactionScriptsVerifier::register_custom_syscall
+use crate::{
+    cost_model::transferred_byte_cycles,
+    syscalls::{SLICE_OUT_OF_BOUND, SUCCESS},
+    types::CoreMachine,
+};
+use ckb_hash::blake2b_256;
+use ckb_types::core::Cycle;
+use ckb_vm::{
+    registers::{A0, A1, A2, A7},
+    Error as VMError, Memory, Register, SupportMachine, Syscalls,
+};
+use std::sync::Arc;
+
+/// The minimum number of the custom syscalls, the numbers below it are reserved for the builtin
+/// syscalls.
+pub const MIN_CUSTOM_SYSCALL_NUMBER: u64 = 0x1_0000;
+
+/// A syscall registered by the dev chain operator.
+pub trait CustomSyscall: Send + Sync {
+    /// The syscall number, which is read from register `A7`.
+    fn number(&self) -> u64;
+
+    /// The cycles charged for the call, the arguments can be read from the registers.
+    fn cycles(&self, machine: &mut CoreMachine) -> Result<Cycle, VMError>;
+
+    /// Runs the syscall after the cycles are charged.
+    fn call(&self, machine: &mut CoreMachine) -> Result<(), VMError>;
+}
+
+/// Charges the cycles priced by the custom syscall before running it.
+pub(crate) struct MeteredSyscall {
+    inner: Arc<dyn CustomSyscall>,
+}
+
+impl MeteredSyscall {
+    pub(crate) fn new(inner: Arc<dyn CustomSyscall>) -> Self {
+        MeteredSyscall { inner }
+    }
+}
+
+impl Syscalls<CoreMachine> for MeteredSyscall {
+    fn initialize(&mut self, _machine: &mut CoreMachine) -> Result<(), VMError> {
+        Ok(())
+    }
+
+    fn ecall(&mut self, machine: &mut CoreMachine) -> Result<bool, VMError> {
+        if machine.registers()[A7].to_u64() != self.inner.number() {
+            return Ok(false);
+        }
+        let cycles = self.inner.cycles(machine)?;
+        machine.add_cycles_no_checking(cycles)?;
+        self.inner.call(machine)?;
+        Ok(true)
+    }
+}
+
+/// An example custom syscall which hashes a memory slice with blake2b.
+///
+/// Arguments:
+///
+/// * `A0`: the address to store the 32-byte hash.
+/// * `A1`: the address of the data.
+/// * `A2`: the length of the data, at most [`Blake2bSyscall::MAX_DATA_LEN`].
+///
+/// Returns `SUCCESS` in `A0`, or `SLICE_OUT_OF_BOUND` if the data is too long.
+#[derive(Debug, Default)]
+pub struct Blake2bSyscall {}
+
+impl Blake2bSyscall {
+    /// The syscall number.
+    pub const NUMBER: u64 = MIN_CUSTOM_SYSCALL_NUMBER;
+    /// The cycles charged for each call besides the data.
+    pub const BASE_CYCLES: Cycle = 500;
+    /// The maximum length of the data in one call.
+    pub const MAX_DATA_LEN: u64 = 1024 * 1024;
+
+    /// Creates the syscall.
+    pub fn new() -> Self {
+        Blake2bSyscall {}
+    }
+}
+
+impl CustomSyscall for Blake2bSyscall {
+    fn number(&self) -> u64 {
+        Self::NUMBER
+    }
+
+    fn cycles(&self, machine: &mut CoreMachine) -> Result<Cycle, VMError> {
+        let len = machine.registers()[A2].to_u64().min(Self::MAX_DATA_LEN);
+        Ok(Self::BASE_CYCLES + transferred_byte_cycles(len))
+    }
+
+    fn call(&self, machine: &mut CoreMachine) -> Result<(), VMError> {
+        let hash_addr = machine.registers()[A0].to_u64();
+        let data_addr = machine.registers()[A1].to_u64();
+        let len = machine.registers()[A2].to_u64();
+        if len > Self::MAX_DATA_LEN {
+            machine.set_register(A0, u64::from(SLICE_OUT_OF_BOUND));
+            return Ok(());
+        }
+        let data = machine.memory_mut().load_bytes(data_addr, len)?;
+        machine
+            .memory_mut()
+            .store_bytes(hash_addr, &blake2b_256(&data))?;
+        machine.set_register(A0, u64::from(SUCCESS));
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 mod close;
 mod current_cycles;
+#[cfg(any(feature = "custom-syscalls", test))]
+mod custom;
 mod debugger;
 mod exec;
 mod inherited_fd;
@@ -29,6 +31,10 @@ mod tests;
 
 pub use self::close::Close;
 pub use self::current_cycles::CurrentCycles;
+#[cfg(any(feature = "custom-syscalls", test))]
+pub(crate) use self::custom::MeteredSyscall;
+#[cfg(any(feature = "custom-syscalls", test))]
+pub use self::custom::{Blake2bSyscall, CustomSyscall, MIN_CUSTOM_SYSCALL_NUMBER};
 pub use self::debugger::Debugger;
 pub use self::exec::Exec;
 pub use self::inherited_fd::InheritedFd;
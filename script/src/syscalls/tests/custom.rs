@@ -0,0 +1,123 @@
+use crate::{
+    cost_model::transferred_byte_cycles,
+    syscalls::{tests::utils::MockDataLoader, *},
+    ScriptError, ScriptVersion, TransactionScriptsVerifier, TxVerifyEnv,
+};
+use ckb_chain_spec::consensus::ConsensusBuilder;
+use ckb_hash::blake2b_256;
+use ckb_types::core::{cell::ResolvedTransaction, HeaderView, TransactionBuilder};
+use ckb_vm::{
+    registers::{A0, A1, A2, A7},
+    Memory, SupportMachine, Syscalls,
+};
+use std::sync::Arc;
+
+fn verifier(chain: &str) -> TransactionScriptsVerifier<MockDataLoader> {
+    let rtx = ResolvedTransaction::dummy_resolve(TransactionBuilder::default().build());
+    let consensus = ConsensusBuilder::default().id(chain.to_owned()).build();
+    let header = HeaderView::new_advanced_builder().build();
+    TransactionScriptsVerifier::new(
+        Arc::new(rtx),
+        MockDataLoader::default(),
+        Arc::new(consensus),
+        Arc::new(TxVerifyEnv::new_commit(&header)),
+    )
+}
+
+struct Numbered(u64);
+
+impl CustomSyscall for Numbered {
+    fn number(&self) -> u64 {
+        self.0
+    }
+
+    fn cycles(&self, _machine: &mut crate::CoreMachine) -> Result<u64, ckb_vm::Error> {
+        Ok(0)
+    }
+
+    fn call(&self, _machine: &mut crate::CoreMachine) -> Result<(), ckb_vm::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_register_custom_syscall() {
+    let mut verifier = verifier("ckb_dev");
+    verifier
+        .register_custom_syscall(Arc::new(Blake2bSyscall::new()))
+        .expect("register on dev chain");
+
+    let err = verifier
+        .register_custom_syscall(Arc::new(Numbered(Blake2bSyscall::NUMBER)))
+        .unwrap_err();
+    assert!(matches!(err, ScriptError::Other(_)), "{err}");
+
+    // never shadow the builtin syscalls
+    let err = verifier
+        .register_custom_syscall(Arc::new(Numbered(LOAD_WITNESS_SYSCALL_NUMBER)))
+        .unwrap_err();
+    assert!(matches!(err, ScriptError::Other(_)), "{err}");
+    verifier
+        .register_custom_syscall(Arc::new(Numbered(MIN_CUSTOM_SYSCALL_NUMBER + 1)))
+        .expect("register another number");
+}
+
+#[test]
+fn test_register_custom_syscall_on_public_chains() {
+    for chain in ["ckb", "ckb_testnet"] {
+        let mut verifier = verifier(chain);
+        let err = verifier
+            .register_custom_syscall(Arc::new(Blake2bSyscall::new()))
+            .unwrap_err();
+        assert!(matches!(err, ScriptError::Other(_)), "{err}");
+    }
+}
+
+#[test]
+fn test_blake2b_syscall_charges_cycles() {
+    let mut machine = ScriptVersion::latest().init_core_machine_without_limit();
+    let data = vec![7u8; 1000];
+    let data_addr: u64 = 0x1000;
+    let hash_addr: u64 = 0x100;
+    machine
+        .memory_mut()
+        .store_bytes(data_addr, &data)
+        .expect("store data");
+
+    machine.set_register(A0, hash_addr);
+    machine.set_register(A1, data_addr);
+    machine.set_register(A2, data.len() as u64);
+    machine.set_register(A7, Blake2bSyscall::NUMBER);
+
+    let mut syscall = MeteredSyscall::new(Arc::new(Blake2bSyscall::new()));
+    assert!(syscall.ecall(&mut machine).unwrap());
+    assert_eq!(machine.registers()[A0], u64::from(SUCCESS));
+    assert_eq!(
+        machine.cycles(),
+        Blake2bSyscall::BASE_CYCLES + transferred_byte_cycles(data.len() as u64)
+    );
+    let hash = machine.memory_mut().load_bytes(hash_addr, 32).unwrap();
+    assert_eq!(&hash[..], &blake2b_256(&data)[..]);
+
+    // other numbers are left to the next syscalls
+    machine.set_register(A7, Blake2bSyscall::NUMBER + 1);
+    assert!(!syscall.ecall(&mut machine).unwrap());
+}
+
+#[test]
+fn test_blake2b_syscall_rejects_long_data() {
+    let mut machine = ScriptVersion::latest().init_core_machine_without_limit();
+    machine.set_register(A0, 0);
+    machine.set_register(A1, 0);
+    machine.set_register(A2, Blake2bSyscall::MAX_DATA_LEN + 1);
+    machine.set_register(A7, Blake2bSyscall::NUMBER);
+
+    let mut syscall = MeteredSyscall::new(Arc::new(Blake2bSyscall::new()));
+    assert!(syscall.ecall(&mut machine).unwrap());
+    assert_eq!(machine.registers()[A0], u64::from(SLICE_OUT_OF_BOUND));
+    // the cycles are capped by the maximum length
+    assert_eq!(
+        machine.cycles(),
+        Blake2bSyscall::BASE_CYCLES + transferred_byte_cycles(Blake2bSyscall::MAX_DATA_LEN)
+    );
+}
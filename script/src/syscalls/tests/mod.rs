@@ -4,6 +4,8 @@
 
 pub(crate) mod utils;
 
+#[cfg(any(feature = "custom-syscalls", test))]
+mod custom;
 mod vm_version_0;
 #[path = "vm_latest/mod.rs"]
 mod vm_version_1;
@@ -1,3 +1,4 @@
+use crate::error::TransactionScriptError;
 use ckb_error::Error;
 use ckb_types::{
     core::{Cycle, ScriptHashType},
@@ -34,6 +35,13 @@ pub type VmIsa = u8;
 /// /// The type of CKB-VM version.
 pub type VmVersion = u32;
 
+/// The CKB-VM backend selected at build time.
+#[cfg(has_asm)]
+pub const VM_BACKEND: &str = "asm";
+/// The CKB-VM backend selected at build time.
+#[cfg(not(has_asm))]
+pub const VM_BACKEND: &str = "interpreter";
+
 #[cfg(has_asm)]
 pub(crate) type CoreMachineType = AsmCoreMachine;
 #[cfg(all(not(has_asm), not(feature = "flatmemory")))]
@@ -285,6 +293,42 @@ pub enum VerifyResult {
     Suspended(TransactionState),
 }
 
+/// The cycles of a script group in
+/// [`TransactionScriptsVerifier::verify_with_vm_cycles`](crate::TransactionScriptsVerifier::verify_with_vm_cycles).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptGroupCycles {
+    /// The group type
+    pub group_type: ScriptGroupType,
+    /// The script hash of the group
+    pub script_hash: Byte32,
+    /// The consumed cycles of the group
+    pub cycles: Cycle,
+    /// The cycles consumed by each VM, ordered by the VM ids. It's empty for the type id script,
+    /// which is not run in the VM.
+    pub vms: Vec<VmCycles>,
+}
+
+/// The result of a script group in
+/// [`TransactionScriptsVerifier::verify_exhaustive`](crate::TransactionScriptsVerifier::verify_exhaustive).
+#[derive(Debug)]
+pub struct ScriptGroupResult {
+    /// The group type
+    pub group_type: ScriptGroupType,
+    /// The script hash of the group
+    pub script_hash: Byte32,
+    /// The consumed cycles, which are unknown if the script fails to load or the VM fails.
+    pub cycles: Option<Cycle>,
+    /// The error if the group fails
+    pub error: Option<TransactionScriptError>,
+}
+
+impl ScriptGroupResult {
+    /// Whether the group passes
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 impl std::fmt::Debug for TransactionSnapshot {
     fn fmt(&self, f: &mut ::core::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("TransactionSnapshot")
@@ -347,6 +391,20 @@ where
 pub type VmId = u64;
 pub const FIRST_VM_ID: VmId = 0;
 
+/// The cycles consumed by a VM of a script. The root VM and the VMs spawned by it form a tree by
+/// the parent ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VmCycles {
+    /// The VM id, the root VM is [`ROOT_VM_ID`](crate::ROOT_VM_ID).
+    pub id: VmId,
+    /// The id of the VM which spawns it, `None` for the root VM.
+    pub parent_id: Option<VmId>,
+    /// The cycles consumed by the VM itself, excluding the VMs spawned by it.
+    ///
+    /// The cycles to suspend and resume the VMs are charged to the VM which gets to run.
+    pub cycles: Cycle,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Fd(pub(crate) u64);
 
@@ -508,6 +566,7 @@ pub struct FullSuspendedState {
     pub inherited_fd: Vec<(VmId, Vec<Fd>)>,
     pub terminated_vms: Vec<(VmId, i8)>,
     pub instantiated_ids: Vec<VmId>,
+    pub vm_cycles: Vec<VmCycles>,
 }
 
 impl FullSuspendedState {
@@ -537,6 +596,7 @@ impl FullSuspendedState {
             + (self.inherited_fd.len() * (size_of::<Fd>())) as u64
             + (self.terminated_vms.len() * (size_of::<VmId>() + size_of::<i8>())) as u64
             + (self.instantiated_ids.len() * size_of::<VmId>()) as u64
+            + (self.vm_cycles.len() * size_of::<VmCycles>()) as u64
     }
 }
 
@@ -1,6 +1,8 @@
 use crate::scheduler::Scheduler;
 #[cfg(test)]
 use crate::syscalls::Pause;
+#[cfg(any(feature = "custom-syscalls", test))]
+use crate::syscalls::{CustomSyscall, MeteredSyscall, MIN_CUSTOM_SYSCALL_NUMBER};
 use crate::syscalls::{InheritedFd, ProcessID, EXEC_LOAD_ELF_V2_CYCLES_BASE};
 use crate::types::{DataPieceId, FullSuspendedState, Message, RunMode, TxData, VmId, FIRST_VM_ID};
 use crate::{
@@ -12,8 +14,9 @@ use crate::{
     },
     type_id::TypeIdSystemScript,
     types::{
-        CoreMachine, DebugPrinter, Indices, ScriptGroup, ScriptGroupType, ScriptVersion,
-        TransactionSnapshot, TransactionState, VerifyResult,
+        CoreMachine, DebugPrinter, Indices, ScriptGroup, ScriptGroupCycles, ScriptGroupResult,
+        ScriptGroupType, ScriptVersion, TransactionSnapshot, TransactionState, VerifyResult,
+        VmCycles,
     },
     verify_env::TxVerifyEnv,
     ChunkCommand,
@@ -39,6 +42,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     sync::RwLock,
 };
+#[cfg(feature = "async-verify")]
 use tokio::sync::{
     oneshot,
     watch::{self, Receiver},
@@ -144,6 +148,8 @@ where
     DL: CellDataProvider + HeaderProvider + ExtensionProvider + Send + Sync + Clone + 'static,
 {
     pub(crate) base_cycles: Arc<Mutex<u64>>,
+    #[cfg(any(feature = "custom-syscalls", test))]
+    pub(crate) custom_syscalls: Vec<Arc<dyn CustomSyscall>>,
     pub(crate) data_loader: DL,
     pub(crate) debug_printer: DebugPrinter,
     pub(crate) message_box: Arc<Mutex<Vec<Message>>>,
@@ -348,6 +354,10 @@ where
                 Box::new(self.close()),
             ]);
         }
+        #[cfg(any(feature = "custom-syscalls", test))]
+        for custom_syscall in &self.custom_syscalls {
+            syscalls.push(Box::new(MeteredSyscall::new(Arc::clone(custom_syscall))));
+        }
         #[cfg(test)]
         syscalls.push(Box::new(Pause::new(Arc::clone(&self.skip_pause))));
         syscalls
@@ -379,6 +389,8 @@ where
     tx_env: Arc<TxVerifyEnv>,
 
     syscalls_generator: TransactionScriptsSyscallsGenerator<DL>,
+
+    max_group_cycles: Option<Cycle>,
 }
 
 impl<DL> TransactionScriptsVerifier<DL>
@@ -477,6 +489,8 @@ where
 
         let syscalls_generator = TransactionScriptsSyscallsGenerator {
             base_cycles: Arc::new(Mutex::new(0)),
+            #[cfg(any(feature = "custom-syscalls", test))]
+            custom_syscalls: Vec::new(),
             data_loader: data_loader.clone(),
             debug_printer: Arc::clone(&debug_printer),
             message_box: Arc::new(Mutex::new(Vec::new())),
@@ -499,6 +513,7 @@ where
             consensus,
             tx_env,
             syscalls_generator,
+            max_group_cycles: None,
         }
     }
 
@@ -515,6 +530,72 @@ where
         self.syscalls_generator.debug_printer = Arc::new(func);
     }
 
+    /// Sets the maximum cycles a single script group is allowed to consume.
+    ///
+    /// It is a local policy, the consensus only limits the cycles of the whole transaction. A
+    /// group exceeding the limit fails with `ScriptError::ExceededMaximumGroupCycles`. Only
+    /// `verify` and `resumable_verify_with_signal` apply the limit.
+    pub fn set_max_group_cycles(&mut self, max_group_cycles: Option<Cycle>) {
+        self.max_group_cycles = max_group_cycles;
+    }
+
+    /// Registers an extra syscall for experiments on dev chains.
+    ///
+    /// The cycles priced by the syscall are charged before it runs. It fails on the public
+    /// chains, if the number is less than `MIN_CUSTOM_SYSCALL_NUMBER`, or if the number is
+    /// already registered.
+    #[cfg(any(feature = "custom-syscalls", test))]
+    pub fn register_custom_syscall(
+        &mut self,
+        syscall: Arc<dyn CustomSyscall>,
+    ) -> Result<(), ScriptError> {
+        if self.consensus.is_public_chain() {
+            return Err(ScriptError::Other(format!(
+                "custom syscalls are not allowed on chain {}",
+                self.consensus.id
+            )));
+        }
+        let number = syscall.number();
+        if number < MIN_CUSTOM_SYSCALL_NUMBER {
+            return Err(ScriptError::Other(format!(
+                "custom syscall number {number} is less than {MIN_CUSTOM_SYSCALL_NUMBER}"
+            )));
+        }
+        if self
+            .syscalls_generator
+            .custom_syscalls
+            .iter()
+            .any(|registered| registered.number() == number)
+        {
+            return Err(ScriptError::Other(format!(
+                "custom syscall number {number} is already registered"
+            )));
+        }
+        self.syscalls_generator.custom_syscalls.push(syscall);
+        Ok(())
+    }
+
+    // The cycles limit to run a group when `remain_cycles` are left for the transaction.
+    fn group_cycles_limit(&self, remain_cycles: Cycle) -> Cycle {
+        self.max_group_cycles
+            .map_or(remain_cycles, |limit| limit.min(remain_cycles))
+    }
+
+    // Tells the group limit from the transaction limit when a group runs out of cycles.
+    fn group_cycles_error(
+        &self,
+        group: &ScriptGroup,
+        remain_cycles: Cycle,
+        error: ScriptError,
+    ) -> ScriptError {
+        match (error, self.max_group_cycles) {
+            (ScriptError::ExceededMaximumCycles(_), Some(limit)) if limit < remain_cycles => {
+                ScriptError::ExceededMaximumGroupCycles(group.script.calc_script_hash(), limit)
+            }
+            (error, _) => error,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn set_skip_pause(&self, skip_pause: bool) {
         self.skip_pause.store(skip_pause, Ordering::SeqCst);
@@ -626,8 +707,10 @@ where
         // Now run each script group
         for (_hash, group) in self.groups() {
             // max_cycles must reduce by each group exec
+            let remain_cycles = max_cycles - cycles;
             let used_cycles = self
-                .verify_script_group(group, max_cycles - cycles)
+                .verify_script_group(group, self.group_cycles_limit(remain_cycles))
+                .map_err(|e| self.group_cycles_error(group, remain_cycles, e))
                 .map_err(|e| {
                     #[cfg(feature = "logging")]
                     logging::on_script_error(_hash, &self.hash(), &e);
@@ -639,6 +722,92 @@ where
         Ok(cycles)
     }
 
+    /// Verifies the transaction like [`verify`](Self::verify), and returns the cycles consumed by
+    /// each script group and by each VM spawned in the group as well.
+    pub fn verify_with_vm_cycles(
+        &self,
+        max_cycles: Cycle,
+    ) -> Result<(Cycle, Vec<ScriptGroupCycles>), Error> {
+        let mut cycles: Cycle = 0;
+        let mut groups = Vec::new();
+
+        for (group_type, hash, group) in self.groups_with_type() {
+            let remain_cycles = max_cycles - cycles;
+            let (used_cycles, vms) = self
+                .run_group_with_vm_cycles(group, self.group_cycles_limit(remain_cycles))
+                .map_err(|e| self.group_cycles_error(group, remain_cycles, e))
+                .map_err(|e| {
+                    #[cfg(feature = "logging")]
+                    logging::on_script_error(hash, &self.hash(), &e);
+                    e.source(group)
+                })?;
+
+            cycles = wrapping_cycles_add(cycles, used_cycles, group)?;
+            groups.push(ScriptGroupCycles {
+                group_type,
+                script_hash: hash.clone(),
+                cycles: used_cycles,
+                vms,
+            });
+        }
+        Ok((cycles, groups))
+    }
+
+    /// Runs all the script groups even after some of them fail, so a dry run reports all the
+    /// failures at once.
+    ///
+    /// The transaction is valid only if all the groups pass. Each group runs with the cycles left
+    /// by the groups before it, so the groups after the limit is reached fail with
+    /// `ExceededMaximumCycles`.
+    pub fn verify_exhaustive(&self, max_cycles: Cycle) -> Vec<ScriptGroupResult> {
+        let mut cycles: Cycle = 0;
+        self.groups_with_type()
+            .map(|(group_type, hash, group)| {
+                let remain_cycles = max_cycles.saturating_sub(cycles);
+                let (used_cycles, error) =
+                    self.run_group_to_end(group, self.group_cycles_limit(remain_cycles));
+                cycles = cycles.saturating_add(used_cycles.unwrap_or_default());
+                ScriptGroupResult {
+                    group_type,
+                    script_hash: hash.clone(),
+                    cycles: used_cycles,
+                    error: error.map(|e| {
+                        #[cfg(feature = "logging")]
+                        logging::on_script_error(hash, &self.hash(), &e);
+                        self.group_cycles_error(group, remain_cycles, e)
+                            .source(group)
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    // Returns the consumed cycles of the group, which are still known if the script exits with
+    // an error code or runs out of cycles.
+    fn run_group_to_end(
+        &self,
+        group: &ScriptGroup,
+        max_cycles: Cycle,
+    ) -> (Option<Cycle>, Option<ScriptError>) {
+        if group.script.code_hash() == TYPE_ID_CODE_HASH.pack()
+            && Into::<u8>::into(group.script.hash_type()) == Into::<u8>::into(ScriptHashType::Type)
+        {
+            return match self.verify_script_group(group, max_cycles) {
+                Ok(cycles) => (Some(cycles), None),
+                Err(e) => (None, Some(e)),
+            };
+        }
+        match self.detailed_run(group, max_cycles) {
+            Ok((0, cycles)) => (Some(cycles), None),
+            Ok((code, cycles)) => (
+                Some(cycles),
+                Some(ScriptError::validation_failure(&group.script, code)),
+            ),
+            Err(e @ ScriptError::ExceededMaximumCycles(_)) => (Some(max_cycles), Some(e)),
+            Err(e) => (None, Some(e)),
+        }
+    }
+
     /// Performing a resumable verification on the transaction scripts.
     ///
     /// ## Params
@@ -689,6 +858,9 @@ where
     /// Performing a resumable verification on the transaction scripts with signal channel,
     /// if `Suspend` comes from `command_rx`, the process will be hang up until `Resume` comes,
     /// otherwise, it will return until the verification is completed.
+    ///
+    /// Only available with the `async-verify` feature.
+    #[cfg(feature = "async-verify")]
     pub async fn resumable_verify_with_signal(
         &self,
         limit_cycles: Cycle,
@@ -705,8 +877,9 @@ where
             })?;
 
             match self
-                .verify_group_with_signal(group, remain_cycles, command_rx)
+                .verify_group_with_signal(group, self.group_cycles_limit(remain_cycles), command_rx)
                 .await
+                .map_err(|e| self.group_cycles_error(group, remain_cycles, e))
             {
                 Ok(used_cycles) => {
                     cycles = wrapping_cycles_add(cycles, used_cycles, group)?;
@@ -975,6 +1148,33 @@ where
             self.run(group, max_cycles)
         }
     }
+    // Runs the group like `verify_script_group`, and returns the cycles consumed by each VM.
+    fn run_group_with_vm_cycles(
+        &self,
+        group: &ScriptGroup,
+        max_cycles: Cycle,
+    ) -> Result<(Cycle, Vec<VmCycles>), ScriptError> {
+        if group.script.code_hash() == TYPE_ID_CODE_HASH.pack()
+            && Into::<u8>::into(group.script.hash_type()) == Into::<u8>::into(ScriptHashType::Type)
+        {
+            return self
+                .verify_script_group(group, max_cycles)
+                .map(|cycles| (cycles, Vec::new()));
+        }
+        let mut scheduler = self.create_scheduler(group)?;
+        let (code, cycles) = scheduler
+            .run(RunMode::LimitCycles(max_cycles))
+            .map_err(|error| match error {
+                VMInternalError::CyclesExceeded => ScriptError::ExceededMaximumCycles(max_cycles),
+                _ => ScriptError::VMInternalError(error),
+            })?;
+        if code == 0 {
+            Ok((cycles, scheduler.vm_cycles()))
+        } else {
+            Err(ScriptError::validation_failure(&group.script, code))
+        }
+    }
+
     /// Returns all script groups.
     pub fn groups(&self) -> impl Iterator<Item = (&'_ Byte32, &'_ ScriptGroup)> {
         self.lock_groups.iter().chain(self.type_groups.iter())
@@ -1072,6 +1272,7 @@ where
         }
     }
 
+    #[cfg(feature = "async-verify")]
     async fn verify_group_with_signal(
         &self,
         group: &ScriptGroup,
@@ -1163,6 +1364,7 @@ where
         }
     }
 
+    #[cfg(feature = "async-verify")]
     async fn chunk_run_with_signal(
         &self,
         script_group: &ScriptGroup,
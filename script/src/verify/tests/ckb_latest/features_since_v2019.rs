@@ -52,6 +52,67 @@ fn check_always_success_hash() {
     assert_eq!(result.ok(), Some(ALWAYS_SUCCESS_SCRIPT_CYCLE));
 }
 
+#[test]
+fn check_max_group_cycles() {
+    let script_version = SCRIPT_VERSION;
+
+    let (always_success_cell, always_success_cell_data, always_success_script) =
+        always_success_cell();
+    let output = CellOutputBuilder::default()
+        .capacity(capacity_bytes!(100).pack())
+        .lock(always_success_script.clone())
+        .build();
+    let input = CellInput::new(OutPoint::null(), 0);
+
+    let transaction = TransactionBuilder::default().input(input).build();
+    let dummy_cell = create_dummy_cell(output);
+
+    let always_success_cell = CellMetaBuilder::from_cell_output(
+        always_success_cell.clone(),
+        always_success_cell_data.to_owned(),
+    )
+    .transaction_info(default_transaction_info())
+    .build();
+
+    let rtx = ResolvedTransaction {
+        transaction,
+        resolved_cell_deps: vec![always_success_cell],
+        resolved_inputs: vec![dummy_cell],
+        resolved_dep_groups: vec![],
+    };
+
+    let verifier = TransactionScriptsVerifierWithEnv::new();
+    let verify_with_group_limit = |max_group_cycles, max_cycles| {
+        verifier.verify_map(script_version, &rtx, |mut verifier| {
+            verifier.set_max_group_cycles(Some(max_group_cycles));
+            verifier.verify(max_cycles)
+        })
+    };
+
+    let result = verify_with_group_limit(ALWAYS_SUCCESS_SCRIPT_CYCLE, u64::MAX);
+    assert_eq!(result.ok(), Some(ALWAYS_SUCCESS_SCRIPT_CYCLE));
+
+    // the group limit is hit before the tx limit
+    let max_group_cycles = ALWAYS_SUCCESS_SCRIPT_CYCLE - 1;
+    let result = verify_with_group_limit(max_group_cycles, u64::MAX);
+    assert_error_eq!(
+        result.unwrap_err(),
+        ScriptError::ExceededMaximumGroupCycles(
+            always_success_script.calc_script_hash(),
+            max_group_cycles
+        )
+        .input_lock_script(0),
+    );
+
+    // the tx limit is hit first
+    let max_cycles = ALWAYS_SUCCESS_SCRIPT_CYCLE - 2;
+    let result = verify_with_group_limit(max_group_cycles, max_cycles);
+    assert_error_eq!(
+        result.unwrap_err(),
+        ScriptError::ExceededMaximumCycles(max_cycles).input_lock_script(0),
+    );
+}
+
 #[test]
 fn check_signature() {
     let script_version = SCRIPT_VERSION;
@@ -460,6 +521,118 @@ fn check_invalid_output_contract() {
     );
 }
 
+#[test]
+fn check_exhaustive_verification() {
+    let script_version = SCRIPT_VERSION;
+
+    let mut file = open_cell_always_failure();
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).unwrap();
+
+    let (always_success_cell, always_success_cell_data, always_success_script) =
+        always_success_cell();
+    let dummy_cell = create_dummy_cell(
+        CellOutputBuilder::default()
+            .capacity(capacity_bytes!(100).pack())
+            .lock(always_success_script.clone())
+            .build(),
+    );
+    let always_success_cell = CellMetaBuilder::from_cell_output(
+        always_success_cell.to_owned(),
+        always_success_cell_data.to_owned(),
+    )
+    .transaction_info(default_transaction_info())
+    .build();
+
+    // two failing type script groups told apart by the args
+    let failure_script = |arg: u8| {
+        Script::new_builder()
+            .args(Bytes::from(vec![arg]).pack())
+            .code_hash(blake2b_256(&buffer).pack())
+            .hash_type(ScriptHashType::Data.into())
+            .build()
+    };
+    let (script1, script2) = (failure_script(1), failure_script(2));
+    let dep_out_point = OutPoint::new(h256!("0x123").pack(), 8);
+    let dep_cell = {
+        let dep_cell_data = Bytes::from(buffer.clone());
+        let output = CellOutputBuilder::default()
+            .capacity(Capacity::bytes(dep_cell_data.len()).unwrap().pack())
+            .build();
+        CellMetaBuilder::from_cell_output(output, dep_cell_data)
+            .transaction_info(default_transaction_info())
+            .build()
+    };
+
+    let transaction = TransactionBuilder::default()
+        .input(CellInput::new(OutPoint::null(), 0))
+        .output(
+            CellOutputBuilder::default()
+                .type_(Some(script1.clone()).pack())
+                .build(),
+        )
+        .output(
+            CellOutputBuilder::default()
+                .type_(Some(script2.clone()).pack())
+                .build(),
+        )
+        .outputs_data(vec![Bytes::new().pack(); 2])
+        .cell_dep(CellDep::new_builder().out_point(dep_out_point).build())
+        .build();
+
+    let rtx = ResolvedTransaction {
+        transaction,
+        resolved_cell_deps: vec![dep_cell, always_success_cell],
+        resolved_inputs: vec![dummy_cell],
+        resolved_dep_groups: vec![],
+    };
+
+    let verifier = TransactionScriptsVerifierWithEnv::new();
+    let results = verifier.verify_map(script_version, &rtx, |verifier| {
+        verifier.verify_exhaustive(u64::MAX)
+    });
+    assert_eq!(results.len(), 3);
+
+    let lock = &results[0];
+    assert_eq!(lock.group_type, ScriptGroupType::Lock);
+    assert_eq!(lock.script_hash, always_success_script.calc_script_hash());
+    assert_eq!(lock.cycles, Some(ALWAYS_SUCCESS_SCRIPT_CYCLE));
+    assert!(lock.is_ok());
+
+    let output_index = |result: &ScriptGroupResult| {
+        if result.script_hash == script1.calc_script_hash() {
+            0
+        } else {
+            1
+        }
+    };
+    // both failures are reported with the cycles consumed before the scripts exit
+    for result in &results[1..] {
+        let index = output_index(result);
+        let script = if index == 0 { &script1 } else { &script2 };
+        assert_eq!(result.group_type, ScriptGroupType::Type);
+        assert_eq!(result.script_hash, script.calc_script_hash());
+        assert!(result.cycles.is_some());
+        assert_error_eq!(
+            result.error.clone().unwrap(),
+            ScriptError::validation_failure(script, -1).output_type_script(index),
+        );
+    }
+
+    // the groups after the limit is reached run out of cycles
+    let results = verifier.verify_map(script_version, &rtx, |verifier| {
+        verifier.verify_exhaustive(ALWAYS_SUCCESS_SCRIPT_CYCLE)
+    });
+    assert!(results[0].is_ok());
+    for result in &results[1..] {
+        assert_eq!(result.cycles, Some(0));
+        assert_error_eq!(
+            result.error.clone().unwrap(),
+            ScriptError::ExceededMaximumCycles(0).output_type_script(output_index(result)),
+        );
+    }
+}
+
 #[test]
 fn check_same_lock_and_type_script_are_executed_twice() {
     let script_version = SCRIPT_VERSION;
@@ -1,5 +1,5 @@
 use super::SCRIPT_VERSION;
-use crate::scheduler::{MAX_FDS, MAX_VMS_COUNT};
+use crate::scheduler::{MAX_FDS, MAX_VMS_COUNT, ROOT_VM_ID};
 use crate::syscalls::SOURCE_GROUP_FLAG;
 use crate::verify::{tests::utils::*, *};
 use ckb_types::{
@@ -173,6 +173,67 @@ fn check_spawn_strcat() {
     assert_eq!(result.is_ok(), script_version >= ScriptVersion::V2);
 }
 
+#[test]
+fn check_spawn_vm_cycles() {
+    let script_version = SCRIPT_VERSION;
+
+    let (spawn_caller_cell, spawn_caller_data_hash) =
+        load_cell_from_path("testdata/spawn_caller_strcat");
+    let (spawn_callee_cell, _spawn_callee_data_hash) =
+        load_cell_from_path("testdata/spawn_callee_strcat");
+
+    let spawn_caller_script = Script::new_builder()
+        .hash_type(script_version.data_hash_type().into())
+        .code_hash(spawn_caller_data_hash)
+        .build();
+    let output = CellOutputBuilder::default()
+        .capacity(capacity_bytes!(100).pack())
+        .lock(spawn_caller_script.clone())
+        .build();
+    let input = CellInput::new(OutPoint::null(), 0);
+
+    let transaction = TransactionBuilder::default().input(input).build();
+    let dummy_cell = create_dummy_cell(output);
+
+    let rtx = ResolvedTransaction {
+        transaction,
+        resolved_cell_deps: vec![spawn_caller_cell, spawn_callee_cell],
+        resolved_inputs: vec![dummy_cell],
+        resolved_dep_groups: vec![],
+    };
+    let verifier = TransactionScriptsVerifierWithEnv::new();
+    let result = verifier.verify_map(script_version, &rtx, |verifier| {
+        verifier.verify_with_vm_cycles(u64::MAX)
+    });
+    if script_version < ScriptVersion::V2 {
+        assert!(result.is_err());
+        return;
+    }
+
+    let (cycles, groups) = result.unwrap();
+    assert_eq!(
+        cycles,
+        verifier.verify_without_limit(script_version, &rtx).unwrap()
+    );
+    assert_eq!(groups.len(), 1);
+    let group = &groups[0];
+    assert_eq!(group.group_type, ScriptGroupType::Lock);
+    assert_eq!(group.script_hash, spawn_caller_script.calc_script_hash());
+    assert_eq!(group.cycles, cycles);
+
+    // the caller spawns the callee
+    assert!(group.vms.len() >= 2);
+    assert_eq!(group.vms[0].id, ROOT_VM_ID);
+    assert_eq!(group.vms[0].parent_id, None);
+    for vm in &group.vms[1..] {
+        let parent_id = vm.parent_id.unwrap();
+        assert!(group.vms.iter().any(|parent| parent.id == parent_id));
+        assert!(vm.cycles > 0);
+    }
+    // all the cycles are charged to the vms
+    assert_eq!(group.vms.iter().map(|vm| vm.cycles).sum::<Cycle>(), cycles);
+}
+
 #[test]
 fn check_spawn_out_of_cycles() {
     let script_version = SCRIPT_VERSION;
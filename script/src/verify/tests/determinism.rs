@@ -0,0 +1,153 @@
+//! Determinism self-test of the CKB-VM backends.
+//!
+//! The ASM machine and the Rust interpreter are selected at build time, so the same corpus is run
+//! by two builds and the outcomes are compared, see `make script-determinism`:
+//!
+//! - `CKB_SCRIPT_DETERMINISM_RECORD=<path>` writes the outcomes of this build to the file.
+//! - `CKB_SCRIPT_DETERMINISM_BASELINE=<path>` compares the outcomes of this build with the file.
+//!
+//! Any difference in the results or the consumed cycles is a consensus bug of a backend.
+use ckb_types::{
+    core::{capacity_bytes, Capacity, TransactionBuilder},
+    packed::{CellInput, CellOutputBuilder, OutPoint, Script},
+};
+use std::fmt::Write as _;
+use std::{env, fs};
+
+use crate::verify::{tests::utils::*, *};
+use crate::VM_BACKEND;
+
+const RECORD_ENV: &str = "CKB_SCRIPT_DETERMINISM_RECORD";
+const BASELINE_ENV: &str = "CKB_SCRIPT_DETERMINISM_BASELINE";
+
+const MAX_CYCLES: Cycle = 70_000_000;
+
+// The scripts which run without any other cells, and the args passed to them.
+const CORPUS: &[(&str, &[u8])] = &[
+    ("testdata/always_success", &[]),
+    ("testdata/always_failure", &[]),
+    ("testdata/cadd_hint_lock", &[]),
+    ("testdata/current_cycles", &[]),
+    ("testdata/infinite_loop", &[]),
+    ("testdata/vm_version", &[]),
+    ("testdata/vm_version_2", &[]),
+    ("testdata/spawn_cases", &[1]),
+    ("testdata/spawn_cases", &[2]),
+    ("testdata/spawn_cases", &[3]),
+    ("testdata/spawn_recursive", &[]),
+    ("testdata/spawn_cycles", &[]),
+];
+
+const SCRIPT_VERSIONS: &[ScriptVersion] =
+    &[ScriptVersion::V0, ScriptVersion::V1, ScriptVersion::V2];
+
+fn corpus_rtx(bin_path: &str, args: &[u8], script_version: ScriptVersion) -> ResolvedTransaction {
+    let (cell, data_hash) = load_cell_from_path(bin_path);
+    let script = Script::new_builder()
+        .hash_type(script_version.data_hash_type().into())
+        .code_hash(data_hash)
+        .args(Bytes::copy_from_slice(args).pack())
+        .build();
+    let output = CellOutputBuilder::default()
+        .capacity(capacity_bytes!(100).pack())
+        .lock(script)
+        .build();
+    let input = CellInput::new(OutPoint::null(), 0);
+
+    let transaction = TransactionBuilder::default().input(input).build();
+    let dummy_cell = create_dummy_cell(output);
+
+    ResolvedTransaction {
+        transaction,
+        resolved_cell_deps: vec![cell],
+        resolved_inputs: vec![dummy_cell],
+        resolved_dep_groups: vec![],
+    }
+}
+
+fn outcome(result: Result<Cycle, Error>) -> String {
+    match result {
+        Ok(cycles) => format!("ok {cycles}"),
+        Err(err) => format!("err {err}"),
+    }
+}
+
+// Runs the corpus, one line per case.
+fn run_corpus() -> Vec<String> {
+    let verifier = TransactionScriptsVerifierWithEnv::new();
+    let mut cases = Vec::new();
+    for script_version in SCRIPT_VERSIONS {
+        let mut rtxs: Vec<(String, ResolvedTransaction)> = CORPUS
+            .iter()
+            .map(|(bin_path, args)| {
+                let name = format!("{bin_path}{args:?}");
+                (name, corpus_rtx(bin_path, args, *script_version))
+            })
+            .collect();
+        rtxs.push(("2_in_2_out".to_string(), random_2_in_2_out_rtx()));
+
+        for (name, rtx) in rtxs {
+            let result = verifier.verify_without_pause(*script_version, &rtx, MAX_CYCLES);
+            // the cycles must not depend on how the verification is suspended either
+            if let Ok(cycles) = result {
+                let (chunked_cycles, _) = verifier
+                    .verify_until_completed(*script_version, &rtx)
+                    .expect("resumable verify");
+                assert_eq!(
+                    cycles, chunked_cycles,
+                    "{name} on {script_version:?} consumes different cycles when resumed"
+                );
+            }
+            cases.push(format!("{script_version:?} {name}: {}", outcome(result)));
+        }
+    }
+    cases
+}
+
+#[test]
+fn check_vm_backend_determinism() {
+    let cases = run_corpus();
+    assert_eq!(
+        cases,
+        run_corpus(),
+        "the outcomes of a backend are not stable"
+    );
+
+    if let Ok(path) = env::var(RECORD_ENV) {
+        let mut content = format!("# backend: {VM_BACKEND}\n");
+        for case in &cases {
+            writeln!(content, "{case}").unwrap();
+        }
+        fs::write(&path, content).unwrap_or_else(|err| panic!("write {path}: {err}"));
+    }
+
+    if let Ok(path) = env::var(BASELINE_ENV) {
+        let content = fs::read_to_string(&path).unwrap_or_else(|err| panic!("read {path}: {err}"));
+        let baseline_backend = content
+            .lines()
+            .find_map(|line| line.strip_prefix("# backend: "))
+            .unwrap_or("unknown");
+        let baseline: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        assert_eq!(
+            baseline.len(),
+            cases.len(),
+            "the baseline is recorded from another corpus"
+        );
+        let diverged: Vec<String> = baseline
+            .iter()
+            .zip(cases.iter())
+            .filter(|(expected, actual)| expected != actual)
+            .map(|(expected, actual)| {
+                format!("  {baseline_backend}: {expected}\n  {VM_BACKEND}: {actual}")
+            })
+            .collect();
+        assert!(
+            diverged.is_empty(),
+            "the {VM_BACKEND} backend diverges from the {baseline_backend} backend:\n{}",
+            diverged.join("\n")
+        );
+    }
+}
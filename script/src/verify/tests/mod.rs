@@ -8,3 +8,4 @@ mod ckb_2019;
 mod ckb_2021;
 #[path = "ckb_latest/mod.rs"]
 mod ckb_2023;
+mod determinism;
@@ -0,0 +1,101 @@
+//! Sliding window statistics of the stale blocks and the uncles, which reflect how often the
+//! miners are working on competing blocks.
+use ckb_store::ChainStore;
+use ckb_types::{
+    core::{BlockNumber, HeaderView},
+    packed::Byte32,
+};
+use std::collections::{BTreeMap, HashSet};
+
+/// The stale blocks and uncles in a window of the most recent main chain blocks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowStats {
+    /// The configured window size in blocks.
+    pub window: BlockNumber,
+    /// The main chain blocks in the window, which is less than `window` near the genesis.
+    pub blocks: u64,
+    /// The uncles included by the main chain blocks in the window.
+    pub uncles: u64,
+    /// The verified blocks in the window which are not in the main chain, also known as orphan
+    /// blocks.
+    ///
+    /// Only the blocks observed since the node started are counted.
+    pub stale_blocks: u64,
+}
+
+/// Tracks the verified blocks which are not in the main chain.
+///
+/// A block becomes stale when it is verified without becoming the tip, or when it is detached by
+/// a reorg. It is not stale any more when a reorg attaches it again.
+pub struct StaleBlockTracker {
+    windows: Vec<BlockNumber>,
+    stale: BTreeMap<BlockNumber, HashSet<Byte32>>,
+}
+
+impl StaleBlockTracker {
+    /// Creates a tracker for the windows, the empty windows are ignored.
+    pub fn new(mut windows: Vec<BlockNumber>) -> Self {
+        windows.retain(|window| *window > 0);
+        windows.sort_unstable();
+        windows.dedup();
+        StaleBlockTracker {
+            windows,
+            stale: BTreeMap::new(),
+        }
+    }
+
+    /// The windows in ascending order.
+    pub fn windows(&self) -> &[BlockNumber] {
+        &self.windows
+    }
+
+    /// Records a block which is not in the main chain.
+    pub fn record_stale(&mut self, number: BlockNumber, hash: Byte32) {
+        self.stale.entry(number).or_default().insert(hash);
+    }
+
+    /// Records a block which is attached to the main chain.
+    pub fn record_main(&mut self, number: BlockNumber, hash: &Byte32) {
+        if let Some(hashes) = self.stale.get_mut(&number) {
+            hashes.remove(hash);
+            if hashes.is_empty() {
+                self.stale.remove(&number);
+            }
+        }
+    }
+
+    /// Forgets the blocks which are out of the largest window.
+    pub fn prune(&mut self, tip_number: BlockNumber) {
+        let max_window = self.windows.last().copied().unwrap_or(0);
+        let start = tip_number.saturating_sub(max_window) + 1;
+        self.stale = self.stale.split_off(&start);
+    }
+
+    /// Returns the statistics of every window ending at the tip.
+    pub fn stats<S: ChainStore>(&self, store: &S, tip: &HeaderView) -> Vec<WindowStats> {
+        let total_uncles_count = |number: BlockNumber| {
+            store
+                .get_block_hash(number)
+                .and_then(|hash| store.get_block_ext(&hash))
+                .map(|ext| ext.total_uncles_count)
+                .unwrap_or(0)
+        };
+        let tip_uncles_count = total_uncles_count(tip.number());
+        self.windows
+            .iter()
+            .map(|window| {
+                let start = tip.number().saturating_sub(*window);
+                WindowStats {
+                    window: *window,
+                    blocks: tip.number() - start,
+                    uncles: tip_uncles_count.saturating_sub(total_uncles_count(start)),
+                    stale_blocks: self
+                        .stale
+                        .range(start + 1..)
+                        .map(|(_, hashes)| hashes.len() as u64)
+                        .sum(),
+                }
+            })
+            .collect()
+    }
+}
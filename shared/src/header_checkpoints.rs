@@ -0,0 +1,147 @@
+//! The header checkpoints signed by the network secret key of the node.
+//!
+//! A light client starting from a checkpoint only has to sync the headers after it, the
+//! signature tells which node vouches for the checkpoints, so the light client infrastructure
+//! can decide whether to trust them by the node ID.
+use crate::Snapshot;
+use ckb_app_config::NetworkConfig;
+use ckb_crypto::secp::{Message, Privkey, Signature};
+use ckb_network::PublicKey;
+use ckb_store::ChainStore;
+use ckb_types::{
+    bytes::Bytes,
+    core::{BlockNumber, HeaderCheckpoint, SignedHeaderCheckpoints},
+    packed::Byte32,
+};
+use std::fs;
+use std::io::{Error, ErrorKind};
+
+/// The maximum number of the checkpoints signed at once.
+pub const MAX_HEADER_CHECKPOINTS: usize = 100;
+
+/// Collects the most recent `count` checkpoints of the main chain, one every `interval` blocks.
+///
+/// The checkpoints are at the block numbers which are multiples of `interval`, in ascending
+/// order. The genesis block is included when the chain is not long enough.
+pub fn collect_header_checkpoints(
+    snapshot: &Snapshot,
+    interval: BlockNumber,
+    count: usize,
+) -> Vec<HeaderCheckpoint> {
+    let interval = interval.max(1);
+    let last = snapshot.tip_number() / interval * interval;
+    let mut checkpoints: Vec<HeaderCheckpoint> = (0..count as u64)
+        .map_while(|index| last.checked_sub(index * interval))
+        .filter_map(|number| {
+            let hash = snapshot.get_block_hash(number)?;
+            let header = snapshot.get_block_header(&hash)?;
+            let ext = snapshot.get_block_ext(&hash)?;
+            Some(HeaderCheckpoint {
+                number,
+                hash,
+                total_difficulty: ext.total_difficulty,
+                epoch: header.epoch(),
+            })
+        })
+        .collect();
+    checkpoints.reverse();
+    checkpoints
+}
+
+/// Verifies that the checkpoints of the chain `genesis_hash` are signed by the owner of
+/// `public_key`, and that `node_id` is the peer ID of it.
+pub fn verify_header_checkpoints(signed: &SignedHeaderCheckpoints) -> Result<(), String> {
+    let signature = Signature::from_slice(&signed.signature)
+        .map_err(|err| format!("invalid signature: {err}"))?;
+    let message = Message::from(SignedHeaderCheckpoints::message(
+        &signed.genesis_hash,
+        &signed.checkpoints,
+    ));
+    let pubkey = signature
+        .recover(&message)
+        .map_err(|err| format!("recover the public key error: {err}"))?;
+    if pubkey.serialize() != signed.public_key.as_ref() {
+        return Err("the signature is not signed by the public key".to_owned());
+    }
+    let node_id = PublicKey::from_raw_key(signed.public_key.to_vec())
+        .peer_id()
+        .to_base58();
+    if node_id != signed.node_id {
+        return Err(format!(
+            "the node ID {} does not match the public key of the node {node_id}",
+            signed.node_id
+        ));
+    }
+    Ok(())
+}
+
+/// Signs the header checkpoints with the network secret key.
+pub struct HeaderCheckpointSigner {
+    privkey: Privkey,
+    public_key: Bytes,
+    node_id: String,
+}
+
+impl HeaderCheckpointSigner {
+    /// Loads the network secret key, it is generated if the key file does not exist.
+    pub fn from_network_config(config: &NetworkConfig) -> Result<Self, Error> {
+        let node_id = config.fetch_private_key()?.peer_id().to_base58();
+        let raw = fs::read(config.secret_key_path())?;
+        if raw.len() != 32 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "invalid secret key data",
+            ));
+        }
+        let privkey = Privkey::from_slice(&raw);
+        let public_key: Bytes = privkey
+            .pubkey()
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?
+            .serialize()
+            .into();
+        // the signatures are verified against the node ID, which the network derives from the
+        // same key
+        let signer_id = PublicKey::from_raw_key(public_key.to_vec())
+            .peer_id()
+            .to_base58();
+        if signer_id != node_id {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("the signing key of {signer_id} is not the network key of {node_id}"),
+            ));
+        }
+        Ok(HeaderCheckpointSigner {
+            privkey,
+            public_key,
+            node_id,
+        })
+    }
+
+    /// The peer ID of the node.
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Signs the checkpoints of the chain `genesis_hash`.
+    pub fn sign(
+        &self,
+        genesis_hash: Byte32,
+        checkpoints: Vec<HeaderCheckpoint>,
+    ) -> SignedHeaderCheckpoints {
+        let message = Message::from(SignedHeaderCheckpoints::message(
+            &genesis_hash,
+            &checkpoints,
+        ));
+        let signature = self
+            .privkey
+            .sign_recoverable(&message)
+            .expect("sign with a valid secret key");
+        SignedHeaderCheckpoints {
+            genesis_hash,
+            node_id: self.node_id.clone(),
+            public_key: self.public_key.clone(),
+            checkpoints,
+            signature: signature.serialize().into(),
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 // num_cpus is used in proc_macro
 pub mod chain_services_builder;
+pub mod chain_stats;
+pub mod header_checkpoints;
 pub mod shared;
 pub mod shared_builder;
 
@@ -1,12 +1,14 @@
 //! Provide Shared
 #![allow(missing_docs)]
 use crate::block_status::BlockStatus;
+use crate::chain_stats::{StaleBlockTracker, WindowStats};
 use crate::{HeaderMap, Snapshot, SnapshotMgr};
 use arc_swap::{ArcSwap, Guard};
 use ckb_async_runtime::Handle;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_constant::store::TX_INDEX_UPPER_BOUND;
 use ckb_constant::sync::MAX_TIP_AGE;
+use ckb_dao::DaoCalculator;
 use ckb_db::{Direction, IteratorMode};
 use ckb_db_schema::{COLUMN_BLOCK_BODY, COLUMN_NUMBER_HASH};
 use ckb_error::{AnyError, Error};
@@ -14,11 +16,17 @@ use ckb_logger::debug;
 use ckb_notify::NotifyController;
 use ckb_proposal_table::ProposalView;
 use ckb_stop_handler::{new_crossbeam_exit_rx, register_thread};
-use ckb_store::{ChainDB, ChainStore};
+use ckb_store::integrity::{
+    check_main_chain, check_main_chain_block, repair_main_chain, IntegrityIssue, IntegrityIssueKind,
+};
+use ckb_store::{ChainDB, ChainStore, StoreTransaction};
 use ckb_systemtime::unix_time_as_millis;
 use ckb_tx_pool::{BlockTemplate, TokioRwLock, TxPoolController};
 use ckb_types::{
-    core::{BlockNumber, EpochExt, EpochNumber, HeaderView, Version},
+    core::{
+        cell::{CellMetaBuilder, ResolvedTransaction},
+        BlockNumber, BlockView, Capacity, EpochExt, EpochNumber, HeaderView, Version,
+    },
     packed::{self, Byte32},
     prelude::*,
     H256, U256,
@@ -36,6 +44,9 @@ use std::time::Duration;
 const FREEZER_INTERVAL: Duration = Duration::from_secs(60);
 const THRESHOLD_EPOCH: EpochNumber = 2;
 const MAX_FREEZE_LIMIT: BlockNumber = 30_000;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_PRUNE_LIMIT: BlockNumber = 30_000;
+const PRUNE_BATCH_SIZE: BlockNumber = 1_000;
 
 pub const SHRINK_THRESHOLD: usize = 300;
 
@@ -61,13 +72,19 @@ pub struct Shared {
     pub(crate) snapshot_mgr: Arc<SnapshotMgr>,
     pub(crate) async_handle: Handle,
     pub(crate) ibd_finished: Arc<AtomicBool>,
+    // set by the chain service while a deep reorganization settles
+    pub(crate) reorg_settling: Arc<AtomicBool>,
 
     pub(crate) assume_valid_target: Arc<Mutex<Option<H256>>>,
     pub(crate) assume_valid_target_specified: Arc<Option<H256>>,
+    // the block number and the time when the full verification resumed from the assume valid mode
+    pub(crate) full_verification_resumed: Arc<Mutex<Option<(BlockNumber, u64)>>>,
 
     pub header_map: Arc<HeaderMap>,
     pub(crate) block_status_map: Arc<DashMap<Byte32, BlockStatus>>,
     pub(crate) unverified_tip: Arc<ArcSwap<crate::HeaderIndex>>,
+    pub(crate) missing_block_bodies: Arc<DashMap<Byte32, BlockNumber>>,
+    pub(crate) stale_blocks: Arc<Mutex<StaleBlockTracker>>,
 }
 
 impl Shared {
@@ -87,6 +104,7 @@ impl Shared {
         assume_valid_target_specified: Arc<Option<H256>>,
         header_map: Arc<HeaderMap>,
         block_status_map: Arc<DashMap<Byte32, BlockStatus>>,
+        chain_stats_windows: Vec<BlockNumber>,
     ) -> Shared {
         let header = store
             .get_tip_header()
@@ -106,11 +124,15 @@ impl Shared {
             snapshot_mgr,
             async_handle,
             ibd_finished,
+            reorg_settling: Arc::new(AtomicBool::new(false)),
             assume_valid_target,
             assume_valid_target_specified,
+            full_verification_resumed: Arc::new(Mutex::new(None)),
             header_map,
             block_status_map,
             unverified_tip,
+            missing_block_bodies: Arc::new(DashMap::new()),
+            stale_blocks: Arc::new(Mutex::new(StaleBlockTracker::new(chain_stats_windows))),
         }
     }
     /// Spawn freeze background thread that periodically checks and moves ancient data from the kv database into the freezer.
@@ -201,6 +223,111 @@ impl Shared {
         Ok(())
     }
 
+    /// Spawn a background thread that periodically prunes the proposal ids and the uncle proposals
+    /// of the main chain blocks older than `retention_epochs`.
+    ///
+    /// Pruning is skipped when the freezer is enabled, the frozen blocks keep their full data.
+    pub fn spawn_prune_uncles_and_proposals(&self, retention_epochs: Option<EpochNumber>) {
+        let retention_epochs = match retention_epochs {
+            Some(epochs) => epochs,
+            None => return,
+        };
+        if self.store.freezer().is_some() {
+            ckb_logger::warn!(
+                "store.uncles_and_proposals_retention_epochs is ignored since the freezer is enabled"
+            );
+            return;
+        }
+        let retention_epochs = if retention_epochs < THRESHOLD_EPOCH {
+            ckb_logger::warn!(
+                "store.uncles_and_proposals_retention_epochs {} is too small, use {} instead",
+                retention_epochs,
+                THRESHOLD_EPOCH
+            );
+            THRESHOLD_EPOCH
+        } else {
+            retention_epochs
+        };
+        ckb_logger::info!(
+            "Prune the uncles and proposals older than {} epochs",
+            retention_epochs
+        );
+
+        let signal_receiver = new_crossbeam_exit_rx();
+        let shared = self.clone();
+        let prune_jh = thread::Builder::new()
+            .spawn(move || loop {
+                match signal_receiver.recv_timeout(PRUNE_INTERVAL) {
+                    Err(_) => {
+                        if let Err(e) = shared.prune_uncles_and_proposals(retention_epochs) {
+                            ckb_logger::error!("Prune uncles and proposals error {}", e);
+                            break;
+                        }
+                    }
+                    Ok(_) => {
+                        ckb_logger::info!("Uncles and proposals pruner closing");
+                        break;
+                    }
+                }
+            })
+            .expect("Start uncles and proposals pruner failed");
+
+        register_thread("prune", prune_jh);
+    }
+
+    fn prune_uncles_and_proposals(&self, retention_epochs: EpochNumber) -> Result<(), Error> {
+        if self.is_initial_block_download() {
+            ckb_logger::trace!("is_initial_block_download prune skip");
+            return Ok(());
+        }
+
+        let snapshot = self.snapshot();
+        let current_epoch = snapshot.epoch_ext().number();
+        if current_epoch <= retention_epochs {
+            return Ok(());
+        }
+
+        let limit_block_hash = snapshot
+            .get_epoch_index(current_epoch + 1 - retention_epochs)
+            .and_then(|index| snapshot.get_epoch_ext(&index))
+            .expect("get_epoch_ext")
+            .last_block_hash_in_previous_epoch();
+        let limit = snapshot
+            .get_block_number(&limit_block_hash)
+            .expect("get_block_number");
+
+        // the genesis block has no uncles and proposals
+        let start = snapshot
+            .get_pruned_uncles_and_proposals_number()
+            .unwrap_or(0)
+            + 1;
+        let end = cmp::min(limit, start + MAX_PRUNE_LIMIT - 1);
+        if start > end {
+            return Ok(());
+        }
+
+        let mut batch_start = start;
+        while batch_start <= end {
+            let batch_end = cmp::min(end, batch_start + PRUNE_BATCH_SIZE - 1);
+            let db_txn = self.store.begin_transaction();
+            for number in batch_start..=batch_end {
+                if let Some(hash) = snapshot.get_block_hash(number) {
+                    db_txn.prune_block_uncles_and_proposals(&hash)?;
+                }
+            }
+            db_txn.set_pruned_uncles_and_proposals_number(batch_end)?;
+            db_txn.commit()?;
+            batch_start = batch_end + 1;
+        }
+
+        ckb_logger::debug!(
+            "Pruned the uncles and proposals of blocks {}-{}",
+            start,
+            end
+        );
+        Ok(())
+    }
+
     fn wipe_out_frozen_data(
         &self,
         snapshot: &Snapshot,
@@ -390,6 +517,17 @@ impl Shared {
         }
     }
 
+    /// Return whether a deep reorganization is settling, the tx relay and the tx verification are
+    /// paused until it settles
+    pub fn is_reorg_settling(&self) -> bool {
+        self.reorg_settling.load(Ordering::Acquire)
+    }
+
+    /// Marks whether a deep reorganization is settling, only the chain service should call this
+    pub fn set_reorg_settling(&self, settling: bool) {
+        self.reorg_settling.store(settling, Ordering::Release);
+    }
+
     /// Generate and return block_template
     pub fn get_block_template(
         &self,
@@ -418,6 +556,118 @@ impl Shared {
         self.header_map.remove(hash);
     }
 
+    /// Checks the main chain blocks from the tip down to `from` for the data and indexes left
+    /// inconsistent by an unclean shutdown, and returns the issues found.
+    ///
+    /// When `repair` is true, the indexes and block exts are repaired from the local data, and
+    /// the blocks whose bodies are missing are scheduled to be downloaded again from the peers.
+    pub fn check_chain_integrity(
+        &self,
+        from: BlockNumber,
+        repair: bool,
+    ) -> Result<Vec<IntegrityIssue>, Error> {
+        let issues = check_main_chain(&self.store, from);
+        if repair && !issues.is_empty() {
+            let txn = self.store.begin_transaction();
+            let unrepaired =
+                repair_main_chain(&txn, &issues, |block| self.block_txs_fees(&txn, block))?;
+            txn.commit()?;
+            self.refresh_snapshot();
+            for issue in unrepaired {
+                if issue.kind != IntegrityIssueKind::MissingBody {
+                    continue;
+                }
+                let is_genesis = issue.hash == self.genesis_hash();
+                self.missing_block_bodies.insert(issue.hash, issue.number);
+                // peers refuse to send the genesis block, it is rebuilt from the consensus
+                if is_genesis {
+                    self.repair_block_body(self.consensus.genesis_block())?;
+                }
+            }
+        }
+        Ok(issues)
+    }
+
+    // Recomputes the fees of the block transactions, except the cellbase, from the committed
+    // transactions whose outputs are spent by the block
+    fn block_txs_fees(&self, txn: &StoreTransaction, block: &BlockView) -> Option<Vec<Capacity>> {
+        let data_loader = txn.borrow_as_data_loader();
+        let calculator = DaoCalculator::new(&self.consensus, &data_loader);
+        block
+            .transactions()
+            .into_iter()
+            .skip(1)
+            .map(|tx| {
+                let resolved_inputs = tx
+                    .input_pts_iter()
+                    .map(|out_point| {
+                        let (input_tx, info) =
+                            txn.get_transaction_with_info(&out_point.tx_hash())?;
+                        let (output, data) =
+                            input_tx.output_with_data(out_point.index().unpack())?;
+                        Some(
+                            CellMetaBuilder::from_cell_output(output, data)
+                                .out_point(out_point)
+                                .transaction_info(info)
+                                .build(),
+                        )
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let rtx = ResolvedTransaction {
+                    transaction: tx,
+                    resolved_cell_deps: vec![],
+                    resolved_inputs,
+                    resolved_dep_groups: vec![],
+                };
+                calculator.transaction_fee(&rtx).ok()
+            })
+            .collect()
+    }
+
+    /// Returns the main chain blocks whose bodies are missing and should be downloaded again,
+    /// in ascending block number order.
+    pub fn missing_block_bodies(&self) -> Vec<Byte32> {
+        let mut blocks: Vec<(BlockNumber, Byte32)> = self
+            .missing_block_bodies
+            .iter()
+            .map(|kv_pair| (*kv_pair.value(), kv_pair.key().clone()))
+            .collect();
+        blocks.sort();
+        blocks.into_iter().map(|(_, hash)| hash).collect()
+    }
+
+    /// Returns true if the block is a main chain block whose body is being downloaded again.
+    pub fn is_missing_block_body(&self, block_hash: &Byte32) -> bool {
+        self.missing_block_bodies.contains_key(block_hash)
+    }
+
+    /// Stores the downloaded body of a block returned by [`Shared::missing_block_bodies`], then
+    /// repairs the indexes of the block.
+    ///
+    /// Returns false if the body does not match the commitments in the block header.
+    pub fn repair_block_body(&self, block: &BlockView) -> Result<bool, Error> {
+        if block.transactions_root() != block.calc_transactions_root()
+            || block.proposals_hash() != block.calc_proposals_hash()
+            || block.extra_hash() != block.calc_extra_hash().extra_hash()
+        {
+            return Ok(false);
+        }
+
+        let txn = self.store.begin_transaction();
+        txn.insert_block(block)?;
+        let issues = check_main_chain_block(&txn, &block.header());
+        repair_main_chain(&txn, &issues, |block| self.block_txs_fees(&txn, block))?;
+        txn.commit()?;
+        self.refresh_snapshot();
+        self.missing_block_bodies.remove(&block.hash());
+        debug!(
+            "repaired the body of block {} {}",
+            block.number(),
+            block.hash()
+        );
+        Ok(true)
+    }
+
     pub fn block_status_map(&self) -> &DashMap<Byte32, BlockStatus> {
         &self.block_status_map
     }
@@ -474,4 +724,74 @@ impl Shared {
     pub fn assume_valid_target_specified(&self) -> Arc<Option<H256>> {
         Arc::clone(&self.assume_valid_target_specified)
     }
+
+    /// Records that the assume valid target is reached or ignored, the blocks from `block_number`
+    /// on are fully verified.
+    pub fn record_full_verification_resumed(&self, block_number: BlockNumber) {
+        let mut resumed = self.full_verification_resumed.lock();
+        if resumed.is_none() {
+            *resumed = Some((block_number, unix_time_as_millis()));
+        }
+    }
+
+    /// Returns the block number and the time when the full verification resumed, `None` if the
+    /// assume valid target is still active or was reached before the node started.
+    pub fn full_verification_resumed(&self) -> Option<(BlockNumber, u64)> {
+        *self.full_verification_resumed.lock()
+    }
+
+    /// Records a verified block which does not become the tip.
+    pub fn record_stale_block(&self, header: &HeaderView) {
+        self.stale_blocks
+            .lock()
+            .record_stale(header.number(), header.hash());
+        self.update_chain_stats_metrics();
+    }
+
+    /// Records the blocks detached and attached by a new tip.
+    pub fn record_main_chain_switch<'a>(
+        &self,
+        detached: impl Iterator<Item = &'a BlockView>,
+        attached: impl Iterator<Item = &'a BlockView>,
+        tip_number: BlockNumber,
+    ) {
+        {
+            let mut stale_blocks = self.stale_blocks.lock();
+            for block in detached {
+                stale_blocks.record_stale(block.number(), block.hash());
+            }
+            for block in attached {
+                stale_blocks.record_main(block.number(), &block.hash());
+            }
+            stale_blocks.prune(tip_number);
+        }
+        self.update_chain_stats_metrics();
+    }
+
+    /// Returns the stale block and uncle statistics of the configured windows ending at the tip.
+    pub fn chain_stats(&self) -> Vec<WindowStats> {
+        let snapshot = self.snapshot();
+        self.stale_blocks
+            .lock()
+            .stats(snapshot.as_ref(), snapshot.tip_header())
+    }
+
+    fn update_chain_stats_metrics(&self) {
+        if let Some(metrics) = ckb_metrics::handle() {
+            for stats in self.chain_stats() {
+                if stats.blocks == 0 {
+                    continue;
+                }
+                let window = stats.window.to_string();
+                metrics
+                    .ckb_chain_stale_block_rate
+                    .with_label_values(&[&window])
+                    .set(stats.stale_blocks as f64 / stats.blocks as f64);
+                metrics
+                    .ckb_chain_uncle_rate
+                    .with_label_values(&[&window])
+                    .set(stats.uncles as f64 / stats.blocks as f64);
+            }
+        }
+    }
 }
@@ -3,28 +3,31 @@ use crate::ChainServicesBuilder;
 use crate::{HeaderMap, Shared};
 use ckb_app_config::{
     BlockAssemblerConfig, DBConfig, ExitCode, NotifyConfig, StoreConfig, SyncConfig, TxPoolConfig,
+    DEFAULT_CHAIN_STATS_WINDOWS,
 };
 use ckb_async_runtime::{new_background_runtime, Handle};
 use ckb_chain_spec::consensus::Consensus;
 use ckb_chain_spec::SpecError;
 use ckb_channel::Receiver;
-use ckb_db::RocksDB;
+use ckb_db::{resolve_column_paths, RocksDB};
 use ckb_db_schema::COLUMNS;
 use ckb_error::{Error, InternalErrorKind};
 use ckb_logger::{error, info};
-use ckb_migrate::migrate::Migrate;
+use ckb_migrate::migrate::{BackgroundMigrationOutcome, Migrate};
 use ckb_notify::{NotifyController, NotifyService};
 use ckb_proposal_table::ProposalTable;
 use ckb_proposal_table::ProposalView;
 use ckb_snapshot::{Snapshot, SnapshotMgr};
 use ckb_store::{ChainDB, ChainStore, Freezer};
 use ckb_tx_pool::{
-    service::TxVerificationResult, TokioRwLock, TxEntry, TxPool, TxPoolServiceBuilder,
+    service::TxVerificationResult, PoolEventListener, TokioRwLock, TxEntry, TxPool,
+    TxPoolServiceBuilder,
 };
 use ckb_types::core::hardfork::HardForks;
 use ckb_types::prelude::Pack;
 use ckb_types::{
-    core::service::PoolTransactionEntry, core::tx_pool::Reject, core::EpochExt, core::HeaderView,
+    core::service::PoolTransactionEntry, core::tx_pool::Reject, core::BlockNumber, core::EpochExt,
+    core::HeaderView,
 };
 use ckb_util::Mutex;
 use ckb_verification::cache::init_cache;
@@ -49,6 +52,9 @@ pub struct SharedBuilder {
     async_handle: Handle,
 
     header_map_tmp_dir: Option<PathBuf>,
+    chain_stats_windows: Option<Vec<BlockNumber>>,
+    // the outcome of the background migrations started when the database is opened
+    background_migration: Option<BackgroundMigrationOutcome>,
 }
 
 /// Open or create a rocksdb
@@ -58,7 +64,22 @@ pub fn open_or_create_db(
     config: &DBConfig,
     hardforks: HardForks,
 ) -> Result<RocksDB, ExitCode> {
-    let migrate = Migrate::new(&config.path, hardforks);
+    open_db(bin_name, root_dir, config, hardforks).map(|(db, _)| db)
+}
+
+// Opens or creates the database, and returns the outcome of the background migrations if they
+// are started.
+fn open_db(
+    bin_name: &str,
+    root_dir: &Path,
+    config: &DBConfig,
+    hardforks: HardForks,
+) -> Result<(RocksDB, Option<BackgroundMigrationOutcome>), ExitCode> {
+    let column_paths = resolve_column_paths(config).map_err(|err| {
+        eprintln!("Config error {err}");
+        ExitCode::Config
+    })?;
+    let migrate = Migrate::new(&config.path, hardforks).column_paths(column_paths);
 
     let read_only_db = migrate.open_read_only_db().map_err(|e| {
         eprintln!("Migration error {e}");
@@ -75,7 +96,7 @@ pub fn open_or_create_db(
                 );
                 Err(ExitCode::Failure)
             }
-            Ordering::Equal => Ok(RocksDB::open(config, COLUMNS)),
+            Ordering::Equal => Ok((RocksDB::open(config, COLUMNS), None)),
             Ordering::Less => {
                 let can_run_in_background = migrate.can_run_in_background(&db);
                 if migrate.require_expensive(&db, false) && !can_run_in_background {
@@ -92,11 +113,12 @@ pub fn open_or_create_db(
                 } else if can_run_in_background {
                     info!("process migrations in background ...");
                     let db = RocksDB::open(config, COLUMNS);
+                    let background_outcome = migrate.background_outcome();
                     migrate.migrate(db.clone(), true).map_err(|err| {
                         eprintln!("Run error: {err:?}");
                         ExitCode::Failure
                     })?;
-                    Ok(db)
+                    Ok((db, Some(background_outcome)))
                 } else {
                     info!("Processing fast migrations ...");
 
@@ -112,7 +134,7 @@ pub fn open_or_create_db(
                         })?;
                     }
 
-                    Ok(RocksDB::open(config, COLUMNS))
+                    Ok((RocksDB::open(config, COLUMNS), None))
                 }
             }
         }
@@ -122,7 +144,7 @@ pub fn open_or_create_db(
             eprintln!("Migrate init_db_version error {e}");
             ExitCode::Failure
         })?;
-        Ok(db)
+        Ok((db, None))
     }
 }
 
@@ -136,7 +158,7 @@ impl SharedBuilder {
         async_handle: Handle,
         consensus: Consensus,
     ) -> Result<SharedBuilder, ExitCode> {
-        let db = open_or_create_db(
+        let (db, background_migration) = open_db(
             bin_name,
             root_dir,
             db_config,
@@ -154,6 +176,8 @@ impl SharedBuilder {
             block_assembler_config: None,
             async_handle,
             header_map_tmp_dir: None,
+            chain_stats_windows: None,
+            background_migration,
         })
     }
 
@@ -202,6 +226,8 @@ impl SharedBuilder {
             async_handle: runtime.get_or_init(new_background_runtime).clone(),
 
             header_map_tmp_dir: None,
+            chain_stats_windows: None,
+            background_migration: None,
         })
     }
 }
@@ -243,6 +269,12 @@ impl SharedBuilder {
         self
     }
 
+    /// Specifies the windows, in blocks, of the stale block and uncle statistics
+    pub fn chain_stats_windows(mut self, windows: Vec<BlockNumber>) -> Self {
+        self.chain_stats_windows = Some(windows);
+        self
+    }
+
     /// TODO(doc): @quake
     pub fn block_assembler_config(mut self, config: Option<BlockAssemblerConfig>) -> Self {
         self.block_assembler_config = config;
@@ -365,12 +397,16 @@ impl SharedBuilder {
             notify_config,
             async_handle,
             header_map_tmp_dir,
+            chain_stats_windows,
+            background_migration,
         } = self;
 
         let tx_pool_config = tx_pool_config.unwrap_or_default();
         let notify_config = notify_config.unwrap_or_default();
         let store_config = store_config.unwrap_or_default();
         let sync_config = sync_config.unwrap_or_default();
+        let chain_stats_windows =
+            chain_stats_windows.unwrap_or_else(|| DEFAULT_CHAIN_STATS_WINDOWS.to_vec());
         let consensus = Arc::new(consensus);
 
         let header_map_memory_limit = sync_config.header_map.memory_limit.as_u64() as usize;
@@ -385,6 +421,12 @@ impl SharedBuilder {
         ));
 
         let notify_controller = start_notify_service(notify_config, async_handle.clone());
+        if let Some(background_migration) = background_migration {
+            let notify_migration = notify_controller.clone();
+            background_migration.on_finished(move |outcome| {
+                notify_migration.notify_migration_finished(outcome.clone())
+            });
+        }
 
         let store = build_store(db, store_config, ancient_path).map_err(|e| {
             eprintln!("build_store {e}");
@@ -438,6 +480,7 @@ impl SharedBuilder {
             assume_valid_target_specified,
             header_map,
             block_status_map,
+            chain_stats_windows,
         );
 
         let chain_services_builder = ChainServicesBuilder::new(shared.clone(), table);
@@ -473,6 +516,14 @@ impl SharedPackage {
         self.tx_pool_builder.take().expect("take tx_pool_builder")
     }
 
+    /// Registers a tx-pool event listener, must be called before the tx_pool_builder is taken.
+    pub fn register_pool_event_listener(&mut self, listener: Arc<dyn PoolEventListener>) {
+        self.tx_pool_builder
+            .as_mut()
+            .expect("tx_pool_builder has been taken")
+            .register_listener(listener);
+    }
+
     /// Takes the relay_tx_receiver out of the package, leaving a None in its place.
     pub fn take_relay_tx_receiver(&mut self) -> Receiver<TxVerificationResult> {
         self.relay_tx_receiver
@@ -505,6 +556,12 @@ fn register_tx_pool_callback(tx_pool_builder: &mut TxPoolServiceBuilder, notify:
     let tx_relay_sender = tx_pool_builder.tx_relay_sender();
     let create_notify_entry = |entry: &TxEntry| PoolTransactionEntry {
         transaction: entry.rtx.transaction.clone(),
+        input_cells: entry
+            .rtx
+            .resolved_inputs
+            .iter()
+            .map(|cell| cell.cell_output.clone())
+            .collect(),
         cycles: entry.cycles,
         size: entry.size,
         fee: entry.fee,
@@ -516,6 +573,13 @@ fn register_tx_pool_callback(tx_pool_builder: &mut TxPoolServiceBuilder, notify:
         notify_pending.notify_new_transaction(notify_tx_entry);
     }));
 
+    let notify_gap = notify.clone();
+    tx_pool_builder.register_gap(Box::new(move |entry: &TxEntry| {
+        // notify
+        let notify_tx_entry = create_notify_entry(entry);
+        notify_gap.notify_gap_transaction(notify_tx_entry);
+    }));
+
     let notify_proposed = notify.clone();
     tx_pool_builder.register_proposed(Box::new(move |entry: &TxEntry| {
         // notify
@@ -523,6 +587,13 @@ fn register_tx_pool_callback(tx_pool_builder: &mut TxPoolServiceBuilder, notify:
         notify_proposed.notify_proposed_transaction(notify_tx_entry);
     }));
 
+    let notify_committed = notify.clone();
+    tx_pool_builder.register_committed(Box::new(move |entry: &TxEntry| {
+        // notify
+        let notify_tx_entry = create_notify_entry(entry);
+        notify_committed.notify_committed_transaction(notify_tx_entry);
+    }));
+
     let notify_reject = notify;
     tx_pool_builder.register_reject(Box::new(
         move |tx_pool: &mut TxPool, entry: &TxEntry, reject: Reject| {
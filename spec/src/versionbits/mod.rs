@@ -4,6 +4,7 @@
 mod convert;
 
 use crate::consensus::Consensus;
+#[cfg(not(target_arch = "wasm32"))]
 use ckb_logger::error;
 use ckb_types::global::DATA_DIR;
 use ckb_types::{
@@ -140,15 +141,18 @@ pub struct Deployment {
 
 /// Signal state cache
 ///
-/// Persistent signal state cache, mmap-based cacache
+/// Persistent signal state cache, mmap-based cacache.
+/// On wasm32 there is no file system, the cache is always empty.
 #[derive(Clone, Debug)]
 pub struct Cache {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     path: PathBuf,
 }
 
 impl Cache {
     /// Reads the entire contents of a cache file synchronously into a bytes vector,
     /// looking the data up by key.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn get(&self, key: &Byte32) -> Option<ThresholdState> {
         match cacache::read_sync(&self.path, Self::encode_key(key)) {
             Ok(bytes) => Some(Self::decode_value(bytes)),
@@ -160,7 +164,14 @@ impl Cache {
         }
     }
 
+    /// Reads the cached state, always `None` on wasm32.
+    #[cfg(target_arch = "wasm32")]
+    pub fn get(&self, _key: &Byte32) -> Option<ThresholdState> {
+        None
+    }
+
     /// Writes data to the cache synchronously
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn insert(&self, key: &Byte32, value: ThresholdState) {
         if let Err(e) =
             cacache::write_sync(&self.path, Self::encode_key(key), Self::encode_value(value))
@@ -169,14 +180,21 @@ impl Cache {
         }
     }
 
+    /// Writes data to the cache, a no-op on wasm32.
+    #[cfg(target_arch = "wasm32")]
+    pub fn insert(&self, _key: &Byte32, _value: ThresholdState) {}
+
+    #[cfg(not(target_arch = "wasm32"))]
     fn decode_value(value: Vec<u8>) -> ThresholdState {
         ThresholdState::from_u8(value[0])
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     fn encode_key(key: &Byte32) -> String {
         format!("{}", key)
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     fn encode_value(value: ThresholdState) -> Vec<u8> {
         vec![value as u8]
     }
@@ -1,6 +1,13 @@
 use crate::{ChainStore, StoreTransaction};
 use ckb_error::Error;
-use ckb_types::{core::BlockView, packed, prelude::*};
+use ckb_types::{
+    core::{
+        cell::{CellMeta, CellProvider, CellStatus},
+        BlockView,
+    },
+    packed,
+    prelude::*,
+};
 use std::collections::HashMap;
 
 /**
@@ -149,3 +156,44 @@ pub fn detach_block_cell(txn: &StoreTransaction, block: &BlockView) -> Result<()
 
     Ok(())
 }
+
+/// A cell provider with the cells read from the store in a batch beforehand.
+///
+/// The cells not prefetched are read from the store one by one.
+pub struct PrefetchedCells<'a, CS> {
+    store: &'a CS,
+    cells: HashMap<packed::OutPoint, Option<CellMeta>>,
+}
+
+impl<'a, CS: ChainStore> PrefetchedCells<'a, CS> {
+    /// Reads the cells with the out points from the store in a batch.
+    pub fn new(store: &'a CS, out_points: &[packed::OutPoint]) -> Self {
+        let cells = out_points
+            .iter()
+            .cloned()
+            .zip(store.get_cells(out_points))
+            .collect();
+        PrefetchedCells { store, cells }
+    }
+}
+
+impl<'a, CS: ChainStore> CellProvider for PrefetchedCells<'a, CS> {
+    fn cell(&self, out_point: &packed::OutPoint, eager_load: bool) -> CellStatus {
+        let cell_meta = match self.cells.get(out_point) {
+            Some(cell_meta) => cell_meta.clone(),
+            None => self.store.get_cell(out_point),
+        };
+        match cell_meta {
+            Some(mut cell_meta) => {
+                if eager_load {
+                    if let Some((data, data_hash)) = self.store.get_cell_data(out_point) {
+                        cell_meta.mem_cell_data = Some(data);
+                        cell_meta.mem_cell_data_hash = Some(data_hash);
+                    }
+                }
+                CellStatus::live_cell(cell_meta)
+            }
+            None => CellStatus::Unknown,
+        }
+    }
+}
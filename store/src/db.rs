@@ -1,5 +1,6 @@
 use crate::cache::StoreCache;
 use crate::cell::attach_block_cell;
+use crate::epoch_stats::attach_block_epoch_stats;
 use crate::store::ChainStore;
 use crate::transaction::StoreTransaction;
 use crate::write_batch::StoreWriteBatch;
@@ -47,6 +48,12 @@ impl ChainStore for ChainDB {
     fn get_iter(&self, col: Col, mode: IteratorMode) -> DBIter {
         self.db.iter(col, mode).expect("db operation should be ok")
     }
+
+    fn multi_get<K: AsRef<[u8]>>(&self, col: Col, keys: &[K]) -> Vec<Option<DBPinnableSlice>> {
+        self.db
+            .multi_get_pinned(col, keys)
+            .expect("db operation should be ok")
+    }
 }
 
 impl VersionbitsIndexer for ChainDB {
@@ -193,6 +200,7 @@ impl ChainDB {
         db_txn.insert_block_epoch_index(&genesis_hash, &last_block_hash_in_previous_epoch)?;
         db_txn.insert_epoch_ext(&last_block_hash_in_previous_epoch, epoch)?;
         db_txn.attach_block(genesis)?;
+        attach_block_epoch_stats(&db_txn, genesis)?;
 
         let mut mmr = ChainRootMMR::new(0, &db_txn);
         mmr.push(genesis.digest())
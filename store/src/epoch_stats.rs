@@ -0,0 +1,53 @@
+use crate::{ChainStore, StoreTransaction};
+use ckb_error::Error;
+use ckb_types::core::{BlockExt, BlockView, Capacity, Cycle, EpochStats};
+
+fn block_fees_and_cycles(ext: Option<BlockExt>) -> (u64, Cycle) {
+    ext.map(|ext| {
+        let fees = ext
+            .txs_fees
+            .iter()
+            .map(|fee| fee.as_u64())
+            .fold(0u64, u64::saturating_add);
+        let cycles = ext
+            .cycles
+            .unwrap_or_default()
+            .into_iter()
+            .fold(0u64, u64::saturating_add);
+        (fees, cycles)
+    })
+    .unwrap_or_default()
+}
+
+// Apply the effects of this block on the stats of its epoch.
+//
+// The block ext must have been inserted before, so the fees and cycles are available.
+pub fn attach_block_epoch_stats(txn: &StoreTransaction, block: &BlockView) -> Result<(), Error> {
+    let epoch = block.epoch().number();
+    let (fees, cycles) = block_fees_and_cycles(txn.get_block_ext(&block.hash()));
+    let mut stats = txn.get_epoch_stats(epoch).unwrap_or_default();
+    stats.block_count += 1;
+    stats.tx_count = stats
+        .tx_count
+        .map(|count| count + (block.transactions().len() as u64).saturating_sub(1));
+    stats.total_fees = Capacity::shannons(stats.total_fees.as_u64().saturating_add(fees));
+    stats.total_cycles = stats.total_cycles.saturating_add(cycles);
+    txn.insert_epoch_stats(epoch, &stats)
+}
+
+// Undo the effects of this block on the stats of its epoch.
+pub fn detach_block_epoch_stats(txn: &StoreTransaction, block: &BlockView) -> Result<(), Error> {
+    let epoch = block.epoch().number();
+    let (fees, cycles) = block_fees_and_cycles(txn.get_block_ext(&block.hash()));
+    let mut stats: EpochStats = txn.get_epoch_stats(epoch).unwrap_or_default();
+    stats.block_count = stats.block_count.saturating_sub(1);
+    if stats.block_count == 0 {
+        return txn.delete_epoch_stats(epoch);
+    }
+    stats.tx_count = stats
+        .tx_count
+        .map(|count| count.saturating_sub((block.transactions().len() as u64).saturating_sub(1)));
+    stats.total_fees = Capacity::shannons(stats.total_fees.as_u64().saturating_sub(fees));
+    stats.total_cycles = stats.total_cycles.saturating_sub(cycles);
+    txn.insert_epoch_stats(epoch, &stats)
+}
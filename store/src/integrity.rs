@@ -0,0 +1,243 @@
+//! Checks the recent main chain blocks for the data and indexes left inconsistent by an
+//! unclean shutdown.
+use crate::cell::{attach_block_cell, detach_block_cell};
+use crate::{ChainStore, StoreTransaction};
+use ckb_db::iter::{Direction, IteratorMode};
+use ckb_db_schema::{
+    COLUMN_BLOCK_BODY, COLUMN_BLOCK_PROPOSAL_IDS, COLUMN_BLOCK_UNCLE, COLUMN_INDEX,
+};
+use ckb_error::Error;
+use ckb_types::{
+    core::{BlockExt, BlockNumber, BlockView, Capacity, HeaderView},
+    packed::{self, Byte32},
+    prelude::*,
+};
+
+/// The kind of an inconsistency found in a main chain block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IntegrityIssueKind {
+    /// The header, transactions, uncles or proposals of the block are missing.
+    ///
+    /// It can not be repaired from the local data, the block must be downloaded again.
+    MissingBody,
+    /// The block ext is missing.
+    MissingBlockExt,
+    /// The number to hash index or the hash to number index of the block is missing, or points
+    /// to another block.
+    IndexMismatch,
+    /// Some transactions of the block are missing from the transaction index.
+    MissingTransactionInfo,
+}
+
+/// An inconsistency found in a main chain block.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntegrityIssue {
+    /// The block number.
+    pub number: BlockNumber,
+    /// The block hash.
+    pub hash: Byte32,
+    /// What is inconsistent.
+    pub kind: IntegrityIssueKind,
+}
+
+/// Checks the main chain blocks from the tip down to `from`.
+///
+/// The main chain is followed through the parent hashes of the headers, so a broken index does
+/// not stop the scan. The scan stops at a missing header, at the frozen blocks, whose data has
+/// been moved to the freezer, or at the genesis block.
+///
+/// Issues are returned in ascending block number order.
+pub fn check_main_chain<S: ChainStore>(store: &S, from: BlockNumber) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    let frozen = store.freezer().map(|freezer| freezer.number()).unwrap_or(0);
+    let lowest = from.max(frozen);
+
+    let mut next = store.get_tip_header();
+    while let Some(header) = next.take() {
+        if header.number() < lowest {
+            break;
+        }
+        let mut block_issues = check_main_chain_block(store, &header);
+        block_issues.reverse();
+        issues.extend(block_issues);
+        if header.is_genesis() {
+            break;
+        }
+        let parent_number = header.number() - 1;
+        let parent_hash = header.parent_hash();
+        next = store.get_block_header(&parent_hash);
+        if next.is_none() && parent_number >= lowest {
+            issues.push(IntegrityIssue {
+                number: parent_number,
+                hash: parent_hash,
+                kind: IntegrityIssueKind::MissingBody,
+            });
+        }
+    }
+
+    issues.reverse();
+    issues
+}
+
+/// Checks a single main chain block, see [`check_main_chain`].
+pub fn check_main_chain_block<S: ChainStore>(
+    store: &S,
+    header: &HeaderView,
+) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    let number = header.number();
+    let hash = header.hash();
+    let mut push = |kind| {
+        issues.push(IntegrityIssue {
+            number,
+            hash: hash.clone(),
+            kind,
+        })
+    };
+
+    if store.get_block_hash(number).as_ref() != Some(&hash)
+        || store.get_block_number(&hash) != Some(number)
+    {
+        push(IntegrityIssueKind::IndexMismatch);
+    }
+    if store.get_block_ext(&hash).is_none() {
+        push(IntegrityIssueKind::MissingBlockExt);
+    }
+
+    let txs_hashes = block_txs_hashes(store, &hash);
+    // every block has a cellbase transaction, so no transactions means the body is missing
+    if txs_hashes.is_empty()
+        || store.get(COLUMN_BLOCK_UNCLE, hash.as_slice()).is_none()
+        || store
+            .get(COLUMN_BLOCK_PROPOSAL_IDS, hash.as_slice())
+            .is_none()
+    {
+        push(IntegrityIssueKind::MissingBody);
+        return issues;
+    }
+    let indexed = txs_hashes.iter().all(|tx_hash| {
+        store
+            .get_transaction_info(tx_hash)
+            .map(|info| info.block_hash == hash)
+            .unwrap_or(false)
+    });
+    if !indexed {
+        push(IntegrityIssueKind::MissingTransactionInfo);
+    }
+    issues
+}
+
+// Reads the transaction hashes from the db directly, the cached ones may outlive the body
+fn block_txs_hashes<S: ChainStore>(store: &S, hash: &Byte32) -> Vec<Byte32> {
+    let prefix = hash.as_slice();
+    store
+        .get_iter(
+            COLUMN_BLOCK_BODY,
+            IteratorMode::From(prefix, Direction::Forward),
+        )
+        .take_while(|(key, _)| key.starts_with(prefix))
+        .map(|(_key, value)| {
+            packed::TransactionViewReader::from_slice_should_be_ok(value.as_ref())
+                .hash()
+                .to_entity()
+        })
+        .collect()
+}
+
+/// Repairs the issues which can be fixed from the local data.
+///
+/// - The indexes of a block are rebuilt from its body. The block which took its number is
+///   detached first, and its effects on the live cell set are reverted.
+/// - A missing block ext is rebuilt from the parent block ext and the block itself. The fees of
+///   the transactions, except the cellbase, are recomputed by `txs_fees`, which returns `None`
+///   when they can not be computed, then the fees are left empty. The cycles are unknown, so
+///   they are left empty.
+///
+/// `issues` must be in ascending block number order, as returned by [`check_main_chain`].
+/// Returns the issues which are not repaired, the missing bodies must be downloaded again.
+pub fn repair_main_chain<F>(
+    txn: &StoreTransaction,
+    issues: &[IntegrityIssue],
+    txs_fees: F,
+) -> Result<Vec<IntegrityIssue>, Error>
+where
+    F: Fn(&BlockView) -> Option<Vec<Capacity>>,
+{
+    let mut unrepaired = Vec::new();
+    let missing_body = |hash: &Byte32| {
+        issues
+            .iter()
+            .any(|issue| &issue.hash == hash && issue.kind == IntegrityIssueKind::MissingBody)
+    };
+
+    for issue in issues {
+        let repaired = match issue.kind {
+            IntegrityIssueKind::MissingBody => false,
+            _ if missing_body(&issue.hash) => false,
+            IntegrityIssueKind::IndexMismatch | IntegrityIssueKind::MissingTransactionInfo => {
+                reindex_block(txn, issue)?
+            }
+            IntegrityIssueKind::MissingBlockExt => rebuild_block_ext(txn, issue, &txs_fees)?,
+        };
+        if !repaired {
+            unrepaired.push(issue.clone());
+        }
+    }
+    Ok(unrepaired)
+}
+
+fn reindex_block(txn: &StoreTransaction, issue: &IntegrityIssue) -> Result<bool, Error> {
+    let block = match txn.get_block(&issue.hash) {
+        Some(block) => block,
+        None => return Ok(false),
+    };
+    let mut reverted = false;
+    if let Some(stale_hash) = txn
+        .get_block_hash(issue.number)
+        .filter(|stale_hash| stale_hash != &issue.hash)
+    {
+        match txn.get_block(&stale_hash) {
+            Some(stale_block) => {
+                // the live cell set follows the stale block, revert it before the reindex
+                detach_block_cell(txn, &stale_block)?;
+                txn.detach_block(&stale_block)?;
+                reverted = true;
+            }
+            None => txn.delete(COLUMN_INDEX, stale_hash.as_slice())?,
+        }
+    }
+    txn.attach_block(&block)?;
+    if reverted {
+        attach_block_cell(txn, &block)?;
+    }
+    Ok(true)
+}
+
+fn rebuild_block_ext<F>(
+    txn: &StoreTransaction,
+    issue: &IntegrityIssue,
+    txs_fees: &F,
+) -> Result<bool, Error>
+where
+    F: Fn(&BlockView) -> Option<Vec<Capacity>>,
+{
+    let block = match txn.get_block(&issue.hash) {
+        Some(block) => block,
+        None => return Ok(false),
+    };
+    let parent_ext = match txn.get_block_ext(&block.parent_hash()) {
+        Some(parent_ext) => parent_ext,
+        None => return Ok(false),
+    };
+    let ext = BlockExt {
+        received_at: block.header().timestamp(),
+        total_difficulty: parent_ext.total_difficulty + block.header().difficulty(),
+        total_uncles_count: parent_ext.total_uncles_count + block.data().uncles().len() as u64,
+        verified: Some(true),
+        txs_fees: txs_fees(&block).unwrap_or_default(),
+        cycles: None,
+        txs_sizes: None,
+    };
+    txn.insert_block_ext(&issue.hash, &ext)?;
+    Ok(true)
+}
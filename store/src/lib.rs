@@ -7,6 +7,8 @@ mod cache;
 mod cell;
 pub mod data_loader_wrapper;
 mod db;
+mod epoch_stats;
+pub mod integrity;
 mod snapshot;
 mod store;
 mod transaction;
@@ -16,8 +18,9 @@ mod write_batch;
 mod tests;
 
 pub use cache::StoreCache;
-pub use cell::{attach_block_cell, detach_block_cell};
+pub use cell::{attach_block_cell, detach_block_cell, PrefetchedCells};
 pub use db::ChainDB;
+pub use epoch_stats::{attach_block_epoch_stats, detach_block_epoch_stats};
 pub use snapshot::StoreSnapshot;
 pub use store::ChainStore;
 pub use transaction::StoreTransaction;
@@ -35,4 +35,10 @@ impl ChainStore for StoreSnapshot {
             .iter(col, mode)
             .expect("db operation should be ok")
     }
+
+    fn multi_get<K: AsRef<[u8]>>(&self, col: Col, keys: &[K]) -> Vec<Option<DBPinnableSlice>> {
+        self.inner
+            .multi_get_pinned(col, keys)
+            .expect("db operation should be ok")
+    }
 }
@@ -8,15 +8,16 @@ use ckb_db_schema::{
     Col, COLUMN_BLOCK_BODY, COLUMN_BLOCK_EPOCH, COLUMN_BLOCK_EXT, COLUMN_BLOCK_EXTENSION,
     COLUMN_BLOCK_FILTER, COLUMN_BLOCK_FILTER_HASH, COLUMN_BLOCK_HEADER, COLUMN_BLOCK_PROPOSAL_IDS,
     COLUMN_BLOCK_UNCLE, COLUMN_CELL, COLUMN_CELL_DATA, COLUMN_CELL_DATA_HASH,
-    COLUMN_CHAIN_ROOT_MMR, COLUMN_EPOCH, COLUMN_INDEX, COLUMN_META, COLUMN_TRANSACTION_INFO,
-    COLUMN_UNCLES, META_CURRENT_EPOCH_KEY, META_LATEST_BUILT_FILTER_DATA_KEY, META_TIP_HEADER_KEY,
+    COLUMN_CHAIN_ROOT_MMR, COLUMN_EPOCH, COLUMN_EPOCH_STATS, COLUMN_INDEX, COLUMN_META,
+    COLUMN_TRANSACTION_INFO, COLUMN_UNCLES, META_CURRENT_EPOCH_KEY,
+    META_LATEST_BUILT_FILTER_DATA_KEY, META_PRUNED_UNCLES_AND_PROPOSALS_KEY, META_TIP_HEADER_KEY,
 };
 use ckb_freezer::Freezer;
 use ckb_types::{
     bytes::Bytes,
     core::{
-        cell::CellMeta, BlockExt, BlockNumber, BlockView, EpochExt, EpochNumber, HeaderView,
-        TransactionInfo, TransactionView, UncleBlockVecView,
+        cell::CellMeta, BlockExt, BlockNumber, BlockView, EpochExt, EpochNumber, EpochStats,
+        HeaderView, TransactionInfo, TransactionView, UncleBlockVecView,
     },
     packed::{self, OutPoint},
     prelude::*,
@@ -32,6 +33,14 @@ pub trait ChainStore: Send + Sync + Sized {
     fn get(&self, col: Col, key: &[u8]) -> Option<DBPinnableSlice>;
     /// Return an iterator over the database key-value pairs in the given column family.
     fn get_iter(&self, col: Col, mode: IteratorMode) -> DBIter;
+    /// Return the bytes associated with the keys and the given column family, in the order of
+    /// the keys.
+    ///
+    /// The stores backed by RocksDB read the keys in a batch, which is cheaper than calling
+    /// `get` for each key.
+    fn multi_get<K: AsRef<[u8]>>(&self, col: Col, keys: &[K]) -> Vec<Option<DBPinnableSlice>> {
+        keys.iter().map(|key| self.get(col, key.as_ref())).collect()
+    }
     /// Return the borrowed data loader wrapper
     fn borrow_as_data_loader(&self) -> BorrowedDataLoaderWrapper<Self> {
         BorrowedDataLoaderWrapper::new(self)
@@ -353,6 +362,24 @@ pub trait ChainStore: Send + Sync + Sized {
         })
     }
 
+    /// Gets the cells meta data with the out points in a batch, in the order of the out points.
+    fn get_cells(&self, out_points: &[OutPoint]) -> Vec<Option<CellMeta>> {
+        let keys: Vec<_> = out_points
+            .iter()
+            .map(|out_point| out_point.to_cell_key())
+            .collect();
+        self.multi_get(COLUMN_CELL, &keys)
+            .into_iter()
+            .zip(out_points)
+            .map(|(slice, out_point)| {
+                slice.map(|slice| {
+                    let reader = packed::CellEntryReader::from_slice_should_be_ok(slice.as_ref());
+                    build_cell_meta_from_reader(out_point.clone(), reader)
+                })
+            })
+            .collect()
+    }
+
     /// TODO(doc): @quake
     fn get_cell_data(&self, out_point: &OutPoint) -> Option<(Bytes, packed::Byte32)> {
         let key = out_point.to_cell_key();
@@ -429,6 +456,13 @@ pub trait ChainStore: Send + Sync + Sized {
             .map(|raw| packed::Byte32Reader::from_slice_should_be_ok(raw.as_ref()).to_entity())
     }
 
+    /// Gets the aggregated statistics of the main chain blocks in the epoch
+    fn get_epoch_stats(&self, number: EpochNumber) -> Option<EpochStats> {
+        let epoch_number: packed::Uint64 = number.pack();
+        self.get(COLUMN_EPOCH_STATS, epoch_number.as_slice())
+            .map(|slice| packed::Uint64VecReader::from_slice_should_be_ok(slice.as_ref()).unpack())
+    }
+
     /// Gets epoch index by block hash
     fn get_block_epoch_index(&self, block_hash: &packed::Byte32) -> Option<packed::Byte32> {
         self.get(COLUMN_BLOCK_EPOCH, block_hash.as_slice())
@@ -481,6 +515,32 @@ pub trait ChainStore: Send + Sync + Sized {
             .map(|raw| packed::Byte32Reader::from_slice_should_be_ok(raw.as_ref()).to_entity())
     }
 
+    /// Gets the number of the latest main chain block whose uncle proposals and proposal ids are
+    /// pruned
+    fn get_pruned_uncles_and_proposals_number(&self) -> Option<BlockNumber> {
+        self.get(COLUMN_META, META_PRUNED_UNCLES_AND_PROPOSALS_KEY)
+            .map(|raw| packed::Uint64Reader::from_slice_should_be_ok(raw.as_ref()).unpack())
+    }
+
+    /// Returns true if the uncle proposals and the proposal ids of the block are pruned.
+    ///
+    /// The headers of the block and its uncles are kept, so the block commitments can still be
+    /// verified, but the block can not be rebuilt in full.
+    fn is_uncles_and_proposals_pruned(&self, hash: &packed::Byte32) -> bool {
+        let pruned_number = match self.get_pruned_uncles_and_proposals_number() {
+            Some(number) => number,
+            None => return false,
+        };
+        match self.get_block_number(hash) {
+            Some(number) => {
+                number > 0
+                    && number <= pruned_number
+                    && self.get_block_hash(number).as_ref() == Some(hash)
+            }
+            None => false,
+        }
+    }
+
     /// Gets block filter data by block hash
     fn get_block_filter(&self, hash: &packed::Byte32) -> Option<packed::Bytes> {
         self.get(COLUMN_BLOCK_FILTER, hash.as_slice())
@@ -2,7 +2,11 @@ use ckb_chain_spec::consensus::ConsensusBuilder;
 use ckb_db::RocksDB;
 use ckb_db_schema::{COLUMNS, COLUMN_BLOCK_HEADER};
 use ckb_freezer::Freezer;
-use ckb_types::{core::BlockExt, packed, prelude::*};
+use ckb_types::{
+    core::{BlockExt, BlockView, Capacity},
+    packed,
+    prelude::*,
+};
 use tempfile::TempDir;
 
 use crate::{db::ChainDB, store::ChainStore};
@@ -159,3 +163,354 @@ fn freeze_blockv1_with_extension() {
     let block = store.get_block(&block_hash).expect("get_block");
     assert_eq!(store.get_block(&block_hash), Some(block));
 }
+
+#[test]
+fn attach_and_detach_epoch_stats() {
+    use crate::{attach_block_epoch_stats, detach_block_epoch_stats};
+    use ckb_types::core::{Capacity, EpochStats};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db = RocksDB::open_in(&tmp_dir, COLUMNS);
+    let store = ChainDB::new(db, Default::default());
+    let block = packed::Block::new_builder()
+        .transactions(
+            (0..3)
+                .map(|_| packed::Transaction::new_builder().build())
+                .collect::<Vec<_>>()
+                .pack(),
+        )
+        .build()
+        .into_view();
+    let ext = BlockExt {
+        txs_fees: vec![Capacity::shannons(100), Capacity::shannons(200)],
+        cycles: Some(vec![1000, 2000]),
+        ..Default::default()
+    };
+
+    let txn = store.begin_transaction();
+    txn.insert_block(&block).unwrap();
+    txn.insert_block_ext(&block.hash(), &ext).unwrap();
+    attach_block_epoch_stats(&txn, &block).unwrap();
+    attach_block_epoch_stats(&txn, &block).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(
+        store.get_epoch_stats(0),
+        Some(EpochStats {
+            block_count: 2,
+            tx_count: Some(4),
+            total_fees: Capacity::shannons(600),
+            total_cycles: 6000,
+        })
+    );
+
+    let txn = store.begin_transaction();
+    detach_block_epoch_stats(&txn, &block).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(
+        store.get_epoch_stats(0),
+        Some(EpochStats {
+            block_count: 1,
+            tx_count: Some(2),
+            total_fees: Capacity::shannons(300),
+            total_cycles: 3000,
+        })
+    );
+
+    let txn = store.begin_transaction();
+    detach_block_epoch_stats(&txn, &block).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(store.get_epoch_stats(0), None);
+}
+
+#[test]
+fn epoch_stats_with_unknown_tx_count() {
+    use crate::attach_block_epoch_stats;
+    use ckb_types::core::{Capacity, EpochStats};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db = RocksDB::open_in(&tmp_dir, COLUMNS);
+    let store = ChainDB::new(db, Default::default());
+    let block = packed::Block::new_builder()
+        .transactions(vec![packed::Transaction::new_builder().build()].pack())
+        .build()
+        .into_view();
+    // the stats built by the migration, when some blocks of the epoch were frozen
+    let stats = EpochStats {
+        block_count: 1,
+        tx_count: None,
+        total_fees: Capacity::shannons(100),
+        total_cycles: 1000,
+    };
+
+    let txn = store.begin_transaction();
+    txn.insert_block(&block).unwrap();
+    txn.insert_epoch_stats(0, &stats).unwrap();
+    attach_block_epoch_stats(&txn, &block).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(
+        store.get_epoch_stats(0),
+        Some(EpochStats {
+            block_count: 2,
+            ..stats
+        })
+    );
+
+    // the stats stored before the tx count could be unknown
+    let stored: EpochStats = packed::Uint64Vec::new_builder()
+        .set(vec![1u64.pack(), 2u64.pack(), 3u64.pack(), 4u64.pack()])
+        .build()
+        .unpack();
+    assert_eq!(stored.tx_count, Some(2));
+}
+
+#[test]
+fn check_and_repair_main_chain() {
+    use crate::integrity::{check_main_chain, repair_main_chain, IntegrityIssueKind};
+    use ckb_db_schema::{COLUMN_BLOCK_BODY, COLUMN_BLOCK_EXT, COLUMN_INDEX};
+    use ckb_types::core::{BlockBuilder, TransactionBuilder};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db = RocksDB::open_in(&tmp_dir, COLUMNS);
+    let store = ChainDB::new(db, Default::default());
+    let consensus = ConsensusBuilder::default().build();
+    store.init(&consensus).unwrap();
+    let genesis = consensus.genesis_block();
+    let genesis_ext = store.get_block_ext(&genesis.hash()).unwrap();
+
+    let cellbase = TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(1))
+        .build();
+    let block = BlockBuilder::default()
+        .parent_hash(genesis.hash())
+        .number(1.pack())
+        .transaction(cellbase)
+        .build();
+    let hash = block.hash();
+    let txn = store.begin_transaction();
+    txn.insert_block(&block).unwrap();
+    txn.insert_block_ext(&hash, &genesis_ext).unwrap();
+    txn.attach_block(&block).unwrap();
+    txn.insert_tip_header(&block.header()).unwrap();
+    txn.commit().unwrap();
+    assert!(check_main_chain(&store, 0).is_empty());
+
+    // broken indexes and block ext are rebuilt from the local data
+    let txn = store.begin_transaction();
+    let number: packed::Uint64 = 1u64.pack();
+    txn.delete(COLUMN_INDEX, number.as_slice()).unwrap();
+    txn.delete(COLUMN_BLOCK_EXT, hash.as_slice()).unwrap();
+    txn.commit().unwrap();
+    let kinds: Vec<_> = check_main_chain(&store, 0)
+        .into_iter()
+        .map(|issue| issue.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            IntegrityIssueKind::IndexMismatch,
+            IntegrityIssueKind::MissingBlockExt
+        ]
+    );
+
+    let txn = store.begin_transaction();
+    let txs_fees = |_: &BlockView| Some(vec![Capacity::shannons(1)]);
+    let unrepaired = repair_main_chain(&txn, &check_main_chain(&store, 0), txs_fees).unwrap();
+    txn.commit().unwrap();
+    assert!(unrepaired.is_empty());
+    assert!(check_main_chain(&store, 0).is_empty());
+    assert_eq!(store.get_block_hash(1), Some(hash.clone()));
+    let ext = store.get_block_ext(&hash).unwrap();
+    assert_eq!(
+        ext.total_difficulty,
+        genesis_ext.total_difficulty + block.difficulty()
+    );
+    assert_eq!(ext.txs_fees, vec![Capacity::shannons(1)]);
+
+    // a missing body is left to be downloaded again
+    let txn = store.begin_transaction();
+    let key = packed::TransactionKey::new_builder()
+        .block_hash(hash.clone())
+        .index(0u32.pack())
+        .build();
+    txn.delete(COLUMN_BLOCK_BODY, key.as_slice()).unwrap();
+    txn.commit().unwrap();
+    let issues = check_main_chain(&store, 0);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].kind, IntegrityIssueKind::MissingBody);
+    assert_eq!(issues[0].hash, hash);
+
+    let txn = store.begin_transaction();
+    let unrepaired = repair_main_chain(&txn, &issues, |_| None).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(unrepaired, issues);
+}
+
+#[test]
+fn repair_main_chain_reverts_stale_block() {
+    use crate::cell::attach_block_cell;
+    use crate::integrity::{check_main_chain, repair_main_chain, IntegrityIssueKind};
+    use ckb_types::core::{BlockBuilder, TransactionBuilder};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db = RocksDB::open_in(&tmp_dir, COLUMNS);
+    let store = ChainDB::new(db, Default::default());
+    let consensus = ConsensusBuilder::default().build();
+    store.init(&consensus).unwrap();
+    let genesis = consensus.genesis_block();
+    let genesis_ext = store.get_block_ext(&genesis.hash()).unwrap();
+
+    let new_block = |capacity: u64| {
+        let cellbase = TransactionBuilder::default()
+            .input(packed::CellInput::new_cellbase_input(1))
+            .output(
+                packed::CellOutput::new_builder()
+                    .capacity(capacity.pack())
+                    .build(),
+            )
+            .output_data(Default::default())
+            .build();
+        BlockBuilder::default()
+            .parent_hash(genesis.hash())
+            .number(1.pack())
+            .transaction(cellbase)
+            .build()
+    };
+    let cellbase_cell =
+        |block: &BlockView| packed::OutPoint::new(block.transactions()[0].hash(), 0);
+    let block = new_block(1);
+    let stale_block = new_block(2);
+
+    // the tip is the block, but the indexes and the live cells follow the stale block
+    let txn = store.begin_transaction();
+    for block in [&block, &stale_block] {
+        txn.insert_block(block).unwrap();
+        txn.insert_block_ext(&block.hash(), &genesis_ext).unwrap();
+    }
+    txn.attach_block(&stale_block).unwrap();
+    attach_block_cell(&txn, &stale_block).unwrap();
+    txn.insert_tip_header(&block.header()).unwrap();
+    txn.commit().unwrap();
+    let issues = check_main_chain(&store, 0);
+    assert_eq!(issues.len(), 2);
+    assert!(issues
+        .iter()
+        .all(|issue| issue.hash == block.hash() && issue.kind != IntegrityIssueKind::MissingBody));
+
+    let txn = store.begin_transaction();
+    let unrepaired = repair_main_chain(&txn, &issues, |_| None).unwrap();
+    txn.commit().unwrap();
+    assert!(unrepaired.is_empty());
+    assert!(check_main_chain(&store, 0).is_empty());
+    assert!(store.get_cell(&cellbase_cell(&stale_block)).is_none());
+    assert!(store.get_cell(&cellbase_cell(&block)).is_some());
+}
+
+#[test]
+fn prune_block_uncles_and_proposals() {
+    use ckb_types::core::{BlockBuilder, TransactionBuilder};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db = RocksDB::open_in(&tmp_dir, COLUMNS);
+    let store = ChainDB::new(db, Default::default());
+    let consensus = ConsensusBuilder::default().build();
+    store.init(&consensus).unwrap();
+    let genesis = consensus.genesis_block();
+
+    let proposal = packed::ProposalShortId::new([1u8; 10]);
+    let uncle = BlockBuilder::default()
+        .parent_hash(genesis.hash())
+        .number(1.pack())
+        .timestamp(1.pack())
+        .proposal(proposal.clone())
+        .build()
+        .as_uncle();
+    let cellbase = TransactionBuilder::default()
+        .input(packed::CellInput::new_cellbase_input(2))
+        .build();
+    let block = BlockBuilder::default()
+        .parent_hash(genesis.hash())
+        .number(1.pack())
+        .transaction(cellbase)
+        .uncle(uncle.clone())
+        .proposal(proposal)
+        .build();
+    let hash = block.hash();
+    let txn = store.begin_transaction();
+    txn.insert_block(&block).unwrap();
+    txn.attach_block(&block).unwrap();
+    txn.insert_tip_header(&block.header()).unwrap();
+    txn.commit().unwrap();
+    assert!(!store.is_uncles_and_proposals_pruned(&hash));
+
+    let txn = store.begin_transaction();
+    txn.prune_block_uncles_and_proposals(&hash).unwrap();
+    txn.set_pruned_uncles_and_proposals_number(1).unwrap();
+    txn.commit().unwrap();
+
+    assert_eq!(store.get_pruned_uncles_and_proposals_number(), Some(1));
+    assert!(store.is_uncles_and_proposals_pruned(&hash));
+    assert!(!store.is_uncles_and_proposals_pruned(&genesis.hash()));
+
+    let pruned = store.get_unfrozen_block(&hash).unwrap();
+    assert!(pruned.data().proposals().is_empty());
+    assert_eq!(
+        pruned.uncles().hashes().as_slice(),
+        block.uncles().hashes().as_slice()
+    );
+    assert!(pruned
+        .uncles()
+        .data()
+        .into_iter()
+        .all(|uncle| uncle.proposals().is_empty()));
+    // the commitments in the header are unchanged
+    assert_eq!(pruned.calc_uncles_hash(), block.calc_uncles_hash());
+    assert_eq!(pruned.transactions(), block.transactions());
+}
+
+#[test]
+fn get_cells_in_the_order_of_out_points() {
+    use crate::attach_block_cell;
+    use ckb_types::core::{capacity_bytes, Capacity, TransactionBuilder};
+
+    let tmp_dir = TempDir::new().unwrap();
+    let db = RocksDB::open_in(&tmp_dir, COLUMNS);
+    let store = ChainDB::new(db, Default::default());
+    let tx = TransactionBuilder::default()
+        .outputs((1..=3).map(|i| {
+            packed::CellOutput::new_builder()
+                .capacity(Capacity::bytes(i).unwrap().pack())
+                .build()
+        }))
+        .outputs_data((0..3).map(|_| packed::Bytes::default()))
+        .build();
+    let block = packed::Block::new_builder()
+        .transactions(vec![tx.data()].pack())
+        .build()
+        .into_view();
+
+    let txn = store.begin_transaction();
+    txn.insert_block(&block).unwrap();
+    attach_block_cell(&txn, &block).unwrap();
+    txn.commit().unwrap();
+
+    let missing = packed::OutPoint::new(tx.hash(), 3);
+    let out_points = vec![
+        tx.output_pts()[2].clone(),
+        missing,
+        tx.output_pts()[0].clone(),
+    ];
+    let cells = store.get_cells(&out_points);
+    assert_eq!(cells.len(), 3);
+    assert_eq!(
+        cells[0].as_ref().map(|cell| cell.cell_output.capacity()),
+        Some(capacity_bytes!(3).pack())
+    );
+    assert!(cells[1].is_none());
+    assert_eq!(
+        cells[2].as_ref().map(|cell| cell.cell_output.capacity()),
+        Some(capacity_bytes!(1).pack())
+    );
+    for (cell, out_point) in cells.iter().zip(&out_points) {
+        assert_eq!(cell, &store.get_cell(out_point));
+    }
+}
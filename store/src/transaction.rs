@@ -9,9 +9,9 @@ use ckb_db_schema::{
     Col, COLUMN_BLOCK_BODY, COLUMN_BLOCK_EPOCH, COLUMN_BLOCK_EXT, COLUMN_BLOCK_EXTENSION,
     COLUMN_BLOCK_FILTER, COLUMN_BLOCK_FILTER_HASH, COLUMN_BLOCK_HEADER, COLUMN_BLOCK_PROPOSAL_IDS,
     COLUMN_BLOCK_UNCLE, COLUMN_CELL, COLUMN_CELL_DATA, COLUMN_CELL_DATA_HASH,
-    COLUMN_CHAIN_ROOT_MMR, COLUMN_EPOCH, COLUMN_INDEX, COLUMN_META, COLUMN_NUMBER_HASH,
-    COLUMN_TRANSACTION_INFO, COLUMN_UNCLES, META_CURRENT_EPOCH_KEY,
-    META_LATEST_BUILT_FILTER_DATA_KEY, META_TIP_HEADER_KEY,
+    COLUMN_CHAIN_ROOT_MMR, COLUMN_EPOCH, COLUMN_EPOCH_STATS, COLUMN_INDEX, COLUMN_META,
+    COLUMN_NUMBER_HASH, COLUMN_TRANSACTION_INFO, COLUMN_UNCLES, META_CURRENT_EPOCH_KEY,
+    META_LATEST_BUILT_FILTER_DATA_KEY, META_PRUNED_UNCLES_AND_PROPOSALS_KEY, META_TIP_HEADER_KEY,
 };
 use ckb_error::Error;
 use ckb_freezer::Freezer;
@@ -19,7 +19,8 @@ use ckb_merkle_mountain_range::{Error as MMRError, MMRStore, Result as MMRResult
 use ckb_types::{
     core::{
         cell::{CellChecker, CellProvider, CellStatus},
-        BlockExt, BlockView, EpochExt, HeaderView, TransactionView,
+        BlockExt, BlockNumber, BlockView, EpochExt, EpochNumber, EpochStats, HeaderView,
+        TransactionView,
     },
     packed::{self, Byte32, OutPoint},
     prelude::*,
@@ -54,6 +55,12 @@ impl ChainStore for StoreTransaction {
             .iter(col, mode)
             .expect("db operation should be ok")
     }
+
+    fn multi_get<K: AsRef<[u8]>>(&self, col: Col, keys: &[K]) -> Vec<Option<DBPinnableSlice>> {
+        self.inner
+            .multi_get_pinned(col, keys)
+            .expect("db operation should be ok")
+    }
 }
 
 impl VersionbitsIndexer for StoreTransaction {
@@ -314,6 +321,22 @@ impl StoreTransaction {
         self.insert_raw(COLUMN_EPOCH, epoch_number.as_slice(), hash.as_slice())
     }
 
+    /// Inserts the aggregated statistics of the main chain blocks in the epoch
+    pub fn insert_epoch_stats(&self, number: EpochNumber, stats: &EpochStats) -> Result<(), Error> {
+        let epoch_number: packed::Uint64 = number.pack();
+        self.insert_raw(
+            COLUMN_EPOCH_STATS,
+            epoch_number.as_slice(),
+            stats.pack().as_slice(),
+        )
+    }
+
+    /// Deletes the aggregated statistics of the epoch
+    pub fn delete_epoch_stats(&self, number: EpochNumber) -> Result<(), Error> {
+        let epoch_number: packed::Uint64 = number.pack();
+        self.delete(COLUMN_EPOCH_STATS, epoch_number.as_slice())
+    }
+
     /// TODO(doc): @quake
     pub fn insert_current_epoch_ext(&self, epoch: &EpochExt) -> Result<(), Error> {
         self.insert_raw(COLUMN_META, META_CURRENT_EPOCH_KEY, epoch.pack().as_slice())
@@ -406,6 +429,44 @@ impl StoreTransaction {
             block_hash.as_slice(),
         )
     }
+
+    /// Discards the proposal ids of the block and the proposals of its uncles.
+    ///
+    /// The uncle headers are kept, so the uncles hash of the block is unchanged.
+    pub fn prune_block_uncles_and_proposals(&self, block_hash: &Byte32) -> Result<(), Error> {
+        if let Some(uncles) = self.get_block_uncles(block_hash) {
+            let data: packed::UncleBlockVec = uncles
+                .data()
+                .into_iter()
+                .map(|uncle| {
+                    uncle
+                        .as_builder()
+                        .proposals(packed::ProposalShortIdVec::default())
+                        .build()
+                })
+                .pack();
+            let pruned = packed::UncleBlockVecView::new_builder()
+                .data(data)
+                .hashes(uncles.hashes())
+                .build();
+            self.insert_raw(COLUMN_BLOCK_UNCLE, block_hash.as_slice(), pruned.as_slice())?;
+        }
+        self.insert_raw(
+            COLUMN_BLOCK_PROPOSAL_IDS,
+            block_hash.as_slice(),
+            packed::ProposalShortIdVec::default().as_slice(),
+        )
+    }
+
+    /// Sets the number of the latest main chain block whose uncle proposals and proposal ids are
+    /// pruned
+    pub fn set_pruned_uncles_and_proposals_number(&self, number: BlockNumber) -> Result<(), Error> {
+        self.insert_raw(
+            COLUMN_META,
+            META_PRUNED_UNCLES_AND_PROPOSALS_KEY,
+            number.pack().as_slice(),
+        )
+    }
 }
 
 impl MMRStore<packed::HeaderDigest> for &StoreTransaction {
@@ -2,7 +2,7 @@ mod get_block_filter_check_points_process;
 mod get_block_filter_hashes_process;
 mod get_block_filters_process;
 
-use crate::{types::SyncShared, Status};
+use crate::{types::SyncShared, Status, StatusCode};
 use get_block_filter_check_points_process::GetBlockFilterCheckPointsProcess;
 use get_block_filter_hashes_process::GetBlockFilterHashesProcess;
 use get_block_filters_process::GetBlockFiltersProcess;
@@ -88,7 +88,12 @@ impl BlockFilter {
                 ban_time,
                 status
             );
-            nc.ban_peer(peer, ban_time, status.to_string());
+            nc.report_misbehavior(
+                peer,
+                &status.code().misbehavior_kind(),
+                ban_time,
+                status.to_string(),
+            );
         } else if status.should_warn() {
             warn_target!(
                 crate::LOG_TARGET_RELAY,
@@ -127,8 +132,9 @@ impl CKBProtocolHandler for BlockFilter {
                     "Peer {} sends us a malformed message",
                     peer_index
                 );
-                nc.ban_peer(
+                nc.report_misbehavior(
                     peer_index,
+                    &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                     BAD_MESSAGE_BAN_TIME,
                     String::from("send us a malformed message"),
                 );
@@ -16,7 +16,7 @@ mod tests;
 
 pub use crate::filter::BlockFilter;
 pub use crate::net_time_checker::NetTimeProtocol;
-pub use crate::relayer::Relayer;
+pub use crate::relayer::{build_compact_block, Relayer};
 pub use crate::status::{Status, StatusCode};
 pub use crate::synchronizer::Synchronizer;
 pub use crate::types::{ActiveChain, SyncShared};
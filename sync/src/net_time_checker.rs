@@ -1,4 +1,5 @@
 use crate::utils::send_message_to;
+use crate::StatusCode;
 use ckb_constant::sync::BAD_MESSAGE_BAN_TIME;
 use ckb_logger::{debug, info, warn};
 use ckb_network::async_trait;
@@ -143,8 +144,9 @@ impl CKBProtocolHandler for NetTimeProtocol {
             Some(timestamp) => timestamp,
             None => {
                 info!("Received a malformed message from peer {}", peer_index);
-                nc.ban_peer(
+                nc.report_misbehavior(
                     peer_index,
+                    &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                     BAD_MESSAGE_BAN_TIME,
                     String::from("send us a malformed message"),
                 );
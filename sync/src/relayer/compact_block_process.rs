@@ -104,6 +104,10 @@ impl<'a> CompactBlockProcess<'a> {
                         .ckb_relay_cb_transaction_count
                         .inc_by(block.transactions().len() as u64);
                     metrics.ckb_relay_cb_reconstruct_ok.inc();
+                    metrics
+                        .ckb_relay_cb_reconstruct_result
+                        .with_label_values(&["hit"])
+                        .inc();
                 }
 
                 pending_compact_blocks.remove(&block_hash);
@@ -136,6 +140,10 @@ impl<'a> CompactBlockProcess<'a> {
                         .ckb_relay_cb_fresh_tx_cnt
                         .inc_by(missing_transactions.len() as u64);
                     metrics.ckb_relay_cb_reconstruct_fail.inc();
+                    metrics
+                        .ckb_relay_cb_reconstruct_result
+                        .with_label_values(&["miss"])
+                        .inc();
                 }
 
                 let missing_uncles: Vec<u32> = uncles.into_iter().map(|i| i as u32).collect();
@@ -152,6 +160,12 @@ impl<'a> CompactBlockProcess<'a> {
                 StatusCode::CompactBlockRequiresFreshTransactions.with_context(&block_hash)
             }
             ReconstructionResult::Collided => {
+                if let Some(metrics) = ckb_metrics::handle() {
+                    metrics
+                        .ckb_relay_cb_reconstruct_result
+                        .with_label_values(&["collided"])
+                        .inc();
+                }
                 let missing_transactions: Vec<u32> = compact_block
                     .short_id_indexes()
                     .into_iter()
@@ -31,11 +31,12 @@ use ckb_logger::{
     debug, debug_target, error, error_target, info_target, trace_target, warn_target,
 };
 use ckb_network::{
-    async_trait, bytes::Bytes, tokio, CKBProtocolContext, CKBProtocolHandler, PeerIndex,
-    SupportProtocols, TargetSession,
+    async_trait, bytes::Bytes, tokio, CKBProtocolContext, CKBProtocolHandler, PeerFeatures,
+    PeerIndex, SupportProtocols, TargetSession,
 };
 use ckb_shared::block_status::BlockStatus;
 use ckb_shared::Shared;
+use ckb_store::ChainStore;
 use ckb_systemtime::unix_time_as_millis;
 use ckb_tx_pool::service::TxVerificationResult;
 use ckb_types::BlockNumberAndHash;
@@ -46,7 +47,7 @@ use ckb_types::{
 };
 use ckb_util::Mutex;
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -57,6 +58,15 @@ pub const TX_HASHES_TOKEN: u64 = 2;
 pub const MAX_RELAY_PEERS: usize = 128;
 pub const MAX_RELAY_TXS_NUM_PER_BATCH: usize = 32767;
 pub const MAX_RELAY_TXS_BYTES_PER_BATCH: usize = 1024 * 1024;
+/// The max size of a transaction pushed without the request. The peers accepting the push take
+/// the transactions up to this size, whatever their own `announce_size_threshold` is.
+pub const MAX_PUSHED_TX_BYTES: usize = 64 * 1024;
+pub const MAX_PREFILLED_TXS_NUM: usize = 64;
+/// The max number of the tx relay messages kept while a deep reorg settles, the later ones are
+/// dropped.
+pub const MAX_SETTLING_RELAYS: usize = 1024;
+/// The max number of the tx hashes kept for a congested peer, the later ones are dropped.
+pub const MAX_DEFERRED_TX_HASHES_PER_PEER: usize = MAX_RELAY_TXS_NUM_PER_BATCH;
 
 type RateLimiter<T> = governor::RateLimiter<
     T,
@@ -72,12 +82,21 @@ pub enum ReconstructionResult {
     Error(Status),
 }
 
+// A tx relay message received while a deep reorg settles, it's processed after the reorg settles
+enum SettlingRelay {
+    Transactions(packed::RelayTransactions),
+    TransactionHashes(packed::RelayTransactionHashes),
+}
+
 /// Relayer protocol handle
 pub struct Relayer {
     chain: ChainController,
     pub(crate) shared: Arc<SyncShared>,
     rate_limiter: Arc<Mutex<RateLimiter<(PeerIndex, u32)>>>,
     v3: bool,
+    settling_relays: VecDeque<(PeerIndex, SettlingRelay)>,
+    // the tx hashes to announce after the congestion of the peers clears
+    deferred_tx_hashes: Mutex<HashMap<PeerIndex, Vec<Byte32>>>,
 }
 
 impl Relayer {
@@ -95,6 +114,8 @@ impl Relayer {
             shared,
             rate_limiter,
             v3: false,
+            settling_relays: VecDeque::new(),
+            deferred_tx_hashes: Mutex::new(HashMap::new()),
         }
     }
 
@@ -143,6 +164,10 @@ impl Relayer {
                     RelaySwitch::Ckb2023RelayV3 | RelaySwitch::Ckb2021RelayV2 => (),
                 }
                 if reader.check_data() {
+                    if self.shared.shared().is_reorg_settling() {
+                        self.defer_relay(peer, SettlingRelay::Transactions(reader.to_entity()));
+                        return Status::ok();
+                    }
                     TransactionsProcess::new(reader, self, nc, peer).execute()
                 } else {
                     StatusCode::ProtocolMessageIsMalformed
@@ -158,6 +183,10 @@ impl Relayer {
                     }
                     RelaySwitch::Ckb2023RelayV3 | RelaySwitch::Ckb2021RelayV2 => (),
                 }
+                if self.shared.shared().is_reorg_settling() {
+                    self.defer_relay(peer, SettlingRelay::TransactionHashes(reader.to_entity()));
+                    return Status::ok();
+                }
                 TransactionHashesProcess::new(reader, self, peer).execute()
             }
             packed::RelayMessageUnionReader::GetRelayTransactions(reader) => {
@@ -209,6 +238,48 @@ impl Relayer {
             item_bytes,
         );
 
+        self.report_status(nc.as_ref(), peer, item_name, status);
+    }
+
+    // Keeps the tx relay message received while a deep reorg settles
+    fn defer_relay(&mut self, peer: PeerIndex, relay: SettlingRelay) {
+        if self.settling_relays.len() < MAX_SETTLING_RELAYS {
+            self.settling_relays.push_back((peer, relay));
+        } else {
+            debug_target!(
+                crate::LOG_TARGET_RELAY,
+                "drop the tx relay message from {} while the reorg settles",
+                peer
+            );
+        }
+    }
+
+    // Processes the tx relay messages kept while the deep reorg settled
+    fn process_settling_relays(&mut self, nc: &Arc<dyn CKBProtocolContext + Sync>) {
+        let relays = std::mem::take(&mut self.settling_relays);
+        for (peer, relay) in relays {
+            let (item_name, status) = match &relay {
+                SettlingRelay::Transactions(message) => (
+                    "RelayTransactions",
+                    TransactionsProcess::new(message.as_reader(), self, Arc::clone(nc), peer)
+                        .execute(),
+                ),
+                SettlingRelay::TransactionHashes(message) => (
+                    "RelayTransactionHashes",
+                    TransactionHashesProcess::new(message.as_reader(), self, peer).execute(),
+                ),
+            };
+            self.report_status(nc.as_ref(), peer, item_name, status);
+        }
+    }
+
+    fn report_status(
+        &self,
+        nc: &(dyn CKBProtocolContext + Sync),
+        peer: PeerIndex,
+        item_name: &str,
+        status: Status,
+    ) {
         if let Some(ban_time) = status.should_ban() {
             error_target!(
                 crate::LOG_TARGET_RELAY,
@@ -218,7 +289,12 @@ impl Relayer {
                 ban_time,
                 status
             );
-            nc.ban_peer(peer, ban_time, status.to_string());
+            nc.report_misbehavior(
+                peer,
+                &status.code().misbehavior_kind(),
+                ban_time,
+                status.to_string(),
+            );
         } else if status.should_warn() {
             warn_target!(
                 crate::LOG_TARGET_RELAY,
@@ -719,7 +795,45 @@ impl Relayer {
                 }
             }
         }
+        // the hashes deferred by the congestion are announced first
+        {
+            let mut deferred_tx_hashes = self.deferred_tx_hashes.lock();
+            deferred_tx_hashes.retain(|peer, _| connected_peers.contains(peer));
+            for (peer, mut hashes) in deferred_tx_hashes.drain() {
+                if let Some(new_hashes) = selected.remove(&peer) {
+                    hashes.extend(new_hashes);
+                }
+                selected.insert(peer, hashes);
+            }
+        }
+        // keep the hashes to the congested peers until the block messages are flushed
+        selected.retain(|peer, hashes| {
+            if nc.send_congested(*peer) {
+                self.defer_tx_hashes(*peer, std::mem::take(hashes));
+                false
+            } else {
+                if hashes.len() > MAX_RELAY_TXS_NUM_PER_BATCH {
+                    let rest = hashes.split_off(MAX_RELAY_TXS_NUM_PER_BATCH);
+                    self.defer_tx_hashes(*peer, rest);
+                }
+                true
+            }
+        });
+        let push_peers: HashSet<PeerIndex> = selected
+            .keys()
+            .filter(|peer| nc.peer_supports(**peer, PeerFeatures::TX_PUSH))
+            .copied()
+            .collect();
+        let pushed_txs = self.fetch_txs_to_push(&selected, &push_peers);
         for (peer, hashes) in selected {
+            let hashes = if push_peers.contains(&peer) {
+                self.push_transactions(nc, peer, hashes, &pushed_txs)
+            } else {
+                hashes
+            };
+            if hashes.is_empty() {
+                continue;
+            }
             let content = packed::RelayTransactionHashes::new_builder()
                 .tx_hashes(hashes.pack())
                 .build();
@@ -731,11 +845,205 @@ impl Relayer {
                     "relayer send TransactionHashes error: {:?}",
                     err,
                 );
+                if nc.send_congested(peer) {
+                    self.defer_tx_hashes(peer, hashes);
+                }
+            }
+        }
+    }
+
+    // Keeps the tx hashes to announce after the congestion of the peer clears
+    fn defer_tx_hashes(&self, peer: PeerIndex, hashes: Vec<Byte32>) {
+        let mut deferred_tx_hashes = self.deferred_tx_hashes.lock();
+        let deferred = deferred_tx_hashes.entry(peer).or_default();
+        let room = MAX_DEFERRED_TX_HASHES_PER_PEER.saturating_sub(deferred.len());
+        deferred.extend(hashes.into_iter().take(room));
+    }
+
+    // Fetches the txs relayed to the peers accepting the push, which are not larger than the
+    // announce threshold.
+    fn fetch_txs_to_push(
+        &self,
+        selected: &HashMap<PeerIndex, Vec<Byte32>>,
+        push_peers: &HashSet<PeerIndex>,
+    ) -> HashMap<Byte32, packed::RelayTransaction> {
+        if push_peers.is_empty() {
+            return HashMap::default();
+        }
+        let threshold = self
+            .shared
+            .state()
+            .tx_relay_config()
+            .announce_size_threshold
+            .min(MAX_PUSHED_TX_BYTES);
+        let short_ids: HashSet<ProposalShortId> = push_peers
+            .iter()
+            .filter_map(|peer| selected.get(peer))
+            .flatten()
+            .map(ProposalShortId::from_tx_hash)
+            .collect();
+        match self
+            .shared
+            .shared()
+            .tx_pool_controller()
+            .fetch_txs_with_cycles(short_ids)
+        {
+            Ok(txs) => txs
+                .into_iter()
+                .filter(|(_, (tx, _))| tx.data().serialized_size_in_block() <= threshold)
+                .map(|(_, (tx, cycles))| {
+                    let relay_tx = packed::RelayTransaction::new_builder()
+                        .cycles(cycles.pack())
+                        .transaction(tx.data())
+                        .build();
+                    (tx.hash(), relay_tx)
+                })
+                .collect(),
+            Err(err) => {
+                debug_target!(
+                    crate::LOG_TARGET_RELAY,
+                    "relayer tx_pool_controller send fetch_txs_with_cycles error: {:?}",
+                    err,
+                );
+                HashMap::default()
+            }
+        }
+    }
+
+    // Pushes the full bodies of the fetched txs to the peer, and returns the hashes of the rest,
+    // which are still announced by hashes.
+    fn push_transactions(
+        &self,
+        nc: &dyn CKBProtocolContext,
+        peer: PeerIndex,
+        hashes: Vec<Byte32>,
+        pushed_txs: &HashMap<Byte32, packed::RelayTransaction>,
+    ) -> Vec<Byte32> {
+        let mut announced = Vec::new();
+        let mut relay_bytes = 0;
+        let mut relay_txs = Vec::new();
+        for hash in hashes {
+            let tx = match pushed_txs.get(&hash) {
+                Some(tx) => tx.clone(),
+                None => {
+                    announced.push(hash);
+                    continue;
+                }
+            };
+            if relay_bytes + tx.total_size() > MAX_RELAY_TXS_BYTES_PER_BATCH {
+                let txs = std::mem::take(&mut relay_txs);
+                announced.extend(self.send_relay_transactions(nc, peer, txs));
+                relay_bytes = tx.total_size();
+            } else {
+                relay_bytes += tx.total_size();
+            }
+            relay_txs.push(tx);
+        }
+        if !relay_txs.is_empty() {
+            announced.extend(self.send_relay_transactions(nc, peer, relay_txs));
+        }
+        announced
+    }
+
+    // Returns the hashes of the txs not pushed due to the congestion, which are announced instead
+    fn send_relay_transactions(
+        &self,
+        nc: &dyn CKBProtocolContext,
+        peer: PeerIndex,
+        txs: Vec<packed::RelayTransaction>,
+    ) -> Vec<Byte32> {
+        let hashes: Vec<Byte32> = txs
+            .iter()
+            .map(|tx| tx.transaction().calc_tx_hash())
+            .collect();
+        let message = packed::RelayMessage::new_builder()
+            .set(
+                packed::RelayTransactions::new_builder()
+                    .transactions(packed::RelayTransactionVec::new_builder().set(txs).build())
+                    .build(),
+            )
+            .build();
+        let status = send_message_to(nc, peer, &message);
+        if !status.is_ok() {
+            debug_target!(
+                crate::LOG_TARGET_RELAY,
+                "relayer push transactions to {} error: {}",
+                peer,
+                status,
+            );
+            if nc.send_congested(peer) {
+                return hashes;
             }
         }
+        Vec::new()
     }
 }
 
+/// Builds a compact block, prefilling the transactions which peers likely miss.
+///
+/// Besides the cellbase, the prefilled transactions are
+///
+/// * the transactions whose inputs have been replaced by RBF recently, peers which have not
+///   received the replacement still hold the replaced one.
+/// * the transactions which are just proposed in the closest proposal window, peers may
+///   have not fetched them yet.
+///
+/// At most `MAX_PREFILLED_TXS_NUM` transactions are prefilled.
+pub fn build_compact_block(shared: &Shared, block: &BlockView) -> packed::CompactBlock {
+    let prefilled = prefilled_transactions_indexes(shared, block);
+    if let Some(metrics) = ckb_metrics::handle() {
+        metrics
+            .ckb_relay_cb_prefilled_tx_count
+            .inc_by(prefilled.len() as u64);
+    }
+    packed::CompactBlock::build_from_block(block, &prefilled)
+}
+
+fn prefilled_transactions_indexes(shared: &Shared, block: &BlockView) -> HashSet<usize> {
+    let txs = block.transactions();
+    if txs.len() <= 1 {
+        return HashSet::new();
+    }
+
+    let just_proposed: HashSet<ProposalShortId> = {
+        let snapshot = shared.snapshot();
+        let closest = shared.consensus().tx_proposal_window().closest();
+        block
+            .number()
+            .checked_sub(closest)
+            .and_then(|number| snapshot.get_block_hash(number))
+            .and_then(|hash| snapshot.get_block_proposal_txs_ids(&hash))
+            .map(|ids| ids.into_iter().collect())
+            .unwrap_or_default()
+    };
+
+    let recently_replaced = match shared
+        .tx_pool_controller()
+        .filter_recently_replaced(txs[1..].to_vec())
+    {
+        Ok(short_ids) => short_ids,
+        Err(err) => {
+            debug_target!(
+                crate::LOG_TARGET_RELAY,
+                "relayer filter_recently_replaced error: {:?}",
+                err,
+            );
+            HashSet::new()
+        }
+    };
+
+    txs.iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, tx)| {
+            let short_id = tx.proposal_short_id();
+            recently_replaced.contains(&short_id) || just_proposed.contains(&short_id)
+        })
+        .map(|(index, _)| index)
+        .take(MAX_PREFILLED_TXS_NUM)
+        .collect()
+}
+
 fn build_and_broadcast_compact_block(
     nc: &dyn CKBProtocolContext,
     shared: &Shared,
@@ -750,7 +1058,7 @@ fn build_and_broadcast_compact_block(
     );
     let block_hash = block.hash();
     shared.remove_header_view(&block_hash);
-    let cb = packed::CompactBlock::build_from_block(&block, &HashSet::new());
+    let cb = build_compact_block(shared, &block);
     let message = packed::RelayMessage::new_builder().set(cb).build();
 
     let selected_peers: Vec<PeerIndex> = nc
@@ -858,8 +1166,9 @@ impl CKBProtocolHandler for Relayer {
                              too many fields in CompactBlock",
                             peer_index
                         );
-                        nc.ban_peer(
+                        nc.report_misbehavior(
                             peer_index,
+                            &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                             BAD_MESSAGE_BAN_TIME,
                             String::from(
                                 "send us a malformed message: \
@@ -880,8 +1189,9 @@ impl CKBProtocolHandler for Relayer {
                                  too many fields",
                                 peer_index
                             );
-                            nc.ban_peer(
+                            nc.report_misbehavior(
                                 peer_index,
+                                &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                                 BAD_MESSAGE_BAN_TIME,
                                 String::from(
                                     "send us a malformed message \
@@ -899,8 +1209,9 @@ impl CKBProtocolHandler for Relayer {
                     "Peer {} sends us a malformed message",
                     peer_index
                 );
-                nc.ban_peer(
+                nc.report_misbehavior(
                     peer_index,
+                    &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                     BAD_MESSAGE_BAN_TIME,
                     String::from("send us a malformed message"),
                 );
@@ -962,6 +1273,7 @@ impl CKBProtocolHandler for Relayer {
         );
         // Retains all keys in the rate limiter that were used recently enough.
         self.rate_limiter.lock().retain_recent();
+        self.deferred_tx_hashes.lock().remove(&peer_index);
     }
 
     async fn notify(&mut self, nc: Arc<dyn CKBProtocolContext + Sync>, token: u64) {
@@ -993,6 +1305,17 @@ impl CKBProtocolHandler for Relayer {
             RelaySwitch::Ckb2023RelayV3 | RelaySwitch::Ckb2021RelayV2 => (),
         }
 
+        // The tx relay is paused while a deep reorg settles, the pending hashes and the received
+        // relay messages are kept
+        if self.shared.shared().is_reorg_settling()
+            && (token == ASK_FOR_TXS_TOKEN || token == TX_HASHES_TOKEN)
+        {
+            return;
+        }
+        if token == ASK_FOR_TXS_TOKEN && !self.settling_relays.is_empty() {
+            tokio::task::block_in_place(|| self.process_settling_relays(&nc));
+        }
+
         let start_time = Instant::now();
         trace_target!(crate::LOG_TARGET_RELAY, "start notify token={}", token);
         match token {
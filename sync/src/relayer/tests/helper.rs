@@ -1,13 +1,13 @@
 use crate::{Relayer, SyncShared};
-use ckb_app_config::NetworkConfig;
+use ckb_app_config::{NetworkConfig, SyncConfig};
 use ckb_chain::start_chain_services;
 use ckb_chain_spec::consensus::{build_genesis_epoch_ext, ConsensusBuilder};
 use ckb_dao::DaoCalculator;
 use ckb_dao_utils::genesis_dao_data;
 use ckb_network::{
     async_trait, bytes::Bytes as P2pBytes, Behaviour, CKBProtocolContext, Error, Flags,
-    NetworkController, NetworkService, NetworkState, Peer, PeerIndex, ProtocolId, SupportProtocols,
-    TargetSession,
+    NetworkController, NetworkService, NetworkState, Peer, PeerFeatures, PeerIndex, ProtocolId,
+    SupportProtocols, TargetSession,
 };
 use ckb_reward_calculator::RewardCalculator;
 use ckb_shared::{Shared, SharedBuilder, Snapshot};
@@ -133,6 +133,13 @@ pub(crate) fn dummy_network(shared: &Shared) -> NetworkController {
 }
 
 pub(crate) fn build_chain(tip: BlockNumber) -> (Relayer, OutPoint) {
+    build_chain_with_sync_config(tip, Default::default())
+}
+
+pub(crate) fn build_chain_with_sync_config(
+    tip: BlockNumber,
+    sync_config: SyncConfig,
+) -> (Relayer, OutPoint) {
     let (always_success_cell, always_success_cell_data, always_success_script) =
         always_success_cell();
     let always_success_tx = TransactionBuilder::default()
@@ -219,7 +226,7 @@ pub(crate) fn build_chain(tip: BlockNumber) -> (Relayer, OutPoint) {
 
     let sync_shared = Arc::new(SyncShared::new(
         shared,
-        Default::default(),
+        sync_config,
         pack.take_relay_tx_receiver(),
     ));
     (
@@ -299,6 +306,8 @@ pub(crate) fn gen_block(
 pub(crate) struct MockProtocolContext {
     protocol: SupportProtocols,
     sent_messages: RefCell<Vec<(ProtocolId, PeerIndex, P2pBytes)>>,
+    connected_peers: RefCell<Vec<PeerIndex>>,
+    congested_peers: RefCell<HashSet<PeerIndex>>,
 }
 
 // test mock context with single thread
@@ -311,9 +320,27 @@ impl MockProtocolContext {
         Self {
             protocol,
             sent_messages: Default::default(),
+            connected_peers: Default::default(),
+            congested_peers: Default::default(),
+        }
+    }
+
+    pub(crate) fn connect(&self, peer_index: PeerIndex) {
+        self.connected_peers.borrow_mut().push(peer_index);
+    }
+
+    pub(crate) fn set_congested(&self, peer_index: PeerIndex, congested: bool) {
+        if congested {
+            self.congested_peers.borrow_mut().insert(peer_index);
+        } else {
+            self.congested_peers.borrow_mut().remove(&peer_index);
         }
     }
 
+    pub(crate) fn sent_count(&self) -> usize {
+        self.sent_messages.borrow().len()
+    }
+
     pub(crate) fn has_sent(
         &self,
         protocol_id: ProtocolId,
@@ -433,8 +460,15 @@ impl CKBProtocolContext for MockProtocolContext {
         self.send_message(protocol_id, peer_index, data)
     }
 
-    fn filter_broadcast(&self, _target: TargetSession, _data: P2pBytes) -> Result<(), Error> {
-        unimplemented!();
+    fn filter_broadcast(&self, target: TargetSession, data: P2pBytes) -> Result<(), Error> {
+        match target {
+            TargetSession::Single(peer_index) => self.send_message_to(peer_index, data),
+            _ => unimplemented!(),
+        }
+    }
+
+    fn send_congested(&self, peer_index: PeerIndex) -> bool {
+        self.congested_peers.borrow().contains(&peer_index)
     }
     fn disconnect(&self, _peer_index: PeerIndex, _message: &str) -> Result<(), Error> {
         unimplemented!();
@@ -446,7 +480,10 @@ impl CKBProtocolContext for MockProtocolContext {
         unimplemented!();
     }
     fn connected_peers(&self) -> Vec<PeerIndex> {
-        vec![]
+        self.connected_peers.borrow().clone()
+    }
+    fn peer_supports(&self, _peer_index: PeerIndex, _features: PeerFeatures) -> bool {
+        false
     }
     fn report_peer(&self, _peer_index: PeerIndex, _behaviour: Behaviour) {
         unimplemented!();
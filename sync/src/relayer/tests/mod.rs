@@ -8,3 +8,5 @@ mod get_block_proposal_process;
 mod get_transactions_process;
 pub(crate) mod helper;
 mod reconstruct_block;
+mod transactions_process;
+mod tx_hashes;
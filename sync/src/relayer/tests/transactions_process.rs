@@ -0,0 +1,105 @@
+use crate::relayer::tests::helper::{
+    build_chain, build_chain_with_sync_config, new_transaction, MockProtocolContext,
+};
+use crate::relayer::transactions_process::TransactionsProcess;
+use crate::relayer::{Relayer, MAX_PUSHED_TX_BYTES};
+use crate::Status;
+use ckb_app_config::SyncConfig;
+use ckb_network::{CKBProtocolContext, PeerIndex, SupportProtocols};
+use ckb_types::{bytes::Bytes, core::TransactionView, packed, prelude::*};
+use std::sync::Arc;
+
+fn build_chain_accepting_push() -> (Relayer, packed::OutPoint) {
+    let mut sync_config = SyncConfig::default();
+    sync_config.tx_relay.accept_push = true;
+    build_chain_with_sync_config(5, sync_config)
+}
+
+// A tx whose serialized size is about `size` bytes.
+fn tx_of_size(relayer: &Relayer, out_point: &packed::OutPoint, size: usize) -> TransactionView {
+    new_transaction(relayer, 1, out_point)
+        .as_advanced_builder()
+        .witness(Bytes::from(vec![0u8; size]).pack())
+        .build()
+}
+
+fn push(relayer: &Relayer, tx: &TransactionView) -> Status {
+    let content = packed::RelayTransactions::new_builder()
+        .transactions(
+            packed::RelayTransactionVec::new_builder()
+                .push(
+                    packed::RelayTransaction::new_builder()
+                        .transaction(tx.data())
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+    let nc = Arc::new(MockProtocolContext::new(SupportProtocols::RelayV3));
+    let peer_index: PeerIndex = 1.into();
+    TransactionsProcess::new(content.as_reader(), relayer, nc, peer_index).execute()
+}
+
+#[test]
+fn test_ignore_unrequested_txs() {
+    let (relayer, out_point) = build_chain(5);
+    let tx = tx_of_size(&relayer, &out_point, 100);
+
+    assert!(push(&relayer, &tx).is_ok());
+    assert!(!relayer.shared().state().already_known_tx(&tx.hash()));
+}
+
+#[test]
+fn test_accept_pushed_txs_larger_than_own_threshold() {
+    let (relayer, out_point) = build_chain_accepting_push();
+    let threshold = relayer
+        .shared()
+        .state()
+        .tx_relay_config()
+        .announce_size_threshold;
+    // the peer announces the txs by a larger threshold
+    let tx = tx_of_size(&relayer, &out_point, threshold * 2);
+
+    assert!(push(&relayer, &tx).is_ok());
+    assert!(relayer.shared().state().already_known_tx(&tx.hash()));
+}
+
+#[test]
+fn test_ignore_pushed_txs_over_hard_limit() {
+    let (relayer, out_point) = build_chain_accepting_push();
+    let tx = tx_of_size(&relayer, &out_point, MAX_PUSHED_TX_BYTES);
+
+    assert!(push(&relayer, &tx).is_ok());
+    assert!(!relayer.shared().state().already_known_tx(&tx.hash()));
+}
+
+#[test]
+fn test_process_txs_received_while_reorg_settles() {
+    let (mut relayer, out_point) = build_chain_accepting_push();
+    let tx = tx_of_size(&relayer, &out_point, 100);
+    let content = packed::RelayTransactions::new_builder()
+        .transactions(
+            packed::RelayTransactionVec::new_builder()
+                .push(
+                    packed::RelayTransaction::new_builder()
+                        .transaction(tx.data())
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+    let message = packed::RelayMessage::new_builder().set(content).build();
+    let nc: Arc<dyn CKBProtocolContext + Sync> =
+        Arc::new(MockProtocolContext::new(SupportProtocols::RelayV2));
+    let peer_index: PeerIndex = 1.into();
+
+    relayer.shared().shared().set_reorg_settling(true);
+    let status = relayer.try_process(Arc::clone(&nc), peer_index, message.as_reader().to_enum());
+    assert!(status.is_ok());
+    assert!(!relayer.shared().state().already_known_tx(&tx.hash()));
+
+    // the kept txs are processed after the reorg settles
+    relayer.shared().shared().set_reorg_settling(false);
+    relayer.process_settling_relays(&nc);
+    assert!(relayer.shared().state().already_known_tx(&tx.hash()));
+}
@@ -0,0 +1,36 @@
+use crate::relayer::tests::helper::{build_chain, MockProtocolContext};
+use ckb_network::{PeerIndex, SupportProtocols};
+use ckb_types::{packed, prelude::*};
+
+#[test]
+fn test_defer_tx_hashes_to_congested_peers() {
+    let (relayer, _) = build_chain(5);
+    let nc = MockProtocolContext::new(SupportProtocols::RelayV2);
+    let protocol_id = SupportProtocols::RelayV2.protocol_id();
+    let (congested, other): (PeerIndex, PeerIndex) = (1.into(), 2.into());
+    nc.connect(congested);
+    nc.connect(other);
+    nc.set_congested(congested, true);
+
+    let hashes = vec![packed::Byte32::new([1; 32])];
+    relayer.defer_tx_hashes(congested, hashes.clone());
+    relayer.defer_tx_hashes(other, hashes.clone());
+    let message = packed::RelayMessage::new_builder()
+        .set(
+            packed::RelayTransactionHashes::new_builder()
+                .tx_hashes(hashes.pack())
+                .build(),
+        )
+        .build();
+
+    // the hashes to the congested peer are kept
+    relayer.send_bulk_of_tx_hashes(&nc);
+    assert!(nc.has_sent(protocol_id, other, message.as_bytes()));
+    assert!(!nc.has_sent(protocol_id, congested, message.as_bytes()));
+
+    // and announced after the congestion clears
+    nc.set_congested(congested, false);
+    relayer.send_bulk_of_tx_hashes(&nc);
+    assert!(nc.has_sent(protocol_id, congested, message.as_bytes()));
+    assert_eq!(nc.sent_count(), 2);
+}
@@ -1,4 +1,4 @@
-use crate::relayer::Relayer;
+use crate::relayer::{Relayer, MAX_PUSHED_TX_BYTES};
 use crate::Status;
 use ckb_logger::error;
 use ckb_network::{CKBProtocolContext, PeerIndex};
@@ -37,10 +37,13 @@ impl<'a> TransactionsProcess<'a> {
     pub fn execute(self) -> Status {
         let shared_state = self.relayer.shared().state();
         let txs: Vec<(TransactionView, Cycle)> = {
-            // ignore the tx if it's already known or it has never been requested before
+            // ignore the tx if it's already known or it has never been requested before, unless
+            // it's pushed to the node accepting the push. The peers may announce the txs by a
+            // different threshold, so the pushed ones are taken up to the hard limit.
             let mut tx_filter = shared_state.tx_filter();
             tx_filter.remove_expired();
             let unknown_tx_hashes = shared_state.unknown_tx_hashes();
+            let tx_relay_config = shared_state.tx_relay_config();
 
             self.message
                 .transactions()
@@ -52,11 +55,14 @@ impl<'a> TransactionsProcess<'a> {
                     )
                 })
                 .filter(|(tx, _)| {
+                    let pushed = tx_relay_config.accept_push
+                        && tx.data().serialized_size_in_block() <= MAX_PUSHED_TX_BYTES;
                     !tx_filter.contains(&tx.hash())
-                        && unknown_tx_hashes
-                            .get_priority(&tx.hash())
-                            .map(|priority| priority.requesting_peer() == Some(self.peer))
-                            .unwrap_or_default()
+                        && (pushed
+                            || unknown_tx_hashes
+                                .get_priority(&tx.hash())
+                                .map(|priority| priority.requesting_peer() == Some(self.peer))
+                                .unwrap_or_default())
                 })
                 .collect()
         };
@@ -70,8 +76,9 @@ impl<'a> TransactionsProcess<'a> {
             .iter()
             .any(|(_, declared_cycles)| declared_cycles > &max_block_cycles)
         {
-            self.nc.ban_peer(
+            self.nc.report_misbehavior(
                 self.peer,
+                "RelayDeclaredCyclesExceedLimit",
                 DEFAULT_BAN_TIME,
                 String::from("relay declared cycles greater than max_block_cycles"),
             );
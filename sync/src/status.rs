@@ -127,6 +127,12 @@ impl StatusCode {
     pub fn name(self) -> String {
         format!("{:?}({})", self, self as u16)
     }
+
+    /// The misbehavior kind reported to the network, whose weight is configured by
+    /// `network.misbehavior.weights`
+    pub fn misbehavior_kind(self) -> String {
+        format!("{self:?}")
+    }
 }
 
 /// Process message status
@@ -3,7 +3,7 @@ use crate::types::post_sync_process;
 use crate::StatusCode;
 use ckb_chain::RemoteBlock;
 use ckb_error::is_internal_db_error;
-use ckb_logger::debug;
+use ckb_logger::{debug, error};
 use ckb_network::{CKBProtocolContext, PeerIndex};
 use ckb_types::packed::Byte32;
 use ckb_types::{packed, prelude::*};
@@ -40,6 +40,20 @@ impl<'a> BlockProcess<'a> {
         );
         let shared = self.synchronizer.shared();
 
+        if shared.shared().is_missing_block_body(&block.hash()) {
+            return match shared.shared().repair_block_body(&block) {
+                Ok(true) => crate::Status::ok(),
+                Ok(false) => StatusCode::BlockIsInvalid.with_context(format!(
+                    "block {} body does not match its header",
+                    block.hash()
+                )),
+                Err(err) => {
+                    error!("repair the body of block {} failed: {}", block.hash(), err);
+                    crate::Status::ignored()
+                }
+            };
+        }
+
         if shared.new_block_received(&block) {
             let verify_callback = {
                 let nc: Arc<dyn CKBProtocolContext + Sync> = Arc::clone(&self.nc);
@@ -5,6 +5,7 @@ use ckb_constant::sync::{INIT_BLOCKS_IN_TRANSIT_PER_PEER, MAX_HEADERS_LEN};
 use ckb_logger::debug;
 use ckb_network::{CKBProtocolContext, PeerIndex};
 use ckb_shared::block_status::BlockStatus;
+use ckb_store::ChainStore;
 use ckb_types::{packed, prelude::*};
 use std::collections::HashSet;
 
@@ -65,6 +66,32 @@ impl<'a> GetBlocksProcess<'a> {
                 continue;
             }
 
+            if self
+                .synchronizer
+                .shared()
+                .shared()
+                .is_missing_block_body(&block_hash)
+            {
+                debug!(
+                    "Stopping getblocks, since the body of {} is missing",
+                    block_hash
+                );
+                break;
+            }
+
+            if self
+                .synchronizer
+                .shared()
+                .store()
+                .is_uncles_and_proposals_pruned(&block_hash)
+            {
+                debug!(
+                    "Stopping getblocks, since the uncles and proposals of {} are pruned",
+                    block_hash
+                );
+                break;
+            }
+
             if let Some(block) = active_chain.get_block(&block_hash) {
                 debug!(
                     "respond_block {} {} to peer {:?}",
@@ -8,9 +8,12 @@ use ckb_network::{CKBProtocolContext, PeerIndex};
 use ckb_shared::block_status::BlockStatus;
 use ckb_traits::HeaderFieldsProvider;
 use ckb_types::{core, packed, prelude::*};
-use ckb_verification::{HeaderError, HeaderVerifier};
+use ckb_verification::{BatchPowVerifier, HeaderError, HeaderVerifier};
 use ckb_verification_traits::Verifier;
 
+// Fewer headers are not worth the overhead of the parallel PoW checks.
+const MIN_BATCH_POW_HEADERS: usize = 16;
+
 pub struct HeadersProcess<'a> {
     message: packed::SendHeadersReader<'a>,
     synchronizer: &'a Synchronizer,
@@ -52,13 +55,51 @@ impl<'a> HeadersProcess<'a> {
         true
     }
 
-    pub fn accept_first(&self, first: &core::HeaderView) -> ValidationResult {
+    pub fn accept_first(&self, first: &core::HeaderView, pow_verified: bool) -> ValidationResult {
         let shared: &SyncShared = self.synchronizer.shared();
-        let verifier = HeaderVerifier::new(shared, shared.consensus());
+        let mut verifier = HeaderVerifier::new(shared, shared.consensus());
+        if pow_verified {
+            verifier = verifier.pow_verified();
+        }
         let acceptor = HeaderAcceptor::new(first, self.peer, verifier, self.active_chain.clone());
         acceptor.accept()
     }
 
+    // Checks the PoW of the continuous headers in parallel during IBD, returns how many leading
+    // headers have valid PoW. The header with invalid PoW is rejected when it's accepted in order.
+    //
+    // The batch is only hashed once it links to a known valid header, the headers failing the
+    // cheap linkage checks are rejected one by one without hashing the whole batch.
+    pub(crate) fn batch_verify_pow(&self, headers: &[core::HeaderView]) -> usize {
+        if !self.active_chain.is_initial_block_download() || headers.len() < MIN_BATCH_POW_HEADERS {
+            return 0;
+        }
+        let parent_hash = headers[0].parent_hash();
+        if self
+            .active_chain
+            .contains_block_status(&parent_hash, BlockStatus::BLOCK_INVALID)
+            || self
+                .synchronizer
+                .shared()
+                .get_header_fields(&parent_hash)
+                .is_none()
+        {
+            return 0;
+        }
+        // the same headers may have been received from other peers and verified already
+        let last = headers.last().expect("empty checked");
+        if self
+            .active_chain
+            .contains_block_status(&last.hash(), BlockStatus::HEADER_VALID)
+        {
+            return 0;
+        }
+        let consensus = self.synchronizer.shared().consensus();
+        self.synchronizer
+            .pow_pool()
+            .install(|| BatchPowVerifier::new(headers, consensus.pow_engine().as_ref()).verify())
+    }
+
     fn debug(&self) {
         if log_enabled!(Level::Debug) {
             // Regain the updated best known
@@ -128,7 +169,8 @@ impl<'a> HeadersProcess<'a> {
             return StatusCode::HeadersIsInvalid.with_context("not continuous");
         }
 
-        let result = self.accept_first(&headers[0]);
+        let pow_verified = self.batch_verify_pow(&headers);
+        let result = self.accept_first(&headers[0], pow_verified > 0);
         match result.state {
             ValidationState::Invalid => {
                 debug!(
@@ -150,8 +192,11 @@ impl<'a> HeadersProcess<'a> {
             }
         };
 
-        for header in headers.iter().skip(1) {
-            let verifier = HeaderVerifier::new(shared, consensus);
+        for (index, header) in headers.iter().enumerate().skip(1) {
+            let mut verifier = HeaderVerifier::new(shared, consensus);
+            if index < pow_verified {
+                verifier = verifier.pow_verified();
+            }
             let acceptor =
                 HeaderAcceptor::new(header, self.peer, verifier, self.active_chain.clone());
             let result = acceptor.accept();
@@ -51,7 +51,7 @@ use ckb_types::{
 };
 use std::{
     collections::HashSet,
-    sync::{atomic::Ordering, Arc},
+    sync::{atomic::Ordering, Arc, OnceLock},
     time::{Duration, Instant},
 };
 
@@ -59,11 +59,13 @@ pub const SEND_GET_HEADERS_TOKEN: u64 = 0;
 pub const IBD_BLOCK_FETCH_TOKEN: u64 = 1;
 pub const NOT_IBD_BLOCK_FETCH_TOKEN: u64 = 2;
 pub const TIMEOUT_EVICTION_TOKEN: u64 = 3;
+pub const REPAIR_BLOCK_FETCH_TOKEN: u64 = 4;
 pub const NO_PEER_CHECK_TOKEN: u64 = 255;
 
 const SYNC_NOTIFY_INTERVAL: Duration = Duration::from_secs(1);
 const IBD_BLOCK_FETCH_INTERVAL: Duration = Duration::from_millis(40);
 const NOT_IBD_BLOCK_FETCH_INTERVAL: Duration = Duration::from_millis(200);
+const REPAIR_BLOCK_FETCH_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Copy, Clone)]
 enum CanStart {
@@ -243,6 +245,9 @@ impl BlockFetchCMD {
                         // Blocks that are no longer in the scope of ibd must be forced to verify
                         if unix_time_as_millis().saturating_sub(header.timestamp()) < MAX_TIP_AGE {
                             assume_valid_target.take();
+                            shared.record_full_verification_resumed(
+                                shared.snapshot().tip_number() + 1,
+                            );
                             warn!("the duration gap between 'assume valid target' and 'now' is less than 24h; CKB will ignore the specified assume valid target and do full verification from now on");
                         }
                     }
@@ -255,6 +260,9 @@ impl BlockFetchCMD {
                             warn!("the duration gap between 'shared_best_header' and 'now' is less than 24h, but CKB haven't found the assume valid target in header_map; CKB will ignore the specified assume valid target and do full verification from now on");
                             *flag = CanStart::Ready;
                             assume_valid_target.take();
+                            shared.record_full_verification_resumed(
+                                shared.snapshot().tip_number() + 1,
+                            );
                         }
                     }
                 }
@@ -302,6 +310,8 @@ pub struct Synchronizer {
     /// Sync shared state
     pub shared: Arc<SyncShared>,
     fetch_channel: Option<channel::Sender<FetchCMD>>,
+    // checks the PoW of the header batches, see `HeadersProcess`, built on first use
+    pow_pool: OnceLock<rayon::ThreadPool>,
 }
 
 impl Synchronizer {
@@ -313,6 +323,7 @@ impl Synchronizer {
             chain,
             shared,
             fetch_channel: None,
+            pow_pool: OnceLock::new(),
         }
     }
 
@@ -321,6 +332,17 @@ impl Synchronizer {
         &self.shared
     }
 
+    /// The dedicated workers checking the PoW of the header batches, so the hashing neither
+    /// waits for nor blocks the jobs on the global rayon pool
+    pub(crate) fn pow_pool(&self) -> &rayon::ThreadPool {
+        self.pow_pool.get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .thread_name(|i| format!("HeaderPow-{i}"))
+                .build()
+                .expect("Init the thread pool for the header PoW failed")
+        })
+    }
+
     fn try_process(
         &self,
         nc: Arc<dyn CKBProtocolContext + Sync>,
@@ -778,6 +800,24 @@ impl Synchronizer {
         }
     }
 
+    // Download again the bodies of the main chain blocks which the integrity check found missing,
+    // each peer is asked for a different batch
+    fn fetch_missing_block_bodies(&self, nc: &dyn CKBProtocolContext) {
+        let missing = self.shared.shared().missing_block_bodies();
+        if missing.is_empty() {
+            return;
+        }
+        let peers: Vec<PeerIndex> = self.peers().state.iter().map(|kv| *kv.key()).collect();
+        for (hashes, peer) in missing.chunks(INIT_BLOCKS_IN_TRANSIT_PER_PEER).zip(peers) {
+            debug!(
+                "fetch {} missing block bodies from peer={}",
+                hashes.len(),
+                peer
+            );
+            self.send_getblocks(hashes.to_vec(), nc, peer);
+        }
+    }
+
     fn send_getblocks(
         &self,
         v_fetch: Vec<packed::Byte32>,
@@ -810,6 +850,9 @@ impl CKBProtocolHandler for Synchronizer {
         nc.set_notify(NOT_IBD_BLOCK_FETCH_INTERVAL, NOT_IBD_BLOCK_FETCH_TOKEN)
             .await
             .expect("set_notify at init is ok");
+        nc.set_notify(REPAIR_BLOCK_FETCH_INTERVAL, REPAIR_BLOCK_FETCH_TOKEN)
+            .await
+            .expect("set_notify at init is ok");
         nc.set_notify(Duration::from_secs(2), NO_PEER_CHECK_TOKEN)
             .await
             .expect("set_notify at init is ok");
@@ -831,8 +874,9 @@ impl CKBProtocolHandler for Synchronizer {
                              excessive fields detected in SendBlock",
                             peer_index
                         );
-                        nc.ban_peer(
+                        nc.report_misbehavior(
                             peer_index,
+                            &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                             BAD_MESSAGE_BAN_TIME,
                             String::from(
                                 "send us a malformed message: \
@@ -852,8 +896,9 @@ impl CKBProtocolHandler for Synchronizer {
                                  excessive fields",
                                 peer_index
                             );
-                            nc.ban_peer(
+                            nc.report_misbehavior(
                                 peer_index,
+                                &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                                 BAD_MESSAGE_BAN_TIME,
                                 String::from(
                                     "send us a malformed message: \
@@ -867,8 +912,9 @@ impl CKBProtocolHandler for Synchronizer {
             }
             _ => {
                 info!("A malformed message from peer {}", peer_index);
-                nc.ban_peer(
+                nc.report_misbehavior(
                     peer_index,
+                    &StatusCode::ProtocolMessageIsMalformed.misbehavior_kind(),
                     BAD_MESSAGE_BAN_TIME,
                     String::from("send us a malformed message"),
                 );
@@ -946,6 +992,9 @@ impl CKBProtocolHandler for Synchronizer {
                 TIMEOUT_EVICTION_TOKEN => {
                     self.eviction(nc.as_ref());
                 }
+                REPAIR_BLOCK_FETCH_TOKEN => {
+                    self.fetch_missing_block_bodies(nc.as_ref());
+                }
                 // Here is just for NO_PEER_CHECK_TOKEN token, only handle it when there is no peer.
                 _ => {}
             }
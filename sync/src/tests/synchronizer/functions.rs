@@ -8,6 +8,7 @@ use ckb_network::{
     SessionType, TargetSession,
 };
 use ckb_reward_calculator::RewardCalculator;
+use ckb_shared::block_status::BlockStatus;
 use ckb_shared::types::HeaderIndex;
 use ckb_shared::{Shared, SharedBuilder, Snapshot};
 use ckb_store::ChainStore;
@@ -708,6 +709,62 @@ fn test_sync_process() {
     );
 }
 
+#[test]
+fn test_batch_verify_pow_after_linkage() {
+    let (chain_controller, shared, _) = start_chain(None);
+    let num = 20;
+    for i in 1..=num {
+        insert_block(&chain_controller, &shared, u128::from(i), i);
+    }
+    let headers: Vec<HeaderView> = (1..=num)
+        .map(|i| {
+            let hash = shared.store().get_block_hash(i).unwrap();
+            shared.store().get_block_header(&hash).unwrap()
+        })
+        .collect();
+    let send_headers = |headers: &[HeaderView]| {
+        SendHeadersBuilder::default()
+            .headers(headers.iter().map(|h| h.data()).pack())
+            .build()
+    };
+    let mock_nc = mock_network_context(1);
+    let peer: PeerIndex = 0.into();
+
+    // the batch doesn't link to a known header, it's rejected without hashing the whole batch
+    {
+        let (_, _, synchronizer) = start_chain(None);
+        synchronizer.on_connected(&mock_nc, peer);
+        let active_chain = synchronizer.shared.active_chain();
+        assert!(active_chain.is_initial_block_download());
+
+        let unlinked = &headers[1..];
+        let message = send_headers(unlinked);
+        let process = HeadersProcess::new(message.as_reader(), &synchronizer, peer, &mock_nc);
+        assert_eq!(process.batch_verify_pow(unlinked), 0);
+        assert_eq!(process.execute().code(), StatusCode::HeadersIsInvalid);
+        assert!(unlinked.iter().all(|header| {
+            !active_chain.contains_block_status(&header.hash(), BlockStatus::HEADER_VALID)
+        }));
+    }
+
+    // the batch links to the genesis, its PoW is checked by the dedicated workers
+    {
+        let (_, _, synchronizer) = start_chain(None);
+        synchronizer.on_connected(&mock_nc, peer);
+
+        let message = send_headers(&headers);
+        let process = HeadersProcess::new(message.as_reader(), &synchronizer, peer, &mock_nc);
+        assert_eq!(process.batch_verify_pow(&headers), headers.len());
+        assert_eq!(process.execute(), Status::ok());
+        let active_chain = synchronizer.shared.active_chain();
+        assert!(headers.iter().all(|header| {
+            active_chain.contains_block_status(&header.hash(), BlockStatus::HEADER_VALID)
+        }));
+        let best_known_header = synchronizer.peers().get_best_known_header(peer).unwrap();
+        assert_eq!(best_known_header.hash(), headers.last().unwrap().hash());
+    }
+}
+
 #[test]
 fn test_header_sync_timeout() {
     let _faketime_guard = ckb_systemtime::faketime();
@@ -1,5 +1,5 @@
 use crate::{Status, StatusCode, FAST_INDEX, LOW_INDEX, NORMAL_INDEX, TIME_TRACE_SIZE};
-use ckb_app_config::SyncConfig;
+use ckb_app_config::{SyncConfig, TxRelayConfig};
 #[cfg(test)]
 use ckb_chain::VerifyResult;
 use ckb_chain::{ChainController, RemoteBlock};
@@ -1023,6 +1023,7 @@ impl SyncShared {
             inflight_blocks: RwLock::new(InflightBlocks::default()),
             pending_get_headers: RwLock::new(LruCache::new(GET_HEADERS_CACHE_SIZE)),
             tx_relay_receiver,
+            tx_relay_config: sync_config.tx_relay,
             min_chain_work: sync_config.min_chain_work,
         };
 
@@ -1329,10 +1330,15 @@ pub struct SyncState {
 
     /* cached for sending bulk */
     tx_relay_receiver: Receiver<TxVerificationResult>,
+    tx_relay_config: TxRelayConfig,
     min_chain_work: U256,
 }
 
 impl SyncState {
+    pub fn tx_relay_config(&self) -> &TxRelayConfig {
+        &self.tx_relay_config
+    }
+
     pub fn min_chain_work(&self) -> &U256 {
         &self.min_chain_work
     }
@@ -2000,7 +2006,12 @@ pub(crate) fn post_sync_process(
             "Receive {} from {}. Ban {:?} for {}",
             item_name, peer, ban_time, status
         );
-        nc.ban_peer(peer, ban_time, status.to_string());
+        nc.report_misbehavior(
+            peer,
+            &status.code().misbehavior_kind(),
+            ban_time,
+            status.to_string(),
+        );
     } else if status.should_warn() {
         warn!("Receive {} from {}, {}", item_name, peer, status);
     } else if !status.is_ok() {
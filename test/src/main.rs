@@ -446,6 +446,7 @@ fn all_specs() -> Vec<Box<dyn Spec>> {
         Box::new(PoolResurrect),
         Box::new(PoolResolveConflictAfterReorg),
         Box::new(InvalidHeaderDep),
+        Box::new(RecentHeaderDep),
         // TODO fix this on windows platform
         #[cfg(not(target_os = "windows"))]
         Box::new(PoolPersisted),
@@ -474,6 +475,8 @@ fn all_specs() -> Vec<Box<dyn Spec>> {
         Box::new(RelayWithWrongTx::new()),
         Box::new(TxsRelayOrder),
         Box::new(SendTxChain),
+        Box::new(SendTxPackage),
+        Box::new(SendTxPackageFullPool),
         Box::new(SendTxChainRevOrder),
         Box::new(TxPoolEntryStatus),
         Box::new(DifferentTxsWithSameInputWithOutRBF),
@@ -482,6 +485,7 @@ fn all_specs() -> Vec<Box<dyn Spec>> {
         Box::new(RbfSameInput),
         Box::new(RbfOnlyForResolveDead),
         Box::new(RbfSameInputwithLessFee),
+        Box::new(RbfSimulateReplacement),
         Box::new(RbfTooManyDescendants),
         Box::new(RbfContainNewTx),
         Box::new(RbfContainInvalidInput),
@@ -489,6 +493,8 @@ fn all_specs() -> Vec<Box<dyn Spec>> {
         Box::new(RbfContainInvalidCells),
         Box::new(RbfRejectReplaceProposed),
         Box::new(RbfReplaceProposedSuccess),
+        Box::new(RbfReplaceProposedDisallowed),
+        Box::new(RbfTooManyConflictDescendants),
         Box::new(RbfConcurrency),
         Box::new(RbfCellDepsCheck),
         Box::new(CompactBlockEmpty),
@@ -554,6 +560,7 @@ fn all_specs() -> Vec<Box<dyn Spec>> {
         Box::new(SubmitTransactionWhenItsParentInProposed),
         Box::new(ProposeTransactionButParentNot),
         Box::new(ProposalExpireRuleForCommittingAndExpiredAtOneTime),
+        Box::new(SimulateProposalWindow),
         Box::new(ReorgHandleProposals),
         Box::new(TransactionHashCollisionDifferentWitnessHashes),
         Box::new(DuplicatedTransaction),
@@ -659,6 +659,7 @@ impl Node {
                 &self.working_dir().to_string_lossy(),
                 "run",
                 "--ba-advanced",
+                "--skip-preflight",
             ])
             .stdin(Stdio::null())
             .stdout(Stdio::null())
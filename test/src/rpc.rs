@@ -7,9 +7,9 @@ use ckb_error::AnyError;
 use ckb_jsonrpc_types::{
     Alert, BannedAddr, Block, BlockEconomicState, BlockFilter, BlockNumber, BlockTemplate,
     BlockView, Capacity, CellWithStatus, ChainInfo, EpochNumber, EpochView, EstimateCycles,
-    HeaderView, LocalNode, OutPoint, PoolTxDetailInfo, RawTxPool, RemoteNode, SyncState, Timestamp,
-    Transaction, TransactionProof, TransactionWithStatusResponse, TxPoolInfo, Uint32, Uint64,
-    Version,
+    HeaderView, LocalNode, OutPoint, PoolTxDetailInfo, ProposalWindowSimulation, RawTxPool,
+    RemoteNode, ReplacementSimulation, SyncState, Timestamp, Transaction, TransactionProof,
+    TransactionWithStatusResponse, TxPoolInfo, Uint32, Uint64, Version,
 };
 use ckb_types::core::{
     BlockNumber as CoreBlockNumber, Capacity as CoreCapacity, EpochNumber as CoreEpochNumber,
@@ -96,6 +96,18 @@ impl RpcClient {
             .expect("rpc call get_transaction_tx_pool_details")
     }
 
+    pub fn simulate_replacement(&self, tx: Transaction) -> ReplacementSimulation {
+        self.inner
+            .simulate_replacement(tx)
+            .expect("rpc call simulate_replacement")
+    }
+
+    pub fn simulate_proposal_window(&self, tx: Transaction) -> ProposalWindowSimulation {
+        self.inner
+            .simulate_proposal_window(tx)
+            .expect("rpc call simulate_proposal_window")
+    }
+
     pub fn get_block_hash(&self, number: CoreBlockNumber) -> Option<Byte32> {
         self.inner
             .get_block_hash(number.into())
@@ -224,6 +236,14 @@ impl RpcClient {
             .send_transaction(tx, Some("passthrough".to_string()))
     }
 
+    pub fn send_transaction_package_result(
+        &self,
+        txs: Vec<Transaction>,
+    ) -> Result<Vec<H256>, AnyError> {
+        self.inner
+            .send_transaction_package(txs, Some("passthrough".to_string()))
+    }
+
     pub fn remove_transaction(&self, tx_hash: Byte32) -> bool {
         self.inner
             .remove_transaction(tx_hash.unpack())
@@ -361,6 +381,7 @@ jsonrpc!(
     pub fn get_block_median_time(&self, block_hash: H256) -> Option<Timestamp>;
     pub fn estimate_cycles(&self, _tx: Transaction) -> EstimateCycles;
     pub fn send_transaction(&self, tx: Transaction, outputs_validator: Option<String>) -> H256;
+    pub fn send_transaction_package(&self, transactions: Vec<Transaction>, outputs_validator: Option<String>) -> Vec<H256>;
     pub fn remove_transaction(&self, tx_hash: H256) -> bool;
     pub fn tx_pool_info(&self) -> TxPoolInfo;
     pub fn get_raw_tx_pool(&self, verbose: Option<bool>) -> RawTxPool;
@@ -381,4 +402,6 @@ jsonrpc!(
     pub fn notify_transaction(&self, tx: Transaction) -> H256;
     pub fn tx_pool_ready(&self) -> bool;
     pub fn get_pool_tx_detail_info(&self, _hash: H256) -> PoolTxDetailInfo;
+    pub fn simulate_replacement(&self, tx: Transaction) -> ReplacementSimulation;
+    pub fn simulate_proposal_window(&self, tx: Transaction) -> ProposalWindowSimulation;
 });
@@ -0,0 +1,61 @@
+use crate::node::waiting_for_sync;
+use crate::{Node, Spec};
+use ckb_logger::info;
+use ckb_types::prelude::*;
+
+const MIN_HEADER_DEP_DEPTH: u64 = 2;
+
+pub struct RecentHeaderDep;
+
+impl Spec for RecentHeaderDep {
+    crate::setup!(num_nodes: 3);
+
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node0 = &nodes[0];
+        let node1 = &nodes[1];
+        let node2 = &nodes[2];
+
+        node0.mine_until_out_bootstrap_period();
+        node0.connect(node1);
+        waiting_for_sync(&[node0, node1]);
+        node0.disconnect(node1);
+
+        info!("Reject the tx depending on the tip header");
+        let header_dep = node0.get_tip_block().hash();
+        let tx = node0
+            .new_transaction_spend_tip_cellbase()
+            .as_advanced_builder()
+            .set_header_deps(vec![header_dep])
+            .build();
+        let result = node0.rpc_client().send_transaction_result(tx.data().into());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("PoolRejectedRecentHeaderDep"));
+
+        info!("Accept the tx once the header dep is deep enough");
+        node0.mine(MIN_HEADER_DEP_DEPTH);
+        node0.submit_transaction(&tx);
+        node0.assert_tx_pool_size(1, 0);
+
+        info!("Keep the tx after a reorg above the header dep");
+        node1.mine(MIN_HEADER_DEP_DEPTH + 2);
+        node0.connect(node1);
+        waiting_for_sync(&[node0, node1]);
+        node0.wait_for_tx_pool();
+        node0.assert_tx_pool_size(1, 0);
+
+        info!("Remove the tx after a reorg detaching the header dep");
+        node2.mine_until_out_bootstrap_period();
+        node2.mine(node0.get_tip_block_number() + 2 - node2.get_tip_block_number());
+        node0.connect(node2);
+        waiting_for_sync(&[node0, node2]);
+        node0.wait_for_tx_pool();
+        node0.assert_tx_pool_size(0, 0);
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.min_header_dep_depth = Some(MIN_HEADER_DEP_DEPTH);
+    }
+}
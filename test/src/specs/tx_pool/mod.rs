@@ -6,6 +6,7 @@ mod depend_tx_in_same_block;
 mod descendant;
 mod different_txs_with_same_input;
 mod get_raw_tx_pool;
+mod header_dep_depth;
 mod limit;
 mod orphan_tx;
 mod pool_persisted;
@@ -33,6 +34,7 @@ pub use depend_tx_in_same_block::*;
 pub use descendant::*;
 pub use different_txs_with_same_input::*;
 pub use get_raw_tx_pool::*;
+pub use header_dep_depth::*;
 pub use limit::*;
 pub use orphan_tx::*;
 pub use pool_persisted::*;
@@ -1,6 +1,6 @@
 use crate::{Node, Spec};
 use ckb_logger::info;
-use ckb_types::core::BlockNumber;
+use ckb_types::{core::BlockNumber, prelude::*};
 
 pub struct ProposalExpireRuleForCommittingAndExpiredAtOneTime;
 
@@ -64,6 +64,36 @@ impl Spec for ProposalExpireRuleForCommittingAndExpiredAtOneTime {
     }
 }
 
+pub struct SimulateProposalWindow;
+
+impl Spec for SimulateProposalWindow {
+    // Case: The simulated proposal and commit blocks of a transaction are the blocks which
+    //       propose and commit it, when nothing else is in the pool.
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node = &nodes[0];
+        node.mine_until_out_bootstrap_period();
+
+        let tx = node.new_transaction_spend_tip_cellbase();
+        let tip_number = node.get_tip_block_number();
+        let simulation = node.rpc_client().simulate_proposal_window(tx.data().into());
+        let proposed_number = tip_number + 1;
+        let commit_window = commit_window(node, proposed_number);
+        assert_eq!(simulation.tx_hash, tx.hash().unpack());
+        assert_eq!(simulation.tip_number.value(), tip_number);
+        assert_eq!(simulation.rank.value(), 0);
+        assert_eq!(simulation.earliest_proposal_block.value(), proposed_number);
+        assert_eq!(simulation.earliest_commit_block.value(), commit_window.0);
+        assert_eq!(simulation.latest_commit_block.value(), commit_window.1);
+
+        node.submit_transaction(&tx);
+        node.mine(commit_window.0 - 1 - tip_number);
+        node.wait_for_tx_pool();
+        let committed_block = node.new_block(None, None, None);
+        assert_eq!(committed_block.number(), commit_window.0);
+        assert_eq!(committed_block.transaction(1), Some(tx));
+    }
+}
+
 fn commit_window(node: &Node, proposed_number: BlockNumber) -> (BlockNumber, BlockNumber) {
     let proposal_window = node.consensus().tx_proposal_window();
     (
@@ -9,7 +9,7 @@ use crate::{
     utils::wait_until,
     Node, Spec,
 };
-use ckb_jsonrpc_types::Status;
+use ckb_jsonrpc_types::{RbfRule, Status};
 use ckb_logger::info;
 use ckb_types::{
     core::{capacity_bytes, cell::CellMetaBuilder, Capacity, DepType, TransactionView},
@@ -264,6 +264,72 @@ impl Spec for RbfSameInputwithLessFee {
     }
 }
 
+pub struct RbfSimulateReplacement;
+
+impl Spec for RbfSimulateReplacement {
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node0 = &nodes[0];
+
+        node0.mine_until_out_bootstrap_period();
+        node0.new_block_with_blocking(|template| template.number.value() != 13);
+        let tx_hash_0 = node0.generate_transaction();
+        let tx1 = node0.new_transaction(tx_hash_0.clone());
+        let tx1 = tx1
+            .as_advanced_builder()
+            .set_outputs(vec![CellOutputBuilder::default()
+                .capacity(capacity_bytes!(80).pack())
+                .build()])
+            .build();
+        node0.rpc_client().send_transaction(tx1.data().into());
+
+        // the fee is too low, and the other rules pass
+        let tx2 = node0
+            .new_transaction(tx_hash_0.clone())
+            .as_advanced_builder()
+            .set_outputs(vec![CellOutputBuilder::default()
+                .capacity(capacity_bytes!(90).pack())
+                .build()])
+            .build();
+        let simulation = node0.rpc_client().simulate_replacement(tx2.data().into());
+        assert_eq!(simulation.conflicts, vec![tx1.hash().unpack()]);
+        assert_eq!(simulation.evicted, vec![tx1.hash().unpack()]);
+        assert_eq!(simulation.evicted_fee.value(), 2000000000);
+        assert_eq!(
+            simulation.min_replace_fee.map(|fee| fee.value()),
+            Some(2000000363)
+        );
+        assert!(!simulation.replaceable);
+        let failed: Vec<_> = simulation
+            .rules
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.rule)
+            .collect();
+        assert_eq!(failed, vec![RbfRule::MinReplaceFee]);
+
+        // nothing is submitted
+        let ret = node0.rpc_client().get_transaction(tx1.hash());
+        assert!(matches!(ret.tx_status.status, Status::Pending));
+        let ret = node0.rpc_client().get_transaction(tx2.hash());
+        assert!(matches!(ret.tx_status.status, Status::Unknown));
+
+        let tx3 = node0
+            .new_transaction(tx_hash_0)
+            .as_advanced_builder()
+            .set_outputs(vec![CellOutputBuilder::default()
+                .capacity(capacity_bytes!(70).pack())
+                .build()])
+            .build();
+        let simulation = node0.rpc_client().simulate_replacement(tx3.data().into());
+        assert!(simulation.replaceable);
+        assert_eq!(simulation.rules.len(), 4);
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.min_rbf_rate = ckb_types::core::FeeRate(1500);
+    }
+}
+
 pub struct RbfTooManyDescendants;
 
 // RBF Rule #5
@@ -872,6 +938,124 @@ impl Spec for RbfReplaceProposedSuccess {
     }
 }
 
+pub struct RbfReplaceProposedDisallowed;
+
+// RBF Rule #5, the proposed txs are not replaceable if `rbf_replace_proposed` is false
+impl Spec for RbfReplaceProposedDisallowed {
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node0 = &nodes[0];
+
+        node0.mine_until_out_bootstrap_period();
+
+        let tx1 = node0.new_transaction_spend_tip_cellbase();
+        let ret = node0
+            .rpc_client()
+            .send_transaction_result(tx1.data().into());
+        assert!(ret.is_ok());
+
+        let proposed = node0.mine_with_blocking(|template| template.proposals.is_empty());
+        node0.mine_with_blocking(|template| template.number.value() != (proposed + 1));
+
+        let rpc_client0 = node0.rpc_client();
+        let ret = wait_until(20, || {
+            let res = rpc_client0.get_transaction(tx1.hash());
+            res.tx_status.status == Status::Proposed
+        });
+        assert!(ret, "tx1 should be proposed");
+
+        // Set tx2 fee to a higher value
+        let output2 = CellOutputBuilder::default()
+            .capacity(capacity_bytes!(70).pack())
+            .build();
+        let tx2 = tx1.as_advanced_builder().set_outputs(vec![output2]).build();
+
+        let res = node0
+            .rpc_client()
+            .send_transaction_result(tx2.data().into());
+        assert!(res.is_err(), "tx2 should be rejected");
+        assert!(res
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("which is not allowed to be replaced"));
+
+        let tx1_status = node0.rpc_client().get_transaction(tx1.hash()).tx_status;
+        assert_eq!(tx1_status.status, Status::Proposed);
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.min_rbf_rate = ckb_types::core::FeeRate(1500);
+        config.tx_pool.rbf_replace_proposed = false;
+    }
+}
+
+pub struct RbfTooManyConflictDescendants;
+
+// RBF Rule #5, the descendants of a conflict are limited by `max_rbf_conflict_descendants`
+impl Spec for RbfTooManyConflictDescendants {
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node0 = &nodes[0];
+
+        node0.mine_until_out_bootstrap_period();
+
+        // build txs chain
+        let tx0 = node0.new_transaction_spend_tip_cellbase();
+        let mut txs = vec![tx0];
+        while txs.len() < 4 {
+            let parent = txs.last().unwrap();
+            let child = parent
+                .as_advanced_builder()
+                .set_inputs(vec![{
+                    CellInput::new_builder()
+                        .previous_output(OutPoint::new(parent.hash(), 0))
+                        .build()
+                }])
+                .set_outputs(vec![parent.output(0).unwrap()])
+                .build();
+            txs.push(child);
+        }
+        for tx in txs.iter() {
+            let ret = node0.rpc_client().send_transaction_result(tx.data().into());
+            assert!(ret.is_ok());
+        }
+
+        // Set the fee to a higher value
+        let output = CellOutputBuilder::default()
+            .capacity(capacity_bytes!(70).pack())
+            .build();
+
+        // txs[0] has 3 descendants
+        let replace_tx0 = txs[0]
+            .as_advanced_builder()
+            .set_outputs(vec![output.clone()])
+            .build();
+        let res = node0
+            .rpc_client()
+            .send_transaction_result(replace_tx0.data().into());
+        assert!(res.is_err(), "the replacement of tx0 should be rejected");
+        assert!(res
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Tx conflict with a tx which has too many descendants"));
+
+        // txs[1] has 2 descendants
+        let replace_tx1 = txs[1]
+            .as_advanced_builder()
+            .set_outputs(vec![output])
+            .build();
+        let res = node0
+            .rpc_client()
+            .send_transaction_result(replace_tx1.data().into());
+        assert!(res.is_ok());
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.min_rbf_rate = ckb_types::core::FeeRate(1500);
+        config.tx_pool.max_rbf_conflict_descendants = 2;
+    }
+}
+
 pub struct RbfConcurrency;
 impl Spec for RbfConcurrency {
     fn run(&self, nodes: &mut Vec<Node>) {
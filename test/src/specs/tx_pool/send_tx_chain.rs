@@ -1,10 +1,11 @@
 use crate::specs::tx_pool::utils::prepare_tx_family;
+use crate::util::{cell::gen_spendable, transaction::always_success_transaction};
 use crate::utils::blank;
 use crate::utils::propose;
 use crate::{Node, Spec};
 use ckb_jsonrpc_types::TxStatus;
 use ckb_logger::info;
-use ckb_types::core::FeeRate;
+use ckb_types::core::{Capacity, FeeRate};
 use ckb_types::{
     packed::{CellInput, OutPoint},
     prelude::*,
@@ -115,6 +116,128 @@ impl Spec for SendTxChain {
     }
 }
 
+pub struct SendTxPackage;
+
+impl Spec for SendTxPackage {
+    // Case: A chain of txs is accepted as a package, and none of them is accepted if one is
+    //       invalid.
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node0 = &nodes[0];
+
+        node0.mine_until_out_bootstrap_period();
+        let mut txs = vec![node0.new_transaction_spend_tip_cellbase()];
+        while txs.len() < 3 {
+            let parent = txs.last().unwrap();
+            let child = parent
+                .as_advanced_builder()
+                .set_inputs(vec![CellInput::new(OutPoint::new(parent.hash(), 0), 0)])
+                .set_outputs(vec![parent.output(0).unwrap()])
+                .build();
+            txs.push(child);
+        }
+
+        info!("the package with an unknown input is rejected");
+        let invalid = txs[2]
+            .as_advanced_builder()
+            .set_inputs(vec![CellInput::new(OutPoint::new(txs[2].hash(), 0), 0)])
+            .build();
+        let package = vec![txs[0].clone(), txs[1].clone(), invalid];
+        let ret = node0
+            .rpc_client()
+            .send_transaction_package_result(package.iter().map(|tx| tx.data().into()).collect());
+        let err = ret.expect_err("the package is rejected");
+        assert!(err.to_string().contains("PoolRejectedPackage"), "{err}");
+        for tx in txs.iter() {
+            assert_eq!(node0.get_transaction(tx.hash()), TxStatus::unknown());
+        }
+
+        info!("the chain is accepted as a package");
+        let tx_hashes = node0
+            .rpc_client()
+            .send_transaction_package_result(txs.iter().map(|tx| tx.data().into()).collect())
+            .expect("the package is accepted");
+        assert_eq!(
+            tx_hashes,
+            txs.iter().map(|tx| tx.hash().unpack()).collect::<Vec<_>>()
+        );
+        for tx in txs.iter() {
+            assert_eq!(node0.get_transaction(tx.hash()), TxStatus::pending());
+        }
+        let tx_pool_info = node0.rpc_client().tx_pool_info();
+        assert_eq!(tx_pool_info.pending.value(), 3);
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.min_fee_rate = FeeRate::from_u64(0);
+    }
+}
+
+pub struct SendTxPackageFullPool;
+
+const MAX_MEM_SIZE_FOR_PACKAGE: usize = 2000;
+
+impl Spec for SendTxPackageFullPool {
+    // Case: A package is rejected as a whole if the full pool would evict any of it, and the txs
+    //       already in the pool are kept.
+    fn run(&self, nodes: &mut Vec<Node>) {
+        let node0 = &nodes[0];
+
+        let mut cells = gen_spendable(node0, 20).into_iter();
+        let package_cell = cells.next().unwrap();
+
+        info!("fill the pool with the txs paying fees");
+        let mut fill_txs = Vec::new();
+        for cell in cells {
+            let tx = always_success_transaction(node0, &cell);
+            let output = tx.output(0).unwrap();
+            let capacity: Capacity = output.capacity().unpack();
+            let output = output
+                .as_builder()
+                .capacity(capacity.safe_sub(Capacity::shannons(1000)).unwrap().pack())
+                .build();
+            let tx = tx.as_advanced_builder().set_outputs(vec![output]).build();
+            let total_tx_size = node0.get_tip_tx_pool_info().total_tx_size.value();
+            if total_tx_size + tx.data().serialized_size_in_block() as u64
+                > MAX_MEM_SIZE_FOR_PACKAGE as u64
+            {
+                break;
+            }
+            node0.submit_transaction(&tx);
+            fill_txs.push(tx);
+        }
+        assert!(fill_txs.len() > 1);
+
+        info!("the package paying no fee is rejected since it would be evicted");
+        let mut txs = vec![always_success_transaction(node0, &package_cell)];
+        while txs.len() < 3 {
+            let parent = txs.last().unwrap();
+            let child = parent
+                .as_advanced_builder()
+                .set_inputs(vec![CellInput::new(OutPoint::new(parent.hash(), 0), 0)])
+                .build();
+            txs.push(child);
+        }
+        let ret = node0
+            .rpc_client()
+            .send_transaction_package_result(txs.iter().map(|tx| tx.data().into()).collect());
+        let err = ret.expect_err("the package is rejected");
+        assert!(err.to_string().contains("PoolRejectedPackage"), "{err}");
+        for tx in txs.iter() {
+            assert_eq!(node0.get_transaction(tx.hash()), TxStatus::unknown());
+        }
+        for tx in fill_txs.iter() {
+            assert_eq!(node0.get_transaction(tx.hash()), TxStatus::pending());
+        }
+        let tx_pool_info = node0.rpc_client().tx_pool_info();
+        assert_eq!(tx_pool_info.pending.value(), fill_txs.len() as u64);
+    }
+
+    fn modify_app_config(&self, config: &mut ckb_app_config::CKBAppConfig) {
+        config.tx_pool.max_tx_pool_size = MAX_MEM_SIZE_FOR_PACKAGE;
+        config.tx_pool.min_fee_rate = FeeRate::from_u64(0);
+    }
+}
+
 pub struct SendTxChainRevOrder;
 
 impl Spec for SendTxChainRevOrder {
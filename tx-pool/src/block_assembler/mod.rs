@@ -10,6 +10,7 @@ use crate::component::entry::TxEntry;
 use crate::error::BlockAssemblerError;
 pub use candidate_uncles::CandidateUncles;
 use ckb_app_config::BlockAssemblerConfig;
+use ckb_chain_spec::consensus::Consensus;
 use ckb_dao::DaoCalculator;
 use ckb_error::{AnyError, InternalErrorKind};
 use ckb_jsonrpc_types::{
@@ -18,13 +19,15 @@ use ckb_jsonrpc_types::{
 use ckb_logger::{debug, error, trace};
 use ckb_reward_calculator::RewardCalculator;
 use ckb_snapshot::Snapshot;
-use ckb_store::ChainStore;
+use ckb_store::{data_loader_wrapper::AsDataLoader, ChainStore};
 use ckb_systemtime::unix_time_as_millis;
+use ckb_traits::HeaderFieldsProvider;
 use ckb_types::{
     core::{
         cell::{OverlayCellChecker, TransactionsChecker},
-        BlockNumber, Capacity, Cycle, EpochExt, EpochNumberWithFraction, ScriptHashType,
-        TransactionBuilder, TransactionView, UncleBlockView, Version,
+        tx_pool::{TemplateBacklog, TxsBacklog},
+        BlockNumber, Capacity, Cycle, EpochExt, EpochNumberWithFraction, HeaderBuilder, HeaderView,
+        ScriptHashType, TransactionBuilder, TransactionView, UncleBlockView, Version,
     },
     packed::{
         self, Byte32, Bytes, CellInput, CellOutput, CellbaseWitness, ProposalShortId, Script,
@@ -32,8 +35,9 @@ use ckb_types::{
     },
     prelude::*,
 };
+use ckb_verification::{TimeRelativeTransactionVerifier, TxVerifyEnv};
 use hyper::{client::HttpConnector, Body, Client, Method, Request};
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::sync::{
     atomic::{AtomicU64, Ordering},
     Arc,
@@ -84,6 +88,9 @@ impl TemplateSize {
     }
 }
 
+// The backlogs of the recent template generations kept for `get_block_template_backlog`
+const MAX_TEMPLATE_BACKLOGS: usize = 32;
+
 #[derive(Clone)]
 pub(crate) struct CurrentTemplate {
     pub(crate) template: BlockTemplate,
@@ -100,6 +107,8 @@ pub struct BlockAssembler {
     pub(crate) candidate_uncles: Arc<Mutex<CandidateUncles>>,
     pub(crate) current: Arc<Mutex<CurrentTemplate>>,
     pub(crate) poster: Arc<Client<HttpConnector, Body>>,
+    // the txs left out of the recent templates, the latest last
+    pub(crate) backlogs: Arc<Mutex<VecDeque<TemplateBacklog>>>,
 }
 
 impl BlockAssembler {
@@ -159,6 +168,7 @@ impl BlockAssembler {
             candidate_uncles: Arc::new(Mutex::new(CandidateUncles::new())),
             current: Arc::new(Mutex::new(current)),
             poster: Arc::new(Client::new()),
+            backlogs: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_TEMPLATE_BACKLOGS))),
         }
     }
 
@@ -202,12 +212,18 @@ impl BlockAssembler {
             current_template.cellbase.clone(),
             txs,
         )?;
-        if !failed_txs.is_empty() {
+        let needs_protection = tx_pool.read().await.record_template_txs(&checked_txs);
+        if !failed_txs.is_empty() || needs_protection {
             let mut tx_pool_writer = tx_pool.write().await;
             for id in failed_txs {
                 tx_pool_writer.remove_tx(&id);
             }
+            if needs_protection {
+                tx_pool_writer.protect_template_txs(&checked_txs);
+            }
         }
+        // the failed txs are removed from the pool first
+        let backlog = tx_pool.read().await.template_backlog(&checked_txs);
 
         let txs_size = checked_txs.iter().map(|tx| tx.size).sum();
         let total_size = basic_size + txs_size;
@@ -227,6 +243,7 @@ impl BlockAssembler {
         current.size.txs = txs_size;
         current.size.total = total_size;
         current.size.proposals = proposals_size;
+        self.record_backlog(&current.template, backlog).await;
 
         trace!(
             "[BlockAssembler] update_full {} uncles-{} proposals-{} txs-{}",
@@ -418,6 +435,16 @@ impl BlockAssembler {
             current_template.cellbase.clone(),
             txs,
         ) {
+            let (needs_protection, backlog) = {
+                let tx_pool_reader = tx_pool.read().await;
+                (
+                    tx_pool_reader.record_template_txs(&checked_txs),
+                    tx_pool_reader.template_backlog(&checked_txs),
+                )
+            };
+            if needs_protection {
+                tx_pool.write().await.protect_template_txs(&checked_txs);
+            }
             let new_txs_size = checked_txs.iter().map(|tx| tx.size).sum();
             let new_total_size = current.size.calc_total_by_txs(new_txs_size);
             let mut builder = BlockTemplateBuilder::from_template(&current.template);
@@ -435,6 +462,7 @@ impl BlockAssembler {
             current.template = builder.build();
             current.size.txs = new_txs_size;
             current.size.total = new_total_size;
+            self.record_backlog(&current.template, backlog).await;
 
             trace!(
                 "[BlockAssembler] update_transactions-{} epoch-{} uncles-{} proposals-{} txs-{}",
@@ -453,6 +481,40 @@ impl BlockAssembler {
         (&current.template).into()
     }
 
+    /// Returns the txs left out of the recent templates, the latest first.
+    pub(crate) async fn get_backlogs(&self) -> Vec<TemplateBacklog> {
+        let backlogs = self.backlogs.lock().await;
+        backlogs.iter().rev().cloned().collect()
+    }
+
+    async fn record_backlog(
+        &self,
+        template: &BlockTemplate,
+        (proposed, pending): (TxsBacklog, TxsBacklog),
+    ) {
+        if let Some(metrics) = ckb_metrics::handle() {
+            let gauges = &metrics.ckb_tx_pool_template_backlog;
+            for (gauges, backlog) in [(&gauges.proposed, &proposed), (&gauges.pending, &pending)] {
+                gauges.count.set(backlog.tx_count as i64);
+                gauges.size.set(backlog.tx_size as i64);
+                gauges.cycles.set(backlog.cycles as i64);
+                gauges.weight.set(backlog.weight as i64);
+                gauges.fees.set(backlog.fees.as_u64() as i64);
+            }
+        }
+        let mut backlogs = self.backlogs.lock().await;
+        if backlogs.len() == MAX_TEMPLATE_BACKLOGS {
+            backlogs.pop_front();
+        }
+        backlogs.push_back(TemplateBacklog {
+            number: template.number,
+            work_id: template.work_id,
+            generated_at: unix_time_as_millis(),
+            proposed,
+            pending,
+        });
+    }
+
     pub(crate) fn build_cellbase_witness(
         config: &BlockAssemblerConfig,
         snapshot: &Snapshot,
@@ -582,7 +644,7 @@ impl BlockAssembler {
     }
 
     fn calc_dao(
-        snapshot: &Snapshot,
+        snapshot: &Arc<Snapshot>,
         current_epoch: &EpochExt,
         cellbase: TransactionView,
         entries: Vec<TxEntry>,
@@ -590,6 +652,24 @@ impl BlockAssembler {
         let tip_header = snapshot.tip_header();
         let consensus = snapshot.consensus();
         let mut seen_inputs = HashSet::new();
+
+        // The immature txs stay in the pool, they are packaged by the later templates.
+        let tx_env = Arc::new(Self::candidate_tx_env(tip_header, current_epoch));
+        let (entries, immature_entries) = block_in_place(|| {
+            filter_immature_txs(
+                entries,
+                snapshot.cloned_consensus(),
+                snapshot.as_data_loader(),
+                tx_env,
+            )
+        });
+        if !immature_entries.is_empty() {
+            debug!(
+                "Skip {} immature txs while building block template, tip_number: {}",
+                immature_entries.len(),
+                tip_header.number(),
+            );
+        }
         let mut transactions_checker = TransactionsChecker::new(iter::once(&cellbase));
 
         let mut checked_failed_txs = vec![];
@@ -634,6 +714,17 @@ impl BlockAssembler {
         Ok((dao, checked_entries, checked_failed_txs))
     }
 
+    // The verification environment of the txs committed in the block following the tip.
+    fn candidate_tx_env(tip_header: &HeaderView, current_epoch: &EpochExt) -> TxVerifyEnv {
+        let candidate_number = tip_header.number() + 1;
+        let candidate_header = HeaderBuilder::default()
+            .parent_hash(tip_header.hash())
+            .number(candidate_number.pack())
+            .epoch(current_epoch.number_with_fraction(candidate_number).pack())
+            .build();
+        TxVerifyEnv::new_commit(&candidate_header)
+    }
+
     pub(crate) async fn notify(&self) {
         if !self.need_to_notify() {
             return;
@@ -916,6 +1007,41 @@ impl BlockTemplateBuilder {
     }
 }
 
+/// Splits the entries into the ones whose `since` and maturity requirements are satisfied in the
+/// block described by `tx_env` and the immature ones.
+///
+/// The entries are in the packaging order, a tx spending the outputs of an immature tx is immature
+/// too.
+pub(crate) fn filter_immature_txs<DL>(
+    entries: Vec<TxEntry>,
+    consensus: Arc<Consensus>,
+    data_loader: DL,
+    tx_env: Arc<TxVerifyEnv>,
+) -> (Vec<TxEntry>, Vec<TxEntry>)
+where
+    DL: HeaderFieldsProvider + Clone,
+{
+    let mut immature_hashes = HashSet::new();
+    entries.into_iter().partition(|entry| {
+        let tx = entry.transaction();
+        let mature = !tx
+            .input_pts_iter()
+            .any(|out_point| immature_hashes.contains(&out_point.tx_hash()))
+            && TimeRelativeTransactionVerifier::new(
+                Arc::clone(&entry.rtx),
+                Arc::clone(&consensus),
+                data_loader.clone(),
+                Arc::clone(&tx_env),
+            )
+            .verify()
+            .is_ok();
+        if !mature {
+            immature_hashes.insert(tx.hash());
+        }
+        mature
+    })
+}
+
 pub(crate) fn uncle_to_template(uncle: &UncleBlockView) -> UncleTemplate {
     UncleTemplate {
         hash: uncle.hash().unpack(),
@@ -1,11 +1,22 @@
+use ckb_chain_spec::consensus::ConsensusBuilder;
+use ckb_traits::{HeaderFields, HeaderFieldsProvider};
 use ckb_types::{
-    core::{BlockBuilder, BlockNumber, EpochNumberWithFraction},
+    bytes::Bytes,
+    core::{
+        BlockBuilder, BlockNumber, Capacity, EpochNumberWithFraction, HeaderBuilder,
+        TransactionBuilder,
+    },
+    packed::{Byte32, CellInput, CellOutput, OutPoint},
     prelude::*,
 };
+use ckb_verification::TxVerifyEnv;
+use std::sync::Arc;
 
 use crate::block_assembler::candidate_uncles::{
     CandidateUncles, MAX_CANDIDATE_UNCLES, MAX_PER_HEIGHT,
 };
+use crate::block_assembler::filter_immature_txs;
+use crate::component::entry::TxEntry;
 
 #[test]
 fn test_candidate_uncles_basic() {
@@ -72,3 +83,118 @@ fn test_candidate_uncles_max_per_height() {
     assert_eq!(candidate_uncles.map.len(), 1);
     assert_eq!(candidate_uncles.len(), MAX_PER_HEIGHT);
 }
+
+#[derive(Clone)]
+struct NoHeaders;
+
+impl HeaderFieldsProvider for NoHeaders {
+    fn get_header_fields(&self, _hash: &Byte32) -> Option<HeaderFields> {
+        None
+    }
+}
+
+fn entry_with_since(previous_tx_hash: Byte32, since: u64) -> TxEntry {
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new(OutPoint::new(previous_tx_hash, 0), since))
+        .output(CellOutput::new_builder().build())
+        .output_data(Bytes::new().pack())
+        .build();
+    TxEntry::dummy_resolve(tx, 0, Capacity::zero(), 0)
+}
+
+fn absolute_epoch_since(epoch: EpochNumberWithFraction) -> u64 {
+    0x2000_0000_0000_0000 | epoch.full_value()
+}
+
+fn immature_count(
+    entries: &[TxEntry],
+    number: BlockNumber,
+    epoch: EpochNumberWithFraction,
+) -> usize {
+    let header = HeaderBuilder::default()
+        .number(number.pack())
+        .epoch(epoch.pack())
+        .build();
+    let (_mature, immature) = filter_immature_txs(
+        entries.to_vec(),
+        Arc::new(ConsensusBuilder::default().build()),
+        NoHeaders,
+        Arc::new(TxVerifyEnv::new_commit(&header)),
+    );
+    immature.len()
+}
+
+#[test]
+fn test_filter_immature_txs_at_epoch_boundary() {
+    let since_epoch_10 = entry_with_since(
+        Byte32::zero(),
+        absolute_epoch_since(EpochNumberWithFraction::new(10, 0, 1000)),
+    );
+    let entries = vec![since_epoch_10];
+
+    // the last block of epoch 9
+    assert_eq!(
+        immature_count(&entries, 9999, EpochNumberWithFraction::new(9, 999, 1000)),
+        1
+    );
+    // the first block of epoch 10
+    assert_eq!(
+        immature_count(&entries, 10000, EpochNumberWithFraction::new(10, 0, 1000)),
+        0
+    );
+    // the epoch length doesn't matter
+    assert_eq!(
+        immature_count(&entries, 10000, EpochNumberWithFraction::new(10, 0, 1800)),
+        0
+    );
+}
+
+#[test]
+fn test_filter_immature_txs_by_epoch_fraction() {
+    // the middle of epoch 10
+    let since_half_epoch = entry_with_since(
+        Byte32::zero(),
+        absolute_epoch_since(EpochNumberWithFraction::new(10, 1, 2)),
+    );
+    let entries = vec![since_half_epoch];
+
+    assert_eq!(
+        immature_count(&entries, 10499, EpochNumberWithFraction::new(10, 499, 1000)),
+        1
+    );
+    assert_eq!(
+        immature_count(&entries, 10500, EpochNumberWithFraction::new(10, 500, 1000)),
+        0
+    );
+}
+
+#[test]
+fn test_filter_immature_txs_with_descendants() {
+    let parent = entry_with_since(
+        Byte32::zero(),
+        absolute_epoch_since(EpochNumberWithFraction::new(10, 0, 1000)),
+    );
+    let child = entry_with_since(parent.transaction().hash(), 0);
+    let unrelated = entry_with_since(Byte32::new([1u8; 32]), 0);
+    let entries = vec![parent, child, unrelated.clone()];
+
+    let header = HeaderBuilder::default()
+        .number(9999u64.pack())
+        .epoch(EpochNumberWithFraction::new(9, 999, 1000).pack())
+        .build();
+    let (mature, immature) = filter_immature_txs(
+        entries,
+        Arc::new(ConsensusBuilder::default().build()),
+        NoHeaders,
+        Arc::new(TxVerifyEnv::new_commit(&header)),
+    );
+    // the child spends the output of the immature parent
+    assert_eq!(immature.len(), 2);
+    assert_eq!(
+        mature
+            .iter()
+            .map(|entry| entry.transaction().hash())
+            .collect::<Vec<_>>(),
+        vec![unrelated.transaction().hash()]
+    );
+}
@@ -1,19 +1,60 @@
 use super::component::TxEntry;
 use crate::error::Reject;
 use crate::pool::TxPool;
+use std::sync::Arc;
 
 /// Callback boxed fn pointer wrapper
 pub type PendingCallback = Box<dyn Fn(&TxEntry) + Sync + Send>;
+/// Gap Callback boxed fn pointer wrapper
+pub type GapCallback = Box<dyn Fn(&TxEntry) + Sync + Send>;
 /// Proposed Callback boxed fn pointer wrapper
 pub type ProposedCallback = Box<dyn Fn(&TxEntry) + Sync + Send>;
+/// Committed Callback boxed fn pointer wrapper
+pub type CommittedCallback = Box<dyn Fn(&TxEntry) + Sync + Send>;
 /// Reject Callback boxed fn pointer wrapper
 pub type RejectCallback = Box<dyn Fn(&mut TxPool, &TxEntry, Reject) + Sync + Send>;
 
+/// Observer of the tx-pool events.
+///
+/// Compiled-in plugins implement this trait and register it with
+/// [`TxPoolServiceBuilder::register_listener`](crate::TxPoolServiceBuilder::register_listener)
+/// before the tx-pool service starts, to observe the pool without forking it.
+///
+/// All methods have empty default implementations. They are invoked synchronously inside the
+/// tx-pool service while holding the pool lock, so implementations must return quickly and
+/// should hand heavy work over to their own threads.
+pub trait PoolEventListener: Sync + Send {
+    /// Called after a transaction is accepted into the pending (or gap) set.
+    fn on_accepted(&self, _entry: &TxEntry) {}
+
+    /// Called after a transaction enters the gap set, that is it is proposed in a block which is
+    /// not in the proposal window yet, either submitted directly or moved from pending.
+    fn on_gap(&self, _entry: &TxEntry) {}
+
+    /// Called after a transaction enters the proposed set, either submitted directly or
+    /// promoted from pending.
+    fn on_proposed(&self, _entry: &TxEntry) {}
+
+    /// Called after a transaction is rejected or removed from the pool for `reject`.
+    fn on_rejected(&self, _entry: &TxEntry, _reject: &Reject) {}
+
+    /// Called after an in-pool transaction is committed in a block and removed from the pool.
+    fn on_committed(&self, _entry: &TxEntry) {}
+
+    /// Called after an in-pool transaction `old` is removed by the RBF replacement `new`.
+    ///
+    /// `on_rejected` is called for `old` as well.
+    fn on_replaced(&self, _old: &TxEntry, _new: &TxEntry) {}
+}
+
 /// Struct hold callbacks
 pub struct Callbacks {
     pub(crate) pending: Option<PendingCallback>,
+    pub(crate) gap: Option<GapCallback>,
     pub(crate) proposed: Option<ProposedCallback>,
+    pub(crate) committed: Option<CommittedCallback>,
     pub(crate) reject: Option<RejectCallback>,
+    pub(crate) listeners: Vec<Arc<dyn PoolEventListener>>,
 }
 
 impl Default for Callbacks {
@@ -27,8 +68,11 @@ impl Callbacks {
     pub fn new() -> Self {
         Callbacks {
             pending: None,
+            gap: None,
             proposed: None,
+            committed: None,
             reject: None,
+            listeners: Vec::new(),
         }
     }
 
@@ -37,21 +81,49 @@ impl Callbacks {
         self.pending = Some(callback);
     }
 
+    /// Register a new gap callback
+    pub fn register_gap(&mut self, callback: GapCallback) {
+        self.gap = Some(callback);
+    }
+
     /// Register a new proposed callback
     pub fn register_proposed(&mut self, callback: ProposedCallback) {
         self.proposed = Some(callback);
     }
 
+    /// Register a new committed callback
+    pub fn register_committed(&mut self, callback: CommittedCallback) {
+        self.committed = Some(callback);
+    }
+
     /// Register a new abandon callback
     pub fn register_reject(&mut self, callback: RejectCallback) {
         self.reject = Some(callback);
     }
 
+    /// Register a new event listener
+    pub fn register_listener(&mut self, listener: Arc<dyn PoolEventListener>) {
+        self.listeners.push(listener);
+    }
+
     /// Call on after pending
     pub fn call_pending(&self, entry: &TxEntry) {
         if let Some(call) = &self.pending {
             call(entry)
         }
+        for listener in &self.listeners {
+            listener.on_accepted(entry);
+        }
+    }
+
+    /// Call on after gap
+    pub fn call_gap(&self, entry: &TxEntry) {
+        if let Some(call) = &self.gap {
+            call(entry)
+        }
+        for listener in &self.listeners {
+            listener.on_gap(entry);
+        }
     }
 
     /// Call on after proposed
@@ -59,12 +131,35 @@ impl Callbacks {
         if let Some(call) = &self.proposed {
             call(entry)
         }
+        for listener in &self.listeners {
+            listener.on_proposed(entry);
+        }
     }
 
     /// Call on after reject
     pub fn call_reject(&self, tx_pool: &mut TxPool, entry: &TxEntry, reject: Reject) {
+        for listener in &self.listeners {
+            listener.on_rejected(entry, &reject);
+        }
         if let Some(call) = &self.reject {
             call(tx_pool, entry, reject)
         }
     }
+
+    /// Call on after committed
+    pub fn call_committed(&self, entry: &TxEntry) {
+        if let Some(call) = &self.committed {
+            call(entry)
+        }
+        for listener in &self.listeners {
+            listener.on_committed(entry);
+        }
+    }
+
+    /// Call on after replaced by RBF
+    pub fn call_replaced(&self, old: &TxEntry, new: &TxEntry) {
+        for listener in &self.listeners {
+            listener.on_replaced(old, new);
+        }
+    }
 }
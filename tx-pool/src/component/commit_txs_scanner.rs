@@ -1,10 +1,14 @@
 extern crate slab;
 use crate::component::pool_map::PoolMap;
 use crate::component::{entry::TxEntry, sort_key::AncestorsScoreSortKey};
-use ckb_types::{core::Cycle, packed::ProposalShortId};
+use ckb_types::{
+    core::{tx_pool::get_transaction_weight, Capacity, Cycle},
+    packed::ProposalShortId,
+};
 use ckb_util::LinkedHashMap;
 use multi_index_map::MultiIndexMap;
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
 
 // A template data struct used to store modified entries when package txs
 #[derive(MultiIndexMap, Clone)]
@@ -43,6 +47,33 @@ impl MultiIndexModifiedTxMap {
     }
 }
 
+/// A proposed tx to commit with its proposed descendants as a whole, child-pays-for-parent.
+///
+/// The ancestors packages only let a parent paying a low fee in with one of its children, while
+/// the children may only pay enough for it together. The package is committed once its fee rate
+/// beats the best ancestors package.
+///
+/// The candidates come from the package scores kept by the pool, see
+/// `PoolMap::sorted_proposed_packages_iter`, and a package is only built exactly when its
+/// candidate comes to the top.
+#[derive(Eq, PartialEq)]
+struct DescendantsPackage {
+    score: AncestorsScoreSortKey,
+    id: ProposalShortId,
+}
+
+impl PartialOrd for DescendantsPackage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DescendantsPackage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
 // Limit the number of attempts to add transactions to the block when it is
 // close to full; this is just a simple heuristic to finish quickly if the
 // mempool has a lot of entries.
@@ -81,6 +112,9 @@ impl<'a> CommitTxsScanner<'a> {
         let mut size: usize = 0;
         let mut cycles: Cycle = 0;
         let mut consecutive_failed = 0;
+        // the candidates kept by the pool, and the ones evaluated again
+        let mut packages = self.pool_map.sorted_proposed_packages_iter().peekable();
+        let mut reevaluated: BinaryHeap<DescendantsPackage> = BinaryHeap::new();
 
         let mut iter = self.pool_map.sorted_proposed_iter().peekable();
         loop {
@@ -93,6 +127,68 @@ impl<'a> CommitTxsScanner<'a> {
                 }
             }
 
+            // Then see whether a descendants package beats the best ancestors package.
+            let best_score = match (iter.peek(), self.modified_entries.next_best_entry()) {
+                (Some(entry), Some(best_modified)) => {
+                    Some(entry.as_score_key().max(best_modified.as_score_key()))
+                }
+                (Some(entry), None) => Some(entry.as_score_key()),
+                (None, Some(best_modified)) => Some(best_modified.as_score_key()),
+                (None, None) => None,
+            };
+            let best_package = match (packages.peek(), reevaluated.peek()) {
+                (Some(&(score, _)), Some(package)) => Some(score.max(&package.score)),
+                (Some(&(score, _)), None) => Some(score),
+                (None, Some(package)) => Some(&package.score),
+                (None, None) => None,
+            };
+            if best_package.map_or(false, |package| {
+                best_score.as_ref().map_or(true, |score| package > score)
+            }) {
+                let from_pool = match (packages.peek(), reevaluated.peek()) {
+                    (Some(&(score, _)), Some(package)) => score > &package.score,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+                let candidate = if from_pool {
+                    packages.next().map(|(score, entry)| DescendantsPackage {
+                        score: score.clone(),
+                        id: entry.proposal_short_id(),
+                    })
+                } else {
+                    reevaluated.pop()
+                }
+                .expect("peek guarded");
+                match self.descendants_package(&candidate.id) {
+                    // the package differs from the candidate counting all the in-pool relatives,
+                    // or some txs of it are committed, evaluate it again
+                    Some((_, score)) if score != candidate.score => {
+                        reevaluated.push(DescendantsPackage {
+                            score,
+                            id: candidate.id,
+                        });
+                    }
+                    Some((package, _)) => {
+                        let next_size = package
+                            .values()
+                            .fold(size, |sum, entry| sum.saturating_add(entry.size));
+                        let next_cycles = package
+                            .values()
+                            .fold(cycles, |sum, entry| sum.saturating_add(entry.cycles));
+                        if next_cycles > cycles_limit || next_size > size_limit {
+                            consecutive_failed += 1;
+                            if consecutive_failed > MAX_CONSECUTIVE_FAILURES {
+                                break;
+                            }
+                        } else {
+                            self.commit_package(package, &mut size, &mut cycles);
+                        }
+                    }
+                    None => {}
+                }
+                continue;
+            }
+
             // First try to find a new transaction in `proposed_pool` to evaluate.
             let tx_entry: TxEntry = match (iter.peek(), self.modified_entries.next_best_entry()) {
                 (Some(entry), Some(best_modified)) => {
@@ -163,21 +259,82 @@ impl<'a> CommitTxsScanner<'a> {
                 .map(|entry| (entry.proposal_short_id(), entry))
                 .collect();
 
-            for (short_id, entry) in &ancestors {
-                let is_inserted = self.fetched_txs.insert(short_id.clone());
-                debug_assert!(is_inserted, "package duplicate txs");
-                cycles = cycles.saturating_add(entry.cycles);
-                size = size.saturating_add(entry.size);
-                self.entries.push(entry.to_owned());
-                // try remove from modified
-                self.modified_entries.remove(short_id);
-            }
-
-            self.update_modified_entries(&ancestors);
+            self.commit_package(ancestors, &mut size, &mut cycles);
         }
         (self.entries, size, cycles)
     }
 
+    fn commit_package(
+        &mut self,
+        package: LinkedHashMap<ProposalShortId, TxEntry>,
+        size: &mut usize,
+        cycles: &mut Cycle,
+    ) {
+        for (short_id, entry) in &package {
+            let is_inserted = self.fetched_txs.insert(short_id.clone());
+            debug_assert!(is_inserted, "package duplicate txs");
+            *cycles = cycles.saturating_add(entry.cycles);
+            *size = size.saturating_add(entry.size);
+            self.entries.push(entry.to_owned());
+            // try remove from modified
+            self.modified_entries.remove(short_id);
+        }
+
+        self.update_modified_entries(&package);
+    }
+
+    /// Returns the uncommitted txs in the descendants package of the tx, sorted so the ancestors
+    /// come first, and the score of the package as a whole.
+    fn descendants_package(
+        &self,
+        short_id: &ProposalShortId,
+    ) -> Option<(
+        LinkedHashMap<ProposalShortId, TxEntry>,
+        AncestorsScoreSortKey,
+    )> {
+        if self.fetched_txs.contains(short_id) {
+            return None;
+        }
+        let tx_hash = self.pool_map.get(short_id)?.transaction().hash();
+        let mut members: Vec<(usize, TxEntry)> = self
+            .pool_map
+            .calc_descendants_package(short_id)
+            .iter()
+            .filter(|id| !self.fetched_txs.contains(id))
+            .filter_map(|id| {
+                // the ancestors count in the pool orders the txs topologically
+                let ancestors_count = self.pool_map.get(id)?.ancestors_count;
+                let entry = self.retrieve_entry(id)?;
+                Some((ancestors_count, entry.clone()))
+            })
+            .collect();
+        members.sort_unstable_by_key(|(ancestors_count, _)| *ancestors_count);
+
+        let (fee, size, cycles) = members.iter().fold(
+            (Capacity::zero(), 0usize, 0 as Cycle),
+            |(fee, size, cycles), (_, entry)| {
+                (
+                    Capacity::shannons(fee.as_u64().saturating_add(entry.modified_fee().as_u64())),
+                    size.saturating_add(entry.size),
+                    cycles.saturating_add(entry.cycles),
+                )
+            },
+        );
+        let weight = get_transaction_weight(size, cycles);
+        let score = AncestorsScoreSortKey {
+            fee,
+            weight,
+            ancestors_fee: fee,
+            ancestors_weight: weight,
+            tx_hash,
+        };
+        let package = members
+            .into_iter()
+            .map(|(_, entry)| (entry.proposal_short_id(), entry))
+            .collect();
+        Some((package, score))
+    }
+
     fn retrieve_entry(&self, short_id: &ProposalShortId) -> Option<&TxEntry> {
         self.modified_entries
             .get(short_id)
@@ -0,0 +1,93 @@
+use ckb_types::core::TransactionView;
+use ckb_types::packed::ProposalShortId;
+use lru::LruCache;
+
+/// The transactions replaced by RBF or conflicting with the pool.
+///
+/// They are kept to serve the relay requests and the RPC for a while, bounded by both the count
+/// and the total serialized size, the least recently used ones are evicted first.
+pub(crate) struct ConflictsPool {
+    max_count: usize,
+    max_size: usize,
+    // the total serialized size of the txs
+    size: usize,
+    txs: LruCache<ProposalShortId, TransactionView>,
+}
+
+impl ConflictsPool {
+    pub(crate) fn new(max_count: usize, max_size: usize) -> Self {
+        ConflictsPool {
+            max_count,
+            max_size,
+            size: 0,
+            txs: LruCache::unbounded(),
+        }
+    }
+
+    /// Add a tx, returns the count of the txs evicted by the limits.
+    pub(crate) fn put(&mut self, tx: TransactionView) -> usize {
+        let tx_size = tx_size(&tx);
+        if self.max_count == 0 || tx_size > self.max_size {
+            return 0;
+        }
+        if let Some(old) = self.txs.put(tx.proposal_short_id(), tx) {
+            self.size -= tx_size(&old);
+        }
+        self.size += tx_size;
+
+        let mut evicted = 0;
+        while self.txs.len() > self.max_count || self.size > self.max_size {
+            match self.txs.pop_lru() {
+                Some((_, tx)) => {
+                    self.size -= tx_size(&tx);
+                    evicted += 1;
+                }
+                None => break,
+            }
+        }
+        self.update_metrics(evicted);
+        evicted
+    }
+
+    pub(crate) fn pop(&mut self, id: &ProposalShortId) -> Option<TransactionView> {
+        let tx = self.txs.pop(id)?;
+        self.size -= tx_size(&tx);
+        self.update_metrics(0);
+        Some(tx)
+    }
+
+    pub(crate) fn peek(&self, id: &ProposalShortId) -> Option<&TransactionView> {
+        self.txs.peek(id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &TransactionView> {
+        self.txs.iter().map(|(_, tx)| tx)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// The total serialized size of the txs.
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.txs.clear();
+        self.size = 0;
+        self.update_metrics(0);
+    }
+
+    fn update_metrics(&self, evicted: usize) {
+        if let Some(metrics) = ckb_metrics::handle() {
+            metrics.ckb_tx_pool_conflicts.count.set(self.len() as i64);
+            metrics.ckb_tx_pool_conflicts.size.set(self.size as i64);
+            metrics.ckb_tx_pool_conflicts_evicted.inc_by(evicted as u64);
+        }
+    }
+}
+
+fn tx_size(tx: &TransactionView) -> usize {
+    tx.data().serialized_size_in_block()
+}
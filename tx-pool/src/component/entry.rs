@@ -41,6 +41,8 @@ pub struct TxEntry {
     pub descendants_count: usize,
     /// The unix timestamp when entering the Txpool, unit: Millisecond
     pub timestamp: u64,
+    /// The virtual fee added by `prioritise_transaction`, only used to sort and package txs
+    pub fee_delta: u64,
 }
 
 impl TxEntry {
@@ -71,6 +73,7 @@ impl TxEntry {
             descendants_cycles: cycles,
             descendants_count: 1,
             ancestors_count: 1,
+            fee_delta: 0,
         }
     }
 
@@ -106,11 +109,63 @@ impl TxEntry {
         AncestorsScoreSortKey::from(self)
     }
 
+    /// Returns the sort key of the descendants package, the tx with its ancestors and descendants
+    /// as a whole, which lets the descendants pay for a parent together
+    pub fn as_package_score_key(&self) -> AncestorsScoreSortKey {
+        let fee = Capacity::shannons(
+            self.ancestors_fee
+                .as_u64()
+                .saturating_add(self.descendants_fee.as_u64())
+                .saturating_sub(self.modified_fee().as_u64()),
+        );
+        let size = self
+            .ancestors_size
+            .saturating_add(self.descendants_size)
+            .saturating_sub(self.size);
+        let cycles = self
+            .ancestors_cycles
+            .saturating_add(self.descendants_cycles)
+            .saturating_sub(self.cycles);
+        let weight = get_transaction_weight(size, cycles);
+        AncestorsScoreSortKey {
+            fee,
+            weight,
+            ancestors_fee: fee,
+            ancestors_weight: weight,
+            tx_hash: self.transaction().hash(),
+        }
+    }
+
     /// Returns a evict_key
     pub fn as_evict_key(&self) -> EvictKey {
         EvictKey::from(self)
     }
 
+    /// Returns the fee with the virtual fee delta, which is used to sort and package txs
+    pub fn modified_fee(&self) -> Capacity {
+        Capacity::shannons(self.fee.as_u64().saturating_add(self.fee_delta))
+    }
+
+    /// Set the virtual fee delta, the ancestors and descendants fees which include the modified
+    /// fee of this tx are updated
+    pub fn set_fee_delta(&mut self, fee_delta: u64) {
+        let old_fee = self.modified_fee().as_u64();
+        self.fee_delta = fee_delta;
+        let new_fee = self.modified_fee().as_u64();
+        self.ancestors_fee = Capacity::shannons(
+            self.ancestors_fee
+                .as_u64()
+                .saturating_sub(old_fee)
+                .saturating_add(new_fee),
+        );
+        self.descendants_fee = Capacity::shannons(
+            self.descendants_fee
+                .as_u64()
+                .saturating_sub(old_fee)
+                .saturating_add(new_fee),
+        );
+    }
+
     /// Returns fee rate
     pub fn fee_rate(&self) -> FeeRate {
         let weight = get_transaction_weight(self.size, self.cycles);
@@ -125,7 +180,7 @@ impl TxEntry {
         self.descendants_fee = Capacity::shannons(
             self.descendants_fee
                 .as_u64()
-                .saturating_add(entry.fee.as_u64()),
+                .saturating_add(entry.modified_fee().as_u64()),
         );
     }
 
@@ -137,7 +192,7 @@ impl TxEntry {
         self.descendants_fee = Capacity::shannons(
             self.descendants_fee
                 .as_u64()
-                .saturating_sub(entry.fee.as_u64()),
+                .saturating_sub(entry.modified_fee().as_u64()),
         );
     }
 
@@ -149,7 +204,7 @@ impl TxEntry {
         self.ancestors_fee = Capacity::shannons(
             self.ancestors_fee
                 .as_u64()
-                .saturating_add(entry.fee.as_u64()),
+                .saturating_add(entry.modified_fee().as_u64()),
         );
     }
 
@@ -161,7 +216,7 @@ impl TxEntry {
         self.ancestors_fee = Capacity::shannons(
             self.ancestors_fee
                 .as_u64()
-                .saturating_sub(entry.fee.as_u64()),
+                .saturating_sub(entry.modified_fee().as_u64()),
         );
     }
 
@@ -170,12 +225,12 @@ impl TxEntry {
         self.ancestors_count = 1;
         self.ancestors_size = self.size;
         self.ancestors_cycles = self.cycles;
-        self.ancestors_fee = self.fee;
+        self.ancestors_fee = self.modified_fee();
 
         self.descendants_count = 1;
         self.descendants_size = self.size;
         self.descendants_cycles = self.cycles;
-        self.descendants_fee = self.fee;
+        self.descendants_fee = self.modified_fee();
     }
 
     /// Converts entry to a `TxEntryInfo`.
@@ -223,10 +278,11 @@ impl From<&TxEntry> for AncestorsScoreSortKey {
         let weight = get_transaction_weight(entry.size, entry.cycles);
         let ancestors_weight = get_transaction_weight(entry.ancestors_size, entry.ancestors_cycles);
         AncestorsScoreSortKey {
-            fee: entry.fee,
+            fee: entry.modified_fee(),
             weight,
             ancestors_fee: entry.ancestors_fee,
             ancestors_weight,
+            tx_hash: entry.transaction().hash(),
         }
     }
 }
@@ -238,11 +294,12 @@ impl From<&TxEntry> for EvictKey {
             get_transaction_weight(entry.descendants_size, entry.descendants_cycles);
 
         let descendants_feerate = FeeRate::calculate(entry.descendants_fee, descendants_weight);
-        let feerate = FeeRate::calculate(entry.fee, weight);
+        let feerate = FeeRate::calculate(entry.modified_fee(), weight);
         EvictKey {
             fee_rate: descendants_feerate.max(feerate),
             timestamp: entry.timestamp,
             descendants_count: entry.descendants_count,
+            tx_hash: entry.transaction().hash(),
         }
     }
 }
@@ -0,0 +1,140 @@
+use ckb_types::core::{BlockNumber, FeeRate};
+use ckb_types::packed::ProposalShortId;
+use std::collections::HashMap;
+
+/// The max number of blocks to estimate the fee rate for.
+pub const MAX_TARGET_BLOCKS: BlockNumber = 128;
+
+// The fee rate buckets grow exponentially from the min to the max
+const MIN_BUCKET_FEE_RATE: u64 = 1_000;
+const MAX_BUCKET_FEE_RATE: u64 = 10_000_000;
+const BUCKET_SPACING: f64 = 1.1;
+// The stats are decayed by this factor per block, so the half-life is about 350 blocks
+const DECAY: f64 = 0.998;
+// A fee rate is estimated for the target if this ratio of the txs paying it are committed in time
+const SUCCESS_THRESHOLD: f64 = 0.85;
+// The min decayed count of the txs to estimate a fee rate from
+const MIN_SAMPLES: f64 = 10.0;
+
+/// Estimates the fee rate for a tx to be committed within the target blocks.
+///
+/// The txs entering the pool are tracked in the buckets by their fee rates. Once committed, the
+/// number of blocks a tx took since it entered the pool is recorded in its bucket. The txs still
+/// in the pool longer than the target count as failures for the target. The txs removed from the
+/// pool without committing, e.g., replaced or expired, are not counted.
+pub(crate) struct FeeEstimator {
+    // the lower bounds of the buckets, in ascending order
+    buckets: Vec<FeeRate>,
+    // bucket => decayed count of the committed txs by the blocks they took, index i is i + 1 blocks
+    committed: Vec<Vec<f64>>,
+    // bucket => decayed count of the committed txs, including the ones which took longer than
+    // `MAX_TARGET_BLOCKS`
+    totals: Vec<f64>,
+    // the txs in the pool => (bucket, the tip number when entering the pool)
+    tracked: HashMap<ProposalShortId, (usize, BlockNumber)>,
+    tip_number: BlockNumber,
+}
+
+impl FeeEstimator {
+    pub(crate) fn new(tip_number: BlockNumber) -> Self {
+        let mut buckets = Vec::new();
+        let mut fee_rate = MIN_BUCKET_FEE_RATE as f64;
+        while fee_rate <= MAX_BUCKET_FEE_RATE as f64 {
+            buckets.push(FeeRate::from_u64(fee_rate as u64));
+            fee_rate *= BUCKET_SPACING;
+        }
+        FeeEstimator {
+            committed: vec![vec![0.0; MAX_TARGET_BLOCKS as usize]; buckets.len()],
+            totals: vec![0.0; buckets.len()],
+            buckets,
+            tracked: HashMap::default(),
+            tip_number,
+        }
+    }
+
+    fn bucket_index(&self, fee_rate: FeeRate) -> usize {
+        self.buckets
+            .partition_point(|bucket| *bucket <= fee_rate)
+            .saturating_sub(1)
+    }
+
+    /// Tracks a tx entering the pool, the tx re-entering the pool keeps its first entry.
+    pub(crate) fn track(
+        &mut self,
+        id: ProposalShortId,
+        fee_rate: FeeRate,
+        tip_number: BlockNumber,
+    ) {
+        let bucket = self.bucket_index(fee_rate);
+        self.tracked.entry(id).or_insert((bucket, tip_number));
+    }
+
+    /// Decays the stats by the new blocks, and stops tracking the txs no longer in the pool.
+    pub(crate) fn process_blocks(
+        &mut self,
+        tip_number: BlockNumber,
+        in_pool: impl Fn(&ProposalShortId) -> bool,
+    ) {
+        let blocks = tip_number.saturating_sub(self.tip_number);
+        self.tip_number = tip_number;
+        if blocks > 0 {
+            let factor = DECAY.powi(blocks.min(i32::MAX as u64) as i32);
+            for (committed, total) in self.committed.iter_mut().zip(self.totals.iter_mut()) {
+                committed.iter_mut().for_each(|count| *count *= factor);
+                *total *= factor;
+            }
+        }
+        self.tracked.retain(|id, _| in_pool(id));
+    }
+
+    /// Records a tracked tx committed in the block.
+    pub(crate) fn commit(&mut self, id: &ProposalShortId, block_number: BlockNumber) {
+        if let Some((bucket, entered_at)) = self.tracked.remove(id) {
+            let blocks = block_number.saturating_sub(entered_at).max(1);
+            if blocks <= MAX_TARGET_BLOCKS {
+                self.committed[bucket][blocks as usize - 1] += 1.0;
+            }
+            self.totals[bucket] += 1.0;
+        }
+    }
+
+    /// Stops tracking a tx removed from the pool right after entering it, e.g., a tx of a
+    /// rejected package.
+    pub(crate) fn untrack(&mut self, id: &ProposalShortId) {
+        self.tracked.remove(id);
+    }
+
+    /// Returns the lowest fee rate, with which at least 85% of the txs are committed within the
+    /// target blocks, starting from the highest fee rates. Returns `None` if there are not
+    /// enough txs to tell.
+    pub(crate) fn estimate(&self, target_blocks: BlockNumber) -> Option<FeeRate> {
+        let target = target_blocks.clamp(1, MAX_TARGET_BLOCKS) as usize;
+        let mut failures = vec![0.0; self.buckets.len()];
+        for (bucket, entered_at) in self.tracked.values() {
+            if self.tip_number.saturating_sub(*entered_at) >= target as u64 {
+                failures[*bucket] += 1.0;
+            }
+        }
+
+        let mut estimated = None;
+        let (mut committed, mut total) = (0.0, 0.0);
+        for bucket in (0..self.buckets.len()).rev() {
+            committed += self.committed[bucket][..target].iter().sum::<f64>();
+            total += self.totals[bucket] + failures[bucket];
+            if total < MIN_SAMPLES {
+                continue;
+            }
+            if committed / total < SUCCESS_THRESHOLD {
+                break;
+            }
+            estimated = Some(self.buckets[bucket]);
+            committed = 0.0;
+            total = 0.0;
+        }
+        estimated
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.tracked.clear();
+    }
+}
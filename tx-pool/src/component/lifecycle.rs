@@ -0,0 +1,292 @@
+use crate::callback::PoolEventListener;
+use crate::component::entry::TxEntry;
+use crate::error::Reject;
+use ckb_async_runtime::Handle;
+use ckb_logger::debug;
+use ckb_systemtime::unix_time_as_millis;
+use ckb_types::core::tx_pool::{TxLifecycle, TxLifecycleEvent, TxLifecycleStage};
+use ckb_types::packed::Byte32;
+use ckb_util::Mutex;
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use lru::LruCache;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::time::timeout;
+
+// the events of a tx are capped, a tx may be packaged and re-proposed many times by reorgs
+const MAX_EVENTS: usize = 32;
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+// the finished traces waiting for the export, the ones beyond are dropped
+const EXPORT_QUEUE_SIZE: usize = 1024;
+// the max traces exported in a request
+const MAX_EXPORT_BATCH: usize = 128;
+// OTLP `SPAN_KIND_INTERNAL`
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// Trace the lifecycle of the transactions through the pool, the block templates and the chain.
+///
+/// A trace id is assigned at the first event of a transaction, a new trace starts if the
+/// transaction comes back after its lifecycle has finished, e.g., re-added after a reorg. The
+/// traces of the most recent `capacity` transactions are kept, and the finished traces are
+/// exported to the OTLP endpoint if configured.
+pub(crate) struct TxLifecycleTracer {
+    next_trace_id: AtomicU64,
+    traces: Option<Mutex<LruCache<Byte32, TxLifecycle>>>,
+    exporter: Option<OtlpExporter>,
+}
+
+impl TxLifecycleTracer {
+    /// Creates a tracer keeping at most `capacity` txs, it's disabled if `capacity` is 0.
+    pub(crate) fn new(capacity: usize) -> Self {
+        TxLifecycleTracer {
+            // OTLP requires non-zero span ids
+            next_trace_id: AtomicU64::new(1),
+            traces: (capacity > 0).then(|| Mutex::new(LruCache::new(capacity))),
+            exporter: None,
+        }
+    }
+
+    pub(crate) fn with_exporter(mut self, exporter: OtlpExporter) -> Self {
+        self.exporter = Some(exporter);
+        self
+    }
+
+    /// Records that the tx enters the stage now.
+    pub(crate) fn record(&self, tx_hash: &Byte32, stage: TxLifecycleStage, detail: Option<String>) {
+        let traces = match self.traces {
+            Some(ref traces) => traces,
+            None => return,
+        };
+        let finished = {
+            let mut traces = traces.lock();
+            if traces.peek(tx_hash).map_or(true, TxLifecycle::is_finished) {
+                let trace = TxLifecycle {
+                    trace_id: self.next_trace_id.fetch_add(1, Ordering::Relaxed),
+                    tx_hash: tx_hash.clone(),
+                    events: Vec::new(),
+                };
+                traces.put(tx_hash.clone(), trace);
+            }
+            let trace = traces.get_mut(tx_hash).expect("inserted above");
+            let repeated = stage == TxLifecycleStage::Packaged
+                && trace
+                    .events
+                    .last()
+                    .map_or(false, |event| event.stage == stage);
+            if repeated || (trace.events.len() >= MAX_EVENTS && !stage.is_final()) {
+                return;
+            }
+            trace.events.push(TxLifecycleEvent {
+                stage,
+                timestamp: unix_time_as_millis(),
+                detail,
+            });
+            stage.is_final().then(|| trace.clone())
+        };
+        if let (Some(trace), Some(exporter)) = (finished, &self.exporter) {
+            exporter.export(&trace);
+        }
+    }
+
+    /// Returns the latest trace of the tx.
+    pub(crate) fn get(&self, tx_hash: &Byte32) -> Option<TxLifecycle> {
+        self.traces
+            .as_ref()
+            .and_then(|traces| traces.lock().peek(tx_hash).cloned())
+    }
+}
+
+impl PoolEventListener for TxLifecycleTracer {
+    fn on_accepted(&self, entry: &TxEntry) {
+        self.record(
+            &entry.transaction().hash(),
+            TxLifecycleStage::Verified,
+            None,
+        );
+    }
+
+    fn on_gap(&self, entry: &TxEntry) {
+        self.record(&entry.transaction().hash(), TxLifecycleStage::Gap, None);
+    }
+
+    fn on_proposed(&self, entry: &TxEntry) {
+        self.record(
+            &entry.transaction().hash(),
+            TxLifecycleStage::Proposed,
+            None,
+        );
+    }
+
+    fn on_rejected(&self, entry: &TxEntry, reject: &Reject) {
+        self.record(
+            &entry.transaction().hash(),
+            TxLifecycleStage::Rejected,
+            Some(reject.to_string()),
+        );
+    }
+
+    fn on_committed(&self, entry: &TxEntry) {
+        self.record(
+            &entry.transaction().hash(),
+            TxLifecycleStage::Committed,
+            None,
+        );
+    }
+}
+
+/// Exports the finished traces as OTLP spans in the JSON encoding over HTTP.
+///
+/// Each trace is a span named `tx_lifecycle` spanning from the first event to the last one, and
+/// the lifecycle events are the span events. The traces are queued and exported in batches by a
+/// background task, the ones beyond the queue are dropped if the endpoint can't keep up.
+pub(crate) struct OtlpExporter {
+    sender: mpsc::Sender<TxLifecycle>,
+}
+
+impl OtlpExporter {
+    pub(crate) fn new(endpoint: &str, handle: Handle) -> Result<Self, String> {
+        let endpoint = endpoint
+            .parse()
+            .map_err(|err| format!("invalid OTLP endpoint {endpoint}: {err}"))?;
+        let (sender, receiver) = mpsc::channel(EXPORT_QUEUE_SIZE);
+        let worker = OtlpExportWorker {
+            endpoint,
+            client: Client::new(),
+            trace_id_prefix: rand::random(),
+        };
+        handle.spawn(worker.run(receiver));
+        Ok(OtlpExporter { sender })
+    }
+
+    fn export(&self, trace: &TxLifecycle) {
+        if let Err(TrySendError::Full(trace)) = self.sender.try_send(trace.clone()) {
+            debug!(
+                "the OTLP export queue is full, trace {} is dropped",
+                trace.trace_id
+            );
+        }
+    }
+}
+
+// Exports the queued traces, one request at a time.
+struct OtlpExportWorker {
+    endpoint: Uri,
+    client: Client<HttpConnector, Body>,
+    // the higher half of the OTLP trace ids, which tells the traces of different runs apart
+    trace_id_prefix: u64,
+}
+
+impl OtlpExportWorker {
+    async fn run(self, mut receiver: mpsc::Receiver<TxLifecycle>) {
+        while let Some(trace) = receiver.recv().await {
+            let mut batch = vec![trace];
+            while batch.len() < MAX_EXPORT_BATCH {
+                match receiver.try_recv() {
+                    Ok(trace) => batch.push(trace),
+                    Err(_) => break,
+                }
+            }
+            self.export(&batch).await;
+        }
+    }
+
+    async fn export(&self, traces: &[TxLifecycle]) {
+        let body = self.encode(traces).to_string();
+        let request = match Request::builder()
+            .method(Method::POST)
+            .uri(self.endpoint.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+        {
+            Ok(request) => request,
+            Err(err) => {
+                debug!("failed to build the OTLP export request: {}", err);
+                return;
+            }
+        };
+        match timeout(EXPORT_TIMEOUT, self.client.request(request)).await {
+            Ok(Ok(response)) if !response.status().is_success() => {
+                debug!(
+                    "OTLP endpoint responded {} to {} traces",
+                    response.status(),
+                    traces.len()
+                );
+            }
+            Ok(Err(err)) => debug!("failed to export {} traces: {}", traces.len(), err),
+            Err(_) => debug!("exporting {} traces timed out", traces.len()),
+            _ => {}
+        }
+    }
+
+    fn encode(&self, traces: &[TxLifecycle]) -> Value {
+        let spans: Vec<Value> = traces.iter().map(|trace| self.encode_span(trace)).collect();
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [string_attribute("service.name", "ckb")],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "ckb-tx-pool" },
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+
+    fn encode_span(&self, trace: &TxLifecycle) -> Value {
+        let nanos = |millis: u64| millis.saturating_mul(1_000_000).to_string();
+        let start = trace.events.first().map_or(0, |event| event.timestamp);
+        let end = trace.events.last().map_or(0, |event| event.timestamp);
+        let events: Vec<Value> = trace
+            .events
+            .iter()
+            .map(|event| {
+                let attributes: Vec<Value> = event
+                    .detail
+                    .iter()
+                    .map(|detail| string_attribute("detail", detail))
+                    .collect();
+                json!({
+                    "timeUnixNano": nanos(event.timestamp),
+                    "name": stage_name(event.stage),
+                    "attributes": attributes,
+                })
+            })
+            .collect();
+        let last_stage = trace
+            .events
+            .last()
+            .map_or("", |event| stage_name(event.stage));
+        json!({
+            "traceId": format!("{:016x}{:016x}", self.trace_id_prefix, trace.trace_id),
+            "spanId": format!("{:016x}", trace.trace_id),
+            "name": "tx_lifecycle",
+            "kind": SPAN_KIND_INTERNAL,
+            "startTimeUnixNano": nanos(start),
+            "endTimeUnixNano": nanos(end),
+            "attributes": [
+                string_attribute("ckb.tx_hash", &format!("{:#x}", trace.tx_hash)),
+                string_attribute("ckb.tx_final_stage", last_stage),
+            ],
+            "events": events,
+        })
+    }
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn stage_name(stage: TxLifecycleStage) -> &'static str {
+    match stage {
+        TxLifecycleStage::Submitted => "submitted",
+        TxLifecycleStage::Verified => "verified",
+        TxLifecycleStage::Gap => "gap",
+        TxLifecycleStage::Proposed => "proposed",
+        TxLifecycleStage::Packaged => "packaged",
+        TxLifecycleStage::Committed => "committed",
+        TxLifecycleStage::Rejected => "rejected",
+    }
+}
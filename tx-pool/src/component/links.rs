@@ -1,5 +1,12 @@
 use ckb_types::packed::ProposalShortId;
+use ckb_util::Mutex;
 use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::sync::Arc;
+
+// The cache is dropped when it holds more sets than the limit, to bound the memory of a pool with
+// many wide and deep chains.
+const MAX_CACHED_SETS: usize = 10_000;
 
 #[derive(Default, Debug, Clone)]
 pub struct TxLinks {
@@ -22,23 +29,163 @@ impl TxLinks {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+/// The memoized sets of one relation.
+///
+/// `containing` indexes the sets by their members, so a stale set is found without scanning the
+/// whole cache.
+#[derive(Default, Debug)]
+struct RelationSets {
+    sets: HashMap<ProposalShortId, Arc<HashSet<ProposalShortId>>>,
+    // id -> the keys whose set is the id's own or contains the id
+    containing: HashMap<ProposalShortId, HashSet<ProposalShortId>>,
+}
+
+impl RelationSets {
+    fn get(&self, short_id: &ProposalShortId) -> Option<&Arc<HashSet<ProposalShortId>>> {
+        self.sets.get(short_id)
+    }
+
+    fn insert(&mut self, short_id: ProposalShortId, ids: Arc<HashSet<ProposalShortId>>) {
+        self.remove(&short_id);
+        if self.sets.len() >= MAX_CACHED_SETS {
+            self.clear();
+        }
+        for id in ids.iter().chain(iter::once(&short_id)) {
+            self.containing
+                .entry(id.clone())
+                .or_default()
+                .insert(short_id.clone());
+        }
+        self.sets.insert(short_id, ids);
+    }
+
+    fn remove(&mut self, short_id: &ProposalShortId) {
+        if let Some(ids) = self.sets.remove(short_id) {
+            for id in ids.iter().chain(iter::once(short_id)) {
+                if let Some(keys) = self.containing.get_mut(id) {
+                    keys.remove(short_id);
+                    if keys.is_empty() {
+                        self.containing.remove(id);
+                    }
+                }
+            }
+        }
+    }
+
+    // drops the set of `short_id` and every set containing it
+    fn invalidate(&mut self, short_id: &ProposalShortId) {
+        if let Some(keys) = self.containing.remove(short_id) {
+            for key in keys {
+                self.remove(&key);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.sets.clear();
+        self.containing.clear();
+    }
+}
+
+/// The memoized ancestor and descendant sets.
+///
+/// A set is computed on the first query and kept until a link change makes it stale.
+#[derive(Default, Debug)]
+struct RelationCache {
+    ancestors: RelationSets,
+    descendants: RelationSets,
+}
+
+impl RelationCache {
+    fn sets(&self, relation: Relation) -> &RelationSets {
+        match relation {
+            Relation::Parents => &self.ancestors,
+            Relation::Children => &self.descendants,
+        }
+    }
+
+    fn sets_mut(&mut self, relation: Relation) -> &mut RelationSets {
+        match relation {
+            Relation::Parents => &mut self.ancestors,
+            Relation::Children => &mut self.descendants,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        relation: Relation,
+        short_id: ProposalShortId,
+        ids: Arc<HashSet<ProposalShortId>>,
+    ) {
+        self.sets_mut(relation).insert(short_id, ids);
+    }
+
+    // Adding or removing the link `parent -> child` only changes the descendants of `parent` and
+    // its ancestors, and the ancestors of `child` and its descendants.
+    fn invalidate_link(&mut self, parent: &ProposalShortId, child: &ProposalShortId) {
+        self.descendants.invalidate(parent);
+        self.ancestors.invalidate(child);
+    }
+
+    fn invalidate_tx(&mut self, short_id: &ProposalShortId) {
+        self.descendants.invalidate(short_id);
+        self.ancestors.invalidate(short_id);
+    }
+
+    fn clear(&mut self) {
+        self.ancestors.clear();
+        self.descendants.clear();
+    }
+}
+
+#[derive(Default, Debug)]
 pub struct TxLinksMap {
     pub inner: HashMap<ProposalShortId, TxLinks>,
+    // the pool is read concurrently, the cache is filled under the read lock
+    cache: Mutex<RelationCache>,
+}
+
+impl Clone for TxLinksMap {
+    fn clone(&self) -> Self {
+        TxLinksMap {
+            inner: self.inner.clone(),
+            cache: Default::default(),
+        }
+    }
 }
 
 impl TxLinksMap {
     pub fn new() -> Self {
         TxLinksMap {
             inner: Default::default(),
+            cache: Default::default(),
         }
     }
 
+    #[cfg(test)]
+    pub(crate) fn cached_sets_len(&self) -> (usize, usize) {
+        let cache = self.cache.lock();
+        (cache.ancestors.sets.len(), cache.descendants.sets.len())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn cache_index_len(&self) -> (usize, usize) {
+        let cache = self.cache.lock();
+        (
+            cache.ancestors.containing.len(),
+            cache.descendants.containing.len(),
+        )
+    }
+
     fn calc_relative_ids(
         &self,
         short_id: &ProposalShortId,
         relation: Relation,
-    ) -> HashSet<ProposalShortId> {
+    ) -> Arc<HashSet<ProposalShortId>> {
+        if let Some(ids) = self.cache.lock().sets(relation).get(short_id) {
+            return Arc::clone(ids);
+        }
+
         let direct = self
             .inner
             .get(short_id)
@@ -46,7 +193,13 @@ impl TxLinksMap {
             .cloned()
             .unwrap_or_default();
 
-        self.calc_relation_ids(direct, relation)
+        let ids = Arc::new(self.calc_relation_ids(direct, relation));
+        if self.inner.contains_key(short_id) {
+            self.cache
+                .lock()
+                .insert(relation, short_id.clone(), Arc::clone(&ids));
+        }
+        ids
     }
 
     pub fn calc_relation_ids(
@@ -54,9 +207,20 @@ impl TxLinksMap {
         mut stage: HashSet<ProposalShortId>,
         relation: Relation,
     ) -> HashSet<ProposalShortId> {
+        let cache = self.cache.lock();
+        let cached_sets = cache.sets(relation);
         let mut relation_ids = HashSet::with_capacity(stage.len());
 
         while let Some(id) = stage.iter().next().cloned() {
+            stage.remove(&id);
+            if !relation_ids.insert(id.clone()) {
+                continue;
+            }
+            // a memoized set is transitively closed, no need to walk through it
+            if let Some(ids) = cached_sets.get(&id) {
+                relation_ids.extend(ids.iter().cloned());
+                continue;
+            }
             //recursively
             if let Some(tx_links) = self.inner.get(&id) {
                 for direct_id in tx_links.get_direct_ids(relation) {
@@ -65,22 +229,40 @@ impl TxLinksMap {
                     }
                 }
             }
-            stage.remove(&id);
-            relation_ids.insert(id);
         }
         relation_ids
     }
 
     pub fn add_link(&mut self, short_id: ProposalShortId, links: TxLinks) {
+        let cache = self.cache.get_mut();
+        cache.invalidate_tx(&short_id);
+        for parent in &links.parents {
+            cache.invalidate_link(parent, &short_id);
+        }
+        for child in &links.children {
+            cache.invalidate_link(&short_id, child);
+        }
         self.inner.insert(short_id, links);
     }
 
     pub fn calc_ancestors(&self, short_id: &ProposalShortId) -> HashSet<ProposalShortId> {
         self.calc_relative_ids(short_id, Relation::Parents)
+            .as_ref()
+            .clone()
     }
 
     pub fn calc_descendants(&self, short_id: &ProposalShortId) -> HashSet<ProposalShortId> {
         self.calc_relative_ids(short_id, Relation::Children)
+            .as_ref()
+            .clone()
+    }
+
+    pub fn ancestors_count(&self, short_id: &ProposalShortId) -> usize {
+        self.calc_relative_ids(short_id, Relation::Parents).len()
+    }
+
+    pub fn descendants_count(&self, short_id: &ProposalShortId) -> usize {
+        self.calc_relative_ids(short_id, Relation::Children).len()
     }
 
     pub fn get_children(&self, short_id: &ProposalShortId) -> Option<&HashSet<ProposalShortId>> {
@@ -92,6 +274,7 @@ impl TxLinksMap {
     }
 
     pub fn remove(&mut self, short_id: &ProposalShortId) -> Option<TxLinks> {
+        self.cache.get_mut().invalidate_tx(short_id);
         self.inner.remove(short_id)
     }
 
@@ -100,6 +283,7 @@ impl TxLinksMap {
         short_id: &ProposalShortId,
         child: &ProposalShortId,
     ) -> Option<bool> {
+        self.cache.get_mut().invalidate_link(short_id, child);
         self.inner
             .get_mut(short_id)
             .map(|links| links.children.remove(child))
@@ -110,6 +294,7 @@ impl TxLinksMap {
         short_id: &ProposalShortId,
         parent: &ProposalShortId,
     ) -> Option<bool> {
+        self.cache.get_mut().invalidate_link(parent, short_id);
         self.inner
             .get_mut(short_id)
             .map(|links| links.parents.remove(parent))
@@ -120,6 +305,7 @@ impl TxLinksMap {
         short_id: &ProposalShortId,
         child: ProposalShortId,
     ) -> Option<bool> {
+        self.cache.get_mut().invalidate_link(short_id, &child);
         self.inner
             .get_mut(short_id)
             .map(|links| links.children.insert(child))
@@ -130,12 +316,14 @@ impl TxLinksMap {
         short_id: &ProposalShortId,
         parent: ProposalShortId,
     ) -> Option<bool> {
+        self.cache.get_mut().invalidate_link(&parent, short_id);
         self.inner
             .get_mut(short_id)
             .map(|links| links.parents.insert(parent))
     }
 
     pub fn clear(&mut self) {
+        self.cache.get_mut().clear();
         self.inner.clear();
     }
 }
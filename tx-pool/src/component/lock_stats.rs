@@ -0,0 +1,84 @@
+use crate::component::entry::TxEntry;
+use ckb_types::core::{tx_pool::LockScriptStats, Capacity};
+use ckb_types::{packed::Byte32, prelude::*};
+use std::cmp::Ordering;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+/// The max count of the lock scripts reported in `tx_pool_info`.
+pub(crate) const MAX_TOP_LOCK_SCRIPTS: usize = 10;
+
+/// The txs in the pool grouped by the lock scripts of their inputs, updated incrementally as the
+/// txs enter and leave the pool.
+///
+/// A spam campaign usually unlocks the cells of a few lock scripts, which stand out in the
+/// groups taking the most bytes.
+#[derive(Default)]
+pub(crate) struct LockStats {
+    groups: HashMap<Byte32, LockScriptStats>,
+}
+
+impl LockStats {
+    pub(crate) fn add(&mut self, entry: &TxEntry) {
+        for lock_hash in input_lock_hashes(entry) {
+            let stats = self
+                .groups
+                .entry(lock_hash.clone())
+                .or_insert_with(|| LockScriptStats {
+                    lock_hash,
+                    ..Default::default()
+                });
+            stats.tx_count += 1;
+            stats.tx_size = stats.tx_size.saturating_add(entry.size);
+            stats.fees = Capacity::shannons(stats.fees.as_u64().saturating_add(entry.fee.as_u64()));
+        }
+    }
+
+    pub(crate) fn remove(&mut self, entry: &TxEntry) {
+        for lock_hash in input_lock_hashes(entry) {
+            if let Entry::Occupied(mut group) = self.groups.entry(lock_hash) {
+                let stats = group.get_mut();
+                stats.tx_count = stats.tx_count.saturating_sub(1);
+                stats.tx_size = stats.tx_size.saturating_sub(entry.size);
+                stats.fees =
+                    Capacity::shannons(stats.fees.as_u64().saturating_sub(entry.fee.as_u64()));
+                if stats.tx_count == 0 {
+                    group.remove();
+                }
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.groups.clear();
+    }
+
+    /// Returns at most `n` groups taking the most bytes, the largest first.
+    pub(crate) fn top(&self, n: usize) -> Vec<LockScriptStats> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut groups: Vec<&LockScriptStats> = self.groups.values().collect();
+        if groups.len() > n {
+            groups.select_nth_unstable_by(n - 1, |a, b| cmp_by_size(a, b));
+            groups.truncate(n);
+        }
+        groups.sort_unstable_by(|a, b| cmp_by_size(a, b));
+        groups.into_iter().cloned().collect()
+    }
+}
+
+fn cmp_by_size(a: &LockScriptStats, b: &LockScriptStats) -> Ordering {
+    b.tx_size
+        .cmp(&a.tx_size)
+        .then_with(|| b.tx_count.cmp(&a.tx_count))
+        .then_with(|| a.lock_hash.as_slice().cmp(b.lock_hash.as_slice()))
+}
+
+fn input_lock_hashes(entry: &TxEntry) -> HashSet<Byte32> {
+    entry
+        .rtx
+        .resolved_inputs
+        .iter()
+        .map(|cell| cell.cell_output.calc_lock_hash())
+        .collect()
+}
@@ -1,14 +1,22 @@
 pub mod commit_txs_scanner;
 pub mod entry;
 
+pub(crate) mod conflicts_pool;
 pub(crate) mod edges;
+pub(crate) mod fee_estimator;
+pub(crate) mod lifecycle;
 pub(crate) mod links;
+pub(crate) mod lock_stats;
 pub(crate) mod orphan;
 pub(crate) mod pool_map;
+pub(crate) mod rbf_limiter;
+pub(crate) mod read_view;
 pub(crate) mod recent_reject;
 pub(crate) mod sort_key;
+pub(crate) mod template_protection;
 #[cfg(test)]
 mod tests;
 pub(crate) mod verify_queue;
+pub(crate) mod witness_store;
 
 pub use self::entry::TxEntry;
@@ -4,12 +4,17 @@ extern crate slab;
 use super::links::TxLinks;
 use crate::component::edges::Edges;
 use crate::component::links::{Relation, TxLinksMap};
+use crate::component::lock_stats::LockStats;
 use crate::component::sort_key::{AncestorsScoreSortKey, EvictKey};
+use crate::component::witness_store::WitnessStore;
 use crate::error::Reject;
 use crate::TxEntry;
 use ckb_logger::{debug, error, trace};
 use ckb_types::core::error::OutPointError;
-use ckb_types::core::Cycle;
+use ckb_types::core::tx_pool::{
+    get_transaction_weight, CellPoolStatus, LockScriptStats, TxsBacklog,
+};
+use ckb_types::core::{Capacity, Cycle};
 use ckb_types::packed::OutPoint;
 use ckb_types::prelude::*;
 use ckb_types::{
@@ -18,7 +23,10 @@ use ckb_types::{
     packed::{Byte32, CellOutput, ProposalShortId},
 };
 use multi_index_map::MultiIndexMap;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Bound;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 type ConflictEntry = (TxEntry, Reject);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -38,6 +46,13 @@ impl ToString for Status {
     }
 }
 
+/// A page of the pool entries in the descending order of the scores.
+pub(crate) struct ScoreSortedPage<'a> {
+    pub(crate) entries: Vec<&'a TxEntry>,
+    /// The cursor of the next page, `None` if it's the last page
+    pub(crate) next: Option<AncestorsScoreSortKey>,
+}
+
 #[derive(Copy, Clone)]
 enum EntryOp {
     Add,
@@ -54,6 +69,8 @@ pub struct PoolEntry {
     pub status: Status,
     #[multi_index(ordered_non_unique)]
     pub evict_key: EvictKey,
+    #[multi_index(ordered_non_unique)]
+    pub package_score: AncestorsScoreSortKey,
     // other sort key
     pub inner: TxEntry,
 }
@@ -73,6 +90,19 @@ pub struct PoolMap {
     pub(crate) pending_count: usize,
     pub(crate) gap_count: usize,
     pub(crate) proposed_count: usize,
+    // the totals of the proposed txs, and of the pending or gap txs, see `backlog`
+    proposed_backlog: TxsBacklog,
+    pending_backlog: TxsBacklog,
+    // bumped on every mutation, see `PoolReadView`
+    pub(crate) version: Arc<AtomicU64>,
+    // keeps the witnesses of the large txs out of memory, see `lazy_witnesses_threshold`
+    pub(crate) witness_store: Option<WitnessStore>,
+    // the txs grouped by the lock scripts of their inputs
+    pub(crate) lock_stats: LockStats,
+    // the proposed txs kept in pending as they exceed `max_ancestors_count`, see `add_demoted_entry`
+    pub(crate) demoted: HashSet<ProposalShortId>,
+    // mirrors the score index of `entries`, which the pages seek by a range bound
+    score_index: BTreeMap<AncestorsScoreSortKey, ProposalShortId>,
 }
 
 impl PoolMap {
@@ -87,9 +117,21 @@ impl PoolMap {
             pending_count: 0,
             gap_count: 0,
             proposed_count: 0,
+            proposed_backlog: TxsBacklog::default(),
+            pending_backlog: TxsBacklog::default(),
+            version: Arc::new(AtomicU64::new(0)),
+            witness_store: None,
+            lock_stats: LockStats::default(),
+            demoted: HashSet::new(),
+            score_index: BTreeMap::new(),
         }
     }
 
+    /// Marks the pool as mutated, the read view copies built before are outdated
+    pub(crate) fn bump_version(&self) {
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
     #[cfg(test)]
     pub(crate) fn header_deps_len(&self) -> usize {
         self.edges.header_deps_len()
@@ -152,10 +194,34 @@ impl PoolMap {
         self.proposed_count
     }
 
+    /// Returns at most `n` lock scripts whose txs take the most bytes in the pool
+    pub(crate) fn top_lock_scripts(&self, n: usize) -> Vec<LockScriptStats> {
+        self.lock_stats.top(n)
+    }
+
     pub(crate) fn sorted_proposed_iter(&self) -> impl Iterator<Item = &TxEntry> {
         self.score_sorted_iter_by_status(Status::Proposed)
     }
 
+    /// Iterates the proposed txs whose descendants packages beat their own ancestors packages, in
+    /// the descending order of the package scores, see `TxEntry::as_package_score_key`.
+    ///
+    /// The package scores are kept along with the ancestors and descendants states, they count
+    /// all the in-pool relatives, so the packages are only candidates to evaluate.
+    pub(crate) fn sorted_proposed_packages_iter(
+        &self,
+    ) -> impl Iterator<Item = (&AncestorsScoreSortKey, &TxEntry)> {
+        self.entries
+            .iter_by_package_score()
+            .rev()
+            .filter(|entry| {
+                entry.status == Status::Proposed
+                    && entry.inner.descendants_count > 1
+                    && entry.package_score > entry.score
+            })
+            .map(|entry| (&entry.package_score, &entry.inner))
+    }
+
     pub(crate) fn get(&self, id: &ProposalShortId) -> Option<&TxEntry> {
         self.get_by_id(id).map(|entry| &entry.inner)
     }
@@ -181,6 +247,38 @@ impl PoolMap {
         self.links.calc_descendants(short_id)
     }
 
+    /// calculate the package of a proposed tx to commit with its proposed descendants, which
+    /// includes the tx, its descendants and the ancestors of them all, only the proposed ones
+    pub(crate) fn calc_descendants_package(
+        &self,
+        short_id: &ProposalShortId,
+    ) -> HashSet<ProposalShortId> {
+        let mut package: HashSet<ProposalShortId> = self
+            .calc_descendants(short_id)
+            .into_iter()
+            .filter(|id| self.has_proposed(id))
+            .collect();
+        package.insert(short_id.clone());
+        // a descendant may spend the outputs of the txs other than the ancestors of `short_id`
+        let ancestors: Vec<ProposalShortId> = package
+            .iter()
+            .flat_map(|id| self.calc_ancestors(id))
+            .filter(|id| self.has_proposed(id))
+            .collect();
+        package.extend(ancestors);
+        package
+    }
+
+    /// count all ancestors from pool, without copying the memoized set
+    pub(crate) fn ancestors_count(&self, short_id: &ProposalShortId) -> usize {
+        self.links.ancestors_count(short_id)
+    }
+
+    /// count all descendants from pool, without copying the memoized set
+    pub(crate) fn descendants_count(&self, short_id: &ProposalShortId) -> usize {
+        self.links.descendants_count(short_id)
+    }
+
     pub(crate) fn get_output_with_data(&self, out_point: &OutPoint) -> Option<(CellOutput, Bytes)> {
         self.get(&ProposalShortId::from_tx_hash(&out_point.tx_hash()))
             .and_then(|entry| {
@@ -190,6 +288,29 @@ impl PoolMap {
             })
     }
 
+    /// Returns the pool txs which create and spend the cell
+    pub(crate) fn cell_pool_status(&self, out_point: &OutPoint) -> CellPoolStatus {
+        let created_by = self
+            .get_output_with_data(out_point)
+            .map(|_| out_point.tx_hash());
+        let spent_by = self
+            .edges
+            .get_input_ref(out_point)
+            .and_then(|id| self.get(id))
+            .map(|entry| entry.transaction().hash());
+        CellPoolStatus {
+            created_by,
+            spent_by,
+        }
+    }
+
+    /// Returns true if the in-pool ancestors of the tx exceed `max_ancestors_count`, `add_entry`
+    /// either rejects it or evicts the txs referring its inputs as cell deps.
+    pub(crate) fn exceeds_ancestors_count(&self, tx: &TransactionView) -> bool {
+        let (ancestors, _parents, _cell_ref_parents) = self.get_tx_ancenstors(tx);
+        ancestors.len() + 1 > self.max_ancestors_count
+    }
+
     /// Inesrt a `TxEntry` into pool_map.
     ///
     /// ## Returns
@@ -210,24 +331,97 @@ impl PoolMap {
         }
         trace!("pool_map.add_{:?} {}", status, entry.transaction().hash());
         evicts = self.check_and_record_ancestors(&mut entry)?;
+        self.record_entry(entry, status)?;
+        Ok((true, evicts))
+    }
+
+    /// Insert a proposed `TxEntry` which exceeds `max_ancestors_count` into pending, instead of
+    /// rejecting it.
+    ///
+    /// The demoted entry stays in pending until enough of its in-pool ancestors are committed,
+    /// see `promote`.
+    pub(crate) fn add_demoted_entry(&mut self, mut entry: TxEntry) -> Result<bool, Reject> {
+        let tx_short_id = entry.proposal_short_id();
+        if self.entries.get_by_id(&tx_short_id).is_some() {
+            return Ok(false);
+        }
+        trace!("pool_map.add_demoted {}", entry.transaction().hash());
+        let (ancestors, parents, _cell_ref_parents) = self.get_tx_ancenstors(entry.transaction());
+        self._record_ancestors(&mut entry, ancestors, parents);
+        self.record_entry(entry, Status::Pending)?;
+        self.demoted.insert(tx_short_id);
+        Ok(true)
+    }
+
+    fn record_entry(&mut self, mut entry: TxEntry, status: Status) -> Result<(), Reject> {
         self.record_entry_edges(&entry)?;
+        if let Some(witness_store) = self.witness_store.as_mut() {
+            witness_store.strip(&mut entry);
+        }
         self.insert_entry(&entry, status);
         self.record_entry_descendants(&entry);
         self.track_entry_statics(None, Some(status));
+        self.track_entry_backlog(&entry, None, Some(status));
         self.update_stat_for_add_tx(entry.size, entry.cycles);
-        Ok((true, evicts))
+        self.lock_stats.add(&entry);
+        Ok(())
+    }
+
+    /// Returns true if the entry is demoted, see `add_demoted_entry`
+    pub(crate) fn is_demoted(&self, id: &ProposalShortId) -> bool {
+        self.demoted.contains(id)
+    }
+
+    /// Clears the demotion of the entry if its in-pool ancestors fit `max_ancestors_count` now,
+    /// returns false if it still exceeds the limit.
+    pub(crate) fn promote(&mut self, id: &ProposalShortId) -> bool {
+        if self.ancestors_count(id) >= self.max_ancestors_count {
+            return false;
+        }
+        self.demoted.remove(id);
+        true
     }
 
     /// Change the status of the entry, only used for `gap_rtx` and `proposed_rtx`
     pub(crate) fn set_entry(&mut self, short_id: &ProposalShortId, status: Status) {
-        let mut old_status = None;
+        let mut old = None;
         self.entries
             .modify_by_id(short_id, |e| {
-                old_status = Some(e.status);
+                old = Some((e.status, e.inner.clone()));
                 e.status = status;
             })
             .expect("unconsistent pool");
-        self.track_entry_statics(old_status, Some(status));
+        let (old_status, entry) = old.expect("unconsistent pool");
+        self.track_entry_statics(Some(old_status), Some(status));
+        self.track_entry_backlog(&entry, Some(old_status), Some(status));
+        self.bump_version();
+    }
+
+    /// Set the virtual fee delta of the entry, and update the score keys of its relatives
+    pub(crate) fn set_fee_delta(&mut self, id: &ProposalShortId, fee_delta: u64) -> bool {
+        let old_entry = match self.entries.get_by_id(id) {
+            Some(entry) => entry.inner.clone(),
+            None => return false,
+        };
+        let mut new_entry = old_entry.clone();
+        new_entry.set_fee_delta(fee_delta);
+
+        // replace the modified fee of the entry in the ancestors and descendants states
+        self.update_ancestors_index_key(&old_entry, EntryOp::Remove);
+        self.update_descendants_index_key(&old_entry, EntryOp::Remove);
+        let score_index = &mut self.score_index;
+        self.entries.modify_by_id(id, |e| {
+            e.inner.set_fee_delta(fee_delta);
+            score_index.remove(&e.score);
+            e.score = e.inner.as_score_key();
+            score_index.insert(e.score.clone(), e.id.clone());
+            e.evict_key = e.inner.as_evict_key();
+            e.package_score = e.inner.as_package_score_key();
+        });
+        self.update_ancestors_index_key(&new_entry, EntryOp::Add);
+        self.update_descendants_index_key(&new_entry, EntryOp::Add);
+        self.bump_version();
+        true
     }
 
     pub(crate) fn remove_entry(&mut self, id: &ProposalShortId) -> Option<TxEntry> {
@@ -237,16 +431,67 @@ impl PoolMap {
                 entry.inner.transaction().hash(),
                 entry.status
             );
+            self.score_index.remove(&entry.score);
             self.update_ancestors_index_key(&entry.inner, EntryOp::Remove);
             self.update_descendants_index_key(&entry.inner, EntryOp::Remove);
             self.remove_entry_edges(&entry.inner);
             self.remove_entry_links(id);
             self.track_entry_statics(Some(entry.status), None);
+            self.track_entry_backlog(&entry.inner, Some(entry.status), None);
             self.update_stat_for_remove_tx(entry.inner.size, entry.inner.cycles);
-            entry.inner
+            self.lock_stats.remove(&entry.inner);
+            self.demoted.remove(id);
+            self.bump_version();
+            match self.witness_store.as_mut() {
+                Some(witness_store) => {
+                    let hash = entry.inner.transaction().hash();
+                    let full = witness_store.restore(&entry.inner).unwrap_or_else(|err| {
+                        error!("Failed to restore the removed tx {}: {}", hash, err);
+                        entry.inner
+                    });
+                    witness_store.remove(&hash);
+                    full
+                }
+                None => entry.inner,
+            }
         })
     }
 
+    /// Returns the entry with the full transaction, the witnesses of the large txs are kept in
+    /// the witness store. Returns `None` if the witnesses can't be read from the store.
+    pub(crate) fn full_entry(&self, entry: &TxEntry) -> Option<TxEntry> {
+        match self.witness_store.as_ref() {
+            Some(witness_store) => witness_store
+                .restore(entry)
+                .map_err(|err| {
+                    error!(
+                        "Failed to restore tx {}: {}",
+                        entry.transaction().hash(),
+                        err
+                    )
+                })
+                .ok(),
+            None => Some(entry.clone()),
+        }
+    }
+
+    /// Returns the full transaction of an entry, see `full_entry`.
+    pub(crate) fn full_transaction(&self, entry: &TxEntry) -> Option<TransactionView> {
+        match self.witness_store.as_ref() {
+            Some(witness_store) => witness_store
+                .restore_transaction(entry.transaction())
+                .map_err(|err| {
+                    error!(
+                        "Failed to restore tx {}: {}",
+                        entry.transaction().hash(),
+                        err
+                    )
+                })
+                .ok(),
+            None => Some(entry.transaction().clone()),
+        }
+    }
+
     pub(crate) fn remove_entry_and_descendants(&mut self, id: &ProposalShortId) -> Vec<TxEntry> {
         let mut removed_ids = vec![id.to_owned()];
         removed_ids.extend(self.calc_descendants(id));
@@ -348,15 +593,45 @@ impl PoolMap {
         self.entries.iter().map(|(_, entry)| entry)
     }
 
+    /// Sums up the txs besides the `packaged` ones, returns the proposed txs and the pending or
+    /// gap txs.
+    ///
+    /// The totals are maintained as the entries change, only the `packaged` txs are walked.
+    pub(crate) fn backlog(&self, packaged: &[TxEntry]) -> (TxsBacklog, TxsBacklog) {
+        let mut proposed = self.proposed_backlog;
+        for entry in packaged {
+            if let Some(entry) = self.get_by_id(&entry.proposal_short_id()) {
+                if entry.status == Status::Proposed {
+                    sub_backlog(&mut proposed, &entry.inner);
+                }
+            }
+        }
+        (proposed, self.pending_backlog)
+    }
+
+    #[cfg(test)]
     pub(crate) fn next_evict_entry(&self, status: Status) -> Option<ProposalShortId> {
+        self.next_evict_entry_by(status, |_| true)
+    }
+
+    /// Returns the next entry in `status` to evict, skipping the entries which are not `evictable`
+    pub(crate) fn next_evict_entry_by<F>(
+        &self,
+        status: Status,
+        evictable: F,
+    ) -> Option<ProposalShortId>
+    where
+        F: Fn(&ProposalShortId) -> bool,
+    {
         self.entries
             .iter_by_evict_key()
-            .find(move |entry| entry.status == status)
+            .find(move |entry| entry.status == status && evictable(&entry.id))
             .map(|entry| entry.id.clone())
     }
 
     pub(crate) fn clear(&mut self) {
         self.entries = MultiIndexPoolEntryMap::default();
+        self.score_index.clear();
         self.edges.clear();
         self.links.clear();
         self.total_tx_size = 0;
@@ -364,26 +639,64 @@ impl PoolMap {
         self.pending_count = 0;
         self.gap_count = 0;
         self.proposed_count = 0;
+        self.proposed_backlog = TxsBacklog::default();
+        self.pending_backlog = TxsBacklog::default();
+        self.lock_stats.clear();
+        self.demoted.clear();
+        if let Some(witness_store) = self.witness_store.as_mut() {
+            witness_store.clear();
+        }
+        self.bump_version();
     }
 
-    pub(crate) fn score_sorted_iter_by_status(
-        &self,
-        status: Status,
-    ) -> impl Iterator<Item = &TxEntry> {
+    /// Iterates all the entries and their statuses in the descending order of the scores, the
+    /// score index is walked backwards without collecting the entries.
+    pub(crate) fn score_sorted_iter(&self) -> impl Iterator<Item = (Status, &TxEntry)> {
         self.entries
             .iter_by_score()
             .rev()
-            .filter_map(move |entry| (entry.status == status).then_some(&entry.inner))
+            .map(|entry| (entry.status, &entry.inner))
     }
 
-    pub(crate) fn score_sorted_iter_by_statuses(
+    pub(crate) fn score_sorted_iter_by_status(
         &self,
-        statuses: Vec<Status>,
+        status: Status,
     ) -> impl Iterator<Item = &TxEntry> {
-        self.entries
-            .iter_by_score()
+        self.score_sorted_iter()
+            .filter_map(move |(s, entry)| (s == status).then_some(entry))
+    }
+
+    /// Returns at most `limit` entries of the statuses in the descending order of the scores,
+    /// starting after the cursor `after`, which is the `next` of the previous page.
+    ///
+    /// The score keys are unique as they end with the tx hash, so the entries staying in the pool
+    /// are neither repeated nor skipped by the pages when the other entries are added or removed
+    /// in between, unless their own scores change.
+    pub(crate) fn score_sorted_page(
+        &self,
+        statuses: &[Status],
+        after: Option<&AncestorsScoreSortKey>,
+        limit: usize,
+    ) -> ScoreSortedPage<'_> {
+        let upper = after.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut iter = self
+            .score_index
+            .range((Bound::Unbounded, upper))
             .rev()
-            .filter_map(move |entry| statuses.contains(&entry.status).then_some(&entry.inner))
+            .filter_map(|(_, id)| self.entries.get_by_id(id))
+            .filter(|entry| statuses.contains(&entry.status));
+        let mut entries = Vec::with_capacity(limit.min(self.entries.len()));
+        let mut last = None;
+        for entry in iter.by_ref().take(limit) {
+            entries.push(&entry.inner);
+            last = Some(&entry.score);
+        }
+        let next = if entries.len() == limit && iter.next().is_some() {
+            last.cloned()
+        } else {
+            None
+        };
+        ScoreSortedPage { entries, next }
     }
 
     fn remove_entry_links(&mut self, id: &ProposalShortId) {
@@ -411,6 +724,7 @@ impl PoolMap {
                     EntryOp::Add => e.inner.add_descendant_weight(child),
                 };
                 e.evict_key = e.inner.as_evict_key();
+                e.package_score = e.inner.as_package_score_key();
             });
         }
     }
@@ -418,6 +732,7 @@ impl PoolMap {
     fn update_descendants_index_key(&mut self, parent: &TxEntry, op: EntryOp) {
         let descendants: HashSet<ProposalShortId> =
             self.links.calc_descendants(&parent.proposal_short_id());
+        let score_index = &mut self.score_index;
         for desc_id in &descendants {
             // update child score
             self.entries.modify_by_id(desc_id, |e| {
@@ -425,7 +740,10 @@ impl PoolMap {
                     EntryOp::Remove => e.inner.sub_ancestor_weight(parent),
                     EntryOp::Add => e.inner.add_ancestor_weight(parent),
                 };
+                score_index.remove(&e.score);
                 e.score = e.inner.as_score_key();
+                score_index.insert(e.score.clone(), e.id.clone());
+                e.package_score = e.inner.as_package_score_key();
             });
         }
     }
@@ -471,11 +789,9 @@ impl PoolMap {
         }
         // update children
         if !children.is_empty() {
-            for child in &children {
-                self.links.add_parent(child, tx_short_id.clone());
-            }
-            if let Some(links) = self.links.inner.get_mut(&tx_short_id) {
-                links.children.extend(children);
+            for child in children {
+                self.links.add_parent(&child, tx_short_id.clone());
+                self.links.add_child(&tx_short_id, child);
             }
             self.update_descendants_index_key(entry, EntryOp::Add);
         }
@@ -627,13 +943,17 @@ impl PoolMap {
         let tx_short_id = entry.proposal_short_id();
         let score = entry.as_score_key();
         let evict_key = entry.as_evict_key();
+        let package_score = entry.as_package_score_key();
+        self.score_index.insert(score.clone(), tx_short_id.clone());
         self.entries.insert(PoolEntry {
             id: tx_short_id,
             score,
             status,
             inner: entry.clone(),
             evict_key,
+            package_score,
         });
+        self.bump_version();
     }
 
     fn track_entry_statics(&mut self, remove: Option<Status>, add: Option<Status>) {
@@ -666,6 +986,27 @@ impl PoolMap {
         }
     }
 
+    fn track_entry_backlog(
+        &mut self,
+        entry: &TxEntry,
+        remove: Option<Status>,
+        add: Option<Status>,
+    ) {
+        if let Some(status) = remove {
+            sub_backlog(self.backlog_mut(status), entry);
+        }
+        if let Some(status) = add {
+            add_backlog(self.backlog_mut(status), entry);
+        }
+    }
+
+    fn backlog_mut(&mut self, status: Status) -> &mut TxsBacklog {
+        match status {
+            Status::Proposed => &mut self.proposed_backlog,
+            Status::Pending | Status::Gap => &mut self.pending_backlog,
+        }
+    }
+
     /// Update size and cycles statistics for add tx
     fn update_stat_for_add_tx(&mut self, tx_size: usize, cycles: Cycle) {
         let total_tx_size = self.total_tx_size.checked_add(tx_size).unwrap_or_else(|| {
@@ -707,3 +1048,23 @@ impl PoolMap {
         self.total_tx_cycles = total_tx_cycles;
     }
 }
+
+fn add_backlog(backlog: &mut TxsBacklog, tx: &TxEntry) {
+    backlog.tx_count += 1;
+    backlog.tx_size = backlog.tx_size.saturating_add(tx.size);
+    backlog.cycles = backlog.cycles.saturating_add(tx.cycles);
+    backlog.weight = backlog
+        .weight
+        .saturating_add(get_transaction_weight(tx.size, tx.cycles));
+    backlog.fees = Capacity::shannons(backlog.fees.as_u64().saturating_add(tx.fee.as_u64()));
+}
+
+fn sub_backlog(backlog: &mut TxsBacklog, tx: &TxEntry) {
+    backlog.tx_count = backlog.tx_count.saturating_sub(1);
+    backlog.tx_size = backlog.tx_size.saturating_sub(tx.size);
+    backlog.cycles = backlog.cycles.saturating_sub(tx.cycles);
+    backlog.weight = backlog
+        .weight
+        .saturating_sub(get_transaction_weight(tx.size, tx.cycles));
+    backlog.fees = Capacity::shannons(backlog.fees.as_u64().saturating_sub(tx.fee.as_u64()));
+}
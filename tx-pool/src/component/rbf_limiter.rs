@@ -0,0 +1,93 @@
+use ckb_types::{core::Capacity, packed::OutPoint};
+use lru::LruCache;
+use std::collections::VecDeque;
+
+const RBF_LIMITER_CACHE_SIZE: usize = 100_000;
+
+/// Track the recent RBF replacements of outpoints within a sliding window.
+///
+/// It's used to protect the node from fee-delta griefing, in which an attacker repeatedly
+/// replaces a tx with tiny fee increments to consume the CPU and bandwidth of the node.
+/// The fees of the replaced txs are summed in the window as well, since the churn of many
+/// outpoints amplifies the relay traffic even if each of them is replaced a few times.
+pub(crate) struct RbfLimiter {
+    // window in milliseconds
+    window: u64,
+    // outpoint => timestamps of the recent replacements, in ascending order
+    replacements: LruCache<OutPoint, VecDeque<u64>>,
+    // (timestamp, fees of the replaced txs) of the recent replacements, in ascending order
+    replaced_fees: VecDeque<(u64, u64)>,
+    // the sum of `replaced_fees`
+    replaced_fees_sum: u64,
+}
+
+impl RbfLimiter {
+    pub(crate) fn new(window_secs: u64) -> Self {
+        RbfLimiter {
+            window: window_secs.saturating_mul(1000),
+            replacements: LruCache::new(RBF_LIMITER_CACHE_SIZE),
+            replaced_fees: VecDeque::new(),
+            replaced_fees_sum: 0,
+        }
+    }
+
+    /// Returns the max replacements count within the window among the outpoints.
+    pub(crate) fn replacements_count<'a>(
+        &self,
+        outpoints: impl Iterator<Item = &'a OutPoint>,
+        now_ms: u64,
+    ) -> usize {
+        let since = now_ms.saturating_sub(self.window);
+        outpoints
+            .filter_map(|pt| self.replacements.peek(pt))
+            .map(|timestamps| timestamps.iter().filter(|t| **t >= since).count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the total fees of the txs replaced within the window.
+    pub(crate) fn replaced_fees(&self, now_ms: u64) -> Capacity {
+        let since = now_ms.saturating_sub(self.window);
+        let expired = self
+            .replaced_fees
+            .iter()
+            .take_while(|(t, _)| *t < since)
+            .fold(0u64, |sum, (_, fees)| sum.saturating_add(*fees));
+        Capacity::shannons(self.replaced_fees_sum.saturating_sub(expired))
+    }
+
+    /// Record a replacement of the outpoints, which replaced the txs paying `replaced_fees`.
+    pub(crate) fn record(
+        &mut self,
+        outpoints: impl Iterator<Item = OutPoint>,
+        replaced_fees: Capacity,
+        now_ms: u64,
+    ) {
+        let since = now_ms.saturating_sub(self.window);
+        while let Some(&(_, fees)) = self.replaced_fees.front().filter(|(t, _)| *t < since) {
+            self.replaced_fees_sum = self.replaced_fees_sum.saturating_sub(fees);
+            self.replaced_fees.pop_front();
+        }
+        self.replaced_fees
+            .push_back((now_ms, replaced_fees.as_u64()));
+        self.replaced_fees_sum = self
+            .replaced_fees_sum
+            .saturating_add(replaced_fees.as_u64());
+        for pt in outpoints {
+            if let Some(timestamps) = self.replacements.get_mut(&pt) {
+                while timestamps.front().map_or(false, |t| *t < since) {
+                    timestamps.pop_front();
+                }
+                timestamps.push_back(now_ms);
+            } else {
+                self.replacements.put(pt, VecDeque::from([now_ms]));
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.replacements.clear();
+        self.replaced_fees.clear();
+        self.replaced_fees_sum = 0;
+    }
+}
@@ -0,0 +1,74 @@
+use arc_swap::ArcSwapOption;
+use ckb_types::core::tx_pool::{TxPoolEntryInfo, TxPoolIds};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Immutable copies of the pool indices, which are served to the read RPCs without taking the
+/// tx-pool lock. The copies are shared by `Arc`, a read never clones the entries.
+///
+/// Every mutation of the pool bumps the shared version counter under the write lock. A copy is
+/// only served when it was built at the latest version, otherwise the reader rebuilds it under
+/// the read lock, so a read never sees a state older than the writes which completed before it,
+/// and the copy is rebuilt at most once per batch of mutations.
+pub(crate) struct PoolReadView {
+    version: Arc<AtomicU64>,
+    ids: ArcSwapOption<Versioned<TxPoolIds>>,
+    entry_info: ArcSwapOption<Versioned<TxPoolEntryInfo>>,
+}
+
+struct Versioned<T> {
+    version: u64,
+    value: Arc<T>,
+}
+
+impl PoolReadView {
+    pub(crate) fn new() -> Self {
+        PoolReadView {
+            version: Arc::new(AtomicU64::new(0)),
+            ids: ArcSwapOption::empty(),
+            entry_info: ArcSwapOption::empty(),
+        }
+    }
+
+    /// The version counter, which should be shared with the pool it tracks
+    pub(crate) fn version_counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.version)
+    }
+
+    pub(crate) fn current_version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Returns the ids if the copy is up to date
+    pub(crate) fn ids(&self) -> Option<Arc<TxPoolIds>> {
+        load_fresh(&self.ids, self.current_version())
+    }
+
+    /// Returns the entry info if the copy is up to date
+    pub(crate) fn entry_info(&self) -> Option<Arc<TxPoolEntryInfo>> {
+        load_fresh(&self.entry_info, self.current_version())
+    }
+
+    /// Publishes the ids built at `version`
+    pub(crate) fn publish_ids(&self, version: u64, ids: Arc<TxPoolIds>) {
+        publish(&self.ids, version, ids);
+    }
+
+    /// Publishes the entry info built at `version`
+    pub(crate) fn publish_entry_info(&self, version: u64, entry_info: Arc<TxPoolEntryInfo>) {
+        publish(&self.entry_info, version, entry_info);
+    }
+}
+
+fn load_fresh<T>(slot: &ArcSwapOption<Versioned<T>>, version: u64) -> Option<Arc<T>> {
+    slot.load()
+        .as_ref()
+        .filter(|copy| copy.version == version)
+        .map(|copy| Arc::clone(&copy.value))
+}
+
+fn publish<T>(slot: &ArcSwapOption<Versioned<T>>, version: u64, value: Arc<T>) {
+    // a concurrent reader may replace it with an older copy, which is never served since the
+    // versions don't match, the next reader rebuilds it
+    slot.store(Some(Arc::new(Versioned { version, value })));
+}
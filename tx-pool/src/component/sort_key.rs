@@ -1,15 +1,20 @@
-use ckb_types::core::{
-    tx_pool::AncestorsScoreSortKey as CoreAncestorsScoreSortKey, Capacity, FeeRate,
+use ckb_types::{
+    core::{tx_pool::AncestorsScoreSortKey as CoreAncestorsScoreSortKey, Capacity, FeeRate},
+    packed::Byte32,
 };
 use std::cmp::Ordering;
 
 /// A struct to use as a sorted key
+///
+/// The keys with the same fee rate and ancestors weight are ordered by the tx hash, the smaller
+/// hash ranks higher, so the nodes with identical pools package the same txs in the same order.
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub struct AncestorsScoreSortKey {
     pub fee: Capacity,
     pub weight: u64,
     pub ancestors_fee: Capacity,
     pub ancestors_weight: u64,
+    pub tx_hash: Byte32,
 }
 
 impl AncestorsScoreSortKey {
@@ -41,8 +46,11 @@ impl Ord for AncestorsScoreSortKey {
         let self_weight = u128::from(fee.as_u64()) * u128::from(other_weight);
         let other_weight = u128::from(other_fee.as_u64()) * u128::from(weight);
         if self_weight == other_weight {
-            // if fee rate weight is same, then compare with ancestor weight
-            self.ancestors_weight.cmp(&other.ancestors_weight)
+            // if fee rate weight is same, then compare with ancestor weight,
+            // and break the tie by the tx hash reversely, the smaller hash ranks higher
+            self.ancestors_weight
+                .cmp(&other.ancestors_weight)
+                .then_with(|| other.tx_hash.cmp(&self.tx_hash))
         } else {
             self_weight.cmp(&other_weight)
         }
@@ -75,11 +83,15 @@ impl ToString for AncestorsScoreSortKey {
 /// First compare fee_rate, select the smallest fee_rate,
 /// and then select the latest timestamp, for eviction,
 /// the latest timestamp which also means that the fewer descendants may exist.
+///
+/// The keys with the same fee rate, descendants count and timestamp are ordered by the tx hash,
+/// the larger hash is evicted first, consistent with `AncestorsScoreSortKey`.
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub struct EvictKey {
     pub fee_rate: FeeRate,
     pub timestamp: u64,
     pub descendants_count: usize,
+    pub tx_hash: Byte32,
 }
 
 impl PartialOrd for EvictKey {
@@ -92,7 +104,9 @@ impl Ord for EvictKey {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.fee_rate == other.fee_rate {
             if self.descendants_count == other.descendants_count {
-                self.timestamp.cmp(&other.timestamp)
+                self.timestamp
+                    .cmp(&other.timestamp)
+                    .then_with(|| other.tx_hash.cmp(&self.tx_hash))
             } else {
                 self.descendants_count.cmp(&other.descendants_count)
             }
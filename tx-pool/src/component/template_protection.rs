@@ -0,0 +1,62 @@
+use ckb_types::packed::ProposalShortId;
+use std::collections::HashMap;
+
+/// Track the transactions referenced by the recent block templates.
+///
+/// A miner may be working on a template, so its transactions are protected from the eviction
+/// by the pool size limit until the protection expires.
+pub(crate) struct TemplateProtection {
+    // ttl in milliseconds
+    ttl: u64,
+    // short id => expiration timestamp in milliseconds
+    protected: HashMap<ProposalShortId, u64>,
+}
+
+impl TemplateProtection {
+    pub(crate) fn new(ttl_secs: u64) -> Self {
+        TemplateProtection {
+            ttl: ttl_secs.saturating_mul(1000),
+            protected: HashMap::new(),
+        }
+    }
+
+    /// Protect the transactions of a new template, which also forgets the expired protections.
+    pub(crate) fn protect(&mut self, ids: impl Iterator<Item = ProposalShortId>, now_ms: u64) {
+        if self.ttl == 0 {
+            return;
+        }
+        self.protected.retain(|_, expiry| *expiry > now_ms);
+        let expiry = now_ms.saturating_add(self.ttl);
+        for id in ids {
+            self.protected.insert(id, expiry);
+        }
+    }
+
+    /// Whether any of the transactions is not protected, or its protection expires within half
+    /// of the ttl, so `protect` is only called when it changes anything.
+    pub(crate) fn needs_refresh(
+        &self,
+        mut ids: impl Iterator<Item = ProposalShortId>,
+        now_ms: u64,
+    ) -> bool {
+        if self.ttl == 0 {
+            return false;
+        }
+        let refresh_before = now_ms.saturating_add(self.ttl / 2);
+        ids.any(|id| {
+            self.protected
+                .get(&id)
+                .map_or(true, |expiry| *expiry <= refresh_before)
+        })
+    }
+
+    pub(crate) fn is_protected(&self, id: &ProposalShortId, now_ms: u64) -> bool {
+        self.protected
+            .get(id)
+            .map_or(false, |expiry| *expiry > now_ms)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.protected.clear();
+    }
+}
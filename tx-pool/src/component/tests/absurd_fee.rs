@@ -0,0 +1,34 @@
+use ckb_app_config::TxPoolConfig;
+use ckb_types::core::{tx_pool::Reject, Capacity};
+
+use crate::util::check_absurd_fee;
+
+#[test]
+fn test_absurd_fee_unlimited_by_default() {
+    let config = TxPoolConfig::default();
+    assert!(check_absurd_fee(&config, Capacity::shannons(u64::MAX), 1).is_ok());
+}
+
+#[test]
+fn test_check_absurd_fee() {
+    let config = TxPoolConfig {
+        max_absolute_fee: Some(100_000),
+        max_fee_rate: Some(10_000),
+        ..Default::default()
+    };
+
+    // the max fee rate allows 5_000 shannons for a 500 bytes tx
+    assert!(check_absurd_fee(&config, Capacity::shannons(5_000), 500).is_ok());
+    assert!(matches!(
+        check_absurd_fee(&config, Capacity::shannons(5_001), 500),
+        Err(Reject::AbsurdFee(5_001, _))
+    ));
+
+    // the max fee rate allows 200_000 shannons for a 20_000 bytes tx, but the max absolute fee
+    // does not
+    assert!(check_absurd_fee(&config, Capacity::shannons(100_000), 20_000).is_ok());
+    assert!(matches!(
+        check_absurd_fee(&config, Capacity::shannons(100_001), 20_000),
+        Err(Reject::AbsurdFee(100_001, _))
+    ));
+}
@@ -1,9 +1,11 @@
 use crate::component::tests::util::build_tx;
-use crate::component::verify_queue::{Entry, VerifyQueue};
+use crate::component::verify_queue::{Clock, Entry, VerifyQueue};
 use ckb_network::SessionId;
-use ckb_types::core::TransactionBuilder;
+use ckb_types::core::{tx_pool::Reject, FeeRate, TransactionBuilder};
 use ckb_types::prelude::Pack;
 use ckb_types::H256;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tokio::select;
 use tokio::sync::watch;
 use tokio::time::sleep;
@@ -147,3 +149,187 @@ async fn test_verify_different_cycles() {
     assert_eq!(counts, 4);
     assert_eq!(queue.total_tx_size(), 0);
 }
+
+#[tokio::test]
+async fn test_verify_queue_limits() {
+    let remote = |cycles| Some((cycles, SessionId::default()));
+    let tx0 = build_tx(vec![(&H256([0; 32]).pack(), 0)], 1);
+    let tx1 = build_tx(vec![(&H256([1; 32]).pack(), 0)], 1);
+    let tx2 = build_tx(vec![(&H256([2; 32]).pack(), 0)], 1);
+    let tx_size = tx0.data().serialized_size_in_block();
+
+    // room for two txs by size
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES).with_limits(tx_size * 2, None);
+    assert!(queue.add_tx(tx0.clone(), None).unwrap());
+    assert!(queue.add_tx(tx1.clone(), None).unwrap());
+    let err = queue.add_tx(tx2.clone(), None).unwrap_err();
+    assert!(matches!(err, Reject::Full(ref reason) if reason.contains("total_tx_size")));
+    queue.remove_tx(&tx0.proposal_short_id());
+    assert!(queue.add_tx(tx2.clone(), None).unwrap());
+
+    // the declared cycles of the relayed txs are limited, the local txs are not counted
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES).with_limits(usize::MAX, Some(2000));
+    assert!(queue.add_tx(tx0.clone(), remote(1500)).unwrap());
+    assert!(queue.add_tx(tx1.clone(), None).unwrap());
+    assert_eq!(queue.total_cycles(), 1500);
+    let err = queue.add_tx(tx2.clone(), remote(1000)).unwrap_err();
+    assert!(matches!(err, Reject::Full(ref reason) if reason.contains("total_cycles")));
+    assert!(queue.add_tx(tx2.clone(), remote(500)).unwrap());
+
+    queue.pop_front(false);
+    assert_eq!(queue.total_cycles(), 500);
+    queue.clear();
+    assert_eq!(queue.total_cycles(), 0);
+}
+
+// Pops all the txs and returns whether each one is local
+fn pop_sources(queue: &mut VerifyQueue) -> Vec<bool> {
+    std::iter::from_fn(|| queue.pop_front(false))
+        .map(|entry| entry.remote.is_none())
+        .collect()
+}
+
+// A clock of the queue advanced by the test
+fn mock_clock() -> (Arc<AtomicU64>, Clock) {
+    let now = Arc::new(AtomicU64::new(0));
+    let clock: Clock = {
+        let now = Arc::clone(&now);
+        Arc::new(move || now.load(Ordering::SeqCst))
+    };
+    (now, clock)
+}
+
+#[tokio::test]
+async fn test_verify_queue_fairness() {
+    let remote = Some((1000, SessionId::default()));
+    let txs: Vec<_> = (0..12u8)
+        .map(|i| build_tx(vec![(&H256([i; 32]).pack(), 0)], 1))
+        .collect();
+
+    // the relayed txs flooding the queue before the local ones don't starve them
+    let (now, clock) = mock_clock();
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES)
+        .with_fairness(1, 1, u64::MAX)
+        .with_clock(clock);
+    for tx in &txs[..6] {
+        assert!(queue.add_tx(tx.clone(), remote).unwrap());
+    }
+    now.fetch_add(10, Ordering::SeqCst);
+    for tx in &txs[6..9] {
+        assert!(queue.add_tx(tx.clone(), None).unwrap());
+    }
+    assert_eq!(
+        pop_sources(&mut queue),
+        vec![false, true, false, true, false, true, false, false, false]
+    );
+
+    // the weights set the share of each source
+    let (now, clock) = mock_clock();
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES)
+        .with_fairness(3, 1, u64::MAX)
+        .with_clock(clock);
+    for tx in &txs[..6] {
+        assert!(queue.add_tx(tx.clone(), remote).unwrap());
+    }
+    now.fetch_add(10, Ordering::SeqCst);
+    for tx in &txs[6..12] {
+        assert!(queue.add_tx(tx.clone(), None).unwrap());
+    }
+    assert_eq!(
+        pop_sources(&mut queue)[..9],
+        [false, true, true, true, false, true, true, true, false]
+    );
+}
+
+#[tokio::test]
+async fn test_verify_queue_starvation_protection() {
+    let remote = Some((1000, SessionId::default()));
+    let txs: Vec<_> = (0..7u8)
+        .map(|i| build_tx(vec![(&H256([i; 32]).pack(), 0)], 1))
+        .collect();
+    let fill = |queue: &mut VerifyQueue| {
+        assert!(queue.add_tx(txs[0].clone(), remote).unwrap());
+        assert!(queue.add_tx(txs[1].clone(), remote).unwrap());
+    };
+
+    // the relayed tx waits for all the local txs by the weights
+    let (now, clock) = mock_clock();
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES)
+        .with_fairness(100, 1, u64::MAX)
+        .with_clock(clock);
+    fill(&mut queue);
+    now.fetch_add(10, Ordering::SeqCst);
+    for tx in &txs[2..] {
+        assert!(queue.add_tx(tx.clone(), None).unwrap());
+    }
+    assert_eq!(
+        pop_sources(&mut queue),
+        vec![false, true, true, true, true, true, false]
+    );
+
+    // unless it has waited too long
+    let (now, clock) = mock_clock();
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES)
+        .with_fairness(100, 1, 5)
+        .with_clock(clock);
+    fill(&mut queue);
+    now.fetch_add(10, Ordering::SeqCst);
+    for tx in &txs[2..] {
+        assert!(queue.add_tx(tx.clone(), None).unwrap());
+    }
+    assert_eq!(
+        pop_sources(&mut queue),
+        vec![false, false, true, true, true, true, true]
+    );
+}
+
+#[tokio::test]
+async fn test_verify_queue_priority() {
+    let remote = Some((1000, SessionId::default()));
+    let txs: Vec<_> = (0..5u8)
+        .map(|i| build_tx(vec![(&H256([i; 32]).pack(), 0)], 1))
+        .collect();
+    let fee_rates = [Some(1_000), Some(3_000), None, Some(2_000)];
+    let fill = |queue: &mut VerifyQueue| {
+        for (tx, fee_rate) in txs.iter().zip(fee_rates) {
+            let fee_rate = fee_rate.map(FeeRate::from_u64);
+            assert!(queue
+                .add_tx_with_fee_rate(tx.clone(), remote, fee_rate)
+                .unwrap());
+        }
+        assert!(queue
+            .add_tx_with_fee_rate(txs[4].clone(), None, Some(FeeRate::zero()))
+            .unwrap());
+    };
+    let pop_hashes = |queue: &mut VerifyQueue| {
+        std::iter::from_fn(|| queue.pop_front(false))
+            .map(|entry| entry.tx.hash())
+            .collect::<Vec<_>>()
+    };
+
+    // the txs of each source are verified in the order they are added by default
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES).with_fairness(1, 1, u64::MAX);
+    fill(&mut queue);
+    assert_eq!(
+        pop_hashes(&mut queue),
+        [0, 4, 1, 2, 3]
+            .iter()
+            .map(|i| txs[*i].hash())
+            .collect::<Vec<_>>()
+    );
+
+    // the local tx goes first, then the higher fee rate ones, the unknown fee rate one at last
+    let mut queue = VerifyQueue::new(MAX_TX_VERIFY_CYCLES)
+        .with_fairness(1, 1, u64::MAX)
+        .with_priority(true);
+    fill(&mut queue);
+    assert_eq!(queue.position(&txs[4].proposal_short_id()), Some(0));
+    assert_eq!(queue.position(&txs[2].proposal_short_id()), Some(4));
+    assert_eq!(
+        pop_hashes(&mut queue),
+        [4, 1, 3, 0, 2]
+            .iter()
+            .map(|i| txs[*i].hash())
+            .collect::<Vec<_>>()
+    );
+}
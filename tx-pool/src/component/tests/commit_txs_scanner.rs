@@ -0,0 +1,158 @@
+use crate::component::commit_txs_scanner::CommitTxsScanner;
+use crate::component::tests::util::{build_tx, DEFAULT_MAX_ANCESTORS_COUNT};
+use crate::component::{
+    entry::TxEntry,
+    pool_map::{PoolMap, Status},
+};
+use ckb_types::{
+    core::{tx_pool::TxsBacklog, Capacity, TransactionView},
+    packed::Byte32,
+};
+
+const SIZE: usize = 200;
+
+fn add_proposed(pool: &mut PoolMap, tx: &TransactionView, fee: u64) {
+    pool.add_proposed(TxEntry::dummy_resolve(
+        tx.clone(),
+        0,
+        Capacity::shannons(fee),
+        SIZE,
+    ))
+    .unwrap();
+}
+
+#[test]
+fn test_children_pay_for_parent_together() {
+    let parent = build_tx(vec![(&Byte32::zero(), 0)], 2);
+    let child1 = build_tx(vec![(&parent.hash(), 0)], 1);
+    let child2 = build_tx(vec![(&parent.hash(), 1)], 1);
+    let other = build_tx(vec![(&Byte32::zero(), 1)], 1);
+
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    add_proposed(&mut pool, &parent, 0);
+    add_proposed(&mut pool, &child1, 500);
+    add_proposed(&mut pool, &child2, 500);
+    // beats the ancestors package of either child, but not the package of all three
+    add_proposed(&mut pool, &other, 320);
+
+    let (entries, size, _) = CommitTxsScanner::new(&pool).txs_to_commit(SIZE * 3, u64::MAX);
+    let hashes: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.transaction().hash())
+        .collect();
+    assert_eq!(size, SIZE * 3);
+    assert_eq!(hashes.len(), 3);
+    assert_eq!(hashes[0], parent.hash());
+    assert!(hashes.contains(&child1.hash()));
+    assert!(hashes.contains(&child2.hash()));
+
+    // the package doesn't fit, the txs are committed by the ancestors packages
+    let (entries, _, _) = CommitTxsScanner::new(&pool).txs_to_commit(SIZE * 2, u64::MAX);
+    let hashes: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.transaction().hash())
+        .collect();
+    assert_eq!(hashes, vec![other.hash(), parent.hash()]);
+}
+
+#[test]
+fn test_descendants_packages_kept_by_pool() {
+    let parent = build_tx(vec![(&Byte32::zero(), 0)], 2);
+    let child1 = build_tx(vec![(&parent.hash(), 0)], 1);
+    let child2 = build_tx(vec![(&parent.hash(), 1)], 1);
+
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    add_proposed(&mut pool, &parent, 0);
+    assert_eq!(pool.sorted_proposed_packages_iter().count(), 0);
+
+    add_proposed(&mut pool, &child1, 500);
+    add_proposed(&mut pool, &child2, 300);
+    let packages: Vec<_> = pool
+        .sorted_proposed_packages_iter()
+        .map(|(score, entry)| (score.clone(), entry.transaction().hash()))
+        .collect();
+    assert_eq!(packages.len(), 1);
+    let (score, hash) = &packages[0];
+    assert_eq!(hash, &parent.hash());
+    assert_eq!(score.fee, Capacity::shannons(800));
+
+    // the package shrinks with the removed descendant
+    pool.remove_entry(&child1.proposal_short_id());
+    let (score, _) = pool.sorted_proposed_packages_iter().next().unwrap();
+    assert_eq!(score.fee, Capacity::shannons(300));
+
+    pool.remove_entry(&child2.proposal_short_id());
+    assert_eq!(pool.sorted_proposed_packages_iter().count(), 0);
+}
+
+#[test]
+fn test_child_pays_for_parent() {
+    let parent = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let child = build_tx(vec![(&parent.hash(), 0)], 1);
+    let other = build_tx(vec![(&Byte32::zero(), 1)], 1);
+
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    add_proposed(&mut pool, &parent, 0);
+    add_proposed(&mut pool, &child, 1000);
+    add_proposed(&mut pool, &other, 300);
+
+    let (entries, _, _) = CommitTxsScanner::new(&pool).txs_to_commit(SIZE * 2, u64::MAX);
+    let hashes: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.transaction().hash())
+        .collect();
+    assert_eq!(hashes, vec![parent.hash(), child.hash()]);
+}
+
+#[test]
+fn test_backlog_left_out_of_block() {
+    let parent = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let child = build_tx(vec![(&parent.hash(), 0)], 1);
+    let other = build_tx(vec![(&Byte32::zero(), 1)], 1);
+    let waiting = build_tx(vec![(&Byte32::zero(), 2)], 1);
+    let waiting_id = waiting.proposal_short_id();
+
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    add_proposed(&mut pool, &parent, 0);
+    add_proposed(&mut pool, &child, 1000);
+    add_proposed(&mut pool, &other, 300);
+    pool.add_entry(
+        TxEntry::dummy_resolve(waiting, 0, Capacity::shannons(100), SIZE),
+        Status::Pending,
+    )
+    .unwrap();
+
+    let (entries, _, _) = CommitTxsScanner::new(&pool).txs_to_commit(SIZE * 2, u64::MAX);
+    let (proposed, pending) = pool.backlog(&entries);
+    assert_eq!(
+        proposed,
+        TxsBacklog {
+            tx_count: 1,
+            tx_size: SIZE,
+            cycles: 0,
+            weight: SIZE as u64,
+            fees: Capacity::shannons(300),
+        }
+    );
+    assert_eq!(pending.tx_count, 1);
+    assert_eq!(pending.fees, Capacity::shannons(100));
+
+    // nothing fits, all the proposed txs are left out
+    let (proposed, _) = pool.backlog(&[]);
+    assert_eq!(proposed.tx_count, 3);
+    assert_eq!(proposed.tx_size, SIZE * 3);
+    assert_eq!(proposed.fees, Capacity::shannons(1300));
+
+    // the totals follow the status changes and the removals
+    pool.set_entry(&waiting_id, Status::Proposed);
+    pool.remove_entry(&other.proposal_short_id());
+    let (proposed, pending) = pool.backlog(&[]);
+    assert_eq!(proposed.tx_count, 3);
+    assert_eq!(proposed.fees, Capacity::shannons(1100));
+    assert_eq!(pending, TxsBacklog::default());
+
+    // the waiting tx is left out of the same packaged txs
+    let (proposed, _) = pool.backlog(&entries);
+    assert_eq!(proposed.tx_count, 1);
+    assert_eq!(proposed.fees, Capacity::shannons(100));
+}
@@ -0,0 +1,52 @@
+use ckb_types::{h256, prelude::*};
+
+use crate::component::conflicts_pool::ConflictsPool;
+use crate::component::tests::util::build_tx;
+
+#[test]
+fn test_conflicts_pool_count_limit() {
+    let mut pool = ConflictsPool::new(2, usize::MAX);
+    let tx1 = build_tx(vec![(&h256!("0x1").pack(), 0)], 1);
+    let tx2 = build_tx(vec![(&h256!("0x2").pack(), 0)], 1);
+    let tx3 = build_tx(vec![(&h256!("0x3").pack(), 0)], 1);
+
+    assert_eq!(pool.put(tx1.clone()), 0);
+    assert_eq!(pool.put(tx2.clone()), 0);
+    // tx1 becomes the most recently used one
+    assert!(pool.peek(&tx1.proposal_short_id()).is_some());
+    pool.put(tx1.clone());
+
+    // the least recently used tx2 is evicted
+    assert_eq!(pool.put(tx3.clone()), 1);
+    assert_eq!(pool.len(), 2);
+    assert!(pool.peek(&tx1.proposal_short_id()).is_some());
+    assert!(pool.peek(&tx2.proposal_short_id()).is_none());
+    assert!(pool.peek(&tx3.proposal_short_id()).is_some());
+}
+
+#[test]
+fn test_conflicts_pool_size_limit() {
+    let tx1 = build_tx(vec![(&h256!("0x1").pack(), 0)], 1);
+    let tx2 = build_tx(vec![(&h256!("0x2").pack(), 0)], 1);
+    let tx3 = build_tx(vec![(&h256!("0x3").pack(), 0)], 2);
+    let tx_size = tx1.data().serialized_size_in_block();
+
+    let mut pool = ConflictsPool::new(100, tx_size * 2);
+    pool.put(tx1.clone());
+    pool.put(tx2.clone());
+    assert_eq!(pool.size(), tx_size * 2);
+
+    // the txs larger than the limit are not kept at all
+    let big = build_tx(vec![(&h256!("0x4").pack(), 0)], 100);
+    assert_eq!(pool.put(big.clone()), 0);
+    assert!(pool.peek(&big.proposal_short_id()).is_none());
+    assert_eq!(pool.len(), 2);
+
+    // both txs are evicted to make room for the larger tx3
+    assert_eq!(pool.put(tx3.clone()), 2);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.size(), tx3.data().serialized_size_in_block());
+
+    assert!(pool.pop(&tx3.proposal_short_id()).is_some());
+    assert_eq!(pool.size(), 0);
+}
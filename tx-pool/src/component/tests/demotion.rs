@@ -0,0 +1,105 @@
+use ckb_app_config::{StoreConfig, TxPoolConfig};
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_store::ChainDB;
+use ckb_types::{core::TransactionView, packed::Byte32, prelude::*};
+use ckb_util::LinkedHashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::callback::Callbacks;
+use crate::component::entry::TxEntry;
+use crate::component::pool_map::Status;
+use crate::component::tests::util::{build_snapshot, build_tx, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE};
+use crate::pool::TxPool;
+use crate::process::{_submit_entry, _update_tx_pool_for_reorg, TxStatus};
+
+// Counts the txs entering pending and proposed
+fn build_callbacks() -> (Callbacks, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let pending = Arc::new(AtomicUsize::new(0));
+    let proposed = Arc::new(AtomicUsize::new(0));
+    let mut callbacks = Callbacks::new();
+    let counter = Arc::clone(&pending);
+    callbacks.register_pending(Box::new(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+    let counter = Arc::clone(&proposed);
+    callbacks.register_proposed(Box::new(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+    (callbacks, pending, proposed)
+}
+
+fn entry(tx: &TransactionView) -> TxEntry {
+    TxEntry::dummy_resolve(tx.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE)
+}
+
+#[test]
+fn test_demote_and_promote_on_reorg() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let db = ChainDB::new(RocksDB::open_in(&tmp_dir, COLUMNS), StoreConfig::default());
+    let config = TxPoolConfig {
+        max_ancestors_count: 1,
+        ..Default::default()
+    };
+    let mut tx_pool = TxPool::new(config, build_snapshot(&db, HashSet::new()));
+    let (callbacks, pending, proposed) = build_callbacks();
+
+    let tx1 = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let tx2 = build_tx(vec![(&tx1.hash(), 0)], 1);
+    let tx2_id = tx2.proposal_short_id();
+
+    assert!(_submit_entry(&mut tx_pool, TxStatus::Proposed, entry(&tx1), &callbacks).is_ok());
+    assert_eq!(proposed.load(Ordering::SeqCst), 1);
+
+    // the proposed tx exceeding max_ancestors_count is demoted to pending instead of rejected
+    assert!(_submit_entry(&mut tx_pool, TxStatus::Proposed, entry(&tx2), &callbacks).is_ok());
+    assert_eq!(pending.load(Ordering::SeqCst), 1);
+    assert_eq!(proposed.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        tx_pool.get_pool_entry(&tx2_id).unwrap().status,
+        Status::Pending
+    );
+    assert!(tx_pool.pool_map.is_demoted(&tx2_id));
+
+    // the tx is still proposed by the new tip, but its parent is not committed yet
+    let proposals = HashSet::from([tx2_id.clone()]);
+    _update_tx_pool_for_reorg(
+        &mut tx_pool,
+        &LinkedHashSet::default(),
+        &HashMap::new(),
+        &HashSet::new(),
+        HashSet::new(),
+        build_snapshot(&db, proposals.clone()),
+        &callbacks,
+        true,
+    );
+    assert_eq!(proposed.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        tx_pool.get_pool_entry(&tx2_id).unwrap().status,
+        Status::Pending
+    );
+    assert!(tx_pool.pool_map.is_demoted(&tx2_id));
+
+    // the parent is committed, the demoted tx is promoted to proposed
+    let committed_at = HashMap::from([(tx1.proposal_short_id(), 10)]);
+    let mut attached = LinkedHashSet::default();
+    attached.insert(tx1);
+    _update_tx_pool_for_reorg(
+        &mut tx_pool,
+        &attached,
+        &committed_at,
+        &HashSet::new(),
+        HashSet::new(),
+        build_snapshot(&db, proposals),
+        &callbacks,
+        true,
+    );
+    assert_eq!(proposed.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        tx_pool.get_pool_entry(&tx2_id).unwrap().status,
+        Status::Proposed
+    );
+    assert!(!tx_pool.pool_map.is_demoted(&tx2_id));
+}
@@ -1,5 +1,9 @@
 use crate::component::sort_key::EvictKey;
-use ckb_types::core::{Capacity, FeeRate};
+use ckb_types::{
+    core::{Capacity, FeeRate},
+    packed::Byte32,
+};
+use proptest::prelude::*;
 
 #[test]
 fn test_min_fee_and_weight_evict() {
@@ -9,6 +13,7 @@ fn test_min_fee_and_weight_evict() {
             fee_rate: FeeRate::calculate(Capacity::shannons(fee), weight),
             timestamp,
             descendants_count: 0,
+            tx_hash: Byte32::zero(),
         })
         .collect::<Vec<_>>();
     result.sort();
@@ -26,6 +31,7 @@ fn test_min_timestamp_evict() {
             fee_rate: FeeRate::calculate(Capacity::shannons(fee), weight),
             timestamp,
             descendants_count: 0,
+            tx_hash: Byte32::zero(),
         })
         .collect::<Vec<_>>();
     result.sort();
@@ -43,6 +49,7 @@ fn test_min_weight_evict() {
             fee_rate: FeeRate::calculate(Capacity::shannons(fee), weight),
             timestamp,
             descendants_count: 0,
+            tx_hash: Byte32::zero(),
         })
         .collect::<Vec<_>>();
     result.sort();
@@ -51,3 +58,58 @@ fn test_min_weight_evict() {
         vec![32, 31, 30]
     );
 }
+
+#[test]
+fn test_evict_key_tie_break_by_tx_hash() {
+    let mut result = [[3u8; 32], [1u8; 32], [2u8; 32]]
+        .into_iter()
+        .map(|hash| EvictKey {
+            fee_rate: FeeRate::calculate(Capacity::shannons(500), 10),
+            timestamp: 30,
+            descendants_count: 0,
+            tx_hash: Byte32::new(hash),
+        })
+        .collect::<Vec<_>>();
+    result.sort();
+    // the larger hash is evicted first
+    assert_eq!(
+        result
+            .iter()
+            .map(|key| key.tx_hash.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Byte32::new([3u8; 32]),
+            Byte32::new([2u8; 32]),
+            Byte32::new([1u8; 32])
+        ]
+    );
+}
+
+fn evict_key() -> impl Strategy<Value = EvictKey> {
+    // the small ranges make the ties common
+    (1..4u64, 1..4u64, 0..3u64, 0..3usize, any::<[u8; 32]>()).prop_map(
+        |(fee, weight, timestamp, descendants_count, hash)| EvictKey {
+            fee_rate: FeeRate::calculate(Capacity::shannons(fee), weight),
+            timestamp,
+            descendants_count,
+            tx_hash: Byte32::new(hash),
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn test_evict_key_total_order(a in evict_key(), b in evict_key()) {
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        prop_assert_eq!(a.cmp(&b) == std::cmp::Ordering::Equal, a == b);
+    }
+
+    #[test]
+    fn test_evict_key_order_independent_of_insertion(keys in prop::collection::vec(evict_key(), 0..32)) {
+        let mut sorted = keys.clone();
+        sorted.sort();
+        let mut reversed = keys.into_iter().rev().collect::<Vec<_>>();
+        reversed.sort();
+        prop_assert_eq!(sorted, reversed);
+    }
+}
@@ -0,0 +1,68 @@
+use ckb_app_config::{StoreConfig, TxPoolConfig};
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_store::ChainDB;
+use ckb_types::{
+    core::{Capacity, FeeRate, TransactionView},
+    packed::Byte32,
+};
+use std::collections::HashSet;
+
+use crate::component::entry::TxEntry;
+use crate::component::tests::util::{build_snapshot, build_tx, MOCK_CYCLES};
+use crate::pool::TxPool;
+
+fn add_pending(tx_pool: &mut TxPool, tx: &TransactionView, fee: u64) {
+    let entry = TxEntry::dummy_resolve(tx.clone(), MOCK_CYCLES, Capacity::shannons(fee), 100);
+    tx_pool.add_pending(entry).unwrap();
+}
+
+#[test]
+fn test_fee_breakdown() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let db = ChainDB::new(RocksDB::open_in(&tmp_dir, COLUMNS), StoreConfig::default());
+    let config = TxPoolConfig {
+        min_rbf_rate: FeeRate::from_u64(1_500),
+        ..Default::default()
+    };
+    let mut tx_pool = TxPool::new(config, build_snapshot(&db, HashSet::new()));
+
+    // the outputs of the parent are 1 and 2 CKBytes
+    let parent = build_tx(vec![(&Byte32::zero(), 0)], 2);
+    add_pending(&mut tx_pool, &parent, 0);
+    let tx = build_tx(vec![(&parent.hash(), 0), (&parent.hash(), 1)], 1);
+
+    let breakdown = tx_pool.fee_breakdown(&tx).unwrap();
+    let capacities: Vec<_> = breakdown.inputs.iter().map(|cell| cell.capacity).collect();
+    assert_eq!(
+        capacities,
+        vec![Capacity::bytes(1).unwrap(), Capacity::bytes(2).unwrap()]
+    );
+    let output = tx.outputs().get(0).unwrap();
+    assert_eq!(
+        breakdown.outputs[0].occupied_capacity,
+        output.occupied_capacity(Capacity::zero()).unwrap()
+    );
+    assert_eq!(breakdown.inputs_capacity, Capacity::bytes(3).unwrap());
+    assert_eq!(breakdown.outputs_capacity, Capacity::bytes(1).unwrap());
+    assert_eq!(breakdown.fee, Capacity::bytes(2).unwrap());
+    assert_eq!(
+        breakdown.min_fee,
+        FeeRate::from_u64(1_000).fee(breakdown.size as u64)
+    );
+    assert_eq!(breakdown.min_replace_fee, None);
+
+    // a pool tx spends the same input, the tx has to pay for replacing it
+    let conflict = build_tx(vec![(&parent.hash(), 0)], 1);
+    add_pending(&mut tx_pool, &conflict, 500);
+    let breakdown = tx_pool.fee_breakdown(&tx).unwrap();
+    let extra_fee = FeeRate::from_u64(1_500).fee(breakdown.size as u64);
+    assert_eq!(
+        breakdown.min_replace_fee,
+        Some(Capacity::shannons(500).safe_add(extra_fee).unwrap())
+    );
+
+    // the inputs are unknown
+    let orphan = build_tx(vec![(&conflict.hash(), 1)], 1);
+    assert!(tx_pool.fee_breakdown(&orphan).is_err());
+}
@@ -0,0 +1,100 @@
+use ckb_types::core::FeeRate;
+use ckb_types::packed::ProposalShortId;
+
+use crate::component::fee_estimator::FeeEstimator;
+
+fn short_id(n: u32) -> ProposalShortId {
+    let mut id = [0u8; 10];
+    id[..4].copy_from_slice(&n.to_le_bytes());
+    ProposalShortId::new(id)
+}
+
+// Tracks `count` txs paying the fee rate at the tip 0, and commits them in the block `committed_at`
+fn commit_txs(
+    estimator: &mut FeeEstimator,
+    first_id: u32,
+    count: u32,
+    fee_rate: u64,
+    committed_at: u64,
+) {
+    for n in first_id..first_id + count {
+        estimator.track(short_id(n), FeeRate::from_u64(fee_rate), 0);
+    }
+    for n in first_id..first_id + count {
+        estimator.commit(&short_id(n), committed_at);
+    }
+}
+
+#[test]
+fn test_estimate_without_enough_txs() {
+    let mut estimator = FeeEstimator::new(0);
+    assert_eq!(estimator.estimate(3), None);
+
+    commit_txs(&mut estimator, 0, 5, 2_000, 2);
+    assert_eq!(estimator.estimate(3), None);
+}
+
+#[test]
+fn test_estimate_by_committed_blocks() {
+    let mut estimator = FeeEstimator::new(0);
+    // the high fee rate txs are committed in 2 blocks, and the low ones in 10 blocks
+    commit_txs(&mut estimator, 0, 20, 5_000, 2);
+    commit_txs(&mut estimator, 100, 20, 1_000, 10);
+
+    let fast = estimator.estimate(3).unwrap();
+    assert!(fast <= FeeRate::from_u64(5_000) && fast > FeeRate::from_u64(4_000));
+    assert_eq!(estimator.estimate(10), Some(FeeRate::from_u64(1_000)));
+    assert_eq!(estimator.estimate(1), None);
+}
+
+#[test]
+fn test_pending_txs_count_as_failures() {
+    let mut estimator = FeeEstimator::new(0);
+    commit_txs(&mut estimator, 0, 20, 1_000, 2);
+    assert_eq!(estimator.estimate(3), Some(FeeRate::from_u64(1_000)));
+
+    // the txs paying the same fee rate are stuck in the pool
+    for n in 100..110 {
+        estimator.track(short_id(n), FeeRate::from_u64(1_000), 0);
+    }
+    estimator.process_blocks(2, |_| true);
+    assert_eq!(estimator.estimate(3), Some(FeeRate::from_u64(1_000)));
+    estimator.process_blocks(5, |_| true);
+    assert_eq!(estimator.estimate(3), None);
+
+    // they are no longer counted once removed from the pool
+    estimator.process_blocks(6, |_| false);
+    assert_eq!(estimator.estimate(3), Some(FeeRate::from_u64(1_000)));
+}
+
+#[test]
+fn test_untracked_txs_are_not_failures() {
+    let mut estimator = FeeEstimator::new(0);
+    commit_txs(&mut estimator, 0, 20, 1_000, 2);
+
+    // the txs of a rejected package are removed from the pool right after entering it
+    for n in 100..110 {
+        estimator.track(short_id(n), FeeRate::from_u64(1_000), 0);
+        estimator.untrack(&short_id(n));
+    }
+    estimator.process_blocks(5, |_| true);
+    assert_eq!(estimator.estimate(3), Some(FeeRate::from_u64(1_000)));
+}
+
+#[test]
+fn test_only_tracked_txs_are_committed() {
+    let mut estimator = FeeEstimator::new(10);
+    for n in 0..20 {
+        estimator.commit(&short_id(n), 12);
+    }
+    assert_eq!(estimator.estimate(3), None);
+
+    // the tx re-entering the pool keeps its first entry
+    for n in 0..20 {
+        estimator.track(short_id(n), FeeRate::from_u64(1_000), 10);
+        estimator.track(short_id(n), FeeRate::from_u64(1_000), 11);
+        estimator.commit(&short_id(n), 14);
+    }
+    assert_eq!(estimator.estimate(3), None);
+    assert_eq!(estimator.estimate(4), Some(FeeRate::from_u64(1_000)));
+}
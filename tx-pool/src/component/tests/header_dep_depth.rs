@@ -0,0 +1,55 @@
+use ckb_app_config::{StoreConfig, TxPoolConfig};
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_store::ChainDB;
+use ckb_types::{
+    core::{tx_pool::Reject, BlockBuilder, BlockView},
+    h256,
+    packed::Byte32,
+    prelude::*,
+};
+use std::collections::HashSet;
+
+use crate::component::tests::util::{build_snapshot, build_tx_with_header_dep};
+use crate::util::check_header_deps_depth;
+
+fn attach_block(db: &ChainDB, number: u64) -> BlockView {
+    let block = BlockBuilder::default().number(number.pack()).build();
+    let txn = db.begin_transaction();
+    txn.attach_block(&block).unwrap();
+    txn.commit().unwrap();
+    block
+}
+
+#[test]
+fn test_check_header_deps_depth() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let db = ChainDB::new(RocksDB::open_in(&tmp_dir, COLUMNS), StoreConfig::default());
+    let deep = attach_block(&db, 5);
+    let recent = attach_block(&db, 8);
+    // the tip is 10
+    let snapshot = build_snapshot(&db, HashSet::new());
+    let config = TxPoolConfig {
+        min_header_dep_depth: Some(3),
+        ..Default::default()
+    };
+    let check = |config: &TxPoolConfig, header_deps: Vec<Byte32>| {
+        let tx = build_tx_with_header_dep(vec![(&Byte32::zero(), 0)], header_deps, 1);
+        check_header_deps_depth(config, &snapshot, &tx)
+    };
+
+    assert!(check(&config, vec![deep.hash()]).is_ok());
+    assert!(matches!(
+        check(&config, vec![deep.hash(), recent.hash()]),
+        Err(Reject::HeaderDepTooRecent(hash, 2, 3)) if hash == recent.hash()
+    ));
+    // the header deps not in the main chain are left to the resolving
+    assert!(check(&config, vec![h256!("0x1").pack()]).is_ok());
+
+    // disabled
+    let config = TxPoolConfig {
+        min_header_dep_depth: None,
+        ..Default::default()
+    };
+    assert!(check(&config, vec![recent.hash()]).is_ok());
+}
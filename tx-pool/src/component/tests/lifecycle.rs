@@ -0,0 +1,114 @@
+use ckb_types::core::{
+    tx_pool::{Reject, TxLifecycleStage},
+    Capacity, TransactionBuilder,
+};
+use ckb_types::packed::Byte32;
+
+use crate::callback::PoolEventListener;
+use crate::component::entry::TxEntry;
+use crate::component::lifecycle::TxLifecycleTracer;
+
+fn stages(tracer: &TxLifecycleTracer, tx_hash: &Byte32) -> Vec<TxLifecycleStage> {
+    tracer
+        .get(tx_hash)
+        .map(|trace| trace.events.iter().map(|event| event.stage).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_lifecycle_record() {
+    let tracer = TxLifecycleTracer::new(10);
+    let tx = TransactionBuilder::default().build();
+    let entry = TxEntry::dummy_resolve(tx.clone(), 0, Capacity::shannons(100), 100);
+
+    tracer.record(
+        &tx.hash(),
+        TxLifecycleStage::Submitted,
+        Some("submitted locally".to_string()),
+    );
+    tracer.on_accepted(&entry);
+    tracer.on_proposed(&entry);
+    tracer.record(&tx.hash(), TxLifecycleStage::Packaged, None);
+    // the tx stays in the later templates
+    tracer.record(&tx.hash(), TxLifecycleStage::Packaged, None);
+    tracer.on_committed(&entry);
+
+    let trace = tracer.get(&tx.hash()).unwrap();
+    assert_eq!(trace.tx_hash, tx.hash());
+    assert!(trace.is_finished());
+    assert_eq!(
+        stages(&tracer, &tx.hash()),
+        vec![
+            TxLifecycleStage::Submitted,
+            TxLifecycleStage::Verified,
+            TxLifecycleStage::Proposed,
+            TxLifecycleStage::Packaged,
+            TxLifecycleStage::Committed,
+        ]
+    );
+    assert_eq!(trace.events[0].detail.as_deref(), Some("submitted locally"));
+    assert!(trace
+        .events
+        .windows(2)
+        .all(|pair| pair[0].timestamp <= pair[1].timestamp));
+}
+
+#[test]
+fn test_lifecycle_restart_after_finished() {
+    let tracer = TxLifecycleTracer::new(10);
+    let tx = TransactionBuilder::default().build();
+    let entry = TxEntry::dummy_resolve(tx.clone(), 0, Capacity::shannons(100), 100);
+
+    tracer.record(&tx.hash(), TxLifecycleStage::Submitted, None);
+    tracer.on_committed(&entry);
+    let first = tracer.get(&tx.hash()).unwrap();
+
+    // re-added to the pool after a reorg
+    tracer.on_accepted(&entry);
+    tracer.on_rejected(&entry, &Reject::Expiry(0));
+    let second = tracer.get(&tx.hash()).unwrap();
+    assert_ne!(first.trace_id, second.trace_id);
+    assert_eq!(
+        stages(&tracer, &tx.hash()),
+        vec![TxLifecycleStage::Verified, TxLifecycleStage::Rejected]
+    );
+    assert_eq!(second.events[1].detail, Some(Reject::Expiry(0).to_string()));
+}
+
+#[test]
+fn test_lifecycle_events_capped() {
+    let tracer = TxLifecycleTracer::new(10);
+    let tx = TransactionBuilder::default().build();
+
+    tracer.record(&tx.hash(), TxLifecycleStage::Submitted, None);
+    for _ in 0..100 {
+        tracer.record(&tx.hash(), TxLifecycleStage::Proposed, None);
+        tracer.record(&tx.hash(), TxLifecycleStage::Packaged, None);
+    }
+    let events = tracer.get(&tx.hash()).unwrap().events;
+    assert!(events.len() < 100);
+
+    // the final stage is always recorded
+    tracer.record(&tx.hash(), TxLifecycleStage::Committed, None);
+    let trace = tracer.get(&tx.hash()).unwrap();
+    assert_eq!(trace.events.len(), events.len() + 1);
+    assert!(trace.is_finished());
+}
+
+#[test]
+fn test_lifecycle_capacity() {
+    let disabled = TxLifecycleTracer::new(0);
+    let tx = TransactionBuilder::default().build();
+    disabled.record(&tx.hash(), TxLifecycleStage::Submitted, None);
+    assert!(disabled.get(&tx.hash()).is_none());
+
+    let tracer = TxLifecycleTracer::new(2);
+    let hashes: Vec<Byte32> = (0..3u8).map(|i| Byte32::new([i; 32])).collect();
+    for hash in &hashes {
+        tracer.record(hash, TxLifecycleStage::Submitted, None);
+    }
+    // the oldest trace is evicted
+    assert!(tracer.get(&hashes[0]).is_none());
+    assert!(tracer.get(&hashes[1]).is_some());
+    assert!(tracer.get(&hashes[2]).is_some());
+}
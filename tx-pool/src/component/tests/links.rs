@@ -31,3 +31,109 @@ fn test_link_map() {
     let parents = map.calc_relation_ids([id1.clone()].into(), Relation::Parents);
     assert_eq!(parents.len(), 2);
 }
+
+fn id(n: u8) -> ProposalShortId {
+    ProposalShortId::from_slice(&[n; 10]).unwrap()
+}
+
+fn link(map: &mut TxLinksMap, parent: &ProposalShortId, child: &ProposalShortId) {
+    map.add_child(parent, child.clone());
+    map.add_parent(child, parent.clone());
+}
+
+// The memoized sets must match the ones computed from scratch, a clone starts with an empty cache.
+fn assert_relations(map: &TxLinksMap) {
+    let fresh = map.clone();
+    for short_id in map.inner.keys() {
+        assert_eq!(map.calc_ancestors(short_id), fresh.calc_ancestors(short_id));
+        assert_eq!(
+            map.calc_descendants(short_id),
+            fresh.calc_descendants(short_id)
+        );
+        assert_eq!(
+            map.ancestors_count(short_id),
+            fresh.calc_ancestors(short_id).len()
+        );
+        assert_eq!(
+            map.descendants_count(short_id),
+            fresh.calc_descendants(short_id).len()
+        );
+    }
+}
+
+#[test]
+fn test_memoized_relations() {
+    let mut map = TxLinksMap::default();
+    // 1 -> 2 -> 3 -> 4
+    for n in 1..=4 {
+        map.add_link(id(n), TxLinks::default());
+    }
+    for n in 1..4 {
+        link(&mut map, &id(n), &id(n + 1));
+    }
+    assert_eq!(map.descendants_count(&id(1)), 3);
+    assert_eq!(map.ancestors_count(&id(4)), 3);
+    assert_relations(&map);
+
+    // 1 -> 2 -> 3 -> 4
+    //       \-> 5
+    map.add_link(
+        id(5),
+        TxLinks {
+            parents: [id(2)].into(),
+            children: Default::default(),
+        },
+    );
+    map.add_child(&id(2), id(5));
+    assert_eq!(
+        map.calc_descendants(&id(1)),
+        [id(2), id(3), id(4), id(5)].into()
+    );
+    assert_relations(&map);
+
+    // remove 3 as the pool does
+    map.remove_child(&id(2), &id(3));
+    map.remove_parent(&id(4), &id(3));
+    map.remove(&id(3));
+    assert_eq!(map.calc_descendants(&id(1)), [id(2), id(5)].into());
+    assert!(map.calc_ancestors(&id(4)).is_empty());
+    assert_relations(&map);
+
+    // the stage can start from ids with memoized sets
+    let ancestors = map.calc_relation_ids([id(5), id(4)].into(), Relation::Parents);
+    assert_eq!(ancestors, [id(1), id(2), id(4), id(5)].into());
+
+    map.clear();
+    assert_eq!(map.descendants_count(&id(1)), 0);
+}
+
+#[test]
+fn test_invalidate_only_stale_sets() {
+    let mut map = TxLinksMap::default();
+    // 1 -> 2 -> 3 and 4 -> 5
+    for n in 1..=5 {
+        map.add_link(id(n), TxLinks::default());
+    }
+    link(&mut map, &id(1), &id(2));
+    link(&mut map, &id(2), &id(3));
+    link(&mut map, &id(4), &id(5));
+    for n in 1..=5 {
+        map.calc_ancestors(&id(n));
+        map.calc_descendants(&id(n));
+    }
+    assert_eq!(map.cached_sets_len(), (5, 5));
+
+    // the link 2 -> 3 changes the descendants of 1 and 2, and the ancestors of 3
+    map.remove_child(&id(2), &id(3));
+    assert_eq!(map.cached_sets_len(), (4, 3));
+    map.remove_parent(&id(3), &id(2));
+    assert_eq!(map.cached_sets_len(), (4, 3));
+    assert_relations(&map);
+
+    // no index entry outlives its sets
+    map.remove(&id(4));
+    map.remove(&id(5));
+    map.remove(&id(3));
+    assert_eq!(map.cached_sets_len(), (2, 2));
+    assert_eq!(map.cache_index_len(), (2, 2));
+}
@@ -0,0 +1,102 @@
+use ckb_app_config::{StoreConfig, TxPoolConfig};
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_store::ChainDB;
+use ckb_types::{
+    core::{tx_pool::Reject, TransactionView},
+    packed::Byte32,
+    prelude::*,
+};
+use ckb_util::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::callback::{Callbacks, PoolEventListener};
+use crate::component::entry::TxEntry;
+use crate::component::tests::util::{build_snapshot, build_tx, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE};
+use crate::pool::TxPool;
+use crate::process::{_submit_entry, TxStatus};
+
+// Records the events as `(event, tx_hash)`
+#[derive(Default)]
+struct RecordingListener {
+    events: Mutex<Vec<(&'static str, Byte32)>>,
+}
+
+impl RecordingListener {
+    fn record(&self, event: &'static str, entry: &TxEntry) {
+        self.events.lock().push((event, entry.transaction().hash()));
+    }
+
+    fn take(&self) -> Vec<(&'static str, Byte32)> {
+        std::mem::take(&mut *self.events.lock())
+    }
+}
+
+impl PoolEventListener for RecordingListener {
+    fn on_accepted(&self, entry: &TxEntry) {
+        self.record("accepted", entry);
+    }
+
+    fn on_proposed(&self, entry: &TxEntry) {
+        self.record("proposed", entry);
+    }
+
+    fn on_rejected(&self, entry: &TxEntry, _reject: &Reject) {
+        self.record("rejected", entry);
+    }
+
+    fn on_committed(&self, entry: &TxEntry) {
+        self.record("committed", entry);
+    }
+
+    fn on_replaced(&self, old: &TxEntry, _new: &TxEntry) {
+        self.record("replaced", old);
+    }
+}
+
+fn entry(tx: &TransactionView) -> TxEntry {
+    TxEntry::dummy_resolve(tx.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE)
+}
+
+#[test]
+fn test_listener_events() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let db = ChainDB::new(RocksDB::open_in(&tmp_dir, COLUMNS), StoreConfig::default());
+    let mut tx_pool = TxPool::new(TxPoolConfig::default(), build_snapshot(&db, HashSet::new()));
+    let listener = Arc::new(RecordingListener::default());
+    let mut callbacks = Callbacks::new();
+    callbacks.register_listener(Arc::clone(&listener) as Arc<dyn PoolEventListener>);
+
+    let tx1 = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let tx2 = build_tx(vec![(&Byte32::zero(), 1)], 1);
+    // spends the same input as tx2
+    let tx3 = build_tx(vec![(&Byte32::zero(), 1)], 2);
+
+    assert!(_submit_entry(&mut tx_pool, TxStatus::Fresh, entry(&tx1), &callbacks).is_ok());
+    assert!(_submit_entry(&mut tx_pool, TxStatus::Proposed, entry(&tx2), &callbacks).is_ok());
+    assert_eq!(
+        listener.take(),
+        vec![("accepted", tx1.hash()), ("proposed", tx2.hash())]
+    );
+
+    // tx1 is committed, tx2 is removed by the committed conflicting tx3
+    tx_pool.remove_committed_txs(
+        vec![(&tx1, 11), (&tx3, 11)].into_iter(),
+        &callbacks,
+        &HashSet::new(),
+    );
+    assert_eq!(
+        listener.take(),
+        vec![("committed", tx1.hash()), ("rejected", tx2.hash())]
+    );
+    assert!(tx_pool.get_pool_entry(&tx1.proposal_short_id()).is_none());
+    assert!(tx_pool.get_pool_entry(&tx2.proposal_short_id()).is_none());
+
+    // txs unknown to the pool are not reported as committed
+    tx_pool.remove_committed_txs(vec![(&tx2, 12)].into_iter(), &callbacks, &HashSet::new());
+    assert!(listener.take().is_empty());
+
+    callbacks.call_replaced(&entry(&tx2), &entry(&tx3));
+    assert_eq!(listener.take(), vec![("replaced", tx2.hash())]);
+}
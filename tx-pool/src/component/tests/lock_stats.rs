@@ -0,0 +1,98 @@
+use crate::component::entry::TxEntry;
+use crate::component::lock_stats::LockStats;
+use crate::component::pool_map::{PoolMap, Status};
+use crate::component::tests::util::build_tx;
+use ckb_types::{
+    bytes::Bytes,
+    core::{cell::CellMetaBuilder, cell::ResolvedTransaction, Capacity, TransactionView},
+    packed::{Byte32, CellOutput, Script},
+    prelude::*,
+};
+use std::sync::Arc;
+
+fn lock(arg: u8) -> Script {
+    Script::new_builder().args(vec![arg].pack()).build()
+}
+
+// resolves the inputs of the tx to the cells locked by `locks` in order
+fn entry_with_locks(tx: TransactionView, locks: &[Script], fee: u64, size: usize) -> TxEntry {
+    let resolved_inputs = tx
+        .inputs()
+        .into_iter()
+        .zip(locks)
+        .map(|(input, lock)| {
+            let output = CellOutput::new_builder().lock(lock.clone()).build();
+            CellMetaBuilder::from_cell_output(output, Bytes::new())
+                .out_point(input.previous_output())
+                .build()
+        })
+        .collect();
+    let rtx = ResolvedTransaction {
+        transaction: tx,
+        resolved_cell_deps: vec![],
+        resolved_inputs,
+        resolved_dep_groups: vec![],
+    };
+    TxEntry::new(Arc::new(rtx), 0, Capacity::shannons(fee), size)
+}
+
+#[test]
+fn test_lock_stats_group_by_input_locks() {
+    let (a, b) = (lock(1), lock(2));
+    let tx1 = build_tx(vec![(&Byte32::zero(), 0), (&Byte32::zero(), 1)], 1);
+    let tx2 = build_tx(vec![(&Byte32::zero(), 2)], 1);
+    // both inputs of tx1 are locked by `a`, it's counted once
+    let entry1 = entry_with_locks(tx1, &[a.clone(), a.clone()], 100, 300);
+    let entry2 = entry_with_locks(tx2, &[b.clone()], 200, 200);
+
+    let mut stats = LockStats::default();
+    stats.add(&entry1);
+    stats.add(&entry2);
+    let top = stats.top(10);
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[0].lock_hash, a.calc_script_hash());
+    assert_eq!(top[0].tx_count, 1);
+    assert_eq!(top[0].tx_size, 300);
+    assert_eq!(top[0].fees, Capacity::shannons(100));
+    assert_eq!(top[1].lock_hash, b.calc_script_hash());
+
+    // only the largest group is kept
+    assert_eq!(stats.top(1), vec![top[0].clone()]);
+    assert!(stats.top(0).is_empty());
+
+    stats.remove(&entry1);
+    let top = stats.top(10);
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].lock_hash, b.calc_script_hash());
+}
+
+#[test]
+fn test_lock_stats_in_pool_map() {
+    let (a, b) = (lock(1), lock(2));
+    let tx1 = build_tx(vec![(&Byte32::zero(), 0), (&Byte32::zero(), 1)], 1);
+    let tx2 = build_tx(vec![(&Byte32::zero(), 2)], 1);
+    let entry1 = entry_with_locks(tx1, &[a.clone(), b.clone()], 100, 300);
+    let entry2 = entry_with_locks(tx2, &[b.clone()], 200, 200);
+
+    let mut pool = PoolMap::new(100);
+    pool.add_entry(entry1.clone(), Status::Pending).unwrap();
+    pool.add_entry(entry2, Status::Pending).unwrap();
+    let top = pool.top_lock_scripts(10);
+    assert_eq!(top.len(), 2);
+    // tx1 unlocks the cells of both `a` and `b`
+    assert_eq!(top[0].lock_hash, b.calc_script_hash());
+    assert_eq!(top[0].tx_count, 2);
+    assert_eq!(top[0].tx_size, 500);
+    assert_eq!(top[0].fees, Capacity::shannons(300));
+    assert_eq!(top[1].lock_hash, a.calc_script_hash());
+    assert_eq!(top[1].tx_count, 1);
+
+    pool.remove_entry(&entry1.proposal_short_id());
+    let top = pool.top_lock_scripts(10);
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].tx_count, 1);
+    assert_eq!(top[0].tx_size, 200);
+
+    pool.clear();
+    assert!(pool.top_lock_scripts(10).is_empty());
+}
@@ -1,9 +1,25 @@
+mod absurd_fee;
 mod chunk;
+mod commit_txs_scanner;
+mod conflicts_pool;
+mod demotion;
 mod entry;
+mod fee_breakdown;
+mod fee_estimator;
+mod header_dep_depth;
+mod lifecycle;
 mod links;
+mod listener;
+mod lock_stats;
 mod orphan;
 mod pending;
 mod proposed;
+mod rbf_limiter;
+mod read_view;
 mod recent_reject;
 mod score_key;
+mod template_protection;
+mod tx_graph;
 mod util;
+mod verify_cache;
+mod witness_store;
@@ -79,6 +79,44 @@ fn test_add_entry() {
     assert_eq!(pool.edges.inputs_len(), 1);
 }
 
+#[test]
+fn test_cell_pool_status() {
+    let tx1 = build_tx(vec![(&Byte32::zero(), 1)], 2);
+    let tx1_hash = tx1.hash();
+    let tx2 = build_tx(vec![(&tx1_hash, 0)], 1);
+    let tx2_hash = tx2.hash();
+
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    for tx in [tx1, tx2] {
+        pool.add_proposed(TxEntry::new(
+            dummy_resolve(tx, |_| None),
+            MOCK_CYCLES,
+            MOCK_FEE,
+            MOCK_SIZE,
+        ))
+        .unwrap();
+    }
+
+    // a store cell spent by tx1
+    let status = pool.cell_pool_status(&OutPoint::new(Byte32::zero(), 1));
+    assert_eq!(status.created_by, None);
+    assert_eq!(status.spent_by, Some(tx1_hash.clone()));
+
+    // created by tx1 and spent by tx2
+    let status = pool.cell_pool_status(&OutPoint::new(tx1_hash.clone(), 0));
+    assert_eq!(status.created_by, Some(tx1_hash.clone()));
+    assert_eq!(status.spent_by, Some(tx2_hash));
+
+    // created by tx1 and not spent
+    let status = pool.cell_pool_status(&OutPoint::new(tx1_hash.clone(), 1));
+    assert_eq!(status.created_by, Some(tx1_hash.clone()));
+    assert_eq!(status.spent_by, None);
+
+    // tx1 has no such output
+    let status = pool.cell_pool_status(&OutPoint::new(tx1_hash, 2));
+    assert_eq!(status, Default::default());
+}
+
 #[test]
 fn test_add_entry_from_detached() {
     let tx1 = build_tx(vec![(&Byte32::zero(), 1), (&Byte32::zero(), 2)], 1);
@@ -667,6 +705,40 @@ fn test_max_ancestors() {
     assert_eq!(pool.edges.inputs_len(), 1);
 }
 
+#[test]
+fn test_demoted_entry() {
+    let mut pool = PoolMap::new(1);
+    let tx1 = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let tx1_id = tx1.proposal_short_id();
+    let tx2 = build_tx(vec![(&tx1.hash(), 0)], 1);
+    let tx2_id = tx2.proposal_short_id();
+
+    let entry1 = TxEntry::dummy_resolve(tx1, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+    let entry2 = TxEntry::dummy_resolve(tx2, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+
+    assert!(pool.add_proposed(entry1).is_ok());
+    assert!(pool.add_proposed(entry2.clone()).is_err());
+
+    // the tx exceeding the ancestors limit is kept in pending
+    assert!(pool.add_demoted_entry(entry2.clone()).unwrap());
+    assert!(!pool.add_demoted_entry(entry2).unwrap());
+    assert_eq!(pool.get_by_id(&tx2_id).unwrap().status, Status::Pending);
+    assert!(pool.is_demoted(&tx2_id));
+    assert_eq!(
+        pool.calc_ancestors(&tx2_id),
+        HashSet::from([tx1_id.clone()])
+    );
+    assert!(!pool.promote(&tx2_id));
+
+    // the parent is committed
+    pool.remove_entry(&tx1_id);
+    assert!(pool.promote(&tx2_id));
+    assert!(!pool.is_demoted(&tx2_id));
+
+    pool.remove_entry(&tx2_id);
+    assert!(pool.demoted.is_empty());
+}
+
 #[test]
 fn test_max_ancestors_with_dep() {
     let mut pool = PoolMap::new(1);
@@ -798,3 +870,51 @@ fn test_pool_map_bench() {
     );
     assert!(diff < expect_diff_range);
 }
+
+#[test]
+fn test_set_fee_delta() {
+    let tx1 = build_tx(vec![(&Byte32::zero(), 1)], 1);
+    let tx2 = build_tx(vec![(&tx1.hash(), 0)], 1);
+    let tx3 = build_tx(vec![(&Byte32::zero(), 2)], 1);
+    let id1 = tx1.proposal_short_id();
+    let id2 = tx2.proposal_short_id();
+    let id3 = tx3.proposal_short_id();
+
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    for (tx, fee) in [(tx1, 100), (tx2, 100), (tx3, 300)] {
+        pool.add_proposed(TxEntry::dummy_resolve(
+            tx,
+            MOCK_CYCLES,
+            Capacity::shannons(fee),
+            MOCK_SIZE,
+        ))
+        .unwrap();
+    }
+    let first_id = |pool: &PoolMap| {
+        pool.score_sorted_iter_by_status(Status::Proposed)
+            .next()
+            .map(|entry| entry.proposal_short_id())
+    };
+    assert_eq!(first_id(&pool), Some(id3.clone()));
+
+    assert!(pool.set_fee_delta(&id1, 1000));
+    assert_eq!(first_id(&pool), Some(id1.clone()));
+    let entry1 = &pool.get_by_id(&id1).unwrap().inner;
+    assert_eq!(entry1.fee, Capacity::shannons(100));
+    assert_eq!(entry1.modified_fee(), Capacity::shannons(1100));
+    assert_eq!(entry1.descendants_fee, Capacity::shannons(1200));
+    let entry2 = &pool.get_by_id(&id2).unwrap().inner;
+    assert_eq!(entry2.ancestors_fee, Capacity::shannons(1200));
+
+    // replace the delta, then remove it
+    assert!(pool.set_fee_delta(&id1, 50));
+    let entry2 = &pool.get_by_id(&id2).unwrap().inner;
+    assert_eq!(entry2.ancestors_fee, Capacity::shannons(250));
+    assert!(pool.set_fee_delta(&id1, 0));
+    assert_eq!(first_id(&pool), Some(id3));
+    let entry2 = &pool.get_by_id(&id2).unwrap().inner;
+    assert_eq!(entry2.ancestors_fee, Capacity::shannons(200));
+
+    pool.remove_entry(&id2);
+    assert!(!pool.set_fee_delta(&id2, 1000));
+}
@@ -0,0 +1,115 @@
+use ckb_app_config::{StoreConfig, TxPoolConfig};
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_store::ChainDB;
+use ckb_types::{
+    core::{Capacity, FeeRate},
+    h256,
+    packed::{Byte32, OutPoint},
+    prelude::*,
+};
+use std::collections::HashSet;
+
+use crate::component::entry::TxEntry;
+use crate::component::rbf_limiter::RbfLimiter;
+use crate::component::tests::util::{build_snapshot, build_tx, MOCK_CYCLES};
+use crate::pool::TxPool;
+
+#[test]
+fn test_replacements_count_in_window() {
+    let mut limiter = RbfLimiter::new(10);
+    let pt1 = OutPoint::new(h256!("0x1").pack(), 0);
+    let pt2 = OutPoint::new(h256!("0x2").pack(), 0);
+
+    assert_eq!(limiter.replacements_count([pt1.clone()].iter(), 0), 0);
+
+    let fees = Capacity::shannons(100);
+    limiter.record(vec![pt1.clone()].into_iter(), fees, 1_000);
+    limiter.record(vec![pt1.clone(), pt2.clone()].into_iter(), fees, 2_000);
+    limiter.record(vec![pt1.clone()].into_iter(), fees, 3_000);
+
+    assert_eq!(limiter.replacements_count([pt1.clone()].iter(), 3_000), 3);
+    assert_eq!(limiter.replacements_count([pt2.clone()].iter(), 3_000), 1);
+    // the max count among the outpoints
+    assert_eq!(
+        limiter.replacements_count([pt1.clone(), pt2.clone()].iter(), 3_000),
+        3
+    );
+
+    // the first replacement slides out of the window
+    assert_eq!(limiter.replacements_count([pt1.clone()].iter(), 11_500), 2);
+    assert_eq!(limiter.replacements_count([pt1.clone()].iter(), 13_500), 0);
+
+    limiter.clear();
+    assert_eq!(limiter.replacements_count([pt1, pt2].iter(), 3_000), 0);
+}
+
+#[test]
+fn test_replaced_fees_in_window() {
+    let mut limiter = RbfLimiter::new(10);
+    let pt1 = OutPoint::new(h256!("0x1").pack(), 0);
+    let pt2 = OutPoint::new(h256!("0x2").pack(), 0);
+
+    assert_eq!(limiter.replaced_fees(0), Capacity::zero());
+
+    limiter.record(
+        vec![pt1.clone()].into_iter(),
+        Capacity::shannons(100),
+        1_000,
+    );
+    limiter.record(vec![pt2].into_iter(), Capacity::shannons(200), 2_000);
+    limiter.record(
+        vec![pt1.clone()].into_iter(),
+        Capacity::shannons(300),
+        3_000,
+    );
+    // the fees are summed across the outpoints
+    assert_eq!(limiter.replaced_fees(3_000), Capacity::shannons(600));
+
+    // the first replacement slides out of the window
+    assert_eq!(limiter.replaced_fees(11_500), Capacity::shannons(500));
+    assert_eq!(limiter.replaced_fees(13_500), Capacity::zero());
+
+    // the expired replacements are dropped on recording
+    limiter.record(vec![pt1].into_iter(), Capacity::shannons(400), 12_500);
+    assert_eq!(limiter.replaced_fees(12_500), Capacity::shannons(700));
+
+    limiter.clear();
+    assert_eq!(limiter.replaced_fees(12_500), Capacity::zero());
+}
+
+#[test]
+fn test_min_replace_fee_over_replaced_fees_cap() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let db = ChainDB::new(RocksDB::open_in(&tmp_dir, COLUMNS), StoreConfig::default());
+    let config = TxPoolConfig {
+        min_rbf_rate: FeeRate::from_u64(1_500),
+        rbf_replaced_fees_cap: Some(1_000),
+        ..Default::default()
+    };
+    let mut tx_pool = TxPool::new(config, build_snapshot(&db, HashSet::new()));
+
+    let tx = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let entry = TxEntry::dummy_resolve(tx, MOCK_CYCLES, Capacity::shannons(500), 1_000);
+    tx_pool.add_pending(entry.clone()).unwrap();
+    // the fee of the tx plus 1_500 shannons for its 1_000 bytes
+    assert_eq!(
+        tx_pool.min_replace_fee(&entry),
+        Some(Capacity::shannons(2_000))
+    );
+
+    // the fees replaced in the window reach the cap
+    let other = build_tx(vec![(&h256!("0x1").pack(), 0)], 1);
+    tx_pool.record_rbf_replacement(&other, Capacity::shannons(1_000));
+    assert_eq!(
+        tx_pool.min_replace_fee(&entry),
+        Some(Capacity::shannons(2_000))
+    );
+
+    // the fees replaced in the window are 3 times the cap, so is the extra fee
+    tx_pool.record_rbf_replacement(&other, Capacity::shannons(2_000));
+    assert_eq!(
+        tx_pool.min_replace_fee(&entry),
+        Some(Capacity::shannons(5_000))
+    );
+}
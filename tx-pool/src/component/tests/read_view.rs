@@ -0,0 +1,52 @@
+use crate::component::entry::TxEntry;
+use crate::component::pool_map::{PoolMap, Status};
+use crate::component::read_view::PoolReadView;
+use crate::component::tests::util::{
+    build_tx, DEFAULT_MAX_ANCESTORS_COUNT, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE,
+};
+use ckb_types::core::tx_pool::TxPoolIds;
+use ckb_types::packed::Byte32;
+use std::sync::Arc;
+
+fn pool_ids(pool: &PoolMap) -> Arc<TxPoolIds> {
+    Arc::new(TxPoolIds {
+        pending: pool
+            .score_sorted_iter_by_status(Status::Pending)
+            .map(|entry| entry.transaction().hash())
+            .collect(),
+        proposed: vec![],
+    })
+}
+
+#[test]
+fn test_read_view_follows_pool_version() {
+    let view = PoolReadView::new();
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    pool.version = view.version_counter();
+    assert!(view.ids().is_none());
+
+    let tx1 = build_tx(vec![(&Byte32::zero(), 1)], 1);
+    let entry1 = TxEntry::dummy_resolve(tx1.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+    pool.add_entry(entry1, Status::Pending).unwrap();
+
+    let version = view.current_version();
+    let ids = pool_ids(&pool);
+    view.publish_ids(version, Arc::clone(&ids));
+    // the copy is shared instead of cloned
+    assert!(Arc::ptr_eq(&view.ids().unwrap(), &ids));
+
+    // any mutation outdates the published copy
+    pool.set_entry(&tx1.proposal_short_id(), Status::Gap);
+    assert!(view.ids().is_none());
+
+    let version = view.current_version();
+    view.publish_ids(version, pool_ids(&pool));
+    assert!(view.ids().is_some());
+    pool.remove_entry(&tx1.proposal_short_id());
+    assert!(view.ids().is_none());
+
+    // a copy built at an older version is never served
+    view.publish_ids(version, pool_ids(&pool));
+    assert!(view.ids().is_none());
+    assert!(view.entry_info().is_none());
+}
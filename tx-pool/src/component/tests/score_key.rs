@@ -1,11 +1,16 @@
 use ckb_types::{
     bytes::Bytes,
     core::{Capacity, TransactionBuilder},
-    packed::{CellInput, OutPoint},
+    packed::{Byte32, CellInput, OutPoint},
     prelude::*,
 };
+use proptest::prelude::*;
 
-use crate::component::{entry::TxEntry, pool_map::PoolMap, sort_key::AncestorsScoreSortKey};
+use crate::component::{
+    entry::TxEntry,
+    pool_map::{PoolMap, Status},
+    sort_key::AncestorsScoreSortKey,
+};
 
 const DEFAULT_MAX_ANCESTORS_COUNT: usize = 125;
 
@@ -28,6 +33,7 @@ fn test_min_fee_and_weight() {
             weight,
             ancestors_fee: Capacity::shannons(ancestors_fee),
             ancestors_weight,
+            tx_hash: Byte32::zero(),
         };
         key.min_fee_and_weight()
     })
@@ -71,6 +77,7 @@ fn test_ancestors_sorted_key_order() {
                 weight,
                 ancestors_fee: Capacity::shannons(ancestors_fee),
                 ancestors_weight,
+                tx_hash: Byte32::zero(),
             },
         )
         .collect::<Vec<_>>();
@@ -95,6 +102,93 @@ fn test_ancestors_sorted_key_order() {
     assert_eq!(now, expect);
 }
 
+#[test]
+fn test_ancestors_sorted_key_tie_break_by_tx_hash() {
+    let mut keys = [[3u8; 32], [1u8; 32], [2u8; 32]]
+        .into_iter()
+        .map(|hash| AncestorsScoreSortKey {
+            fee: Capacity::shannons(500),
+            weight: 10,
+            ancestors_fee: Capacity::shannons(1000),
+            ancestors_weight: 30,
+            tx_hash: Byte32::new(hash),
+        })
+        .collect::<Vec<_>>();
+    keys.sort();
+    // the pool is iterated by the score reversely, the smaller hash is packaged first
+    assert_eq!(
+        keys.iter()
+            .rev()
+            .map(|key| key.tx_hash.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Byte32::new([1u8; 32]),
+            Byte32::new([2u8; 32]),
+            Byte32::new([3u8; 32])
+        ]
+    );
+}
+
+fn score_key() -> impl Strategy<Value = AncestorsScoreSortKey> {
+    // the small ranges make the ties common
+    (1..4u64, 1..4u64, 0..3u64, 0..3u64, any::<[u8; 32]>()).prop_map(
+        |(fee, weight, ancestors_fee, ancestors_weight, hash)| AncestorsScoreSortKey {
+            fee: Capacity::shannons(fee),
+            weight,
+            ancestors_fee: Capacity::shannons(fee + ancestors_fee),
+            ancestors_weight: weight + ancestors_weight,
+            tx_hash: Byte32::new(hash),
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn test_ancestors_sorted_key_total_order(a in score_key(), b in score_key()) {
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        prop_assert_eq!(a.cmp(&b) == std::cmp::Ordering::Equal, a == b);
+    }
+
+    #[test]
+    fn test_ancestors_sorted_key_order_independent_of_insertion(keys in prop::collection::vec(score_key(), 0..32)) {
+        let mut sorted = keys.clone();
+        sorted.sort();
+        let mut reversed = keys.into_iter().rev().collect::<Vec<_>>();
+        reversed.sort();
+        prop_assert_eq!(sorted, reversed);
+    }
+}
+
+#[test]
+fn test_score_order_independent_of_insertion() {
+    let entries = (0..8u8)
+        .map(|i| {
+            TxEntry::dummy_resolve(
+                TransactionBuilder::default()
+                    .output_data(Bytes::from(vec![i]).pack())
+                    .build(),
+                100,
+                Capacity::shannons(100),
+                100,
+            )
+        })
+        .collect::<Vec<_>>();
+    let sorted_ids = |entries: Vec<TxEntry>| {
+        let mut map = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+        for entry in entries {
+            map.add_proposed(entry).unwrap();
+        }
+        map.sorted_proposed_iter()
+            .map(|entry| entry.transaction().hash())
+            .collect::<Vec<_>>()
+    };
+    let ids = sorted_ids(entries.clone());
+    assert_eq!(ids, sorted_ids(entries.into_iter().rev().collect()));
+    let mut expect = ids.clone();
+    expect.sort();
+    assert_eq!(ids, expect);
+}
+
 #[test]
 fn test_remove_entry() {
     let mut map = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
@@ -229,3 +323,61 @@ fn test_remove_entry_and_descendants() {
     assert!(!descendants_set.contains(&tx2_id));
     assert!(!descendants_set.contains(&tx3_id));
 }
+
+#[test]
+fn test_score_sorted_page() {
+    let mut map = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    for i in 0..10u8 {
+        let entry = TxEntry::dummy_resolve(
+            TransactionBuilder::default()
+                .output_data(Bytes::from(vec![i]).pack())
+                .build(),
+            100,
+            Capacity::shannons(100 + u64::from(i)),
+            100,
+        );
+        let status = if i % 2 == 0 {
+            Status::Pending
+        } else {
+            Status::Proposed
+        };
+        map.add_entry(entry, status).unwrap();
+    }
+    let expect: Vec<_> = map
+        .score_sorted_iter_by_status(Status::Pending)
+        .map(|entry| entry.proposal_short_id())
+        .collect();
+    assert_eq!(expect.len(), 5);
+
+    let page = map.score_sorted_page(&[Status::Pending], None, 2);
+    let mut ids: Vec<_> = page.entries.iter().map(|e| e.proposal_short_id()).collect();
+    assert_eq!(ids, expect[..2]);
+
+    // the entries removed between the pages don't shift the next page
+    map.remove_entry(&expect[1]);
+    map.remove_entry(&expect[2]);
+    let page = map.score_sorted_page(&[Status::Pending], page.next.as_ref(), 2);
+    assert!(page.next.is_none());
+    ids.extend(page.entries.iter().map(|e| e.proposal_short_id()));
+    assert_eq!(
+        ids,
+        vec![
+            expect[0].clone(),
+            expect[1].clone(),
+            expect[3].clone(),
+            expect[4].clone()
+        ]
+    );
+
+    let page = map.score_sorted_page(&[Status::Pending, Status::Proposed], None, 10);
+    assert_eq!(page.entries.len(), 8);
+    assert!(page.next.is_none());
+
+    // the pages follow the scores changed by the fee deltas
+    assert!(map.set_fee_delta(&expect[4], 1000));
+    let page = map.score_sorted_page(&[Status::Pending], None, 1);
+    assert_eq!(page.entries[0].proposal_short_id(), expect[4]);
+    let page = map.score_sorted_page(&[Status::Pending], page.next.as_ref(), 10);
+    let ids: Vec<_> = page.entries.iter().map(|e| e.proposal_short_id()).collect();
+    assert_eq!(ids, vec![expect[0].clone(), expect[3].clone()]);
+}
@@ -0,0 +1,53 @@
+use ckb_types::packed::ProposalShortId;
+
+use crate::component::template_protection::TemplateProtection;
+
+#[test]
+fn test_template_protection_expires() {
+    let mut protection = TemplateProtection::new(10);
+    let id1 = ProposalShortId::new([1u8; 10]);
+    let id2 = ProposalShortId::new([2u8; 10]);
+
+    assert!(!protection.is_protected(&id1, 0));
+
+    protection.protect(vec![id1.clone()].into_iter(), 1_000);
+    protection.protect(vec![id2.clone()].into_iter(), 5_000);
+    assert!(protection.is_protected(&id1, 5_000));
+    assert!(protection.is_protected(&id2, 5_000));
+
+    // the protection of the first template expires
+    assert!(!protection.is_protected(&id1, 11_000));
+    assert!(protection.is_protected(&id2, 11_000));
+
+    // a later template refreshes the protection
+    protection.protect(vec![id1.clone()].into_iter(), 12_000);
+    assert!(protection.is_protected(&id1, 20_000));
+
+    protection.clear();
+    assert!(!protection.is_protected(&id1, 12_000));
+}
+
+#[test]
+fn test_template_protection_needs_refresh() {
+    let mut protection = TemplateProtection::new(10);
+    let id1 = ProposalShortId::new([1u8; 10]);
+    let id2 = ProposalShortId::new([2u8; 10]);
+
+    assert!(protection.needs_refresh(vec![id1.clone()].into_iter(), 1_000));
+    protection.protect(vec![id1.clone()].into_iter(), 1_000);
+    assert!(!protection.needs_refresh(vec![id1.clone()].into_iter(), 5_000));
+    // a tx not protected yet
+    assert!(protection.needs_refresh(vec![id1.clone(), id2].into_iter(), 5_000));
+    // the protection expires within half of the ttl
+    assert!(protection.needs_refresh(vec![id1].into_iter(), 6_000));
+}
+
+#[test]
+fn test_template_protection_disabled() {
+    let mut protection = TemplateProtection::new(0);
+    let id = ProposalShortId::new([1u8; 10]);
+
+    assert!(!protection.needs_refresh(vec![id.clone()].into_iter(), 1_000));
+    protection.protect(vec![id.clone()].into_iter(), 1_000);
+    assert!(!protection.is_protected(&id, 1_000));
+}
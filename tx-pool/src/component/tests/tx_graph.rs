@@ -0,0 +1,100 @@
+use ckb_app_config::{StoreConfig, TxPoolConfig};
+use ckb_db::RocksDB;
+use ckb_db_schema::COLUMNS;
+use ckb_store::ChainDB;
+use ckb_types::{
+    core::{
+        tx_pool::{PoolTxGraph, PoolTxGraphEdge, PoolTxGraphRelation},
+        TransactionView,
+    },
+    packed::{Byte32, ProposalShortId},
+    prelude::*,
+};
+use std::collections::{HashMap, HashSet};
+
+use crate::component::entry::TxEntry;
+use crate::component::tests::util::{build_snapshot, build_tx, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE};
+use crate::pool::TxPool;
+
+fn entry(tx: &TransactionView) -> TxEntry {
+    TxEntry::dummy_resolve(tx.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE)
+}
+
+// The graph keeps the nodes and edges in the search order, compare them regardless of it
+fn relations(graph: &PoolTxGraph) -> HashMap<Byte32, (PoolTxGraphRelation, usize)> {
+    graph
+        .nodes
+        .iter()
+        .map(|node| (node.tx_hash.clone(), (node.relation, node.depth)))
+        .collect()
+}
+
+fn edges(graph: &PoolTxGraph) -> HashSet<(Byte32, Byte32)> {
+    graph
+        .edges
+        .iter()
+        .map(|PoolTxGraphEdge { parent, child }| (parent.clone(), child.clone()))
+        .collect()
+}
+
+#[test]
+fn test_get_tx_graph() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let db = ChainDB::new(RocksDB::open_in(&tmp_dir, COLUMNS), StoreConfig::default());
+    let mut tx_pool = TxPool::new(TxPoolConfig::default(), build_snapshot(&db, HashSet::new()));
+
+    // tx1 <- tx2 <- tx3
+    //          ^--- tx4
+    let tx1 = build_tx(vec![(&Byte32::zero(), 0)], 1);
+    let tx2 = build_tx(vec![(&tx1.hash(), 0)], 2);
+    let tx3 = build_tx(vec![(&tx2.hash(), 0)], 1);
+    let tx4 = build_tx(vec![(&tx2.hash(), 1)], 1);
+    for tx in [&tx1, &tx2, &tx3, &tx4] {
+        assert!(tx_pool.add_pending(entry(tx)).unwrap().0);
+    }
+
+    let graph = tx_pool.get_tx_graph(&tx2.proposal_short_id(), 2).unwrap();
+    assert_eq!(graph.tx_hash, tx2.hash());
+    assert!(!graph.truncated);
+    let root = graph.nodes.iter().find(|node| node.tx_hash == tx2.hash());
+    assert_eq!(root.unwrap().status, "pending");
+    assert_eq!(
+        relations(&graph),
+        HashMap::from([
+            (tx1.hash(), (PoolTxGraphRelation::Ancestor, 1)),
+            (tx2.hash(), (PoolTxGraphRelation::Root, 0)),
+            (tx3.hash(), (PoolTxGraphRelation::Descendant, 1)),
+            (tx4.hash(), (PoolTxGraphRelation::Descendant, 1)),
+        ])
+    );
+    assert_eq!(
+        edges(&graph),
+        HashSet::from([
+            (tx1.hash(), tx2.hash()),
+            (tx2.hash(), tx3.hash()),
+            (tx2.hash(), tx4.hash()),
+        ])
+    );
+
+    // the grandchildren are beyond the depth limit
+    let graph = tx_pool.get_tx_graph(&tx1.proposal_short_id(), 1).unwrap();
+    assert!(graph.truncated);
+    assert_eq!(
+        relations(&graph),
+        HashMap::from([
+            (tx1.hash(), (PoolTxGraphRelation::Root, 0)),
+            (tx2.hash(), (PoolTxGraphRelation::Descendant, 1)),
+        ])
+    );
+    assert_eq!(edges(&graph), HashSet::from([(tx1.hash(), tx2.hash())]));
+
+    // the leaf has no descendants, the whole chain is within the depth limit
+    let graph = tx_pool.get_tx_graph(&tx3.proposal_short_id(), 2).unwrap();
+    assert!(!graph.truncated);
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges.len(), 2);
+
+    assert!(tx_pool
+        .get_tx_graph(&ProposalShortId::new([1u8; 10]), 2)
+        .is_none());
+}
@@ -1,15 +1,34 @@
+use ckb_chain_spec::consensus::Consensus;
+use ckb_proposal_table::ProposalView;
+use ckb_snapshot::Snapshot;
+use ckb_store::ChainDB;
 use ckb_types::{
     bytes::Bytes,
-    core::{Capacity, Cycle, TransactionBuilder, TransactionView},
-    packed::{Byte32, CellDep, CellInput, CellOutput, OutPoint},
+    core::{Capacity, Cycle, EpochExt, HeaderBuilder, TransactionBuilder, TransactionView},
+    packed::{Byte32, CellDep, CellInput, CellOutput, OutPoint, ProposalShortId},
     prelude::*,
+    U256,
 };
+use std::collections::HashSet;
+use std::sync::Arc;
 
 pub(crate) const DEFAULT_MAX_ANCESTORS_COUNT: usize = 125;
 pub(crate) const MOCK_CYCLES: Cycle = 0;
 pub(crate) const MOCK_FEE: Capacity = Capacity::zero();
 pub(crate) const MOCK_SIZE: usize = 0;
 
+// A snapshot at the tip 10 of an empty store, proposing the given txs
+pub(crate) fn build_snapshot(db: &ChainDB, proposed: HashSet<ProposalShortId>) -> Arc<Snapshot> {
+    Arc::new(Snapshot::new(
+        HeaderBuilder::default().number(10.pack()).build(),
+        U256::zero(),
+        EpochExt::default(),
+        db.get_snapshot(),
+        ProposalView::new(HashSet::new(), proposed),
+        Arc::new(Consensus::default()),
+    ))
+}
+
 pub(crate) fn build_tx(inputs: Vec<(&Byte32, u32)>, outputs_len: usize) -> TransactionView {
     TransactionBuilder::default()
         .inputs(
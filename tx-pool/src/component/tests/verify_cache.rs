@@ -0,0 +1,96 @@
+use ckb_chain_spec::consensus::ConsensusBuilder;
+use ckb_types::{core::Capacity, h256, packed::Byte32, prelude::*};
+use ckb_verification::cache::{init_cache, CacheEntry};
+
+use crate::persisted::{
+    load_verify_cache, save_verify_cache, verify_cache_key, VERIFY_CACHE_VERSION,
+};
+
+fn entry(cycles: u64) -> CacheEntry {
+    CacheEntry {
+        cycles,
+        fee: Capacity::shannons(cycles * 2),
+    }
+}
+
+#[test]
+fn test_persisted_verify_cache() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let path = tmp_dir.path().join("persisted_verify_cache");
+    let consensus = ConsensusBuilder::default().build();
+    let key = verify_cache_key(&consensus, 0);
+
+    // nothing is persisted yet
+    assert!(load_verify_cache(&path, &key).unwrap().is_empty());
+
+    let hashes: Vec<Byte32> = vec![
+        h256!("0x1").pack(),
+        h256!("0x2").pack(),
+        h256!("0x3").pack(),
+    ];
+    let mut cache = init_cache();
+    for (i, hash) in hashes.iter().enumerate() {
+        cache.put(hash.clone(), entry(i as u64 + 1));
+    }
+    // the first one becomes the most recently used
+    cache.get(&hashes[0]);
+    save_verify_cache(&path, &key, &cache).unwrap();
+
+    let loaded = load_verify_cache(&path, &key).unwrap();
+    assert_eq!(
+        loaded,
+        vec![
+            (hashes[1].clone(), entry(2)),
+            (hashes[2].clone(), entry(3)),
+            (hashes[0].clone(), entry(1)),
+        ]
+    );
+
+    // the cache persisted by another chain is discarded
+    let other = ConsensusBuilder::default()
+        .genesis_block(
+            consensus
+                .genesis_block()
+                .as_advanced_builder()
+                .timestamp(1u64.pack())
+                .build(),
+        )
+        .build();
+    let other_key = verify_cache_key(&other, 0);
+    assert_ne!(key, other_key);
+    assert!(load_verify_cache(&path, &other_key).unwrap().is_empty());
+}
+
+#[test]
+fn test_broken_persisted_verify_cache() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let path = tmp_dir.path().join("persisted_verify_cache");
+    let key = verify_cache_key(&ConsensusBuilder::default().build(), 0);
+    let mut cache = init_cache();
+    cache.put(h256!("0x1").pack(), entry(1));
+    save_verify_cache(&path, &key, &cache).unwrap();
+
+    let mut file = path.clone();
+    file.set_extension(format!("v{VERIFY_CACHE_VERSION}"));
+    let saved = std::fs::read(&file).unwrap();
+    assert_eq!(load_verify_cache(&path, &key).unwrap().len(), 1);
+
+    // a trailing garbage byte
+    let mut data = saved.clone();
+    data.push(0);
+    std::fs::write(&file, data).unwrap();
+    assert!(load_verify_cache(&path, &key).is_err());
+
+    // the cycles of the entry are corrupted, the length is still valid
+    let mut data = saved.clone();
+    data[32 + 32] ^= 1;
+    std::fs::write(&file, data).unwrap();
+    assert!(load_verify_cache(&path, &key).is_err());
+
+    // the checksum is corrupted
+    let mut data = saved;
+    let last = data.len() - 1;
+    data[last] ^= 1;
+    std::fs::write(&file, data).unwrap();
+    assert!(load_verify_cache(&path, &key).is_err());
+}
@@ -0,0 +1,96 @@
+use ckb_types::{bytes::Bytes, core::TransactionView, h256, prelude::*};
+
+use crate::component::pool_map::{PoolMap, Status};
+use crate::component::tests::util::{
+    build_tx, DEFAULT_MAX_ANCESTORS_COUNT, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE,
+};
+use crate::component::witness_store::WitnessStore;
+use crate::TxEntry;
+
+fn with_witness(tx: TransactionView, witness_size: usize) -> TransactionView {
+    tx.as_advanced_builder()
+        .witness(Bytes::from(vec![0u8; witness_size]).pack())
+        .build()
+}
+
+#[test]
+fn test_strip_and_restore() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let mut store = WitnessStore::new(tmp_dir.path().join("witness_store"), 100).unwrap();
+
+    let large = with_witness(build_tx(vec![(&h256!("0x1").pack(), 0)], 1), 200);
+    let mut entry = TxEntry::dummy_resolve(large.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+    store.strip(&mut entry);
+    assert_eq!(store.len(), 1);
+
+    // the stripped tx keeps the hashes, the inputs and the outputs
+    let stripped = entry.transaction();
+    assert!(stripped.witnesses().is_empty());
+    assert_eq!(stripped.hash(), large.hash());
+    assert_eq!(stripped.witness_hash(), large.witness_hash());
+    assert_eq!(stripped.proposal_short_id(), large.proposal_short_id());
+    assert_eq!(stripped.outputs(), large.outputs());
+
+    let restored = store.restore(&entry).unwrap();
+    assert_eq!(restored.transaction().data(), large.data());
+    assert_eq!(restored.transaction().witness_hash(), large.witness_hash());
+
+    store.remove(&large.hash());
+    assert_eq!(store.len(), 0);
+    assert_eq!(store.size(), 0);
+
+    // the small txs stay in memory
+    let small = with_witness(build_tx(vec![(&h256!("0x2").pack(), 0)], 1), 10);
+    let mut entry = TxEntry::dummy_resolve(small.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+    store.strip(&mut entry);
+    assert_eq!(store.len(), 0);
+    assert_eq!(entry.transaction().data(), small.data());
+}
+
+#[test]
+fn test_pool_map_with_witness_store() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+    let mut pool = PoolMap::new(DEFAULT_MAX_ANCESTORS_COUNT);
+    pool.witness_store =
+        Some(WitnessStore::new(tmp_dir.path().join("witness_store"), 100).unwrap());
+
+    let parent = with_witness(build_tx(vec![(&h256!("0x1").pack(), 0)], 1), 200);
+    let child = with_witness(build_tx(vec![(&parent.hash(), 0)], 1), 200);
+    for tx in [&parent, &child] {
+        let entry = TxEntry::dummy_resolve(tx.clone(), MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+        assert!(pool.add_entry(entry, Status::Pending).unwrap().0);
+    }
+    assert_eq!(pool.calc_descendants(&parent.proposal_short_id()).len(), 1);
+
+    let entry = pool.get(&parent.proposal_short_id()).unwrap();
+    assert!(entry.transaction().witnesses().is_empty());
+    assert_eq!(pool.full_transaction(entry).unwrap().data(), parent.data());
+
+    // the removed entries are restored, and their witnesses are dropped from the store
+    let removed = pool.remove_entry_and_descendants(&parent.proposal_short_id());
+    let removed: Vec<_> = removed.iter().map(|e| e.transaction().data()).collect();
+    assert_eq!(removed, vec![parent.data(), child.data()]);
+    assert_eq!(pool.witness_store.as_ref().unwrap().len(), 0);
+}
+
+#[test]
+fn test_only_clear_the_store_directory() {
+    let tmp_dir = tempfile::Builder::new().tempdir().unwrap();
+
+    // a directory with other data is refused and left as it is
+    let other = tmp_dir.path().join("other");
+    std::fs::create_dir_all(&other).unwrap();
+    std::fs::write(other.join("data"), b"data").unwrap();
+    assert!(WitnessStore::new(&other, 100).is_err());
+    assert!(other.join("data").exists());
+
+    // the directory created by the store is cleared on the next start
+    let path = tmp_dir.path().join("witness_store");
+    let mut store = WitnessStore::new(&path, 100).unwrap();
+    let large = with_witness(build_tx(vec![(&h256!("0x1").pack(), 0)], 1), 200);
+    let mut entry = TxEntry::dummy_resolve(large, MOCK_CYCLES, MOCK_FEE, MOCK_SIZE);
+    store.strip(&mut entry);
+    drop(store);
+    let store = WitnessStore::new(&path, 100).unwrap();
+    assert_eq!(store.len(), 0);
+}
@@ -6,17 +6,26 @@ use ckb_logger::error;
 use ckb_network::PeerIndex;
 use ckb_systemtime::unix_time_as_millis;
 use ckb_types::{
-    core::{tx_pool::Reject, Cycle, TransactionView},
-    packed::ProposalShortId,
+    core::{tx_pool::Reject, Cycle, FeeRate, TransactionView},
+    packed::{Byte32, OutPoint, ProposalShortId},
 };
 use ckb_util::shrink_to_fit;
 use multi_index_map::MultiIndexMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Notify;
 
-// 256mb for total_tx_size limit, default max_tx_pool_size is 180mb
+// 256mb for total_tx_size limit if not configured, default max_tx_pool_size is 180mb
 const DEFAULT_MAX_VERIFY_QUEUE_TX_SIZE: usize = 256_000_000;
 const SHRINK_THRESHOLD: usize = 100;
+// the local and the relayed txs share the verification equally if not configured
+const DEFAULT_SOURCE_WEIGHT: u64 = 1;
+// 5 seconds for max_wait if not configured
+const DEFAULT_MAX_WAIT_MS: u64 = 5_000;
+
+/// The clock of the queue in milliseconds, the system time if not mocked.
+pub(crate) type Clock = Arc<dyn Fn() -> u64 + Send + Sync>;
 
 /// The verify queue Entry to verify.
 #[derive(Debug, Clone, Eq)]
@@ -31,25 +40,68 @@ impl PartialEq for Entry {
     }
 }
 
+/// The verification order of a tx, the lesser is verified first.
+///
+/// The local txs go before the relayed ones, then the higher fee rate ones, and the earlier added
+/// ones at last. Without the prioritization, all the txs are treated as relayed with the fee rate
+/// 0, so they are verified in the order they are added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Priority {
+    local: bool,
+    fee_rate: FeeRate,
+    added_time: u64,
+}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .local
+            .cmp(&self.local)
+            .then_with(|| other.fee_rate.cmp(&self.fee_rate))
+            .then_with(|| self.added_time.cmp(&other.added_time))
+    }
+}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[derive(MultiIndexMap, Clone)]
 struct VerifyEntry {
     /// The transaction id
     #[multi_index(hashed_unique)]
     id: ProposalShortId,
     /// The unix timestamp when entering the Txpool, unit: Millisecond
-    /// This field is used to sort the txs in the queue
-    /// We may add more other sort keys in the future
     #[multi_index(ordered_non_unique)]
     added_time: u64,
+    /// This field is used to sort the txs in the queue
+    #[multi_index(ordered_non_unique)]
+    priority: Priority,
 
     /// whether the tx is a large cycle tx
     #[multi_index(hashed_non_unique)]
     is_large_cycle: bool,
+    /// the order the tx is added, breaks the ties of the same added time
+    seq: u64,
 
     /// other sort key
     inner: Entry,
 }
 
+/// The queued txs of a source and a cycle class, ordered so the scheduling reads the first ones
+/// without walking the queue.
+#[derive(Default)]
+struct SourceIndex {
+    by_added_time: BTreeMap<(u64, u64), ProposalShortId>,
+    by_priority: BTreeMap<(Priority, u64), ProposalShortId>,
+}
+
+fn source_index(local: bool, is_large_cycle: bool) -> usize {
+    usize::from(local) << 1 | usize::from(is_large_cycle)
+}
+
 /// The verify queue is a priority queue of transactions to verify.
 pub(crate) struct VerifyQueue {
     /// inner tx entry
@@ -58,8 +110,34 @@ pub(crate) struct VerifyQueue {
     ready_rx: Arc<Notify>,
     /// total tx size in the queue, will reject new transaction if exceed the limit
     total_tx_size: usize,
+    /// total declared cycles of the remote txs in the queue
+    total_cycles: Cycle,
     /// large cycle threshold, from `pool_config.max_tx_verify_cycles`
     large_cycle_threshold: u64,
+    /// limit of `total_tx_size`, from `pool_config.max_verify_queue_size`
+    max_tx_size: usize,
+    /// limit of `total_cycles`, from `pool_config.max_verify_queue_cycles`
+    max_cycles: Option<Cycle>,
+    /// share of the local txs when both sources are waiting, from
+    /// `pool_config.verify_queue_local_weight`
+    local_weight: u64,
+    /// share of the relayed txs when both sources are waiting, from
+    /// `pool_config.verify_queue_remote_weight`
+    remote_weight: u64,
+    /// the oldest tx waited longer than this is popped regardless of the weights, from
+    /// `pool_config.verify_queue_max_wait_ms`
+    max_wait_ms: u64,
+    /// whether the txs are verified by the priority, from `pool_config.verify_queue_prioritize`
+    prioritize: bool,
+    /// count of the local txs popped since both sources are waiting
+    served_local: u64,
+    /// count of the relayed txs popped since both sources are waiting
+    served_remote: u64,
+    /// the txs by the source and the cycle class, see `source_index`
+    sources: [SourceIndex; 4],
+    /// the `seq` of the next added tx
+    next_seq: u64,
+    clock: Clock,
 }
 
 impl VerifyQueue {
@@ -69,10 +147,59 @@ impl VerifyQueue {
             inner: MultiIndexVerifyEntryMap::default(),
             ready_rx: Arc::new(Notify::new()),
             total_tx_size: 0,
+            total_cycles: 0,
             large_cycle_threshold,
+            max_tx_size: DEFAULT_MAX_VERIFY_QUEUE_TX_SIZE,
+            max_cycles: None,
+            local_weight: DEFAULT_SOURCE_WEIGHT,
+            remote_weight: DEFAULT_SOURCE_WEIGHT,
+            max_wait_ms: DEFAULT_MAX_WAIT_MS,
+            prioritize: false,
+            served_local: 0,
+            served_remote: 0,
+            sources: Default::default(),
+            next_seq: 0,
+            clock: Arc::new(unix_time_as_millis),
         }
     }
 
+    /// Sets the limits of the total tx size and the total declared cycles in the queue
+    pub(crate) fn with_limits(mut self, max_tx_size: usize, max_cycles: Option<Cycle>) -> Self {
+        self.max_tx_size = max_tx_size;
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    /// Sets the weighted fair scheduling between the local and the relayed txs.
+    ///
+    /// When both sources have txs waiting, they are popped in the ratio of the weights, and the
+    /// oldest tx is popped first once it has waited `max_wait_ms`, so neither source starves.
+    /// The weights are at least 1.
+    pub(crate) fn with_fairness(
+        mut self,
+        local_weight: u32,
+        remote_weight: u32,
+        max_wait_ms: u64,
+    ) -> Self {
+        self.local_weight = u64::from(local_weight.max(1));
+        self.remote_weight = u64::from(remote_weight.max(1));
+        self.max_wait_ms = max_wait_ms;
+        self
+    }
+
+    /// Sets whether the txs are verified by the priority, see `Priority`.
+    pub(crate) fn with_priority(mut self, prioritize: bool) -> Self {
+        self.prioritize = prioritize;
+        self
+    }
+
+    /// Replaces the system time with a mock clock
+    #[cfg(test)]
+    pub(crate) fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Returns true if the queue contains no txs.
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
@@ -82,14 +209,42 @@ impl VerifyQueue {
         self.inner.len()
     }
 
-    #[cfg(test)]
+    /// Total serialized size of the txs in the queue
     pub fn total_tx_size(&self) -> usize {
         self.total_tx_size
     }
 
-    /// Returns true if the queue is full.
-    pub fn is_full(&self, add_tx_size: usize) -> bool {
-        add_tx_size >= DEFAULT_MAX_VERIFY_QUEUE_TX_SIZE - self.total_tx_size
+    /// Total declared cycles of the remote txs in the queue, the local txs have no declared
+    /// cycles and are not counted
+    pub fn total_cycles(&self) -> Cycle {
+        self.total_cycles
+    }
+
+    /// Returns the reason if the queue has no room for a tx of the size and the declared cycles.
+    pub fn full_reason(&self, add_tx_size: usize, add_cycles: Cycle) -> Option<String> {
+        if self.total_tx_size.saturating_add(add_tx_size) > self.max_tx_size {
+            return Some(format!(
+                "verify_queue total_tx_size exceeded, {} bytes of {} txs in the queue, \
+                 adding {} bytes exceeds the limit {}",
+                self.total_tx_size,
+                self.len(),
+                add_tx_size,
+                self.max_tx_size
+            ));
+        }
+        match self.max_cycles {
+            Some(max_cycles) if self.total_cycles.saturating_add(add_cycles) > max_cycles => {
+                Some(format!(
+                    "verify_queue total_cycles exceeded, {} cycles of {} txs in the queue, \
+                     adding {} cycles exceeds the limit {}",
+                    self.total_cycles,
+                    self.len(),
+                    add_cycles,
+                    max_cycles
+                ))
+            }
+            _ => None,
+        }
     }
 
     /// Returns true if the queue contains a tx with the specified id.
@@ -97,6 +252,22 @@ impl VerifyQueue {
         self.inner.get_by_id(id).is_some()
     }
 
+    /// Returns the position of the tx in the queue by the priority, starting from 0
+    pub fn position(&self, id: &ProposalShortId) -> Option<usize> {
+        self.inner.iter_by_priority().position(|e| &e.id == id)
+    }
+
+    /// Returns the hashes of the other txs in the queue which spend the same inputs as `tx`
+    pub fn find_conflicts(&self, tx: &TransactionView) -> Vec<Byte32> {
+        let id = tx.proposal_short_id();
+        let inputs: HashSet<OutPoint> = tx.input_pts_iter().collect();
+        self.inner
+            .iter_by_added_time()
+            .filter(|e| e.id != id && e.inner.tx.input_pts_iter().any(|pt| inputs.contains(&pt)))
+            .map(|e| e.inner.tx.hash())
+            .collect()
+    }
+
     /// Shrink the capacity of the queue as much as possible.
     pub fn shrink_to_fit(&mut self) {
         shrink_to_fit!(self.inner, SHRINK_THRESHOLD);
@@ -110,6 +281,10 @@ impl VerifyQueue {
     /// Remove a tx from the queue
     pub fn remove_tx(&mut self, id: &ProposalShortId) -> Option<Entry> {
         self.inner.remove_by_id(id).map(|e| {
+            let source =
+                &mut self.sources[source_index(e.inner.remote.is_none(), e.is_large_cycle)];
+            source.by_added_time.remove(&(e.added_time, e.seq));
+            source.by_priority.remove(&(e.priority, e.seq));
             let tx_size = e.inner.tx.data().serialized_size_in_block();
             self.total_tx_size = self.total_tx_size.checked_sub(tx_size).unwrap_or_else(|| {
                 error!(
@@ -118,7 +293,16 @@ impl VerifyQueue {
                 );
                 0
             });
+            let cycles = declared_cycles(&e.inner.remote);
+            self.total_cycles = self.total_cycles.checked_sub(cycles).unwrap_or_else(|| {
+                error!(
+                    "verify_queue total_cycles {} overflown by sub {}",
+                    self.total_cycles, cycles
+                );
+                0
+            });
             self.shrink_to_fit();
+            self.update_metrics();
             e.inner
         })
     }
@@ -130,23 +314,94 @@ impl VerifyQueue {
         }
     }
 
-    /// Returns the first entry in the queue and remove it
+    /// Returns the next entry to verify and remove it
     pub fn pop_front(&mut self, only_small_cycle: bool) -> Option<Entry> {
-        if let Some(short_id) = self.peek(only_small_cycle) {
-            self.remove_tx(&short_id)
+        let (short_id, local, contended) = self.schedule(only_small_cycle)?;
+        if !contended {
+            self.served_local = 0;
+            self.served_remote = 0;
+        } else if local {
+            self.served_local = self.served_local.saturating_add(1);
         } else {
-            None
+            self.served_remote = self.served_remote.saturating_add(1);
         }
+        self.remove_tx(&short_id)
     }
 
-    /// Returns the first entry in the queue
+    /// Returns the next entry to verify
     pub fn peek(&self, only_small_cycle: bool) -> Option<ProposalShortId> {
-        if only_small_cycle {
-            self.inner.iter_by_added_time().find(|e| !e.is_large_cycle)
-        } else {
-            self.inner.iter_by_added_time().next()
+        self.schedule(only_small_cycle)
+            .map(|(short_id, _, _)| short_id)
+    }
+
+    /// Picks the first tx by the priority of the source behind its share, returns the tx id,
+    /// whether it is local, and whether the other source has txs waiting as well.
+    fn schedule(&self, only_small_cycle: bool) -> Option<(ProposalShortId, bool, bool)> {
+        let local_oldest = self.oldest(true, only_small_cycle);
+        let remote_oldest = self.oldest(false, only_small_cycle);
+        let contended = local_oldest.is_some() && remote_oldest.is_some();
+        // the oldest tx waited too long is popped regardless of the weights and the priority
+        let ((added_time, _), oldest, oldest_local) = match (local_oldest, remote_oldest) {
+            (Some(local), Some(remote)) if remote.0 < local.0 => (remote.0, remote.1, false),
+            (Some(local), _) => (local.0, local.1, true),
+            (None, Some(remote)) => (remote.0, remote.1, false),
+            (None, None) => return None,
+        };
+        if (self.clock)().saturating_sub(*added_time) >= self.max_wait_ms {
+            return Some((oldest.clone(), oldest_local, contended));
         }
-        .map(|entry| entry.inner.tx.proposal_short_id())
+        if !contended {
+            let (_, first) = self.first_by_priority(oldest_local, only_small_cycle)?;
+            return Some((first.clone(), oldest_local, false));
+        }
+
+        let (local_key, local_first) = self.first_by_priority(true, only_small_cycle)?;
+        let (remote_key, remote_first) = self.first_by_priority(false, only_small_cycle)?;
+        let local_share = self.served_local.saturating_mul(self.remote_weight);
+        let remote_share = self.served_remote.saturating_mul(self.local_weight);
+        let pick_local = match local_share.cmp(&remote_share) {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            Ordering::Equal => local_key < remote_key,
+        };
+        let picked = if pick_local {
+            local_first
+        } else {
+            remote_first
+        };
+        Some((picked.clone(), pick_local, true))
+    }
+
+    // the indexes of the source, without the large cycle txs if `only_small_cycle`
+    fn source_indexes(
+        &self,
+        local: bool,
+        only_small_cycle: bool,
+    ) -> impl Iterator<Item = &SourceIndex> {
+        let large = (!only_small_cycle).then(|| &self.sources[source_index(local, true)]);
+        std::iter::once(&self.sources[source_index(local, false)]).chain(large)
+    }
+
+    // the earliest added tx of the source
+    fn oldest(
+        &self,
+        local: bool,
+        only_small_cycle: bool,
+    ) -> Option<(&(u64, u64), &ProposalShortId)> {
+        self.source_indexes(local, only_small_cycle)
+            .filter_map(|index| index.by_added_time.first_key_value())
+            .min_by(|a, b| a.0.cmp(b.0))
+    }
+
+    // the first tx of the source by the priority
+    fn first_by_priority(
+        &self,
+        local: bool,
+        only_small_cycle: bool,
+    ) -> Option<(&(Priority, u64), &ProposalShortId)> {
+        self.source_indexes(local, only_small_cycle)
+            .filter_map(|index| index.by_priority.first_key_value())
+            .min_by(|a, b| a.0.cmp(b.0))
     }
 
     /// If the queue did not have this tx present, true is returned.
@@ -155,25 +410,57 @@ impl VerifyQueue {
         &mut self,
         tx: TransactionView,
         remote: Option<(Cycle, PeerIndex)>,
+    ) -> Result<bool, Reject> {
+        self.add_tx_with_fee_rate(tx, remote, None)
+    }
+
+    /// Adds a tx like `add_tx`, the fee rate is used to prioritize the tx if enabled, the tx
+    /// with an unknown fee rate has the lowest priority among the txs of its source.
+    pub fn add_tx_with_fee_rate(
+        &mut self,
+        tx: TransactionView,
+        remote: Option<(Cycle, PeerIndex)>,
+        fee_rate: Option<FeeRate>,
     ) -> Result<bool, Reject> {
         if self.contains_key(&tx.proposal_short_id()) {
             return Ok(false);
         }
         let tx_size = tx.data().serialized_size_in_block();
-        let is_large_cycle = remote
-            .map(|(cycles, _)| cycles > self.large_cycle_threshold)
-            .unwrap_or(false);
-        if self.is_full(tx_size) {
+        let cycles = declared_cycles(&remote);
+        let is_large_cycle = cycles > self.large_cycle_threshold;
+        if let Some(reason) = self.full_reason(tx_size, cycles) {
             return Err(Reject::Full(format!(
-                "verify_queue total_tx_size exceeded, failed to add tx: {:#x}",
+                "{reason}, failed to add tx: {:#x}",
                 tx.hash()
             )));
         }
+        let added_time = (self.clock)();
+        let priority = if self.prioritize {
+            Priority {
+                local: remote.is_none(),
+                fee_rate: fee_rate.unwrap_or_default(),
+                added_time,
+            }
+        } else {
+            Priority {
+                local: false,
+                fee_rate: FeeRate::zero(),
+                added_time,
+            }
+        };
+        let id = tx.proposal_short_id();
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        let source = &mut self.sources[source_index(remote.is_none(), is_large_cycle)];
+        source.by_added_time.insert((added_time, seq), id.clone());
+        source.by_priority.insert((priority, seq), id.clone());
         self.inner.insert(VerifyEntry {
-            id: tx.proposal_short_id(),
-            added_time: unix_time_as_millis(),
+            id,
+            added_time,
+            priority,
             inner: Entry { tx, remote },
             is_large_cycle,
+            seq,
         });
         self.total_tx_size = self.total_tx_size.checked_add(tx_size).unwrap_or_else(|| {
             error!(
@@ -182,6 +469,8 @@ impl VerifyQueue {
             );
             self.total_tx_size
         });
+        self.total_cycles = self.total_cycles.saturating_add(cycles);
+        self.update_metrics();
         self.ready_rx.notify_one();
         Ok(true)
     }
@@ -194,7 +483,33 @@ impl VerifyQueue {
     /// Clears the map, removing all elements.
     pub fn clear(&mut self) {
         self.inner.clear();
+        self.sources = Default::default();
         self.total_tx_size = 0;
+        self.total_cycles = 0;
+        self.served_local = 0;
+        self.served_remote = 0;
         self.shrink_to_fit();
+        self.update_metrics();
     }
+
+    fn update_metrics(&self) {
+        if let Some(metrics) = ckb_metrics::handle() {
+            metrics
+                .ckb_tx_pool_verify_queue
+                .count
+                .set(self.len() as i64);
+            metrics
+                .ckb_tx_pool_verify_queue
+                .size
+                .set(self.total_tx_size as i64);
+            metrics
+                .ckb_tx_pool_verify_queue
+                .cycles
+                .set(self.total_cycles as i64);
+        }
+    }
+}
+
+fn declared_cycles(remote: &Option<(Cycle, PeerIndex)>) -> Cycle {
+    remote.map(|(cycles, _)| cycles).unwrap_or(0)
 }
@@ -0,0 +1,173 @@
+use crate::component::entry::TxEntry;
+use ckb_db::RocksDB;
+use ckb_error::{AnyError, OtherError};
+use ckb_logger::error;
+use ckb_types::{
+    core::TransactionView,
+    packed::{Byte32, BytesVec, BytesVecReader},
+    prelude::*,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+// The file marking a directory created by the store, which is safe to clear on the next start
+const WITNESS_STORE_MARKER: &str = "WITNESS_STORE";
+
+/// The side store of the witnesses of the large txs in the pool.
+///
+/// The entries of these txs keep a stripped transaction in memory, which has the same hashes,
+/// inputs, outputs and outputs data as the full one, that is all the pool needs to sort, resolve
+/// and check conflicts. The witnesses are only fetched when the full transaction leaves the pool,
+/// e.g. relayed, packaged into a block template or persisted.
+pub(crate) struct WitnessStore {
+    threshold: usize,
+    db: RocksDB,
+    // the witnesses size of the stripped txs
+    stored: HashMap<Byte32, usize>,
+    size: usize,
+}
+
+impl WitnessStore {
+    /// Opens the store, the witnesses left by the last run are discarded since the pool is
+    /// persisted with the full transactions.
+    ///
+    /// Only a directory created by the store is cleared, a non-empty directory without the marker
+    /// file is likely misconfigured to other data and is refused.
+    pub(crate) fn new<P: AsRef<Path>>(path: P, threshold: usize) -> Result<Self, AnyError> {
+        let path = path.as_ref();
+        if path.exists() {
+            if !path.join(WITNESS_STORE_MARKER).exists() && path.read_dir()?.next().is_some() {
+                return Err(OtherError::new(format!(
+                    "the witness store directory {} is not empty and was not created by the store",
+                    path.display()
+                ))
+                .into());
+            }
+            std::fs::remove_dir_all(path)?;
+        }
+        std::fs::create_dir_all(path)?;
+        std::fs::write(path.join(WITNESS_STORE_MARKER), [])?;
+        Ok(WitnessStore {
+            threshold,
+            db: RocksDB::try_open_in(path, 0)?,
+            stored: HashMap::new(),
+            size: 0,
+        })
+    }
+
+    /// Moves the witnesses of a large tx into the store, the entry keeps the stripped transaction.
+    pub(crate) fn strip(&mut self, entry: &mut TxEntry) {
+        let tx = entry.transaction();
+        let witnesses = tx.witnesses();
+        let witnesses_size = witnesses.total_size();
+        if witnesses_size < self.threshold || self.stored.contains_key(&tx.hash()) {
+            return;
+        }
+        let hash = tx.hash();
+        if let Err(err) = self.db.put_default(hash.as_slice(), witnesses.as_slice()) {
+            error!("Failed to store the witnesses of tx {}: {}", hash, err);
+            return;
+        }
+        let stripped = tx
+            .data()
+            .as_builder()
+            .witnesses(BytesVec::default())
+            .build()
+            .into_view()
+            .fake_hash(hash.clone())
+            .fake_witness_hash(tx.witness_hash());
+        let mut rtx = entry.rtx.as_ref().clone();
+        rtx.transaction = stripped;
+        entry.rtx = Arc::new(rtx);
+
+        self.stored.insert(hash, witnesses_size);
+        self.size += witnesses_size;
+        self.update_metrics();
+    }
+
+    /// Returns the entry with the full transaction.
+    pub(crate) fn restore(&self, entry: &TxEntry) -> Result<TxEntry, AnyError> {
+        let mut entry = entry.clone();
+        if self.stored.contains_key(&entry.transaction().hash()) {
+            let mut rtx = entry.rtx.as_ref().clone();
+            rtx.transaction = self.restore_transaction(&rtx.transaction)?;
+            entry.rtx = Arc::new(rtx);
+        }
+        Ok(entry)
+    }
+
+    /// Returns the full transaction.
+    pub(crate) fn restore_transaction(
+        &self,
+        tx: &TransactionView,
+    ) -> Result<TransactionView, AnyError> {
+        let hash = tx.hash();
+        if !self.stored.contains_key(&hash) {
+            return Ok(tx.clone());
+        }
+        let slice = self
+            .db
+            .get_pinned_default(hash.as_slice())?
+            .ok_or_else(|| {
+                OtherError::new(format!("the witnesses of tx {hash} are lost in the store"))
+            })?;
+        let witnesses = BytesVecReader::from_slice(&*slice)
+            .map_err(|err| {
+                OtherError::new(format!(
+                    "the witnesses of tx {hash} are broken in the store: {err}"
+                ))
+            })?
+            .to_entity();
+        Ok(tx
+            .data()
+            .as_builder()
+            .witnesses(witnesses)
+            .build()
+            .into_view())
+    }
+
+    /// Drops the witnesses of a tx removed from the pool.
+    pub(crate) fn remove(&mut self, hash: &Byte32) {
+        if let Some(witnesses_size) = self.stored.remove(hash) {
+            let mut batch = self.db.new_write_batch();
+            let ret = batch
+                .delete_default(hash.as_slice())
+                .and_then(|_| self.db.write(&batch));
+            if let Err(err) = ret {
+                error!("Failed to delete the witnesses of tx {}: {}", hash, err);
+            }
+            self.size -= witnesses_size;
+            self.update_metrics();
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let hashes: Vec<_> = self.stored.keys().cloned().collect();
+        for hash in hashes {
+            self.remove(&hash);
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.stored.len()
+    }
+
+    /// The total size of the witnesses in the store.
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    fn update_metrics(&self) {
+        if let Some(metrics) = ckb_metrics::handle() {
+            metrics
+                .ckb_tx_pool_lazy_witnesses
+                .count
+                .set(self.len() as i64);
+            metrics
+                .ckb_tx_pool_lazy_witnesses
+                .size
+                .set(self.size as i64);
+        }
+    }
+}
@@ -13,8 +13,10 @@ pub mod service;
 mod util;
 mod verify_mgr;
 
+pub use callback::PoolEventListener;
 pub use ckb_jsonrpc_types::BlockTemplate;
 pub use component::entry::TxEntry;
+pub use component::fee_estimator::MAX_TARGET_BLOCKS as MAX_FEE_ESTIMATE_TARGET_BLOCKS;
 pub use pool::TxPool;
 pub use process::PlugTarget;
 pub use service::{TxPoolController, TxPoolServiceBuilder};
@@ -1,18 +1,32 @@
 use crate::TxPool;
+use ckb_chain_spec::consensus::Consensus;
 use ckb_error::{AnyError, OtherError};
+use ckb_hash::{blake2b_256, new_blake2b};
+use ckb_script::ScriptVersion;
 use ckb_types::{
-    core::TransactionView,
-    packed::{TransactionVec, TransactionVecReader},
+    core::{Capacity, EpochNumber, TransactionView},
+    packed::{Byte32, TransactionVec, TransactionVecReader},
     prelude::*,
 };
+use ckb_verification::cache::{CacheEntry, TxVerificationCache};
 use std::{
     fs::OpenOptions,
     io::{Read as _, Write as _},
+    path::{Path, PathBuf},
 };
 
 /// The version of the persisted tx-pool data.
 pub(crate) const VERSION: u32 = 1;
 
+/// The version of the persisted verify cache.
+pub(crate) const VERIFY_CACHE_VERSION: u32 = 2;
+
+// The header is the key, each entry is the tx hash, the cycles and the fee, and the trailer is
+// the blake2b checksum of the header and the entries.
+const VERIFY_CACHE_HEADER_SIZE: usize = 32;
+const VERIFY_CACHE_ENTRY_SIZE: usize = 32 + 8 + 8;
+const VERIFY_CACHE_CHECKSUM_SIZE: usize = 32;
+
 impl TxPool {
     pub(crate) fn load_from_file(&self) -> Result<Vec<TransactionView>, AnyError> {
         let mut persisted_data_file = self.config.persisted_data.clone();
@@ -89,3 +103,131 @@ impl TxPool {
         Ok(())
     }
 }
+
+/// The key of the persisted verify cache.
+///
+/// The cached results are only valid for the same chain, the same verifier code and the same
+/// VM versions, so the cache persisted with a different key is discarded.
+pub(crate) fn verify_cache_key(consensus: &Consensus, epoch: EpochNumber) -> Byte32 {
+    let hardfork_switch = consensus.hardfork_switch();
+    let mut hasher = new_blake2b();
+    hasher.update(&VERIFY_CACHE_VERSION.to_le_bytes());
+    hasher.update(consensus.genesis_hash().as_slice());
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(&[
+        ScriptVersion::latest() as u8,
+        hardfork_switch
+            .ckb2021
+            .is_vm_version_1_and_syscalls_2_enabled(epoch) as u8,
+        hardfork_switch
+            .ckb2023
+            .is_vm_version_2_and_syscalls_3_enabled(epoch) as u8,
+    ]);
+    let mut key = [0u8; 32];
+    hasher.finalize(&mut key);
+    key.pack()
+}
+
+fn verify_cache_file(path: &Path) -> PathBuf {
+    let mut file = path.to_path_buf();
+    file.set_extension(format!("v{VERIFY_CACHE_VERSION}"));
+    file
+}
+
+/// Loads the persisted verify cache, from the least recently used entry to the most recently
+/// used one.
+///
+/// Returns nothing if the file doesn't exist or it was persisted with another key, and an error
+/// if the file is corrupt.
+pub(crate) fn load_verify_cache(
+    path: &Path,
+    key: &Byte32,
+) -> Result<Vec<(Byte32, CacheEntry)>, AnyError> {
+    let file_path = verify_cache_file(path);
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut buffer = Vec::new();
+    OpenOptions::new()
+        .read(true)
+        .open(&file_path)
+        .and_then(|mut file| file.read_to_end(&mut buffer))
+        .map_err(|err| {
+            let errmsg =
+                format!("Failed to read the verify cache file [{file_path:?}], cause: {err}");
+            OtherError::new(errmsg)
+        })?;
+    let fixed_size = VERIFY_CACHE_HEADER_SIZE + VERIFY_CACHE_CHECKSUM_SIZE;
+    if buffer.len() < fixed_size || (buffer.len() - fixed_size) % VERIFY_CACHE_ENTRY_SIZE != 0 {
+        let errmsg = format!(
+            "The verify cache file [{file_path:?}] is broken, length: {}",
+            buffer.len()
+        );
+        return Err(OtherError::new(errmsg).into());
+    }
+    let (data, checksum) = buffer.split_at(buffer.len() - VERIFY_CACHE_CHECKSUM_SIZE);
+    if blake2b_256(data) != checksum {
+        let errmsg = format!("The verify cache file [{file_path:?}] is broken, checksum mismatch");
+        return Err(OtherError::new(errmsg).into());
+    }
+    if &data[..VERIFY_CACHE_HEADER_SIZE] != key.as_slice() {
+        return Ok(Vec::new());
+    }
+
+    let u64_at = |bytes: &[u8]| {
+        let mut le = [0u8; 8];
+        le.copy_from_slice(bytes);
+        u64::from_le_bytes(le)
+    };
+    Ok(data[VERIFY_CACHE_HEADER_SIZE..]
+        .chunks_exact(VERIFY_CACHE_ENTRY_SIZE)
+        .map(|entry| {
+            let tx_hash = Byte32::from_slice(&entry[..32]).expect("checked length");
+            let cycles = u64_at(&entry[32..40]);
+            let fee = Capacity::shannons(u64_at(&entry[40..48]));
+            (tx_hash, CacheEntry { cycles, fee })
+        })
+        .collect())
+}
+
+/// Persists the verify cache, so the restarted node doesn't verify the same txs again.
+pub(crate) fn save_verify_cache(
+    path: &Path,
+    key: &Byte32,
+    cache: &TxVerificationCache,
+) -> Result<(), AnyError> {
+    let file_path = verify_cache_file(path);
+    let mut buffer = Vec::with_capacity(
+        VERIFY_CACHE_HEADER_SIZE
+            + cache.len() * VERIFY_CACHE_ENTRY_SIZE
+            + VERIFY_CACHE_CHECKSUM_SIZE,
+    );
+    buffer.extend_from_slice(key.as_slice());
+    // from the least recently used, so the recency is kept after loading
+    for (tx_hash, entry) in cache.iter().rev() {
+        buffer.extend_from_slice(tx_hash.as_slice());
+        buffer.extend_from_slice(&entry.cycles.to_le_bytes());
+        buffer.extend_from_slice(&entry.fee.as_u64().to_le_bytes());
+    }
+    let checksum = blake2b_256(&buffer);
+    buffer.extend_from_slice(&checksum);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&file_path)
+        .map_err(|err| {
+            let errmsg =
+                format!("Failed to open the verify cache file [{file_path:?}], cause: {err}");
+            OtherError::new(errmsg)
+        })?;
+    file.write_all(&buffer)
+        .and_then(|_| file.sync_all())
+        .map_err(|err| {
+            let errmsg =
+                format!("Failed to write the verify cache file [{file_path:?}], cause: {err}");
+            OtherError::new(errmsg)
+        })?;
+    Ok(())
+}
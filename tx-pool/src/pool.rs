@@ -3,32 +3,71 @@ extern crate rustc_hash;
 extern crate slab;
 use super::component::{commit_txs_scanner::CommitTxsScanner, TxEntry};
 use crate::callback::Callbacks;
+use crate::component::conflicts_pool::ConflictsPool;
+use crate::component::fee_estimator::FeeEstimator;
+use crate::component::lifecycle::TxLifecycleTracer;
 use crate::component::pool_map::{PoolEntry, PoolMap, Status};
+use crate::component::rbf_limiter::RbfLimiter;
 use crate::component::recent_reject::RecentReject;
+use crate::component::sort_key::AncestorsScoreSortKey;
+use crate::component::template_protection::TemplateProtection;
+use crate::component::witness_store::WitnessStore;
 use crate::error::Reject;
 use crate::pool_cell::PoolCell;
+use crate::util::{calculate_tx_fee, check_tx_fee};
 use ckb_app_config::TxPoolConfig;
 use ckb_logger::{debug, error, warn};
 use ckb_snapshot::Snapshot;
 use ckb_store::ChainStore;
-use ckb_types::core::tx_pool::PoolTxDetailInfo;
-use ckb_types::core::CapacityError;
-use ckb_types::packed::OutPoint;
+use ckb_types::core::tx_pool::{
+    get_transaction_weight, CellCapacityBreakdown, PoolPolicy, PoolTxDetailInfo, PoolTxGraph,
+    PoolTxGraphEdge, PoolTxGraphNode, PoolTxGraphRelation, ProposalWindowSimulation, RbfRule,
+    RbfRuleCheck, ReplacementSimulation, TxDiagnosis, TxDiagnosisIssue, TxDiagnosisIssueKind,
+    TxFeeBreakdown, TxLifecycleStage, TxsBacklog,
+};
+use ckb_types::core::{error::OutPointError, CapacityError};
+use ckb_types::packed::{CellOutput, OutPoint};
 use ckb_types::{
     core::{
-        cell::{resolve_transaction, OverlayCellChecker, OverlayCellProvider, ResolvedTransaction},
-        tx_pool::{TxPoolEntryInfo, TxPoolIds},
-        Capacity, Cycle, TransactionView, UncleBlockView,
+        cell::{
+            resolve_transaction, CellMeta, CellMetaBuilder, OverlayCellChecker,
+            OverlayCellProvider, ResolvedTransaction, TransactionsProvider,
+        },
+        tx_pool::{TxEntryInfo, TxPoolIds, TRANSACTION_SIZE_LIMIT},
+        BlockNumber, Capacity, Cycle, FeeRate, TransactionView, UncleBlockView,
     },
     packed::{Byte32, ProposalShortId},
+    prelude::*,
 };
 use lru::LruCache;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 
 const COMMITTED_HASH_CACHE_SIZE: usize = 100_000;
-const CONFLICTES_CACHE_SIZE: usize = 10_000;
-const MAX_REPLACEMENT_CANDIDATES: usize = 100;
+// A tx expiring within an hour is reported by `diagnose_tx`
+const EXPIRES_SOON_MS: u64 = 60 * 60 * 1000;
+
+// The RBF rules checked for a tx, with the pool txs it would replace
+struct RbfCheck<'a> {
+    conflict_ids: HashSet<ProposalShortId>,
+    // the conflicts and their descendants, may contain duplicates
+    all_conflicted: Vec<&'a PoolEntry>,
+    min_replace_fee: Option<Capacity>,
+    rules: Vec<(RbfRule, Result<(), Reject>)>,
+}
+
+impl RbfCheck<'_> {
+    // Records the result of a rule, returns true if the check stops here.
+    fn record(&mut self, rule: RbfRule, result: Result<(), Reject>, check_all: bool) -> bool {
+        let stop = result.is_err() && !check_all;
+        self.rules.push((rule, result));
+        stop
+    }
+}
+
+/// The cursor of the pages of the in-pool txs, see [`TxPool::ids_page`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScoreCursor(AncestorsScoreSortKey);
 
 /// Tx-pool implementation
 pub struct TxPool {
@@ -42,8 +81,20 @@ pub struct TxPool {
     pub recent_reject: Option<RecentReject>,
     // expiration milliseconds,
     pub(crate) expiry: u64,
-    // conflicted transaction cache
-    pub(crate) conflicts_cache: lru::LruCache<ProposalShortId, TransactionView>,
+    // conflicted transactions, bounded by count and size
+    pub(crate) conflicts_pool: ConflictsPool,
+    // recent RBF replacements of outpoints
+    pub(crate) rbf_limiter: RbfLimiter,
+    // virtual fee deltas set by `prioritise_transaction`, kept while the tx stays in the pool
+    pub(crate) fee_deltas: HashMap<ProposalShortId, u64>,
+    // txs of the recent block templates, protected from the eviction by size limit
+    pub(crate) template_protection: TemplateProtection,
+    // increases every time the policy is reloaded with changes
+    pub(crate) policy_version: u64,
+    // lifecycle events of the txs, shared with the tx-pool service
+    pub(crate) lifecycle: Arc<TxLifecycleTracer>,
+    // how many blocks the txs take to be committed by their fee rates
+    pub(crate) fee_estimator: FeeEstimator,
 }
 
 impl TxPool {
@@ -51,14 +102,27 @@ impl TxPool {
     pub fn new(config: TxPoolConfig, snapshot: Arc<Snapshot>) -> TxPool {
         let recent_reject = Self::build_recent_reject(&config);
         let expiry = config.expiry_hours as u64 * 60 * 60 * 1000;
+        let rbf_limiter = RbfLimiter::new(config.rbf_replacement_window_secs);
+        let template_protection = TemplateProtection::new(config.template_protection_secs);
+        let mut pool_map = PoolMap::new(config.max_ancestors_count);
+        pool_map.witness_store = Self::build_witness_store(&config);
         TxPool {
-            pool_map: PoolMap::new(config.max_ancestors_count),
+            pool_map,
             committed_txs_hash_cache: LruCache::new(COMMITTED_HASH_CACHE_SIZE),
             config,
-            snapshot,
             recent_reject,
             expiry,
-            conflicts_cache: LruCache::new(CONFLICTES_CACHE_SIZE),
+            conflicts_pool: ConflictsPool::new(
+                config.max_conflicts_count,
+                config.max_conflicts_size,
+            ),
+            rbf_limiter,
+            fee_deltas: HashMap::new(),
+            template_protection,
+            policy_version: 0,
+            lifecycle: Arc::new(TxLifecycleTracer::new(0)),
+            fee_estimator: FeeEstimator::new(snapshot.tip_number()),
+            snapshot,
         }
     }
 
@@ -72,6 +136,58 @@ impl TxPool {
         Arc::clone(&self.snapshot)
     }
 
+    /// The effective relay and admission policy
+    pub fn policy(&self) -> PoolPolicy {
+        PoolPolicy {
+            version: self.policy_version,
+            min_fee_rate: self.config.min_fee_rate,
+            min_rbf_rate: self.config.min_rbf_rate,
+            max_ancestors_count: self.pool_map.max_ancestors_count,
+            max_tx_pool_size: self.config.max_tx_pool_size as u64,
+            tx_size_limit: TRANSACTION_SIZE_LIMIT,
+            max_tx_verify_cycles: self.config.max_tx_verify_cycles,
+            max_script_group_cycles: self.config.max_script_group_cycles,
+            rbf_replacement_window_secs: self.config.rbf_replacement_window_secs,
+            rbf_free_replacements: self.config.rbf_free_replacements,
+            max_rbf_replacements: self.config.max_rbf_replacements,
+            max_rbf_candidates: self.config.max_rbf_candidates,
+            max_rbf_conflict_descendants: self.config.max_rbf_conflict_descendants,
+            rbf_replace_gap: self.config.rbf_replace_gap,
+            rbf_replace_proposed: self.config.rbf_replace_proposed,
+            rbf_replaced_fees_cap: self.config.rbf_replaced_fees_cap,
+            max_absolute_fee: self.config.max_absolute_fee,
+            max_fee_rate: self.config.max_fee_rate,
+            min_header_dep_depth: self.config.min_header_dep_depth,
+        }
+    }
+
+    /// Applies the hot-reloadable policy options in `config`, the version increases if the policy
+    /// changes.
+    ///
+    /// The other options, such as the cycles limits, only take effect after restart.
+    pub(crate) fn reload_policy(&mut self, config: &TxPoolConfig) -> PoolPolicy {
+        let old_policy = self.policy();
+        self.config.min_fee_rate = config.min_fee_rate;
+        self.config.min_rbf_rate = config.min_rbf_rate;
+        self.config.max_ancestors_count = config.max_ancestors_count;
+        self.pool_map.max_ancestors_count = config.max_ancestors_count;
+        self.config.max_tx_pool_size = config.max_tx_pool_size;
+        self.config.rbf_free_replacements = config.rbf_free_replacements;
+        self.config.max_rbf_replacements = config.max_rbf_replacements;
+        self.config.max_rbf_candidates = config.max_rbf_candidates;
+        self.config.max_rbf_conflict_descendants = config.max_rbf_conflict_descendants;
+        self.config.rbf_replace_gap = config.rbf_replace_gap;
+        self.config.rbf_replace_proposed = config.rbf_replace_proposed;
+        self.config.rbf_replaced_fees_cap = config.rbf_replaced_fees_cap;
+        self.config.max_absolute_fee = config.max_absolute_fee;
+        self.config.max_fee_rate = config.max_fee_rate;
+        self.config.min_header_dep_depth = config.min_header_dep_depth;
+        if self.policy() != old_policy {
+            self.policy_version += 1;
+        }
+        self.policy()
+    }
+
     /// Check whether tx-pool enable RBF
     pub fn enable_rbf(&self) -> bool {
         self.config.min_rbf_rate > self.config.min_fee_rate
@@ -90,12 +206,96 @@ impl TxPool {
             .filter_map(|id| self.get_pool_entry(id))
             .collect::<Vec<_>>();
         conflicts.extend(descendants);
-        self.calculate_min_replace_fee(&conflicts, tx.size)
+        let replacements = self.rbf_replacements_count(tx.transaction());
+        self.calculate_min_replace_fee(&conflicts, tx.size, replacements)
+    }
+
+    /// The max count of recent RBF replacements among the inputs of the tx
+    fn rbf_replacements_count(&self, tx: &TransactionView) -> usize {
+        let inputs: Vec<OutPoint> = tx.input_pts_iter().collect();
+        self.rbf_limiter
+            .replacements_count(inputs.iter(), ckb_systemtime::unix_time_as_millis())
+    }
+
+    /// Whether the inputs of the tx have been replaced by RBF recently
+    pub(crate) fn is_recently_replaced(&self, tx: &TransactionView) -> bool {
+        self.rbf_replacements_count(tx) > 0
+    }
+
+    /// Record the inputs of the tx as replaced by RBF, along with the fees of the replaced txs
+    pub(crate) fn record_rbf_replacement(&mut self, tx: &TransactionView, replaced_fees: Capacity) {
+        self.rbf_limiter.record(
+            tx.input_pts_iter(),
+            replaced_fees,
+            ckb_systemtime::unix_time_as_millis(),
+        );
+    }
+
+    /// Total fees of the txs replaced by RBF within the replacement window
+    pub fn rbf_replaced_fees(&self) -> Capacity {
+        self.rbf_limiter
+            .replaced_fees(ckb_systemtime::unix_time_as_millis())
+    }
+
+    /// Record the txs of a new block template, returns whether their protection from the eviction
+    /// by size limit needs to be refreshed by `protect_template_txs`
+    ///
+    /// It only takes the read lock, so the write lock is taken only when the protection changes.
+    pub(crate) fn record_template_txs(&self, entries: &[TxEntry]) -> bool {
+        for entry in entries {
+            self.lifecycle.record(
+                &entry.transaction().hash(),
+                TxLifecycleStage::Packaged,
+                None,
+            );
+        }
+        self.template_protection.needs_refresh(
+            entries.iter().map(|entry| entry.proposal_short_id()),
+            ckb_systemtime::unix_time_as_millis(),
+        )
+    }
+
+    /// Protect the txs of a new block template from the eviction by size limit
+    pub(crate) fn protect_template_txs(&mut self, entries: &[TxEntry]) {
+        self.template_protection.protect(
+            entries.iter().map(|entry| entry.proposal_short_id()),
+            ckb_systemtime::unix_time_as_millis(),
+        );
     }
 
     /// min_replace_fee = sum(replaced_txs.fee) + extra_rbf_fee
-    fn calculate_min_replace_fee(&self, conflicts: &[&PoolEntry], size: usize) -> Option<Capacity> {
+    ///
+    /// extra_rbf_fee doubles for each replacement beyond `rbf_free_replacements` in the window,
+    /// and it's scaled by `replaced_fees / rbf_replaced_fees_cap` once the fees replaced in the
+    /// window exceed the cap
+    fn calculate_min_replace_fee(
+        &self,
+        conflicts: &[&PoolEntry],
+        size: usize,
+        replacements: usize,
+    ) -> Option<Capacity> {
+        let escalation = replacements.saturating_sub(self.config.rbf_free_replacements);
+        let multiplier = u32::try_from(escalation)
+            .ok()
+            .and_then(|shift| 1u64.checked_shl(shift))
+            .unwrap_or(u64::MAX);
         let extra_rbf_fee = self.config.min_rbf_rate.fee(size as u64);
+        let extra_rbf_fee = extra_rbf_fee
+            .safe_mul(multiplier)
+            .unwrap_or_else(|_| Capacity::shannons(u64::MAX));
+        let extra_rbf_fee = match self.config.rbf_replaced_fees_cap {
+            Some(cap) => {
+                let replaced = self.rbf_replaced_fees().as_u64();
+                if replaced > cap {
+                    let scaled = u128::from(extra_rbf_fee.as_u64()) * u128::from(replaced)
+                        / u128::from(cap.max(1));
+                    Capacity::shannons(u64::try_from(scaled).unwrap_or(u64::MAX))
+                } else {
+                    extra_rbf_fee
+                }
+            }
+            None => extra_rbf_fee,
+        };
         // don't account for duplicate txs
         let replaced_fees: HashMap<_, _> = conflicts
             .iter()
@@ -127,12 +327,12 @@ impl TxPool {
         &mut self,
         entry: TxEntry,
     ) -> Result<(bool, HashSet<TxEntry>), Reject> {
-        self.pool_map.add_entry(entry, Status::Pending)
+        self.add_entry(entry, Status::Pending)
     }
 
     /// Add tx which proposed but still uncommittable to gap
     pub(crate) fn add_gap(&mut self, entry: TxEntry) -> Result<(bool, HashSet<TxEntry>), Reject> {
-        self.pool_map.add_entry(entry, Status::Gap)
+        self.add_entry(entry, Status::Gap)
     }
 
     /// Add tx with proposed status
@@ -140,7 +340,71 @@ impl TxPool {
         &mut self,
         entry: TxEntry,
     ) -> Result<(bool, HashSet<TxEntry>), Reject> {
-        self.pool_map.add_entry(entry, Status::Proposed)
+        self.add_entry(entry, Status::Proposed)
+    }
+
+    fn add_entry(
+        &mut self,
+        entry: TxEntry,
+        status: Status,
+    ) -> Result<(bool, HashSet<TxEntry>), Reject> {
+        let entry = self.with_fee_delta(entry);
+        let (id, fee_rate) = (entry.proposal_short_id(), entry.fee_rate());
+        let ret = self.pool_map.add_entry(entry, status)?;
+        if ret.0 {
+            self.fee_estimator
+                .track(id, fee_rate, self.snapshot.tip_number());
+        }
+        Ok(ret)
+    }
+
+    /// Add tx which is proposed but exceeds max_ancestors_count to pending, it's retried at the
+    /// next proposal window
+    pub(crate) fn add_demoted(&mut self, entry: TxEntry) -> Result<bool, Reject> {
+        let entry = self.with_fee_delta(entry);
+        self.pool_map.add_demoted_entry(entry)
+    }
+
+    // Re-applies the fee delta to a tx which re-enters the pool, e.g., after a reorg
+    fn with_fee_delta(&self, mut entry: TxEntry) -> TxEntry {
+        if let Some(fee_delta) = self.fee_deltas.get(&entry.proposal_short_id()) {
+            entry.set_fee_delta(*fee_delta);
+        }
+        entry
+    }
+
+    /// Sets the virtual fee delta of an in-pool tx, which only affects how the tx is sorted and
+    /// packaged. Returns false if the tx is not in the pool.
+    pub(crate) fn prioritise_tx(&mut self, id: &ProposalShortId, fee_delta: u64) -> bool {
+        if !self.pool_map.set_fee_delta(id, fee_delta) {
+            return false;
+        }
+        if fee_delta == 0 {
+            self.fee_deltas.remove(id);
+        } else {
+            self.fee_deltas.insert(id.clone(), fee_delta);
+        }
+        self.prune_fee_deltas();
+        true
+    }
+
+    fn drop_fee_deltas<'a>(&mut self, entries: impl IntoIterator<Item = &'a TxEntry>) {
+        if self.fee_deltas.is_empty() {
+            return;
+        }
+        for entry in entries {
+            self.fee_deltas.remove(&entry.proposal_short_id());
+        }
+    }
+
+    // Drops the fee deltas of the txs no longer in the pool, e.g., the ones rejected for the
+    // conflicts, so the deltas are bounded by the pool size.
+    fn prune_fee_deltas(&mut self) {
+        if self.fee_deltas.len() > self.pool_map.size() {
+            let pool_map = &self.pool_map;
+            self.fee_deltas
+                .retain(|id, _| pool_map.get_by_id(id).is_some());
+        }
     }
 
     /// Returns true if the tx-pool contains a tx with specified id.
@@ -158,20 +422,25 @@ impl TxPool {
 
     pub(crate) fn record_conflict(&mut self, tx: TransactionView) {
         let short_id = tx.proposal_short_id();
-        self.conflicts_cache.put(short_id.clone(), tx);
+        let evicted = self.conflicts_pool.put(tx);
+        self.pool_map.bump_version();
         debug!(
-            "record_conflict {:?} now cache size: {}",
+            "record_conflict {:?} now conflicts count: {} size: {} evicted: {}",
             short_id,
-            self.conflicts_cache.len()
+            self.conflicts_pool.len(),
+            self.conflicts_pool.size(),
+            evicted
         );
     }
 
     pub(crate) fn remove_conflict(&mut self, short_id: &ProposalShortId) {
-        self.conflicts_cache.pop(short_id);
+        if self.conflicts_pool.pop(short_id).is_some() {
+            self.pool_map.bump_version();
+        }
         debug!(
-            "remove_conflict {:?} now cache size: {}",
+            "remove_conflict {:?} now conflicts count: {}",
             short_id,
-            self.conflicts_cache.len()
+            self.conflicts_pool.len()
         );
     }
 
@@ -180,31 +449,39 @@ impl TxPool {
         &self,
         id: &ProposalShortId,
     ) -> Option<(TransactionView, Cycle)> {
-        self.pool_map
-            .get_by_id(id)
-            .map(|entry| (entry.inner.transaction().clone(), entry.inner.cycles))
+        self.pool_map.get_by_id(id).and_then(|entry| {
+            self.pool_map
+                .full_transaction(&entry.inner)
+                .map(|tx| (tx, entry.inner.cycles))
+        })
     }
 
     pub(crate) fn get_pool_entry(&self, id: &ProposalShortId) -> Option<&PoolEntry> {
         self.pool_map.get_by_id(id)
     }
 
-    pub(crate) fn get_tx_from_pool(&self, id: &ProposalShortId) -> Option<&TransactionView> {
+    pub(crate) fn get_tx_from_pool(&self, id: &ProposalShortId) -> Option<TransactionView> {
         self.pool_map
             .get_by_id(id)
-            .map(|entry| entry.inner.transaction())
+            .and_then(|entry| self.pool_map.full_transaction(&entry.inner))
     }
 
+    /// Removes the txs committed in the attached blocks, each along with the number of the block
+    /// committing it.
     pub(crate) fn remove_committed_txs<'a>(
         &mut self,
-        txs: impl Iterator<Item = &'a TransactionView>,
+        txs: impl Iterator<Item = (&'a TransactionView, BlockNumber)>,
         callbacks: &Callbacks,
         detached_headers: &HashSet<Byte32>,
     ) {
-        for tx in txs {
+        let tip_number = self.snapshot.tip_number();
+        let pool_map = &self.pool_map;
+        self.fee_estimator
+            .process_blocks(tip_number, |id| pool_map.get_by_id(id).is_some());
+        for (tx, block_number) in txs {
             let tx_hash = tx.hash();
             debug!("try remove_committed_tx {}", tx_hash);
-            self.remove_committed_tx(tx, callbacks);
+            self.remove_committed_tx(tx, block_number, callbacks);
 
             self.committed_txs_hash_cache
                 .put(tx.proposal_short_id(), tx_hash);
@@ -213,6 +490,7 @@ impl TxPool {
         if !detached_headers.is_empty() {
             self.resolve_conflict_header_dep(detached_headers, callbacks)
         }
+        self.prune_fee_deltas();
     }
 
     fn resolve_conflict_header_dep(
@@ -225,10 +503,18 @@ impl TxPool {
         }
     }
 
-    fn remove_committed_tx(&mut self, tx: &TransactionView, callbacks: &Callbacks) {
+    fn remove_committed_tx(
+        &mut self,
+        tx: &TransactionView,
+        block_number: BlockNumber,
+        callbacks: &Callbacks,
+    ) {
         let short_id = tx.proposal_short_id();
-        if let Some(_entry) = self.pool_map.remove_entry(&short_id) {
+        self.fee_deltas.remove(&short_id);
+        self.fee_estimator.commit(&short_id, block_number);
+        if let Some(entry) = self.pool_map.remove_entry(&short_id) {
             debug!("remove_committed_tx for {}", tx.hash());
+            callbacks.call_committed(&entry);
         }
         {
             for (entry, reject) in self.pool_map.resolve_conflict(tx) {
@@ -250,13 +536,17 @@ impl TxPool {
             .pool_map
             .iter()
             .filter(|&entry| self.expiry + entry.inner.timestamp < now_ms)
-            .map(|entry| entry.inner.clone())
+            .map(|entry| entry.id.clone())
             .collect();
 
-        for entry in removed {
+        for id in removed {
+            let entry = match self.pool_map.remove_entry(&id) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            self.drop_fee_deltas([&entry]);
             let tx_hash = entry.transaction().hash();
             debug!("remove_expired {} timestamp({})", tx_hash, entry.timestamp);
-            self.pool_map.remove_entry(&entry.proposal_short_id());
             let reject = Reject::Expiry(entry.timestamp);
             callbacks.call_reject(self, &entry, reject);
         }
@@ -264,45 +554,143 @@ impl TxPool {
 
     // Remove transactions from the pool until total size <= size_limit.
     // Return a `Reject` for current inserting entry if it's removed
+    //
+    // The txs of the recent block templates are not evicted, so the pool may stay over the limit
+    // until their protection expires.
     pub(crate) fn limit_size(
         &mut self,
         callbacks: &Callbacks,
         current_entry_id: Option<&ProposalShortId>,
     ) -> Option<Reject> {
-        let mut ret = None;
-        while self.pool_map.total_tx_size > self.config.max_tx_pool_size {
-            let next_evict_entry = || {
-                self.pool_map
-                    .next_evict_entry(Status::Pending)
-                    .or_else(|| self.pool_map.next_evict_entry(Status::Gap))
-                    .or_else(|| self.pool_map.next_evict_entry(Status::Proposed))
-            };
+        self.limit_size_keeping(callbacks, current_entry_id, &HashSet::new())
+    }
 
-            if let Some(id) = next_evict_entry() {
+    /// Same as `limit_size`, but the `kept` txs are never evicted, e.g., a package which has been
+    /// checked by `size_limit_victims`.
+    pub(crate) fn limit_size_keeping(
+        &mut self,
+        callbacks: &Callbacks,
+        current_entry_id: Option<&ProposalShortId>,
+        kept: &HashSet<ProposalShortId>,
+    ) -> Option<Reject> {
+        let now_ms = ckb_systemtime::unix_time_as_millis();
+        let mut evicted = Vec::new();
+        while self.pool_map.total_tx_size > self.config.max_tx_pool_size {
+            if let Some(id) = self.next_evict_entry(now_ms, kept) {
                 let removed = self.pool_map.remove_entry_and_descendants(&id);
-                for entry in removed {
-                    let tx_hash = entry.transaction().hash();
+                for entry in removed.iter() {
                     debug!(
                         "Removed by size limit {} timestamp({})",
-                        tx_hash, entry.timestamp
+                        entry.transaction().hash(),
+                        entry.timestamp
                     );
-                    let reject = Reject::Full(format!(
-                        "the fee_rate for this transaction is: {}",
-                        entry.fee_rate()
-                    ));
-                    if let Some(short_id) = current_entry_id {
-                        if entry.proposal_short_id() == *short_id {
-                            ret = Some(reject.clone());
-                        }
-                    }
-                    callbacks.call_reject(self, &entry, reject);
                 }
+                evicted.extend(removed);
+            } else {
+                debug!(
+                    "the pool size {} exceeds the limit, the remaining txs are protected by block templates",
+                    self.pool_map.total_tx_size
+                );
+                break;
+            }
+        }
+        if evicted.is_empty() {
+            return None;
+        }
+        self.drop_fee_deltas(&evicted);
+
+        // A tx has to pay a higher fee rate than the next one to be evicted to stay in the pool,
+        // or than the evicted ones if the remaining are all protected.
+        let fee_rate_floor = self
+            .next_evict_entry(now_ms, kept)
+            .and_then(|id| self.pool_map.get_by_id(&id))
+            .map(|entry| entry.evict_key.fee_rate)
+            .or_else(|| {
+                evicted
+                    .iter()
+                    .map(|entry| entry.as_evict_key().fee_rate)
+                    .max()
+            })
+            .map_or(self.config.min_fee_rate, |fee_rate| {
+                fee_rate.max(self.config.min_fee_rate)
+            });
+        let mut ret = None;
+        for entry in evicted {
+            let reject = Reject::FullFeeRateFloor(entry.fee_rate(), fee_rate_floor);
+            if current_entry_id.map_or(false, |short_id| entry.proposal_short_id() == *short_id) {
+                ret = Some(reject.clone());
             }
+            callbacks.call_reject(self, &entry, reject);
         }
         self.pool_map.entries.shrink_to_fit();
         ret
     }
 
+    /// Returns the txs `limit_size` would evict now without evicting them, and the fee rate a tx
+    /// has to pay to stay in the pool.
+    pub(crate) fn size_limit_victims(&self) -> (HashSet<ProposalShortId>, FeeRate) {
+        let now_ms = ckb_systemtime::unix_time_as_millis();
+        let mut victims = HashSet::new();
+        let mut victims_fee_rate = None;
+        let mut total_tx_size = self.pool_map.total_tx_size;
+        let mut next_fee_rate = None;
+        for status in [Status::Pending, Status::Gap, Status::Proposed] {
+            let candidates = self
+                .pool_map
+                .entries
+                .iter_by_evict_key()
+                .filter(|entry| entry.status == status);
+            for entry in candidates {
+                if victims.contains(&entry.id)
+                    || self.template_protection.is_protected(&entry.id, now_ms)
+                {
+                    continue;
+                }
+                if total_tx_size <= self.config.max_tx_pool_size {
+                    next_fee_rate = Some(entry.evict_key.fee_rate);
+                    break;
+                }
+                victims_fee_rate = victims_fee_rate.max(Some(entry.evict_key.fee_rate));
+                let descendants = self.pool_map.calc_descendants(&entry.id);
+                for id in std::iter::once(entry.id.clone()).chain(descendants) {
+                    if let Some(victim) = self.pool_map.get_by_id(&id) {
+                        if victims.insert(id) {
+                            total_tx_size = total_tx_size.saturating_sub(victim.inner.size);
+                        }
+                    }
+                }
+            }
+            if next_fee_rate.is_some() {
+                break;
+            }
+        }
+        let fee_rate_floor = next_fee_rate
+            .or(victims_fee_rate)
+            .map_or(self.config.min_fee_rate, |fee_rate| {
+                fee_rate.max(self.config.min_fee_rate)
+            });
+        (victims, fee_rate_floor)
+    }
+
+    // The next tx to be evicted when the pool is full, the txs of the recent block templates and
+    // the `kept` txs are skipped.
+    fn next_evict_entry(
+        &self,
+        now_ms: u64,
+        kept: &HashSet<ProposalShortId>,
+    ) -> Option<ProposalShortId> {
+        let evictable = |id: &ProposalShortId| {
+            !kept.contains(id) && !self.template_protection.is_protected(id, now_ms)
+        };
+        self.pool_map
+            .next_evict_entry_by(Status::Pending, evictable)
+            .or_else(|| self.pool_map.next_evict_entry_by(Status::Gap, evictable))
+            .or_else(|| {
+                self.pool_map
+                    .next_evict_entry_by(Status::Proposed, evictable)
+            })
+    }
+
     // remove transaction with detached proposal from gap and proposed
     // try re-put to pending
     pub(crate) fn remove_by_detached_proposal<'a>(
@@ -332,9 +720,32 @@ impl TxPool {
 
     pub(crate) fn remove_tx(&mut self, id: &ProposalShortId) -> bool {
         let entries = self.pool_map.remove_entry_and_descendants(id);
+        self.drop_fee_deltas(&entries);
         !entries.is_empty()
     }
 
+    /// Add tx with pending status, skip verification. only for test and benchmark
+    #[cfg(feature = "internal")]
+    pub fn plug_pending(&mut self, entry: TxEntry) -> Result<bool, Reject> {
+        self.add_pending(entry).map(|(succ, _evicted)| succ)
+    }
+
+    /// Remove the tx and its descendants. only for test and benchmark
+    #[cfg(feature = "internal")]
+    pub fn unplug(&mut self, id: &ProposalShortId) -> bool {
+        self.remove_tx(id)
+    }
+
+    /// The bytes of the transactions kept in memory, the witnesses in the witness store are
+    /// excluded. only for test and benchmark
+    #[cfg(feature = "internal")]
+    pub fn txs_memory_size(&self) -> usize {
+        self.pool_map
+            .iter()
+            .map(|entry| entry.inner.transaction().data().total_size())
+            .sum()
+    }
+
     pub(crate) fn check_rtx_from_pool(&self, rtx: &ResolvedTransaction) -> Result<(), Reject> {
         let snapshot = self.snapshot();
         let pool_cell = PoolCell::new(&self.pool_map, false);
@@ -358,6 +769,30 @@ impl TxPool {
             .map_err(Reject::Resolve)
     }
 
+    /// Resolves the txs of a package in order, a tx can spend or depend on the outputs of the
+    /// txs before it in the package. The conflicts with the pool are not allowed, and neither are
+    /// the conflicts between the txs of the package.
+    pub(crate) fn resolve_package(
+        &self,
+        txs: &[TransactionView],
+    ) -> Vec<Result<Arc<ResolvedTransaction>, Reject>> {
+        let snapshot = self.snapshot();
+        let pool_cell = PoolCell::new(&self.pool_map, false);
+        let pool_provider = OverlayCellProvider::new(&pool_cell, snapshot);
+        let mut package_provider = TransactionsProvider::new(std::iter::empty());
+        let mut seen_inputs = HashSet::new();
+        txs.iter()
+            .map(|tx| {
+                let provider = OverlayCellProvider::new(&package_provider, &pool_provider);
+                let ret = resolve_transaction(tx.clone(), &mut seen_inputs, &provider, snapshot)
+                    .map(Arc::new)
+                    .map_err(Reject::Resolve);
+                package_provider.insert(tx);
+                ret
+            })
+            .collect()
+    }
+
     pub(crate) fn gap_rtx(&mut self, short_id: &ProposalShortId) -> Result<(), Reject> {
         match self.get_pool_entry(short_id) {
             Some(entry) => {
@@ -411,8 +846,7 @@ impl TxPool {
         proposal_id: &ProposalShortId,
     ) -> Option<TransactionView> {
         self.get_tx_from_pool(proposal_id)
-            .cloned()
-            .or_else(|| self.conflicts_cache.peek(proposal_id).cloned())
+            .or_else(|| self.conflicts_pool.peek(proposal_id).cloned())
             .or_else(|| {
                 self.committed_txs_hash_cache
                     .peek(proposal_id)
@@ -421,44 +855,68 @@ impl TxPool {
     }
 
     pub(crate) fn get_ids(&self) -> TxPoolIds {
-        let pending = self
-            .pool_map
-            .score_sorted_iter_by_statuses(vec![Status::Pending, Status::Gap])
-            .map(|entry| entry.transaction().hash())
-            .collect();
-
-        let proposed = self
-            .pool_map
-            .sorted_proposed_iter()
-            .map(|entry| entry.transaction().hash())
-            .collect();
-
+        // split the statuses in one pass, and size the lists up front for the large pools
+        let mut pending = Vec::with_capacity(self.pool_map.pending_size());
+        let mut proposed = Vec::with_capacity(self.pool_map.proposed_size());
+        for (status, entry) in self.pool_map.score_sorted_iter() {
+            match status {
+                Status::Pending | Status::Gap => pending.push(entry.transaction().hash()),
+                Status::Proposed => proposed.push(entry.transaction().hash()),
+            }
+        }
         TxPoolIds { pending, proposed }
     }
 
-    pub(crate) fn get_all_entry_info(&self) -> TxPoolEntryInfo {
-        let pending = self
-            .pool_map
-            .score_sorted_iter_by_statuses(vec![Status::Pending, Status::Gap])
-            .map(|entry| (entry.transaction().hash(), entry.to_info()))
-            .collect();
+    /// Returns at most `limit` hashes of the in-pool txs in the descending order of the scores,
+    /// starting after the cursor returned with the previous page. The pending txs include the
+    /// ones in the gap set.
+    ///
+    /// It's for the consumers walking through a large pool page by page, instead of copying all
+    /// the ids at once like [`get_ids`](Self::get_ids).
+    pub fn ids_page(
+        &self,
+        proposed: bool,
+        after: Option<&ScoreCursor>,
+        limit: usize,
+    ) -> (Vec<Byte32>, Option<ScoreCursor>) {
+        self.entries_page(proposed, after, limit, |entry| entry.transaction().hash())
+    }
+
+    /// Returns at most `limit` entry infos of the in-pool txs by their hashes, paged like
+    /// [`ids_page`](Self::ids_page).
+    pub fn entry_info_page(
+        &self,
+        proposed: bool,
+        after: Option<&ScoreCursor>,
+        limit: usize,
+    ) -> (Vec<(Byte32, TxEntryInfo)>, Option<ScoreCursor>) {
+        self.entries_page(proposed, after, limit, |entry| {
+            (entry.transaction().hash(), entry.to_info())
+        })
+    }
 
-        let proposed = self
+    fn entries_page<T>(
+        &self,
+        proposed: bool,
+        after: Option<&ScoreCursor>,
+        limit: usize,
+        f: impl Fn(&TxEntry) -> T,
+    ) -> (Vec<T>, Option<ScoreCursor>) {
+        let statuses: &[Status] = if proposed {
+            &[Status::Proposed]
+        } else {
+            &[Status::Pending, Status::Gap]
+        };
+        let page = self
             .pool_map
-            .sorted_proposed_iter()
-            .map(|entry| (entry.transaction().hash(), entry.to_info()))
-            .collect();
+            .score_sorted_page(statuses, after.map(|cursor| &cursor.0), limit);
+        let items = page.entries.into_iter().map(f).collect();
+        (items, page.next.map(ScoreCursor))
+    }
 
-        let conflicted = self
-            .conflicts_cache
-            .iter()
-            .map(|(_id, tx)| tx.hash())
-            .collect();
-        TxPoolEntryInfo {
-            pending,
-            proposed,
-            conflicted,
-        }
+    /// Returns the hashes of the txs in the conflicts pool.
+    pub fn conflicted_ids(&self) -> Vec<Byte32> {
+        self.conflicts_pool.iter().map(|tx| tx.hash()).collect()
     }
 
     pub(crate) fn drain_all_transactions(&mut self) -> Vec<TransactionView> {
@@ -466,14 +924,14 @@ impl TxPool {
             .txs_to_commit(usize::MAX, Cycle::MAX)
             .0
             .into_iter()
-            .map(|tx_entry| tx_entry.into_transaction())
+            .filter_map(|tx_entry| self.pool_map.full_transaction(&tx_entry))
             .collect::<Vec<_>>();
         let mut pending = self
             .pool_map
             .entries
             .remove_by_status(&Status::Pending)
             .into_iter()
-            .map(|e| e.inner.into_transaction())
+            .filter_map(|e| self.pool_map.full_transaction(&e.inner))
             .collect::<Vec<_>>();
         txs.append(&mut pending);
         let mut gap = self
@@ -481,7 +939,7 @@ impl TxPool {
             .entries
             .remove_by_status(&Status::Gap)
             .into_iter()
-            .map(|e| e.inner.into_transaction())
+            .filter_map(|e| self.pool_map.full_transaction(&e.inner))
             .collect::<Vec<_>>();
         txs.append(&mut gap);
         self.pool_map.clear();
@@ -492,7 +950,11 @@ impl TxPool {
         self.pool_map.clear();
         self.snapshot = snapshot;
         self.committed_txs_hash_cache = LruCache::new(COMMITTED_HASH_CACHE_SIZE);
-        self.conflicts_cache = LruCache::new(CONFLICTES_CACHE_SIZE);
+        self.conflicts_pool.clear();
+        self.rbf_limiter.clear();
+        self.fee_deltas.clear();
+        self.template_protection.clear();
+        self.fee_estimator.clear();
     }
 
     pub(crate) fn package_proposals(
@@ -512,8 +974,22 @@ impl TxPool {
         max_block_cycles: Cycle,
         txs_size_limit: usize,
     ) -> (Vec<TxEntry>, usize, Cycle) {
-        let (entries, size, cycles) =
+        let (entries, _, _) =
             CommitTxsScanner::new(&self.pool_map).txs_to_commit(txs_size_limit, max_block_cycles);
+        // the txs after a lost one are left out too, they may depend on it
+        let entries: Vec<_> = entries
+            .iter()
+            .map_while(|entry| self.pool_map.full_entry(entry))
+            .collect();
+        // sums up the txs actually packaged
+        let (size, cycles) = entries
+            .iter()
+            .fold((0usize, 0 as Cycle), |(size, cycles), entry| {
+                (
+                    size.saturating_add(entry.size),
+                    cycles.saturating_add(entry.cycles),
+                )
+            });
 
         if !entries.is_empty() {
             ckb_logger::info!(
@@ -528,27 +1004,54 @@ impl TxPool {
         (entries, size, cycles)
     }
 
+    /// Sums up the txs left out of a block template with the `packaged` txs, returns the proposed
+    /// ones which didn't fit in the block, and the pending or gap ones.
+    pub(crate) fn template_backlog(&self, packaged: &[TxEntry]) -> (TxsBacklog, TxsBacklog) {
+        self.pool_map.backlog(packaged)
+    }
+
     pub(crate) fn check_rbf(
         &self,
         snapshot: &Snapshot,
         entry: &TxEntry,
     ) -> Result<HashSet<ProposalShortId>, Reject> {
         assert!(self.enable_rbf());
-        let tx_inputs: Vec<OutPoint> = entry.transaction().input_pts_iter().collect();
-        let conflict_ids = self.pool_map.find_conflict_tx(entry.transaction());
+        let check = self.rbf_check(snapshot, entry, false);
+        match check.rules.into_iter().find_map(|(_, result)| result.err()) {
+            Some(reject) => Err(reject),
+            None => Ok(check.conflict_ids),
+        }
+    }
 
-        if conflict_ids.is_empty() {
-            return Ok(HashSet::new());
+    // Checks the RBF rules of the entry. It stops at the first failed rule, unless `check_all`
+    // is set for the simulation, which reports all the rules.
+    fn rbf_check<'a>(
+        &'a self,
+        snapshot: &Snapshot,
+        entry: &TxEntry,
+        check_all: bool,
+    ) -> RbfCheck<'a> {
+        let tx_inputs: Vec<OutPoint> = entry.transaction().input_pts_iter().collect();
+        let mut check = RbfCheck {
+            conflict_ids: self.pool_map.find_conflict_tx(entry.transaction()),
+            all_conflicted: vec![],
+            min_replace_fee: None,
+            rules: vec![],
+        };
+
+        if check.conflict_ids.is_empty() {
+            return check;
         }
 
         let short_id = entry.proposal_short_id();
 
         // Rule #1, the node has enabled RBF, which is checked by caller
-        let conflicts = conflict_ids
+        let conflicts = check
+            .conflict_ids
             .iter()
             .filter_map(|id| self.get_pool_entry(id))
             .collect::<Vec<_>>();
-        assert!(conflicts.len() == conflict_ids.len());
+        assert!(conflicts.len() == check.conflict_ids.len());
 
         // Rule #2, new tx don't contain any new unconfirmed inputs
         let mut inputs = HashSet::new();
@@ -556,34 +1059,47 @@ impl TxPool {
             inputs.extend(c.inner.transaction().input_pts_iter());
         }
 
-        if tx_inputs
+        let rule_2 = if tx_inputs
             .iter()
             .any(|pt| !inputs.contains(pt) && !snapshot.transaction_exists(&pt.tx_hash()))
         {
-            return Err(Reject::RBFRejected(
+            Err(Reject::RBFRejected(
                 "new Tx contains unconfirmed inputs".to_string(),
-            ));
+            ))
+        } else {
+            Ok(())
+        };
+        if check.record(RbfRule::NoNewUnconfirmedInputs, rule_2, check_all) {
+            return check;
         }
 
-        // Rule #5, the replaced tx's descendants can not more than 100
+        // Rule #5, the replaced txs can not more than max_rbf_candidates, the descendants of a
+        // conflict can not more than max_rbf_conflict_descendants,
         // and the ancestor of the new tx don't have common set with the replaced tx's descendants
+        let mut rule_5 = Ok(());
         let mut replace_count: usize = 0;
         let mut all_conflicted = conflicts.clone();
         let ancestors = self.pool_map.calc_ancestors(&short_id);
         for conflict in conflicts.iter() {
             let descendants = self.pool_map.calc_descendants(&conflict.id);
             replace_count += descendants.len() + 1;
-            if replace_count > MAX_REPLACEMENT_CANDIDATES {
-                return Err(Reject::RBFRejected(format!(
-                    "Tx conflict with too many txs, conflict txs count: {}, expect <= {}",
-                    replace_count, MAX_REPLACEMENT_CANDIDATES,
-                )));
-            }
-
-            if !descendants.is_disjoint(&ancestors) {
-                return Err(Reject::RBFRejected(
-                    "Tx ancestors have common with conflict Tx descendants".to_string(),
-                ));
+            if rule_5.is_ok() {
+                if descendants.len() > self.config.max_rbf_conflict_descendants {
+                    rule_5 = Err(Reject::RBFRejected(format!(
+                        "Tx conflict with a tx which has too many descendants, descendants count: {}, expect <= {}",
+                        descendants.len(),
+                        self.config.max_rbf_conflict_descendants,
+                    )));
+                } else if replace_count > self.config.max_rbf_candidates {
+                    rule_5 = Err(Reject::RBFRejected(format!(
+                        "Tx conflict with too many txs, conflict txs count: {}, expect <= {}",
+                        replace_count, self.config.max_rbf_candidates,
+                    )));
+                } else if !descendants.is_disjoint(&ancestors) {
+                    rule_5 = Err(Reject::RBFRejected(
+                        "Tx ancestors have common with conflict Tx descendants".to_string(),
+                    ));
+                }
             }
 
             let entries = descendants
@@ -591,48 +1107,245 @@ impl TxPool {
                 .filter_map(|id| self.get_pool_entry(id))
                 .collect::<Vec<_>>();
 
-            for entry in entries.iter() {
-                let hash = entry.inner.transaction().hash();
-                if tx_inputs.iter().any(|pt| pt.tx_hash() == hash) {
-                    return Err(Reject::RBFRejected(
-                        "new Tx contains inputs in descendants of to be replaced Tx".to_string(),
-                    ));
+            if rule_5.is_ok() {
+                for entry in entries.iter() {
+                    let hash = entry.inner.transaction().hash();
+                    if tx_inputs.iter().any(|pt| pt.tx_hash() == hash) {
+                        rule_5 = Err(Reject::RBFRejected(
+                            "new Tx contains inputs in descendants of to be replaced Tx"
+                                .to_string(),
+                        ));
+                        break;
+                    }
                 }
             }
             all_conflicted.extend(entries);
+            if rule_5.is_err() && !check_all {
+                break;
+            }
         }
 
-        let tx_cells_deps: Vec<OutPoint> = entry
-            .transaction()
-            .cell_deps_iter()
-            .map(|c| c.out_point())
-            .collect();
-        for entry in all_conflicted.iter() {
-            let hash = entry.inner.transaction().hash();
-            if tx_cells_deps.iter().any(|pt| pt.tx_hash() == hash) {
-                return Err(Reject::RBFRejected(
-                    "new Tx contains cell deps from conflicts".to_string(),
-                ));
+        if rule_5.is_ok() {
+            if let Some(replaced) = all_conflicted
+                .iter()
+                .find(|e| !self.rbf_replaceable(e.status))
+            {
+                rule_5 = Err(Reject::RBFRejected(format!(
+                    "Tx conflict with the {} tx {}, which is not allowed to be replaced",
+                    replaced.status.to_string(),
+                    replaced.inner.transaction().hash(),
+                )));
             }
         }
 
-        // Rule #4, new tx's fee need to higher than min_rbf_fee computed from the tx_pool configuration
+        if rule_5.is_ok() {
+            let tx_cells_deps: Vec<OutPoint> = entry
+                .transaction()
+                .cell_deps_iter()
+                .map(|c| c.out_point())
+                .collect();
+            for entry in all_conflicted.iter() {
+                let hash = entry.inner.transaction().hash();
+                if tx_cells_deps.iter().any(|pt| pt.tx_hash() == hash) {
+                    rule_5 = Err(Reject::RBFRejected(
+                        "new Tx contains cell deps from conflicts".to_string(),
+                    ));
+                    break;
+                }
+            }
+        }
+        if check.record(RbfRule::ReplacedTxs, rule_5, check_all) {
+            return check;
+        }
+
+        // Rule #6, the replacements of the inputs in the recent window can not more than max_rbf_replacements
+        let replacements = self.rbf_replacements_count(entry.transaction());
+        let rule_6 = if replacements >= self.config.max_rbf_replacements {
+            Err(Reject::RBFRejected(format!(
+                "Tx inputs have been replaced too many times recently, replacements count: {}, expect < {}",
+                replacements, self.config.max_rbf_replacements,
+            )))
+        } else {
+            Ok(())
+        };
+        if check.record(RbfRule::ReplacementChurn, rule_6, check_all) {
+            return check;
+        }
+
+        // Rule #4, new tx's fee need to higher than min_rbf_fee computed from the tx_pool configuration,
+        // which escalates with the recent replacements of the inputs
         // Rule #3, new tx's fee need to higher than conflicts, here we only check the all conflicted txs fee
         let fee = entry.fee;
-        if let Some(min_replace_fee) = self.calculate_min_replace_fee(&all_conflicted, entry.size) {
-            if fee < min_replace_fee {
-                return Err(Reject::RBFRejected(format!(
-                    "Tx's current fee is {}, expect it to >= {} to replace old txs",
-                    fee, min_replace_fee,
-                )));
+        let min_replace_fee =
+            self.calculate_min_replace_fee(&all_conflicted, entry.size, replacements);
+        let rule_4 = match min_replace_fee {
+            Some(min_replace_fee) if fee < min_replace_fee => {
+                Err(Reject::RBFRejectedByFee(fee, min_replace_fee))
             }
-        } else {
-            return Err(Reject::RBFRejected(
+            Some(_) => Ok(()),
+            None => Err(Reject::RBFRejected(
                 "calculate_min_replace_fee failed".to_string(),
-            ));
+            )),
+        };
+        check.record(RbfRule::MinReplaceFee, rule_4, check_all);
+        check.all_conflicted = all_conflicted;
+        check.min_replace_fee = min_replace_fee;
+        check
+    }
+
+    fn rbf_replaceable(&self, status: Status) -> bool {
+        match status {
+            Status::Pending => true,
+            Status::Gap => self.config.rbf_replace_gap,
+            Status::Proposed => self.config.rbf_replace_proposed,
+        }
+    }
+
+    /// Simulates the replacement of the conflicting txs in the pool by a tx, without submitting
+    /// it. The inputs are looked up in the pool and the chain, dead or not.
+    pub(crate) fn simulate_replacement(
+        &self,
+        tx: &TransactionView,
+    ) -> Result<ReplacementSimulation, Reject> {
+        if !self.enable_rbf() {
+            return Err(Reject::RBFRejected("RBF is disabled".to_string()));
+        }
+        let snapshot = self.snapshot();
+        let resolved_inputs = tx
+            .input_pts_iter()
+            .map(|out_point| self.input_cell_meta(out_point))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rtx = ResolvedTransaction {
+            transaction: tx.clone(),
+            resolved_cell_deps: vec![],
+            resolved_inputs,
+            resolved_dep_groups: vec![],
+        };
+        let fee = calculate_tx_fee(snapshot, &rtx)?;
+        let size = tx.data().serialized_size_in_block();
+        // the cycles are not checked by the RBF rules
+        let entry = TxEntry::new(Arc::new(rtx), 0, fee, size);
+
+        let check = self.rbf_check(snapshot, &entry, true);
+        let mut evicted_ids = HashSet::new();
+        let evicted: Vec<&PoolEntry> = check
+            .all_conflicted
+            .into_iter()
+            .filter(|e| evicted_ids.insert(e.id.clone()))
+            .collect();
+        let evicted_fee = evicted
+            .iter()
+            .try_fold(Capacity::zero(), |acc, e| acc.safe_add(e.inner.fee))
+            .map_err(|err| Reject::Malformed(format!("{err}"), "evicted fee".to_owned()))?;
+        Ok(ReplacementSimulation {
+            tx_hash: tx.hash(),
+            fee,
+            size,
+            conflicts: check
+                .conflict_ids
+                .iter()
+                .filter_map(|id| self.get_pool_entry(id))
+                .map(|e| e.inner.transaction().hash())
+                .collect(),
+            evicted: evicted
+                .iter()
+                .map(|e| e.inner.transaction().hash())
+                .collect(),
+            evicted_fee,
+            min_replace_fee: check.min_replace_fee,
+            rules: check
+                .rules
+                .into_iter()
+                .map(|(rule, result)| RbfRuleCheck {
+                    rule,
+                    passed: result.is_ok(),
+                    reason: result.err().map(|reject| reject.to_string()),
+                })
+                .collect(),
+        })
+    }
+
+    /// Estimates the fee rate for a tx to be committed within the target blocks, by how many
+    /// blocks the recent txs took to be committed. It's never lower than `min_fee_rate`.
+    pub(crate) fn estimate_fee_rate(&self, target_blocks: BlockNumber) -> Option<FeeRate> {
+        self.fee_estimator
+            .estimate(target_blocks)
+            .map(|fee_rate| fee_rate.max(self.config.min_fee_rate))
+    }
+
+    /// Simulates when a tx could be proposed and committed if it were submitted now, by its rank
+    /// among the pending txs and the proposal window. The inputs are looked up in the pool and
+    /// the chain, dead or not.
+    pub(crate) fn simulate_proposal_window(
+        &self,
+        tx: &TransactionView,
+    ) -> Result<ProposalWindowSimulation, Reject> {
+        if self.pool_map.contains_key(&tx.proposal_short_id()) {
+            return Err(Reject::Duplicated(tx.hash()));
+        }
+        let snapshot = self.snapshot();
+        let resolved_inputs = tx
+            .input_pts_iter()
+            .map(|out_point| self.input_cell_meta(out_point))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rtx = ResolvedTransaction {
+            transaction: tx.clone(),
+            resolved_cell_deps: vec![],
+            resolved_inputs,
+            resolved_dep_groups: vec![],
+        };
+        let size = tx.data().serialized_size_in_block();
+        let fee = check_tx_fee(self, snapshot, &rtx, size)?;
+        // the cycles are unknown before the verification, the weight is estimated by the size
+        let mut entry = TxEntry::new(Arc::new(rtx), 0, fee, size);
+
+        let mut ancestors: HashSet<ProposalShortId> = tx
+            .input_pts_iter()
+            .chain(tx.cell_deps_iter().map(|dep| dep.out_point()))
+            .map(|out_point| ProposalShortId::from_tx_hash(&out_point.tx_hash()))
+            .filter(|id| self.pool_map.contains_key(id))
+            .collect();
+        let parents: Vec<ProposalShortId> = ancestors.iter().cloned().collect();
+        for parent in &parents {
+            ancestors.extend(self.pool_map.calc_ancestors(parent));
+        }
+        for ancestor in ancestors.iter().filter_map(|id| self.pool_map.get(id)) {
+            entry.add_ancestor_weight(ancestor);
         }
 
-        Ok(conflict_ids)
+        // the tx can't be committed before its pending ancestors are proposed
+        let score = entry.as_score_key();
+        let mut rank = 0;
+        for (position, pending) in self
+            .pool_map
+            .score_sorted_iter_by_status(Status::Pending)
+            .enumerate()
+        {
+            if pending.as_score_key() > score {
+                rank = position + 1;
+            } else if ancestors.contains(&pending.proposal_short_id()) {
+                rank = position;
+            }
+        }
+
+        let consensus = snapshot.consensus();
+        let proposals_limit = consensus.max_block_proposals_limit();
+        let proposal_window = consensus.tx_proposal_window();
+        let tip_number = snapshot.tip_number();
+        let earliest_proposal_block = tip_number + 1 + rank as u64 / proposals_limit.max(1);
+        Ok(ProposalWindowSimulation {
+            tx_hash: tx.hash(),
+            fee_rate: FeeRate::calculate(
+                entry.ancestors_fee,
+                get_transaction_weight(entry.ancestors_size, entry.ancestors_cycles),
+            ),
+            tip_number,
+            rank,
+            proposals_limit,
+            earliest_proposal_block,
+            earliest_commit_block: earliest_proposal_block + proposal_window.closest(),
+            latest_commit_block: earliest_proposal_block + proposal_window.farthest(),
+        })
     }
 
     /// query the details of a transaction in the pool, only for trouble shooting
@@ -657,8 +1370,8 @@ impl TxPool {
                 pending_count: self.pool_map.pending_size(),
                 rank_in_pending,
                 proposed_count: ids.proposed.len(),
-                descendants_count: self.pool_map.calc_descendants(id).len(),
-                ancestors_count: self.pool_map.calc_ancestors(id).len(),
+                descendants_count: self.pool_map.descendants_count(id),
+                ancestors_count: self.pool_map.ancestors_count(id),
                 score_sortkey: entry.inner.as_score_key().into(),
             };
             Some(res)
@@ -667,6 +1380,331 @@ impl TxPool {
         }
     }
 
+    /// explains why a transaction in the pool is not progressing, only for trouble shooting
+    pub(crate) fn diagnose_tx(&self, id: &ProposalShortId, now_ms: u64) -> Option<TxDiagnosis> {
+        let entry = self.pool_map.get_by_id(id)?;
+        let tx = entry.inner.transaction();
+        let mut diagnosis = TxDiagnosis::with_status(tx.hash(), &entry.status.to_string());
+        let fee_rate = entry.inner.fee_rate();
+        diagnosis.fee_rate = Some(fee_rate);
+
+        let min_fee = self.config.min_fee_rate.fee(entry.inner.size as u64);
+        if entry.inner.fee < min_fee {
+            diagnosis.issues.push(TxDiagnosisIssue {
+                kind: TxDiagnosisIssueKind::BelowFeeFloor,
+                message: format!(
+                    "fee {} is below {} required by min_fee_rate {}",
+                    entry.inner.fee, min_fee, self.config.min_fee_rate
+                ),
+                related_txs: vec![],
+            });
+        }
+
+        if entry.status == Status::Pending {
+            let rank = self
+                .pool_map
+                .score_sorted_iter_by_status(Status::Pending)
+                .position(|e| &e.proposal_short_id() == id)
+                .unwrap_or_default();
+            diagnosis.rank_in_pending = Some(rank);
+            let proposals_limit = self.snapshot.consensus().max_block_proposals_limit() as usize;
+            if rank >= proposals_limit {
+                diagnosis.issues.push(TxDiagnosisIssue {
+                    kind: TxDiagnosisIssueKind::LowRank,
+                    message: format!(
+                        "ranked {} among {} pending txs by fee rate, \
+                         a block proposes at most {} txs",
+                        rank,
+                        self.pool_map.pending_size(),
+                        proposals_limit
+                    ),
+                    related_txs: vec![],
+                });
+            }
+        }
+
+        let ancestors_count = self.pool_map.ancestors_count(id);
+        diagnosis.ancestors_count = Some(ancestors_count);
+        if ancestors_count >= self.pool_map.max_ancestors_count {
+            diagnosis.issues.push(TxDiagnosisIssue {
+                kind: TxDiagnosisIssueKind::TooManyAncestors,
+                message: format!(
+                    "{} in-pool ancestors reach max_ancestors_count {}, \
+                     they must be committed first",
+                    ancestors_count, self.pool_map.max_ancestors_count
+                ),
+                related_txs: vec![],
+            });
+        }
+
+        let inputs: HashSet<OutPoint> = tx.input_pts_iter().collect();
+        let conflicts: Vec<Byte32> = self
+            .conflicts_pool
+            .iter()
+            .filter(|conflict| conflict.input_pts_iter().any(|pt| inputs.contains(&pt)))
+            .map(|conflict| conflict.hash())
+            .collect();
+        if !conflicts.is_empty() {
+            diagnosis.issues.push(TxDiagnosisIssue {
+                kind: TxDiagnosisIssueKind::Conflicts,
+                message: format!(
+                    "{} conflicted txs spend the same inputs, \
+                     they are resubmitted if this tx is removed",
+                    conflicts.len()
+                ),
+                related_txs: conflicts,
+            });
+        }
+
+        let expires_at = entry.inner.timestamp + self.expiry;
+        diagnosis.expires_at = Some(expires_at);
+        if expires_at <= now_ms + EXPIRES_SOON_MS {
+            diagnosis.issues.push(TxDiagnosisIssue {
+                kind: TxDiagnosisIssueKind::ExpiresSoon,
+                message: format!(
+                    "expires in {} seconds",
+                    expires_at.saturating_sub(now_ms) / 1000
+                ),
+                related_txs: vec![],
+            });
+        }
+        Some(diagnosis)
+    }
+
+    /// Breaks down the capacities and the fee of a tx, which may be in the pool, committed or
+    /// not submitted yet. The inputs are looked up in the pool and the chain, dead or not.
+    pub(crate) fn fee_breakdown(&self, tx: &TransactionView) -> Result<TxFeeBreakdown, Reject> {
+        let snapshot = self.snapshot();
+        let resolved_inputs = tx
+            .input_pts_iter()
+            .map(|out_point| self.input_cell_meta(out_point))
+            .collect::<Result<Vec<_>, _>>()?;
+        let capacities = |cells: Vec<(CellOutput, usize)>| {
+            cells
+                .into_iter()
+                .map(|(output, data_len)| {
+                    let occupied_capacity = Capacity::bytes(data_len)
+                        .and_then(|data| output.occupied_capacity(data))
+                        .map_err(|err| {
+                            Reject::Malformed(format!("{err}"), "occupied capacity".to_owned())
+                        })?;
+                    Ok(CellCapacityBreakdown {
+                        capacity: output.capacity().unpack(),
+                        occupied_capacity,
+                    })
+                })
+                .collect::<Result<Vec<_>, Reject>>()
+        };
+        let inputs = capacities(
+            resolved_inputs
+                .iter()
+                .map(|cell| (cell.cell_output.clone(), cell.data_bytes as usize))
+                .collect(),
+        )?;
+        let outputs = capacities(
+            tx.outputs_with_data_iter()
+                .map(|(output, data)| (output, data.len()))
+                .collect(),
+        )?;
+
+        let rtx = ResolvedTransaction {
+            transaction: tx.clone(),
+            resolved_cell_deps: vec![],
+            resolved_inputs,
+            resolved_dep_groups: vec![],
+        };
+        let fee = calculate_tx_fee(snapshot, &rtx)?;
+        let outputs_capacity = tx
+            .outputs_capacity()
+            .map_err(|err| Reject::Malformed(format!("{err}"), "outputs capacity".to_owned()))?;
+        let size = tx.data().serialized_size_in_block();
+
+        let min_replace_fee = if self.enable_rbf() {
+            let id = tx.proposal_short_id();
+            let mut conflicts: Vec<&PoolEntry> = vec![];
+            for conflict_id in self.pool_map.find_conflict_tx(tx) {
+                if conflict_id == id {
+                    continue;
+                }
+                conflicts.extend(self.get_pool_entry(&conflict_id));
+                conflicts.extend(
+                    self.pool_map
+                        .calc_descendants(&conflict_id)
+                        .iter()
+                        .filter_map(|id| self.get_pool_entry(id)),
+                );
+            }
+            if conflicts.is_empty() {
+                None
+            } else {
+                let replacements = self.rbf_replacements_count(tx);
+                self.calculate_min_replace_fee(&conflicts, size, replacements)
+            }
+        } else {
+            None
+        };
+
+        Ok(TxFeeBreakdown {
+            tx_hash: tx.hash(),
+            inputs,
+            outputs,
+            inputs_capacity: outputs_capacity
+                .safe_add(fee)
+                .map_err(|err| Reject::Malformed(format!("{err}"), "inputs capacity".to_owned()))?,
+            outputs_capacity,
+            fee,
+            size,
+            fee_rate: FeeRate::calculate(fee, size as u64),
+            min_fee_rate: self.config.min_fee_rate,
+            min_fee: self.config.min_fee_rate.fee(size as u64),
+            min_rbf_rate: self.config.min_rbf_rate,
+            min_replace_fee,
+        })
+    }
+
+    /// The fee rate of a tx by the inputs in the pool and the chain, `None` if any input is
+    /// unknown. The inputs are not checked to be live, it's only used to prioritize the
+    /// verification.
+    pub(crate) fn tx_fee_rate(&self, tx: &TransactionView) -> Option<FeeRate> {
+        let resolved_inputs = tx
+            .input_pts_iter()
+            .map(|out_point| self.input_cell_meta(out_point))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        let rtx = ResolvedTransaction {
+            transaction: tx.clone(),
+            resolved_cell_deps: vec![],
+            resolved_inputs,
+            resolved_dep_groups: vec![],
+        };
+        let fee = calculate_tx_fee(self.snapshot(), &rtx).ok()?;
+        let size = tx.data().serialized_size_in_block();
+        Some(FeeRate::calculate(fee, size as u64))
+    }
+
+    // The cell of an input, from the pool or the chain, dead or not
+    fn input_cell_meta(&self, out_point: OutPoint) -> Result<CellMeta, Reject> {
+        let index: usize = out_point.index().unpack();
+        let id = ProposalShortId::from_tx_hash(&out_point.tx_hash());
+        let (tx, transaction_info) = match self.get_tx_from_pool(&id) {
+            Some(tx) => (tx, None),
+            None => {
+                let (tx, _) = self
+                    .snapshot()
+                    .get_transaction(&out_point.tx_hash())
+                    .ok_or_else(|| Reject::Resolve(OutPointError::Unknown(out_point.clone())))?;
+                let info = self.snapshot().get_transaction_info(&out_point.tx_hash());
+                (tx, info)
+            }
+        };
+        let (output, data) = tx
+            .output_with_data(index)
+            .ok_or_else(|| Reject::Resolve(OutPointError::Unknown(out_point.clone())))?;
+        let mut builder = CellMetaBuilder::from_cell_output(output, data).out_point(out_point);
+        if let Some(info) = transaction_info {
+            builder = builder.transaction_info(info);
+        }
+        Ok(builder.build())
+    }
+
+    /// query the in-pool ancestors and descendants of a transaction, with the links between them,
+    /// up to `max_depth` links away from it, only for trouble shooting
+    pub(crate) fn get_tx_graph(
+        &self,
+        id: &ProposalShortId,
+        max_depth: usize,
+    ) -> Option<PoolTxGraph> {
+        let root = self.pool_map.get_by_id(id)?;
+        let links = &self.pool_map.links;
+
+        // the depth of every visited transaction, ancestors and descendants are searched apart,
+        // a transaction can not be both since the links are acyclic
+        let mut visited: Vec<(ProposalShortId, PoolTxGraphRelation, usize)> =
+            vec![(id.clone(), PoolTxGraphRelation::Root, 0)];
+        let mut seen: HashSet<ProposalShortId> = HashSet::from([id.clone()]);
+        let mut truncated = false;
+        for relation in [
+            PoolTxGraphRelation::Ancestor,
+            PoolTxGraphRelation::Descendant,
+        ] {
+            let mut queue = VecDeque::from([(id.clone(), 0)]);
+            while let Some((current, depth)) = queue.pop_front() {
+                let next = match relation {
+                    PoolTxGraphRelation::Ancestor => links.get_parents(&current),
+                    _ => links.get_children(&current),
+                };
+                for next_id in next.into_iter().flatten() {
+                    if seen.contains(next_id) {
+                        continue;
+                    }
+                    if depth >= max_depth {
+                        truncated = true;
+                        continue;
+                    }
+                    seen.insert(next_id.clone());
+                    visited.push((next_id.clone(), relation, depth + 1));
+                    queue.push_back((next_id.clone(), depth + 1));
+                }
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(visited.len());
+        let mut edges = Vec::new();
+        for (node_id, relation, depth) in visited {
+            let entry = match self.pool_map.get_by_id(&node_id) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let tx_hash = entry.inner.transaction().hash();
+            for parent_id in links.get_parents(&node_id).into_iter().flatten() {
+                if !seen.contains(parent_id) {
+                    continue;
+                }
+                if let Some(parent) = self.pool_map.get_by_id(parent_id) {
+                    edges.push(PoolTxGraphEdge {
+                        parent: parent.inner.transaction().hash(),
+                        child: tx_hash.clone(),
+                    });
+                }
+            }
+            nodes.push(PoolTxGraphNode {
+                tx_hash,
+                relation,
+                depth,
+                fee: entry.inner.fee,
+                size: entry.inner.size,
+                cycles: entry.inner.cycles,
+                status: entry.status.to_string(),
+                timestamp: entry.inner.timestamp,
+            });
+        }
+
+        Some(PoolTxGraph {
+            tx_hash: root.inner.transaction().hash(),
+            nodes,
+            edges,
+            truncated,
+        })
+    }
+
+    fn build_witness_store(config: &TxPoolConfig) -> Option<WitnessStore> {
+        let threshold = config.lazy_witnesses_threshold?;
+        if config.witness_store.as_os_str().is_empty() {
+            warn!("The witness store path is not set, the witnesses are kept in memory");
+            return None;
+        }
+        match WitnessStore::new(&config.witness_store, threshold) {
+            Ok(witness_store) => Some(witness_store),
+            Err(err) => {
+                error!(
+                    "Failed to open the witness store {:?} {}",
+                    config.witness_store, err
+                );
+                None
+            }
+        }
+    }
+
     fn build_recent_reject(config: &TxPoolConfig) -> Option<RecentReject> {
         if !config.recent_reject.as_os_str().is_empty() {
             let recent_reject_ttl =
@@ -3,12 +3,14 @@ use crate::component::entry::TxEntry;
 use crate::component::orphan::Entry as OrphanEntry;
 use crate::component::pool_map::Status;
 use crate::error::Reject;
+use crate::persisted::{load_verify_cache, save_verify_cache, verify_cache_key};
 use crate::pool::TxPool;
 use crate::service::{BlockAssemblerMessage, TxPoolService, TxVerificationResult};
 use crate::try_or_return_with_snapshot;
 use crate::util::{
-    after_delay_window, check_tx_fee, check_txid_collision, is_missing_input,
-    non_contextual_verify, time_relative_verify, verify_rtx,
+    after_delay_window, calculate_tx_fee, check_absurd_fee, check_header_deps_depth, check_tx_fee,
+    check_txid_collision, is_missing_input, non_contextual_verify, time_relative_verify,
+    verify_rtx,
 };
 use ckb_chain_spec::consensus::MAX_BLOCK_PROPOSALS_LIMIT;
 use ckb_error::{AnyError, InternalErrorKind};
@@ -20,7 +22,11 @@ use ckb_script::ChunkCommand;
 use ckb_snapshot::Snapshot;
 use ckb_types::core::error::OutPointError;
 use ckb_types::{
-    core::{cell::ResolvedTransaction, BlockView, Capacity, Cycle, HeaderView, TransactionView},
+    core::{
+        cell::ResolvedTransaction,
+        tx_pool::{TemplateBacklog, TxLifecycleStage, TRANSACTION_SIZE_LIMIT},
+        BlockNumber, BlockView, Capacity, Cycle, HeaderView, TransactionView,
+    },
     packed::{Byte32, ProposalShortId},
 };
 use ckb_util::LinkedHashSet;
@@ -72,6 +78,18 @@ impl TxPoolService {
         }
     }
 
+    pub(crate) async fn get_block_template_backlog(
+        &self,
+    ) -> Result<Vec<TemplateBacklog>, AnyError> {
+        if let Some(ref block_assembler) = self.block_assembler {
+            Ok(block_assembler.get_backlogs().await)
+        } else {
+            Err(InternalErrorKind::Config
+                .other("BlockAssembler disabled")
+                .into())
+        }
+    }
+
     pub(crate) async fn fetch_tx_verify_cache(&self, tx: &TransactionView) -> Option<CacheEntry> {
         let guard = self.txs_verify_cache.read().await;
         guard.peek(&tx.witness_hash()).cloned()
@@ -133,25 +151,28 @@ impl TxPoolService {
                 }
 
                 // try to remove conflicted tx here
+                let mut replaced_fees = Capacity::zero();
                 for id in conflicts.iter() {
                     let removed = tx_pool.pool_map.remove_entry_and_descendants(id);
                     for old in removed {
+                        replaced_fees = replaced_fees.safe_add(old.fee).unwrap_or(replaced_fees);
                         debug!(
                             "remove conflict tx {} for RBF by new tx {}",
                             old.transaction().hash(),
                             entry.transaction().hash()
                         );
-                        let reject = Reject::RBFRejected(format!(
-                            "replaced by tx {}",
-                            entry.transaction().hash()
-                        ));
+                        let reject = Reject::RBFReplaced(entry.transaction().hash());
                         // RBF replace successfully, put old transactions into conflicts pool
                         tx_pool.record_conflict(old.transaction().clone());
                         // after removing old tx from tx_pool, we call reject callbacks manually
                         self.callbacks.call_reject(tx_pool, &old, reject);
+                        self.callbacks.call_replaced(&old, &entry);
                     }
                 }
                 let evicted = _submit_entry(tx_pool, status, entry.clone(), &self.callbacks)?;
+                if !conflicts.is_empty() {
+                    tx_pool.record_rbf_replacement(entry.transaction(), replaced_fees);
+                }
                 for evict in evicted {
                     let reject = Reject::Invalidated(format!(
                         "invalidated by tx {}",
@@ -246,6 +267,7 @@ impl TxPoolService {
                 // Same txid means exactly the same transaction, including inputs, outputs, witnesses, etc.
                 // It's also not possible for RBF, reject it directly
                 check_txid_collision(tx_pool, tx)?;
+                check_header_deps_depth(&tx_pool.config, &snapshot, tx)?;
 
                 // Try normal path first, if double-spending check success we don't need RBF check
                 // this make sure RBF won't introduce extra performance cost for hot path
@@ -342,6 +364,7 @@ impl TxPoolService {
         if self.verify_queue_contains(&tx).await || self.orphan_contains(&tx).await {
             return Err(Reject::Duplicated(tx.hash()));
         }
+        self.trace_submitted(&tx.hash(), remote);
 
         if let Some((ret, snapshot)) = self
             ._process_tx(tx.clone(), remote.map(|r| r.0), None)
@@ -358,6 +381,218 @@ impl TxPoolService {
         }
     }
 
+    pub(crate) async fn process_local_tx(
+        &self,
+        tx: TransactionView,
+        check_fee: bool,
+    ) -> Result<Completed, Reject> {
+        if check_fee {
+            self.check_absurd_fee(&tx).await?;
+        }
+        self.process_tx(tx, None).await
+    }
+
+    /// Submits the local txs as a package, either all of them are accepted or none is.
+    ///
+    /// A tx can spend or depend on the outputs of the txs before it in the package. The txs are
+    /// checked the same way as `process_local_tx`, except that they must not conflict with the
+    /// pool, and the rejects of all the invalid txs are returned in the order of the package.
+    pub(crate) async fn process_local_package(
+        &self,
+        txs: Vec<TransactionView>,
+        check_fee: bool,
+    ) -> Result<(), Vec<(Byte32, Reject)>> {
+        let mut ids = HashSet::with_capacity(txs.len());
+        let mut checked = Vec::with_capacity(txs.len());
+        for tx in txs.iter() {
+            let ret = if !ids.insert(tx.proposal_short_id())
+                || self.verify_queue_contains(tx).await
+                || self.orphan_contains(tx).await
+            {
+                Err(Reject::Duplicated(tx.hash()))
+            } else {
+                self.non_contextual_verify(tx, None)
+            };
+            checked.push(ret);
+        }
+        for tx in txs.iter() {
+            self.trace_submitted(&tx.hash(), None);
+        }
+
+        let (pre_checked, snapshot) = self
+            .with_tx_pool_read_lock(|tx_pool, snapshot| {
+                let resolved = tx_pool.resolve_package(&txs);
+                txs.iter()
+                    .zip(resolved)
+                    .zip(checked.iter())
+                    .map(|((tx, resolved), checked)| -> Result<_, Reject> {
+                        checked.clone()?;
+                        check_txid_collision(tx_pool, tx)?;
+                        check_header_deps_depth(&tx_pool.config, &snapshot, tx)?;
+                        let rtx = resolved?;
+                        let tx_size = tx.data().serialized_size_in_block();
+                        let fee = check_tx_fee(tx_pool, &snapshot, &rtx, tx_size)?;
+                        if check_fee {
+                            check_absurd_fee(&tx_pool.config, fee, tx_size)?;
+                        }
+                        let status = get_tx_status(&snapshot, &tx.proposal_short_id());
+                        Ok((rtx, status, fee, tx_size))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+
+        let tip_header = snapshot.tip_header();
+        let max_cycles = self.consensus.max_block_cycles();
+        let mut entries = Vec::with_capacity(txs.len());
+        let mut rejects = Vec::new();
+        for (tx, ret) in txs.iter().zip(pre_checked) {
+            let ret = match ret {
+                Ok((rtx, status, fee, tx_size)) => {
+                    let verify_cache = self.fetch_tx_verify_cache(tx).await;
+                    let tx_env = Arc::new(status.with_env(tip_header));
+                    verify_rtx(
+                        Arc::clone(&snapshot),
+                        Arc::clone(&rtx),
+                        tx_env,
+                        &verify_cache,
+                        max_cycles,
+                        self.tx_pool_config.max_script_group_cycles,
+                        None,
+                    )
+                    .await
+                    .map(|verified| (TxEntry::new(rtx, verified.cycles, fee, tx_size), status))
+                }
+                Err(reject) => Err(reject),
+            };
+            match ret {
+                Ok(entry) => entries.push(entry),
+                Err(reject) => rejects.push((tx.hash(), reject)),
+            }
+        }
+
+        if rejects.is_empty() {
+            let (ret, submit_snapshot) = self.submit_package(snapshot.tip_hash(), entries).await;
+            match ret {
+                Ok(submitted) => {
+                    for (entry, status) in submitted {
+                        self.notify_block_assembler(status).await;
+                        let completed = Completed {
+                            cycles: entry.cycles,
+                            fee: entry.fee,
+                        };
+                        let tx = entry.transaction().clone();
+                        self.after_process(tx, None, &submit_snapshot, &Ok(completed))
+                            .await;
+                    }
+                    return Ok(());
+                }
+                Err(reject) => rejects.push(reject),
+            }
+        }
+
+        for tx in txs.iter() {
+            match rejects.iter().find(|(tx_hash, _)| tx_hash == &tx.hash()) {
+                Some((_, reject)) => {
+                    self.after_process(tx.clone(), None, &snapshot, &Err(reject.clone()))
+                        .await
+                }
+                None => self.lifecycle.record(
+                    &tx.hash(),
+                    TxLifecycleStage::Rejected,
+                    Some("rejected with the package".to_string()),
+                ),
+            }
+        }
+        Err(rejects)
+    }
+
+    // Inserts the verified txs of a package in order. The inserted txs are removed again if any
+    // of them fails or the size limit would evict any of them, the callbacks are called only
+    // after the whole package is committed, so that nothing outside the package is touched by a
+    // rejected one.
+    async fn submit_package(
+        &self,
+        pre_resolve_tip: Byte32,
+        entries: Vec<(TxEntry, TxStatus)>,
+    ) -> (
+        Result<Vec<(TxEntry, TxStatus)>, (Byte32, Reject)>,
+        Arc<Snapshot>,
+    ) {
+        self.with_tx_pool_write_lock(move |tx_pool, snapshot| {
+            let context_changed = pre_resolve_tip != snapshot.tip_hash();
+            let mut submitted = Vec::with_capacity(entries.len());
+            let mut ret = Ok(());
+            for (entry, status) in entries.iter() {
+                let tx_hash = entry.transaction().hash();
+                match add_package_entry(tx_pool, &snapshot, context_changed, entry, *status) {
+                    Ok(status) => submitted.push((entry.clone(), status)),
+                    Err(reject) => {
+                        ret = Err((tx_hash, reject));
+                        break;
+                    }
+                }
+            }
+            if ret.is_ok() {
+                // the package is rejected as a whole if the pool is full and any of it would be
+                // evicted, rather than evicting the others for a part of it
+                let (victims, fee_rate_floor) = tx_pool.size_limit_victims();
+                if let Some((entry, _)) = submitted
+                    .iter()
+                    .find(|(entry, _)| victims.contains(&entry.proposal_short_id()))
+                {
+                    let fee_rate = entry.fee_rate();
+                    ret = Err((
+                        entry.transaction().hash(),
+                        Reject::FullFeeRateFloor(fee_rate, fee_rate_floor),
+                    ));
+                }
+            }
+            if let Err(reject) = ret {
+                for (entry, _) in submitted.iter().rev() {
+                    let short_id = entry.proposal_short_id();
+                    tx_pool.pool_map.remove_entry(&short_id);
+                    tx_pool.fee_estimator.untrack(&short_id);
+                }
+                return Err(reject);
+            }
+
+            let package: HashSet<ProposalShortId> = submitted
+                .iter()
+                .map(|(entry, _)| entry.proposal_short_id())
+                .collect();
+            for (entry, status) in submitted.iter() {
+                call_submitted(&self.callbacks, entry, *status);
+                tx_pool.remove_conflict(&entry.proposal_short_id());
+            }
+            tx_pool.limit_size_keeping(&self.callbacks, None, &package);
+            Ok(submitted)
+        })
+        .await
+    }
+
+    // Checks the fee against `max_absolute_fee` and `max_fee_rate`. The txs which cannot be
+    // resolved are left to `process_tx` to report the error.
+    async fn check_absurd_fee(&self, tx: &TransactionView) -> Result<(), Reject> {
+        let tx_size = tx.data().serialized_size_in_block();
+        let (ret, _snapshot) = self
+            .with_tx_pool_read_lock(|tx_pool, snapshot| {
+                if tx_pool.config.max_absolute_fee.is_none()
+                    && tx_pool.config.max_fee_rate.is_none()
+                {
+                    return Ok(());
+                }
+                match resolve_tx(tx_pool, &snapshot, tx.clone(), true)
+                    .and_then(|(rtx, _status)| calculate_tx_fee(&snapshot, &rtx))
+                {
+                    Ok(fee) => check_absurd_fee(&tx_pool.config, fee, tx_size),
+                    Err(_) => Ok(()),
+                }
+            })
+            .await;
+        ret
+    }
+
     pub(crate) async fn put_recent_reject(&self, tx_hash: &Byte32, reject: &Reject) {
         let mut tx_pool = self.tx_pool.write().await;
         if let Some(ref mut recent_reject) = tx_pool.recent_reject {
@@ -397,6 +632,17 @@ impl TxPoolService {
     ) {
         let tx_hash = tx.hash();
 
+        if let Err(reject) = ret {
+            // the duplicated txs and the orphans are still alive
+            if !matches!(reject, Reject::Duplicated(_)) && !is_missing_input(reject) {
+                self.lifecycle.record(
+                    &tx_hash,
+                    TxLifecycleStage::Rejected,
+                    Some(reject.to_string()),
+                );
+            }
+        }
+
         // The network protocol is switched after tx-pool confirms the cache,
         // there will be no problem with the current state as the choice of the broadcast protocol.
         let with_vm_2023 = {
@@ -425,7 +671,9 @@ impl TxPoolService {
 
         if matches!(
             ret,
-            Err(Reject::RBFRejected(..) | Reject::Resolve(OutPointError::Dead(_)))
+            Err(Reject::RBFRejected(..)
+                | Reject::RBFRejectedByFee(..)
+                | Reject::Resolve(OutPointError::Dead(_)))
         ) {
             let mut tx_pool = self.tx_pool.write().await;
             if tx_pool.pool_map.find_conflict_outpoint(&tx).is_some() {
@@ -678,6 +926,7 @@ impl TxPoolService {
             tx_env,
             &verify_cache,
             max_cycles,
+            self.tx_pool_config.max_script_group_cycles,
             command_rx,
         )
         .await;
@@ -739,6 +988,7 @@ impl TxPoolService {
             tx_env,
             &verify_cache,
             max_cycles,
+            self.tx_pool_config.max_script_group_cycles,
             None,
         )
         .await
@@ -772,8 +1022,12 @@ impl TxPoolService {
             detached.extend(blk.transactions().into_iter().skip(1))
         }
 
+        let mut committed_at = HashMap::new();
         for blk in attached_blocks {
-            attached.extend(blk.transactions().into_iter().skip(1));
+            for tx in blk.transactions().into_iter().skip(1) {
+                committed_at.insert(tx.proposal_short_id(), blk.number());
+                attached.insert(tx);
+            }
         }
         let retain: Vec<TransactionView> = detached.difference(&attached).cloned().collect();
 
@@ -804,6 +1058,7 @@ impl TxPoolService {
             _update_tx_pool_for_reorg(
                 &mut tx_pool,
                 &attached,
+                &committed_at,
                 &detached_headers,
                 detached_proposal_id,
                 snapshot,
@@ -876,8 +1131,31 @@ impl TxPoolService {
         tx: TransactionView,
         remote: Option<(Cycle, PeerIndex)>,
     ) -> Result<bool, Reject> {
-        let mut queue = self.verify_queue.write().await;
-        queue.add_tx(tx, remote)
+        let tx_hash = tx.hash();
+        // the fee rate is only needed to prioritize the verification
+        let fee_rate = if self.tx_pool_config.verify_queue_prioritize {
+            self.tx_pool.read().await.tx_fee_rate(&tx)
+        } else {
+            None
+        };
+        let added = self
+            .verify_queue
+            .write()
+            .await
+            .add_tx_with_fee_rate(tx, remote, fee_rate)?;
+        if added {
+            self.trace_submitted(&tx_hash, remote);
+        }
+        Ok(added)
+    }
+
+    fn trace_submitted(&self, tx_hash: &Byte32, remote: Option<(Cycle, PeerIndex)>) {
+        let detail = match remote {
+            Some((_, peer)) => format!("relayed by peer {peer}"),
+            None => "submitted locally".to_string(),
+        };
+        self.lifecycle
+            .record(tx_hash, TxLifecycleStage::Submitted, Some(detail));
     }
 
     async fn remove_orphan_txs_by_attach<'a>(&self, txs: &LinkedHashSet<TransactionView>) {
@@ -895,11 +1173,44 @@ impl TxPoolService {
         fetched_cache: HashMap<Byte32, CacheEntry>,
     ) {
         let max_cycles = self.tx_pool_config.max_tx_verify_cycles;
+        let bypass_policy = self.tx_pool_config.reorg_readd_bypass_policy;
+        let max_script_group_cycles = if bypass_policy {
+            None
+        } else {
+            self.tx_pool_config.max_script_group_cycles
+        };
         for tx in txs {
             let tx_size = tx.data().serialized_size_in_block();
             let tx_hash = tx.hash();
+            if !bypass_policy && tx_size as u64 > TRANSACTION_SIZE_LIMIT {
+                debug!("readd_detached_tx {} dropped, exceeded size limit", tx_hash);
+                readd_dropped_by_policy("exceeded_size_limit");
+                continue;
+            }
             if let Ok((rtx, status)) = resolve_tx(tx_pool, tx_pool.snapshot(), tx, false) {
-                if let Ok(fee) = check_tx_fee(tx_pool, tx_pool.snapshot(), &rtx, tx_size) {
+                if !bypass_policy {
+                    if let Err(reject) = check_header_deps_depth(
+                        &tx_pool.config,
+                        tx_pool.snapshot(),
+                        &rtx.transaction,
+                    ) {
+                        debug!("readd_detached_tx {} dropped, {}", tx_hash, reject);
+                        readd_dropped_by_policy("recent_header_dep");
+                        continue;
+                    }
+                }
+                let fee = if bypass_policy {
+                    calculate_tx_fee(tx_pool.snapshot(), &rtx)
+                } else {
+                    check_tx_fee(tx_pool, tx_pool.snapshot(), &rtx, tx_size).map_err(|reject| {
+                        if let Reject::LowFeeRate(..) = reject {
+                            debug!("readd_detached_tx {} dropped, {}", tx_hash, reject);
+                            readd_dropped_by_policy("low_fee_rate");
+                        }
+                        reject
+                    })
+                };
+                if let Ok(fee) = fee {
                     let verify_cache = fetched_cache.get(&tx_hash).cloned();
                     let snapshot = tx_pool.cloned_snapshot();
                     let tip_header = snapshot.tip_header();
@@ -910,6 +1221,7 @@ impl TxPoolService {
                         tx_env,
                         &verify_cache,
                         max_cycles,
+                        max_script_group_cycles,
                         None,
                     )
                     .await
@@ -951,6 +1263,48 @@ impl TxPoolService {
         }
     }
 
+    async fn verify_cache_key(&self) -> Byte32 {
+        let epoch = self
+            .tx_pool
+            .read()
+            .await
+            .snapshot()
+            .tip_header()
+            .epoch()
+            .number();
+        verify_cache_key(&self.consensus, epoch)
+    }
+
+    pub(crate) async fn save_verify_cache(&self) {
+        let key = self.verify_cache_key().await;
+        let cache = self.txs_verify_cache.read().await;
+        if let Err(err) =
+            save_verify_cache(&self.tx_pool_config.persisted_verify_cache, &key, &cache)
+        {
+            error!("failed to save verify cache, error: {:?}", err)
+        } else {
+            info!("TxPool verify cache saved, {} entries", cache.len())
+        }
+    }
+
+    pub(crate) async fn load_verify_cache(&self) {
+        let key = self.verify_cache_key().await;
+        match load_verify_cache(&self.tx_pool_config.persisted_verify_cache, &key) {
+            Ok(entries) => {
+                let count = entries.len();
+                let mut cache = self.txs_verify_cache.write().await;
+                for (tx_hash, entry) in entries {
+                    // the entries added since the node started are more recent
+                    if !cache.contains(&tx_hash) {
+                        cache.put(tx_hash, entry);
+                    }
+                }
+                info!("TxPool verify cache loaded, {} entries", count)
+            }
+            Err(err) => error!("failed to load verify cache, error: {:?}", err),
+        }
+    }
+
     // # Notice
     //
     // This method assumes that the inputs transactions are sorted.
@@ -1021,7 +1375,7 @@ fn resolve_tx(
         .map(|rtx| (rtx, tx_status))
 }
 
-fn _submit_entry(
+pub(crate) fn _submit_entry(
     tx_pool: &mut TxPool,
     status: TxStatus,
     entry: TxEntry,
@@ -1032,21 +1386,105 @@ fn _submit_entry(
     let (succ, evicts) = match status {
         TxStatus::Fresh => tx_pool.add_pending(entry.clone())?,
         TxStatus::Gap => tx_pool.add_gap(entry.clone())?,
-        TxStatus::Proposed => tx_pool.add_proposed(entry.clone())?,
+        TxStatus::Proposed => match tx_pool.add_proposed(entry.clone()) {
+            Err(Reject::ExceededMaximumAncestorsCount) => {
+                // keep the tx in pending instead of rejecting it, the long CPFP chains near the
+                // limit can be committed once their ancestors are
+                if tx_pool.add_demoted(entry.clone())? {
+                    debug!("demote proposed tx {} to pending", tx_hash);
+                    record_ancestors_demotion("demoted");
+                    callbacks.call_pending(&entry);
+                }
+                return Ok(Default::default());
+            }
+            ret => ret?,
+        },
     };
     if succ {
-        match status {
-            TxStatus::Fresh => callbacks.call_pending(&entry),
-            TxStatus::Gap => callbacks.call_pending(&entry),
-            TxStatus::Proposed => callbacks.call_proposed(&entry),
-        }
+        call_submitted(callbacks, &entry, status);
     }
     Ok(evicts)
 }
 
-fn _update_tx_pool_for_reorg(
+fn call_submitted(callbacks: &Callbacks, entry: &TxEntry, status: TxStatus) {
+    match status {
+        TxStatus::Fresh => callbacks.call_pending(entry),
+        TxStatus::Gap => {
+            callbacks.call_pending(entry);
+            callbacks.call_gap(entry);
+        }
+        TxStatus::Proposed => callbacks.call_proposed(entry),
+    }
+}
+
+// Adds a tx of a package to the pool without calling the callbacks, the tx is checked again if
+// the tip is changed since it's verified.
+fn add_package_entry(
+    tx_pool: &mut TxPool,
+    snapshot: &Arc<Snapshot>,
+    context_changed: bool,
+    entry: &TxEntry,
+    mut status: TxStatus,
+) -> Result<TxStatus, Reject> {
+    if context_changed {
+        status = check_rtx(tx_pool, snapshot, &entry.rtx)?;
+        let tx_env = status.with_env(snapshot.tip_header());
+        time_relative_verify(Arc::clone(snapshot), Arc::clone(&entry.rtx), tx_env)?;
+    }
+    if let Some(outpoint) = tx_pool.pool_map.find_conflict_outpoint(entry.transaction()) {
+        return Err(Reject::Resolve(OutPointError::Dead(outpoint)));
+    }
+    // `add_entry` would evict the txs referring the inputs as cell deps, which can't be restored
+    // if the package is rejected later
+    if tx_pool
+        .pool_map
+        .exceeds_ancestors_count(entry.transaction())
+    {
+        return Err(Reject::ExceededMaximumAncestorsCount);
+    }
+    match status {
+        TxStatus::Fresh => tx_pool.add_pending(entry.clone())?,
+        TxStatus::Gap => tx_pool.add_gap(entry.clone())?,
+        TxStatus::Proposed => tx_pool.add_proposed(entry.clone())?,
+    };
+    Ok(status)
+}
+
+fn record_ancestors_demotion(kind: &str) {
+    if let Some(metrics) = ckb_metrics::handle() {
+        metrics
+            .ckb_tx_pool_ancestors_demotion
+            .with_label_values(&[kind])
+            .inc();
+    }
+}
+
+// The demoted txs stay in pending until their in-pool ancestors fit max_ancestors_count
+fn try_promote(tx_pool: &mut TxPool, id: &ProposalShortId) -> bool {
+    if !tx_pool.pool_map.is_demoted(id) {
+        return true;
+    }
+    if !tx_pool.pool_map.promote(id) {
+        debug!("demoted tx {:x} still exceeds max_ancestors_count", id);
+        return false;
+    }
+    record_ancestors_demotion("promoted");
+    true
+}
+
+fn readd_dropped_by_policy(reason: &str) {
+    if let Some(metrics) = ckb_metrics::handle() {
+        metrics
+            .ckb_tx_pool_reorg_readd_dropped
+            .with_label_values(&[reason])
+            .inc();
+    }
+}
+
+pub(crate) fn _update_tx_pool_for_reorg(
     tx_pool: &mut TxPool,
     attached: &LinkedHashSet<TransactionView>,
+    committed_at: &HashMap<ProposalShortId, BlockNumber>,
     detached_headers: &HashSet<Byte32>,
     detached_proposal_id: HashSet<ProposalShortId>,
     snapshot: Arc<Snapshot>,
@@ -1060,7 +1498,14 @@ fn _update_tx_pool_for_reorg(
     // which is both expired and committed at the one time(commit at its end of commit-window),
     // we should treat it as a committed and not re-put into pending-pool. So we should ensure
     // that involves `remove_committed_txs` before `remove_expired`.
-    tx_pool.remove_committed_txs(attached.iter(), callbacks, detached_headers);
+    let committed = attached.iter().map(|tx| {
+        let block_number = committed_at
+            .get(&tx.proposal_short_id())
+            .copied()
+            .unwrap_or_else(|| snapshot.tip_number());
+        (tx, block_number)
+    });
+    tx_pool.remove_committed_txs(committed, callbacks, detached_headers);
     tx_pool.remove_by_detached_proposal(detached_proposal_id.iter());
 
     // mine mode:
@@ -1073,13 +1518,18 @@ fn _update_tx_pool_for_reorg(
         for entry in tx_pool.pool_map.entries.get_by_status(&Status::Gap) {
             let short_id = entry.inner.proposal_short_id();
             if snapshot.proposals().contains_proposed(&short_id) {
-                proposals.push((short_id, entry.inner.clone()));
+                if let Some(full) = tx_pool.pool_map.full_entry(&entry.inner) {
+                    proposals.push((short_id, full));
+                }
             }
         }
 
         for entry in tx_pool.pool_map.entries.get_by_status(&Status::Pending) {
             let short_id = entry.inner.proposal_short_id();
-            let elem = (short_id.clone(), entry.inner.clone());
+            let Some(full) = tx_pool.pool_map.full_entry(&entry.inner) else {
+                continue;
+            };
+            let elem = (short_id.clone(), full);
             if snapshot.proposals().contains_proposed(&short_id) {
                 proposals.push(elem);
             } else if snapshot.proposals().contains_gap(&short_id) {
@@ -1088,6 +1538,9 @@ fn _update_tx_pool_for_reorg(
         }
 
         for (id, entry) in proposals {
+            if !try_promote(tx_pool, &id) {
+                continue;
+            }
             debug!("begin to proposed: {:x}", id);
             if let Err(e) = tx_pool.proposed_rtx(&id) {
                 debug!(
@@ -1102,6 +1555,9 @@ fn _update_tx_pool_for_reorg(
         }
 
         for (id, entry) in gaps {
+            if !try_promote(tx_pool, &id) {
+                continue;
+            }
             debug!("begin to gap: {:x}", id);
             if let Err(e) = tx_pool.gap_rtx(&id) {
                 debug!(
@@ -1110,6 +1566,8 @@ fn _update_tx_pool_for_reorg(
                     e
                 );
                 callbacks.call_reject(tx_pool, &entry, e.clone());
+            } else {
+                callbacks.call_gap(&entry)
             }
         }
     }
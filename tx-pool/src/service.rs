@@ -1,12 +1,18 @@
 //! Tx-pool background service
 
 use crate::block_assembler::{self, BlockAssembler};
-use crate::callback::{Callbacks, PendingCallback, ProposedCallback, RejectCallback};
+use crate::callback::{
+    Callbacks, CommittedCallback, GapCallback, PendingCallback, PoolEventListener,
+    ProposedCallback, RejectCallback,
+};
+use crate::component::lifecycle::{OtlpExporter, TxLifecycleTracer};
+use crate::component::lock_stats::MAX_TOP_LOCK_SCRIPTS;
 use crate::component::orphan::OrphanPool;
 use crate::component::pool_map::{PoolEntry, Status};
+use crate::component::read_view::PoolReadView;
 use crate::component::verify_queue::VerifyQueue;
 use crate::error::{handle_recv_error, handle_send_cmd_error, handle_try_send_error};
-use crate::pool::TxPool;
+use crate::pool::{ScoreCursor, TxPool};
 use crate::util::after_delay_window;
 use crate::verify_mgr::VerifyMgr;
 use ckb_app_config::{BlockAssemblerConfig, TxPoolConfig};
@@ -17,18 +23,24 @@ use ckb_error::AnyError;
 use ckb_jsonrpc_types::BlockTemplate;
 use ckb_logger::error;
 use ckb_logger::info;
+use ckb_logger::warn;
 use ckb_network::{NetworkController, PeerIndex};
 use ckb_script::ChunkCommand;
 use ckb_snapshot::Snapshot;
 use ckb_stop_handler::new_tokio_exit_rx;
 use ckb_store::ChainStore;
+use ckb_systemtime::unix_time_as_millis;
 use ckb_types::core::cell::{CellProvider, CellStatus, OverlayCellProvider};
-use ckb_types::core::tx_pool::{EntryCompleted, PoolTxDetailInfo, TransactionWithStatus, TxStatus};
+use ckb_types::core::tx_pool::{
+    CellPoolStatus, EntryCompleted, PoolPolicy, PoolTxDetailInfo, PoolTxGraph,
+    ProposalWindowSimulation, ReplacementSimulation, TemplateBacklog, TransactionWithStatus,
+    TxDiagnosis, TxDiagnosisIssue, TxDiagnosisIssueKind, TxFeeBreakdown, TxLifecycle, TxStatus,
+};
 use ckb_types::packed::OutPoint;
 use ckb_types::{
     core::{
         tx_pool::{Reject, TxPoolEntryInfo, TxPoolIds, TxPoolInfo, TRANSACTION_SIZE_LIMIT},
-        BlockView, Cycle, TransactionView, UncleBlockView, Version,
+        BlockNumber, BlockView, Cycle, FeeRate, TransactionView, UncleBlockView, Version,
     },
     packed::{Byte32, ProposalShortId},
 };
@@ -51,6 +63,8 @@ use crate::{component::entry::TxEntry, process::PlugTarget};
 
 pub(crate) const DEFAULT_CHANNEL_SIZE: usize = 512;
 pub(crate) const BLOCK_ASSEMBLER_CHANNEL_SIZE: usize = 100;
+// the txs read under the tx-pool lock at a time by `read_pages`
+const READ_PAGE_SIZE: usize = 1000;
 
 pub(crate) struct Request<A, R> {
     pub responder: oneshot::Sender<R>,
@@ -78,8 +92,10 @@ impl<A> Notify<A> {
 
 pub(crate) type BlockTemplateResult = Result<BlockTemplate, AnyError>;
 type BlockTemplateArgs = (Option<u64>, Option<u64>, Option<Version>);
+pub(crate) type TemplateBacklogResult = Result<Vec<TemplateBacklog>, AnyError>;
 
 pub(crate) type SubmitTxResult = Result<(), Reject>;
+pub(crate) type SubmitPackageResult = Result<(), Vec<(Byte32, Reject)>>;
 
 pub(crate) type TestAcceptTxResult = Result<EntryCompleted, Reject>;
 
@@ -96,7 +112,11 @@ pub(crate) type ChainReorgArgs = (
 
 pub(crate) enum Message {
     BlockTemplate(Request<BlockTemplateArgs, BlockTemplateResult>),
-    SubmitLocalTx(Request<TransactionView, SubmitTxResult>),
+    GetTemplateBacklog(Request<(), TemplateBacklogResult>),
+    // the flag checks the fee against `max_absolute_fee` and `max_fee_rate`
+    SubmitLocalTx(Request<(TransactionView, bool), SubmitTxResult>),
+    // the flag is the same as `SubmitLocalTx`
+    SubmitLocalPackage(Request<(Vec<TransactionView>, bool), SubmitPackageResult>),
     RemoveLocalTx(Request<Byte32, bool>),
     TestAcceptTx(Request<TransactionView, TestAcceptTxResult>),
     SubmitRemoteTx(Request<(TransactionView, Cycle, PeerIndex), ()>),
@@ -104,16 +124,27 @@ pub(crate) enum Message {
     FreshProposalsFilter(Request<Vec<ProposalShortId>, Vec<ProposalShortId>>),
     FetchTxs(Request<HashSet<ProposalShortId>, HashMap<ProposalShortId, TransactionView>>),
     FetchTxsWithCycles(Request<HashSet<ProposalShortId>, FetchTxsWithCyclesResult>),
+    FilterRecentlyReplaced(Request<Vec<TransactionView>, HashSet<ProposalShortId>>),
     GetTxPoolInfo(Request<(), TxPoolInfo>),
+    GetPoolPolicy(Request<(), PoolPolicy>),
+    ReloadPoolPolicy(Request<Box<TxPoolConfig>, PoolPolicy>),
     GetLiveCell(Request<(OutPoint, bool), CellStatus>),
+    GetLiveCellWithPoolStatus(Request<(OutPoint, bool, bool), (CellStatus, CellPoolStatus)>),
     GetTxStatus(Request<Byte32, GetTxStatusResult>),
     GetTransactionWithStatus(Request<Byte32, GetTransactionWithStatusResult>),
     NewUncle(Notify<UncleBlockView>),
     ClearPool(Request<Arc<Snapshot>, ()>),
-    GetAllEntryInfo(Request<(), TxPoolEntryInfo>),
-    GetAllIds(Request<(), TxPoolIds>),
+    GetAllEntryInfo(Request<(), Arc<TxPoolEntryInfo>>),
+    GetAllIds(Request<(), Arc<TxPoolIds>>),
     SavePool(Request<(), ()>),
     GetPoolTxDetails(Request<Byte32, PoolTxDetailInfo>),
+    GetPoolTxGraph(Request<(Byte32, usize), Option<PoolTxGraph>>),
+    DiagnoseTx(Request<Byte32, TxDiagnosis>),
+    GetTxFeeBreakdown(Request<TransactionView, Result<TxFeeBreakdown, Reject>>),
+    SimulateReplacement(Request<TransactionView, Result<ReplacementSimulation, Reject>>),
+    SimulateProposalWindow(Request<TransactionView, Result<ProposalWindowSimulation, Reject>>),
+    PrioritiseTx(Request<(Byte32, u64), bool>),
+    EstimateFeeRate(Request<BlockNumber, Option<FeeRate>>),
 
     // test
     #[cfg(feature = "internal")]
@@ -141,6 +172,9 @@ pub struct TxPoolController {
     chunk_tx: Arc<watch::Sender<ChunkCommand>>,
     handle: Handle,
     started: Arc<AtomicBool>,
+    enabled: bool,
+    read_view: Arc<PoolReadView>,
+    lifecycle: Arc<TxLifecycleTracer>,
 }
 
 macro_rules! send_message {
@@ -179,6 +213,11 @@ impl TxPoolController {
         self.started.load(Ordering::Relaxed)
     }
 
+    /// Return whether tx-pool is enabled by `tx_pool.enable`, a disabled tx-pool is never started
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     /// Set tx-pool service started, should only used for test
     #[cfg(feature = "internal")]
     pub fn set_service_started(&self, v: bool) {
@@ -204,6 +243,11 @@ impl TxPoolController {
         )
     }
 
+    /// Return the txs left out of the recent block templates, the latest first
+    pub fn get_block_template_backlog(&self) -> Result<TemplateBacklogResult, AnyError> {
+        send_message!(self, GetTemplateBacklog, ())
+    }
+
     /// Notify new uncle
     pub fn notify_new_uncle(&self, uncle: UncleBlockView) -> Result<(), AnyError> {
         send_notify!(self, NewUncle, uncle)
@@ -234,7 +278,26 @@ impl TxPoolController {
 
     /// Submit local tx to tx-pool
     pub fn submit_local_tx(&self, tx: TransactionView) -> Result<SubmitTxResult, AnyError> {
-        send_message!(self, SubmitLocalTx, tx)
+        send_message!(self, SubmitLocalTx, (tx, false))
+    }
+
+    /// Submit local tx to tx-pool, and reject it if the fee exceeds the config option
+    /// `max_absolute_fee` or `max_fee_rate`
+    pub fn submit_local_tx_with_fee_check(
+        &self,
+        tx: TransactionView,
+    ) -> Result<SubmitTxResult, AnyError> {
+        send_message!(self, SubmitLocalTx, (tx, true))
+    }
+
+    /// Submit local txs to tx-pool as a package, either all of them are accepted or none is,
+    /// the fee is checked against `max_absolute_fee` and `max_fee_rate` if `check_fee` is set
+    pub fn submit_local_package(
+        &self,
+        txs: Vec<TransactionView>,
+        check_fee: bool,
+    ) -> Result<SubmitPackageResult, AnyError> {
+        send_message!(self, SubmitLocalPackage, (txs, check_fee))
     }
 
     /// test if a tx can be accepted by tx-pool
@@ -269,6 +332,16 @@ impl TxPoolController {
         send_message!(self, GetTxPoolInfo, ())
     }
 
+    /// Return the effective relay and admission policy
+    pub fn get_pool_policy(&self) -> Result<PoolPolicy, AnyError> {
+        send_message!(self, GetPoolPolicy, ())
+    }
+
+    /// Applies the hot-reloadable policy options in `config`, returns the new policy
+    pub fn reload_pool_policy(&self, config: TxPoolConfig) -> Result<PoolPolicy, AnyError> {
+        send_message!(self, ReloadPoolPolicy, Box::new(config))
+    }
+
     /// Return tx-pool information
     pub fn get_live_cell(
         &self,
@@ -278,6 +351,21 @@ impl TxPoolController {
         send_message!(self, GetLiveCell, (out_point, with_data))
     }
 
+    /// Return the cell status, and how the pool txs create or spend the cell, both are read from
+    /// the same pool state
+    pub fn get_live_cell_with_pool_status(
+        &self,
+        out_point: OutPoint,
+        with_data: bool,
+        include_tx_pool: bool,
+    ) -> Result<(CellStatus, CellPoolStatus), AnyError> {
+        send_message!(
+            self,
+            GetLiveCellWithPoolStatus,
+            (out_point, with_data, include_tx_pool)
+        )
+    }
+
     /// Return fresh proposals
     pub fn fresh_proposals_filter(
         &self,
@@ -308,6 +396,15 @@ impl TxPoolController {
         send_message!(self, FetchTxs, short_ids)
     }
 
+    /// Return the short ids of txs whose inputs have been replaced by RBF recently
+    /// Mainly for prefilling compact block, since peers likely miss these txs
+    pub fn filter_recently_replaced(
+        &self,
+        txs: Vec<TransactionView>,
+    ) -> Result<HashSet<ProposalShortId>, AnyError> {
+        send_message!(self, FilterRecentlyReplaced, txs)
+    }
+
     /// Return txs with cycles
     /// Mainly for relay transactions
     pub fn fetch_txs_with_cycles(
@@ -322,13 +419,22 @@ impl TxPoolController {
         send_message!(self, ClearPool, new_snapshot)
     }
 
-    /// TODO(doc): @zhangsoledad
-    pub fn get_all_entry_info(&self) -> Result<TxPoolEntryInfo, AnyError> {
+    /// Returns the entry info of all the in-pool txs.
+    ///
+    /// It's served from the read view without contending with the writers, unless the pool has
+    /// been mutated since the view was built.
+    pub fn get_all_entry_info(&self) -> Result<Arc<TxPoolEntryInfo>, AnyError> {
+        if let Some(info) = self.read_view.entry_info() {
+            return Ok(info);
+        }
         send_message!(self, GetAllEntryInfo, ())
     }
 
-    /// TODO(doc): @zhangsoledad
-    pub fn get_all_ids(&self) -> Result<TxPoolIds, AnyError> {
+    /// Returns the ids of all the in-pool txs, see [`get_all_entry_info`](Self::get_all_entry_info).
+    pub fn get_all_ids(&self) -> Result<Arc<TxPoolIds>, AnyError> {
+        if let Some(ids) = self.read_view.ids() {
+            return Ok(ids);
+        }
         send_message!(self, GetAllIds, ())
     }
 
@@ -337,6 +443,68 @@ impl TxPoolController {
         send_message!(self, GetPoolTxDetails, tx_hash)
     }
 
+    /// query the in-pool ancestors and descendants of a transaction, up to `max_depth` links away
+    pub fn get_tx_graph(
+        &self,
+        tx_hash: Byte32,
+        max_depth: usize,
+    ) -> Result<Option<PoolTxGraph>, AnyError> {
+        send_message!(self, GetPoolTxGraph, (tx_hash, max_depth))
+    }
+
+    /// explains why a transaction is not progressing
+    pub fn diagnose_tx(&self, tx_hash: Byte32) -> Result<TxDiagnosis, AnyError> {
+        send_message!(self, DiagnoseTx, tx_hash)
+    }
+
+    /// Returns the lifecycle events of a transaction, `None` if it's not traced.
+    ///
+    /// It's served without taking the tx-pool lock.
+    pub fn get_tx_lifecycle(&self, tx_hash: &Byte32) -> Option<TxLifecycle> {
+        self.lifecycle.get(tx_hash)
+    }
+
+    /// breaks down the capacities and the fee of a transaction, with the fee thresholds of the pool
+    pub fn get_tx_fee_breakdown(
+        &self,
+        tx: TransactionView,
+    ) -> Result<Result<TxFeeBreakdown, Reject>, AnyError> {
+        send_message!(self, GetTxFeeBreakdown, tx)
+    }
+
+    /// simulates the replacement of the conflicting txs in the pool by a transaction, without
+    /// submitting it
+    pub fn simulate_replacement(
+        &self,
+        tx: TransactionView,
+    ) -> Result<Result<ReplacementSimulation, Reject>, AnyError> {
+        send_message!(self, SimulateReplacement, tx)
+    }
+
+    /// simulates when a transaction could be proposed and committed if it were submitted now,
+    /// without submitting it
+    pub fn simulate_proposal_window(
+        &self,
+        tx: TransactionView,
+    ) -> Result<Result<ProposalWindowSimulation, Reject>, AnyError> {
+        send_message!(self, SimulateProposalWindow, tx)
+    }
+
+    /// Sets the virtual fee delta of an in-pool tx, which only affects how the tx is sorted and
+    /// packaged
+    pub fn prioritise_tx(&self, tx_hash: Byte32, fee_delta: u64) -> Result<bool, AnyError> {
+        send_message!(self, PrioritiseTx, (tx_hash, fee_delta))
+    }
+
+    /// Estimates the fee rate for a tx to be committed within the target blocks, returns `None` if
+    /// there are not enough committed txs to tell
+    pub fn estimate_fee_rate(
+        &self,
+        target_blocks: BlockNumber,
+    ) -> Result<Option<FeeRate>, AnyError> {
+        send_message!(self, EstimateFeeRate, target_blocks)
+    }
+
     /// Saves tx pool into disk.
     pub fn save_pool(&self) -> Result<(), AnyError> {
         info!("Please be patient, tx-pool are saving data into disk ...");
@@ -416,6 +584,8 @@ pub struct TxPoolServiceBuilder {
     pub(crate) tx_relay_sender: ckb_channel::Sender<TxVerificationResult>,
     pub(crate) chunk_rx: watch::Receiver<ChunkCommand>,
     pub(crate) started: Arc<AtomicBool>,
+    pub(crate) read_view: Arc<PoolReadView>,
+    pub(crate) lifecycle: Arc<TxLifecycleTracer>,
     pub(crate) block_assembler_channel: (
         mpsc::Sender<BlockAssemblerMessage>,
         mpsc::Receiver<BlockAssemblerMessage>,
@@ -438,6 +608,10 @@ impl TxPoolServiceBuilder {
         let signal_receiver: CancellationToken = new_tokio_exit_rx();
         let (chunk_tx, chunk_rx) = watch::channel(ChunkCommand::Resume);
         let started = Arc::new(AtomicBool::new(false));
+        let read_view = Arc::new(PoolReadView::new());
+        let lifecycle = Arc::new(build_lifecycle_tracer(&tx_pool_config, handle));
+        let mut callbacks = Callbacks::new();
+        callbacks.register_listener(Arc::clone(&lifecycle) as Arc<dyn PoolEventListener>);
 
         let controller = TxPoolController {
             sender,
@@ -445,6 +619,9 @@ impl TxPoolServiceBuilder {
             handle: handle.clone(),
             chunk_tx: Arc::new(chunk_tx),
             started: Arc::clone(&started),
+            enabled: tx_pool_config.enable,
+            read_view: Arc::clone(&read_view),
+            lifecycle: Arc::clone(&lifecycle),
         };
 
         let block_assembler =
@@ -455,7 +632,7 @@ impl TxPoolServiceBuilder {
             snapshot,
             block_assembler,
             txs_verify_cache,
-            callbacks: Callbacks::new(),
+            callbacks,
             receiver,
             reorg_receiver,
             signal_receiver,
@@ -463,6 +640,8 @@ impl TxPoolServiceBuilder {
             tx_relay_sender,
             chunk_rx,
             started,
+            read_view,
+            lifecycle,
             block_assembler_channel,
         };
 
@@ -479,26 +658,53 @@ impl TxPoolServiceBuilder {
         self.tx_relay_sender.clone()
     }
 
+    /// Register new gap callback
+    pub fn register_gap(&mut self, callback: GapCallback) {
+        self.callbacks.register_gap(callback);
+    }
+
     /// Register new proposed callback
     pub fn register_proposed(&mut self, callback: ProposedCallback) {
         self.callbacks.register_proposed(callback);
     }
 
+    /// Register new committed callback
+    pub fn register_committed(&mut self, callback: CommittedCallback) {
+        self.callbacks.register_committed(callback);
+    }
+
     /// Register new abandon callback
     pub fn register_reject(&mut self, callback: RejectCallback) {
         self.callbacks.register_reject(callback);
     }
 
+    /// Register new event listener, see [`PoolEventListener`]
+    pub fn register_listener(&mut self, listener: Arc<dyn PoolEventListener>) {
+        self.callbacks.register_listener(listener);
+    }
+
     /// Start a background thread tx-pool service by taking ownership of the Builder, and returns a TxPoolController.
     pub fn start(self, network: NetworkController) {
         let consensus = self.snapshot.cloned_consensus();
         let after_delay_window = after_delay_window(&self.snapshot);
 
-        let verify_queue = Arc::new(RwLock::new(VerifyQueue::new(
-            self.tx_pool_config.max_tx_verify_cycles,
-        )));
+        let verify_queue = Arc::new(RwLock::new(
+            VerifyQueue::new(self.tx_pool_config.max_tx_verify_cycles)
+                .with_limits(
+                    self.tx_pool_config.max_verify_queue_size,
+                    self.tx_pool_config.max_verify_queue_cycles,
+                )
+                .with_fairness(
+                    self.tx_pool_config.verify_queue_local_weight,
+                    self.tx_pool_config.verify_queue_remote_weight,
+                    self.tx_pool_config.verify_queue_max_wait_ms,
+                )
+                .with_priority(self.tx_pool_config.verify_queue_prioritize),
+        ));
 
-        let tx_pool = TxPool::new(self.tx_pool_config, self.snapshot);
+        let mut tx_pool = TxPool::new(self.tx_pool_config, self.snapshot);
+        tx_pool.pool_map.version = self.read_view.version_counter();
+        tx_pool.lifecycle = Arc::clone(&self.lifecycle);
         let txs = match tx_pool.load_from_file() {
             Ok(txs) => txs,
             Err(e) => {
@@ -523,8 +729,14 @@ impl TxPoolServiceBuilder {
             consensus,
             delay: Arc::new(RwLock::new(LinkedHashMap::new())),
             after_delay: Arc::new(AtomicBool::new(after_delay_window)),
+            read_view: self.read_view,
+            lifecycle: self.lifecycle,
         };
 
+        let verify_cache_service = service.clone();
+        self.handle
+            .spawn(async move { verify_cache_service.load_verify_cache().await });
+
         let mut verify_mgr =
             VerifyMgr::new(service.clone(), self.chunk_rx, self.signal_receiver.clone());
         self.handle.spawn(async move { verify_mgr.run().await });
@@ -545,6 +757,7 @@ impl TxPoolServiceBuilder {
                     _ = signal_receiver.cancelled() => {
                         info!("TxPool is saving, please wait...");
                         process_service.save_pool().await;
+                        process_service.save_verify_cache().await;
                         info!("TxPool process_service exit now");
                         break
                     },
@@ -674,6 +887,8 @@ pub(crate) struct TxPoolService {
     pub(crate) block_assembler_sender: mpsc::Sender<BlockAssemblerMessage>,
     pub(crate) delay: Arc<RwLock<LinkedHashMap<ProposalShortId, TransactionView>>>,
     pub(crate) after_delay: Arc<AtomicBool>,
+    pub(crate) read_view: Arc<PoolReadView>,
+    pub(crate) lifecycle: Arc<TxLifecycleTracer>,
 }
 
 /// tx verification result
@@ -701,6 +916,22 @@ pub enum TxVerificationResult {
     },
 }
 
+fn build_lifecycle_tracer(config: &TxPoolConfig, handle: &Handle) -> TxLifecycleTracer {
+    let tracer = TxLifecycleTracer::new(config.max_traced_txs);
+    match config.trace_otlp_endpoint {
+        Some(ref endpoint) if config.max_traced_txs > 0 => {
+            match OtlpExporter::new(endpoint, handle.clone()) {
+                Ok(exporter) => tracer.with_exporter(exporter),
+                Err(err) => {
+                    warn!("{}, the tx lifecycles are not exported", err);
+                    tracer
+                }
+            }
+        }
+        _ => tracer,
+    }
+}
+
 #[allow(clippy::cognitive_complexity)]
 async fn process(mut service: TxPoolService, message: Message) {
     match message {
@@ -710,6 +941,22 @@ async fn process(mut service: TxPoolService, message: Message) {
                 error!("Responder sending get_tx_pool_info failed {:?}", e);
             };
         }
+        Message::GetPoolPolicy(Request { responder, .. }) => {
+            let policy = service.tx_pool.read().await.policy();
+            if let Err(e) = responder.send(policy) {
+                error!("Responder sending get_pool_policy failed {:?}", e);
+            };
+        }
+        Message::ReloadPoolPolicy(Request {
+            responder,
+            arguments: config,
+        }) => {
+            let policy = service.tx_pool.write().await.reload_policy(&config);
+            info!("Tx-pool policy reloaded, version {}", policy.version);
+            if let Err(e) = responder.send(policy) {
+                error!("Responder sending reload_pool_policy failed {:?}", e);
+            };
+        }
         Message::GetLiveCell(Request {
             responder,
             arguments: (out_point, with_data),
@@ -719,6 +966,20 @@ async fn process(mut service: TxPoolService, message: Message) {
                 error!("Responder sending get_live_cell failed {:?}", e);
             };
         }
+        Message::GetLiveCellWithPoolStatus(Request {
+            responder,
+            arguments: (out_point, with_data, include_tx_pool),
+        }) => {
+            let status = service
+                .get_live_cell_with_pool_status(out_point, with_data, include_tx_pool)
+                .await;
+            if let Err(e) = responder.send(status) {
+                error!(
+                    "Responder sending get_live_cell_with_pool_status failed {:?}",
+                    e
+                );
+            };
+        }
         Message::BlockTemplate(Request {
             responder,
             arguments: (_bytes_limit, _proposals_limit, _max_version),
@@ -728,15 +989,30 @@ async fn process(mut service: TxPoolService, message: Message) {
                 error!("Responder sending block_template_result failed {:?}", e);
             };
         }
+        Message::GetTemplateBacklog(Request { responder, .. }) => {
+            let backlog_result = service.get_block_template_backlog().await;
+            if let Err(e) = responder.send(backlog_result) {
+                error!("Responder sending template_backlog_result failed {:?}", e);
+            };
+        }
         Message::SubmitLocalTx(Request {
             responder,
-            arguments: tx,
+            arguments: (tx, check_fee),
         }) => {
-            let result = service.process_tx(tx, None).await.map(|_| ());
+            let result = service.process_local_tx(tx, check_fee).await.map(|_| ());
             if let Err(e) = responder.send(result) {
                 error!("Responder sending submit_tx result failed {:?}", e);
             };
         }
+        Message::SubmitLocalPackage(Request {
+            responder,
+            arguments: (txs, check_fee),
+        }) => {
+            let result = service.process_local_package(txs, check_fee).await;
+            if let Err(e) = responder.send(result) {
+                error!("Responder sending submit_package result failed {:?}", e);
+            };
+        }
         Message::SubmitLocalTestTx(Request {
             responder,
             arguments: tx,
@@ -845,7 +1121,7 @@ async fn process(mut service: TxPoolService, message: Message) {
                     (TxStatus::Pending, tx_pool.min_replace_fee(entry))
                 };
                 Ok(TransactionWithStatus::with_status(
-                    Some(entry.transaction().clone()),
+                    tx_pool.pool_map.full_transaction(entry),
                     entry.cycles,
                     entry.timestamp,
                     tx_status,
@@ -902,6 +1178,20 @@ async fn process(mut service: TxPoolService, message: Message) {
                 error!("Responder sending fetch_txs_with_cycles failed {:?}", e);
             };
         }
+        Message::FilterRecentlyReplaced(Request {
+            responder,
+            arguments: txs,
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let short_ids = txs
+                .iter()
+                .filter(|tx| tx_pool.is_recently_replaced(tx))
+                .map(|tx| tx.proposal_short_id())
+                .collect();
+            if let Err(e) = responder.send(short_ids) {
+                error!("Responder sending filter_recently_replaced failed {:?}", e);
+            };
+        }
         Message::NewUncle(Notify { arguments: uncle }) => {
             service.receive_candidate_uncle(uncle).await;
         }
@@ -927,16 +1217,131 @@ async fn process(mut service: TxPoolService, message: Message) {
                 error!("responder send get_pool_tx_details failed {:?}", e)
             };
         }
-        Message::GetAllEntryInfo(Request { responder, .. }) => {
+        Message::GetPoolTxGraph(Request {
+            responder,
+            arguments: (tx_hash, max_depth),
+        }) => {
             let tx_pool = service.tx_pool.read().await;
-            let info = tx_pool.get_all_entry_info();
+            let id = ProposalShortId::from_tx_hash(&tx_hash);
+            let graph = tx_pool.get_tx_graph(&id, max_depth);
+            if let Err(e) = responder.send(graph) {
+                error!("responder send get_pool_tx_graph failed {:?}", e)
+            };
+        }
+        Message::DiagnoseTx(Request {
+            responder,
+            arguments: tx_hash,
+        }) => {
+            let diagnosis = service.diagnose_tx(tx_hash).await;
+            if let Err(e) = responder.send(diagnosis) {
+                error!("responder send diagnose_tx failed {:?}", e)
+            };
+        }
+        Message::GetTxFeeBreakdown(Request {
+            responder,
+            arguments: tx,
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let breakdown = tx_pool.fee_breakdown(&tx);
+            if let Err(e) = responder.send(breakdown) {
+                error!("responder send get_tx_fee_breakdown failed {:?}", e)
+            };
+        }
+        Message::SimulateReplacement(Request {
+            responder,
+            arguments: tx,
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let simulation = tx_pool.simulate_replacement(&tx);
+            if let Err(e) = responder.send(simulation) {
+                error!("responder send simulate_replacement failed {:?}", e)
+            };
+        }
+        Message::SimulateProposalWindow(Request {
+            responder,
+            arguments: tx,
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let simulation = tx_pool.simulate_proposal_window(&tx);
+            if let Err(e) = responder.send(simulation) {
+                error!("responder send simulate_proposal_window failed {:?}", e)
+            };
+        }
+        Message::PrioritiseTx(Request {
+            responder,
+            arguments: (tx_hash, fee_delta),
+        }) => {
+            let mut tx_pool = service.tx_pool.write().await;
+            let id = ProposalShortId::from_tx_hash(&tx_hash);
+            let found = tx_pool.prioritise_tx(&id, fee_delta);
+            if let Err(e) = responder.send(found) {
+                error!("responder send prioritise_tx failed {:?}", e)
+            };
+        }
+        Message::EstimateFeeRate(Request {
+            responder,
+            arguments: target_blocks,
+        }) => {
+            let tx_pool = service.tx_pool.read().await;
+            let fee_rate = tx_pool.estimate_fee_rate(target_blocks);
+            if let Err(e) = responder.send(fee_rate) {
+                error!("responder send estimate_fee_rate failed {:?}", e)
+            };
+        }
+        Message::GetAllEntryInfo(Request { responder, .. }) => {
+            // another request may have rebuilt it while this one was queued
+            let info = match service.read_view.entry_info() {
+                Some(info) => info,
+                None => {
+                    let version = service.read_view.current_version();
+                    let pending = service
+                        .read_pages(|tx_pool, after| {
+                            tx_pool.entry_info_page(false, after, READ_PAGE_SIZE)
+                        })
+                        .await;
+                    let proposed = service
+                        .read_pages(|tx_pool, after| {
+                            tx_pool.entry_info_page(true, after, READ_PAGE_SIZE)
+                        })
+                        .await;
+                    let conflicted = service.tx_pool.read().await.conflicted_ids();
+                    let info = Arc::new(TxPoolEntryInfo {
+                        pending: pending.into_iter().collect(),
+                        proposed: proposed.into_iter().collect(),
+                        conflicted,
+                    });
+                    // the pages are a consistent copy only if the pool is not mutated in between
+                    if service.read_view.current_version() == version {
+                        service
+                            .read_view
+                            .publish_entry_info(version, Arc::clone(&info));
+                    }
+                    info
+                }
+            };
             if let Err(e) = responder.send(info) {
                 error!("Responder sending get_all_entry_info failed {:?}", e)
             };
         }
         Message::GetAllIds(Request { responder, .. }) => {
-            let tx_pool = service.tx_pool.read().await;
-            let ids = tx_pool.get_ids();
+            let ids = match service.read_view.ids() {
+                Some(ids) => ids,
+                None => {
+                    let version = service.read_view.current_version();
+                    let pending = service
+                        .read_pages(|tx_pool, after| tx_pool.ids_page(false, after, READ_PAGE_SIZE))
+                        .await;
+                    let proposed = service
+                        .read_pages(|tx_pool, after| tx_pool.ids_page(true, after, READ_PAGE_SIZE))
+                        .await;
+                    let ids = Arc::new(TxPoolIds { pending, proposed });
+                    // the pages are a consistent copy only if the pool is not mutated in between
+                    if service.read_view.current_version() == version {
+                        service.read_view.publish_ids(version, Arc::clone(&ids));
+                    }
+                    ids
+                }
+            };
             if let Err(e) = responder.send(ids) {
                 error!("Responder sending get_ids failed {:?}", e)
             };
@@ -978,6 +1383,25 @@ async fn process(mut service: TxPoolService, message: Message) {
 }
 
 impl TxPoolService {
+    /// Reads the in-pool txs page by page, releasing the tx-pool lock between the pages so the
+    /// writers are not held back by the reads of a large pool. The txs staying in the pool are
+    /// read exactly once, see `PoolMap::score_sorted_page`.
+    async fn read_pages<T>(
+        &self,
+        read_page: impl Fn(&TxPool, Option<&ScoreCursor>) -> (Vec<T>, Option<ScoreCursor>),
+    ) -> Vec<T> {
+        let mut items = Vec::new();
+        let mut after = None;
+        loop {
+            let (page, next) = read_page(&*self.tx_pool.read().await, after.as_ref());
+            items.extend(page);
+            match next {
+                Some(next) => after = Some(next),
+                None => return items,
+            }
+        }
+    }
+
     /// Tx-pool information
     async fn info(&self) -> TxPoolInfo {
         let tx_pool = self.tx_pool.read().await;
@@ -992,26 +1416,109 @@ impl TxPoolService {
             orphan_size: orphan.len(),
             total_tx_size: tx_pool.pool_map.total_tx_size,
             total_tx_cycles: tx_pool.pool_map.total_tx_cycles,
-            min_fee_rate: self.tx_pool_config.min_fee_rate,
-            min_rbf_rate: self.tx_pool_config.min_rbf_rate,
+            min_fee_rate: tx_pool.config.min_fee_rate,
+            min_rbf_rate: tx_pool.config.min_rbf_rate,
             last_txs_updated_at: tx_pool.pool_map.get_max_update_time(),
             tx_size_limit: TRANSACTION_SIZE_LIMIT,
-            max_tx_pool_size: self.tx_pool_config.max_tx_pool_size as u64,
+            max_tx_pool_size: tx_pool.config.max_tx_pool_size as u64,
             verify_queue_size: verify_queue.len(),
+            verify_queue_tx_size: verify_queue.total_tx_size(),
+            verify_queue_cycles: verify_queue.total_cycles(),
+            top_lock_scripts: tx_pool.pool_map.top_lock_scripts(MAX_TOP_LOCK_SCRIPTS),
+            rbf_replaced_fees: tx_pool.rbf_replaced_fees(),
+        }
+    }
+
+    /// Explains why a tx is not progressing, from the pool, the verify queue and the recent rejects
+    async fn diagnose_tx(&self, tx_hash: Byte32) -> TxDiagnosis {
+        let tx_pool = self.tx_pool.read().await;
+        let verify_queue = self.verify_queue.read().await;
+        let id = ProposalShortId::from_tx_hash(&tx_hash);
+
+        if let Some(mut diagnosis) = tx_pool.diagnose_tx(&id, unix_time_as_millis()) {
+            let tx = tx_pool
+                .pool_map
+                .get_by_id(&id)
+                .map(|entry| entry.inner.transaction())
+                .expect("diagnosed tx in the pool");
+            let conflicts = verify_queue.find_conflicts(tx);
+            if !conflicts.is_empty() {
+                diagnosis.issues.push(TxDiagnosisIssue {
+                    kind: TxDiagnosisIssueKind::Conflicts,
+                    message: format!(
+                        "{} txs in the verify queue spend the same inputs, \
+                         they may replace this tx",
+                        conflicts.len()
+                    ),
+                    related_txs: conflicts,
+                });
+            }
+            return diagnosis;
+        }
+
+        if let Some(position) = verify_queue.position(&id) {
+            let mut diagnosis = TxDiagnosis::with_status(tx_hash, "verifying");
+            diagnosis.issues.push(TxDiagnosisIssue {
+                kind: TxDiagnosisIssueKind::Verifying,
+                message: format!(
+                    "waiting at position {} of {} in the verify queue",
+                    position,
+                    verify_queue.len()
+                ),
+                related_txs: vec![],
+            });
+            return diagnosis;
+        }
+
+        let reject = tx_pool
+            .recent_reject
+            .as_ref()
+            .and_then(|recent_reject| recent_reject.get(&tx_hash).ok().flatten());
+        if let Some(reason) = reject {
+            let mut diagnosis = TxDiagnosis::with_status(tx_hash, "rejected");
+            diagnosis.issues.push(TxDiagnosisIssue {
+                kind: TxDiagnosisIssueKind::Rejected,
+                message: reason,
+                related_txs: vec![],
+            });
+            diagnosis
+        } else {
+            TxDiagnosis::with_status(tx_hash, "unknown")
         }
     }
 
     /// Get Live Cell Status
     async fn get_live_cell(&self, out_point: OutPoint, eager_load: bool) -> CellStatus {
         let tx_pool = self.tx_pool.read().await;
+        Self::pool_live_cell(&tx_pool, &out_point, eager_load)
+    }
+
+    /// Get Live Cell Status with how the pool txs create or spend it, under one pool lock so the
+    /// store state and the pool state are consistent
+    async fn get_live_cell_with_pool_status(
+        &self,
+        out_point: OutPoint,
+        eager_load: bool,
+        include_tx_pool: bool,
+    ) -> (CellStatus, CellPoolStatus) {
+        let tx_pool = self.tx_pool.read().await;
+        let cell_status = if include_tx_pool {
+            Self::pool_live_cell(&tx_pool, &out_point, eager_load)
+        } else {
+            tx_pool.snapshot().cell(&out_point, eager_load)
+        };
+        (cell_status, tx_pool.pool_map.cell_pool_status(&out_point))
+    }
+
+    fn pool_live_cell(tx_pool: &TxPool, out_point: &OutPoint, eager_load: bool) -> CellStatus {
         let snapshot = tx_pool.snapshot();
         let pool_cell = PoolCell::new(&tx_pool.pool_map, false);
         let provider = OverlayCellProvider::new(&pool_cell, snapshot);
 
-        match provider.cell(&out_point, false) {
+        match provider.cell(out_point, false) {
             CellStatus::Live(mut cell_meta) => {
                 if eager_load {
-                    if let Some((data, data_hash)) = snapshot.get_cell_data(&out_point) {
+                    if let Some((data, data_hash)) = snapshot.get_cell_data(out_point) {
                         cell_meta.mem_cell_data = Some(data);
                         cell_meta.mem_cell_data_hash = Some(data_hash);
                     }
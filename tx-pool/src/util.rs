@@ -1,14 +1,16 @@
 use crate::error::Reject;
 use crate::pool::TxPool;
+use ckb_app_config::TxPoolConfig;
 use ckb_chain_spec::consensus::Consensus;
 use ckb_dao::DaoCalculator;
-use ckb_script::ChunkCommand;
+use ckb_error::Error;
+use ckb_script::{ChunkCommand, ScriptError, TransactionScriptError};
 use ckb_snapshot::Snapshot;
 use ckb_store::data_loader_wrapper::AsDataLoader;
 use ckb_store::ChainStore;
 use ckb_types::core::{
     cell::ResolvedTransaction, tx_pool::TRANSACTION_SIZE_LIMIT, Capacity, Cycle, EpochNumber,
-    TransactionView,
+    FeeRate, TransactionView,
 };
 use ckb_verification::{
     cache::{CacheEntry, Completed},
@@ -26,20 +28,27 @@ pub(crate) fn check_txid_collision(tx_pool: &TxPool, tx: &TransactionView) -> Re
     Ok(())
 }
 
-pub(crate) fn check_tx_fee(
-    tx_pool: &TxPool,
+pub(crate) fn calculate_tx_fee(
     snapshot: &Snapshot,
     rtx: &ResolvedTransaction,
-    tx_size: usize,
 ) -> Result<Capacity, Reject> {
-    let fee = DaoCalculator::new(snapshot.consensus(), &snapshot.borrow_as_data_loader())
+    DaoCalculator::new(snapshot.consensus(), &snapshot.borrow_as_data_loader())
         .transaction_fee(rtx)
         .map_err(|err| {
             Reject::Malformed(
                 format!("{err}"),
                 "expect (outputs capacity) <= (inputs capacity)".to_owned(),
             )
-        })?;
+        })
+}
+
+pub(crate) fn check_tx_fee(
+    tx_pool: &TxPool,
+    snapshot: &Snapshot,
+    rtx: &ResolvedTransaction,
+    tx_size: usize,
+) -> Result<Capacity, Reject> {
+    let fee = calculate_tx_fee(snapshot, rtx)?;
     // Theoretically we cannot use size as weight directly to calculate fee_rate,
     // here min fee rate is used as a cheap check,
     // so we will use size to calculate fee_rate directly
@@ -54,6 +63,58 @@ pub(crate) fn check_tx_fee(
     Ok(fee)
 }
 
+// Rejects the local txs which pay absurdly high fees, most likely by mistake.
+pub(crate) fn check_absurd_fee(
+    config: &TxPoolConfig,
+    fee: Capacity,
+    tx_size: usize,
+) -> Result<(), Reject> {
+    if let Some(max_absolute_fee) = config.max_absolute_fee {
+        if fee.as_u64() > max_absolute_fee {
+            return Err(Reject::AbsurdFee(
+                fee.as_u64(),
+                format!("exceeding max_absolute_fee {max_absolute_fee} shannons"),
+            ));
+        }
+    }
+    if let Some(max_fee_rate) = config.max_fee_rate {
+        let max_fee = FeeRate::from_u64(max_fee_rate).fee(tx_size as u64);
+        if fee > max_fee {
+            return Err(Reject::AbsurdFee(
+                fee.as_u64(),
+                format!(
+                    "exceeding {} shannons allowed by max_fee_rate {max_fee_rate} shannons/KB",
+                    max_fee.as_u64()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Rejects the txs depending on the headers close to the tip, which are likely to be detached by a
+// reorg. The header deps not in the main chain are left to the resolving.
+pub(crate) fn check_header_deps_depth(
+    config: &TxPoolConfig,
+    snapshot: &Snapshot,
+    tx: &TransactionView,
+) -> Result<(), Reject> {
+    let min_depth = match config.min_header_dep_depth {
+        Some(min_depth) if min_depth > 0 => min_depth,
+        _ => return Ok(()),
+    };
+    let tip_number = snapshot.tip_number();
+    for header_dep in tx.header_deps_iter() {
+        if let Some(number) = snapshot.get_block_number(&header_dep) {
+            let depth = tip_number.saturating_sub(number);
+            if depth < min_depth {
+                return Err(Reject::HeaderDepTooRecent(header_dep, depth, min_depth));
+            }
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn non_contextual_verify(
     consensus: &Consensus,
     tx: &TransactionView,
@@ -89,6 +150,7 @@ pub(crate) async fn verify_rtx(
     tx_env: Arc<TxVerifyEnv>,
     cache_entry: &Option<CacheEntry>,
     max_tx_verify_cycles: Cycle,
+    max_script_group_cycles: Option<Cycle>,
     command_rx: Option<&mut watch::Receiver<ChunkCommand>>,
 ) -> Result<Completed, Reject> {
     let consensus = snapshot.cloned_consensus();
@@ -100,23 +162,36 @@ pub(crate) async fn verify_rtx(
             .map(|_| *completed)
             .map_err(Reject::Verification)
     } else if let Some(command_rx) = command_rx {
-        ContextualTransactionVerifier::new(
+        let mut verifier = ContextualTransactionVerifier::new(
             Arc::clone(&rtx),
             consensus,
             data_loader,
             Arc::clone(&tx_env),
-        )
-        .verify_with_pause(max_tx_verify_cycles, command_rx)
-        .await
-        .and_then(|result| {
-            DaoScriptSizeVerifier::new(rtx, snapshot.cloned_consensus(), snapshot.as_data_loader())
+        );
+        verifier.set_max_group_cycles(max_script_group_cycles);
+        verifier
+            .verify_with_pause(max_tx_verify_cycles, command_rx)
+            .await
+            .and_then(|result| {
+                DaoScriptSizeVerifier::new(
+                    rtx,
+                    snapshot.cloned_consensus(),
+                    snapshot.as_data_loader(),
+                )
                 .verify()?;
-            Ok(result)
-        })
-        .map_err(Reject::Verification)
+                Ok(result)
+            })
+            .map_err(verification_reject)
     } else {
         block_in_place(|| {
-            ContextualTransactionVerifier::new(Arc::clone(&rtx), consensus, data_loader, tx_env)
+            let mut verifier = ContextualTransactionVerifier::new(
+                Arc::clone(&rtx),
+                consensus,
+                data_loader,
+                tx_env,
+            );
+            verifier.set_max_group_cycles(max_script_group_cycles);
+            verifier
                 .verify(max_tx_verify_cycles, false)
                 .and_then(|result| {
                     DaoScriptSizeVerifier::new(
@@ -127,11 +202,29 @@ pub(crate) async fn verify_rtx(
                     .verify()?;
                     Ok(result)
                 })
-                .map_err(Reject::Verification)
+                .map_err(verification_reject)
         })
     }
 }
 
+// A script group exceeding `max_script_group_cycles` violates the pool policy only, so the tx is
+// not rejected as malformed.
+fn verification_reject(error: Error) -> Reject {
+    let group_cycles = error
+        .root_cause()
+        .downcast_ref::<TransactionScriptError>()
+        .and_then(|error| match error.script_error() {
+            ScriptError::ExceededMaximumGroupCycles(script_hash, limit) => {
+                Some((script_hash.clone(), *limit))
+            }
+            _ => None,
+        });
+    match group_cycles {
+        Some((script_hash, limit)) => Reject::ExceededMaximumScriptGroupCycles(script_hash, limit),
+        None => Reject::Verification(error),
+    }
+}
+
 pub(crate) fn time_relative_verify(
     snapshot: Arc<Snapshot>,
     rtx: Arc<ResolvedTransaction>,
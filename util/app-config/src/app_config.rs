@@ -9,6 +9,7 @@ use path_clean::PathClean;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use ckb_chain_spec::ChainSpec;
@@ -92,6 +93,12 @@ pub struct CKBAppConfig {
     /// Indexer config options.
     #[serde(default)]
     pub indexer: IndexerConfig,
+    /// Local alert rules config options.
+    #[serde(default)]
+    pub alert_rules: AlertRulesConfig,
+    /// Header checkpoints export config options.
+    #[serde(default)]
+    pub header_checkpoints: HeaderCheckpointsConfig,
 }
 
 /// The miner config file for `ckb miner`. Usually it is the `ckb-miner.toml` in the CKB root
@@ -132,11 +139,21 @@ pub struct MinerAppConfig {
 }
 
 /// The chain config options.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ChainConfig {
     /// Specifies the chain spec.
     pub spec: Resource,
+    /// The windows, in blocks, over which the stale block and uncle rates are tracked.
+    #[serde(default = "default_chain_stats_windows")]
+    pub stats_windows: Vec<u64>,
+}
+
+/// The default windows, in blocks, of the stale block and uncle statistics.
+pub const DEFAULT_CHAIN_STATS_WINDOWS: [u64; 2] = [100, 1800];
+
+fn default_chain_stats_windows() -> Vec<u64> {
+    DEFAULT_CHAIN_STATS_WINDOWS.to_vec()
 }
 
 impl AppConfig {
@@ -270,7 +287,18 @@ impl CKBAppConfig {
         Ok(legacy_config.into())
     }
 
+    /// Returns the JSON schema of `ckb.toml`, which describes the accepted options.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(legacy::CKBAppConfig))
+            .expect("serialize json schema")
+    }
+
     fn derive_options(mut self, root_dir: &Path, subcommand_name: &str) -> Result<Self, ExitCode> {
+        if let Err(err) = check_witness_prefixes(&self.indexer.witness_prefixes) {
+            eprintln!("Config Error: {err}");
+            return Err(ExitCode::Config);
+        }
+
         self.root_dir = root_dir.to_path_buf();
 
         self.data_dir = canonicalize_data_dir(self.data_dir, root_dir);
@@ -285,6 +313,7 @@ impl CKBAppConfig {
         }))?;
 
         self.network.path = self.data_dir.join("network");
+        self.network.adjust(root_dir);
         if self.tmp_dir.is_none() {
             self.tmp_dir = Some(self.data_dir.join("tmp"));
         }
@@ -297,6 +326,9 @@ impl CKBAppConfig {
         let indexer_path = mkdir(self.data_dir.join("indexer"))?;
         self.indexer.adjust(root_dir, indexer_path);
 
+        self.rpc.adjust(root_dir);
+        self.header_checkpoints.adjust(root_dir);
+
         if subcommand_name == cli::CMD_RESET_DATA {
             return Ok(self);
         }
@@ -330,6 +362,12 @@ impl MinerAppConfig {
         Ok(legacy_config.into())
     }
 
+    /// Returns the JSON schema of `ckb-miner.toml`, which describes the accepted options.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(legacy::MinerAppConfig))
+            .expect("serialize json schema")
+    }
+
     fn derive_options(mut self, root_dir: &Path) -> Result<Self, ExitCode> {
         self.root_dir = root_dir.to_path_buf();
 
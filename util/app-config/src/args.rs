@@ -1,9 +1,9 @@
-use crate::{CKBAppConfig, MemoryTrackerConfig, MinerConfig};
+use crate::{CKBAppConfig, DBConfig, MemoryTrackerConfig, MinerConfig};
 use ckb_chain_spec::consensus::Consensus;
 use ckb_jsonrpc_types::ScriptHashType;
 use ckb_pow::PowEngine;
 use ckb_systemtime::unix_time_as_millis;
-use ckb_types::packed::Byte32;
+use ckb_types::{core::BlockNumber, packed::Byte32, H256};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -17,6 +17,16 @@ pub struct ExportArgs {
     pub target: PathBuf,
 }
 
+/// Parsed command line arguments for `ckb export-indexer`.
+pub struct ExportIndexerArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+    /// The target directory to save the snapshot.
+    pub target: PathBuf,
+    /// The block number to export the indexer at, the indexer tip by default.
+    pub height: Option<BlockNumber>,
+}
+
 #[derive(Debug)]
 /// Parsed command line arguments for `ckb daemon`.
 pub struct DaemonArgs {
@@ -52,6 +62,12 @@ pub struct RunArgs {
     ///
     /// [`RunArgs::chain_spec_hash`]: ./struct.RunArgs.html#structfield.chain_spec_hash
     pub overwrite_chain_spec: bool,
+    /// Whether check and repair the recent main chain blocks on startup
+    pub repair_chain: bool,
+    /// Whether skip the preflight checks before the services start
+    pub skip_preflight: bool,
+    /// Whether query the NTP servers for the clock skew in the preflight checks
+    pub preflight_ntp: bool,
     /// Hash of serialized configured chain spec
     pub chain_spec_hash: Byte32,
     /// Whether start indexer, default false
@@ -142,6 +158,10 @@ pub struct InitArgs {
     ///
     /// The spec file will be saved into `specs/{CHAIN}.toml`, where `CHAIN` is the chain name.
     pub import_spec: Option<String>,
+    /// The pinned blake2b-256 hash of the chain spec file fetched from a URL.
+    ///
+    /// It's required when `chain` is an HTTPS URL.
+    pub spec_hash: Option<H256>,
     /// Customize parameters for chain spec or not.
     ///
     /// Only works for dev chains.
@@ -206,10 +226,66 @@ pub struct MigrateArgs {
     pub consensus: Consensus,
     /// Check whether it is required to do migration instead of really perform the migration.
     pub check: bool,
+    /// List the applied and pending migrations instead of really perform the migration.
+    pub list: bool,
+    /// Report the pending migrations instead of really perform the migration.
+    pub dry_run: bool,
     /// Do migration without interactive prompt.
     pub force: bool,
     /// Whether include background migrations
     pub include_background: bool,
+    /// Migrate the standalone copy of the database in this directory instead of `db.path`.
+    pub target: Option<PathBuf>,
+    /// The threads a multi-thread migration uses.
+    pub threads: Option<usize>,
+}
+
+/// Parsed command line arguments for `ckb restore-wal`.
+pub struct RestoreWalArgs {
+    /// The database config, the backup is restored to `db.path`.
+    pub db: DBConfig,
+    /// The backup of the database directory.
+    pub backup: PathBuf,
+    /// Only replay the archived WAL files whose number is less than or equal to this one.
+    pub until: Option<u64>,
+}
+
+/// Parsed command line arguments for `ckb db move-columns`.
+pub struct MoveColumnsArgs {
+    /// The database config, the columns are moved to the directories in `db.column_paths`.
+    pub db: DBConfig,
+}
+
+/// Parsed command line arguments for `ckb db verify-freezer`.
+pub struct VerifyFreezerArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+    /// Loaded consensus.
+    pub consensus: Consensus,
+    /// Verify all the frozen blocks, even if the data files match the checksums.
+    pub full: bool,
+    /// The RPC url of a live node to re-fetch the corrupted blocks from.
+    pub refetch: Option<String>,
+}
+
+/// Parsed command line arguments for `ckb bench`.
+pub struct BenchArgs {
+    /// Parsed `ckb.toml`.
+    pub config: Box<CKBAppConfig>,
+    /// Loaded consensus.
+    pub consensus: Consensus,
+    /// The directory to store the temporary files during the benchmarks.
+    pub tmp_target: PathBuf,
+    /// The benchmark suites to run.
+    pub suites: Vec<String>,
+    /// Replay the blocks from this number.
+    pub from: Option<u64>,
+    /// Replay the blocks to this number.
+    pub to: Option<u64>,
+    /// The number of the operations in the tx-pool and the database benchmarks.
+    pub count: u64,
+    /// Print the report in JSON.
+    pub json: bool,
 }
 
 impl CustomizeSpec {
@@ -14,6 +14,10 @@ pub const CMD_MINER: &str = "miner";
 pub const CMD_EXPORT: &str = "export";
 /// Subcommand `import`.
 pub const CMD_IMPORT: &str = "import";
+/// Subcommand `export-indexer`.
+pub const CMD_EXPORT_INDEXER: &str = "export-indexer";
+/// Subcommand `import-indexer`.
+pub const CMD_IMPORT_INDEXER: &str = "import-indexer";
 /// Subcommand `init`.
 pub const CMD_INIT: &str = "init";
 /// Subcommand `replay`.
@@ -32,6 +36,24 @@ pub const CMD_GEN_SECRET: &str = "gen";
 pub const CMD_FROM_SECRET: &str = "from-secret";
 /// Subcommand `migrate`.
 pub const CMD_MIGRATE: &str = "migrate";
+/// Subcommand `restore-wal`.
+pub const CMD_RESTORE_WAL: &str = "restore-wal";
+/// Subcommand `db`.
+pub const CMD_DB: &str = "db";
+/// Subcommand `db verify-freezer`.
+pub const CMD_VERIFY_FREEZER: &str = "verify-freezer";
+/// Subcommand `db move-columns`.
+pub const CMD_MOVE_COLUMNS: &str = "move-columns";
+/// Subcommand `bench`.
+pub const CMD_BENCH: &str = "bench";
+/// Subcommand `verify-checkpoints`.
+pub const CMD_VERIFY_CHECKPOINTS: &str = "verify-checkpoints";
+/// Subcommand `completions`.
+pub const CMD_COMPLETIONS: &str = "completions";
+/// Subcommand `config`.
+pub const CMD_CONFIG: &str = "config";
+/// Subcommand `config schema`.
+pub const CMD_CONFIG_SCHEMA: &str = "schema";
 /// Subcommand `daemon`
 pub const CMD_DAEMON: &str = "daemon";
 /// Command line argument `--config-dir`.
@@ -52,6 +74,8 @@ pub const ARG_INTERACTIVE: &str = "interactive";
 pub const ARG_CHAIN: &str = "chain";
 /// Command line argument `--import-spec`.
 pub const ARG_IMPORT_SPEC: &str = "import-spec";
+/// Command line argument `--spec-hash`.
+pub const ARG_SPEC_HASH: &str = "spec-hash";
 /// The argument for the genesis message.
 pub const ARG_GENESIS_MESSAGE: &str = "genesis-message";
 /// Command line argument `--p2p-port`.
@@ -86,6 +110,8 @@ pub const ARG_RICH_INDEXER: &str = "rich-indexer";
 pub const ARG_FROM: &str = "from";
 /// Command line argument `--to`.
 pub const ARG_TO: &str = "to";
+/// Command line argument `--height`.
+pub const ARG_HEIGHT: &str = "height";
 /// Command line argument `--all`.
 pub const ARG_ALL: &str = "all";
 /// Command line argument `--limit`.
@@ -114,10 +140,42 @@ pub const ARG_FULL_VERIFICATION: &str = "full-verification";
 pub const ARG_SKIP_CHAIN_SPEC_CHECK: &str = "skip-spec-check";
 /// Present `overwrite-spec` arg to force overriding the chain spec in the database with the present configured chain spec
 pub const ARG_OVERWRITE_CHAIN_SPEC: &str = "overwrite-spec";
+/// Command line argument `--repair-chain`.
+pub const ARG_REPAIR_CHAIN: &str = "repair-chain";
+/// Command line argument `--skip-preflight`.
+pub const ARG_SKIP_PREFLIGHT: &str = "skip-preflight";
+/// Command line argument `--preflight-ntp`.
+pub const ARG_PREFLIGHT_NTP: &str = "preflight-ntp";
 /// Command line argument `--assume-valid-target`.
 pub const ARG_ASSUME_VALID_TARGET: &str = "assume-valid-target";
 /// Command line argument `--check`.
 pub const ARG_MIGRATE_CHECK: &str = "check";
+/// Command line argument `migrate --list`.
+pub const ARG_MIGRATE_LIST: &str = "list";
+/// Command line argument `migrate --dry-run`.
+pub const ARG_MIGRATE_DRY_RUN: &str = "dry-run";
+/// Command line argument `migrate --threads`.
+pub const ARG_MIGRATE_THREADS: &str = "threads";
+/// Command line argument `restore-wal --backup`.
+pub const ARG_BACKUP: &str = "backup";
+/// Command line argument `restore-wal --until`.
+pub const ARG_UNTIL: &str = "until";
+/// Command line argument `db verify-freezer --full`.
+pub const ARG_FULL: &str = "full";
+/// Command line argument `db verify-freezer --refetch`.
+pub const ARG_REFETCH: &str = "refetch";
+/// Command line argument `bench --suite`.
+pub const ARG_SUITE: &str = "suite";
+/// Command line argument `bench --count`.
+pub const ARG_COUNT: &str = "count";
+/// Command line argument `completions <shell>`.
+pub const ARG_SHELL: &str = "shell";
+/// Command line argument `verify-checkpoints <file>`.
+pub const ARG_FILE: &str = "file";
+/// Command line argument `verify-checkpoints --node-id`.
+pub const ARG_NODE_ID: &str = "node-id";
+/// Command line argument `config schema --miner`.
+pub const ARG_MINER: &str = "miner";
 /// Command line argument `daemon --check`
 pub const ARG_DAEMON_CHECK: &str = "check";
 /// Command line argument `daemon --stop`
@@ -148,13 +206,21 @@ pub fn basic_app() -> Command {
         .subcommand(miner())
         .subcommand(export())
         .subcommand(import())
+        .subcommand(export_indexer())
+        .subcommand(import_indexer())
         .subcommand(list_hashes())
         .subcommand(init())
         .subcommand(replay())
         .subcommand(stats())
         .subcommand(reset_data())
         .subcommand(peer_id())
-        .subcommand(migrate());
+        .subcommand(migrate())
+        .subcommand(restore_wal())
+        .subcommand(db())
+        .subcommand(bench())
+        .subcommand(verify_checkpoints())
+        .subcommand(completions())
+        .subcommand(config());
 
     #[cfg(not(target_os = "windows"))]
     let command = command.subcommand(daemon());
@@ -195,6 +261,24 @@ fn run() -> Command {
             .long(ARG_OVERWRITE_CHAIN_SPEC)
             .action(clap::ArgAction::SetTrue)
             .help("Overwrite the chain spec in the database with the present configured chain spec")
+    ).arg(
+        Arg::new(ARG_REPAIR_CHAIN)
+            .long(ARG_REPAIR_CHAIN)
+            .action(clap::ArgAction::SetTrue)
+            .help("Check the recent main chain blocks for missing data and broken indexes \
+after an unclean shutdown, repair the indexes from the existing data and download the missing blocks again")
+    ).arg(
+        Arg::new(ARG_SKIP_PREFLIGHT)
+            .long(ARG_SKIP_PREFLIGHT)
+            .action(clap::ArgAction::SetTrue)
+            .help("Skip the startup checks of the file descriptors limit, the disk space, \
+the clock skew, the listen ports and the database lock")
+    ).arg(
+        Arg::new(ARG_PREFLIGHT_NTP)
+            .long(ARG_PREFLIGHT_NTP)
+            .action(clap::ArgAction::SetTrue)
+            .conflicts_with(ARG_SKIP_PREFLIGHT)
+            .help("Query the NTP servers in the startup checks to warn about the clock skew")
     ).arg(
         Arg::new(ARG_ASSUME_VALID_TARGET)
             .long(ARG_ASSUME_VALID_TARGET)
@@ -402,6 +486,52 @@ fn import() -> Command {
     )
 }
 
+fn export_indexer() -> Command {
+    Command::new(CMD_EXPORT_INDEXER)
+        .about(
+            "Export a snapshot of the indexer store, the node must be stopped\n\
+             Example:\n\
+             ckb export-indexer --target /snapshots/indexer --height 1000000",
+        )
+        .arg(
+            Arg::new(ARG_TARGET)
+                .short('t')
+                .long(ARG_TARGET)
+                .value_name("path")
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .required(true)
+                .help("Specify the snapshot directory, which must not exist"),
+        )
+        .arg(
+            Arg::new(ARG_HEIGHT)
+                .long(ARG_HEIGHT)
+                .value_parser(clap::value_parser!(u64))
+                .action(clap::ArgAction::Set)
+                .help(
+                    "Specify the block number to export the indexer at, the indexer tip by default\n\
+                     The block must be kept for the rollback by the indexer store",
+                ),
+        )
+}
+
+fn import_indexer() -> Command {
+    Command::new(CMD_IMPORT_INDEXER)
+        .about(
+            "Import a snapshot of the indexer store exported by `ckb export-indexer`\n\
+             The blocks in the snapshot are verified against the synced chain, the node must be stopped\n\
+             Example:\n\
+             ckb import-indexer /snapshots/indexer",
+        )
+        .arg(
+            Arg::new(ARG_SOURCE)
+                .index(1)
+                .value_name("path")
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .required(true)
+                .help("Specify the snapshot directory"),
+        )
+}
+
 fn migrate() -> Command {
     Command::new(CMD_MIGRATE)
         .about("Run CKB migration")
@@ -422,12 +552,235 @@ fn migrate() -> Command {
                 .conflicts_with(ARG_MIGRATE_CHECK)
                 .help("Migrate without interactive prompt"),
         )
+        .arg(
+            Arg::new(ARG_MIGRATE_LIST)
+                .long(ARG_MIGRATE_LIST)
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all([ARG_MIGRATE_CHECK, ARG_FORCE])
+                .help(
+                    "List the applied migrations with their completion time and duration, \
+                    and the pending migrations, without migrating",
+                ),
+        )
+        .arg(
+            Arg::new(ARG_MIGRATE_DRY_RUN)
+                .long(ARG_MIGRATE_DRY_RUN)
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all([ARG_MIGRATE_CHECK, ARG_MIGRATE_LIST, ARG_FORCE, ARG_TARGET])
+                .help(
+                    "Report the pending migrations with their descriptions, \
+                    whether they are expensive and whether they run in background, \
+                    without migrating",
+                ),
+        )
         .arg(
             Arg::new(ARG_INCLUDE_BACKGROUND)
                 .long(ARG_INCLUDE_BACKGROUND)
                 .action(clap::ArgAction::SetTrue)
                 .help("Whether include background migrations"),
         )
+        .arg(
+            Arg::new(ARG_TARGET)
+                .short('t')
+                .long(ARG_TARGET)
+                .value_name("path")
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .conflicts_with_all([ARG_MIGRATE_CHECK, ARG_MIGRATE_LIST])
+                .help(
+                    "Migrate a standalone copy of the database in this directory instead of `db.path`, \
+                    including the background migrations, without starting any node services. \
+                    If the directory is empty, a checkpoint of `db.path` is created in it first, \
+                    the node must be stopped until the checkpoint is created, which is quick. \
+                    Swap the migrated copy into `db.path` while the node is stopped",
+                ),
+        )
+        .arg(
+            Arg::new(ARG_MIGRATE_THREADS)
+                .long(ARG_MIGRATE_THREADS)
+                .value_name("number")
+                .value_parser(clap::value_parser!(usize))
+                .help(
+                    "The threads a multi-thread migration uses, \
+                    it is the number of CPUs capped to 6 by default",
+                ),
+        )
+}
+
+fn restore_wal() -> Command {
+    Command::new(CMD_RESTORE_WAL)
+        .about(
+            "Restore a database backup and replay the WAL files archived in `db.wal_archive_dir`\n\
+             Example:\n\
+             ckb restore-wal --backup /backups/db --until 1024",
+        )
+        .arg(
+            Arg::new(ARG_BACKUP)
+                .long(ARG_BACKUP)
+                .value_name("path")
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .required(true)
+                .help("The backup of the database directory, it is copied to `data/db`, which must be empty. \
+The columns in `db.column_paths` are copied from the directories next to it, e.g., `<path>.COLUMN_CELL`"),
+        )
+        .arg(
+            Arg::new(ARG_UNTIL)
+                .long(ARG_UNTIL)
+                .value_name("number")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "Only replay the archived WAL files whose number is less than or equal to this one, \
+                    all the archived WAL files are replayed by default",
+                ),
+        )
+}
+
+fn db() -> Command {
+    Command::new(CMD_DB)
+        .about("About the database, the node must be stopped")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new(CMD_VERIFY_FREEZER)
+                .about(
+                    "Verify the integrity of the freezer data files\n\
+                     The sealed data files are compared with the checksums in the manifest, \
+                     the blocks in the mismatched files and the head file are verified one by one\n\
+                     Example:\n\
+                     ckb db verify-freezer --refetch http://127.0.0.1:8114",
+                )
+                .arg(
+                    Arg::new(ARG_FULL)
+                        .long(ARG_FULL)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Verify all the frozen blocks one by one, even if the files match the checksums"),
+                )
+                .arg(
+                    Arg::new(ARG_REFETCH)
+                        .long(ARG_REFETCH)
+                        .value_name("url")
+                        .action(clap::ArgAction::Set)
+                        .help(
+                            "Re-fetch the corrupted blocks from the RPC of a live node and repair \
+                            the freezer data files in place",
+                        ),
+                ),
+        )
+        .subcommand(Command::new(CMD_MOVE_COLUMNS).about(
+            "Move the existing data of the columns to the directories in `db.column_paths`\n\
+             The columns are compacted, so their files are rewritten to the directories",
+        ))
+}
+
+fn bench() -> Command {
+    Command::new(CMD_BENCH)
+        .about(
+            "Run the standardized benchmarks against the local data directory\n\
+             The node must be stopped. The report is comparable across machines and versions \
+             when the same options are used\n\
+             Example:\n\
+             ckb bench --tmp-target /tmp --suite block-replay --from 1 --to 10000",
+        )
+        .arg(
+            Arg::new(ARG_TMP_TARGET)
+                .long(ARG_TMP_TARGET)
+                .value_parser(clap::builder::PathBufValueParser::new())
+                .action(clap::ArgAction::Set)
+                .required(true)
+                .help(
+                    "Specify a target path. The benchmarks make a temporary directory within the \
+                    specified target path, which is deleted when the command completes",
+                ),
+        )
+        .arg(
+            Arg::new(ARG_SUITE)
+                .long(ARG_SUITE)
+                .value_parser(["block-replay", "tx-pool", "db"])
+                .action(clap::ArgAction::Append)
+                .help("Run only the specified benchmark suites, all the suites run by default"),
+        )
+        .arg(
+            Arg::new(ARG_FROM)
+                .long(ARG_FROM)
+                .value_parser(clap::value_parser!(u64))
+                .action(clap::ArgAction::Set)
+                .help("Replay the blocks from this number, default is 1"),
+        )
+        .arg(
+            Arg::new(ARG_TO)
+                .long(ARG_TO)
+                .value_parser(clap::value_parser!(u64))
+                .action(clap::ArgAction::Set)
+                .help("Replay the blocks to this number, default is 1000 blocks after --from"),
+        )
+        .arg(
+            Arg::new(ARG_COUNT)
+                .long(ARG_COUNT)
+                .value_parser(clap::value_parser!(u64))
+                .default_value("100000")
+                .help("The number of the operations in the tx-pool and the database benchmarks"),
+        )
+        .arg(
+            Arg::new(ARG_FORMAT)
+                .short('f')
+                .long(ARG_FORMAT)
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .help("Set the format of the printed report"),
+        )
+}
+
+fn verify_checkpoints() -> Command {
+    Command::new(CMD_VERIFY_CHECKPOINTS)
+        .about(
+            "Verify the signature of the header checkpoints exported by a node\n\
+             The file is the result of the RPC `get_header_checkpoints` or the file exported by \
+             the option `header_checkpoints.export_file`\n\
+             Example:\n\
+             ckb verify-checkpoints header_checkpoints.json --node-id QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+        )
+        .arg(
+            Arg::new(ARG_FILE)
+                .required(true)
+                .action(clap::ArgAction::Set)
+                .help("The JSON file of the signed header checkpoints"),
+        )
+        .arg(
+            Arg::new(ARG_NODE_ID)
+                .long(ARG_NODE_ID)
+                .value_name("peer id")
+                .action(clap::ArgAction::Set)
+                .help("Require the checkpoints to be signed by the node with the peer ID"),
+        )
+}
+
+fn completions() -> Command {
+    Command::new(CMD_COMPLETIONS)
+        .about(
+            "Print the shell completion script\n\
+             Example:\n\
+             ckb completions bash > /etc/bash_completion.d/ckb",
+        )
+        .arg(
+            Arg::new(ARG_SHELL)
+                .value_parser(clap::value_parser!(clap_complete::Shell))
+                .required(true)
+                .help("The shell to generate the completion script for"),
+        )
+}
+
+fn config() -> Command {
+    Command::new(CMD_CONFIG)
+        .about("About the config files")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new(CMD_CONFIG_SCHEMA)
+                .about("Print the JSON schema of ckb.toml, which can be used to validate the config file")
+                .arg(
+                    Arg::new(ARG_MINER)
+                        .long(ARG_MINER)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Print the JSON schema of ckb-miner.toml instead"),
+                ),
+        )
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -492,14 +845,23 @@ fn init() -> Command {
             Arg::new(ARG_CHAIN)
                 .short('c')
                 .long(ARG_CHAIN)
-                .value_parser(
-                    AVAILABLE_SPECS
-                        .iter()
-                        .map(|v| v.to_string())
-                        .collect::<Vec<_>>(),
-                )
+                .value_parser(is_chain)
                 .default_value(DEFAULT_SPEC)
-                .help("Initialize CKB directory for <chain>"),
+                .help(
+                    "Initialize CKB directory for <chain>, which is either a bundled chain name \
+                     or an HTTPS URL to fetch the chain spec from",
+                ),
+        )
+        .arg(
+            Arg::new(ARG_SPEC_HASH)
+                .long(ARG_SPEC_HASH)
+                .value_name("hash")
+                .value_parser(is_h256)
+                .action(clap::ArgAction::Set)
+                .help(
+                    "The blake2b-256 hash of the chain spec file fetched from --chain <url>, \
+                     the fetched file is rejected if the hash does not match",
+                ),
         )
         .arg(
             Arg::new(ARG_IMPORT_SPEC)
@@ -649,6 +1011,17 @@ fn is_hex(hex: &str) -> Result<String, String> {
     }
 }
 
+fn is_chain(chain: &str) -> Result<String, String> {
+    if AVAILABLE_SPECS.contains(&chain) || chain.starts_with("https://") {
+        Ok(chain.to_owned())
+    } else {
+        Err(format!(
+            "Must be one of {} or an HTTPS URL",
+            AVAILABLE_SPECS.join(", ")
+        ))
+    }
+}
+
 fn is_h256(hex: &str) -> Result<String, String> {
     if hex.len() != 66 {
         Err("Must be 0x-prefixed hexadecimal string and string length is 66".to_owned())
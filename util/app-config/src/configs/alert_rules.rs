@@ -0,0 +1,44 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Local alert rules config options.
+///
+/// The rules are defined by the node operator and evaluated periodically. A violated rule raises
+/// a local alert, which is listed in `get_blockchain_info` and sent to the network alert
+/// subscribers and `notify.network_alert_notify_script`, but is never relayed to the peers.
+/// A rule is disabled when its option is not set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// How often the rules are evaluated, in seconds. Default is 60.
+    pub check_interval_secs: Option<u64>,
+    /// Raises an alert when the tip block timestamp is older than the minutes.
+    pub tip_stalled_minutes: Option<u64>,
+    /// Raises an alert when the connected peers are fewer than the number.
+    pub min_peers: Option<u32>,
+    /// Raises an alert when the tx-pool size reaches the percentage of `tx_pool.max_tx_pool_size`.
+    pub pool_full_percent: Option<u8>,
+    /// Raises an alert when a chain reorganization detaches more blocks than the number.
+    pub max_reorg_depth: Option<u64>,
+    /// An HTTP endpoint to which the raised and resolved alerts are posted as JSON.
+    pub webhook_url: Option<String>,
+}
+
+impl Config {
+    /// Whether any rule is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.tip_stalled_minutes.is_some()
+            || self.min_peers.is_some()
+            || self.pool_full_percent.is_some()
+            || self.max_reorg_depth.is_some()
+    }
+
+    /// How often the rules are evaluated, in seconds.
+    pub fn check_interval_secs(&self) -> u64 {
+        self.check_interval_secs
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS)
+    }
+}
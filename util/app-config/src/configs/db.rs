@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// Database config options.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// Database directory path.
@@ -23,6 +24,22 @@ pub struct Config {
     ///
     /// More details can be found in [the official tuning guide](https://github.com/facebook/rocksdb/wiki/RocksDB-Tuning-Guide).
     pub options_file: Option<PathBuf>,
+    /// Copy the closed WAL (write-ahead log) files to this directory.
+    ///
+    /// The archived WAL files can be replayed over a backup of the database by
+    /// `ckb restore-wal` for point-in-time recovery. WAL archiving is disabled by default.
+    ///
+    /// The directory must exist and must not overlap the database directory.
+    #[serde(default)]
+    pub wal_archive_dir: Option<PathBuf>,
+    /// Place the columns in the directories other than `path`, by the column names, e.g.,
+    /// `COLUMN_CELL = "/mnt/nvme/ckb"`.
+    ///
+    /// The new files of a column are written to its directory, while the existing ones are still
+    /// read from `path`, until they are compacted or moved by `ckb db move-columns`. To move a
+    /// column back, set its directory to `path` instead of removing it.
+    #[serde(default)]
+    pub column_paths: HashMap<String, PathBuf>,
 }
 
 impl Config {
@@ -30,8 +47,8 @@ impl Config {
     ///
     /// If `self.path` is not set, set it to `data_dir / name`.
     ///
-    /// If `self.path` or `self.options_file` is relative, convert them to absolute path using
-    /// `root_dir` as current working directory.
+    /// If `self.path`, `self.options_file` or the directories in `self.column_paths` are relative,
+    /// convert them to absolute path using `root_dir` as current working directory.
     pub fn adjust<P: AsRef<Path>>(&mut self, root_dir: &Path, data_dir: P, name: &str) {
         // If path is not set, use the default path
         if self.path.to_str().is_none() || self.path.to_str() == Some("") {
@@ -47,5 +64,17 @@ impl Config {
                 *file = file_new;
             }
         }
+        // If WAL archive directory is a relative path, set the base path to `ckb.toml`
+        if let Some(dir) = self.wal_archive_dir.iter_mut().next() {
+            if dir.is_relative() {
+                *dir = root_dir.to_path_buf().join(&dir);
+            }
+        }
+        // If a column directory is a relative path, set the base path to `ckb.toml`
+        for dir in self.column_paths.values_mut() {
+            if dir.is_relative() {
+                *dir = root_dir.to_path_buf().join(&dir);
+            }
+        }
     }
 }
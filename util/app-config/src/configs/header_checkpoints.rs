@@ -0,0 +1,65 @@
+use ckb_types::core::BlockNumber;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The default number of blocks between two header checkpoints.
+pub const DEFAULT_CHECKPOINT_INTERVAL: BlockNumber = 1000;
+/// The default number of the most recent header checkpoints.
+pub const DEFAULT_CHECKPOINT_COUNT: usize = 10;
+const DEFAULT_EXPORT_INTERVAL_SECS: u64 = 600;
+
+/// Header checkpoints export config options.
+///
+/// The node periodically writes the recent header checkpoints, signed by the network secret key,
+/// to a JSON file, which can be served to the light clients. The export is disabled when
+/// `export_file` is not set. The same data is also returned by the RPC `get_header_checkpoints`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The file the checkpoints are exported to, relative to the CKB root directory.
+    pub export_file: Option<PathBuf>,
+    /// How often the checkpoints are exported, in seconds. Default is 600.
+    pub export_interval_secs: Option<u64>,
+    /// The number of blocks between two checkpoints. Default is 1000.
+    pub interval: Option<BlockNumber>,
+    /// The number of the most recent checkpoints. Default is 10.
+    pub count: Option<usize>,
+}
+
+impl Config {
+    /// Canonicalizes the paths in the config options.
+    pub fn adjust(&mut self, root_dir: &Path) {
+        if let Some(file) = self.export_file.as_mut() {
+            if file.is_relative() {
+                *file = root_dir.join(&file);
+            }
+        }
+    }
+
+    /// Whether the checkpoints are exported.
+    pub fn is_enabled(&self) -> bool {
+        self.export_file.is_some()
+    }
+
+    /// How often the checkpoints are exported, in seconds.
+    pub fn export_interval_secs(&self) -> u64 {
+        self.export_interval_secs
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_EXPORT_INTERVAL_SECS)
+    }
+
+    /// The number of blocks between two checkpoints.
+    pub fn interval(&self) -> BlockNumber {
+        self.interval
+            .filter(|interval| *interval > 0)
+            .unwrap_or(DEFAULT_CHECKPOINT_INTERVAL)
+    }
+
+    /// The number of the most recent checkpoints.
+    pub fn count(&self) -> usize {
+        self.count
+            .filter(|count| *count > 0)
+            .unwrap_or(DEFAULT_CHECKPOINT_COUNT)
+    }
+}
@@ -1,12 +1,19 @@
 use super::rich_indexer::RichIndexerConfig;
 
+use ckb_jsonrpc_types::JsonBytes;
 use ckb_types::H256;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 
+/// The maximum number of `IndexerConfig::witness_prefixes`, so the index size is bounded.
+pub const MAX_WITNESS_PREFIXES: usize = 16;
+/// The maximum size in bytes of each of `IndexerConfig::witness_prefixes`.
+pub const MAX_WITNESS_PREFIX_SIZE: usize = 32;
+
 /// Indexer config options.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct IndexerConfig {
     /// The index store path, default `data_dir / indexer / store`
     #[serde(default)]
@@ -38,6 +45,14 @@ pub struct IndexerConfig {
     /// limit of indexer reqeust
     #[serde(default)]
     pub request_limit: Option<usize>,
+    /// The witness prefixes to index, none by default
+    ///
+    /// The txs with the witnesses starting with any of the prefixes can be queried by
+    /// `get_transactions_by_witness_prefix`. Only the blocks indexed after a prefix is added
+    /// are indexed by it. At most `MAX_WITNESS_PREFIXES` prefixes, each of 1 to
+    /// `MAX_WITNESS_PREFIX_SIZE` bytes.
+    #[serde(default)]
+    pub witness_prefixes: Vec<JsonBytes>,
     /// Rich indexer config options
     #[serde(default)]
     pub rich_indexer: RichIndexerConfig,
@@ -60,6 +75,7 @@ impl Default for IndexerConfig {
             db_keep_log_file_num: None,
             init_tip_hash: None,
             request_limit: None,
+            witness_prefixes: Vec::new(),
             rich_indexer: RichIndexerConfig::default(),
         }
     }
@@ -94,6 +110,24 @@ impl IndexerConfig {
     }
 }
 
+/// Checks the number and the sizes of the indexer witness prefixes.
+pub fn check_witness_prefixes(witness_prefixes: &[JsonBytes]) -> Result<(), String> {
+    if witness_prefixes.len() > MAX_WITNESS_PREFIXES {
+        return Err(format!(
+            "indexer.witness_prefixes: at most {MAX_WITNESS_PREFIXES} prefixes"
+        ));
+    }
+    if witness_prefixes
+        .iter()
+        .any(|prefix| prefix.is_empty() || prefix.len() > MAX_WITNESS_PREFIX_SIZE)
+    {
+        return Err(format!(
+            "indexer.witness_prefixes: the prefix size should be between 1 and {MAX_WITNESS_PREFIX_SIZE} bytes"
+        ));
+    }
+    Ok(())
+}
+
 fn _adjust(root_dir: &Path, indexer_dir: &Path, target: &mut PathBuf, sub: &str) {
     if target.to_str().is_none() || target.to_str() == Some("") {
         *target = indexer_dir.to_path_buf().join(sub);
@@ -1,8 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
 /// Miner config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// RPC client config options.
@@ -14,7 +15,7 @@ pub struct Config {
 }
 
 /// RPC client config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ClientConfig {
     /// CKB node RPC endpoint.
@@ -30,7 +31,7 @@ pub struct ClientConfig {
 }
 
 /// Miner worker config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "worker_type")]
 pub enum WorkerConfig {
     /// Dummy worker which submits an arbitrary answer.
@@ -43,7 +44,7 @@ pub enum WorkerConfig {
 ///
 /// Dummy worker can submit the new block at any time. This controls the pace that how much time
 /// the worker must wait before submitting a new block.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "delay_type")]
 pub enum DummyConfig {
     /// Waits for a constant delay.
@@ -73,7 +74,7 @@ pub enum DummyConfig {
 }
 
 /// Eaglesong worker config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct EaglesongSimpleConfig {
     /// Number of worker threads.
@@ -84,7 +85,7 @@ pub struct EaglesongSimpleConfig {
 }
 
 /// Specifies the hash function.
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
 pub enum ExtraHashFunction {
     /// Blake2b hash with CKB preferences.
     Blake2b,
@@ -1,4 +1,6 @@
+mod alert_rules;
 mod db;
+mod header_checkpoints;
 mod indexer;
 mod memory_tracker;
 mod miner;
@@ -10,21 +12,32 @@ mod rpc;
 mod store;
 mod tx_pool;
 
+pub use alert_rules::Config as AlertRulesConfig;
 pub use db::Config as DBConfig;
-pub use indexer::{IndexerConfig, IndexerSyncConfig};
+pub use header_checkpoints::{
+    Config as HeaderCheckpointsConfig, DEFAULT_CHECKPOINT_COUNT, DEFAULT_CHECKPOINT_INTERVAL,
+};
+pub use indexer::{
+    check_witness_prefixes, IndexerConfig, IndexerSyncConfig, MAX_WITNESS_PREFIXES,
+    MAX_WITNESS_PREFIX_SIZE,
+};
 pub use memory_tracker::Config as MemoryTrackerConfig;
 pub use miner::{
     ClientConfig as MinerClientConfig, Config as MinerConfig, DummyConfig, EaglesongSimpleConfig,
     ExtraHashFunction, WorkerConfig as MinerWorkerConfig,
 };
 pub use network::{
-    default_support_all_protocols, Config as NetworkConfig, HeaderMapConfig, SupportProtocol,
-    SyncConfig,
+    default_support_all_protocols, BootnodeSetConfig, Config as NetworkConfig, HeaderMapConfig,
+    ListenerConfig, MisbehaviorConfig, OutboundTargetsConfig, SupportProtocol, SyncConfig,
+    TxRelayConfig,
 };
 pub use network_alert::Config as NetworkAlertConfig;
 pub use notify::Config as NotifyConfig;
 pub use rich_indexer::{DBDriver, RichIndexerConfig};
-pub use rpc::{Config as RpcConfig, Module as RpcModule};
+pub use rpc::{
+    Config as RpcConfig, MethodClass as RpcMethodClass, Module as RpcModule,
+    SubscriptionResumeConfig, TimeoutsConfig as RpcTimeoutsConfig, TlsConfig as RpcTlsConfig,
+};
 pub use store::Config as StoreConfig;
 pub use tx_pool::{BlockAssemblerConfig, TxPoolConfig};
 
@@ -1,10 +1,13 @@
 use ckb_types::{H256, U256};
 use multiaddr::Multiaddr;
 use rand::Rng;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{Error, ErrorKind, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use ubyte::ByteUnit;
 
 // Max data size in send buffer: 24MB (a little larger than max frame length)
@@ -13,8 +16,32 @@ const DEFAULT_SEND_BUFFER: usize = 24 * 1024 * 1024;
 // Tentacle inner bound channel size, default 128
 const DEFAULT_CHANNEL_SIZE: usize = 128;
 
+// The lower bound of the adapted outbound targets
+const DEFAULT_OUTBOUND_MIN_TARGET: u32 = 2;
+
+// The interval between the health checks of a bootnode
+const DEFAULT_BOOTNODE_HEALTH_CHECK_INTERVAL_SECS: u64 = 600;
+
+// The interval between the connectivity self-tests
+const DEFAULT_REACHABILITY_CHECK_INTERVAL_SECS: u64 = 600;
+
+// The name of the bootnode set formed by `bootnodes`
+const DEFAULT_BOOTNODE_SET: &str = "default";
+
+// A peer is banned once its misbehavior score reaches this
+const DEFAULT_MISBEHAVIOR_BAN_THRESHOLD: u32 = 100;
+
+// The weight of the misbehavior kinds not configured, which bans the peer at once
+const DEFAULT_MISBEHAVIOR_WEIGHT: u32 = 100;
+
+// The misbehavior scores halve every hour
+const DEFAULT_MISBEHAVIOR_DECAY_HALF_LIFE_SECS: u64 = 3600;
+
+// The transactions larger than 2KB are always announced by hashes
+const DEFAULT_TX_ANNOUNCE_SIZE_THRESHOLD: usize = 2 * 1024;
+
 /// Network config options.
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// Only connect to whitelist peers.
@@ -51,21 +78,55 @@ pub struct Config {
     /// The interval between trials to connect more outbound peers.
     pub connect_outbound_interval_secs: u64,
     /// Listen addresses.
+    #[schemars(with = "Vec<String>")]
     pub listen_addresses: Vec<Multiaddr>,
+    /// Listeners with their own inbound caps and advertisement, used together with
+    /// `listen_addresses`, which are advertised and not capped.
+    ///
+    /// It is used to listen on both IPv4 and IPv6, or on several ports of a multi-homed server.
+    #[serde(default)]
+    pub listeners: Vec<ListenerConfig>,
     /// Public addresses.
     ///
     /// Set this if this is different from `listen_addresses`.
     #[serde(default)]
+    #[schemars(with = "Vec<String>")]
     pub public_addresses: Vec<Multiaddr>,
     /// A list of peers used to boot the node discovery.
     ///
     /// Bootnodes are used to bootstrap the discovery when local peer storage is empty.
+    #[schemars(with = "Vec<String>")]
     pub bootnodes: Vec<Multiaddr>,
+    /// Weighted bootnode sets, used together with `bootnodes`, which form a set named "default"
+    /// with weight 1.
+    ///
+    /// The bootnodes are picked by the weights of their sets, and the ones failing the health
+    /// checks are rotated away until they are reachable again.
+    #[serde(default)]
+    pub bootnode_sets: Vec<BootnodeSetConfig>,
+    /// The interval in seconds between the health checks of a bootnode.
+    ///
+    /// Default is 600.
+    pub bootnode_health_check_interval_secs: Option<u64>,
+    /// The interval in seconds between the connectivity self-tests, which dial the public
+    /// addresses of the node to tell whether it accepts inbound connections.
+    ///
+    /// Default is 600.
+    pub reachability_check_interval_secs: Option<u64>,
     /// A list of peers added in the whitelist.
     ///
     /// When `whitelist_only` is enabled, the node will only connect to peers in this list.
     #[serde(default)]
+    #[schemars(with = "Vec<String>")]
     pub whitelist_peers: Vec<Multiaddr>,
+    /// The allowlist file of a private network.
+    ///
+    /// When it is set, only the peers listed in the file can connect to the node or be connected,
+    /// the whitelist peers included. Each line is a peer ID or a hex encoded compressed
+    /// secp256k1 public key, and the text after `#` is ignored. The file is reloaded when the
+    /// node receives `SIGHUP`, and the peers removed from it are disconnected.
+    #[serde(default)]
+    pub allowed_peers_file: Option<PathBuf>,
     /// Enable UPNP when the router supports it.
     #[serde(default)]
     pub upnp: bool,
@@ -83,15 +144,129 @@ pub struct Config {
     /// Network use reuse port or not
     #[serde(default = "default_reuse")]
     pub reuse_port_on_linux: bool,
+    /// Compress the large P2P messages with snappy, the received messages are decompressed
+    /// either way
+    #[serde(default = "default_compression")]
+    pub compression: bool,
     /// Chain synchronization config options.
     #[serde(default)]
     pub sync: SyncConfig,
     /// Tentacle inner channel_size.
     pub channel_size: Option<usize>,
+    /// Outbound peer targets by role.
+    #[serde(default)]
+    pub outbound_targets: OutboundTargetsConfig,
+    /// The scoring of the peer misbehaviors reported by the protocols.
+    #[serde(default)]
+    pub misbehavior: MisbehaviorConfig,
+}
+
+/// A weighted set of bootnodes.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BootnodeSetConfig {
+    /// The set name, e.g., the region or the operator of the bootnodes.
+    pub name: String,
+    /// The relative probability to pick the bootnodes of this set.
+    ///
+    /// Default is 1, the set is not used if it is 0.
+    #[serde(default = "default_bootnode_set_weight")]
+    pub weight: u32,
+    /// The bootnodes in the set.
+    #[schemars(with = "Vec<String>")]
+    pub bootnodes: Vec<Multiaddr>,
+}
+
+const fn default_bootnode_set_weight() -> u32 {
+    1
+}
+
+/// A listen address with its own inbound cap and advertisement.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ListenerConfig {
+    /// The listen address.
+    #[schemars(with = "String")]
+    pub address: Multiaddr,
+    /// The maximum number of inbound peers accepted via this listener.
+    ///
+    /// The inbound peers are only limited by `max_peers` if it is not set. Tentacle does not
+    /// tell which listener accepts a session, so the inbound peers are counted by the IP version
+    /// and the transport of their addresses, the listeners sharing both share the sum of their
+    /// caps. If all the listeners are capped, the inbound peers are capped by the sum of all the
+    /// caps in total as well.
+    pub max_inbound_peers: Option<u32>,
+    /// Whether to advertise the address to the peers.
+    ///
+    /// Default is true. Disable it for the addresses only reachable in a private network.
+    #[serde(default = "default_advertise_listener")]
+    pub advertise: bool,
+}
+
+const fn default_advertise_listener() -> bool {
+    true
+}
+
+/// Outbound peer targets by role.
+///
+/// A peer usually serves both roles. The node dials more outbound peers until both targets are
+/// met, and never exceeds `max_outbound_peers`. Beyond the targets, the relay protocol of the
+/// newest relay peers is closed, and the newest sync peers not serving the relay role are
+/// disconnected.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OutboundTargetsConfig {
+    /// The target number of outbound peers which open the sync protocol.
+    ///
+    /// Default is `max_outbound_peers`.
+    #[serde(default)]
+    pub sync: Option<u32>,
+    /// The target number of outbound peers which open the relay protocol.
+    ///
+    /// Default is `max_outbound_peers`.
+    #[serde(default)]
+    pub relay: Option<u32>,
+    /// The bandwidth available to the p2p messages per second, in both directions.
+    ///
+    /// The targets are lowered one by one while the measured traffic exceeds 80% of it, and are
+    /// restored one by one while the traffic is below 50% of it. The targets are fixed if it is
+    /// not set.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub bandwidth_limit: Option<ByteUnit>,
+    /// The lower bound of the adapted targets.
+    ///
+    /// Default is 2.
+    #[serde(default)]
+    pub min_peers: Option<u32>,
+}
+
+/// The scoring of the peer misbehaviors.
+///
+/// Every misbehavior reported by the protocols adds the weight of its kind to the score of the
+/// peer, and the peer is banned once the score reaches `ban_threshold`. The scores decay
+/// exponentially over time.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MisbehaviorConfig {
+    /// The score to ban a peer.
+    ///
+    /// Default is 100.
+    pub ban_threshold: Option<u32>,
+    /// The seconds for a score to decay to its half.
+    ///
+    /// Default is 3600, the scores never decay if it is 0.
+    pub decay_half_life_secs: Option<u64>,
+    /// The weights of the misbehavior kinds, e.g., `BlockIsInvalid` and
+    /// `ProtocolMessageIsMalformed`.
+    ///
+    /// The weight of a kind not listed is 100.
+    #[serde(default)]
+    pub weights: BTreeMap<String, u32>,
 }
 
 /// Chain synchronization config options.
-#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SyncConfig {
     /// Header map config options.
@@ -103,12 +278,51 @@ pub struct SyncConfig {
     /// Proof of minimum work during synchronization
     #[serde(skip, default)]
     pub min_chain_work: U256,
+    /// Transaction relay config options.
+    #[serde(default)]
+    pub tx_relay: TxRelayConfig,
+}
+
+/// Transaction relay config options.
+///
+/// The transactions are relayed by announcing the hashes, and the peers fetch the ones they
+/// don't know. A peer can opt in to receive the full small transactions immediately, which saves
+/// a round trip at the cost of receiving the transactions it already knows.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TxRelayConfig {
+    /// The transactions larger than this size in bytes are always announced by hashes and sent
+    /// only on request, even to the peers which opt in to the push. It's capped at 65536, the
+    /// largest transactions the peers accept without the request.
+    ///
+    /// Default is 2048.
+    #[serde(default = "default_tx_announce_size_threshold")]
+    pub announce_size_threshold: usize,
+    /// Whether to ask the peers to push the full small transactions immediately instead of
+    /// announcing the hashes. The peers decide the size by their own `announce_size_threshold`.
+    ///
+    /// Default is false.
+    #[serde(default)]
+    pub accept_push: bool,
+}
+
+impl Default for TxRelayConfig {
+    fn default() -> Self {
+        TxRelayConfig {
+            announce_size_threshold: DEFAULT_TX_ANNOUNCE_SIZE_THRESHOLD,
+            accept_push: false,
+        }
+    }
+}
+
+const fn default_tx_announce_size_threshold() -> usize {
+    DEFAULT_TX_ANNOUNCE_SIZE_THRESHOLD
 }
 
 /// Header map config options.
 ///
 /// Header map stores the block headers before fully verifying the block.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct HeaderMapConfig {
     /// The maximum size of data in memory
@@ -117,6 +331,7 @@ pub struct HeaderMapConfig {
     pub backend_close_threshold: Option<usize>,
     /// The maximum amount memory limit
     #[serde(default = "default_memory_limit")]
+    #[schemars(with = "String")]
     pub memory_limit: ByteUnit,
 }
 
@@ -134,7 +349,7 @@ const fn default_memory_limit() -> ByteUnit {
     ByteUnit::Megabyte(256)
 }
 
-#[derive(Clone, Debug, Copy, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Serialize, Deserialize, Hash, JsonSchema)]
 #[allow(missing_docs)]
 pub enum SupportProtocol {
     Ping,
@@ -248,6 +463,17 @@ impl Config {
         path
     }
 
+    /// Canonicalizes the paths in the config options.
+    ///
+    /// The relative `allowed_peers_file` is relative to the `root_dir`.
+    pub fn adjust(&mut self, root_dir: &Path) {
+        if let Some(file) = self.allowed_peers_file.as_mut() {
+            if file.is_relative() {
+                *file = root_dir.join(&file);
+            }
+        }
+    }
+
     /// Creates missing directories.
     pub fn create_dir_if_not_exists(&self) -> Result<(), Error> {
         if !self.path.exists() {
@@ -267,6 +493,54 @@ impl Config {
         self.max_outbound_peers
     }
 
+    /// Gets the configured outbound target of sync peers.
+    pub fn outbound_sync_target(&self) -> u32 {
+        self.outbound_targets
+            .sync
+            .unwrap_or(self.max_outbound_peers)
+            .min(self.max_outbound_peers)
+    }
+
+    /// Gets the configured outbound target of relay peers.
+    pub fn outbound_relay_target(&self) -> u32 {
+        self.outbound_targets
+            .relay
+            .unwrap_or(self.max_outbound_peers)
+            .min(self.max_outbound_peers)
+    }
+
+    /// Gets the lower bound of the adapted outbound targets.
+    pub fn outbound_min_target(&self) -> u32 {
+        self.outbound_targets
+            .min_peers
+            .unwrap_or(DEFAULT_OUTBOUND_MIN_TARGET)
+    }
+
+    /// Gets the score to ban a misbehaving peer.
+    pub fn misbehavior_ban_threshold(&self) -> u32 {
+        self.misbehavior
+            .ban_threshold
+            .unwrap_or(DEFAULT_MISBEHAVIOR_BAN_THRESHOLD)
+    }
+
+    /// Gets the time for a misbehavior score to decay to its half.
+    pub fn misbehavior_decay_half_life(&self) -> Duration {
+        Duration::from_secs(
+            self.misbehavior
+                .decay_half_life_secs
+                .unwrap_or(DEFAULT_MISBEHAVIOR_DECAY_HALF_LIFE_SECS),
+        )
+    }
+
+    /// Gets the weight of a misbehavior kind.
+    pub fn misbehavior_weight(&self, kind: &str) -> u32 {
+        self.misbehavior
+            .weights
+            .get(kind)
+            .copied()
+            .unwrap_or(DEFAULT_MISBEHAVIOR_WEIGHT)
+    }
+
     /// Gets maximum send buffer size.
     pub fn max_send_buffer(&self) -> usize {
         self.max_send_buffer.unwrap_or(DEFAULT_SEND_BUFFER)
@@ -313,6 +587,53 @@ impl Config {
         self.bootnodes.clone()
     }
 
+    /// Gets the weighted bootnode sets, including the set formed by `bootnodes`.
+    pub fn bootnode_sets(&self) -> Vec<BootnodeSetConfig> {
+        let mut sets = Vec::with_capacity(self.bootnode_sets.len() + 1);
+        if !self.bootnodes.is_empty() {
+            sets.push(BootnodeSetConfig {
+                name: DEFAULT_BOOTNODE_SET.to_owned(),
+                weight: default_bootnode_set_weight(),
+                bootnodes: self.bootnodes.clone(),
+            });
+        }
+        sets.extend(self.bootnode_sets.iter().cloned());
+        sets
+    }
+
+    /// Gets all the listeners, including the ones formed by `listen_addresses`.
+    pub fn listeners(&self) -> Vec<ListenerConfig> {
+        let mut listeners: Vec<ListenerConfig> = self
+            .listen_addresses
+            .iter()
+            // the settings in `listeners` win for an address listed in both
+            .filter(|address| self.listeners.iter().all(|l| &l.address != *address))
+            .map(|address| ListenerConfig {
+                address: address.clone(),
+                max_inbound_peers: None,
+                advertise: default_advertise_listener(),
+            })
+            .collect();
+        listeners.extend(self.listeners.iter().cloned());
+        listeners
+    }
+
+    /// Gets the interval between the health checks of a bootnode.
+    pub fn bootnode_health_check_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.bootnode_health_check_interval_secs
+                .unwrap_or(DEFAULT_BOOTNODE_HEALTH_CHECK_INTERVAL_SECS),
+        )
+    }
+
+    /// Gets the interval between the connectivity self-tests.
+    pub fn reachability_check_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.reachability_check_interval_secs
+                .unwrap_or(DEFAULT_REACHABILITY_CHECK_INTERVAL_SECS),
+        )
+    }
+
     /// Checks whether the outbound peer service should be enabled.
     pub fn outbound_peer_service_enabled(&self) -> bool {
         self.connect_outbound_interval_secs > 0
@@ -329,3 +650,9 @@ impl Config {
 const fn default_reuse() -> bool {
     true
 }
+
+/// The large messages, such as blocks and transactions, are compressed by default to save the
+/// bandwidth
+const fn default_compression() -> bool {
+    true
+}
@@ -1,8 +1,9 @@
 use ckb_jsonrpc_types::JsonBytes;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Network alert config options.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The minimum number of required signatures to send a network alert.
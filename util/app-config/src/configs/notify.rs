@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 /// Notify config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// An executable script to be called whenever there's a new block in the canonical chain.
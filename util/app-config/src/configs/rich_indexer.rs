@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{default::Default, path::PathBuf};
 
@@ -5,7 +6,7 @@ const PGSQL: &str = "postgres://";
 const SQLITE: &str = "sqlite://";
 
 /// Rich indexer database type.
-#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq, Copy)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq, Copy, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DBDriver {
     /// Sqlite config options.
@@ -25,7 +26,7 @@ impl ToString for DBDriver {
 }
 
 /// Rich indexer config options.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RichIndexerConfig {
     /// Rich indexer database type.
     #[serde(default)]
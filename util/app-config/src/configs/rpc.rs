@@ -1,8 +1,11 @@
 use ckb_jsonrpc_types::Script;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// RPC modules.
-#[derive(Clone, Debug, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[allow(missing_docs)]
 pub enum Module {
     Net,
@@ -20,7 +23,7 @@ pub enum Module {
 }
 
 /// RPC config options.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// RPC server listen addresses.
@@ -35,6 +38,9 @@ pub struct Config {
     /// Only TCP and WS are supported to subscribe events via the Subscription RPC module.
     #[serde(default)]
     pub ws_listen_address: Option<String>,
+    /// Serves the HTTP and WS listeners over TLS, i.e., HTTPS and WSS.
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
     /// Max request body size in bytes.
     pub max_request_body_size: usize,
     /// Number of RPC worker threads.
@@ -57,9 +63,136 @@ pub struct Config {
     /// Customized extra well known type scripts.
     #[serde(default)]
     pub extra_well_known_type_scripts: Vec<Script>,
+    /// Keeps the recent events of every subscription topic, so a subscriber reconnecting with a
+    /// resume token receives the events it missed.
+    ///
+    /// The subscriptions can't be resumed when it is not set.
+    #[serde(default)]
+    pub subscription_resume: Option<SubscriptionResumeConfig>,
+    /// How long in seconds the result of `send_transaction` is cached for the retries with the
+    /// same idempotency key, 0 disables the cache.
+    #[serde(default = "default_send_transaction_idempotency_window_secs")]
+    pub send_transaction_idempotency_window_secs: u64,
+    /// Timeouts of the method calls by their classes.
+    #[serde(default)]
+    pub timeouts: TimeoutsConfig,
+}
+
+/// The classes of the RPC methods by how long a call is expected to take.
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MethodClass {
+    /// Cheap lookups, e.g., `get_tip_block_number`.
+    Fast,
+    /// The methods not in the other classes.
+    Standard,
+    /// Scans and verifications, e.g., `get_cells` and `estimate_cycles`.
+    Heavy,
+}
+
+/// Timeout options of the RPC method calls.
+///
+/// A call not completed within the timeout of its method class returns the error `Timeout`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutsConfig {
+    /// Timeout in milliseconds of the fast methods.
+    #[serde(default = "default_fast_timeout_ms")]
+    pub fast_ms: u64,
+    /// Timeout in milliseconds of the standard methods.
+    #[serde(default = "default_standard_timeout_ms")]
+    pub standard_ms: u64,
+    /// Timeout in milliseconds of the heavy methods.
+    #[serde(default = "default_heavy_timeout_ms")]
+    pub heavy_ms: u64,
+    /// Overrides the built-in classes of the methods, keyed by the method names.
+    #[serde(default)]
+    pub methods: BTreeMap<String, MethodClass>,
+}
+
+/// TLS options for the RPC HTTP and WS listeners.
+///
+/// The certificate and key are reloaded from the files when the process receives `SIGHUP`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Path to the PEM encoded certificate chain.
+    pub cert_path: PathBuf,
+    /// Path to the PEM encoded private key.
+    pub key_path: PathBuf,
+}
+
+/// Retention options of the events kept for resuming the subscriptions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionResumeConfig {
+    /// Seconds an event is kept after it is pushed.
+    #[serde(default = "default_subscription_resume_retention_secs")]
+    pub retention_secs: u64,
+    /// Max number of events kept for a topic.
+    #[serde(default = "default_subscription_resume_max_events")]
+    pub max_events: usize,
+}
+
+const fn default_send_transaction_idempotency_window_secs() -> u64 {
+    600
+}
+
+const fn default_fast_timeout_ms() -> u64 {
+    3_000
+}
+
+const fn default_standard_timeout_ms() -> u64 {
+    30_000
+}
+
+const fn default_heavy_timeout_ms() -> u64 {
+    120_000
+}
+
+const fn default_subscription_resume_retention_secs() -> u64 {
+    60
+}
+
+const fn default_subscription_resume_max_events() -> usize {
+    1000
+}
+
+impl Default for SubscriptionResumeConfig {
+    fn default() -> Self {
+        SubscriptionResumeConfig {
+            retention_secs: default_subscription_resume_retention_secs(),
+            max_events: default_subscription_resume_max_events(),
+        }
+    }
+}
+
+impl Default for TimeoutsConfig {
+    fn default() -> Self {
+        TimeoutsConfig {
+            fast_ms: default_fast_timeout_ms(),
+            standard_ms: default_standard_timeout_ms(),
+            heavy_ms: default_heavy_timeout_ms(),
+            methods: BTreeMap::new(),
+        }
+    }
 }
 
 impl Config {
+    /// Canonicalizes paths in the config options.
+    ///
+    /// Relative TLS file paths are relative to the `root_dir`.
+    pub fn adjust(&mut self, root_dir: &Path) {
+        if let Some(tls) = self.tls.as_mut() {
+            if tls.cert_path.is_relative() {
+                tls.cert_path = root_dir.join(&tls.cert_path);
+            }
+            if tls.key_path.is_relative() {
+                tls.key_path = root_dir.join(&tls.key_path);
+            }
+        }
+    }
+
     /// Checks whether the Net module is enabled.
     pub fn net_enable(&self) -> bool {
         self.modules.contains(&Module::Net)
@@ -18,4 +18,9 @@ pub struct Config {
     pub block_extensions_cache_size: usize,
     /// whether enable freezer
     pub freezer_enable: bool,
+    /// Prunes the proposal ids and the uncle proposals of the main chain blocks older than this
+    /// number of epochs, the block and uncle headers are kept.
+    ///
+    /// Pruning is disabled when it is not set, or when the freezer is enabled.
+    pub uncles_and_proposals_retention_epochs: Option<u64>,
 }
@@ -1,6 +1,7 @@
 use ckb_jsonrpc_types::{FeeRateDef, JsonBytes, ScriptHashType};
-use ckb_types::core::{Cycle, FeeRate};
+use ckb_types::core::{BlockNumber, Cycle, FeeRate};
 use ckb_types::H256;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use url::Url;
@@ -9,6 +10,9 @@ use url::Url;
 /// Transaction pool configuration
 #[derive(Clone, Debug, Serialize)]
 pub struct TxPoolConfig {
+    /// Whether the tx-pool service runs, the nodes without the tx-pool can neither relay
+    /// transactions nor mine blocks
+    pub enable: bool,
     /// Keep the transaction pool below <max_tx_pool_size> mb
     pub max_tx_pool_size: usize,
     /// txs with lower fee rate than this will not be relayed or be mined
@@ -20,6 +24,9 @@ pub struct TxPoolConfig {
     /// tx pool rejects txs that cycles greater than max_tx_verify_cycles
     pub max_tx_verify_cycles: Cycle,
     /// max ancestors size limit for a single tx
+    ///
+    /// The tx already proposed in the chain which exceeds the limit is kept in pending instead of
+    /// rejected, and it's retried at the next proposal window.
     pub max_ancestors_count: usize,
     /// rejected tx time to live by days
     pub keep_rejected_tx_hashes_days: u8,
@@ -30,6 +37,12 @@ pub struct TxPoolConfig {
     /// By default, it is a subdirectory of 'tx-pool' subdirectory under the data directory.
     #[serde(default)]
     pub persisted_data: PathBuf,
+    /// The file to persist the verified txs cache on the disk when tx pool have been shutdown,
+    /// so the restarted node doesn't verify the same txs again.
+    ///
+    /// By default, it is a subdirectory of 'tx-pool' subdirectory under the data directory.
+    #[serde(default)]
+    pub persisted_verify_cache: PathBuf,
     /// The recent reject record database directory path.
     ///
     /// By default, it is a subdirectory of 'tx-pool' subdirectory under the data directory.
@@ -37,12 +50,82 @@ pub struct TxPoolConfig {
     pub recent_reject: PathBuf,
     /// The expiration time for pool transactions in hours
     pub expiry_hours: u8,
+    /// The sliding window in seconds to count the RBF replacements of an outpoint
+    pub rbf_replacement_window_secs: u64,
+    /// The replacements count of an outpoint within the window before the required extra RBF fee escalates
+    pub rbf_free_replacements: usize,
+    /// The max replacements count of an outpoint within the window, further replacements are rejected
+    pub max_rbf_replacements: usize,
+    /// The max count of the txs replaced by a RBF tx, including the descendants of the conflicts
+    pub max_rbf_candidates: usize,
+    /// The max count of the descendants of a tx conflicting with a RBF tx
+    pub max_rbf_conflict_descendants: usize,
+    /// Whether a RBF tx can replace the txs in the gap, which are proposed but not yet committable
+    pub rbf_replace_gap: bool,
+    /// Whether a RBF tx can replace the proposed txs
+    pub rbf_replace_proposed: bool,
+    /// The soft cap of the total fees in shannons of the txs replaced by RBF within the window,
+    /// beyond which the required extra RBF fee grows in proportion, no cap if unset
+    pub rbf_replaced_fees_cap: Option<u64>,
+    /// Whether the transactions of detached blocks bypass the fee rate and size policies when
+    /// they are re-added to the pool after a reorg
+    pub reorg_readd_bypass_policy: bool,
+    /// The seconds the txs of a block template are protected from the eviction by the pool size
+    /// limit, 0 disables the protection
+    pub template_protection_secs: u64,
+    /// The local transactions paying more fee than this in shannons are rejected, unless the
+    /// submission allows high fees
+    pub max_absolute_fee: Option<u64>,
+    /// The local transactions paying higher fee rate than this in shannons/KB are rejected, unless
+    /// the submission allows high fees
+    pub max_fee_rate: Option<u64>,
+    /// The txs with a script group consuming more cycles than this are rejected, no limit if unset
+    pub max_script_group_cycles: Option<Cycle>,
+    /// The txs with a header dep less than this many blocks below the tip are rejected, no
+    /// requirement if unset
+    pub min_header_dep_depth: Option<BlockNumber>,
+    /// The max count of the conflicted txs kept, the least recently used ones are evicted first
+    pub max_conflicts_count: usize,
+    /// The max total size in bytes of the conflicted txs kept, the least recently used ones are
+    /// evicted first
+    pub max_conflicts_size: usize,
+    /// The witness store database directory path, which keeps the witnesses of the large txs in
+    /// the pool out of memory.
+    ///
+    /// By default, it is a subdirectory of 'tx-pool' subdirectory under the data directory.
+    #[serde(default)]
+    pub witness_store: PathBuf,
+    /// The txs whose witnesses are larger than this in bytes keep the witnesses in the witness
+    /// store, and only the skeleton needed for sorting and resolving stays in memory, disabled if
+    /// unset
+    pub lazy_witnesses_threshold: Option<usize>,
+    /// The max total size in bytes of the txs waiting in the verify queue
+    pub max_verify_queue_size: usize,
+    /// The max total declared cycles of the relayed txs waiting in the verify queue, no limit if
+    /// unset
+    pub max_verify_queue_cycles: Option<Cycle>,
+    /// The share of the local txs in the verification when the relayed txs are waiting as well
+    pub verify_queue_local_weight: u32,
+    /// The share of the relayed txs in the verification when the local txs are waiting as well
+    pub verify_queue_remote_weight: u32,
+    /// The oldest tx in the verify queue is verified first regardless of the weights once it has
+    /// waited this many milliseconds
+    pub verify_queue_max_wait_ms: u64,
+    /// Whether the txs in the verify queue are verified by the priority, the local txs first and
+    /// then the higher fee rate ones, instead of the order they are added
+    pub verify_queue_prioritize: bool,
+    /// The max count of the txs whose lifecycle events are kept for the debug RPC, the least
+    /// recently traced ones are forgotten first, 0 disables the tracing
+    pub max_traced_txs: usize,
+    /// The OTLP/HTTP traces endpoint, e.g., `http://127.0.0.1:4318/v1/traces`, which the finished
+    /// tx lifecycles are exported to as spans, disabled if unset
+    pub trace_otlp_endpoint: Option<String>,
 }
 
 /// Block assembler config options.
 ///
 /// The block assembler section tells CKB how to claim the miner rewards.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct BlockAssemblerConfig {
     /// The miner lock script code hash.
@@ -64,6 +147,7 @@ pub struct BlockAssemblerConfig {
     pub update_interval_millis: u64,
     /// Notify url
     #[serde(default)]
+    #[schemars(with = "Vec<String>")]
     pub notify: Vec<Url>,
     /// Notify scripts
     #[serde(default)]
@@ -89,6 +173,7 @@ impl TxPoolConfig {
     /// Canonicalizes paths in the config options.
     ///
     /// If `self.persisted_data` is not set, set it to `data_dir / tx_pool_persisted_data`.
+    /// The same for `self.persisted_verify_cache`.
     ///
     /// If `self.path` is relative, convert them to absolute path using
     /// `root_dir` as current working directory.
@@ -99,12 +184,24 @@ impl TxPoolConfig {
             &mut self.persisted_data,
             "persisted_data",
         );
+        _adjust(
+            root_dir,
+            tx_pool_dir.as_ref(),
+            &mut self.persisted_verify_cache,
+            "persisted_verify_cache",
+        );
         _adjust(
             root_dir,
             tx_pool_dir.as_ref(),
             &mut self.recent_reject,
             "recent_reject",
         );
+        _adjust(
+            root_dir,
+            tx_pool_dir.as_ref(),
+            &mut self.witness_store,
+            "witness_store",
+        );
     }
 }
 
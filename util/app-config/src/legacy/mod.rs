@@ -1,5 +1,6 @@
 //! Legacy CKB AppConfig and Miner AppConfig
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::path::PathBuf;
 
@@ -18,7 +19,7 @@ pub(crate) struct DeprecatedField {
 // The core legacy structs.
 //
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct CKBAppConfig {
     data_dir: PathBuf,
@@ -59,9 +60,13 @@ pub(crate) struct CKBAppConfig {
     notify: crate::NotifyConfig,
     #[serde(default)]
     indexer_v2: crate::IndexerConfig,
+    #[serde(default)]
+    alert_rules: crate::AlertRulesConfig,
+    #[serde(default)]
+    header_checkpoints: crate::HeaderCheckpointsConfig,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MinerAppConfig {
     data_dir: PathBuf,
@@ -106,6 +111,8 @@ impl From<CKBAppConfig> for crate::CKBAppConfig {
             alert_signature,
             notify,
             indexer_v2,
+            alert_rules,
+            header_checkpoints,
         } = input;
         #[cfg(not(feature = "with_sentry"))]
         let _ = sentry;
@@ -131,6 +138,8 @@ impl From<CKBAppConfig> for crate::CKBAppConfig {
             alert_signature,
             notify,
             indexer: indexer_v2,
+            alert_rules,
+            header_checkpoints,
         }
     }
 }
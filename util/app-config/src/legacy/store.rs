@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct StoreConfig {
     header_cache_size: usize,
@@ -13,6 +14,8 @@ pub(crate) struct StoreConfig {
     block_extensions_cache_size: usize,
     #[serde(default = "default_freezer_enable")]
     freezer_enable: bool,
+    #[serde(default)]
+    uncles_and_proposals_retention_epochs: Option<u64>,
 }
 
 const fn default_block_extensions_cache_size() -> usize {
@@ -40,6 +43,7 @@ impl Default for StoreConfig {
             cellbase_cache_size: None,
             block_extensions_cache_size: default_block_extensions_cache_size(),
             freezer_enable: default_freezer_enable(),
+            uncles_and_proposals_retention_epochs: None,
         }
     }
 }
@@ -55,6 +59,7 @@ impl From<StoreConfig> for crate::StoreConfig {
             cellbase_cache_size: _,
             block_extensions_cache_size,
             freezer_enable,
+            uncles_and_proposals_retention_epochs,
         } = input;
         Self {
             header_cache_size,
@@ -64,6 +69,7 @@ impl From<StoreConfig> for crate::StoreConfig {
             block_uncles_cache_size,
             block_extensions_cache_size,
             freezer_enable,
+            uncles_and_proposals_retention_epochs,
         }
     }
 }
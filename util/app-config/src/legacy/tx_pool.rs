@@ -1,6 +1,7 @@
 use ckb_chain_spec::consensus::TWO_IN_TWO_OUT_CYCLES;
 use ckb_jsonrpc_types::FeeRateDef;
-use ckb_types::core::{Cycle, FeeRate};
+use ckb_types::core::{BlockNumber, Cycle, FeeRate};
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::cmp;
 use std::path::PathBuf;
@@ -17,11 +18,43 @@ const DEFAULT_MAX_ANCESTORS_COUNT: usize = 2_000;
 const DEFAULT_EXPIRY_HOURS: u8 = 12;
 // Default max_tx_pool_size 180mb
 const DEFAULT_MAX_TX_POOL_SIZE: usize = 180_000_000;
+// Default sliding window to count the RBF replacements of an outpoint, 10 minutes
+const DEFAULT_RBF_REPLACEMENT_WINDOW_SECS: u64 = 600;
+// Default replacements count of an outpoint before the extra RBF fee escalates
+const DEFAULT_RBF_FREE_REPLACEMENTS: usize = 10;
+// Default max replacements count of an outpoint within the window
+const DEFAULT_MAX_RBF_REPLACEMENTS: usize = 30;
+// Default max count of the txs replaced by a RBF tx
+const DEFAULT_MAX_RBF_CANDIDATES: usize = 100;
+// Default max count of the descendants of a tx conflicting with a RBF tx
+const DEFAULT_MAX_RBF_CONFLICT_DESCENDANTS: usize = 100;
+// Default policy for the RBF txs replacing the gap txs, allowed
+const DEFAULT_RBF_REPLACE_GAP: bool = true;
+// Default policy for the RBF txs replacing the proposed txs, allowed
+const DEFAULT_RBF_REPLACE_PROPOSED: bool = true;
+// Default policy for detached transactions re-added after a reorg, checked by fee rate and size
+const DEFAULT_REORG_READD_BYPASS_POLICY: bool = false;
+// Default protection of the block template txs from the eviction by size limit, 1 minute
+const DEFAULT_TEMPLATE_PROTECTION_SECS: u64 = 60;
+// Default max count of the conflicted txs kept
+const DEFAULT_MAX_CONFLICTS_COUNT: usize = 10_000;
+// Default max total size of the conflicted txs kept, 20mb
+const DEFAULT_MAX_CONFLICTS_SIZE: usize = 20_000_000;
+// Default max total size of the txs in the verify queue, 256mb
+const DEFAULT_MAX_VERIFY_QUEUE_SIZE: usize = 256_000_000;
+// Default share of the local and the relayed txs in the verification
+const DEFAULT_VERIFY_QUEUE_SOURCE_WEIGHT: u32 = 1;
+// Default max wait of the oldest tx in the verify queue before the weights are bypassed, 5 seconds
+const DEFAULT_VERIFY_QUEUE_MAX_WAIT_MS: u64 = 5_000;
+// Default max count of the txs whose lifecycle events are kept
+const DEFAULT_MAX_TRACED_TXS: usize = 10_000;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 #[allow(dead_code)]
 pub(crate) struct TxPoolConfig {
+    #[serde(default = "default_enable")]
+    enable: bool,
     #[serde(default = "default_max_tx_pool_size")]
     max_tx_pool_size: usize,
     max_mem_size: Option<usize>,
@@ -34,17 +67,77 @@ pub(crate) struct TxPoolConfig {
     #[serde(default = "default_keep_rejected_tx_hashes_count")]
     keep_rejected_tx_hashes_count: u64,
     #[serde(with = "FeeRateDef")]
+    #[schemars(with = "u64")]
     min_fee_rate: FeeRate,
     #[serde(with = "FeeRateDef", default = "default_min_rbf_rate")]
+    #[schemars(with = "u64")]
     min_rbf_rate: FeeRate,
     max_tx_verify_cycles: Cycle,
     max_ancestors_count: usize,
     #[serde(default)]
     persisted_data: PathBuf,
     #[serde(default)]
+    persisted_verify_cache: PathBuf,
+    #[serde(default)]
     recent_reject: PathBuf,
     #[serde(default = "default_expiry_hours")]
     expiry_hours: u8,
+    #[serde(default = "default_rbf_replacement_window_secs")]
+    rbf_replacement_window_secs: u64,
+    #[serde(default = "default_rbf_free_replacements")]
+    rbf_free_replacements: usize,
+    #[serde(default = "default_max_rbf_replacements")]
+    max_rbf_replacements: usize,
+    #[serde(default = "default_max_rbf_candidates")]
+    max_rbf_candidates: usize,
+    #[serde(default = "default_max_rbf_conflict_descendants")]
+    max_rbf_conflict_descendants: usize,
+    #[serde(default = "default_rbf_replace_gap")]
+    rbf_replace_gap: bool,
+    #[serde(default = "default_rbf_replace_proposed")]
+    rbf_replace_proposed: bool,
+    #[serde(default)]
+    rbf_replaced_fees_cap: Option<u64>,
+    #[serde(default = "default_reorg_readd_bypass_policy")]
+    reorg_readd_bypass_policy: bool,
+    #[serde(default = "default_template_protection_secs")]
+    template_protection_secs: u64,
+    #[serde(default)]
+    max_absolute_fee: Option<u64>,
+    #[serde(default)]
+    max_fee_rate: Option<u64>,
+    #[serde(default)]
+    max_script_group_cycles: Option<Cycle>,
+    #[serde(default)]
+    min_header_dep_depth: Option<BlockNumber>,
+    #[serde(default = "default_max_conflicts_count")]
+    max_conflicts_count: usize,
+    #[serde(default = "default_max_conflicts_size")]
+    max_conflicts_size: usize,
+    #[serde(default)]
+    witness_store: PathBuf,
+    #[serde(default)]
+    lazy_witnesses_threshold: Option<usize>,
+    #[serde(default = "default_max_verify_queue_size")]
+    max_verify_queue_size: usize,
+    #[serde(default)]
+    max_verify_queue_cycles: Option<Cycle>,
+    #[serde(default = "default_verify_queue_source_weight")]
+    verify_queue_local_weight: u32,
+    #[serde(default = "default_verify_queue_source_weight")]
+    verify_queue_remote_weight: u32,
+    #[serde(default = "default_verify_queue_max_wait_ms")]
+    verify_queue_max_wait_ms: u64,
+    #[serde(default)]
+    verify_queue_prioritize: bool,
+    #[serde(default = "default_max_traced_txs")]
+    max_traced_txs: usize,
+    #[serde(default)]
+    trace_otlp_endpoint: Option<String>,
+}
+
+fn default_enable() -> bool {
+    true
 }
 
 fn default_keep_rejected_tx_hashes_days() -> u8 {
@@ -67,6 +160,66 @@ fn default_min_rbf_rate() -> FeeRate {
     DEFAULT_MIN_RBF_RATE
 }
 
+fn default_rbf_replacement_window_secs() -> u64 {
+    DEFAULT_RBF_REPLACEMENT_WINDOW_SECS
+}
+
+fn default_rbf_free_replacements() -> usize {
+    DEFAULT_RBF_FREE_REPLACEMENTS
+}
+
+fn default_max_rbf_replacements() -> usize {
+    DEFAULT_MAX_RBF_REPLACEMENTS
+}
+
+fn default_max_rbf_candidates() -> usize {
+    DEFAULT_MAX_RBF_CANDIDATES
+}
+
+fn default_max_rbf_conflict_descendants() -> usize {
+    DEFAULT_MAX_RBF_CONFLICT_DESCENDANTS
+}
+
+fn default_rbf_replace_gap() -> bool {
+    DEFAULT_RBF_REPLACE_GAP
+}
+
+fn default_rbf_replace_proposed() -> bool {
+    DEFAULT_RBF_REPLACE_PROPOSED
+}
+
+fn default_reorg_readd_bypass_policy() -> bool {
+    DEFAULT_REORG_READD_BYPASS_POLICY
+}
+
+fn default_template_protection_secs() -> u64 {
+    DEFAULT_TEMPLATE_PROTECTION_SECS
+}
+
+fn default_max_conflicts_count() -> usize {
+    DEFAULT_MAX_CONFLICTS_COUNT
+}
+
+fn default_max_conflicts_size() -> usize {
+    DEFAULT_MAX_CONFLICTS_SIZE
+}
+
+fn default_max_verify_queue_size() -> usize {
+    DEFAULT_MAX_VERIFY_QUEUE_SIZE
+}
+
+fn default_verify_queue_source_weight() -> u32 {
+    DEFAULT_VERIFY_QUEUE_SOURCE_WEIGHT
+}
+
+fn default_verify_queue_max_wait_ms() -> u64 {
+    DEFAULT_VERIFY_QUEUE_MAX_WAIT_MS
+}
+
+fn default_max_traced_txs() -> usize {
+    DEFAULT_MAX_TRACED_TXS
+}
+
 impl Default for crate::TxPoolConfig {
     fn default() -> Self {
         TxPoolConfig::default().into()
@@ -76,6 +229,7 @@ impl Default for crate::TxPoolConfig {
 impl Default for TxPoolConfig {
     fn default() -> Self {
         Self {
+            enable: default_enable(),
             max_mem_size: None,
             max_tx_pool_size: DEFAULT_MAX_TX_POOL_SIZE,
             max_cycles: None,
@@ -89,8 +243,35 @@ impl Default for TxPoolConfig {
             max_tx_verify_cycles: DEFAULT_MAX_TX_VERIFY_CYCLES,
             max_ancestors_count: DEFAULT_MAX_ANCESTORS_COUNT,
             persisted_data: Default::default(),
+            persisted_verify_cache: Default::default(),
             recent_reject: Default::default(),
             expiry_hours: DEFAULT_EXPIRY_HOURS,
+            rbf_replacement_window_secs: DEFAULT_RBF_REPLACEMENT_WINDOW_SECS,
+            rbf_free_replacements: DEFAULT_RBF_FREE_REPLACEMENTS,
+            max_rbf_replacements: DEFAULT_MAX_RBF_REPLACEMENTS,
+            max_rbf_candidates: DEFAULT_MAX_RBF_CANDIDATES,
+            max_rbf_conflict_descendants: DEFAULT_MAX_RBF_CONFLICT_DESCENDANTS,
+            rbf_replace_gap: DEFAULT_RBF_REPLACE_GAP,
+            rbf_replace_proposed: DEFAULT_RBF_REPLACE_PROPOSED,
+            rbf_replaced_fees_cap: None,
+            reorg_readd_bypass_policy: DEFAULT_REORG_READD_BYPASS_POLICY,
+            template_protection_secs: DEFAULT_TEMPLATE_PROTECTION_SECS,
+            max_absolute_fee: None,
+            max_fee_rate: None,
+            max_script_group_cycles: None,
+            min_header_dep_depth: None,
+            max_conflicts_count: DEFAULT_MAX_CONFLICTS_COUNT,
+            max_conflicts_size: DEFAULT_MAX_CONFLICTS_SIZE,
+            witness_store: Default::default(),
+            lazy_witnesses_threshold: None,
+            max_verify_queue_size: DEFAULT_MAX_VERIFY_QUEUE_SIZE,
+            max_verify_queue_cycles: None,
+            verify_queue_local_weight: DEFAULT_VERIFY_QUEUE_SOURCE_WEIGHT,
+            verify_queue_remote_weight: DEFAULT_VERIFY_QUEUE_SOURCE_WEIGHT,
+            verify_queue_max_wait_ms: DEFAULT_VERIFY_QUEUE_MAX_WAIT_MS,
+            verify_queue_prioritize: false,
+            max_traced_txs: DEFAULT_MAX_TRACED_TXS,
+            trace_otlp_endpoint: None,
         }
     }
 }
@@ -98,6 +279,7 @@ impl Default for TxPoolConfig {
 impl From<TxPoolConfig> for crate::TxPoolConfig {
     fn from(input: TxPoolConfig) -> Self {
         let TxPoolConfig {
+            enable,
             max_mem_size: _,
             max_tx_pool_size,
             max_cycles: _,
@@ -111,11 +293,39 @@ impl From<TxPoolConfig> for crate::TxPoolConfig {
             max_tx_verify_cycles,
             max_ancestors_count,
             persisted_data,
+            persisted_verify_cache,
             recent_reject,
             expiry_hours,
+            rbf_replacement_window_secs,
+            rbf_free_replacements,
+            max_rbf_replacements,
+            max_rbf_candidates,
+            max_rbf_conflict_descendants,
+            rbf_replace_gap,
+            rbf_replace_proposed,
+            rbf_replaced_fees_cap,
+            reorg_readd_bypass_policy,
+            template_protection_secs,
+            max_absolute_fee,
+            max_fee_rate,
+            max_script_group_cycles,
+            min_header_dep_depth,
+            max_conflicts_count,
+            max_conflicts_size,
+            witness_store,
+            lazy_witnesses_threshold,
+            max_verify_queue_size,
+            max_verify_queue_cycles,
+            verify_queue_local_weight,
+            verify_queue_remote_weight,
+            verify_queue_max_wait_ms,
+            verify_queue_prioritize,
+            max_traced_txs,
+            trace_otlp_endpoint,
         } = input;
 
         Self {
+            enable,
             max_tx_pool_size,
             min_fee_rate,
             min_rbf_rate,
@@ -124,8 +334,35 @@ impl From<TxPoolConfig> for crate::TxPoolConfig {
             keep_rejected_tx_hashes_days,
             keep_rejected_tx_hashes_count,
             persisted_data,
+            persisted_verify_cache,
             recent_reject,
             expiry_hours,
+            rbf_replacement_window_secs,
+            rbf_free_replacements,
+            max_rbf_replacements: cmp::max(rbf_free_replacements, max_rbf_replacements),
+            max_rbf_candidates,
+            max_rbf_conflict_descendants,
+            rbf_replace_gap,
+            rbf_replace_proposed,
+            rbf_replaced_fees_cap,
+            reorg_readd_bypass_policy,
+            template_protection_secs,
+            max_absolute_fee,
+            max_fee_rate,
+            max_script_group_cycles,
+            min_header_dep_depth,
+            max_conflicts_count,
+            max_conflicts_size,
+            witness_store,
+            lazy_witnesses_threshold,
+            max_verify_queue_size,
+            max_verify_queue_cycles,
+            verify_queue_local_weight: cmp::max(1, verify_queue_local_weight),
+            verify_queue_remote_weight: cmp::max(1, verify_queue_remote_weight),
+            verify_queue_max_wait_ms,
+            verify_queue_prioritize,
+            max_traced_txs,
+            trace_otlp_endpoint,
         }
     }
 }
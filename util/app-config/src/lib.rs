@@ -13,10 +13,12 @@ mod tests;
 
 pub use app_config::{
     AppConfig, CKBAppConfig, ChainConfig, LogConfig, MetricsConfig, MinerAppConfig,
+    DEFAULT_CHAIN_STATS_WINDOWS,
 };
 pub use args::{
-    DaemonArgs, ExportArgs, ImportArgs, InitArgs, MigrateArgs, MinerArgs, PeerIDArgs, ReplayArgs,
-    ResetDataArgs, RunArgs, StatsArgs,
+    BenchArgs, DaemonArgs, ExportArgs, ExportIndexerArgs, ImportArgs, InitArgs, MigrateArgs,
+    MinerArgs, MoveColumnsArgs, PeerIDArgs, ReplayArgs, ResetDataArgs, RestoreWalArgs, RunArgs,
+    StatsArgs, VerifyFreezerArgs,
 };
 use ckb_logger::info;
 pub use configs::*;
@@ -122,6 +124,9 @@ impl Setup {
             block_assembler_advanced: matches.get_flag(cli::ARG_BA_ADVANCED),
             skip_chain_spec_check: matches.get_flag(cli::ARG_SKIP_CHAIN_SPEC_CHECK),
             overwrite_chain_spec: matches.get_flag(cli::ARG_OVERWRITE_CHAIN_SPEC),
+            repair_chain: matches.get_flag(cli::ARG_REPAIR_CHAIN),
+            skip_preflight: matches.get_flag(cli::ARG_SKIP_PREFLIGHT),
+            preflight_ntp: matches.get_flag(cli::ARG_PREFLIGHT_NTP),
             chain_spec_hash,
             indexer: matches.get_flag(cli::ARG_INDEXER),
             rich_indexer: matches.get_flag(cli::ARG_RICH_INDEXER),
@@ -135,15 +140,97 @@ impl Setup {
         let consensus = self.consensus()?;
         let config = self.config.into_ckb()?;
         let check = matches.get_flag(cli::ARG_MIGRATE_CHECK);
+        let list = matches.get_flag(cli::ARG_MIGRATE_LIST);
+        let dry_run = matches.get_flag(cli::ARG_MIGRATE_DRY_RUN);
         let force = matches.get_flag(cli::ARG_FORCE);
         let include_background = matches.get_flag(cli::ARG_INCLUDE_BACKGROUND);
+        let target = matches.get_one::<PathBuf>(cli::ARG_TARGET).cloned();
+        let threads = matches.get_one::<usize>(cli::ARG_MIGRATE_THREADS).cloned();
 
         Ok(MigrateArgs {
             config,
             consensus,
             check,
+            list,
+            dry_run,
             force,
             include_background,
+            target,
+            threads,
+        })
+    }
+
+    /// Executes `ckb restore-wal`.
+    pub fn restore_wal(self, matches: &ArgMatches) -> Result<RestoreWalArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let backup = matches
+            .get_one::<PathBuf>(cli::ARG_BACKUP)
+            .expect("required arg")
+            .clone();
+        let until = matches.get_one::<u64>(cli::ARG_UNTIL).cloned();
+
+        Ok(RestoreWalArgs {
+            db: config.db,
+            backup,
+            until,
+        })
+    }
+
+    /// Executes `ckb db move-columns`.
+    pub fn move_columns(self) -> Result<MoveColumnsArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+
+        Ok(MoveColumnsArgs { db: config.db })
+    }
+
+    /// Executes `ckb db verify-freezer`.
+    pub fn verify_freezer(self, matches: &ArgMatches) -> Result<VerifyFreezerArgs, ExitCode> {
+        let consensus = self.consensus()?;
+        let config = self.config.into_ckb()?;
+        let full = matches.get_flag(cli::ARG_FULL);
+        let refetch = matches.get_one::<String>(cli::ARG_REFETCH).cloned();
+
+        Ok(VerifyFreezerArgs {
+            config,
+            consensus,
+            full,
+            refetch,
+        })
+    }
+
+    /// Executes `ckb bench`.
+    pub fn bench(self, matches: &ArgMatches) -> Result<BenchArgs, ExitCode> {
+        let consensus = self.consensus()?;
+        let config = self.config.into_ckb()?;
+        let tmp_target = matches
+            .get_one::<PathBuf>(cli::ARG_TMP_TARGET)
+            .ok_or_else(|| {
+                eprintln!("Args Error: {:?} no found", cli::ARG_TMP_TARGET);
+                ExitCode::Cli
+            })?
+            .clone();
+        let suites = matches
+            .get_many::<String>(cli::ARG_SUITE)
+            .map(|suites| suites.cloned().collect())
+            .unwrap_or_default();
+        let from = matches.get_one::<u64>(cli::ARG_FROM).cloned();
+        let to = matches.get_one::<u64>(cli::ARG_TO).cloned();
+        let count = *matches
+            .get_one::<u64>(cli::ARG_COUNT)
+            .expect("has default value");
+        let json = matches
+            .get_one::<String>(cli::ARG_FORMAT)
+            .map_or(false, |format| format == "json");
+
+        Ok(BenchArgs {
+            config,
+            consensus,
+            tmp_target,
+            suites,
+            from,
+            to,
+            count,
+            json,
         })
     }
 
@@ -249,6 +336,25 @@ impl Setup {
         })
     }
 
+    /// Executes `ckb export-indexer`.
+    pub fn export_indexer(self, matches: &ArgMatches) -> Result<ExportIndexerArgs, ExitCode> {
+        let config = self.config.into_ckb()?;
+        let target = matches
+            .get_one::<PathBuf>(cli::ARG_TARGET)
+            .ok_or_else(|| {
+                eprintln!("Args Error: {:?} no found", cli::ARG_TARGET);
+                ExitCode::Cli
+            })?
+            .clone();
+        let height = matches.get_one::<u64>(cli::ARG_HEIGHT).cloned();
+
+        Ok(ExportIndexerArgs {
+            config,
+            target,
+            height,
+        })
+    }
+
     /// Executes `ckb daemon`.
     pub fn daemon(self, matches: &ArgMatches) -> Result<DaemonArgs, ExitCode> {
         let check = matches.get_flag(cli::ARG_DAEMON_CHECK);
@@ -319,6 +425,9 @@ impl Setup {
         let block_assembler_message = matches.get_one::<String>(cli::ARG_BA_MESSAGE).cloned();
 
         let import_spec = matches.get_one::<String>(cli::ARG_IMPORT_SPEC).cloned();
+        let spec_hash = matches
+            .get_one::<String>(cli::ARG_SPEC_HASH)
+            .map(|hash| H256::from_str(&hash[2..]).expect("checked by clap"));
 
         let customize_spec = {
             let genesis_message = matches.get_one::<String>(cli::ARG_GENESIS_MESSAGE).cloned();
@@ -340,6 +449,7 @@ impl Setup {
             block_assembler_hash_type,
             block_assembler_message,
             import_spec,
+            spec_hash,
             customize_spec,
         })
     }
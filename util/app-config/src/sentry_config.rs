@@ -1,6 +1,7 @@
 #![doc(hidden)]
 
 use ckb_build_info::Version;
+use schemars::JsonSchema;
 use sentry::{
     configure_scope, init, protocol::Event, types::Dsn, ClientInitGuard, ClientOptions, Level,
 };
@@ -8,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::sync::Arc;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SentryConfig {
     pub dsn: String,
@@ -25,6 +25,25 @@ fn test_bundled_config_files() {
         .expect("deserialize config");
 }
 
+#[test]
+fn test_config_json_schema() {
+    let schema = CKBAppConfig::json_schema();
+    let properties = schema["properties"].as_object().expect("properties");
+    for section in [
+        "data_dir", "logger", "chain", "db", "network", "rpc", "tx_pool", "store",
+    ] {
+        assert!(properties.contains_key(section), "missing {section}");
+    }
+    // `deny_unknown_fields` rejects the unknown options
+    assert_eq!(schema["additionalProperties"], false);
+    let required = schema["required"].as_array().expect("required");
+    assert!(required.contains(&"network".into()));
+
+    let schema = MinerAppConfig::json_schema();
+    let properties = schema["properties"].as_object().expect("properties");
+    assert!(properties.contains_key("miner"));
+}
+
 #[test]
 fn test_export_dev_config_files() {
     let dir = mkdir();
@@ -59,6 +78,7 @@ fn test_export_dev_config_files() {
         );
         assert_eq!(ckb_config.network.connect_outbound_interval_secs, 15);
         assert_eq!(ckb_config.rpc.listen_address, "127.0.0.1:7000");
+        assert!(ckb_config.tx_pool.enable);
     }
     {
         Resource::bundled_miner_config()
@@ -118,6 +138,33 @@ fn test_log_to_stdout_only() {
     }
 }
 
+#[test]
+fn test_invalid_indexer_witness_prefixes() {
+    let dir = mkdir();
+    let context = TemplateContext::new(
+        "dev",
+        vec![
+            ("rpc_port", "7000"),
+            ("p2p_port", "8000"),
+            ("log_to_file", "false"),
+            ("log_to_stdout", "true"),
+            ("block_assembler", ""),
+            ("spec_source", "bundled"),
+        ],
+    );
+    Resource::bundled_ckb_config()
+        .export(&context, dir.path())
+        .expect("export config files");
+    let config_file = dir.path().join("ckb.toml");
+    let mut config = std::fs::read_to_string(&config_file).expect("read config file");
+    // an empty prefix
+    config.push_str("\n[indexer_v2]\nwitness_prefixes = [\"0x\"]\n");
+    std::fs::write(&config_file, config).expect("write config file");
+
+    let result = AppConfig::load_for_subcommand(dir.path(), cli::CMD_RUN);
+    assert_eq!(result.err(), Some(crate::ExitCode::Config));
+}
+
 #[test]
 fn test_export_testnet_config_files() {
     let dir = mkdir();
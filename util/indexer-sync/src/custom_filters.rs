@@ -1,3 +1,4 @@
+use crate::error::Error;
 use ckb_types::{
     core::BlockView,
     packed::{Bytes, CellOutput},
@@ -7,7 +8,18 @@ use rhai::{Engine, EvalAltResult, Scope, AST};
 
 /// Custom filters
 ///
-/// base on embedded scripting language Rhai
+/// base on embedded scripting language Rhai, with these extensions:
+///
+/// * `and`, `or` and `not(x)`, the aliases of `&&`, `||` and `!x`, with the same precedences and
+///   short-circuit evaluation.
+/// * `hex_starts_with(hex, prefix)` and `hex_ends_with(hex, suffix)`, the case insensitive
+///   partial matching of the hex strings such as script args, the `0x` prefixes are optional.
+///   They are false if `hex` is `()`, e.g., `output.type?.args.hex_starts_with("0x01")`.
+/// * `between(value, min, max)`, whether `min <= value <= max`, the arguments are either the
+///   numbers returned by `to_uint`, or the strings accepted by `to_uint`, e.g.,
+///   `output.capacity.between("0x0", "0x174876e800")`.
+///
+/// The filters are compiled once, the clones share the compiled filters.
 pub struct CustomFilters {
     engine: Engine,
     block_filter: Option<AST>,
@@ -21,15 +33,93 @@ impl Clone for CustomFilters {
 }
 
 fn to_uint(s: &str) -> Result<U256, Box<EvalAltResult>> {
-    match &s[..2] {
-        "0b" => U256::from_bin_str(&s[2..]),
-        "0o" => U256::from_oct_str(&s[2..]),
-        "0x" => U256::from_hex_str(&s[2..]),
+    match s.get(..2) {
+        Some("0b") => U256::from_bin_str(&s[2..]),
+        Some("0o") => U256::from_oct_str(&s[2..]),
+        Some("0x") => U256::from_hex_str(&s[2..]),
         _ => U256::from_dec_str(s),
     }
     .map_err(|e| e.to_string().into())
 }
 
+fn strip_hex(s: &str) -> String {
+    s.strip_prefix("0x").unwrap_or(s).to_ascii_lowercase()
+}
+
+fn hex_starts_with(hex: &str, prefix: &str) -> bool {
+    strip_hex(hex).starts_with(&strip_hex(prefix))
+}
+
+fn hex_ends_with(hex: &str, suffix: &str) -> bool {
+    strip_hex(hex).ends_with(&strip_hex(suffix))
+}
+
+// Replaces the `and` and `or` keywords outside the literals and comments by `&&` and `||`, so
+// they short-circuit like the builtin operators
+fn alias_logical_operators(filter: &str) -> String {
+    let chars: Vec<char> = filter.chars().collect();
+    let mut result = String::with_capacity(filter.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' | '`' | '\'' => {
+                // copy the literal as is, skipping the escaped chars
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                result.extend(&chars[start..i]);
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                let start = i;
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                result.extend(&chars[start..i]);
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let start = i;
+                i += 2;
+                while i < chars.len() && !(chars[i - 1] == '*' && chars[i] == '/') {
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                result.extend(&chars[start..i]);
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                // a property or a method named `and` or `or` is kept
+                let is_member = result.trim_end().ends_with('.');
+                match word.as_str() {
+                    "and" if !is_member => result.push_str("&&"),
+                    "or" if !is_member => result.push_str("||"),
+                    _ => result.push_str(&word),
+                }
+            }
+            _ => {
+                result.push(c);
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+fn between(value: &str, min: &str, max: &str) -> Result<bool, Box<EvalAltResult>> {
+    let value = to_uint(value)?;
+    Ok(to_uint(min)? <= value && value <= to_uint(max)?)
+}
+
 macro_rules! register_ops {
     ($engine:ident $(, $op:tt)+ $(,)?) => {
         $(
@@ -38,22 +128,39 @@ macro_rules! register_ops {
     };
 }
 
+fn new_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.register_fn("to_uint", to_uint);
+    register_ops!(engine, +, -, *, /, %, ==, !=, <, <=, >, >=);
+    engine.register_fn("not", |a: bool| !a);
+
+    engine.register_fn("hex_starts_with", hex_starts_with);
+    engine.register_fn("hex_starts_with", |_: (), _: &str| false);
+    engine.register_fn("hex_ends_with", hex_ends_with);
+    engine.register_fn("hex_ends_with", |_: (), _: &str| false);
+    engine.register_fn("between", between);
+    engine.register_fn("between", |value: U256, min: U256, max: U256| {
+        min <= value && value <= max
+    });
+    engine
+}
+
 impl CustomFilters {
     /// Construct new CustomFilters
+    ///
+    /// Panics if a filter cannot be compiled, so the invalid filters are reported at startup.
     pub fn new(block_filter_str: Option<&str>, cell_filter_str: Option<&str>) -> Self {
-        let mut engine = Engine::new();
-        engine.register_fn("to_uint", to_uint);
-        register_ops!(engine, +, -, *, /, %, ==, !=, <, <=, >, >=);
+        let engine = new_engine();
 
         let block_filter = block_filter_str.map(|block_filter| {
             engine
-                .compile(block_filter)
-                .expect("compile block_filter should be ok")
+                .compile(alias_logical_operators(block_filter))
+                .unwrap_or_else(|err| panic!("invalid indexer block_filter: {err}"))
         });
         let cell_filter = cell_filter_str.map(|cell_filter| {
             engine
-                .compile(cell_filter)
-                .expect("compile cell_filter should be ok")
+                .compile(alias_logical_operators(cell_filter))
+                .unwrap_or_else(|err| panic!("invalid indexer cell_filter: {err}"))
         });
 
         Self {
@@ -65,53 +172,57 @@ impl CustomFilters {
 
     /// Construct new CustomFilters
     pub fn from_filters(block_filter: Option<AST>, cell_filter: Option<AST>) -> Self {
-        let mut engine = Engine::new();
-        engine.register_fn("to_uint", to_uint);
-        register_ops!(engine, +, -, *, /, %, ==, !=, <, <=, >, >=);
         Self {
-            engine,
+            engine: new_engine(),
             block_filter,
             cell_filter,
         }
     }
 
     /// Returns true if the block filter is match
-    pub fn is_block_filter_match(&self, block: &BlockView) -> bool {
-        self.block_filter
-            .as_ref()
-            .map(|block_filter| {
-                let json_block: ckb_jsonrpc_types::BlockView = block.clone().into();
-                let parsed_block = self
-                    .engine
-                    .parse_json(serde_json::to_string(&json_block).unwrap(), true)
-                    .unwrap();
-                let mut scope = Scope::new();
-                scope.push("block", parsed_block);
-                self.engine
-                    .eval_ast_with_scope(&mut scope, block_filter)
-                    .expect("eval block_filter should be ok")
-            })
-            .unwrap_or(true)
+    ///
+    /// Returns an error if the filter fails to evaluate, e.g., it calls `to_uint` on a malformed
+    /// number, or it doesn't evaluate to a bool.
+    pub fn is_block_filter_match(&self, block: &BlockView) -> Result<bool, Error> {
+        let block_filter = match self.block_filter.as_ref() {
+            Some(block_filter) => block_filter,
+            None => return Ok(true),
+        };
+        let json_block: ckb_jsonrpc_types::BlockView = block.clone().into();
+        let parsed_block = self
+            .engine
+            .parse_json(serde_json::to_string(&json_block).unwrap(), true)
+            .map_err(|err| Error::Filter(format!("parse block: {err}")))?;
+        let mut scope = Scope::new();
+        scope.push("block", parsed_block);
+        self.engine
+            .eval_ast_with_scope(&mut scope, block_filter)
+            .map_err(|err| Error::Filter(format!("eval block_filter: {err}")))
     }
 
     /// Returns true if the cell filter is match
-    pub fn is_cell_filter_match(&self, output: &CellOutput, output_data: &Bytes) -> bool {
-        self.cell_filter
-            .as_ref()
-            .map(|cell_filter| {
-                let json_output: ckb_jsonrpc_types::CellOutput = output.clone().into();
-                let parsed_output = self
-                    .engine
-                    .parse_json(serde_json::to_string(&json_output).unwrap(), true)
-                    .unwrap();
-                let mut scope = Scope::new();
-                scope.push("output", parsed_output);
-                scope.push("output_data", format!("{output_data:#x}"));
-                self.engine
-                    .eval_ast_with_scope(&mut scope, cell_filter)
-                    .expect("eval cell_filter should be ok")
-            })
-            .unwrap_or(true)
+    ///
+    /// Returns an error if the filter fails to evaluate, like `is_block_filter_match`.
+    pub fn is_cell_filter_match(
+        &self,
+        output: &CellOutput,
+        output_data: &Bytes,
+    ) -> Result<bool, Error> {
+        let cell_filter = match self.cell_filter.as_ref() {
+            Some(cell_filter) => cell_filter,
+            None => return Ok(true),
+        };
+        let json_output: ckb_jsonrpc_types::CellOutput = output.clone().into();
+        let parsed_output = self
+            .engine
+            .parse_json(serde_json::to_string(&json_output).unwrap(), true)
+            .map_err(|err| Error::Filter(format!("parse output: {err}")))?;
+        let mut scope = Scope::new();
+        scope.push("output", parsed_output);
+        scope.push("output_data", format!("{output_data:#x}"));
+        self.engine
+            .eval_ast_with_scope(&mut scope, cell_filter)
+            .map_err(|err| Error::Filter(format!("eval cell_filter: {err}")))
     }
 
     /// check cell filter enabled
@@ -10,6 +10,9 @@ pub enum Error {
     /// Invalid params error
     #[error("Invalid params {0}")]
     Params(String),
+    /// Custom filter evaluation error
+    #[error("Filter error {0}")]
+    Filter(String),
 }
 
 impl Error {
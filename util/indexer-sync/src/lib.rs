@@ -15,6 +15,7 @@ use ckb_async_runtime::{
     tokio::{self, time},
     Handle,
 };
+use ckb_db::resolve_column_paths;
 use ckb_db_schema::{
     COLUMN_BLOCK_BODY, COLUMN_BLOCK_EXTENSION, COLUMN_BLOCK_HEADER, COLUMN_BLOCK_PROPOSAL_IDS,
     COLUMN_BLOCK_UNCLE, COLUMN_INDEX, COLUMN_META,
@@ -54,8 +55,11 @@ pub trait IndexerSync {
     fn set_init_tip(&self, init_tip_number: u64, init_tip_hash: &H256);
 }
 
-/// Construct new secondary db instance
-pub fn new_secondary_db(ckb_db_config: &DBConfig, config: &IndexerSyncConfig) -> SecondaryDB {
+/// Construct new secondary db instance, returns an error if `db.column_paths` is invalid
+pub fn new_secondary_db(
+    ckb_db_config: &DBConfig,
+    config: &IndexerSyncConfig,
+) -> Result<SecondaryDB, Error> {
     let cf_names = vec![
         COLUMN_INDEX,
         COLUMN_META,
@@ -66,12 +70,15 @@ pub fn new_secondary_db(ckb_db_config: &DBConfig, config: &IndexerSyncConfig) ->
         COLUMN_BLOCK_EXTENSION,
     ];
     let secondary_opts = indexer_secondary_options(config);
-    SecondaryDB::open_cf(
+    let column_paths = resolve_column_paths(ckb_db_config)
+        .map_err(|err| Error::invalid_params(format!("invalid db.column_paths: {err}")))?;
+    Ok(SecondaryDB::open_cf(
         &secondary_opts,
         &ckb_db_config.path,
         cf_names,
+        &column_paths,
         config.secondary_path.to_string_lossy().to_string(),
-    )
+    ))
 }
 
 /// Indexer sync service
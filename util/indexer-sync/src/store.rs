@@ -1,12 +1,14 @@
 use crate::error::Error;
 
+use ckb_db::column_paths::set_column_path;
 use ckb_db_schema::Col;
 use ckb_store::{ChainStore, Freezer, StoreCache};
 use rocksdb::{
     ops::OpenCF, prelude::*, ColumnFamilyDescriptor, DBIterator, DBPinnableSlice, IteratorMode,
     SecondaryDB as SecondaryRocksDB, SecondaryOpenDescriptor,
 };
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Open DB as secondary instance with specified column families
@@ -42,7 +44,15 @@ pub struct SecondaryDB {
 
 impl SecondaryDB {
     /// Open a SecondaryDB
-    pub fn open_cf<P, I, N>(opts: &Options, path: P, cf_names: I, secondary_path: String) -> Self
+    ///
+    /// The columns placed in other directories by the primary are given by `column_paths`.
+    pub fn open_cf<P, I, N>(
+        opts: &Options,
+        path: P,
+        cf_names: I,
+        column_paths: &HashMap<Col, PathBuf>,
+        secondary_path: String,
+    ) -> Self
     where
         P: AsRef<Path>,
         I: IntoIterator<Item = N>,
@@ -50,7 +60,15 @@ impl SecondaryDB {
     {
         let cf_descriptors: Vec<_> = cf_names
             .into_iter()
-            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
+            .map(|name| {
+                let name = name.into();
+                let mut cf_opts = Options::default();
+                if let Some(dir) = column_paths.get(name.as_str()) {
+                    set_column_path(&mut cf_opts, path.as_ref(), dir)
+                        .expect("Failed to set the column path");
+                }
+                ColumnFamilyDescriptor::new(name, cf_opts)
+            })
             .collect();
 
         let descriptor = SecondaryOpenDescriptor::new(secondary_path);
@@ -18,6 +18,8 @@ pub type TxIndex = u32;
 pub type OutputIndex = u32;
 /// Cell index alias
 pub type CellIndex = u32;
+/// Witness index alias
+pub type WitnessIndex = u32;
 
 /// Cell type enum
 pub enum CellType {
@@ -38,6 +40,7 @@ pub enum CellType {
 /// | 160          | TxTypeScript       | TxHash                   |
 /// | 192          | TxHash             | TransactionInputs        | * rollback and prune
 /// | 224          | Header             | Transactions             |
+/// | 240          | TxWitness          | TxHash                   | * opt-in by witness_prefixes
 /// +--------------+--------------------+--------------------------+
 /// Storage indexer key type enum
 pub enum Key<'a> {
@@ -57,6 +60,8 @@ pub enum Key<'a> {
     TxHash(&'a Byte32),
     /// (block_number, block_hash, txs_filtered)
     Header(BlockNumber, &'a Byte32, bool),
+    /// Tx witness starting with the indexed prefix, used by get_transactions_by_witness_prefix
+    TxWitness(&'a [u8], BlockNumber, TxIndex, WitnessIndex),
 }
 
 /// Storage value
@@ -90,6 +95,8 @@ pub enum KeyPrefix {
     TxHash = 192,
     /// Header
     Header = 224,
+    /// Witness prefix Tx
+    TxWitness = 240,
 }
 
 impl<'a> Key<'a> {
@@ -149,6 +156,12 @@ impl<'a> From<Key<'a>> for Vec<u8> {
                     encoded.push(1);
                 }
             }
+            Key::TxWitness(witness_prefix, block_number, tx_index, witness_index) => {
+                encoded.extend_from_slice(&witness_key_prefix(witness_prefix));
+                encoded.extend_from_slice(&block_number.to_be_bytes());
+                encoded.extend_from_slice(&tx_index.to_be_bytes());
+                encoded.extend_from_slice(&witness_index.to_be_bytes());
+            }
         }
         encoded
     }
@@ -167,6 +180,15 @@ fn append_key(
     encoded.extend_from_slice(&io_index.to_be_bytes());
 }
 
+/// helper fn encodes the key prefix of the txs with the witnesses starting with `witness_prefix`,
+/// the prefix len goes first so that the keys of a prefix don't mix with the keys of the longer
+/// prefixes starting with it
+pub fn witness_key_prefix(witness_prefix: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![KeyPrefix::TxWitness as u8, witness_prefix.len() as u8];
+    encoded.extend_from_slice(witness_prefix);
+    encoded
+}
+
 /// helper fn extracts script fields raw data
 pub fn extract_raw_data(script: &Script) -> Vec<u8> {
     [
@@ -282,6 +304,8 @@ pub(crate) struct Indexer<S> {
     pool: Option<Arc<RwLock<Pool>>>,
     /// custom filters
     custom_filters: CustomFilters,
+    /// the txs with the witnesses starting with any of the prefixes are indexed
+    witness_prefixes: Vec<Vec<u8>>,
 }
 
 impl<S> Indexer<S> {
@@ -299,9 +323,16 @@ impl<S> Indexer<S> {
             prune_interval,
             pool,
             custom_filters,
+            witness_prefixes: Vec::new(),
         }
     }
 
+    /// Index the txs by the witnesses starting with any of the prefixes
+    pub fn with_witness_prefixes(mut self, witness_prefixes: Vec<Vec<u8>>) -> Self {
+        self.witness_prefixes = witness_prefixes;
+        self
+    }
+
     /// Return store reference
     #[allow(dead_code)]
     pub(crate) fn store(&self) -> &S {
@@ -318,7 +349,7 @@ where
         let mut batch = self.store.batch()?;
         let transactions = block.transactions();
         let pool = self.pool.as_ref().map(|p| p.write().expect("acquire lock"));
-        if !self.custom_filters.is_block_filter_match(block) {
+        if !self.custom_filters.is_block_filter_match(block)? {
             batch.put_kv(Key::Header(block.number(), &block.hash(), true), vec![])?;
             batch.commit()?;
 
@@ -368,7 +399,7 @@ where
 
                         if !self
                             .custom_filters
-                            .is_cell_filter_match(&output, &output_data)
+                            .is_cell_filter_match(&output, &output_data)?
                         {
                             continue;
                         } else {
@@ -434,7 +465,7 @@ where
 
                 if !self
                     .custom_filters
-                    .is_cell_filter_match(&output, &output_data)
+                    .is_cell_filter_match(&output, &output_data)?
                 {
                     continue;
                 } else {
@@ -479,6 +510,26 @@ where
                 )?;
             }
 
+            // insert witness prefix => tx_hash mapping, regardless of the cell filter
+            if !self.witness_prefixes.is_empty() {
+                for (witness_index, witness) in tx.witnesses().into_iter().enumerate() {
+                    let witness = witness.raw_data();
+                    for witness_prefix in &self.witness_prefixes {
+                        if witness.starts_with(witness_prefix) {
+                            batch.put_kv(
+                                Key::TxWitness(
+                                    witness_prefix,
+                                    block_number,
+                                    tx_index,
+                                    witness_index as u32,
+                                ),
+                                Value::TxHash(&tx_hash),
+                            )?;
+                        }
+                    }
+                }
+            }
+
             if tx_matched {
                 matched_txs.push((tx.hash(), tx.outputs().len() as u32, Some(tx_index)));
                 // insert tx
@@ -671,6 +722,22 @@ where
                 // delete transaction
                 batch.delete(transaction_key)?;
             }
+            // rollback witness prefixes, including the prefixes no longer configured
+            let mut seek_key = vec![KeyPrefix::TxWitness as u8];
+            while let Some(prefix_key) = self.next_witness_key_prefix(&seek_key)? {
+                let mut key_prefix = prefix_key.clone();
+                key_prefix.extend_from_slice(&block_number.to_be_bytes());
+                for (key, _value) in self
+                    .store
+                    .iter(&key_prefix, IteratorDirection::Forward)?
+                    .take_while(|(key, _value)| key.starts_with(&key_prefix))
+                {
+                    batch.delete(key)?;
+                }
+                // greater than the keys of the prefix, whose suffixes are
+                // BlockNumber (8) + TxIndex (4) + WitnessIndex (4)
+                seek_key = [prefix_key, vec![0xff; 17]].concat();
+            }
             // delete block transactions
             batch.delete(Key::Header(block_number, &block_hash, filtered).into_vec())?;
 
@@ -872,6 +939,37 @@ where
             .collect())
     }
 
+    // Returns the key prefix of the first indexed witness prefix from `seek_key`
+    fn next_witness_key_prefix(&self, seek_key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self
+            .store
+            .iter(seek_key, IteratorDirection::Forward)?
+            .next()
+            .and_then(|(key, _value)| {
+                if key.first() != Some(&(KeyPrefix::TxWitness as u8)) {
+                    return None;
+                }
+                let prefix_len = *key.get(1)? as usize;
+                key.get(..2 + prefix_len)
+                    .map(|prefix_key| prefix_key.to_vec())
+            }))
+    }
+
+    /// Get transactions by indexed witness prefix
+    #[allow(dead_code)]
+    pub(crate) fn get_transactions_by_witness_prefix(
+        &self,
+        witness_prefix: &[u8],
+    ) -> Result<Vec<Byte32>, Error> {
+        let start_key = witness_key_prefix(witness_prefix);
+
+        let iter = self.store.iter(&start_key, IteratorDirection::Forward)?;
+        Ok(iter
+            .take_while(|(key, _)| key.starts_with(&start_key))
+            .map(|(_key, value)| Byte32::from_slice(&value).expect("stored tx hash"))
+            .collect())
+    }
+
     /// Given an OutPoint representing a live cell, returns the following components
     /// related to the live cell:
     /// * CellOutput
@@ -2298,4 +2396,188 @@ mod tests {
                 .len()
         );
     }
+
+    #[test]
+    fn custom_cell_filter_expression() {
+        let filters = CustomFilters::new(
+            None,
+            Some(
+                r#"output.lock.args.hex_starts_with("0x6C6F636B") and
+                   not(output.type?.args.hex_ends_with("32")) and
+                   (output.capacity.between("0x0", "100000000000") or output_data == "0x01")"#,
+            ),
+        );
+
+        let script = |args: &str| {
+            ScriptBuilder::default()
+                .code_hash(H256(rand::random()).pack())
+                .hash_type(ScriptHashType::Type.into())
+                .args(Bytes::from(args.as_bytes().to_vec()).pack())
+                .build()
+        };
+        let output = |capacity: Capacity, lock_args: &str, type_args: Option<&str>| {
+            CellOutputBuilder::default()
+                .capacity(capacity.pack())
+                .lock(script(lock_args))
+                .type_(type_args.map(script).pack())
+                .build()
+        };
+        let empty = Bytes::new().pack();
+        let is_match =
+            |filters: &CustomFilters, output: CellOutput, data: &ckb_types::packed::Bytes| {
+                filters.is_cell_filter_match(&output, data).unwrap()
+            };
+
+        // "lock_script1", without a type script
+        assert!(is_match(
+            &filters,
+            output(capacity_bytes!(1000), "lock_script1", None),
+            &empty
+        ));
+        assert!(is_match(
+            &filters,
+            output(capacity_bytes!(1000), "lock_script1", Some("type_script1")),
+            &empty
+        ));
+        // the type script args end with "2"
+        assert!(!is_match(
+            &filters,
+            output(capacity_bytes!(1000), "lock_script1", Some("type_script2")),
+            &empty
+        ));
+        // the lock script args do not start with "lock"
+        assert!(!is_match(
+            &filters,
+            output(capacity_bytes!(1000), "script1", None),
+            &empty
+        ));
+        // the capacity is out of range unless the data matches
+        assert!(!is_match(
+            &filters,
+            output(capacity_bytes!(2000), "lock_script1", None),
+            &empty
+        ));
+        assert!(is_match(
+            &filters,
+            output(capacity_bytes!(2000), "lock_script1", None),
+            &Bytes::from(vec![1]).pack()
+        ));
+
+        // `and` and `or` short-circuit, the right sides which fail are not evaluated, and the
+        // keywords in the strings are kept
+        let cell = output(capacity_bytes!(1000), "lock_script1", None);
+        let filters = CustomFilters::new(
+            None,
+            Some(r#"output_data != "0x" and to_uint("x") == to_uint("0")"#),
+        );
+        assert!(!is_match(&filters, cell.clone(), &empty));
+        let filters = CustomFilters::new(
+            None,
+            Some(
+                r#"output_data == "0x" or output_data == "a and b" and to_uint("x") == to_uint("0")"#,
+            ),
+        );
+        assert!(is_match(&filters, cell.clone(), &empty));
+
+        // the evaluation errors are returned
+        let filters = CustomFilters::new(None, Some(r#"to_uint("x") == to_uint("0")"#));
+        assert!(filters.is_cell_filter_match(&cell, &empty).is_err());
+    }
+
+    #[test]
+    fn append_and_rollback_with_witness_prefixes() {
+        let indexer = new_indexer::<RocksdbStore>("append_and_rollback_with_witness_prefixes")
+            .with_witness_prefixes(vec![b"proto".to_vec(), b"proto-a".to_vec()]);
+
+        let lock_script1 = ScriptBuilder::default()
+            .code_hash(H256(rand::random()).pack())
+            .hash_type(ScriptHashType::Data.into())
+            .args(Bytes::from(b"lock_script1".to_vec()).pack())
+            .build();
+        let witness = |data: &[u8]| Bytes::from(data.to_vec()).pack();
+        let cellbase = |number| {
+            TransactionBuilder::default()
+                .input(CellInput::new_cellbase_input(number))
+                .witness(Script::default().into_witness())
+                .output(
+                    CellOutputBuilder::default()
+                        .capacity(capacity_bytes!(1000).pack())
+                        .lock(lock_script1.clone())
+                        .build(),
+                )
+                .output_data(Default::default())
+                .build()
+        };
+
+        let block0 = BlockBuilder::default()
+            .transaction(cellbase(0))
+            .header(HeaderBuilder::default().number(0.pack()).build())
+            .build();
+        indexer.append(&block0).unwrap();
+
+        let tx10 = TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(H256(rand::random()).pack(), 0),
+                0,
+            ))
+            .witness(witness(b"unrelated"))
+            .witness(witness(b"proto-a:message"))
+            .build();
+        let tx11 = TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(H256(rand::random()).pack(), 0),
+                0,
+            ))
+            .witness(witness(b"proto-b:message"))
+            .build();
+        let block1 = BlockBuilder::default()
+            .transaction(cellbase(1))
+            .transaction(tx10.clone())
+            .transaction(tx11.clone())
+            .header(
+                HeaderBuilder::default()
+                    .number(1.pack())
+                    .parent_hash(block0.hash())
+                    .epoch(EpochNumberWithFraction::new(0, 1, 10).pack())
+                    .build(),
+            )
+            .build();
+        indexer.append(&block1).unwrap();
+
+        assert_eq!(
+            vec![tx10.hash(), tx11.hash()],
+            indexer
+                .get_transactions_by_witness_prefix(b"proto")
+                .unwrap()
+        );
+        assert_eq!(
+            vec![tx10.hash()],
+            indexer
+                .get_transactions_by_witness_prefix(b"proto-a")
+                .unwrap()
+        );
+        // only the configured prefixes are indexed
+        assert!(indexer
+            .get_transactions_by_witness_prefix(b"proto-b")
+            .unwrap()
+            .is_empty());
+
+        // the prefixes removed from the config are rolled back too
+        let indexer_without_prefixes = Indexer::new(
+            indexer.store().clone(),
+            KEEP_NUM,
+            1,
+            None,
+            CustomFilters::new(None, None),
+        );
+        indexer_without_prefixes.rollback().unwrap();
+        assert!(indexer
+            .get_transactions_by_witness_prefix(b"proto")
+            .unwrap()
+            .is_empty());
+        assert!(indexer
+            .get_transactions_by_witness_prefix(b"proto-a")
+            .unwrap()
+            .is_empty());
+    }
 }
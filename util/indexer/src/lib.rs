@@ -5,5 +5,7 @@ pub(crate) mod store;
 
 /// The indexer service.
 pub mod service;
+/// The export and import of the indexer store.
+pub mod snapshot;
 
 pub use service::{IndexerHandle, IndexerService};
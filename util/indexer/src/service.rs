@@ -1,15 +1,15 @@
 //！The indexer service.
 
-use crate::indexer::{self, extract_raw_data, Indexer, Key, KeyPrefix, Value};
+use crate::indexer::{self, extract_raw_data, witness_key_prefix, Indexer, Key, KeyPrefix, Value};
 use crate::store::{IteratorDirection, RocksdbStore, Store};
 
-use ckb_app_config::IndexerConfig;
+use ckb_app_config::{check_witness_prefixes, IndexerConfig};
 use ckb_async_runtime::Handle;
 use ckb_indexer_sync::{CustomFilters, Error, IndexerSyncService, Pool, PoolService, SecondaryDB};
 use ckb_jsonrpc_types::{
-    IndexerCell, IndexerCellType, IndexerCellsCapacity, IndexerOrder, IndexerPagination,
-    IndexerScriptType, IndexerSearchKey, IndexerSearchMode, IndexerTip, IndexerTx,
-    IndexerTxWithCell, IndexerTxWithCells, JsonBytes, Uint32,
+    pagination::check_limit, IndexerCell, IndexerCellType, IndexerCellsCapacity, IndexerOrder,
+    IndexerPagination, IndexerScriptType, IndexerSearchKey, IndexerSearchMode, IndexerTip,
+    IndexerTx, IndexerTxWithCell, IndexerTxWithCells, IndexerTxWithWitness, JsonBytes, Uint32,
 };
 use ckb_notify::NotifyController;
 use ckb_types::{core, packed, prelude::*, H256};
@@ -30,9 +30,10 @@ const DEFAULT_MAX_BACKGROUND_JOBS: usize = 6;
 pub struct IndexerService {
     store: RocksdbStore,
     sync: IndexerSyncService,
-    block_filter: Option<String>,
-    cell_filter: Option<String>,
+    // compiled once, the indexers share the compiled filters
+    custom_filters: CustomFilters,
     request_limit: usize,
+    witness_prefixes: Vec<Vec<u8>>,
 }
 
 impl IndexerService {
@@ -43,6 +44,8 @@ impl IndexerService {
         config: &IndexerConfig,
         async_handle: Handle,
     ) -> Self {
+        let witness_prefixes = parse_witness_prefixes(&config.witness_prefixes)
+            .expect("the witness prefixes are checked when the config is loaded");
         let store_opts = Self::indexer_store_options(config);
         let store = RocksdbStore::new(&store_opts, &config.store);
         let sync = IndexerSyncService::new(
@@ -56,9 +59,12 @@ impl IndexerService {
         Self {
             store,
             sync,
-            block_filter: config.block_filter.clone(),
-            cell_filter: config.cell_filter.clone(),
+            custom_filters: CustomFilters::new(
+                config.block_filter.as_deref(),
+                config.cell_filter.as_deref(),
+            ),
             request_limit: config.request_limit.unwrap_or(usize::MAX),
+            witness_prefixes,
         }
     }
 
@@ -71,6 +77,7 @@ impl IndexerService {
             store: self.store.clone(),
             pool: self.sync.pool(),
             request_limit: self.request_limit,
+            witness_prefixes: self.witness_prefixes.clone(),
         }
     }
 
@@ -100,8 +107,9 @@ impl IndexerService {
             keep_num,
             1000,
             self.sync.pool(),
-            CustomFilters::new(self.block_filter.as_deref(), self.cell_filter.as_deref()),
+            self.custom_filters.clone(),
         )
+        .with_witness_prefixes(self.witness_prefixes.clone())
     }
 
     /// Processes that handle block cell and expect to be spawned to run in tokio runtime
@@ -129,6 +137,7 @@ pub struct IndexerHandle {
     pub(crate) store: RocksdbStore,
     pub(crate) pool: Option<Arc<RwLock<Pool>>>,
     request_limit: usize,
+    witness_prefixes: Vec<Vec<u8>>,
 }
 
 impl IndexerHandle {
@@ -149,6 +158,21 @@ impl IndexerHandle {
         }))
     }
 
+    /// Get the hash of the indexed block by number, which is `None` if the block is pruned
+    pub fn get_block_hash(&self, block_number: core::BlockNumber) -> Result<Option<H256>, Error> {
+        let mut from_key = vec![KeyPrefix::Header as u8];
+        from_key.extend_from_slice(&block_number.to_be_bytes());
+        let mut iter = self.store.iter(&from_key, IteratorDirection::Forward)?;
+        Ok(iter
+            .next()
+            .filter(|(key, _)| key.starts_with(&from_key))
+            .map(|(key, _)| {
+                packed::Byte32::from_slice(&key[9..41])
+                    .expect("stored block key")
+                    .unpack()
+            }))
+    }
+
     /// Get cells by specified params
     pub fn get_cells(
         &self,
@@ -169,16 +193,7 @@ impl IndexerHandle {
             ));
         }
 
-        let limit = limit.value() as usize;
-        if limit == 0 {
-            return Err(Error::invalid_params("limit should be greater than 0"));
-        }
-        if limit > self.request_limit {
-            return Err(Error::invalid_params(format!(
-                "limit must be less than {}",
-                self.request_limit,
-            )));
-        }
+        let limit = check_limit(limit, self.request_limit).map_err(Error::invalid_params)?;
 
         let (prefix, from_key, direction, skip) = build_query_options(
             &search_key,
@@ -341,16 +356,7 @@ impl IndexerHandle {
         limit: Uint32,
         after_cursor: Option<JsonBytes>,
     ) -> Result<IndexerPagination<IndexerTx>, Error> {
-        let limit = limit.value() as usize;
-        if limit == 0 {
-            return Err(Error::invalid_params("limit should be greater than 0"));
-        }
-        if limit > self.request_limit {
-            return Err(Error::invalid_params(format!(
-                "limit must be less than {}",
-                self.request_limit,
-            )));
-        }
+        let limit = check_limit(limit, self.request_limit).map_err(Error::invalid_params)?;
 
         if search_key
             .script_search_mode
@@ -627,6 +633,83 @@ impl IndexerHandle {
         }
     }
 
+    /// Get transactions with the witnesses starting with the indexed witness prefix
+    pub fn get_transactions_by_witness_prefix(
+        &self,
+        witness_prefix: JsonBytes,
+        order: IndexerOrder,
+        limit: Uint32,
+        after_cursor: Option<JsonBytes>,
+    ) -> Result<IndexerPagination<IndexerTxWithWitness>, Error> {
+        let limit = check_limit(limit, self.request_limit).map_err(Error::invalid_params)?;
+        let witness_prefix = witness_prefix.as_bytes();
+        if !self
+            .witness_prefixes
+            .iter()
+            .any(|prefix| prefix.as_slice() == witness_prefix)
+        {
+            return Err(Error::invalid_params(
+                "the witness prefix is not indexed, please add it to indexer_v2.witness_prefixes",
+            ));
+        }
+
+        let prefix = witness_key_prefix(witness_prefix);
+        let (from_key, direction, skip) = match order {
+            IndexerOrder::Asc => after_cursor.map_or_else(
+                || (prefix.clone(), Direction::Forward, 0),
+                |json_bytes| (json_bytes.as_bytes().into(), Direction::Forward, 1),
+            ),
+            IndexerOrder::Desc => after_cursor.map_or_else(
+                // BlockNumber (8) + TxIndex (4) + WitnessIndex (4)
+                || {
+                    (
+                        [prefix.clone(), vec![0xff; 16]].concat(),
+                        Direction::Reverse,
+                        0,
+                    )
+                },
+                |json_bytes| (json_bytes.as_bytes().into(), Direction::Reverse, 1),
+            ),
+        };
+
+        let mode = IteratorMode::From(from_key.as_ref(), direction);
+        let snapshot = self.store.inner().snapshot();
+        let mut last_key = Vec::new();
+        let txs = snapshot
+            .iterator(mode)
+            .skip(skip)
+            .take_while(|(key, _value)| key.starts_with(&prefix))
+            .take(limit)
+            .map(|(key, value)| {
+                let tx_hash = packed::Byte32::from_slice(&value).expect("stored tx hash");
+                let block_number = u64::from_be_bytes(
+                    key[key.len() - 16..key.len() - 8]
+                        .try_into()
+                        .expect("stored block_number"),
+                );
+                let tx_index = u32::from_be_bytes(
+                    key[key.len() - 8..key.len() - 4]
+                        .try_into()
+                        .expect("stored tx_index"),
+                );
+                let witness_index = u32::from_be_bytes(
+                    key[key.len() - 4..]
+                        .try_into()
+                        .expect("stored witness_index"),
+                );
+                last_key = key.to_vec();
+                IndexerTxWithWitness {
+                    tx_hash: tx_hash.unpack(),
+                    block_number: block_number.into(),
+                    tx_index: tx_index.into(),
+                    witness_index: witness_index.into(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(IndexerPagination::new(txs, JsonBytes::from_vec(last_key)))
+    }
+
     /// Get cells_capacity by specified search_key
     pub fn get_cells_capacity(
         &self,
@@ -796,6 +879,21 @@ impl IndexerHandle {
 
 const MAX_PREFIX_SEARCH_SIZE: usize = u16::max_value() as usize;
 
+/// Checks the witness prefixes, so the index size is bounded, and returns them sorted and
+/// deduplicated.
+pub(crate) fn parse_witness_prefixes(
+    witness_prefixes: &[JsonBytes],
+) -> Result<Vec<Vec<u8>>, Error> {
+    check_witness_prefixes(witness_prefixes).map_err(Error::invalid_params)?;
+    let mut prefixes: Vec<Vec<u8>> = witness_prefixes
+        .iter()
+        .map(|prefix| prefix.as_bytes().to_vec())
+        .collect();
+    prefixes.sort();
+    prefixes.dedup();
+    Ok(prefixes)
+}
+
 // a helper fn to build query options from search parameters, returns prefix, from_key, direction and skip offset
 fn build_query_options(
     search_key: &IndexerSearchKey,
@@ -919,6 +1017,7 @@ impl TryInto<FilterOptions> for IndexerSearchKey {
 mod tests {
     use super::*;
     use crate::store::RocksdbStore;
+    use ckb_app_config::{MAX_WITNESS_PREFIXES, MAX_WITNESS_PREFIX_SIZE};
     use ckb_indexer_sync::IndexerSync;
     use ckb_jsonrpc_types::{IndexerRange, IndexerSearchKeyFilter};
     use ckb_types::{
@@ -950,6 +1049,7 @@ mod tests {
             store,
             pool: Some(Arc::clone(&pool)),
             request_limit: usize::MAX,
+            witness_prefixes: Vec::new(),
         };
 
         // setup test data
@@ -1538,6 +1638,7 @@ mod tests {
             store,
             pool: None,
             request_limit: usize::MAX,
+            witness_prefixes: Vec::new(),
         };
 
         // setup test data
@@ -1784,6 +1885,7 @@ mod tests {
             store,
             pool: None,
             request_limit: 2,
+            witness_prefixes: Vec::new(),
         };
 
         let lock_script1 = ScriptBuilder::default()
@@ -1817,6 +1919,7 @@ mod tests {
             store,
             pool: None,
             request_limit: usize::MAX,
+            witness_prefixes: Vec::new(),
         };
 
         // setup test data
@@ -2071,4 +2174,102 @@ mod tests {
         let capacity: u64 = cells_capacity.unwrap().capacity.into();
         assert_eq!(200000000000, capacity);
     }
+
+    #[test]
+    fn invalid_witness_prefixes() {
+        let prefix = |len: usize| JsonBytes::from_vec(vec![1; len]);
+        assert!(parse_witness_prefixes(&[prefix(0)]).is_err());
+        assert!(parse_witness_prefixes(&[prefix(MAX_WITNESS_PREFIX_SIZE + 1)]).is_err());
+        let too_many: Vec<_> = (0..=MAX_WITNESS_PREFIXES).map(|_| prefix(1)).collect();
+        assert!(parse_witness_prefixes(&too_many).is_err());
+        assert_eq!(
+            parse_witness_prefixes(&[prefix(2), prefix(1), prefix(2)]).unwrap(),
+            vec![vec![1], vec![1, 1]]
+        );
+    }
+
+    #[test]
+    fn witness_prefix_rpc() {
+        let store = new_store("witness_prefix_rpc");
+        let witness_prefixes =
+            parse_witness_prefixes(&[JsonBytes::from_vec(b"proto".to_vec())]).unwrap();
+        let indexer = Indexer::new(store.clone(), 10, 100, None, CustomFilters::new(None, None))
+            .with_witness_prefixes(witness_prefixes.clone());
+        let rpc = IndexerHandle {
+            store,
+            pool: None,
+            request_limit: usize::MAX,
+            witness_prefixes,
+        };
+
+        let witness = |data: &[u8]| Bytes::from(data.to_vec()).pack();
+        let cellbase = TransactionBuilder::default()
+            .input(CellInput::new_cellbase_input(0))
+            .witness(Script::default().into_witness())
+            .build();
+        let tx1 = TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(H256(rand::random()).pack(), 0),
+                0,
+            ))
+            .witness(witness(b"unrelated"))
+            .witness(witness(b"proto:1"))
+            .build();
+        let tx2 = TransactionBuilder::default()
+            .input(CellInput::new(
+                OutPoint::new(H256(rand::random()).pack(), 0),
+                0,
+            ))
+            .witness(witness(b"proto:2"))
+            .build();
+        let block0 = BlockBuilder::default()
+            .transaction(cellbase)
+            .transaction(tx1.clone())
+            .transaction(tx2.clone())
+            .header(HeaderBuilder::default().number(0.pack()).build())
+            .build();
+        indexer.append(&block0).unwrap();
+
+        let prefix = || JsonBytes::from_vec(b"proto".to_vec());
+        let txs = rpc
+            .get_transactions_by_witness_prefix(prefix(), IndexerOrder::Asc, 1.into(), None)
+            .unwrap();
+        assert_eq!(1, txs.objects.len());
+        assert_eq!(tx1.hash().unpack(), txs.objects[0].tx_hash);
+        assert_eq!(1, txs.objects[0].tx_index.value());
+        assert_eq!(1, txs.objects[0].witness_index.value());
+
+        let txs = rpc
+            .get_transactions_by_witness_prefix(
+                prefix(),
+                IndexerOrder::Asc,
+                10.into(),
+                Some(txs.last_cursor),
+            )
+            .unwrap();
+        assert_eq!(1, txs.objects.len());
+        assert_eq!(tx2.hash().unpack(), txs.objects[0].tx_hash);
+        assert_eq!(0, txs.objects[0].witness_index.value());
+
+        let txs = rpc
+            .get_transactions_by_witness_prefix(prefix(), IndexerOrder::Desc, 10.into(), None)
+            .unwrap();
+        assert_eq!(
+            vec![tx2.hash().unpack(), tx1.hash().unpack()],
+            txs.objects
+                .into_iter()
+                .map(|tx| tx.tx_hash)
+                .collect::<Vec<H256>>()
+        );
+
+        // only the configured prefixes can be queried
+        assert!(rpc
+            .get_transactions_by_witness_prefix(
+                JsonBytes::from_vec(b"proto:1".to_vec()),
+                IndexerOrder::Asc,
+                10.into(),
+                None,
+            )
+            .is_err());
+    }
 }
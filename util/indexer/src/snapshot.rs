@@ -0,0 +1,444 @@
+//! Export and import of the indexer store, which saves the reindex when deploying another node.
+//!
+//! A snapshot is a directory containing `manifest.json` and `data`. The data file contains every
+//! key-value pair of the store in the key order, each pair is encoded as the little-endian `u32`
+//! length of the key, the key, the little-endian `u32` length of the value and the value.
+//!
+//! The export is at the indexer tip by default, or at a given height by rolling back a copy of the
+//! store, which requires the block to be kept for the rollback.
+//!
+//! The import verifies every block header kept in the snapshot against the main chain of the
+//! node, so the node must have synced past the snapshot tip.
+use crate::indexer::{Indexer, KeyPrefix};
+use crate::service::parse_witness_prefixes;
+use crate::store::{Batch, IteratorDirection, RocksdbStore, Store};
+use ckb_app_config::IndexerConfig;
+use ckb_indexer_sync::{CustomFilters, Error, IndexerSync};
+use ckb_jsonrpc_types::JsonBytes;
+use ckb_types::{core::BlockNumber, packed::Byte32, prelude::*, H256};
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DATA_FILE: &str = "data";
+const BATCH_SIZE: usize = 10_000;
+
+/// The description of an indexer snapshot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// The tip block number of the indexer.
+    pub tip_number: BlockNumber,
+    /// The tip block hash of the indexer.
+    pub tip_hash: H256,
+    /// The number of the key-value pairs in the data file.
+    pub entries: u64,
+    /// The `indexer.block_filter` of the exporting node.
+    pub block_filter: Option<String>,
+    /// The `indexer.cell_filter` of the exporting node.
+    pub cell_filter: Option<String>,
+    /// The `indexer.witness_prefixes` of the exporting node.
+    #[serde(default)]
+    pub witness_prefixes: Vec<JsonBytes>,
+}
+
+/// Exports the indexer store at `config.store` to the `target` directory, which must not exist.
+///
+/// The snapshot is at the block `height`, or at the indexer tip if it's `None`. The store must not
+/// be opened by a running node, and it's never modified.
+pub fn export_snapshot(
+    config: &IndexerConfig,
+    target: &Path,
+    height: Option<BlockNumber>,
+) -> Result<SnapshotManifest, Error> {
+    if !config.store.exists() {
+        return Err(Error::invalid_params(format!(
+            "the indexer store {} does not exist",
+            config.store.display()
+        )));
+    }
+    if target.exists() {
+        return Err(Error::invalid_params(format!(
+            "the target {} already exists",
+            target.display()
+        )));
+    }
+    let store = RocksdbStore::open(&RocksdbStore::default_options(), &config.store)?;
+    let (tip_number, _tip_hash) =
+        stored_tip(&store)?.ok_or_else(|| Error::invalid_params("the indexer store is empty"))?;
+    let height = match height {
+        Some(height) if height != tip_number => height,
+        _ => return export_store(config, &store, target),
+    };
+    if height > tip_number {
+        return Err(Error::invalid_params(format!(
+            "the height {height} is above the indexer tip {tip_number}"
+        )));
+    }
+    if !has_header(&store, height)? {
+        return Err(Error::invalid_params(format!(
+            "the block {height} is not kept for the rollback by the indexer store"
+        )));
+    }
+
+    // rolls back a temporary copy of the store, which is removed after the export
+    let rollback_path = suffixed_path(target, ".rollback");
+    if rollback_path.exists() {
+        fs::remove_dir_all(&rollback_path).map_err(io_error)?;
+    }
+    let result = export_rolled_back(config, &store, &rollback_path, height, target);
+    let _ignored = fs::remove_dir_all(&rollback_path);
+    result
+}
+
+fn export_rolled_back(
+    config: &IndexerConfig,
+    store: &RocksdbStore,
+    rollback_path: &Path,
+    height: BlockNumber,
+    target: &Path,
+) -> Result<SnapshotManifest, Error> {
+    let copy = RocksdbStore::open(&RocksdbStore::default_options(), rollback_path)?;
+    let mut batch = copy.batch()?;
+    let mut batch_size = 0;
+    for (key, value) in store.iter([0u8; 0], IteratorDirection::Forward)? {
+        batch.put(key, value)?;
+        batch_size += 1;
+        if batch_size == BATCH_SIZE {
+            batch.commit()?;
+            batch = copy.batch()?;
+            batch_size = 0;
+        }
+    }
+    batch.commit()?;
+
+    // the rollback doesn't depend on the filters, nor on the pruning
+    let indexer = Indexer::new(copy.clone(), 0, 0, None, CustomFilters::new(None, None));
+    while let Some((number, _hash)) = stored_tip(&copy)? {
+        if number <= height {
+            break;
+        }
+        indexer.rollback()?;
+    }
+    if stored_tip(&copy)?.map(|(number, _hash)| number) != Some(height) {
+        return Err(Error::invalid_params(format!(
+            "failed to roll back the indexer to the block {height}"
+        )));
+    }
+    export_store(config, &copy, target)
+}
+
+fn export_store(
+    config: &IndexerConfig,
+    store: &RocksdbStore,
+    target: &Path,
+) -> Result<SnapshotManifest, Error> {
+    let (tip_number, tip_hash) =
+        stored_tip(store)?.ok_or_else(|| Error::invalid_params("the indexer store is empty"))?;
+
+    fs::create_dir_all(target).map_err(io_error)?;
+    let mut writer = BufWriter::new(File::create(target.join(DATA_FILE)).map_err(io_error)?);
+    let mut entries = 0u64;
+    for (key, value) in store.iter([0u8; 0], IteratorDirection::Forward)? {
+        write_bytes(&mut writer, &key)?;
+        write_bytes(&mut writer, &value)?;
+        entries += 1;
+    }
+    writer.flush().map_err(io_error)?;
+    writer.get_ref().sync_all().map_err(io_error)?;
+
+    let manifest = SnapshotManifest {
+        tip_number,
+        tip_hash: tip_hash.unpack(),
+        entries,
+        block_filter: config.block_filter.clone(),
+        cell_filter: config.cell_filter.clone(),
+        witness_prefixes: config.witness_prefixes.clone(),
+    };
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| Error::invalid_params(format!("failed to serialize {MANIFEST_FILE}: {e}")))?;
+    fs::write(target.join(MANIFEST_FILE), json).map_err(io_error)?;
+    Ok(manifest)
+}
+
+/// Imports the snapshot in the `source` directory to the indexer store at `config.store`, which
+/// must not exist.
+///
+/// `main_chain_hash` returns the hash of the main chain block by number, every block header in
+/// the snapshot must be in the main chain. Nothing is written to `config.store` if the
+/// verification fails.
+pub fn import_snapshot<F>(
+    config: &IndexerConfig,
+    source: &Path,
+    main_chain_hash: F,
+) -> Result<SnapshotManifest, Error>
+where
+    F: Fn(BlockNumber) -> Option<Byte32>,
+{
+    let manifest: SnapshotManifest = {
+        let json = fs::read(source.join(MANIFEST_FILE)).map_err(io_error)?;
+        serde_json::from_slice(&json)
+            .map_err(|e| Error::invalid_params(format!("invalid {MANIFEST_FILE}: {e}")))?
+    };
+    if manifest.block_filter != config.block_filter || manifest.cell_filter != config.cell_filter {
+        return Err(Error::invalid_params(
+            "the snapshot is exported with different indexer.block_filter or indexer.cell_filter",
+        ));
+    }
+    // the prefixes are compared regardless of the order and the duplicates
+    if parse_witness_prefixes(&manifest.witness_prefixes)?
+        != parse_witness_prefixes(&config.witness_prefixes)?
+    {
+        return Err(Error::invalid_params(
+            "the snapshot is exported with different indexer.witness_prefixes",
+        ));
+    }
+    if config.store.exists() {
+        return Err(Error::invalid_params(format!(
+            "the indexer store {} already exists",
+            config.store.display()
+        )));
+    }
+
+    // imports to a temporary directory, which is renamed after the verification
+    let importing = suffixed_path(&config.store, ".importing");
+    if importing.exists() {
+        fs::remove_dir_all(&importing).map_err(io_error)?;
+    }
+    let result = import_to(&manifest, source, &importing, main_chain_hash);
+    match result {
+        Ok(()) => {
+            fs::rename(&importing, &config.store).map_err(io_error)?;
+            Ok(manifest)
+        }
+        Err(err) => {
+            let _ignored = fs::remove_dir_all(&importing);
+            Err(err)
+        }
+    }
+}
+
+fn import_to<F>(
+    manifest: &SnapshotManifest,
+    source: &Path,
+    store_path: &Path,
+    main_chain_hash: F,
+) -> Result<(), Error>
+where
+    F: Fn(BlockNumber) -> Option<Byte32>,
+{
+    let store = RocksdbStore::open(&RocksdbStore::default_options(), store_path)?;
+    let mut reader = BufReader::new(File::open(source.join(DATA_FILE)).map_err(io_error)?);
+    let mut batch = store.batch()?;
+    let mut batch_size = 0;
+    let mut entries = 0u64;
+    let mut tip = None;
+    while let Some(key) = read_bytes(&mut reader)? {
+        let value = read_bytes(&mut reader)?
+            .ok_or_else(|| Error::invalid_params("the snapshot data is truncated"))?;
+        if let Some((number, hash)) = parse_header_key(&key) {
+            if main_chain_hash(number).as_ref() != Some(&hash) {
+                return Err(Error::invalid_params(format!(
+                    "the snapshot block {number} {hash} is not in the main chain"
+                )));
+            }
+            tip = Some((number, hash));
+        }
+        batch.put(&key, &value)?;
+        batch_size += 1;
+        entries += 1;
+        if batch_size == BATCH_SIZE {
+            batch.commit()?;
+            batch = store.batch()?;
+            batch_size = 0;
+        }
+    }
+    batch.commit()?;
+
+    if entries != manifest.entries {
+        return Err(Error::invalid_params(format!(
+            "the snapshot data contains {entries} entries, expected {}",
+            manifest.entries
+        )));
+    }
+    let expected_tip = (manifest.tip_number, manifest.tip_hash.pack());
+    if tip != Some(expected_tip) {
+        return Err(Error::invalid_params(
+            "the snapshot tip does not match the manifest",
+        ));
+    }
+    Ok(())
+}
+
+fn stored_tip(store: &RocksdbStore) -> Result<Option<(BlockNumber, Byte32)>, Error> {
+    let mut iter = store.iter([KeyPrefix::Header as u8 + 1], IteratorDirection::Reverse)?;
+    Ok(iter.next().and_then(|(key, _)| parse_header_key(&key)))
+}
+
+// The header key is the prefix, the block number in big-endian, the block hash and an optional
+// filtered flag
+fn parse_header_key(key: &[u8]) -> Option<(BlockNumber, Byte32)> {
+    if key.first() != Some(&(KeyPrefix::Header as u8)) || key.len() < 41 {
+        return None;
+    }
+    let number = BlockNumber::from_be_bytes(key[1..9].try_into().ok()?);
+    let hash = Byte32::from_slice(&key[9..41]).ok()?;
+    Some((number, hash))
+}
+
+// The headers of the blocks kept for the rollback, which are not pruned
+fn has_header(store: &RocksdbStore, number: BlockNumber) -> Result<bool, Error> {
+    let mut from_key = vec![KeyPrefix::Header as u8];
+    from_key.extend_from_slice(&number.to_be_bytes());
+    let mut iter = store.iter(from_key, IteratorDirection::Forward)?;
+    Ok(iter
+        .next()
+        .and_then(|(key, _)| parse_header_key(&key))
+        .map_or(false, |(stored, _)| stored == number))
+}
+
+fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), Error> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| Error::invalid_params("the stored key or value is too large"))?;
+    writer.write_all(&len.to_le_bytes()).map_err(io_error)?;
+    writer.write_all(bytes).map_err(io_error)
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, Error> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(io_error(e)),
+    }
+    let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut bytes).map_err(|e| {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            Error::invalid_params("the snapshot data is truncated")
+        } else {
+            io_error(e)
+        }
+    })?;
+    Ok(Some(bytes))
+}
+
+fn io_error(e: io::Error) -> Error {
+    Error::DB(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_key(number: BlockNumber, hash: &Byte32) -> Vec<u8> {
+        let mut key = vec![KeyPrefix::Header as u8];
+        key.extend_from_slice(&number.to_be_bytes());
+        key.extend_from_slice(hash.as_slice());
+        key
+    }
+
+    fn hash(n: u8) -> Byte32 {
+        Byte32::new([n; 32])
+    }
+
+    #[test]
+    fn export_and_import() {
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("indexer_snapshot")
+            .tempdir()
+            .unwrap();
+        let prefix = |byte: u8| JsonBytes::from_vec(vec![byte]);
+        let config = IndexerConfig {
+            store: tmp_dir.path().join("store"),
+            witness_prefixes: vec![prefix(2), prefix(1)],
+            ..Default::default()
+        };
+        {
+            let store = RocksdbStore::new(&RocksdbStore::default_options(), &config.store);
+            let mut batch = store.batch().unwrap();
+            batch.put([0, 1], [1, 1]).unwrap();
+            batch.put(header_key(1, &hash(1)), [0u8; 0]).unwrap();
+            batch.put(header_key(2, &hash(2)), [2]).unwrap();
+            batch.commit().unwrap();
+        }
+
+        let target = tmp_dir.path().join("snapshot");
+        let manifest = export_snapshot(&config, &target, None).unwrap();
+        assert_eq!((manifest.tip_number, manifest.entries), (2, 3));
+        assert_eq!(manifest.tip_hash, hash(2).unpack());
+
+        let mut other = IndexerConfig {
+            store: tmp_dir.path().join("other"),
+            witness_prefixes: vec![prefix(1)],
+            ..Default::default()
+        };
+        let result = import_snapshot(&other, &target, |number| Some(hash(number as u8)));
+        assert!(result.is_err());
+        // the same prefixes in another order
+        other.witness_prefixes = vec![prefix(1), prefix(2), prefix(1)];
+        // the block 2 is not in the main chain
+        let result = import_snapshot(&other, &target, |number| (number == 1).then(|| hash(1)));
+        assert!(result.is_err());
+        assert!(!other.store.exists());
+
+        let imported = import_snapshot(&other, &target, |number| Some(hash(number as u8))).unwrap();
+        assert_eq!(imported, manifest);
+        let store = RocksdbStore::new(&RocksdbStore::default_options(), &other.store);
+        assert_eq!(store.get([0, 1]).unwrap(), Some(vec![1, 1]));
+        assert_eq!(stored_tip(&store).unwrap(), Some((2, hash(2))));
+    }
+
+    #[test]
+    fn export_at_height() {
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("indexer_snapshot_height")
+            .tempdir()
+            .unwrap();
+        let config = IndexerConfig {
+            store: tmp_dir.path().join("store"),
+            ..Default::default()
+        };
+        {
+            let store = RocksdbStore::new(&RocksdbStore::default_options(), &config.store);
+            let mut batch = store.batch().unwrap();
+            batch.put([0, 1], [1, 1]).unwrap();
+            // the block 1 is pruned
+            for number in 2..=4 {
+                batch
+                    .put(header_key(number, &hash(number as u8)), [0u8; 0])
+                    .unwrap();
+            }
+            batch.commit().unwrap();
+        }
+        let target = tmp_dir.path().join("snapshot");
+
+        assert!(export_snapshot(&config, &target, Some(5)).is_err());
+        assert!(export_snapshot(&config, &target, Some(1)).is_err());
+        assert!(!target.exists());
+
+        let manifest = export_snapshot(&config, &target, Some(2)).unwrap();
+        assert_eq!((manifest.tip_number, manifest.entries), (2, 2));
+        assert_eq!(manifest.tip_hash, hash(2).unpack());
+        assert!(!suffixed_path(&target, ".rollback").exists());
+
+        // the store is not modified
+        let store = RocksdbStore::new(&RocksdbStore::default_options(), &config.store);
+        assert_eq!(stored_tip(&store).unwrap(), Some((4, hash(4))));
+        drop(store);
+
+        let other = IndexerConfig {
+            store: tmp_dir.path().join("other"),
+            ..Default::default()
+        };
+        let imported = import_snapshot(&other, &target, |number| Some(hash(number as u8))).unwrap();
+        assert_eq!(imported, manifest);
+    }
+}
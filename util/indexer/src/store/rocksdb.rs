@@ -9,6 +9,17 @@ pub(crate) struct RocksdbStore {
     db: Arc<DB>,
 }
 
+impl RocksdbStore {
+    /// Opens the store, returns the error instead of panicking
+    pub(crate) fn open<P>(opts: &Options, path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let db = Arc::new(DB::open(opts, path.as_ref())?);
+        Ok(Self { db })
+    }
+}
+
 impl Store for RocksdbStore {
     type Batch = RocksdbBatch;
     type Opts = Options;
@@ -17,8 +28,7 @@ impl Store for RocksdbStore {
     where
         P: AsRef<Path>,
     {
-        let db = Arc::new(DB::open(opts, path.as_ref()).expect("Failed to open rocksdb"));
-        Self { db }
+        Self::open(opts, path).expect("Failed to open rocksdb")
     }
 
     fn default_options() -> Self::Opts {
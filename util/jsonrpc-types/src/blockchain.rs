@@ -1,12 +1,12 @@
 use crate::bytes::JsonBytes;
 use crate::{
     BlockNumber, Byte32, Capacity, Cycle, DeploymentPos, EpochNumber, EpochNumberWithFraction,
-    ProposalShortId, ResponseFormat, ResponseFormatInnerType, Timestamp, Uint128, Uint32, Uint64,
-    Version,
+    PoolTransactionRejectType, ProposalShortId, ResponseFormat, ResponseFormatInnerType, Timestamp,
+    Uint128, Uint32, Uint64, Version,
 };
 use ckb_types::core::tx_pool;
 use ckb_types::utilities::MerkleProof as RawMerkleProof;
-use ckb_types::{core, packed, prelude::*, H256};
+use ckb_types::{core, packed, prelude::*, H256, U256};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -597,6 +597,11 @@ pub struct TxStatus {
     pub tx_index: Option<Uint32>,
     /// The reason why the transaction is rejected
     pub reason: Option<String>,
+    /// The stable code of the reason why the transaction is rejected.
+    ///
+    /// The clients should switch on the code rather than parsing `reason`, which is the
+    /// human-readable detail and may change across releases.
+    pub reason_code: Option<PoolTransactionRejectType>,
 }
 
 impl From<tx_pool::TxStatus> for TxStatus {
@@ -622,6 +627,7 @@ impl TxStatus {
             block_hash: None,
             tx_index: None,
             reason: None,
+            reason_code: None,
         }
     }
 
@@ -633,6 +639,7 @@ impl TxStatus {
             block_hash: None,
             tx_index: None,
             reason: None,
+            reason_code: None,
         }
     }
 
@@ -648,6 +655,7 @@ impl TxStatus {
             block_hash: Some(hash),
             tx_index: Some(tx_index),
             reason: None,
+            reason_code: None,
         }
     }
 
@@ -662,6 +670,7 @@ impl TxStatus {
             block_number: None,
             block_hash: None,
             tx_index: None,
+            reason_code: reject_code_of(&reason).map(Into::into),
             reason: Some(reason),
         }
     }
@@ -674,6 +683,7 @@ impl TxStatus {
             block_hash: None,
             tx_index: None,
             reason: None,
+            reason_code: None,
         }
     }
 
@@ -683,6 +693,17 @@ impl TxStatus {
     }
 }
 
+// The reason of a rejected tx is the recorded `PoolTransactionReject` in JSON, the code is looked
+// up by its `type`. The records may be written by the older versions, so they are not parsed as a
+// whole.
+fn reject_code_of(reason: &str) -> Option<tx_pool::RejectCode> {
+    let record: serde_json::Value = serde_json::from_str(reason).ok()?;
+    record
+        .get("type")
+        .and_then(|reject_type| reject_type.as_str())
+        .and_then(tx_pool::RejectCode::from_name)
+}
+
 /// The block header.
 ///
 /// Refer to RFC [CKB Block Structure](https://github.com/nervosnetwork/rfcs/blob/master/rfcs/0027-block-structure/0027-block-structure.md).
@@ -1168,6 +1189,191 @@ impl EpochView {
     }
 }
 
+/// Aggregated statistics of the blocks in an epoch of the canonical chain.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::EpochStats>(r#"
+/// {
+///   "average_block_interval": "0x1d4c",
+///   "block_count": "0x3e8",
+///   "number": "0x1",
+///   "total_cycles": "0x2540be400",
+///   "total_fees": "0x174876e800",
+///   "tx_count": "0x1f4"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct EpochStats {
+    /// The epoch number.
+    pub number: EpochNumber,
+    /// The number of blocks in the epoch committed so far.
+    pub block_count: Uint64,
+    /// The number of transactions committed in the epoch, cellbases excluded.
+    ///
+    /// It's null if some blocks of the epoch had been moved to the freezer when the statistics
+    /// were built for the existing chain.
+    pub tx_count: Option<Uint64>,
+    /// The total fees of the transactions committed in the epoch.
+    pub total_fees: Capacity,
+    /// The total cycles consumed by the transactions committed in the epoch.
+    ///
+    /// Blocks committed without script verification, such as those under the assume valid
+    /// target, don't contribute to the cycles.
+    pub total_cycles: Cycle,
+    /// The average interval between the blocks in the epoch, in milliseconds.
+    pub average_block_interval: Uint64,
+}
+
+/// The stale blocks and uncles in a window of the most recent canonical chain blocks.
+///
+/// The stale block rate is `stale_blocks / blocks`, and the uncle rate is `uncles / blocks`.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::UncleStats>(r#"
+/// {
+///   "blocks": "0x64",
+///   "stale_blocks": "0x2",
+///   "uncles": "0x3",
+///   "window": "0x64"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct UncleStats {
+    /// The window size in blocks, configured by `chain.stats_windows`.
+    pub window: Uint64,
+    /// The canonical chain blocks in the window, which is less than `window` near the genesis
+    /// block.
+    pub blocks: Uint64,
+    /// The uncles included by the canonical chain blocks in the window.
+    pub uncles: Uint64,
+    /// The verified blocks in the window which are not in the canonical chain, also known as
+    /// orphan blocks.
+    ///
+    /// Only the blocks observed by this node since it started are counted, so it may be less
+    /// than `uncles`, which includes the blocks observed by the miners.
+    pub stale_blocks: Uint64,
+}
+
+/// A canonical chain header which light clients can start syncing from.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::HeaderCheckpoint>(r#"
+/// {
+///   "epoch": "0x7080018000001",
+///   "hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
+///   "number": "0x3e8",
+///   "total_difficulty": "0x3e8f4240"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug, JsonSchema)]
+pub struct HeaderCheckpoint {
+    /// The block number.
+    pub number: BlockNumber,
+    /// The block hash.
+    pub hash: H256,
+    /// The total difficulty of the chain ending at the block.
+    #[schemars(schema_with = "crate::json_schema::u256_json_schema")]
+    pub total_difficulty: U256,
+    /// The epoch of the block.
+    pub epoch: EpochNumberWithFraction,
+}
+
+impl From<core::HeaderCheckpoint> for HeaderCheckpoint {
+    fn from(input: core::HeaderCheckpoint) -> Self {
+        HeaderCheckpoint {
+            number: input.number.into(),
+            hash: input.hash.unpack(),
+            total_difficulty: input.total_difficulty,
+            epoch: input.epoch.full_value().into(),
+        }
+    }
+}
+
+impl From<HeaderCheckpoint> for core::HeaderCheckpoint {
+    fn from(input: HeaderCheckpoint) -> Self {
+        core::HeaderCheckpoint {
+            number: input.number.into(),
+            hash: input.hash.pack(),
+            total_difficulty: input.total_difficulty,
+            epoch: core::EpochNumberWithFraction::from_full_value(input.epoch.into()),
+        }
+    }
+}
+
+/// The header checkpoints signed by the network secret key of a node.
+///
+/// The signed message is the blake2b hash of the genesis hash, the domain `ckb-header-checkpoints`
+/// and the checkpoints, each checkpoint is serialized as the block number in 8 bytes, the block hash
+/// in 32 bytes, the total difficulty in 32 bytes and the full value of the epoch in 8 bytes, the
+/// integers are little-endian.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::SignedHeaderCheckpoints>(r#"
+/// {
+///   "checkpoints": [
+///     {
+///       "epoch": "0x7080018000001",
+///       "hash": "0xa5f5c85987a15de25661e5a214f2c1449cd803f071acc7999820f25246471f40",
+///       "number": "0x3e8",
+///       "total_difficulty": "0x3e8f4240"
+///     }
+///   ],
+///   "genesis_hash": "0x7978ec7ce5b507cfb52e149e36b1a23f6062ed150503c85bbf825da3599095ed",
+///   "node_id": "QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+///   "public_key": "0x02f21e0cdbab9e5b6e7eb8a8f4e8b0cad4e6b5d12a5eb2da3ef6b2ac8a2f64dbb6",
+///   "signature": "0x4d2e1e8b1a1e38a2c07e83c1c7a7e8b1e5d53b1c2bfa7c0e43b5d1e0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d501"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug, JsonSchema)]
+pub struct SignedHeaderCheckpoints {
+    /// The hash of the genesis block of the chain.
+    pub genesis_hash: H256,
+    /// The peer ID of the node, derived from `public_key`.
+    pub node_id: String,
+    /// The compressed secp256k1 public key of the node.
+    pub public_key: JsonBytes,
+    /// The checkpoints in ascending order of the block numbers.
+    pub checkpoints: Vec<HeaderCheckpoint>,
+    /// The 65-byte recoverable secp256k1 signature of the checkpoints.
+    pub signature: JsonBytes,
+}
+
+impl From<core::SignedHeaderCheckpoints> for SignedHeaderCheckpoints {
+    fn from(input: core::SignedHeaderCheckpoints) -> Self {
+        SignedHeaderCheckpoints {
+            genesis_hash: input.genesis_hash.unpack(),
+            node_id: input.node_id,
+            public_key: JsonBytes::from_bytes(input.public_key),
+            checkpoints: input.checkpoints.into_iter().map(Into::into).collect(),
+            signature: JsonBytes::from_bytes(input.signature),
+        }
+    }
+}
+
+impl From<SignedHeaderCheckpoints> for core::SignedHeaderCheckpoints {
+    fn from(input: SignedHeaderCheckpoints) -> Self {
+        core::SignedHeaderCheckpoints {
+            genesis_hash: input.genesis_hash.pack(),
+            node_id: input.node_id,
+            public_key: input.public_key.into_bytes(),
+            checkpoints: input.checkpoints.into_iter().map(Into::into).collect(),
+            signature: input.signature.into_bytes(),
+        }
+    }
+}
+
 /// Block base rewards.
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
 pub struct BlockIssuance {
@@ -1273,6 +1479,49 @@ impl From<BlockEconomicState> for core::BlockEconomicState {
     }
 }
 
+/// The economic states of the consecutive blocks summed up.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::EconomicStateSummary>(r#"
+/// {
+///   "block_count": "0x2",
+///   "end_number": "0x2",
+///   "epoch": "0x0",
+///   "issuance": {
+///     "primary": "0x319d245794",
+///     "secondary": "0xfe05d8caa"
+///   },
+///   "miner_reward": {
+///     "committed": "0x0",
+///     "primary": "0x319d245794",
+///     "proposal": "0x0",
+///     "secondary": "0x2f726c0a"
+///   },
+///   "start_number": "0x1",
+///   "txs_fee": "0x0"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct EconomicStateSummary {
+    /// The epoch of the blocks, null if the blocks are not aggregated by epoch.
+    pub epoch: Option<EpochNumber>,
+    /// The number of the first block.
+    pub start_number: BlockNumber,
+    /// The number of the last block, inclusive.
+    pub end_number: BlockNumber,
+    /// The number of the blocks.
+    pub block_count: Uint64,
+    /// The total base rewards of the blocks.
+    pub issuance: BlockIssuance,
+    /// The total rewards for the miners of the blocks.
+    pub miner_reward: MinerReward,
+    /// The total fees of all transactions committed in the blocks.
+    pub txs_fee: Capacity,
+}
+
 /// Merkle proof for transactions in a block.
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
 pub struct TransactionProof {
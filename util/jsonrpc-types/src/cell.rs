@@ -1,6 +1,9 @@
 use crate::{CellOutput, JsonBytes};
 use ckb_types::{
-    core::cell::{CellMeta, CellStatus},
+    core::{
+        cell::{CellMeta, CellStatus},
+        tx_pool::CellPoolStatus as CoreCellPoolStatus,
+    },
     prelude::Unpack,
     H256,
 };
@@ -42,6 +45,19 @@ use serde::{Deserialize, Serialize};
 /// }
 /// # "#).unwrap();
 /// ```
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::CellWithStatus>(r#"
+/// {
+///   "cell": null,
+///   "pool_status": {
+///     "created_by": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3",
+///     "spent_by": null
+///   },
+///   "status": "unknown"
+/// }
+/// # "#).unwrap();
+/// ```
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CellWithStatus {
     /// The cell information.
@@ -61,6 +77,42 @@ pub struct CellWithStatus {
     /// * `unknown` - CKB does not know the status of the cell. Either the transaction creating
     /// this cell is not in the chain yet, or it is no longer live.
     pub status: String,
+    /// How the transactions in the pool create or spend the cell.
+    ///
+    /// It is only returned when `include_pool_status` is true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_status: Option<CellPoolStatus>,
+}
+
+/// How the transactions in the pool relate to a cell.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::CellPoolStatus>(r#"
+/// {
+///   "created_by": null,
+///   "spent_by": "0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CellPoolStatus {
+    /// The hash of the pool transaction which creates the cell, null if the cell is not created
+    /// by a pool transaction.
+    pub created_by: Option<H256>,
+    /// The hash of the pool transaction which spends the cell, null if no pool transaction
+    /// spends the cell.
+    pub spent_by: Option<H256>,
+}
+
+impl From<CoreCellPoolStatus> for CellPoolStatus {
+    fn from(status: CoreCellPoolStatus) -> Self {
+        CellPoolStatus {
+            created_by: status.created_by.map(|hash| hash.unpack()),
+            spent_by: status.spent_by.map(|hash| hash.unpack()),
+        }
+    }
 }
 
 /// The JSON view of a cell combining the fields in cell output and cell data.
@@ -143,6 +195,7 @@ impl From<CellStatus> for CellWithStatus {
         Self {
             cell: cell.map(Into::into),
             status: status.to_string(),
+            pool_status: None,
         }
     }
 }
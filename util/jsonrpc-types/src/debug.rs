@@ -1,3 +1,9 @@
+use crate::{BlockNumber, ScriptGroupDryRun, Timestamp, Uint64};
+use ckb_types::core::tx_pool::{
+    TxLifecycle as CoreTxLifecycle, TxLifecycleEvent as CoreTxLifecycleEvent,
+    TxLifecycleStage as CoreTxLifecycleStage,
+};
+use ckb_types::{prelude::Unpack, H256};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -56,3 +62,157 @@ pub struct MainLoggerConfig {
     /// `null` means keeping the current option unchanged.
     pub color: Option<bool>,
 }
+
+/// The information of a database column.
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub struct DBColumnInfo {
+    /// The column family.
+    pub col: String,
+    /// The name of the column.
+    pub name: String,
+    /// What the column stores.
+    pub description: String,
+    /// The format of the keys.
+    pub key: String,
+    /// The format of the values.
+    pub value: String,
+    /// The estimated number of entries in the column.
+    ///
+    /// It is null if RocksDB fails to report it.
+    pub estimated_entries: Option<Uint64>,
+    /// The estimated size of the live data in the column, in bytes.
+    ///
+    /// It is null if RocksDB fails to report it.
+    pub estimated_size: Option<Uint64>,
+}
+
+/// The kind of an inconsistency found in a main chain block.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainIntegrityIssueKind {
+    /// The header, transactions, uncles or proposals of the block are missing.
+    ///
+    /// The block is downloaded again from the peers.
+    MissingBody,
+    /// The block ext is missing.
+    ///
+    /// It is rebuilt from the parent block, the fees and cycles of the transactions are lost.
+    MissingBlockExt,
+    /// The index between the block number and the block hash is missing or points to another
+    /// block.
+    IndexMismatch,
+    /// Some transactions of the block are missing from the transaction index.
+    MissingTransactionInfo,
+}
+
+/// An inconsistency found in a main chain block.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ChainIntegrityIssue {
+    /// The block number.
+    pub block_number: BlockNumber,
+    /// The block hash.
+    pub block_hash: H256,
+    /// What is inconsistent.
+    pub kind: ChainIntegrityIssueKind,
+}
+
+/// The result of a main chain integrity check.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ChainIntegrityReport {
+    /// The lowest checked block number.
+    pub from: BlockNumber,
+    /// The highest checked block number, the tip block number.
+    pub to: BlockNumber,
+    /// The issues found, in ascending block number order.
+    pub issues: Vec<ChainIntegrityIssue>,
+    /// The blocks whose bodies are being downloaded again from the peers.
+    pub missing_block_bodies: Vec<H256>,
+}
+
+/// The result of the RPC method `check_script_determinism`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ScriptDeterminismReport {
+    /// The CKB-VM backend of the node, `asm` or `interpreter`.
+    pub backend: String,
+    /// The results of the script groups on the backend.
+    pub script_groups: Vec<ScriptGroupDryRun>,
+    /// The hashes of the scripts whose results or cycles differ between two runs on the backend,
+    /// or from the baseline.
+    pub diverged: Vec<H256>,
+}
+
+/// A stage in the lifecycle of a transaction.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TxLifecycleStage {
+    /// Submitted via RPC or relayed by a peer, waiting for the verification.
+    Submitted,
+    /// Passed the verification and entered the pending set.
+    Verified,
+    /// Proposed in a block which is not in the proposal window yet.
+    Gap,
+    /// Proposed and ready to be committed.
+    Proposed,
+    /// Included in a block template.
+    Packaged,
+    /// Committed in a block.
+    Committed,
+    /// Rejected or removed from the pool.
+    Rejected,
+}
+
+impl From<CoreTxLifecycleStage> for TxLifecycleStage {
+    fn from(stage: CoreTxLifecycleStage) -> Self {
+        match stage {
+            CoreTxLifecycleStage::Submitted => TxLifecycleStage::Submitted,
+            CoreTxLifecycleStage::Verified => TxLifecycleStage::Verified,
+            CoreTxLifecycleStage::Gap => TxLifecycleStage::Gap,
+            CoreTxLifecycleStage::Proposed => TxLifecycleStage::Proposed,
+            CoreTxLifecycleStage::Packaged => TxLifecycleStage::Packaged,
+            CoreTxLifecycleStage::Committed => TxLifecycleStage::Committed,
+            CoreTxLifecycleStage::Rejected => TxLifecycleStage::Rejected,
+        }
+    }
+}
+
+/// A timestamped event in the lifecycle of a transaction.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TxLifecycleEvent {
+    /// The stage entered.
+    pub stage: TxLifecycleStage,
+    /// When the stage is entered.
+    pub timestamp: Timestamp,
+    /// Extra information, such as the submitter or the reject reason.
+    pub detail: Option<String>,
+}
+
+impl From<CoreTxLifecycleEvent> for TxLifecycleEvent {
+    fn from(event: CoreTxLifecycleEvent) -> Self {
+        Self {
+            stage: event.stage.into(),
+            timestamp: event.timestamp.into(),
+            detail: event.detail,
+        }
+    }
+}
+
+/// The traced lifecycle of a transaction, from the submission to the commitment.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TxLifecycle {
+    /// The trace id assigned at the first event, which is also the span id in the OTLP export.
+    pub trace_id: Uint64,
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The events in the order they happened.
+    pub events: Vec<TxLifecycleEvent>,
+}
+
+impl From<CoreTxLifecycle> for TxLifecycle {
+    fn from(trace: CoreTxLifecycle) -> Self {
+        Self {
+            trace_id: trace.trace_id.into(),
+            tx_hash: trace.tx_hash.unpack(),
+            events: trace.events.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Cycle, OutPoint};
+use crate::{Cycle, OutPoint, Uint64};
 use ckb_types::H256;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -8,6 +8,62 @@ use serde::{Deserialize, Serialize};
 pub struct EstimateCycles {
     /// The count of cycles that the VM has consumed to verify this transaction.
     pub cycles: Cycle,
+    /// The cycles consumed by each script group, and by each VM spawned in the group.
+    pub script_groups: Vec<ScriptGroupCycles>,
+}
+
+/// The cycles consumed by a script group in the RPC method `estimate_cycles`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ScriptGroupCycles {
+    /// The type of the group.
+    pub group_type: ScriptGroupType,
+    /// The hash of the script of the group.
+    pub script_hash: H256,
+    /// The count of cycles that the VMs have consumed to run the script.
+    pub cycles: Cycle,
+    /// The cycles consumed by each VM, ordered by the VM ids.
+    ///
+    /// The root VM, whose id is 0, runs the script, and the other VMs are spawned by their
+    /// parents. It is empty for the type id script, which is not run in the VM.
+    pub vms: Vec<VmCycles>,
+}
+
+/// The cycles consumed by a VM of a script.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct VmCycles {
+    /// The VM id.
+    pub id: Uint64,
+    /// The id of the VM which spawns it, null for the root VM.
+    pub parent_id: Option<Uint64>,
+    /// The count of cycles that the VM itself has consumed, excluding the VMs spawned by it.
+    ///
+    /// The cycles to suspend and resume the VMs are charged to the VM which gets to run.
+    pub cycles: Cycle,
+}
+
+/// The type of a script group.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptGroupType {
+    /// The group of the input cells with the same lock script.
+    Lock,
+    /// The group of the input and output cells with the same type script.
+    Type,
+}
+
+/// The result of a script group in the RPC method `dry_run_script_groups`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ScriptGroupDryRun {
+    /// The type of the group.
+    pub group_type: ScriptGroupType,
+    /// The hash of the script of the group.
+    pub script_hash: H256,
+    /// The count of cycles that the VM has consumed to run the script.
+    ///
+    /// It is null if the script fails to load or the VM fails.
+    pub cycles: Option<Cycle>,
+    /// The error of the script, null if the script passes.
+    pub error: Option<String>,
 }
 
 /// An enum to represent the two kinds of dao withdrawal amount calculation option.
@@ -181,6 +181,20 @@ pub struct IndexerCellsCapacity {
     pub block_number: BlockNumber,
 }
 
+/// IndexerWaitFor represent indexer params `wait_for`.
+///
+/// The query waits until the indexer has processed the block before answering, so a client that
+/// has just seen a new block, for example via subscription, doesn't get stale results.
+#[derive(Deserialize, Default, JsonSchema)]
+pub struct IndexerWaitFor {
+    /// the block number the indexer must have processed
+    pub block_number: BlockNumber,
+    /// the block hash, optional. If it is set, the query keeps waiting while the indexed block at that height has another hash
+    pub block_hash: Option<H256>,
+    /// the max waiting time in milliseconds, optional default is `3000`, values above `10000` are capped
+    pub timeout: Option<Uint64>,
+}
+
 /// Indexer Transaction Object
 #[derive(Serialize, JsonSchema, Debug)]
 #[serde(untagged)]
@@ -252,6 +266,19 @@ pub struct IndexerTxWithCells {
     pub cells: Vec<(IndexerCellType, Uint32)>,
 }
 
+/// Tx with a witness starting with the indexed witness prefix
+#[derive(Serialize, JsonSchema, Debug)]
+pub struct IndexerTxWithWitness {
+    /// transaction hash
+    pub tx_hash: H256,
+    /// the number of the transaction committed in the block
+    pub block_number: BlockNumber,
+    /// the position index of the transaction committed in the block
+    pub tx_index: Uint32,
+    /// the position index of the witness in the transaction witnesses
+    pub witness_index: Uint32,
+}
+
 /// Cell type
 #[derive(Serialize, Clone, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
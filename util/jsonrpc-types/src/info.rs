@@ -107,3 +107,32 @@ pub struct ChainInfo {
     /// Active alerts stored in the local node.
     pub alerts: Vec<AlertMessage>,
 }
+
+/// A deprecated RPC method.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::DeprecatedMethod>(r#"
+/// {
+///   "enabled": true,
+///   "name": "get_fee_rate_statics",
+///   "replacement": "get_fee_rate_statistics",
+///   "since": "0.109.0",
+///   "sunset": null
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct DeprecatedMethod {
+    /// The method name.
+    pub name: String,
+    /// The version in which the method is deprecated.
+    pub since: String,
+    /// The method which should be used instead, if any.
+    pub replacement: Option<String>,
+    /// The HTTP-date after which the method may be removed, if it is scheduled.
+    pub sunset: Option<String>,
+    /// Whether the method can be called on this node, see `rpc.enable_deprecated_rpc`.
+    pub enabled: bool,
+}
@@ -12,8 +12,10 @@ mod indexer;
 mod info;
 mod json_schema;
 mod net;
+pub mod pagination;
 mod pool;
 mod primitive;
+pub mod projection;
 mod proposal_short_id;
 mod subscription;
 mod uints;
@@ -28,35 +30,56 @@ pub use self::block_template::{
 pub use self::blockchain::{
     Block, BlockEconomicState, BlockFilter, BlockIssuance, BlockResponse, BlockView,
     BlockWithCyclesResponse, CellDep, CellInput, CellOutput, Consensus, DepType, Deployment,
-    EpochView, FeeRateStatistics, HardForkFeature, HardForks, Header, HeaderView, MerkleProof,
-    MinerReward, OutPoint, ProposalWindow, Ratio, Script, ScriptHashType, SoftFork, Status,
-    Transaction, TransactionAndWitnessProof, TransactionProof, TransactionView,
-    TransactionWithStatusResponse, TxStatus, UncleBlock, UncleBlockView,
+    EconomicStateSummary, EpochStats, EpochView, FeeRateStatistics, HardForkFeature, HardForks,
+    Header, HeaderCheckpoint, HeaderView, MerkleProof, MinerReward, OutPoint, ProposalWindow,
+    Ratio, Script, ScriptHashType, SignedHeaderCheckpoints, SoftFork, Status, Transaction,
+    TransactionAndWitnessProof, TransactionProof, TransactionView, TransactionWithStatusResponse,
+    TxStatus, UncleBlock, UncleBlockView, UncleStats,
 };
 pub use self::bytes::JsonBytes;
-pub use self::cell::{CellData, CellInfo, CellWithStatus};
-pub use self::debug::{ExtraLoggerConfig, MainLoggerConfig};
-pub use self::experiment::{DaoWithdrawingCalculationKind, EstimateCycles};
+pub use self::cell::{CellData, CellInfo, CellPoolStatus, CellWithStatus};
+pub use self::debug::{
+    ChainIntegrityIssue, ChainIntegrityIssueKind, ChainIntegrityReport, DBColumnInfo,
+    ExtraLoggerConfig, MainLoggerConfig, ScriptDeterminismReport, TxLifecycle, TxLifecycleEvent,
+    TxLifecycleStage,
+};
+pub use self::experiment::{
+    DaoWithdrawingCalculationKind, EstimateCycles, ScriptGroupCycles, ScriptGroupDryRun,
+    ScriptGroupType, VmCycles,
+};
 pub use self::fee_rate::FeeRateDef;
 pub use self::fixed_bytes::Byte32;
-pub use self::info::{ChainInfo, DeploymentInfo, DeploymentPos, DeploymentState, DeploymentsInfo};
+pub use self::info::{
+    ChainInfo, DeploymentInfo, DeploymentPos, DeploymentState, DeploymentsInfo, DeprecatedMethod,
+};
 pub use self::net::{
-    BannedAddr, LocalNode, LocalNodeProtocol, NodeAddress, PeerSyncState, RemoteNode,
-    RemoteNodeProtocol, SyncState,
+    AssumeValidState, BannedAddr, BootnodeHealth, BootnodeStatus, DialPeerResult, DialStatus,
+    LocalNode, LocalNodeListener, LocalNodeProtocol, MisbehaviorComponent, NodeAddress,
+    NodeReachability, OutboundTargets, PeerMisbehavior, PeerSyncState, ReachabilityStatus,
+    RemoteNode, RemoteNodeProtocol, SyncState,
 };
 pub use self::pool::{
-    AncestorsScoreSortKey, EntryCompleted, OutputsValidator, PoolTransactionEntry,
-    PoolTransactionReject, PoolTxDetailInfo, RawTxPool, TxPoolEntries, TxPoolEntry, TxPoolIds,
-    TxPoolInfo,
+    AncestorsScoreSortKey, CellCapacityBreakdown, EntryCompleted, LockScriptStats,
+    OutputsValidator, PackageTransactionReject, PoolPolicy, PoolTransactionEntry,
+    PoolTransactionReject, PoolTransactionRejectType, PoolTxDetailInfo, PoolTxGraph,
+    PoolTxGraphEdge, PoolTxGraphNode, PoolTxGraphRelation, ProposalWindowSimulation, RawTxPool,
+    RbfRule, RbfRuleCheck, ReplacementSimulation, TemplateBacklog, TransactionOrHash, TxDiagnosis,
+    TxDiagnosisIssue, TxDiagnosisIssueKind, TxFeeBreakdown, TxPoolEntries, TxPoolEntry, TxPoolIds,
+    TxPoolInfo, TxsBacklog,
 };
+pub use self::projection::Projected;
 pub use self::proposal_short_id::ProposalShortId;
-pub use self::subscription::Topic;
+pub use self::subscription::{
+    MigrationFinishedEvent, ProposalWindowEvent, ProposalWindowStatus, SubscribeOptions,
+    SubscriptionBatch, SubscriptionFilter, Topic, TxStatusChangedEvent,
+};
 pub use self::uints::{Uint128, Uint32, Uint64};
 pub use ckb_types::core::RationalU256;
 pub use indexer::{
     IndexerCell, IndexerCellType, IndexerCellsCapacity, IndexerOrder, IndexerPagination,
     IndexerRange, IndexerScriptType, IndexerSearchKey, IndexerSearchKeyFilter, IndexerSearchMode,
-    IndexerTip, IndexerTx, IndexerTxWithCell, IndexerTxWithCells,
+    IndexerTip, IndexerTx, IndexerTxWithCell, IndexerTxWithCells, IndexerTxWithWitness,
+    IndexerWaitFor,
 };
 pub use primitive::{
     AsEpochNumberWithFraction, BlockNumber, Capacity, Cycle, EpochNumber, EpochNumberWithFraction,
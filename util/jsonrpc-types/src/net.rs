@@ -1,4 +1,4 @@
-use crate::{BlockNumber, Byte32, Timestamp, Uint128, Uint64};
+use crate::{BlockNumber, Byte32, Timestamp, Uint128, Uint32, Uint64};
 use ckb_types::H256;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -22,7 +22,21 @@ use serde::{Deserialize, Serialize};
 ///     }
 ///   ],
 ///   "connections": "0xb",
+///   "listeners": [
+///     {
+///       "address": "/ip4/0.0.0.0/tcp/8112",
+///       "advertise": true,
+///       "inbound_peers": "0x3",
+///       "listened_address": "/ip4/0.0.0.0/tcp/8112",
+///       "max_inbound_peers": null
+///     }
+///   ],
 ///   "node_id": "QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS",
+///   "outbound_targets": {
+///     "bandwidth_usage": null,
+///     "relay": "0x8",
+///     "sync": "0x8"
+///   },
 ///   "protocols": [
 ///     {
 ///       "id": "0x0",
@@ -39,6 +53,15 @@ use serde::{Deserialize, Serialize};
 ///       ]
 ///     }
 ///   ],
+///   "reachability": {
+///     "last_checked": "0x174876e8000",
+///     "last_public_inbound": null,
+///     "reachable_addresses": [
+///       "/ip4/192.168.0.2/tcp/8112/p2p/QmTRHCdrRtgUzYLNCin69zEvPvLYdxUZLLfLYyHVY3DZAS"
+///     ],
+///     "status": "reachable",
+///     "unreachable_addresses": []
+///   },
 ///   "version": "0.34.0 (f37f598 2020-07-17)"
 /// }
 /// # "#).unwrap();
@@ -65,6 +88,100 @@ pub struct LocalNode {
     pub protocols: Vec<LocalNodeProtocol>,
     /// Count of currently connected peers.
     pub connections: Uint64,
+    /// The current outbound peer targets by role.
+    pub outbound_targets: OutboundTargets,
+    /// The listeners, configured by `network.listen_addresses` and `network.listeners`.
+    ///
+    /// It is empty in the responses of the nodes which don't report the listeners.
+    #[serde(default)]
+    pub listeners: Vec<LocalNodeListener>,
+    /// Whether the node accepts inbound connections, by the connectivity self-test.
+    pub reachability: NodeReachability,
+}
+
+/// The report of the connectivity self-test.
+///
+/// The public addresses of the node, configured by `network.public_addresses` or observed by the
+/// peers, are dialed every `network.reachability_check_interval_secs`. An address is reachable if
+/// the dial comes back to the node itself. An inbound session from a public IP proves the node
+/// reachable as well, since the dials to its own addresses fail behind the NATs which don't
+/// support hairpinning. The results expire after three check intervals.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct NodeReachability {
+    /// The status concluded from the unexpired results.
+    pub status: ReachabilityStatus,
+    /// The public addresses dialed back to the node.
+    pub reachable_addresses: Vec<String>,
+    /// The public addresses failed to be dialed.
+    pub unreachable_addresses: Vec<String>,
+    /// The last time the public addresses were dialed.
+    pub last_checked: Option<Timestamp>,
+    /// The last time a peer connected from a public IP.
+    pub last_public_inbound: Option<Timestamp>,
+}
+
+/// Whether the node accepts inbound connections.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReachabilityStatus {
+    /// The node has no public address to test, or none of the tests is finished.
+    #[default]
+    Unknown,
+    /// A public address is dialed back to the node, or a peer connects from a public IP.
+    Reachable,
+    /// All the tested public addresses fail, and no peer connects from a public IP.
+    Unreachable,
+}
+
+/// A listener of the local node.
+///
+/// Tentacle does not tell which listener accepts a session, so the inbound peers are counted by
+/// the IP version and the transport of their addresses. The listeners sharing both share the
+/// count and the sum of their caps.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct LocalNodeListener {
+    /// The configured listen address.
+    pub address: String,
+    /// The address listened on, e.g., with the port assigned by the system.
+    ///
+    /// It is null if the listener is not started.
+    pub listened_address: Option<String>,
+    /// The maximum number of inbound peers, null if only limited by `network.max_peers`.
+    pub max_inbound_peers: Option<Uint32>,
+    /// Whether the address is advertised to the peers.
+    pub advertise: bool,
+    /// The count of the inbound peers, excluding the whitelist peers.
+    pub inbound_peers: Uint32,
+}
+
+/// The outbound peer targets by role.
+///
+/// The targets start from `network.outbound_targets.sync` and `network.outbound_targets.relay`
+/// in the config, and are lowered while the p2p traffic exceeds 80% of
+/// `network.outbound_targets.bandwidth_limit`. They are restored while the traffic is below 50%
+/// of the limit.
+///
+/// ## Examples
+///
+/// ```
+/// # serde_json::from_str::<ckb_jsonrpc_types::OutboundTargets>(r#"
+/// {
+///   "bandwidth_usage": "0x19000",
+///   "relay": "0x6",
+///   "sync": "0x8"
+/// }
+/// # "#).unwrap();
+/// ```
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct OutboundTargets {
+    /// The target number of outbound peers which open the sync protocol.
+    pub sync: Uint32,
+    /// The target number of outbound peers which open the relay protocol.
+    pub relay: Uint32,
+    /// The measured p2p traffic in bytes per second, in both directions.
+    ///
+    /// It is null if the bandwidth limit is not configured.
+    pub bandwidth_usage: Option<Uint64>,
 }
 
 /// The information of a P2P protocol that is supported by the local node.
@@ -100,39 +217,64 @@ pub struct LocalNodeProtocol {
 ///     }
 ///   ],
 ///   "connected_duration": "0x2f",
+///   "features": [
+///     "compression",
+///     "compact_relay"
+///   ],
+///   "flags": [
+///     "discovery",
+///     "sync",
+///     "relay"
+///   ],
 ///   "is_outbound": true,
 ///   "last_ping_duration": "0x1a",
 ///   "node_id": "QmXwUgF48ULy6hkgfqrEwEfuHW7WyWyWauueRDAYQHNDfN",
 ///   "protocols": [
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x4",
 ///       "version": "0.0.1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x2",
 ///       "version": "0.0.1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x1",
 ///       "version": "0.0.1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x64",
 ///       "version": "1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x6e",
 ///       "version": "1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x66",
 ///       "version": "1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x65",
 ///       "version": "1"
 ///     },
 ///     {
+///       "bytes_received": "0x1a0",
+///       "bytes_sent": "0x1f4",
 ///       "id": "0x0",
 ///       "version": "0.0.1"
 ///     }
@@ -177,6 +319,15 @@ pub struct RemoteNode {
     /// CKB uses Tentacle multiplexed network framework. Multiple protocols are running
     /// simultaneously in the connection.
     pub protocols: Vec<RemoteNodeProtocol>,
+    /// Feature flags announced by the remote node in the identify protocol, such as `"sync"`
+    /// and `"relay"`.
+    pub flags: Vec<String>,
+    /// P2P features negotiated with the remote node, such as `"compression"` and
+    /// `"compact_relay"`.
+    ///
+    /// A feature is negotiated when the protocols which provide it are active at the versions
+    /// which support it, and the remote node announces the required flags.
+    pub features: Vec<String>,
 }
 
 /// The information about an active running protocol.
@@ -186,6 +337,10 @@ pub struct RemoteNodeProtocol {
     pub id: Uint64,
     /// Active protocol version.
     pub version: String,
+    /// Total bytes of the messages sent to the remote node on this protocol.
+    pub bytes_sent: Uint64,
+    /// Total bytes of the messages received from the remote node on this protocol.
+    pub bytes_received: Uint64,
 }
 
 /// The chain synchronization state between the local node and a remote node.
@@ -248,6 +403,127 @@ pub struct BannedAddr {
     pub created_at: Timestamp,
 }
 
+/// The health of a bootnode.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct BootnodeHealth {
+    /// The bootnode address.
+    pub address: String,
+    /// The name of the bootnode set, the bootnodes in `network.bootnodes` form the set "default".
+    pub set: String,
+    /// The weight of the set.
+    pub weight: Uint32,
+    /// The health status.
+    pub status: BootnodeStatus,
+    /// The count of the dials failed since the last successful one.
+    pub consecutive_failures: Uint32,
+    /// The last time the bootnode was connected.
+    pub last_success: Option<Timestamp>,
+    /// The last time the dial to the bootnode failed.
+    pub last_failure: Option<Timestamp>,
+    /// The last time the bootnode was checked.
+    pub last_checked: Option<Timestamp>,
+}
+
+/// The health status of a bootnode.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BootnodeStatus {
+    /// The bootnode has not been connected yet.
+    Unknown,
+    /// The bootnode is connected recently.
+    Healthy,
+    /// The recent dials to the bootnode failed, it is not picked until it is reachable again.
+    Unhealthy,
+}
+
+/// The result of `dial_peer`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct DialPeerResult {
+    /// The dialed address.
+    pub address: String,
+    /// The outcome of the dial.
+    pub status: DialStatus,
+    /// The reason why the dial failed, only for `handshake_failed` and `failed`.
+    pub message: Option<String>,
+    /// How long the dial took, in milliseconds.
+    pub duration: Uint64,
+}
+
+/// The outcome of a dial.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DialStatus {
+    /// The session with the peer is opened, or the peer has been connected.
+    Connected,
+    /// The connection is established, but the handshake failed, e.g., the peer id mismatches.
+    HandshakeFailed,
+    /// The address is banned, it is not dialed.
+    Banned,
+    /// The dial has not finished within the wait.
+    Timeout,
+    /// The dial failed before the handshake, e.g., the connection is refused, or it is not
+    /// allowed, e.g., the address is the node itself, or the node rejects the session, e.g., the
+    /// peer limits are reached.
+    Failed,
+}
+
+/// The misbehavior score of a connected peer.
+///
+/// The peer is banned once `score` reaches `ban_threshold`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct PeerMisbehavior {
+    /// The remote node ID.
+    pub node_id: String,
+    /// The address of the connection.
+    pub address: String,
+    /// The total score, rounded to an integer.
+    pub score: Uint64,
+    /// The score to ban the peer, `network.misbehavior.ban_threshold` in ckb.toml.
+    pub ban_threshold: Uint32,
+    /// The scores by misbehavior kind, the highest first.
+    pub components: Vec<MisbehaviorComponent>,
+}
+
+/// The score of a peer from one kind of misbehavior.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct MisbehaviorComponent {
+    /// The misbehavior kind, e.g., `BlockIsInvalid` and `ProtocolMessageIsMalformed`.
+    pub kind: String,
+    /// The score decayed to now, rounded to an integer.
+    pub score: Uint64,
+    /// The count of the reports of this kind.
+    pub count: Uint64,
+    /// The reason of the last report.
+    pub last_reason: String,
+    /// The last time this kind was reported.
+    pub last_reported_at: Timestamp,
+}
+
+/// The progress of the synchronization with an assume_valid_target.
+///
+/// The scripts of the blocks up to the target are not verified, the full verification resumes
+/// when the target is reached, or when the target is ignored because it is too recent or it
+/// cannot be found.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct AssumeValidState {
+    /// The assume_valid_target specified by ckb.
+    pub target: H256,
+    /// The number of the target block, null if its header has not been synchronized yet.
+    pub target_number: Option<BlockNumber>,
+    /// Whether the scripts verification is still skipped.
+    pub active: bool,
+    /// The number of the verified tip block.
+    ///
+    /// While active, the remaining blocks to skip the scripts verification are
+    /// `target_number - verified_number`.
+    pub verified_number: BlockNumber,
+    /// The number of the first fully verified block, null if it is still active or the target
+    /// was reached before the node started.
+    pub full_verification_resumed_number: Option<BlockNumber>,
+    /// When the full verification resumed, in milliseconds since UNIX epoch.
+    pub full_verification_resumed_at: Option<Timestamp>,
+}
+
 /// The overall chain synchronization state of this local node.
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
 pub struct SyncState {
@@ -263,6 +539,8 @@ pub struct SyncState {
     pub assume_valid_target_reached: bool,
     /// The assume_valid_target specified by ckb, if no assume_valid_target, this will be all zero.
     pub assume_valid_target: Byte32,
+    /// The progress towards the assume_valid_target, null if no assume_valid_target is specified.
+    pub assume_valid: Option<AssumeValidState>,
     /// Is ckb reached the min_chain_work?
     pub min_chain_work_reached: bool,
     /// This field acts as a security measure to ensure that a node only
@@ -0,0 +1,80 @@
+//! Shared helpers for the cursor/limit pagination used by list-returning RPC methods.
+//!
+//! A page is requested with a `limit` and an optional `after` cursor, and returned as an
+//! [`IndexerPagination`] whose `last_cursor` should be passed as `after` to fetch the next page.
+//!
+//! Cursors are opaque bytes. For in-memory collections they are the sort key of the last
+//! returned object, so iterating is stable under concurrent insertions and removals: an object
+//! is never returned twice, and objects which exist during the whole iteration are never skipped.
+use crate::{IndexerOrder, IndexerPagination, JsonBytes, Uint32};
+
+/// Checks the requested page `limit` against the server side `max_limit`.
+///
+/// Returns the limit as `usize`, or an error message suitable for an invalid params error.
+pub fn check_limit(limit: Uint32, max_limit: usize) -> Result<usize, String> {
+    let limit = limit.value() as usize;
+    if limit == 0 {
+        return Err("limit should be greater than 0".to_string());
+    }
+    if limit > max_limit {
+        return Err(format!("limit must be less than {max_limit}"));
+    }
+    Ok(limit)
+}
+
+/// Encodes a numeric row id as a cursor, in little-endian format.
+pub fn encode_id_cursor(id: i64) -> JsonBytes {
+    JsonBytes::from_vec(id.to_le_bytes().to_vec())
+}
+
+/// Decodes a cursor created by [`encode_id_cursor`].
+pub fn decode_id_cursor(cursor: &JsonBytes) -> Result<i64, String> {
+    let bytes: [u8; 8] = cursor.as_bytes().try_into().map_err(|_| {
+        "unable to convert from bytes to i64 due to insufficient data in little-endian format"
+            .to_string()
+    })?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+/// Returns a page of `objects` ordered by the bytes returned by `key`.
+///
+/// Objects are sorted by `key` in the given `order`, those at or before the `after` cursor are
+/// skipped, and at most `limit` objects are returned. The returned `last_cursor` is the key of
+/// the last returned object, or the `after` cursor itself when the page is empty, so the caller
+/// can keep polling from the same position.
+pub fn paginate_by_key<T, F>(
+    objects: Vec<T>,
+    key: F,
+    order: IndexerOrder,
+    limit: usize,
+    after: Option<JsonBytes>,
+) -> IndexerPagination<T>
+where
+    F: Fn(&T) -> Vec<u8>,
+{
+    let asc = matches!(order, IndexerOrder::Asc);
+    let mut keyed: Vec<(Vec<u8>, T)> = objects.into_iter().map(|obj| (key(&obj), obj)).collect();
+    if asc {
+        keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    } else {
+        keyed.sort_by(|a, b| b.0.cmp(&a.0));
+    }
+
+    let after = after.map(|cursor| cursor.into_bytes().to_vec());
+    let mut last_cursor = after.clone().unwrap_or_default();
+    let objects = keyed
+        .into_iter()
+        .filter(|(k, _)| match &after {
+            None => true,
+            Some(after) if asc => k > after,
+            Some(after) => k < after,
+        })
+        .take(limit)
+        .map(|(k, obj)| {
+            last_cursor = k;
+            obj
+        })
+        .collect();
+
+    IndexerPagination::new(objects, JsonBytes::from_vec(last_cursor))
+}
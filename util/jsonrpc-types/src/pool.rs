@@ -1,9 +1,22 @@
-use crate::{BlockNumber, Capacity, Cycle, Timestamp, TransactionView, Uint64};
+use crate::{
+    BlockNumber, Capacity, Cycle, Timestamp, Transaction, TransactionView, Uint32, Uint64,
+};
 use ckb_types::core::service::PoolTransactionEntry as CorePoolTransactionEntry;
 use ckb_types::core::tx_pool::{
-    AncestorsScoreSortKey as CoreAncestorsScoreSortKey, PoolTxDetailInfo as CorePoolTxDetailInfo,
-    Reject, TxEntryInfo, TxPoolEntryInfo, TxPoolIds as CoreTxPoolIds, TxPoolInfo as CoreTxPoolInfo,
+    AncestorsScoreSortKey as CoreAncestorsScoreSortKey,
+    CellCapacityBreakdown as CoreCellCapacityBreakdown, LockScriptStats as CoreLockScriptStats,
+    PoolPolicy as CorePoolPolicy, PoolTxDetailInfo as CorePoolTxDetailInfo,
+    PoolTxGraph as CorePoolTxGraph, PoolTxGraphEdge as CorePoolTxGraphEdge,
+    PoolTxGraphNode as CorePoolTxGraphNode, PoolTxGraphRelation as CorePoolTxGraphRelation,
+    ProposalWindowSimulation as CoreProposalWindowSimulation, RbfRule as CoreRbfRule,
+    RbfRuleCheck as CoreRbfRuleCheck, Reject, RejectCode,
+    ReplacementSimulation as CoreReplacementSimulation, TemplateBacklog as CoreTemplateBacklog,
+    TxDiagnosis as CoreTxDiagnosis, TxDiagnosisIssue as CoreTxDiagnosisIssue,
+    TxDiagnosisIssueKind as CoreTxDiagnosisIssueKind, TxEntryInfo,
+    TxFeeBreakdown as CoreTxFeeBreakdown, TxPoolEntryInfo, TxPoolIds as CoreTxPoolIds,
+    TxPoolInfo as CoreTxPoolInfo, TxsBacklog as CoreTxsBacklog,
 };
+use ckb_types::packed::Byte32;
 use ckb_types::prelude::Unpack;
 use ckb_types::H256;
 use schemars::JsonSchema;
@@ -62,6 +75,23 @@ pub struct TxPoolInfo {
 
     /// verify_queue size
     pub verify_queue_size: Uint64,
+    /// Total size in bytes of the transactions in the verify queue.
+    pub verify_queue_tx_size: Uint64,
+    /// Total cycles declared by the relaying peers of the transactions in the verify queue.
+    ///
+    /// The transactions submitted via RPC have no declared cycles and are not counted.
+    pub verify_queue_cycles: Uint64,
+    /// The lock scripts whose transactions take the most bytes in the pool, the largest first.
+    ///
+    /// At most 10 lock scripts are listed. A few lock scripts taking a large share of the pool
+    /// often reveal a spam campaign.
+    pub top_lock_scripts: Vec<LockScriptStats>,
+    /// Total fees of the transactions replaced by RBF within the replacement window, see the
+    /// config option `tx_pool.rbf_replacement_window_secs`.
+    ///
+    /// The fees keep rising when the transactions are replaced over and over, which costs the
+    /// bandwidth of the network without getting more transactions committed.
+    pub rbf_replaced_fees: Capacity,
 }
 
 impl From<CoreTxPoolInfo> for TxPoolInfo {
@@ -80,6 +110,124 @@ impl From<CoreTxPoolInfo> for TxPoolInfo {
             tx_size_limit: tx_pool_info.tx_size_limit.into(),
             max_tx_pool_size: tx_pool_info.max_tx_pool_size.into(),
             verify_queue_size: (tx_pool_info.verify_queue_size as u64).into(),
+            verify_queue_tx_size: (tx_pool_info.verify_queue_tx_size as u64).into(),
+            verify_queue_cycles: tx_pool_info.verify_queue_cycles.into(),
+            top_lock_scripts: tx_pool_info
+                .top_lock_scripts
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            rbf_replaced_fees: tx_pool_info.rbf_replaced_fees.into(),
+        }
+    }
+}
+
+/// The statistics of the transactions in the pool unlocking the cells of a lock script.
+///
+/// A transaction unlocking the cells of several lock scripts is counted in each of them.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct LockScriptStats {
+    /// The lock script hash.
+    pub lock_hash: H256,
+    /// Count of the transactions.
+    pub tx_count: Uint64,
+    /// Total size in bytes of the transactions.
+    pub tx_size: Uint64,
+    /// Total fees of the transactions.
+    pub fees: Capacity,
+}
+
+impl From<CoreLockScriptStats> for LockScriptStats {
+    fn from(stats: CoreLockScriptStats) -> Self {
+        LockScriptStats {
+            lock_hash: stats.lock_hash.unpack(),
+            tx_count: (stats.tx_count as u64).into(),
+            tx_size: (stats.tx_size as u64).into(),
+            fees: stats.fees.into(),
+        }
+    }
+}
+
+/// The effective relay and admission policy of the transaction pool.
+///
+/// The policy is specific to the node, wallets can adapt the transactions to it. Some options
+/// are reloaded when the node receives `SIGHUP`, and the `version` increases if they change.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct PoolPolicy {
+    /// Increases by one every time the policy is reloaded with changes, starting from 0.
+    pub version: Uint64,
+    /// Fee rate threshold. The pool rejects transactions which fee rate is below this threshold.
+    ///
+    /// The unit is Shannons per 1000 bytes transaction serialization size in the block.
+    pub min_fee_rate: Uint64,
+    /// RBF rate threshold. If min_rbf_rate > min_fee_rate then RBF is enabled on the node.
+    ///
+    /// The unit is Shannons per 1000 bytes transaction serialization size in the block.
+    pub min_rbf_rate: Uint64,
+    /// The max count of the in-pool ancestors of a transaction.
+    pub max_ancestors_count: Uint64,
+    /// Total limit on the size of transactions in the tx-pool.
+    pub max_tx_pool_size: Uint64,
+    /// The max serialized size of a transaction.
+    pub tx_size_limit: Uint64,
+    /// The max cycles of a transaction.
+    pub max_tx_verify_cycles: Cycle,
+    /// The max cycles of a script group in a transaction, null if unlimited.
+    pub max_script_group_cycles: Option<Cycle>,
+    /// The sliding window in seconds to count the RBF replacements of an outpoint.
+    pub rbf_replacement_window_secs: Uint64,
+    /// The replacements count of an outpoint within the window before the required extra RBF
+    /// fee escalates.
+    pub rbf_free_replacements: Uint64,
+    /// The max replacements count of an outpoint within the window.
+    pub max_rbf_replacements: Uint64,
+    /// The max count of the transactions replaced by a RBF transaction, including the descendants
+    /// of the conflicting ones.
+    pub max_rbf_candidates: Uint64,
+    /// The max count of the descendants of a transaction conflicting with a RBF transaction.
+    pub max_rbf_conflict_descendants: Uint64,
+    /// Whether a RBF transaction can replace the transactions in the gap, which are proposed but
+    /// not yet committable.
+    pub rbf_replace_gap: bool,
+    /// Whether a RBF transaction can replace the proposed transactions.
+    pub rbf_replace_proposed: bool,
+    /// The soft cap of the total fees in shannons of the transactions replaced by RBF within the
+    /// window, null if no cap. Beyond the cap, the extra fee required by RBF grows in proportion
+    /// to the replaced fees.
+    pub rbf_replaced_fees_cap: Option<Uint64>,
+    /// The transactions submitted via RPC paying more fee than this in shannons are rejected,
+    /// null if unlimited.
+    pub max_absolute_fee: Option<Uint64>,
+    /// The transactions submitted via RPC paying higher fee rate than this in shannons/KB are
+    /// rejected, null if unlimited.
+    pub max_fee_rate: Option<Uint64>,
+    /// The transactions with a header dep less than this many blocks below the tip are rejected,
+    /// null if no requirement.
+    pub min_header_dep_depth: Option<Uint64>,
+}
+
+impl From<CorePoolPolicy> for PoolPolicy {
+    fn from(policy: CorePoolPolicy) -> Self {
+        PoolPolicy {
+            version: policy.version.into(),
+            min_fee_rate: policy.min_fee_rate.as_u64().into(),
+            min_rbf_rate: policy.min_rbf_rate.as_u64().into(),
+            max_ancestors_count: (policy.max_ancestors_count as u64).into(),
+            max_tx_pool_size: policy.max_tx_pool_size.into(),
+            tx_size_limit: policy.tx_size_limit.into(),
+            max_tx_verify_cycles: policy.max_tx_verify_cycles.into(),
+            max_script_group_cycles: policy.max_script_group_cycles.map(Into::into),
+            rbf_replacement_window_secs: policy.rbf_replacement_window_secs.into(),
+            rbf_free_replacements: (policy.rbf_free_replacements as u64).into(),
+            max_rbf_replacements: (policy.max_rbf_replacements as u64).into(),
+            max_rbf_candidates: (policy.max_rbf_candidates as u64).into(),
+            max_rbf_conflict_descendants: (policy.max_rbf_conflict_descendants as u64).into(),
+            rbf_replace_gap: policy.rbf_replace_gap,
+            rbf_replace_proposed: policy.rbf_replace_proposed,
+            rbf_replaced_fees_cap: policy.rbf_replaced_fees_cap.map(Into::into),
+            max_absolute_fee: policy.max_absolute_fee.map(Into::into),
+            max_fee_rate: policy.max_fee_rate.map(Into::into),
+            min_header_dep_depth: policy.min_header_dep_depth.map(Into::into),
         }
     }
 }
@@ -140,10 +288,15 @@ pub struct TxPoolIds {
 
 impl From<CoreTxPoolIds> for TxPoolIds {
     fn from(ids: CoreTxPoolIds) -> Self {
-        let CoreTxPoolIds { pending, proposed } = ids;
+        (&ids).into()
+    }
+}
+
+impl From<&CoreTxPoolIds> for TxPoolIds {
+    fn from(ids: &CoreTxPoolIds) -> Self {
         TxPoolIds {
-            pending: pending.iter().map(Unpack::unpack).collect(),
-            proposed: proposed.iter().map(Unpack::unpack).collect(),
+            pending: ids.pending.iter().map(Unpack::unpack).collect(),
+            proposed: ids.proposed.iter().map(Unpack::unpack).collect(),
         }
     }
 }
@@ -194,22 +347,24 @@ pub struct TxPoolEntries {
 
 impl From<TxPoolEntryInfo> for TxPoolEntries {
     fn from(info: TxPoolEntryInfo) -> Self {
-        let TxPoolEntryInfo {
-            pending,
-            proposed,
-            conflicted,
-        } = info;
+        (&info).into()
+    }
+}
 
+impl From<&TxPoolEntryInfo> for TxPoolEntries {
+    fn from(info: &TxPoolEntryInfo) -> Self {
         TxPoolEntries {
-            pending: pending
-                .into_iter()
-                .map(|(hash, entry)| (hash.unpack(), entry.into()))
+            pending: info
+                .pending
+                .iter()
+                .map(|(hash, entry)| (hash.unpack(), entry.clone().into()))
                 .collect(),
-            proposed: proposed
-                .into_iter()
-                .map(|(hash, entry)| (hash.unpack(), entry.into()))
+            proposed: info
+                .proposed
+                .iter()
+                .map(|(hash, entry)| (hash.unpack(), entry.clone().into()))
                 .collect(),
-            conflicted: conflicted.iter().map(Unpack::unpack).collect(),
+            conflicted: info.conflicted.iter().map(Unpack::unpack).collect(),
         }
     }
 }
@@ -289,68 +444,668 @@ impl From<CorePoolTxDetailInfo> for PoolTxDetailInfo {
     }
 }
 
+/// The relation of a node in a [`PoolTxGraph`](#type-pooltxgraph) to the queried transaction.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolTxGraphRelation {
+    /// The queried transaction itself.
+    Root,
+    /// An in-pool ancestor of the queried transaction.
+    Ancestor,
+    /// An in-pool descendant of the queried transaction.
+    Descendant,
+}
+
+impl From<CorePoolTxGraphRelation> for PoolTxGraphRelation {
+    fn from(relation: CorePoolTxGraphRelation) -> Self {
+        match relation {
+            CorePoolTxGraphRelation::Root => PoolTxGraphRelation::Root,
+            CorePoolTxGraphRelation::Ancestor => PoolTxGraphRelation::Ancestor,
+            CorePoolTxGraphRelation::Descendant => PoolTxGraphRelation::Descendant,
+        }
+    }
+}
+
+/// A transaction in a [`PoolTxGraph`](#type-pooltxgraph).
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct PoolTxGraphNode {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The relation to the queried transaction.
+    pub relation: PoolTxGraphRelation,
+    /// The distance in links to the queried transaction, 0 for the queried transaction itself.
+    pub depth: Uint32,
+    /// The transaction fee.
+    pub fee: Capacity,
+    /// The transaction size in block.
+    pub size: Uint64,
+    /// The transaction cycles.
+    pub cycles: Cycle,
+    /// The detailed status in tx-pool, `pending`, `gap`, `proposed`.
+    pub status: String,
+    /// The time added into tx-pool.
+    pub timestamp: Uint64,
+}
+
+impl From<CorePoolTxGraphNode> for PoolTxGraphNode {
+    fn from(node: CorePoolTxGraphNode) -> Self {
+        Self {
+            tx_hash: node.tx_hash.unpack(),
+            relation: node.relation.into(),
+            depth: (node.depth as u32).into(),
+            fee: node.fee.into(),
+            size: (node.size as u64).into(),
+            cycles: node.cycles.into(),
+            status: node.status,
+            timestamp: node.timestamp.into(),
+        }
+    }
+}
+
+/// A link in a [`PoolTxGraph`](#type-pooltxgraph), the child spends an output of the parent or
+/// depends on it as a cell dep.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct PoolTxGraphEdge {
+    /// The parent transaction hash.
+    pub parent: H256,
+    /// The child transaction hash.
+    pub child: H256,
+}
+
+impl From<CorePoolTxGraphEdge> for PoolTxGraphEdge {
+    fn from(edge: CorePoolTxGraphEdge) -> Self {
+        Self {
+            parent: edge.parent.unpack(),
+            child: edge.child.unpack(),
+        }
+    }
+}
+
+/// The in-pool ancestors and descendants of a transaction, with the links between them.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct PoolTxGraph {
+    /// The queried transaction hash.
+    pub tx_hash: H256,
+    /// The queried transaction, its ancestors and its descendants within the depth limit.
+    pub nodes: Vec<PoolTxGraphNode>,
+    /// The links between the nodes, from parent to child.
+    pub edges: Vec<PoolTxGraphEdge>,
+    /// Whether some ancestors or descendants are beyond the depth limit and not included.
+    pub truncated: bool,
+}
+
+impl From<CorePoolTxGraph> for PoolTxGraph {
+    fn from(graph: CorePoolTxGraph) -> Self {
+        Self {
+            tx_hash: graph.tx_hash.unpack(),
+            nodes: graph.nodes.into_iter().map(Into::into).collect(),
+            edges: graph.edges.into_iter().map(Into::into).collect(),
+            truncated: graph.truncated,
+        }
+    }
+}
+
+/// Why a transaction is not progressing.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TxDiagnosisIssueKind {
+    /// The fee rate is below `min_fee_rate`, which may be raised after the transaction is
+    /// accepted.
+    BelowFeeFloor,
+    /// The transaction is pending but ranked beyond the proposals limit of a block, it is not
+    /// proposed until the transactions with higher fee rates are.
+    LowRank,
+    /// The in-pool ancestors reach `max_ancestors_count`, they must be committed first.
+    TooManyAncestors,
+    /// Other transactions spend the same inputs, see `related_txs`.
+    Conflicts,
+    /// The transaction is waiting in the verify queue.
+    Verifying,
+    /// The transaction will be removed from the pool within an hour for expiry.
+    ExpiresSoon,
+    /// The transaction was rejected, the message is the reason.
+    Rejected,
+}
+
+impl From<CoreTxDiagnosisIssueKind> for TxDiagnosisIssueKind {
+    fn from(kind: CoreTxDiagnosisIssueKind) -> Self {
+        match kind {
+            CoreTxDiagnosisIssueKind::BelowFeeFloor => TxDiagnosisIssueKind::BelowFeeFloor,
+            CoreTxDiagnosisIssueKind::LowRank => TxDiagnosisIssueKind::LowRank,
+            CoreTxDiagnosisIssueKind::TooManyAncestors => TxDiagnosisIssueKind::TooManyAncestors,
+            CoreTxDiagnosisIssueKind::Conflicts => TxDiagnosisIssueKind::Conflicts,
+            CoreTxDiagnosisIssueKind::Verifying => TxDiagnosisIssueKind::Verifying,
+            CoreTxDiagnosisIssueKind::ExpiresSoon => TxDiagnosisIssueKind::ExpiresSoon,
+            CoreTxDiagnosisIssueKind::Rejected => TxDiagnosisIssueKind::Rejected,
+        }
+    }
+}
+
+/// An issue found by [`TxDiagnosis`](#type-txdiagnosis).
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TxDiagnosisIssue {
+    /// What the issue is.
+    pub kind: TxDiagnosisIssueKind,
+    /// The explanation with the numbers compared.
+    pub message: String,
+    /// The related transactions, e.g., the conflicting ones.
+    pub related_txs: Vec<H256>,
+}
+
+impl From<CoreTxDiagnosisIssue> for TxDiagnosisIssue {
+    fn from(issue: CoreTxDiagnosisIssue) -> Self {
+        Self {
+            kind: issue.kind.into(),
+            message: issue.message,
+            related_txs: issue.related_txs.into_iter().map(|h| h.unpack()).collect(),
+        }
+    }
+}
+
+/// The explanation of why a transaction is not progressing.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TxDiagnosis {
+    /// The queried transaction hash.
+    pub tx_hash: H256,
+    /// The status, `pending`, `gap`, `proposed`, `verifying`, `rejected` or `unknown`.
+    pub status: String,
+    /// The fee rate in shannons per kilo-weight, only for the transactions in the pool.
+    pub fee_rate: Option<Uint64>,
+    /// The rank by score among the pending transactions, starting from 0.
+    pub rank_in_pending: Option<Uint64>,
+    /// The count of the in-pool ancestors.
+    pub ancestors_count: Option<Uint64>,
+    /// When the transaction expires in the pool.
+    pub expires_at: Option<Timestamp>,
+    /// The issues found, empty if the transaction is expected to progress.
+    pub issues: Vec<TxDiagnosisIssue>,
+}
+
+impl From<CoreTxDiagnosis> for TxDiagnosis {
+    fn from(diagnosis: CoreTxDiagnosis) -> Self {
+        Self {
+            tx_hash: diagnosis.tx_hash.unpack(),
+            status: diagnosis.status,
+            fee_rate: diagnosis.fee_rate.map(|rate| rate.as_u64().into()),
+            rank_in_pending: diagnosis.rank_in_pending.map(|rank| (rank as u64).into()),
+            ancestors_count: diagnosis.ancestors_count.map(|count| (count as u64).into()),
+            expires_at: diagnosis.expires_at.map(Into::into),
+            issues: diagnosis.issues.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A transaction or the hash of a transaction in the pool or the chain.
+/// `TransactionOrHash` is equivalent to [`Transaction`] `|` [`H256`].
+///
+/// [`Transaction`]: struct.Transaction.html
+/// [`H256`]: struct.H256.html
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, JsonSchema)]
+#[serde(untagged)]
+pub enum TransactionOrHash {
+    /// The transaction, which may not be submitted yet
+    Transaction(Transaction),
+    /// The hash of a transaction in the pool or the chain
+    Hash(H256),
+}
+
+/// The capacities of an input or output cell.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct CellCapacityBreakdown {
+    /// The capacity of the cell.
+    pub capacity: Capacity,
+    /// The capacity occupied by the cell itself, the rest is free to spend.
+    pub occupied_capacity: Capacity,
+}
+
+impl From<CoreCellCapacityBreakdown> for CellCapacityBreakdown {
+    fn from(cell: CoreCellCapacityBreakdown) -> Self {
+        Self {
+            capacity: cell.capacity.into(),
+            occupied_capacity: cell.occupied_capacity.into(),
+        }
+    }
+}
+
+/// The capacities, the fee and the fee thresholds of a transaction.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TxFeeBreakdown {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The capacities of the input cells.
+    pub inputs: Vec<CellCapacityBreakdown>,
+    /// The capacities of the output cells.
+    pub outputs: Vec<CellCapacityBreakdown>,
+    /// The total capacity provided by the inputs, including the compensation of the withdrawn
+    /// DAO cells.
+    pub inputs_capacity: Capacity,
+    /// The total capacity of the outputs.
+    pub outputs_capacity: Capacity,
+    /// The transaction fee.
+    pub fee: Capacity,
+    /// The transaction serialized size in block.
+    pub size: Uint64,
+    /// The fee rate by the size in shannons per 1000 bytes, which is what the pool checks against
+    /// `min_fee_rate`.
+    pub fee_rate: Uint64,
+    /// The `min_fee_rate` of this node in shannons per 1000 bytes.
+    pub min_fee_rate: Uint64,
+    /// The minimal fee to be accepted by this node.
+    pub min_fee: Capacity,
+    /// The `min_rbf_rate` of this node in shannons per 1000 bytes.
+    pub min_rbf_rate: Uint64,
+    /// The minimal fee to replace the conflicting transactions in the pool, null if there are no
+    /// conflicts or RBF is disabled.
+    pub min_replace_fee: Option<Capacity>,
+}
+
+impl From<CoreTxFeeBreakdown> for TxFeeBreakdown {
+    fn from(breakdown: CoreTxFeeBreakdown) -> Self {
+        Self {
+            tx_hash: breakdown.tx_hash.unpack(),
+            inputs: breakdown.inputs.into_iter().map(Into::into).collect(),
+            outputs: breakdown.outputs.into_iter().map(Into::into).collect(),
+            inputs_capacity: breakdown.inputs_capacity.into(),
+            outputs_capacity: breakdown.outputs_capacity.into(),
+            fee: breakdown.fee.into(),
+            size: (breakdown.size as u64).into(),
+            fee_rate: breakdown.fee_rate.as_u64().into(),
+            min_fee_rate: breakdown.min_fee_rate.as_u64().into(),
+            min_fee: breakdown.min_fee.into(),
+            min_rbf_rate: breakdown.min_rbf_rate.as_u64().into(),
+            min_replace_fee: breakdown.min_replace_fee.map(Into::into),
+        }
+    }
+}
+
+/// An RBF rule checked by [`ReplacementSimulation`](#type-replacementsimulation).
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RbfRule {
+    /// Rule #2, the replacement spends no unconfirmed inputs besides the ones of the replaced
+    /// transactions.
+    NoNewUnconfirmedInputs,
+    /// Rule #5, the replaced transactions are within `max_rbf_candidates` and
+    /// `max_rbf_conflict_descendants`, they are replaceable in their status, and the replacement
+    /// depends on none of them.
+    ReplacedTxs,
+    /// Rule #6, the inputs have not been replaced too many times recently.
+    ReplacementChurn,
+    /// Rule #3 and #4, the fee is at least `min_replace_fee`.
+    MinReplaceFee,
+}
+
+impl From<CoreRbfRule> for RbfRule {
+    fn from(rule: CoreRbfRule) -> Self {
+        match rule {
+            CoreRbfRule::NoNewUnconfirmedInputs => RbfRule::NoNewUnconfirmedInputs,
+            CoreRbfRule::ReplacedTxs => RbfRule::ReplacedTxs,
+            CoreRbfRule::ReplacementChurn => RbfRule::ReplacementChurn,
+            CoreRbfRule::MinReplaceFee => RbfRule::MinReplaceFee,
+        }
+    }
+}
+
+/// The result of an RBF rule checked by [`ReplacementSimulation`](#type-replacementsimulation).
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct RbfRuleCheck {
+    /// The checked rule.
+    pub rule: RbfRule,
+    /// Whether the rule passes.
+    pub passed: bool,
+    /// Why the rule fails, null if it passes.
+    pub reason: Option<String>,
+}
+
+impl From<CoreRbfRuleCheck> for RbfRuleCheck {
+    fn from(check: CoreRbfRuleCheck) -> Self {
+        Self {
+            rule: check.rule.into(),
+            passed: check.passed,
+            reason: check.reason,
+        }
+    }
+}
+
+/// What would happen if a transaction were submitted to replace the conflicting ones in the
+/// pool.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ReplacementSimulation {
+    /// The replacement transaction hash.
+    pub tx_hash: H256,
+    /// The fee of the replacement.
+    pub fee: Capacity,
+    /// The serialized size in block of the replacement.
+    pub size: Uint64,
+    /// The transactions in the pool spending the same inputs as the replacement.
+    pub conflicts: Vec<H256>,
+    /// The transactions to be removed from the pool, the conflicts and their descendants.
+    pub evicted: Vec<H256>,
+    /// The combined fee of the evicted transactions.
+    pub evicted_fee: Capacity,
+    /// The minimal fee to replace the evicted transactions, null if there are no conflicts.
+    pub min_replace_fee: Option<Capacity>,
+    /// The results of the RBF rules in the order the pool checks them, empty if there are no
+    /// conflicts.
+    pub rules: Vec<RbfRuleCheck>,
+    /// Whether all the RBF rules pass, so the replacement would be accepted if it passes the
+    /// script verification as well.
+    pub replaceable: bool,
+}
+
+impl From<CoreReplacementSimulation> for ReplacementSimulation {
+    fn from(simulation: CoreReplacementSimulation) -> Self {
+        Self {
+            tx_hash: simulation.tx_hash.unpack(),
+            fee: simulation.fee.into(),
+            size: (simulation.size as u64).into(),
+            conflicts: simulation.conflicts.iter().map(Unpack::unpack).collect(),
+            evicted: simulation.evicted.iter().map(Unpack::unpack).collect(),
+            evicted_fee: simulation.evicted_fee.into(),
+            min_replace_fee: simulation.min_replace_fee.map(Into::into),
+            replaceable: simulation.is_replaceable(),
+            rules: simulation.rules.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// When a transaction could be proposed and committed if it were submitted now.
+///
+/// The pending transactions are proposed by their fee rates, at most `proposals_limit` in a
+/// block, and a transaction proposed in block `p` can be committed in the blocks from
+/// `p + closest` to `p + farthest` of the proposal window. The simulation assumes the pool and
+/// the block producers are unchanged, the new transactions paying more push it back.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct ProposalWindowSimulation {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The fee rate in shannons per kilo-weight of the transaction with its in-pool ancestors, by
+    /// which it is ranked. The cycles are unknown before the verification, so the weight is
+    /// estimated by the size.
+    pub fee_rate: Uint64,
+    /// The tip block number when simulating.
+    pub tip_number: BlockNumber,
+    /// The count of the pending transactions ranked ahead of the transaction or its pending
+    /// ancestors.
+    pub rank: Uint64,
+    /// The max count of the transactions proposed in a block.
+    pub proposals_limit: Uint64,
+    /// The earliest block to propose the transaction.
+    pub earliest_proposal_block: BlockNumber,
+    /// The earliest block to commit the transaction.
+    pub earliest_commit_block: BlockNumber,
+    /// The latest block to commit the transaction, after which it must be proposed again.
+    pub latest_commit_block: BlockNumber,
+}
+
+impl From<CoreProposalWindowSimulation> for ProposalWindowSimulation {
+    fn from(simulation: CoreProposalWindowSimulation) -> Self {
+        Self {
+            tx_hash: simulation.tx_hash.unpack(),
+            fee_rate: simulation.fee_rate.as_u64().into(),
+            tip_number: simulation.tip_number.into(),
+            rank: (simulation.rank as u64).into(),
+            proposals_limit: simulation.proposals_limit.into(),
+            earliest_proposal_block: simulation.earliest_proposal_block.into(),
+            earliest_commit_block: simulation.earliest_commit_block.into(),
+            latest_commit_block: simulation.latest_commit_block.into(),
+        }
+    }
+}
+
+/// The totals of the transactions left out of a block template.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TxsBacklog {
+    /// Count of the transactions.
+    pub tx_count: Uint64,
+    /// Total size in bytes of the transactions.
+    pub tx_size: Uint64,
+    /// Total consumed VM cycles of the transactions.
+    pub cycles: Cycle,
+    /// Total weight of the transactions, the larger one of the size and the cycles converted to
+    /// bytes, which is what the block space is allocated by.
+    pub weight: Uint64,
+    /// Total fees of the transactions.
+    pub fees: Capacity,
+}
+
+impl From<CoreTxsBacklog> for TxsBacklog {
+    fn from(backlog: CoreTxsBacklog) -> Self {
+        Self {
+            tx_count: (backlog.tx_count as u64).into(),
+            tx_size: (backlog.tx_size as u64).into(),
+            cycles: backlog.cycles.into(),
+            weight: backlog.weight.into(),
+            fees: backlog.fees.into(),
+        }
+    }
+}
+
+/// The transactions in the pool which did not fit in a generated block template.
+///
+/// The backlog beyond the block limits tells the congestion better than the pool size, e.g., a
+/// large pool which fits in the next block is not congested at all.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, JsonSchema)]
+pub struct TemplateBacklog {
+    /// The number of the block template.
+    pub number: BlockNumber,
+    /// The work id of the block template.
+    pub work_id: Uint64,
+    /// The time when the template is generated. This is the Unix timestamp in milliseconds.
+    pub generated_at: Timestamp,
+    /// The proposed transactions, which are committable but left out by the block size or cycles
+    /// limits.
+    pub proposed: TxsBacklog,
+    /// The pending and gap transactions, which are not committable in the block yet.
+    pub pending: TxsBacklog,
+}
+
+impl From<CoreTemplateBacklog> for TemplateBacklog {
+    fn from(backlog: CoreTemplateBacklog) -> Self {
+        Self {
+            number: backlog.number.into(),
+            work_id: backlog.work_id.into(),
+            generated_at: backlog.generated_at.into(),
+            proposed: backlog.proposed.into(),
+            pending: backlog.pending.into(),
+        }
+    }
+}
+
 /// TX reject message, `PoolTransactionReject` is a JSON object with following fields.
 ///    * `type`:  the Reject type with following enum values
 ///    * `description`: `string` - Detailed description about why the transaction is rejected.
+///    * `code`: `number` - A stable machine-readable code, which is the same as the error code
+///      returned by `send_transaction` for this reject type.
+///    * `replacing_tx_hash`: [`H256`](#type-h256) `|` `null` - The hash of the transaction which
+///      replaced this one via RBF, only present for RBF replacements.
+///    * `min_fee`: [`Capacity`](#type-capacity) `|` `null` - The minimum fee required by the
+///      pool, only present for fee related rejections.
+///    * `min_fee_rate`: [`Uint64`](#type-uint64) `|` `null` - The fee rate in shannons per
+///      kilo-weight the transaction must reach, `min_fee_rate` for `LowFeeRate`, or the fee rate floor of
+///      the full pool which the transaction must exceed for `Full`.
+///    * `min_replace_fee`: [`Capacity`](#type-capacity) `|` `null` - The minimum fee to replace
+///      the conflicting transactions, only present for RBF rejections by fee.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
-#[serde(tag = "type", content = "description")]
-pub enum PoolTransactionReject {
+pub struct PoolTransactionReject {
+    /// The reject type.
+    #[serde(rename = "type")]
+    pub reject_type: PoolTransactionRejectType,
+    /// Detailed description about why the transaction is rejected.
+    pub description: String,
+    /// A stable machine-readable code, the same as the `send_transaction` error code.
+    pub code: i64,
+    /// The hash of the transaction which replaced this one via RBF.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacing_tx_hash: Option<H256>,
+    /// The minimum fee required by the pool for fee related rejections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_fee: Option<Capacity>,
+    /// The fee rate threshold in shannons per kilo-weight for fee rate related rejections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_fee_rate: Option<Uint64>,
+    /// The minimum fee to replace the conflicting transactions for RBF rejections by fee.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_replace_fee: Option<Capacity>,
+}
+
+/// The type of [`PoolTransactionReject`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum PoolTransactionRejectType {
     /// Transaction fee lower than config
-    LowFeeRate(String),
+    LowFeeRate,
 
     /// Transaction exceeded maximum ancestors count limit
-    ExceededMaximumAncestorsCount(String),
+    ExceededMaximumAncestorsCount,
 
     /// Transaction exceeded maximum size limit
-    ExceededTransactionSizeLimit(String),
+    ExceededTransactionSizeLimit,
 
     /// Transaction are replaced because the pool is full
-    Full(String),
+    Full,
 
     /// Transaction already exists in transaction_pool
-    Duplicated(String),
+    Duplicated,
 
     /// Malformed transaction
-    Malformed(String),
+    Malformed,
 
     /// Declared wrong cycles
-    DeclaredWrongCycles(String),
+    DeclaredWrongCycles,
 
     /// Resolve failed
-    Resolve(String),
+    Resolve,
 
     /// Verification failed
-    Verification(String),
+    Verification,
 
     /// Transaction expired
-    Expiry(String),
+    Expiry,
 
     /// RBF rejected
-    RBFRejected(String),
+    RBFRejected,
 
     /// Invalidated rejected
-    Invalidated(String),
+    Invalidated,
+
+    /// Transaction fee exceeds the absurd fee threshold
+    AbsurdFee,
+
+    /// A script group exceeds the cycles limit of a script group
+    ExceededMaximumScriptGroupCycles,
+
+    /// A header dep is too close to the tip
+    HeaderDepTooRecent,
+}
+
+impl PoolTransactionRejectType {
+    /// Returns the stable code of this reject type.
+    ///
+    /// The codes are the same as the error codes returned by the `send_transaction` RPC.
+    pub fn code(self) -> i64 {
+        RejectCode::from(self).rpc_error_code()
+    }
+}
+
+impl From<RejectCode> for PoolTransactionRejectType {
+    fn from(code: RejectCode) -> Self {
+        match code {
+            RejectCode::LowFeeRate => Self::LowFeeRate,
+            RejectCode::ExceededMaximumAncestorsCount => Self::ExceededMaximumAncestorsCount,
+            RejectCode::ExceededTransactionSizeLimit => Self::ExceededTransactionSizeLimit,
+            RejectCode::Full => Self::Full,
+            RejectCode::Duplicated => Self::Duplicated,
+            RejectCode::Malformed => Self::Malformed,
+            RejectCode::DeclaredWrongCycles => Self::DeclaredWrongCycles,
+            RejectCode::Resolve => Self::Resolve,
+            RejectCode::Verification => Self::Verification,
+            RejectCode::Expiry => Self::Expiry,
+            RejectCode::RBFRejected => Self::RBFRejected,
+            RejectCode::Invalidated => Self::Invalidated,
+            RejectCode::AbsurdFee => Self::AbsurdFee,
+            RejectCode::ExceededMaximumScriptGroupCycles => Self::ExceededMaximumScriptGroupCycles,
+            RejectCode::HeaderDepTooRecent => Self::HeaderDepTooRecent,
+        }
+    }
+}
+
+impl From<PoolTransactionRejectType> for RejectCode {
+    fn from(reject_type: PoolTransactionRejectType) -> Self {
+        match reject_type {
+            PoolTransactionRejectType::LowFeeRate => Self::LowFeeRate,
+            PoolTransactionRejectType::ExceededMaximumAncestorsCount => {
+                Self::ExceededMaximumAncestorsCount
+            }
+            PoolTransactionRejectType::ExceededTransactionSizeLimit => {
+                Self::ExceededTransactionSizeLimit
+            }
+            PoolTransactionRejectType::Full => Self::Full,
+            PoolTransactionRejectType::Duplicated => Self::Duplicated,
+            PoolTransactionRejectType::Malformed => Self::Malformed,
+            PoolTransactionRejectType::DeclaredWrongCycles => Self::DeclaredWrongCycles,
+            PoolTransactionRejectType::Resolve => Self::Resolve,
+            PoolTransactionRejectType::Verification => Self::Verification,
+            PoolTransactionRejectType::Expiry => Self::Expiry,
+            PoolTransactionRejectType::RBFRejected => Self::RBFRejected,
+            PoolTransactionRejectType::Invalidated => Self::Invalidated,
+            PoolTransactionRejectType::AbsurdFee => Self::AbsurdFee,
+            PoolTransactionRejectType::ExceededMaximumScriptGroupCycles => {
+                Self::ExceededMaximumScriptGroupCycles
+            }
+            PoolTransactionRejectType::HeaderDepTooRecent => Self::HeaderDepTooRecent,
+        }
+    }
 }
 
 impl From<Reject> for PoolTransactionReject {
     fn from(reject: Reject) -> Self {
+        let mut replacing_tx_hash = None;
+        let mut min_fee = None;
+        let mut min_fee_rate = None;
+        let mut min_replace_fee = None;
         match reject {
-            Reject::LowFeeRate(..) => Self::LowFeeRate(format!("{reject}")),
-            Reject::ExceededMaximumAncestorsCount => {
-                Self::ExceededMaximumAncestorsCount(format!("{reject}"))
+            Reject::LowFeeRate(fee_rate, required, _) => {
+                min_fee = Some(required.into());
+                min_fee_rate = Some(fee_rate.as_u64().into());
+            }
+            Reject::FullFeeRateFloor(_, fee_rate_floor) => {
+                min_fee_rate = Some(fee_rate_floor.as_u64().into());
             }
-            Reject::ExceededTransactionSizeLimit(..) => {
-                Self::ExceededTransactionSizeLimit(format!("{reject}"))
+            Reject::RBFRejectedByFee(_, required) => {
+                min_replace_fee = Some(required.into());
             }
-            Reject::Full(..) => Self::Full(format!("{reject}")),
-            Reject::Duplicated(_) => Self::Duplicated(format!("{reject}")),
-            Reject::Malformed(_, _) => Self::Malformed(format!("{reject}")),
-            Reject::DeclaredWrongCycles(..) => Self::DeclaredWrongCycles(format!("{reject}")),
-            Reject::Resolve(_) => Self::Resolve(format!("{reject}")),
-            Reject::Verification(_) => Self::Verification(format!("{reject}")),
-            Reject::Expiry(_) => Self::Expiry(format!("{reject}")),
-            Reject::RBFRejected(_) => Self::RBFRejected(format!("{reject}")),
-            Reject::Invalidated(_) => Self::Invalidated(format!("{reject}")),
+            Reject::RBFReplaced(ref hash) => {
+                replacing_tx_hash = Some(hash.unpack());
+            }
+            _ => {}
+        }
+        let reject_type = PoolTransactionRejectType::from(reject.code());
+        Self {
+            reject_type,
+            description: format!("{reject}"),
+            code: reject_type.code(),
+            replacing_tx_hash,
+            min_fee,
+            min_fee_rate,
+            min_replace_fee,
+        }
+    }
+}
+
+/// A transaction rejected in a package submitted by `send_transaction_package`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct PackageTransactionReject {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// Why the transaction is rejected.
+    pub reject: PoolTransactionReject,
+}
+
+impl From<(Byte32, Reject)> for PackageTransactionReject {
+    fn from((tx_hash, reject): (Byte32, Reject)) -> Self {
+        Self {
+            tx_hash: tx_hash.unpack(),
+            reject: reject.into(),
         }
     }
 }
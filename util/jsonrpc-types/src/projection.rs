@@ -0,0 +1,442 @@
+//! Shared helpers for the `fields` parameter which trims the large responses.
+//!
+//! A caller lists the fields it needs, and the response object keeps only those fields. A field
+//! is either a top-level key of the response, or a dotted path into the nested objects, such as
+//! `header.number`. The arrays on the path are projected element-wise, so `transactions.hash`
+//! keeps only the hashes of the transactions in a block.
+//!
+//! The fields which are absent in the response are silently skipped, because many optional
+//! fields are omitted when they are null.
+use schemars::JsonSchema;
+use serde::ser::{
+    Error as _, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleStruct,
+};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The maximum number of fields in one request.
+pub const MAX_FIELDS: usize = 32;
+
+/// This is a wrapper for JSON serialization to return either the full response or the response
+/// with only the requested fields.
+///
+/// The fields are picked while the response is serialized, the other fields are skipped without
+/// being serialized.
+///
+/// ## Examples
+///
+/// `Projected<BlockResponse>` returns the block in its full format, or a JSON object with only
+/// the fields in the `fields` parameter.
+#[derive(Clone, Debug)]
+pub struct Projected<V> {
+    value: V,
+    fields: Option<FieldTree>,
+}
+
+impl<V> Projected<V> {
+    /// Returns the full response.
+    pub fn full(value: V) -> Self {
+        Projected {
+            value,
+            fields: None,
+        }
+    }
+
+    /// Returns the response with only the `fields`, or the full response if `fields` is `None`.
+    ///
+    /// Returns an error message suitable for an invalid params error if the fields are malformed.
+    /// The fields only apply to the JSON objects, the responses in other formats, such as the
+    /// hex strings, are returned whole.
+    pub fn project(value: V, fields: Option<Vec<String>>) -> Result<Self, String> {
+        let fields = fields.as_deref().map(FieldTree::parse).transpose()?;
+        Ok(Projected { value, fields })
+    }
+
+    /// Returns the inner value.
+    pub fn inner(&self) -> &V {
+        &self.value
+    }
+}
+
+impl<V: Serialize> Serialize for Projected<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.fields {
+            Some(tree) => ProjectedValue {
+                value: &self.value,
+                tree,
+            }
+            .serialize(serializer),
+            None => self.value.serialize(serializer),
+        }
+    }
+}
+
+impl<V: JsonSchema> JsonSchema for Projected<V> {
+    fn schema_name() -> String {
+        format!("Projected_for_{}", V::schema_name())
+    }
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        gen.subschema_for::<V>()
+    }
+}
+
+// The requested paths merged by their common prefixes.
+#[derive(Clone, Debug, Default)]
+struct FieldTree {
+    // keep the whole value, the nested paths are ignored
+    whole: bool,
+    children: BTreeMap<String, FieldTree>,
+}
+
+impl FieldTree {
+    fn parse(fields: &[String]) -> Result<Self, String> {
+        if fields.is_empty() {
+            return Err("fields should not be empty".to_string());
+        }
+        if fields.len() > MAX_FIELDS {
+            return Err(format!("the number of fields must not exceed {MAX_FIELDS}"));
+        }
+        let mut root = FieldTree::default();
+        for field in fields {
+            if field.split('.').any(str::is_empty) {
+                return Err(format!("invalid field \"{field}\""));
+            }
+            let mut node = &mut root;
+            for key in field.split('.') {
+                node = node.children.entry(key.to_string()).or_default();
+            }
+            node.whole = true;
+        }
+        Ok(root)
+    }
+}
+
+// Serializes the value with only the fields in the tree.
+struct ProjectedValue<'a, T: ?Sized> {
+    value: &'a T,
+    tree: &'a FieldTree,
+}
+
+impl<'a, T: Serialize + ?Sized> Serialize for ProjectedValue<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.tree.whole {
+            self.value.serialize(serializer)
+        } else {
+            self.value.serialize(FieldsSerializer {
+                inner: serializer,
+                tree: self.tree,
+            })
+        }
+    }
+}
+
+// Picks the fields of the objects, and the fields of every element of the arrays. The values
+// which can't be projected further, such as the hex strings, the nulls and the externally tagged
+// enum variants, are kept whole.
+struct FieldsSerializer<'a, S> {
+    inner: S,
+    tree: &'a FieldTree,
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'a, S: Serializer> Serializer for FieldsSerializer<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Elements<'a, S::SerializeSeq>;
+    type SerializeTuple = Elements<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = Elements<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = Entries<'a, S::SerializeMap>;
+    type SerializeStruct = Fields<'a, S::SerializeStruct>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward_serialize!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&ProjectedValue {
+            value,
+            tree: self.tree,
+        })
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(
+            name,
+            &ProjectedValue {
+                value,
+                tree: self.tree,
+            },
+        )
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Ok(Elements {
+            inner: self.inner.serialize_seq(len)?,
+            tree: self.tree,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Ok(Elements {
+            inner: self.inner.serialize_tuple(len)?,
+            tree: self.tree,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Ok(Elements {
+            inner: self.inner.serialize_tuple_struct(name, len)?,
+            tree: self.tree,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        Ok(Entries {
+            inner: self.inner.serialize_map(len)?,
+            tree: self.tree,
+            next: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        Ok(Fields {
+            inner: self.inner.serialize_struct(name, len)?,
+            tree: self.tree,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+// Projects every element by the same tree.
+struct Elements<'a, C> {
+    inner: C,
+    tree: &'a FieldTree,
+}
+
+impl<'a, C: SerializeSeq> SerializeSeq for Elements<'a, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_element(&ProjectedValue {
+            value,
+            tree: self.tree,
+        })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<'a, C: SerializeTuple> SerializeTuple for Elements<'a, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_element(&ProjectedValue {
+            value,
+            tree: self.tree,
+        })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<'a, C: SerializeTupleStruct> SerializeTupleStruct for Elements<'a, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.inner.serialize_field(&ProjectedValue {
+            value,
+            tree: self.tree,
+        })
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+// Keeps the fields in the tree, the others are skipped.
+struct Fields<'a, C> {
+    inner: C,
+    tree: &'a FieldTree,
+}
+
+impl<'a, C: SerializeStruct> SerializeStruct for Fields<'a, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        match self.tree.children.get(key) {
+            Some(tree) => self
+                .inner
+                .serialize_field(key, &ProjectedValue { value, tree }),
+            None => self.inner.skip_field(key),
+        }
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+// Keeps the entries whose keys are in the tree, such as the fields of a flattened struct.
+struct Entries<'a, C> {
+    inner: C,
+    tree: &'a FieldTree,
+    // the tree of the value after the kept key
+    next: Option<&'a FieldTree>,
+}
+
+impl<'a, C: SerializeMap> Entries<'a, C> {
+    fn child<K: Serialize + ?Sized>(&self, key: &K) -> Result<Option<&'a FieldTree>, C::Error> {
+        let key = match serde_json::to_value(key).map_err(C::Error::custom)? {
+            Value::String(key) => key,
+            other => other.to_string(),
+        };
+        Ok(self.tree.children.get(&key))
+    }
+}
+
+impl<'a, C: SerializeMap> SerializeMap for Entries<'a, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.next = self.child(key)?;
+        match self.next {
+            Some(_) => self.inner.serialize_key(key),
+            None => Ok(()),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        match self.next.take() {
+            Some(tree) => self.inner.serialize_value(&ProjectedValue { value, tree }),
+            None => Ok(()),
+        }
+    }
+
+    fn serialize_entry<K: Serialize + ?Sized, V: Serialize + ?Sized>(
+        &mut self,
+        key: &K,
+        value: &V,
+    ) -> Result<(), C::Error> {
+        match self.child(key)? {
+            Some(tree) => self
+                .inner
+                .serialize_entry(key, &ProjectedValue { value, tree }),
+            None => Ok(()),
+        }
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{JsonBytes, PoolTransactionEntry, Status, Uint32, Uint64};
+use ckb_types::{core::service::MigrationOutcome as CoreMigrationOutcome, H256};
 use serde::{Deserialize, Serialize};
 
 /// Specifies the topic which to be added as active subscription.
@@ -14,4 +16,133 @@ pub enum Topic {
     ProposedTransaction,
     /// Subscribe transactions which are abandoned by tx-pool.
     RejectedTransaction,
+    /// Subscribe in-pool transactions which enter the gap or proposed set.
+    ProposalWindow,
+    /// Subscribe the outcome of the background database migrations.
+    MigrationFinished,
+    /// Subscribe the status transitions of the transactions, from submitted to committed or
+    /// rejected.
+    TxStatusChanged,
+}
+
+/// The options of a subscription.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct SubscribeOptions {
+    /// Wraps every push message with a resume token, the `params.result` becomes an object with
+    /// the fields `event` and `resume_token`.
+    #[serde(default)]
+    pub with_resume_token: bool,
+    /// Resumes the subscription after the event of this token, the kept events after it are pushed
+    /// first. It implies `with_resume_token`.
+    #[serde(default)]
+    pub resume_token: Option<JsonBytes>,
+    /// Only pushes the events of these transactions. It only applies to the topics
+    /// `proposal_window` and `tx_status_changed`, and all the events are pushed by default.
+    #[serde(default)]
+    pub tx_hashes: Option<Vec<H256>>,
+    /// Only pushes the transactions using these scripts. It applies to the topics of the
+    /// transactions, which are `new_transaction`, `proposed_transaction`, `rejected_transaction`,
+    /// `proposal_window` and `tx_status_changed`, and all the transactions are pushed by default.
+    #[serde(default)]
+    pub filter: Option<SubscriptionFilter>,
+    /// Pushes the events in arrays instead of one message per event. It only applies to the
+    /// bursty topics `new_transaction` and `rejected_transaction`, and every event is pushed in
+    /// its own message by default.
+    #[serde(default)]
+    pub batch: Option<SubscriptionBatch>,
+}
+
+/// The batching of the events pushed by a subscription.
+///
+/// A batch is pushed when it reaches `max_batch_size` events, or at most `max_batch_latency_ms`
+/// milliseconds after its first event.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionBatch {
+    /// Max number of events in a push message, default 100, at most 1000.
+    #[serde(default)]
+    pub max_batch_size: Option<Uint32>,
+    /// Max milliseconds an event is held before the batch containing it is pushed, default 100,
+    /// at most 10000.
+    #[serde(default)]
+    pub max_batch_latency_ms: Option<Uint64>,
+}
+
+/// The scripts filtering the transactions pushed by a subscription.
+///
+/// A transaction matches if any of its input or output cells uses one of the lock scripts or one
+/// of the type scripts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionFilter {
+    /// The lock script hashes.
+    #[serde(default)]
+    pub lock_hashes: Vec<H256>,
+    /// The type script hashes.
+    #[serde(default)]
+    pub type_hashes: Vec<H256>,
+}
+
+/// The proposal window status which an in-pool transaction enters.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalWindowStatus {
+    /// The transaction is proposed in a block, but it can't be committed until the block is in
+    /// the proposal window.
+    Gap,
+    /// The transaction is proposed in the proposal window and can be committed.
+    Proposed,
+}
+
+/// The event of the topic `proposal_window`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ProposalWindowEvent {
+    /// The status the transaction enters.
+    pub status: ProposalWindowStatus,
+    /// The transaction entry in the pool.
+    pub entry: PoolTransactionEntry,
+}
+
+/// The event of the topic `tx_status_changed`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TxStatusChangedEvent {
+    /// The transaction hash.
+    pub tx_hash: H256,
+    /// The status before the transition, it's `unknown` if the node hasn't pushed any status of
+    /// the transaction.
+    pub old_status: Status,
+    /// The status after the transition.
+    pub new_status: Status,
+}
+
+/// The event of the topic `migration_finished`, the outcome of the background database
+/// migrations.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct MigrationFinishedEvent {
+    /// Whether all the background migrations succeeded.
+    pub success: bool,
+    /// The database version after the migrations, which is the version of the last applied
+    /// migration.
+    pub version: String,
+    /// How long the background migrations took, in milliseconds.
+    pub duration_ms: Uint64,
+    /// The version of the first failed migration, null if all the migrations succeeded.
+    ///
+    /// A failed migration is not applied, and the later migrations still run.
+    pub failed_version: Option<String>,
+    /// Why the migration failed, null if all the migrations succeeded.
+    pub error: Option<String>,
+}
+
+impl From<CoreMigrationOutcome> for MigrationFinishedEvent {
+    fn from(outcome: CoreMigrationOutcome) -> Self {
+        Self {
+            success: outcome.is_success(),
+            version: outcome.version,
+            duration_ms: outcome.duration_ms.into(),
+            failed_version: outcome.failed_version,
+            error: outcome.error,
+        }
+    }
 }
@@ -1,3 +1,5 @@
 mod blockchain;
 mod bytes;
+mod pagination;
 mod pool;
+mod projection;
@@ -0,0 +1,45 @@
+use crate::pagination::{check_limit, decode_id_cursor, encode_id_cursor, paginate_by_key};
+use crate::{IndexerOrder, JsonBytes, Uint32};
+
+#[test]
+fn test_check_limit() {
+    assert!(check_limit(Uint32::from(0u32), 10).is_err());
+    assert!(check_limit(Uint32::from(11u32), 10).is_err());
+    assert_eq!(check_limit(Uint32::from(10u32), 10), Ok(10));
+}
+
+#[test]
+fn test_id_cursor() {
+    let cursor = encode_id_cursor(-42);
+    assert_eq!(decode_id_cursor(&cursor), Ok(-42));
+    assert!(decode_id_cursor(&JsonBytes::from_vec(vec![1, 2, 3])).is_err());
+}
+
+#[test]
+fn test_paginate_by_key_is_stable_under_updates() {
+    let key = |v: &u8| vec![*v];
+
+    let page = paginate_by_key(vec![5u8, 1, 3, 7], key, IndexerOrder::Asc, 2, None);
+    assert_eq!(page.objects, vec![1, 3]);
+
+    // 2 is inserted before the cursor and 3 is removed, neither affects the next page
+    let page = paginate_by_key(
+        vec![2u8, 5, 1, 7, 6],
+        key,
+        IndexerOrder::Asc,
+        2,
+        Some(page.last_cursor),
+    );
+    assert_eq!(page.objects, vec![5, 6]);
+
+    let page = paginate_by_key(vec![7u8], key, IndexerOrder::Asc, 2, Some(page.last_cursor));
+    assert_eq!(page.objects, vec![7]);
+
+    // an empty page keeps the cursor
+    let page = paginate_by_key(vec![7u8], key, IndexerOrder::Asc, 2, Some(page.last_cursor));
+    assert!(page.objects.is_empty());
+    assert_eq!(page.last_cursor, JsonBytes::from_vec(vec![7]));
+
+    let page = paginate_by_key(vec![5u8, 1, 3, 7], key, IndexerOrder::Desc, 3, None);
+    assert_eq!(page.objects, vec![7, 5, 3]);
+}
@@ -8,3 +8,44 @@ fn test_outputs_validator_json_display() {
     );
     assert_eq!("passthrough", OutputsValidator::Passthrough.json_display());
 }
+
+#[test]
+fn test_proposal_window_subscribe_options() {
+    use crate::{ProposalWindowEvent, ProposalWindowStatus, SubscribeOptions};
+
+    let options: SubscribeOptions = serde_json::from_str(
+        r#"{"tx_hashes": ["0xa0ef4eb5f4ceeb08a4c8524d84c5da95dce2f608e0ca2ec8091191b0f330c6e3"]}"#,
+    )
+    .unwrap();
+    assert_eq!(options.tx_hashes.map(|tx_hashes| tx_hashes.len()), Some(1));
+    assert!(!options.with_resume_token);
+
+    let event = ProposalWindowEvent {
+        status: ProposalWindowStatus::Gap,
+        entry: Default::default(),
+    };
+    let json = serde_json::to_value(event).unwrap();
+    assert_eq!(json["status"], "gap");
+}
+
+#[test]
+fn test_migration_finished_event() {
+    use crate::{MigrationFinishedEvent, Topic};
+    use ckb_types::core::service::MigrationOutcome;
+
+    let topic: Topic = serde_json::from_str(r#""migration_finished""#).unwrap();
+    assert_eq!(topic, Topic::MigrationFinished);
+
+    let event: MigrationFinishedEvent = MigrationOutcome {
+        version: "20230101000000".to_string(),
+        duration_ms: 1500,
+        failed_version: Some("20240101000000".to_string()),
+        error: Some("Internal error".to_string()),
+    }
+    .into();
+    let json = serde_json::to_value(event).unwrap();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["version"], "20230101000000");
+    assert_eq!(json["duration_ms"], "0x5dc");
+    assert_eq!(json["failed_version"], "20240101000000");
+}
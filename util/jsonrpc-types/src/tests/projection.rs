@@ -0,0 +1,132 @@
+use crate::projection::MAX_FIELDS;
+use crate::Projected;
+use serde::Serialize;
+use serde_json::json;
+
+#[test]
+fn test_project_fields() {
+    let block = json!({
+        "header": { "hash": "0x01", "number": "0x10" },
+        "transactions": [
+            { "hash": "0x02", "inputs": [] },
+            { "hash": "0x03", "inputs": [] }
+        ],
+        "uncles": [],
+        "extension": null
+    });
+
+    let projected = Projected::project(
+        block.clone(),
+        Some(vec![
+            "header.number".to_string(),
+            "transactions.hash".to_string(),
+            "missing".to_string(),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(projected).unwrap(),
+        json!({
+            "header": { "number": "0x10" },
+            "transactions": [{ "hash": "0x02" }, { "hash": "0x03" }]
+        })
+    );
+
+    // the whole field wins over its nested paths
+    let projected = Projected::project(
+        block.clone(),
+        Some(vec!["header".to_string(), "header.number".to_string()]),
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(projected).unwrap(),
+        json!({ "header": { "hash": "0x01", "number": "0x10" } })
+    );
+
+    let projected = Projected::project(block.clone(), None).unwrap();
+    assert_eq!(projected.inner(), &block);
+    assert_eq!(serde_json::to_value(projected).unwrap(), block);
+}
+
+#[derive(Serialize)]
+struct Header {
+    number: u64,
+    hash: String,
+}
+
+#[derive(Serialize)]
+struct Transaction {
+    #[serde(flatten)]
+    inner: Inputs,
+    hash: String,
+}
+
+#[derive(Serialize)]
+struct Inputs {
+    inputs: Vec<u64>,
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct Block {
+    header: Header,
+    transactions: Vec<Transaction>,
+    extension: Option<Header>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uncles: Option<Vec<Header>>,
+}
+
+#[test]
+fn test_project_fields_while_serializing() {
+    let header = || Header {
+        number: 16,
+        hash: "0x01".to_string(),
+    };
+    let block = Block {
+        header: header(),
+        transactions: vec![Transaction {
+            inner: Inputs {
+                inputs: vec![1, 2],
+                version: 0,
+            },
+            hash: "0x02".to_string(),
+        }],
+        extension: Some(header()),
+        uncles: None,
+    };
+
+    let fields = [
+        "header.number",
+        "transactions.inputs",
+        "extension.hash",
+        "uncles",
+    ];
+    let projected = Projected::project(
+        block,
+        Some(fields.iter().map(ToString::to_string).collect()),
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(projected).unwrap(),
+        json!({
+            "header": { "number": 16 },
+            "transactions": [{ "inputs": [1, 2] }],
+            "extension": { "hash": "0x01" }
+        })
+    );
+}
+
+#[test]
+fn test_project_invalid_fields() {
+    let block = json!({ "header": {} });
+    assert!(Projected::project(block.clone(), Some(vec![])).is_err());
+    assert!(Projected::project(block.clone(), Some(vec!["header.".to_string()])).is_err());
+    assert!(Projected::project(block, Some(vec!["a".to_string(); MAX_FIELDS + 1])).is_err());
+}
+
+#[test]
+fn test_project_non_object() {
+    // the hex strings can't be projected, they are returned whole
+    let projected = Projected::project(json!("0x00"), Some(vec!["header".to_string()])).unwrap();
+    assert_eq!(serde_json::to_value(projected).unwrap(), json!("0x00"));
+}
@@ -0,0 +1,225 @@
+//! The service evaluating the local alert rules.
+use ckb_app_config::AlertRulesConfig;
+use ckb_async_runtime::tokio::{self, time::timeout};
+use ckb_logger::{debug, info, warn};
+use ckb_network::NetworkController;
+use ckb_network_alert::{
+    local_rules::{
+        migration_alert, LocalAlertEvent, LocalAlertRules, NodeStatus, MIGRATION_ALERT_MILLIS,
+    },
+    notifier::Notifier,
+};
+use ckb_shared::Shared;
+use ckb_stop_handler::{new_tokio_exit_rx, CancellationToken};
+use ckb_store::ChainStore;
+use ckb_types::{core::BlockNumber, packed::Byte32, prelude::*};
+use ckb_util::Mutex;
+use hyper::{client::HttpConnector, Body, Client, Method, Request};
+use std::sync::Arc;
+use std::time::Duration;
+
+const NAME: &str = "AlertRules";
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Raises a local alert when the background migrations finish or fail, so the outcome shows up in
+/// the alert RPC, subscription and `notify.network_alert_notify_script`.
+pub fn raise_migration_alert(shared: &Shared, notifier: Arc<Mutex<Notifier>>) {
+    let notify_controller = shared.notify_controller().clone();
+    let stop_rx: CancellationToken = new_tokio_exit_rx();
+    shared.async_handle().spawn(async move {
+        let mut outcome_receiver = notify_controller.subscribe_migration_finished(NAME).await;
+        tokio::select! {
+            Some(outcome) = outcome_receiver.recv() => {
+                let notice_until = ckb_systemtime::unix_time_as_millis() + MIGRATION_ALERT_MILLIS;
+                notifier
+                    .lock()
+                    .raise_local(&migration_alert(&outcome, notice_until));
+            }
+            _ = stop_rx.cancelled() => {}
+        }
+    });
+}
+
+/// Evaluates the local alert rules periodically, the violated rules are raised as local alerts.
+pub struct AlertRulesService {
+    config: AlertRulesConfig,
+    shared: Shared,
+    network_controller: NetworkController,
+    notifier: Arc<Mutex<Notifier>>,
+    rules: LocalAlertRules,
+    // the tip when the rules were evaluated last time
+    last_tip: Option<(BlockNumber, Byte32)>,
+    poster: Arc<Client<HttpConnector>>,
+}
+
+impl AlertRulesService {
+    /// Init
+    pub fn new(
+        config: AlertRulesConfig,
+        shared: Shared,
+        network_controller: NetworkController,
+        notifier: Arc<Mutex<Notifier>>,
+    ) -> Self {
+        AlertRulesService {
+            rules: LocalAlertRules::new(config.clone()),
+            config,
+            shared,
+            network_controller,
+            notifier,
+            last_tip: None,
+            poster: Arc::new(Client::new()),
+        }
+    }
+
+    /// Start the background service
+    pub fn start(mut self) {
+        let async_handle = self.shared.async_handle().clone();
+        let stop_rx: CancellationToken = new_tokio_exit_rx();
+        let check_interval = Duration::from_secs(self.config.check_interval_secs());
+
+        async_handle.spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        tokio::task::block_in_place(|| self.check());
+                    }
+                    _ = stop_rx.cancelled() => {
+                        info!("{} received exit signal, exit now", NAME);
+                        break
+                    },
+                }
+            }
+        });
+    }
+
+    fn check(&mut self) {
+        let status = match self.node_status() {
+            Some(status) => status,
+            None => return,
+        };
+        let now = ckb_systemtime::unix_time_as_millis();
+        let events = self.rules.evaluate(&status, now);
+
+        {
+            let mut notifier = self.notifier.lock();
+            for event in &events {
+                if let LocalAlertEvent::Resolved { rule } = event {
+                    notifier.resolve_local(rule.alert_id());
+                }
+            }
+            // the active alerts expire unless they are refreshed by the next check
+            let notice_until = now + 2 * self.config.check_interval_secs() * 1000;
+            for alert in self.rules.active_alerts(notice_until) {
+                notifier.raise_local(&alert);
+            }
+        }
+
+        for event in events {
+            match &event {
+                LocalAlertEvent::Raised { rule, message } => {
+                    warn!("Local alert {} raised: {}", rule.name(), message)
+                }
+                LocalAlertEvent::Resolved { rule } => {
+                    info!("Local alert {} resolved", rule.name())
+                }
+            }
+            self.post_webhook(&event, now);
+        }
+    }
+
+    fn node_status(&mut self) -> Option<NodeStatus> {
+        let tx_pool_controller = self.shared.tx_pool_controller();
+        // the pool size rule never fires with a zero `max_pool_size`
+        let (pool_size, max_pool_size) = if tx_pool_controller.is_enabled() {
+            match tx_pool_controller.get_tx_pool_info() {
+                Ok(info) => (info.total_tx_size as u64, info.max_tx_pool_size),
+                Err(err) => {
+                    debug!("{} failed to get the tx-pool info: {}", NAME, err);
+                    return None;
+                }
+            }
+        } else {
+            (0, 0)
+        };
+        let snapshot = self.shared.snapshot();
+        let tip_header = snapshot.tip_header();
+        let reorg_depth = self.reorg_depth(tip_header.number(), tip_header.hash());
+
+        Some(NodeStatus {
+            tip_timestamp: tip_header.timestamp(),
+            peers: self.network_controller.connected_peers().len(),
+            pool_size,
+            max_pool_size,
+            reorg_depth,
+        })
+    }
+
+    // The number of the blocks detached since the last check, the reorganizations are detected by
+    // whether the last tip is still on the main chain.
+    fn reorg_depth(&mut self, tip_number: BlockNumber, tip_hash: Byte32) -> u64 {
+        let last_tip = self.last_tip.replace((tip_number, tip_hash));
+        let (last_number, last_hash) = match last_tip {
+            Some(last_tip) => last_tip,
+            None => return 0,
+        };
+        let snapshot = self.shared.snapshot();
+        if snapshot.is_main_chain(&last_hash) {
+            return 0;
+        }
+        let mut header = match snapshot.get_block_header(&last_hash) {
+            Some(header) => header,
+            None => return 0,
+        };
+        while !snapshot.is_main_chain(&header.parent_hash()) {
+            header = match snapshot.get_block_header(&header.parent_hash()) {
+                Some(header) => header,
+                None => break,
+            };
+        }
+        // the header is the first detached block
+        last_number + 1 - header.number()
+    }
+
+    fn post_webhook(&self, event: &LocalAlertEvent, now: u64) {
+        let url = match &self.config.webhook_url {
+            Some(url) => url,
+            None => return,
+        };
+        let body = match event {
+            LocalAlertEvent::Raised { rule, message } => serde_json::json!({
+                "event": "raised",
+                "rule": rule.name(),
+                "alert_id": rule.alert_id(),
+                "message": message,
+                "timestamp": now,
+            }),
+            LocalAlertEvent::Resolved { rule } => serde_json::json!({
+                "event": "resolved",
+                "rule": rule.name(),
+                "alert_id": rule.alert_id(),
+                "timestamp": now,
+            }),
+        };
+        match Request::builder()
+            .method(Method::POST)
+            .uri(url.as_str())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+        {
+            Ok(req) => {
+                let client = Arc::clone(&self.poster);
+                let url = url.to_owned();
+                self.shared.async_handle().spawn(async move {
+                    match timeout(WEBHOOK_TIMEOUT, client.request(req)).await {
+                        Ok(Ok(_resp)) => {}
+                        Ok(Err(err)) => warn!("{} posting to {} failed: {}", NAME, url, err),
+                        Err(_) => warn!("{} posting to {} timed out", NAME, url),
+                    }
+                });
+            }
+            Err(err) => warn!("{} invalid webhook url {}: {}", NAME, url, err),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Reloads the allowlist of the private network on `SIGHUP`.
+use ckb_logger::{error, info};
+use ckb_network::NetworkController;
+use ckb_shared::Shared;
+use ckb_stop_handler::{new_tokio_exit_rx, CancellationToken};
+
+/// Spawns a task reloading `network.allowed_peers_file` every time the node receives `SIGHUP`,
+/// the peers removed from the file are disconnected.
+pub(crate) fn reload_allowed_peers_on_hangup(
+    shared: &Shared,
+    network_controller: NetworkController,
+) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    shared.async_handle().spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(err) => {
+                error!("Failed to listen SIGHUP to reload allowed peers: {}", err);
+                return;
+            }
+        };
+        let exit_signal: CancellationToken = new_tokio_exit_rx();
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => {
+                    match network_controller.reload_allowed_peers() {
+                        Ok(Some(count)) => info!("Reloaded allowed peers, {} peers allowed", count),
+                        Ok(None) => {}
+                        Err(err) => error!("Failed to reload allowed peers: {}", err),
+                    }
+                }
+                _ = exit_signal.cancelled() => break,
+            }
+        }
+    });
+}
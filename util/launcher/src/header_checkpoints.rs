@@ -0,0 +1,91 @@
+//! The service exporting the signed header checkpoints to a file.
+use ckb_app_config::HeaderCheckpointsConfig;
+use ckb_async_runtime::tokio;
+use ckb_jsonrpc_types::SignedHeaderCheckpoints;
+use ckb_logger::{debug, info, warn};
+use ckb_shared::header_checkpoints::{
+    collect_header_checkpoints, HeaderCheckpointSigner, MAX_HEADER_CHECKPOINTS,
+};
+use ckb_shared::Shared;
+use ckb_stop_handler::{new_tokio_exit_rx, CancellationToken};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+const NAME: &str = "HeaderCheckpointsExporter";
+
+/// Exports the recent header checkpoints periodically.
+pub struct HeaderCheckpointsExporter {
+    config: HeaderCheckpointsConfig,
+    shared: Shared,
+    signer: Arc<HeaderCheckpointSigner>,
+}
+
+impl HeaderCheckpointsExporter {
+    /// Init
+    pub fn new(
+        config: HeaderCheckpointsConfig,
+        shared: Shared,
+        signer: Arc<HeaderCheckpointSigner>,
+    ) -> Self {
+        HeaderCheckpointsExporter {
+            config,
+            shared,
+            signer,
+        }
+    }
+
+    /// Start the background service
+    pub fn start(self) {
+        let path = match self.config.export_file.clone() {
+            Some(path) => path,
+            None => return,
+        };
+        let async_handle = self.shared.async_handle().clone();
+        let stop_rx: CancellationToken = new_tokio_exit_rx();
+        let export_interval = Duration::from_secs(self.config.export_interval_secs());
+
+        async_handle.spawn(async move {
+            let mut interval = tokio::time::interval(export_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(err) = tokio::task::block_in_place(|| self.export(&path)) {
+                            warn!("{} failed to write {}: {}", NAME, path.display(), err);
+                        }
+                    }
+                    _ = stop_rx.cancelled() => {
+                        info!("{} received exit signal, exit now", NAME);
+                        break
+                    },
+                }
+            }
+        });
+    }
+
+    // Writes a temporary file then renames it, so the readers never see a partial file.
+    fn export(&self, path: &Path) -> io::Result<()> {
+        let snapshot = self.shared.snapshot();
+        let count = self.config.count().min(MAX_HEADER_CHECKPOINTS);
+        let checkpoints = collect_header_checkpoints(&snapshot, self.config.interval(), count);
+        let signed: SignedHeaderCheckpoints = self
+            .signer
+            .sign(self.shared.genesis_hash(), checkpoints)
+            .into();
+        let content = serde_json::to_string_pretty(&signed)?;
+
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        debug!(
+            "{} exported {} checkpoints to {}",
+            NAME,
+            signed.checkpoints.len(),
+            path.display()
+        );
+        Ok(())
+    }
+}
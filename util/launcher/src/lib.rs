@@ -2,8 +2,19 @@
 //!
 //! ckb launcher is helps to launch ckb node.
 
+mod alert_rules;
+#[cfg(unix)]
+mod allowed_peers;
+mod header_checkpoints;
+#[cfg(unix)]
+mod pool_policy;
+mod preflight;
+
+use crate::alert_rules::{raise_migration_alert, AlertRulesService};
+use crate::header_checkpoints::HeaderCheckpointsExporter;
 use ckb_app_config::{
-    BlockAssemblerConfig, ExitCode, RpcConfig, RpcModule, RunArgs, SupportProtocol,
+    BlockAssemblerConfig, ExitCode, NetworkConfig, RpcConfig, RpcModule, RunArgs, SupportProtocol,
+    TxPoolConfig,
 };
 use ckb_async_runtime::Handle;
 use ckb_block_filter::filter::BlockFilter as BlockFilterService;
@@ -21,6 +32,7 @@ use ckb_network::{
 use ckb_network_alert::alert_relayer::AlertRelayer;
 use ckb_resource::Resource;
 use ckb_rpc::{RpcServer, ServiceBuilder};
+use ckb_shared::header_checkpoints::HeaderCheckpointSigner;
 use ckb_shared::{ChainServicesBuilder, Shared};
 
 use ckb_shared::shared_builder::{SharedBuilder, SharedPackage};
@@ -33,6 +45,8 @@ use ckb_verification_traits::Verifier;
 use std::sync::Arc;
 
 const SECP256K1_BLAKE160_SIGHASH_ALL_ARG_LEN: usize = 20;
+// Unclean shutdowns only damage the latest blocks, so `--repair-chain` does not scan the whole chain
+const REPAIR_CHAIN_DEPTH: u64 = 10_000;
 
 /// Ckb launcher is helps to launch ckb node.
 pub struct Launcher {
@@ -173,6 +187,31 @@ impl Launcher {
         Ok(())
     }
 
+    fn repair_chain(&self, shared: &Shared) -> Result<(), ExitCode> {
+        let tip_number = shared.snapshot().tip_number();
+        let from = tip_number.saturating_sub(REPAIR_CHAIN_DEPTH);
+        info!("Checking main chain blocks from {} to {}", from, tip_number);
+        let issues = shared.check_chain_integrity(from, true).map_err(|err| {
+            eprintln!("repair chain error: {err}");
+            ExitCode::Failure
+        })?;
+        for issue in issues.iter() {
+            warn!(
+                "Repair main chain block {} {}: {:?}",
+                issue.number, issue.hash, issue.kind
+            );
+        }
+        let missing = shared.missing_block_bodies().len();
+        if missing > 0 {
+            info!(
+                "{} main chain blocks will be downloaded again from the peers",
+                missing
+            );
+        }
+        info!("Main chain check finished, {} issues found", issues.len());
+        Ok(())
+    }
+
     fn verify_genesis(&self, shared: &Shared) -> Result<(), ExitCode> {
         GenesisVerifier::new()
             .verify(shared.consensus())
@@ -187,9 +226,16 @@ impl Launcher {
         &self,
         block_assembler_config: Option<BlockAssemblerConfig>,
     ) -> Result<(Shared, SharedPackage), ExitCode> {
-        self.async_handle.block_on(observe_listen_port_occupancy(
-            &self.args.config.network.listen_addresses,
-        ))?;
+        let listen_addresses: Vec<_> = self
+            .args
+            .config
+            .network
+            .listeners()
+            .into_iter()
+            .map(|listener| listener.address)
+            .collect();
+        self.async_handle
+            .block_on(observe_listen_port_occupancy(&listen_addresses))?;
 
         let shared_builder = SharedBuilder::new(
             &self.args.config.bin_name,
@@ -205,6 +251,7 @@ impl Launcher {
             .notify_config(self.args.config.notify.clone())
             .store_config(self.args.config.store)
             .sync_config(self.args.config.network.sync.clone())
+            .chain_stats_windows(self.args.config.chain.stats_windows.clone())
             .header_map_tmp_dir(self.args.config.tmp_dir.clone())
             .block_assembler_config(block_assembler_config)
             .build()?;
@@ -216,6 +263,10 @@ impl Launcher {
         self.verify_genesis(&shared)?;
         self.check_spec(&shared)?;
 
+        if self.args.repair_chain {
+            self.repair_chain(&shared)?;
+        }
+
         Ok((shared, pack))
     }
 
@@ -253,6 +304,31 @@ impl Launcher {
         Ok(())
     }
 
+    /// Check the environment before the services start, unless `--skip-preflight` is set
+    pub fn preflight_check(&self) -> Result<(), ExitCode> {
+        if self.args.skip_preflight {
+            warn!("Preflight checks are skipped");
+            return Ok(());
+        }
+        preflight::preflight(&self.args.config, self.args.preflight_ntp)
+    }
+
+    /// Check the subsystems depending on the tx-pool are disabled along with it
+    pub fn check_tx_pool_config(&self) -> Result<(), ExitCode> {
+        let config = &self.args.config;
+        match tx_pool_config_error(
+            &config.tx_pool,
+            &config.network,
+            config.block_assembler.as_ref(),
+        ) {
+            Some(error) => {
+                eprintln!("Config Error: the tx-pool is disabled, {error}");
+                Err(ExitCode::Config)
+            }
+            None => Ok(()),
+        }
+    }
+
     /// start block filter service
     pub fn start_block_filter(&self, shared: &Shared) {
         if self
@@ -266,6 +342,16 @@ impl Launcher {
         }
     }
 
+    /// Load the network secret key to sign the header checkpoints
+    pub fn load_checkpoint_signer(&self) -> Result<Arc<HeaderCheckpointSigner>, ExitCode> {
+        HeaderCheckpointSigner::from_network_config(&self.args.config.network)
+            .map(Arc::new)
+            .map_err(|err| {
+                eprintln!("Config Error: failed to load the network secret key: {err}");
+                ExitCode::Config
+            })
+    }
+
     /// Start network service and rpc serve
     pub fn start_network_and_rpc(
         &self,
@@ -273,6 +359,7 @@ impl Launcher {
         chain_controller: ChainController,
         miner_enable: bool,
         relay_tx_receiver: Receiver<TxVerificationResult>,
+        checkpoint_signer: Arc<HeaderCheckpointSigner>,
     ) -> NetworkController {
         let sync_shared = Arc::new(SyncShared::new(
             shared.clone(),
@@ -304,6 +391,14 @@ impl Launcher {
         let support_protocols = &self.args.config.network.support_protocols;
         let mut flags = Flags::all();
 
+        // the blocks with the pruned uncles and proposals are not served to the syncing peers
+        let store_config = &self.args.config.store;
+        if store_config.uncles_and_proposals_retention_epochs.is_some()
+            && !store_config.freezer_enable
+        {
+            flags.remove(Flags::SYNC);
+        }
+
         if support_protocols.contains(&SupportProtocol::Relay) {
             let relayer_v3 = Relayer::new(chain_controller.clone(), Arc::clone(&sync_shared)).v3();
 
@@ -323,6 +418,9 @@ impl Launcher {
         } else {
             flags.remove(Flags::RELAY);
         }
+        if !flags.contains(Flags::RELAY) || !self.args.config.network.sync.tx_relay.accept_push {
+            flags.remove(Flags::TX_PUSH);
+        }
 
         if support_protocols.contains(&SupportProtocol::Filter) {
             let filter = BlockFilter::new(Arc::clone(&sync_shared));
@@ -388,6 +486,36 @@ impl Launcher {
         .start(shared.async_handle())
         .expect("Start network service failed");
 
+        #[cfg(unix)]
+        if self.args.config.tx_pool.enable {
+            pool_policy::reload_pool_policy_on_hangup(shared, self.args.config.root_dir.clone());
+        }
+
+        #[cfg(unix)]
+        if self.args.config.network.allowed_peers_file.is_some() {
+            allowed_peers::reload_allowed_peers_on_hangup(shared, network_controller.clone());
+        }
+
+        raise_migration_alert(shared, Arc::clone(&alert_notifier));
+        if self.args.config.alert_rules.is_enabled() {
+            AlertRulesService::new(
+                self.args.config.alert_rules.clone(),
+                shared.clone(),
+                network_controller.clone(),
+                Arc::clone(&alert_notifier),
+            )
+            .start();
+        }
+
+        if self.args.config.header_checkpoints.is_enabled() {
+            HeaderCheckpointsExporter::new(
+                self.args.config.header_checkpoints.clone(),
+                shared.clone(),
+                Arc::clone(&checkpoint_signer),
+            )
+            .start();
+        }
+
         let rpc_config = self.adjust_rpc_config();
         let mut builder = ServiceBuilder::new(&rpc_config)
             .enable_chain(shared.clone())
@@ -414,6 +542,7 @@ impl Launcher {
                 network_controller.clone(),
                 sync_shared,
                 Arc::new(chain_controller.clone()),
+                checkpoint_signer,
             )
             .enable_stats(shared.clone(), Arc::clone(&alert_notifier))
             .enable_experiment(shared.clone())
@@ -438,12 +567,75 @@ impl Launcher {
                 &self.args.config.db,
                 &self.args.config.indexer,
             )
-            .enable_debug();
+            .enable_debug(shared.clone());
         builder.enable_subscription(shared.clone());
         let io_handler = builder.build();
 
-        let _rpc = RpcServer::new(rpc_config, io_handler, self.rpc_handle.clone());
+        let _rpc = RpcServer::new(rpc_config, io_handler, &shared, self.rpc_handle.clone());
 
         network_controller
     }
 }
+
+// Returns the fix if a subsystem depending on the tx-pool is not disabled along with it
+fn tx_pool_config_error(
+    tx_pool: &TxPoolConfig,
+    network: &NetworkConfig,
+    block_assembler: Option<&BlockAssemblerConfig>,
+) -> Option<&'static str> {
+    if tx_pool.enable {
+        return None;
+    }
+    if network.support_protocols.contains(&SupportProtocol::Relay) {
+        return Some("remove `Relay` from `network.support_protocols`");
+    }
+    if block_assembler.is_some() {
+        return Some("remove the `block_assembler` section");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tx_pool_config_error;
+    use ckb_app_config::{BlockAssemblerConfig, NetworkConfig, SupportProtocol, TxPoolConfig};
+    use ckb_jsonrpc_types::ScriptHashType;
+
+    #[test]
+    fn test_tx_pool_config_error() {
+        let enabled = TxPoolConfig::default();
+        let disabled = TxPoolConfig {
+            enable: false,
+            ..Default::default()
+        };
+        let relay = NetworkConfig {
+            support_protocols: vec![SupportProtocol::Sync, SupportProtocol::Relay],
+            ..Default::default()
+        };
+        let no_relay = NetworkConfig {
+            support_protocols: vec![SupportProtocol::Sync],
+            ..Default::default()
+        };
+        let block_assembler = BlockAssemblerConfig {
+            code_hash: Default::default(),
+            args: Default::default(),
+            message: Default::default(),
+            hash_type: ScriptHashType::Type,
+            use_binary_version_as_message_prefix: true,
+            binary_version: String::new(),
+            update_interval_millis: 800,
+            notify: Vec::new(),
+            notify_scripts: Vec::new(),
+            notify_timeout_millis: 800,
+        };
+
+        assert_eq!(
+            tx_pool_config_error(&enabled, &relay, Some(&block_assembler)),
+            None
+        );
+        assert_eq!(tx_pool_config_error(&disabled, &no_relay, None), None);
+        // the subsystems depending on the tx-pool are disabled along with it
+        assert!(tx_pool_config_error(&disabled, &relay, None).is_some());
+        assert!(tx_pool_config_error(&disabled, &no_relay, Some(&block_assembler)).is_some());
+    }
+}
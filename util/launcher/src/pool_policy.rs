@@ -0,0 +1,49 @@
+//! Reloads the tx-pool policy from the config file on `SIGHUP`.
+use ckb_app_config::{CKBAppConfig, ExitCode, TxPoolConfig};
+use ckb_logger::{error, info};
+use ckb_resource::Resource;
+use ckb_shared::Shared;
+use ckb_stop_handler::{new_tokio_exit_rx, CancellationToken};
+use std::path::{Path, PathBuf};
+
+/// Spawns a task reloading the `tx_pool` section of `ckb.toml` every time the node receives
+/// `SIGHUP`, the hot-reloadable policy options take effect without restart.
+pub(crate) fn reload_pool_policy_on_hangup(shared: &Shared, root_dir: PathBuf) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let tx_pool_controller = shared.tx_pool_controller().clone();
+    shared.async_handle().spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(err) => {
+                error!("Failed to listen SIGHUP to reload tx-pool policy: {}", err);
+                return;
+            }
+        };
+        let exit_signal: CancellationToken = new_tokio_exit_rx();
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => {
+                    let config = match load_tx_pool_config(&root_dir) {
+                        Ok(config) => config,
+                        Err(err) => {
+                            error!("Failed to reload tx-pool policy from ckb.toml: {:?}", err);
+                            continue;
+                        }
+                    };
+                    match tx_pool_controller.reload_pool_policy(config) {
+                        Ok(policy) => info!("Reloaded tx-pool policy, version {}", policy.version),
+                        Err(err) => error!("Failed to reload tx-pool policy: {}", err),
+                    }
+                }
+                _ = exit_signal.cancelled() => break,
+            }
+        }
+    });
+}
+
+fn load_tx_pool_config(root_dir: &Path) -> Result<TxPoolConfig, ExitCode> {
+    let resource = Resource::ckb_config(root_dir);
+    let config = CKBAppConfig::load_from_slice(&resource.get()?)?;
+    Ok(config.tx_pool)
+}
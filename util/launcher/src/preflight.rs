@@ -0,0 +1,312 @@
+//! The preflight checks before the services start.
+//!
+//! The node fails late and obscurely when the environment is not ready, e.g., RocksDB runs out of
+//! the file descriptors after syncing for hours, or a port is taken by another process. The
+//! preflight phase checks the environment up front and prints the actionable errors, it can be
+//! skipped by `ckb run --skip-preflight`.
+//!
+//! Only the ports and the database lock fail the startup, the limits and the disk space depend on
+//! the workload, and the clock is only checked by `--preflight-ntp`, so they are warnings.
+use ckb_app_config::{CKBAppConfig, ExitCode};
+use ckb_logger::info;
+use ckb_network::multiaddr_to_socketaddr;
+use ckb_verification::ALLOWED_FUTURE_BLOCKTIME;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc;
+use std::time::Duration;
+
+// The file descriptors reserved for RocksDB when `db.options.max_open_files` is not set, the RPC
+// connections and the others besides the peers
+const RESERVED_FILE_DESCRIPTORS: u64 = 1024;
+// The free space the disks of the data should have, about the chain growth of a few months plus
+// the room of the RocksDB compactions
+const MIN_DISK_HEADROOM: u64 = 10 << 30;
+// The SNTP servers to check the clock skew with, the first one responding is used
+const NTP_SERVERS: [&str; 2] = ["pool.ntp.org:123", "time.cloudflare.com:123"];
+// The timeout of both the DNS lookup and the response of a server
+const NTP_TIMEOUT: Duration = Duration::from_secs(2);
+// The seconds from 1900-01-01, the NTP epoch, to the unix epoch
+const NTP_UNIX_EPOCH_DELTA: u64 = 2_208_988_800;
+
+/// Runs the preflight checks, prints all the failed checks and returns an error if any check
+/// which is not a warning fails. The clock skew is checked only if `ntp` is set.
+pub fn preflight(config: &CKBAppConfig, ntp: bool) -> Result<(), ExitCode> {
+    let mut errors = Vec::new();
+    check_file_descriptors(config);
+    check_disk_space(config);
+    if ntp {
+        check_clock_skew();
+    }
+    check_ports(config, &mut errors);
+    check_db_lock(config, &mut errors);
+
+    if errors.is_empty() {
+        info!("Preflight checks passed");
+        return Ok(());
+    }
+    for error in &errors {
+        eprintln!("Preflight Error: {error}");
+    }
+    eprintln!("Fix the errors above, or skip the checks with `ckb run --skip-preflight`");
+    Err(ExitCode::Config)
+}
+
+#[cfg(unix)]
+fn check_file_descriptors(config: &CKBAppConfig) {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `limit` is a valid pointer to `rlimit`
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        eprintln!(
+            "Preflight Warning: failed to get the file descriptors limit: {}",
+            std::io::Error::last_os_error()
+        );
+        return;
+    }
+    let db_files = config
+        .db
+        .options
+        .get("max_open_files")
+        .and_then(|files| files.parse::<u64>().ok())
+        .unwrap_or(RESERVED_FILE_DESCRIPTORS);
+    let required = u64::from(config.network.max_peers) + db_files;
+    if (limit.rlim_cur as u64) < required {
+        eprintln!(
+            "Preflight Warning: the file descriptors limit {} is less than {required}, which may \
+             be used by `network.max_peers` {} and the database, raise it by \
+             `ulimit -n {required}`, or `LimitNOFILE={required}` in the systemd unit",
+            limit.rlim_cur, config.network.max_peers
+        );
+    }
+}
+
+#[cfg(not(unix))]
+fn check_file_descriptors(_config: &CKBAppConfig) {}
+
+fn check_disk_space(config: &CKBAppConfig) {
+    let mut paths = vec![
+        config.data_dir.clone(),
+        config.db.path.clone(),
+        config.ancient.clone(),
+    ];
+    paths.dedup();
+    for path in paths {
+        // the directories are created when the services start
+        let Some(existing) = path.ancestors().find(|dir| dir.exists()) else {
+            continue;
+        };
+        match fs2::available_space(existing) {
+            Ok(available) if available < MIN_DISK_HEADROOM => eprintln!(
+                "Preflight Warning: only {} MiB is available on the disk of {}, {} MiB is \
+                 suggested for the chain growth, free some space or move the data to a larger disk",
+                available >> 20,
+                path.display(),
+                MIN_DISK_HEADROOM >> 20
+            ),
+            Ok(_) => {}
+            Err(err) => eprintln!(
+                "Preflight Warning: failed to get the available space of {}: {err}",
+                path.display()
+            ),
+        }
+    }
+}
+
+fn check_clock_skew() {
+    let Some(skew) = NTP_SERVERS
+        .iter()
+        .find_map(|server| query_clock_skew(server))
+    else {
+        eprintln!(
+            "Preflight Warning: failed to query the time from the NTP servers {}, the clock skew \
+             is not checked",
+            NTP_SERVERS.join(", ")
+        );
+        return;
+    };
+    if skew.unsigned_abs() > ALLOWED_FUTURE_BLOCKTIME {
+        eprintln!(
+            "Preflight Warning: the system clock is {} ms {} the NTP time, more than the {} ms \
+             allowed for the block timestamps, sync the clock by an NTP service, e.g., chrony or \
+             systemd-timesyncd",
+            skew.unsigned_abs(),
+            if skew > 0 { "ahead of" } else { "behind" },
+            ALLOWED_FUTURE_BLOCKTIME
+        );
+    }
+}
+
+// Returns the local time minus the NTP time in milliseconds.
+fn query_clock_skew(server: &'static str) -> Option<i64> {
+    let addr = resolve_with_timeout(server)?;
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.set_read_timeout(Some(NTP_TIMEOUT)).ok()?;
+    socket.connect(addr).ok()?;
+    // SNTP v4 client request
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+    let sent = ckb_systemtime::unix_time_as_millis();
+    socket.send(&request).ok()?;
+    let mut response = [0u8; 48];
+    let len = socket.recv(&mut response).ok()?;
+    let received = ckb_systemtime::unix_time_as_millis();
+    ntp_clock_skew(&response[..len], sent, received)
+}
+
+// The system resolver has no timeout, the lookup is left to finish in the background if it takes
+// longer than `NTP_TIMEOUT`.
+fn resolve_with_timeout(server: &'static str) -> Option<SocketAddr> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("PreflightNtpLookup".to_string())
+        .spawn(move || {
+            let addr = server
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.next());
+            let _ = sender.send(addr);
+        })
+        .ok()?;
+    receiver.recv_timeout(NTP_TIMEOUT).ok().flatten()
+}
+
+// Parses the transmit timestamp of the SNTP response, and compares it with the local time in the
+// middle of the round trip.
+fn ntp_clock_skew(response: &[u8], sent: u64, received: u64) -> Option<i64> {
+    // the mode 4 is server
+    if response.len() < 48 || response[0] & 0x7 != 4 {
+        return None;
+    }
+    let mut seconds = [0u8; 4];
+    let mut fraction = [0u8; 4];
+    seconds.copy_from_slice(&response[40..44]);
+    fraction.copy_from_slice(&response[44..48]);
+    let seconds = u64::from(u32::from_be_bytes(seconds)).checked_sub(NTP_UNIX_EPOCH_DELTA)?;
+    let millis = (u64::from(u32::from_be_bytes(fraction)) * 1000) >> 32;
+    let ntp_time = seconds * 1000 + millis;
+    let local_time = sent + received.saturating_sub(sent) / 2;
+    Some(local_time as i64 - ntp_time as i64)
+}
+
+fn check_ports(config: &CKBAppConfig, errors: &mut Vec<String>) {
+    let mut addrs: Vec<(&str, SocketAddr)> = config
+        .network
+        .listeners()
+        .into_iter()
+        .filter_map(|listener| {
+            multiaddr_to_socketaddr(&listener.address)
+                .map(|addr| ("network.listen_addresses", addr))
+        })
+        .collect();
+    let rpc_addresses = [
+        ("rpc.listen_address", Some(&config.rpc.listen_address)),
+        (
+            "rpc.tcp_listen_address",
+            config.rpc.tcp_listen_address.as_ref(),
+        ),
+        (
+            "rpc.ws_listen_address",
+            config.rpc.ws_listen_address.as_ref(),
+        ),
+    ];
+    for (option, address) in rpc_addresses {
+        if let Some(addr) = address
+            .and_then(|address| address.to_socket_addrs().ok())
+            .and_then(|mut addrs| addrs.next())
+        {
+            addrs.push((option, addr));
+        }
+    }
+    for (option, addr) in addrs {
+        if let Err(err) = TcpListener::bind(addr) {
+            errors.push(format!(
+                "the address {addr} in `{option}` can't be listened on: {err}, stop the process \
+                 using the port, or change `{option}`"
+            ));
+        }
+    }
+}
+
+#[cfg(unix)]
+fn check_db_lock(config: &CKBAppConfig, errors: &mut Vec<String>) {
+    use std::os::unix::io::AsRawFd;
+
+    let Ok(file) = std::fs::File::open(config.db.path.join("LOCK")) else {
+        return;
+    };
+    // RocksDB locks the file by `fcntl`, query the lock without taking it
+    // SAFETY: all-zero is a valid `flock`
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    // SAFETY: `lock` is a valid pointer to `flock` and the fd is open
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) } != 0 {
+        return;
+    }
+    if lock.l_type != libc::F_UNLCK as libc::c_short {
+        errors.push(format!(
+            "the database {} is locked by the process {}, stop the other node using the same \
+             data directory, or change `data_dir`",
+            config.db.path.display(),
+            lock.l_pid
+        ));
+    }
+}
+
+#[cfg(not(unix))]
+fn check_db_lock(_config: &CKBAppConfig, _errors: &mut Vec<String>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{ntp_clock_skew, NTP_UNIX_EPOCH_DELTA};
+
+    // A server response transmitting the unix time `millis`
+    fn response(millis: u64) -> [u8; 48] {
+        let mut response = [0u8; 48];
+        // LI 0, VN 4, mode 4
+        response[0] = 0x24;
+        let seconds = (millis / 1000 + NTP_UNIX_EPOCH_DELTA) as u32;
+        let fraction = (((millis % 1000) << 32) / 1000) as u32;
+        response[40..44].copy_from_slice(&seconds.to_be_bytes());
+        response[44..48].copy_from_slice(&fraction.to_be_bytes());
+        response
+    }
+
+    #[test]
+    fn test_ntp_clock_skew() {
+        let ntp_time = 1_700_000_000_500;
+        // the local time in the middle of the round trip is compared
+        assert_eq!(
+            ntp_clock_skew(&response(ntp_time), ntp_time - 100, ntp_time + 100),
+            Some(0)
+        );
+        assert_eq!(
+            ntp_clock_skew(&response(ntp_time), ntp_time + 2900, ntp_time + 3100),
+            Some(3000)
+        );
+        assert_eq!(
+            ntp_clock_skew(&response(ntp_time), ntp_time - 3100, ntp_time - 2900),
+            Some(-3000)
+        );
+    }
+
+    #[test]
+    fn test_ntp_clock_skew_invalid_response() {
+        let ntp_time = 1_700_000_000_000;
+        // truncated
+        assert_eq!(
+            ntp_clock_skew(&response(ntp_time)[..47], ntp_time, ntp_time),
+            None
+        );
+        // not from a server
+        let mut client = response(ntp_time);
+        client[0] = 0x23;
+        assert_eq!(ntp_clock_skew(&client, ntp_time, ntp_time), None);
+        // before the unix epoch
+        let mut before_epoch = response(ntp_time);
+        before_epoch[40..44].copy_from_slice(&[0; 4]);
+        assert_eq!(ntp_clock_skew(&before_epoch, ntp_time, ntp_time), None);
+    }
+}
@@ -6,6 +6,7 @@
 
 use std::{collections::HashMap, path::PathBuf};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
@@ -18,7 +19,7 @@ mod tests;
 /// Include configurations of the main logger and any number of extra loggers.
 ///
 /// [`Logger`]: ../ckb_logger_service/struct.Logger.html
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// An optional string which is used to build [env_logger::Filter] for the main logger.
@@ -58,7 +59,7 @@ pub struct Config {
 /// This struct is used to build [`ExtraLogger`].
 ///
 /// [`ExtraLogger`]: ../ckb_logger_service/struct.ExtraLogger.html
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct ExtraLoggerConfig {
     /// A string which is used to build [env_logger::Filter] for the extra logger.
     ///
@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// The whole CKB metrics configuration.
@@ -19,7 +20,7 @@ use serde::{Deserialize, Serialize};
 /// ```
 ///
 /// [CKB metrics service]: ../ckb_metrics_service/index.html
-#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// Stores all exporters configurations.
@@ -28,14 +29,14 @@ pub struct Config {
 }
 
 /// The configuration of an exporter.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Exporter {
     /// How to output the metrics data.
     pub target: Target,
 }
 
 /// The target to output the metrics data.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
 pub enum Target {
@@ -45,6 +45,46 @@ make_static_metric! {
         },
     }
 
+    // Struct for CKB tx-pool conflicts statistics type label
+    struct CkbTxPoolConflictsStatistics: IntGauge{
+        "type" => {
+            count,
+            size,
+        },
+    }
+
+    // Struct for CKB tx-pool lazily stored witnesses statistics type label
+    struct CkbTxPoolLazyWitnessesStatistics: IntGauge{
+        "type" => {
+            count,
+            size,
+        },
+    }
+
+    // Struct for CKB tx-pool verify queue statistics type label
+    struct CkbTxPoolVerifyQueueStatistics: IntGauge{
+        "type" => {
+            count,
+            size,
+            cycles,
+        },
+    }
+
+    // Struct for the CKB tx-pool txs left out of the block template statistics labels
+    struct CkbTxPoolTemplateBacklogStatistics: IntGauge{
+        "status" => {
+            proposed,
+            pending,
+        },
+        "type" => {
+            count,
+            size,
+            cycles,
+            weight,
+            fees,
+        },
+    }
+
     struct CkbHeaderMapMemoryHitMissStatistics: IntCounter{
         "type" => {
             hit,
@@ -74,6 +114,10 @@ pub struct Metrics {
     pub ckb_chain_unverified_block_ch_len: IntGauge,
     pub ckb_chain_preload_unverified_block_ch_len: IntGauge,
     pub ckb_chain_load_full_unverified_block: Histogram,
+    /// Gauge for the ratio of the stale blocks to the main chain blocks, by window
+    pub ckb_chain_stale_block_rate: GaugeVec,
+    /// Gauge for the ratio of the uncles to the main chain blocks, by window
+    pub ckb_chain_uncle_rate: GaugeVec,
     /// ckb_sync_msg_process duration (seconds)
     pub ckb_sync_msg_process_duration: HistogramVec,
     /// ckb_sync_block_fetch duraiton (seconds)
@@ -108,6 +152,10 @@ pub struct Metrics {
     pub ckb_relay_cb_fresh_tx_cnt: IntCounter,
     /// Counter for relay compact block reconstruct fail
     pub ckb_relay_cb_reconstruct_fail: IntCounter,
+    /// Counter for relay compact block reconstruct result, hit, miss or collided
+    pub ckb_relay_cb_reconstruct_result: IntCounterVec,
+    /// Counter for relay compact block prefilled transaction count
+    pub ckb_relay_cb_prefilled_tx_count: IntCounter,
     // Gauge for CKB shared best number
     pub ckb_shared_best_number: IntGauge,
     // GaugeVec for CKB system memory process statistics
@@ -116,12 +164,32 @@ pub struct Metrics {
     pub ckb_sys_mem_jemalloc: CkbSysMemJemallocStatistics,
     // GaugeVec for CKB tx-pool tx entry status statistics
     pub ckb_tx_pool_entry: CkbTxPoolEntryStatistics,
+    // GaugeVec for CKB tx-pool conflicts count and size statistics
+    pub ckb_tx_pool_conflicts: CkbTxPoolConflictsStatistics,
+    /// Counter for the conflicted transactions evicted by the tx-pool conflicts limits
+    pub ckb_tx_pool_conflicts_evicted: IntCounter,
+    // GaugeVec for the count and size of the witnesses the CKB tx-pool keeps in the side store
+    pub ckb_tx_pool_lazy_witnesses: CkbTxPoolLazyWitnessesStatistics,
+    /// Counter for detached transactions dropped by the tx-pool policy on reorg re-entry, by reason
+    pub ckb_tx_pool_reorg_readd_dropped: IntCounterVec,
+    /// Counter for proposed transactions demoted to pending by the ancestors limit, and promoted back
+    pub ckb_tx_pool_ancestors_demotion: IntCounterVec,
+    // GaugeVec for the count, size and declared cycles of the txs in the CKB tx-pool verify queue
+    pub ckb_tx_pool_verify_queue: CkbTxPoolVerifyQueueStatistics,
+    // GaugeVec for the count, size, cycles, weight and fees of the txs left out of the latest block template
+    pub ckb_tx_pool_template_backlog: CkbTxPoolTemplateBacklogStatistics,
     /// Histogram for CKB network connections
     pub ckb_message_bytes: HistogramVec,
     /// Gauge for CKB rocksdb statistics
     pub ckb_sys_mem_rocksdb: IntGaugeVec,
     /// Counter for CKB network ban peers
     pub ckb_network_ban_peer: IntCounter,
+    /// Counter for CKB network message bytes, by direction and protocol
+    pub ckb_network_protocol_bytes: IntCounterVec,
+    /// Counter for CKB network peer misbehaviors, by kind
+    pub ckb_network_misbehavior: IntCounterVec,
+    /// Gauge for the CKB network reachability, 1 for the current status
+    pub ckb_network_reachability: IntGaugeVec,
     pub ckb_inflight_blocks_count: IntGauge,
     pub ckb_inflight_timeout_count: IntCounter,
 }
@@ -177,6 +245,18 @@ static METRICS: once_cell::sync::Lazy<Metrics> = once_cell::sync::Lazy::new(|| {
             "ckb_chain_load_full_unverified_block",
             "The CKB chain load_full_unverified_block duration (seconds)"
         ).unwrap(),
+    ckb_chain_stale_block_rate: register_gauge_vec!(
+        "ckb_chain_stale_block_rate",
+        "The ratio of the verified blocks which are not in the main chain to the main chain blocks in the recent window",
+        &["window"]
+    )
+            .unwrap(),
+    ckb_chain_uncle_rate: register_gauge_vec!(
+        "ckb_chain_uncle_rate",
+        "The ratio of the uncles to the main chain blocks in the recent window",
+        &["window"]
+    )
+            .unwrap(),
     ckb_sync_msg_process_duration: register_histogram_vec!(
             "ckb_sync_msg_process_duration",
             "The CKB sync message process duration (seconds)",
@@ -251,6 +331,17 @@ static METRICS: once_cell::sync::Lazy<Metrics> = once_cell::sync::Lazy::new(|| {
         "The CKB relay compact block reconstruct fail count"
     )
             .unwrap(),
+    ckb_relay_cb_reconstruct_result: register_int_counter_vec!(
+        "ckb_relay_cb_reconstruct_result",
+        "The CKB relay compact block reconstruct result count",
+        &["result"]
+    )
+            .unwrap(),
+    ckb_relay_cb_prefilled_tx_count: register_int_counter!(
+        "ckb_relay_cb_prefilled_tx_count",
+        "The CKB relay compact block prefilled transaction count, cellbase excluded"
+    )
+            .unwrap(),
     ckb_shared_best_number: register_int_gauge!(
         "ckb_shared_best_number",
         "The CKB shared best header number"
@@ -280,6 +371,55 @@ static METRICS: once_cell::sync::Lazy<Metrics> = once_cell::sync::Lazy::new(|| {
         )
                 .unwrap(),
         ),
+    ckb_tx_pool_conflicts: CkbTxPoolConflictsStatistics::from(
+            &register_int_gauge_vec!(
+            "ckb_tx_pool_conflicts",
+            "CKB tx-pool conflicted transactions count and size statistics",
+            &["type"]
+        )
+                .unwrap(),
+        ),
+    ckb_tx_pool_conflicts_evicted: register_int_counter!(
+        "ckb_tx_pool_conflicts_evicted",
+        "The CKB tx-pool conflicted transactions evicted by the conflicts limits"
+    )
+            .unwrap(),
+    ckb_tx_pool_lazy_witnesses: CkbTxPoolLazyWitnessesStatistics::from(
+            &register_int_gauge_vec!(
+            "ckb_tx_pool_lazy_witnesses",
+            "CKB tx-pool transactions whose witnesses are kept in the side store, count and size statistics",
+            &["type"]
+        )
+                .unwrap(),
+        ),
+    ckb_tx_pool_reorg_readd_dropped: register_int_counter_vec!(
+        "ckb_tx_pool_reorg_readd_dropped",
+        "The CKB tx-pool detached transactions dropped by policy on reorg re-entry",
+        &["reason"]
+    )
+            .unwrap(),
+    ckb_tx_pool_ancestors_demotion: register_int_counter_vec!(
+        "ckb_tx_pool_ancestors_demotion",
+        "The CKB tx-pool proposed transactions demoted to pending by max_ancestors_count, and promoted back",
+        &["type"]
+    )
+            .unwrap(),
+    ckb_tx_pool_verify_queue: CkbTxPoolVerifyQueueStatistics::from(
+            &register_int_gauge_vec!(
+            "ckb_tx_pool_verify_queue",
+            "CKB tx-pool verify queue transactions count, size and declared cycles statistics",
+            &["type"]
+        )
+                .unwrap(),
+        ),
+    ckb_tx_pool_template_backlog: CkbTxPoolTemplateBacklogStatistics::from(
+            &register_int_gauge_vec!(
+            "ckb_tx_pool_template_backlog",
+            "CKB tx-pool transactions left out of the latest block template, by status, count, size, cycles, weight and fees statistics",
+            &["status", "type"]
+        )
+                .unwrap(),
+        ),
     ckb_message_bytes: register_histogram_vec!(
         "ckb_message_bytes",
         "The CKB message bytes",
@@ -300,6 +440,24 @@ static METRICS: once_cell::sync::Lazy<Metrics> = once_cell::sync::Lazy::new(|| {
         "CKB network baned peer count"
     )
             .unwrap(),
+    ckb_network_protocol_bytes: register_int_counter_vec!(
+        "ckb_network_protocol_bytes",
+        "CKB network message bytes sent and received by protocol",
+        &["direction", "protocol_id"]
+    )
+            .unwrap(),
+    ckb_network_misbehavior: register_int_counter_vec!(
+        "ckb_network_misbehavior",
+        "CKB network peer misbehaviors reported by the protocols",
+        &["kind"]
+    )
+            .unwrap(),
+    ckb_network_reachability: register_int_gauge_vec!(
+        "ckb_network_reachability",
+        "CKB network reachability by the connectivity self-test, 1 for the current status",
+        &["status"]
+    )
+            .unwrap(),
     ckb_inflight_blocks_count: register_int_gauge!(
             "ckb_inflight_blocks_count",
             "The CKB inflight blocks count"
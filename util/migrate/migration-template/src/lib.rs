@@ -7,6 +7,9 @@ use quote::quote;
 use syn::parse_macro_input;
 
 /// multi thread migration template
+///
+/// The migration has a `threads: Option<usize>` field, which overrides how many threads the
+/// migration uses.
 #[proc_macro]
 pub fn multi_thread_migration(input: TokenStream) -> TokenStream {
     let block_expr = parse_macro_input!(input as syn::ExprBlock);
@@ -19,8 +22,10 @@ pub fn multi_thread_migration(input: TokenStream) -> TokenStream {
         let tip = chain_db.get_tip_header().expect("db tip header index");
         let tip_number = tip.number();
 
-        let tb_num = std::cmp::max(MIN_THREAD, num_cpus::get() as u64);
-        let tb_num = std::cmp::min(tb_num, MAX_THREAD);
+        let tb_num = match self.threads {
+            Some(threads) => threads.max(1) as u64,
+            None => std::cmp::min(std::cmp::max(MIN_THREAD, num_cpus::get() as u64), MAX_THREAD),
+        };
         let chunk_size = tip_number / tb_num;
         let remainder = tip_number % tb_num;
         let _barrier = ::std::sync::Arc::new(::std::sync::Barrier::new(tb_num as usize));
@@ -3,42 +3,68 @@
 use crate::migrations;
 use ckb_db::{ReadOnlyDB, RocksDB};
 use ckb_db_migration::{DefaultMigration, Migrations};
-use ckb_db_schema::{COLUMNS, COLUMN_META};
-use ckb_error::Error;
+use ckb_db_schema::{Col, COLUMNS, COLUMN_META};
+use ckb_error::{Error, InternalErrorKind};
 use ckb_types::core::hardfork::HardForks;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+pub use ckb_db_migration::{BackgroundMigrationOutcome, MigrationInfo, MigrationRecord};
+
 const INIT_DB_VERSION: &str = "20191127135521";
 
 /// migrate helper
 pub struct Migrate {
     migrations: Migrations,
     path: PathBuf,
+    column_paths: HashMap<Col, PathBuf>,
 }
 
 impl Migrate {
     /// Construct new migrate
     pub fn new<P: Into<PathBuf>>(path: P, hardforks: HardForks) -> Self {
+        Self::with_threads(path, hardforks, None)
+    }
+
+    /// Construct new migrate, `threads` overrides how many threads a multi-thread migration uses,
+    /// and how many independent migrations are run concurrently.
+    pub fn with_threads<P: Into<PathBuf>>(
+        path: P,
+        hardforks: HardForks,
+        threads: Option<usize>,
+    ) -> Self {
         let mut migrations = Migrations::default();
+        if let Some(threads) = threads {
+            migrations.set_threads(threads);
+        }
         migrations.add_migration(Arc::new(DefaultMigration::new(INIT_DB_VERSION)));
         migrations.add_migration(Arc::new(migrations::ChangeMoleculeTableToStruct)); // since v0.35.0
-        migrations.add_migration(Arc::new(migrations::CellMigration)); // since v0.37.0
-        migrations.add_migration(Arc::new(migrations::AddNumberHashMapping)); // since v0.40.0
+        migrations.add_migration(Arc::new(migrations::CellMigration::new(threads))); // since v0.37.0
+        migrations.add_migration(Arc::new(migrations::AddNumberHashMapping::new(threads))); // since v0.40.0
         migrations.add_migration(Arc::new(migrations::AddExtraDataHash)); // since v0.43.0
         migrations.add_migration(Arc::new(migrations::AddBlockExtensionColumnFamily)); // since v0.100.0
         migrations.add_migration(Arc::new(migrations::AddChainRootMMR)); // TODO(light-client) update the comment: which version?
         migrations.add_migration(Arc::new(migrations::AddBlockFilterColumnFamily)); // since v0.105.0
         migrations.add_migration(Arc::new(migrations::AddBlockFilterHash)); // since v0.108.0
         migrations.add_migration(Arc::new(migrations::BlockExt2019ToZero::new(hardforks))); // since v0.111.1
+        migrations.add_migration(Arc::new(migrations::AddEpochStats)); // since v0.118.0
 
         Migrate {
             migrations,
             path: path.into(),
+            column_paths: HashMap::new(),
         }
     }
 
+    /// Places the columns in the directories when the database is opened for bulk loading, see
+    /// `db.column_paths`.
+    pub fn column_paths(mut self, column_paths: HashMap<Col, PathBuf>) -> Self {
+        self.column_paths = column_paths;
+        self
+    }
+
     /// Open read only db
     pub fn open_read_only_db(&self) -> Result<Option<ReadOnlyDB>, Error> {
         // open cf meta column for empty check
@@ -67,9 +93,29 @@ impl Migrate {
         self.migrations.can_run_in_background(db)
     }
 
+    /// Returns the applied migrations recorded in the database history log.
+    pub fn history(&self, db: &ReadOnlyDB) -> Vec<MigrationRecord> {
+        self.migrations.history(db)
+    }
+
+    /// Returns the versions of the migrations which are not applied yet.
+    pub fn pending(&self, db: &ReadOnlyDB) -> Vec<String> {
+        self.migrations.pending(db)
+    }
+
+    /// Returns the details of the migrations which are not applied yet, without executing them.
+    pub fn pending_migrations(&self, db: &ReadOnlyDB) -> Vec<MigrationInfo> {
+        self.migrations.pending_migrations(db)
+    }
+
     /// Open bulk load db.
     pub fn open_bulk_load_db(&self) -> Result<Option<RocksDB>, Error> {
-        RocksDB::prepare_for_bulk_load_open(&self.path, COLUMNS)
+        RocksDB::prepare_for_bulk_load_open(&self.path, COLUMNS, &self.column_paths)
+    }
+
+    /// Returns the outcome of the background migrations started by [`migrate`](Self::migrate).
+    pub fn background_outcome(&self) -> BackgroundMigrationOutcome {
+        self.migrations.background_outcome()
     }
 
     /// Perform migrate.
@@ -77,6 +123,21 @@ impl Migrate {
         self.migrations.migrate(db, run_in_background)
     }
 
+    /// Perform all the pending migrations, including the background ones, in the foreground.
+    ///
+    /// It is meant for a standalone copy of the database, no node services are started, so the
+    /// copy can be migrated while the node keeps serving the original one, see also
+    /// [`with_threads`](Self::with_threads).
+    pub fn migrate_offline(self) -> Result<RocksDB, Error> {
+        let db = self.open_bulk_load_db()?.ok_or_else(|| {
+            InternalErrorKind::Database.other(format!(
+                "the database {} does not exist",
+                self.path.display()
+            ))
+        })?;
+        self.migrate(db, false)
+    }
+
     /// Perform init_db_version.
     pub fn init_db_version(self, db: &RocksDB) -> Result<(), Error> {
         self.migrations.init_db_version(db)
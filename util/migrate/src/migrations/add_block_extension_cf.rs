@@ -1,5 +1,6 @@
 use ckb_db::{Result, RocksDB};
 use ckb_db_migration::{Migration, ProgressBar};
+use ckb_db_schema::{Col, COLUMN_BLOCK_EXTENSION};
 use std::sync::Arc;
 
 pub struct AddBlockExtensionColumnFamily;
@@ -19,6 +20,14 @@ impl Migration for AddBlockExtensionColumnFamily {
         VERSION
     }
 
+    fn columns(&self) -> &[Col] {
+        &[COLUMN_BLOCK_EXTENSION]
+    }
+
+    fn description(&self) -> &str {
+        "Add the column family of the block extensions"
+    }
+
     fn expensive(&self) -> bool {
         false
     }
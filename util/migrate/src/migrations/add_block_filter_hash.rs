@@ -1,7 +1,7 @@
 use ckb_app_config::StoreConfig;
 use ckb_db::RocksDB;
 use ckb_db_migration::{Migration, ProgressBar, ProgressStyle};
-use ckb_db_schema::COLUMN_BLOCK_FILTER_HASH;
+use ckb_db_schema::{Col, COLUMN_BLOCK_FILTER_HASH};
 use ckb_error::Error;
 use ckb_hash::blake2b_256;
 use ckb_store::{ChainDB, ChainStore};
@@ -92,6 +92,14 @@ impl Migration for AddBlockFilterHash {
         VERSION
     }
 
+    fn columns(&self) -> &[Col] {
+        &[COLUMN_BLOCK_FILTER_HASH]
+    }
+
+    fn description(&self) -> &str {
+        "Calculate the hashes of the block filters"
+    }
+
     fn expensive(&self) -> bool {
         true
     }
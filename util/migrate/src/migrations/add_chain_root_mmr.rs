@@ -1,6 +1,7 @@
 use ckb_app_config::StoreConfig;
 use ckb_db::{Result, RocksDB};
 use ckb_db_migration::{Migration, ProgressBar, ProgressStyle};
+use ckb_db_schema::{Col, COLUMN_CHAIN_ROOT_MMR};
 use ckb_error::InternalErrorKind;
 use ckb_store::{ChainDB, ChainStore};
 use ckb_types::utilities::merkle_mountain_range::ChainRootMMR;
@@ -82,4 +83,12 @@ impl Migration for AddChainRootMMR {
     fn version(&self) -> &str {
         VERSION
     }
+
+    fn columns(&self) -> &[Col] {
+        &[COLUMN_CHAIN_ROOT_MMR]
+    }
+
+    fn description(&self) -> &str {
+        "Build the chain root MMR of the block headers"
+    }
 }
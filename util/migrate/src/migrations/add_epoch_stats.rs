@@ -0,0 +1,112 @@
+use ckb_app_config::StoreConfig;
+use ckb_db::RocksDB;
+use ckb_db_migration::{Migration, ProgressBar, ProgressStyle};
+use ckb_db_schema::{Col, COLUMN_BLOCK_EXT, COLUMN_EPOCH_STATS};
+use ckb_error::Error;
+use ckb_store::{ChainDB, ChainStore};
+use ckb_types::core::{Capacity, EpochStats};
+use std::sync::Arc;
+
+pub struct AddEpochStats;
+
+const VERSION: &str = "20240601000000";
+
+impl Migration for AddEpochStats {
+    fn migrate(
+        &self,
+        db: RocksDB,
+        pb: Arc<dyn Fn(u64) -> ProgressBar + Send + Sync>,
+    ) -> Result<RocksDB, Error> {
+        let chain_db = ChainDB::new(db, StoreConfig::default());
+        let tip_number = match chain_db.get_tip_header() {
+            Some(tip) => tip.number(),
+            None => return Ok(chain_db.into_inner()),
+        };
+
+        let pb = ::std::sync::Arc::clone(&pb);
+        let pbi = pb(tip_number + 1);
+        pbi.set_style(
+                ProgressStyle::default_bar()
+                    .template(
+                        "{prefix:.bold.dim} {spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}",
+                    )
+                    .progress_chars("#>-"),
+            );
+        pbi.set_position(0);
+        pbi.enable_steady_tick(5000);
+
+        let mut block_number = 0;
+        let mut epoch = 0;
+        let mut stats = EpochStats::default();
+        loop {
+            let db_txn = chain_db.begin_transaction();
+            for _ in 0..10000 {
+                if block_number > tip_number {
+                    break;
+                }
+                let block_hash = chain_db.get_block_hash(block_number).expect("index stored");
+                let header = chain_db
+                    .get_block_header(&block_hash)
+                    .expect("header stored");
+                if header.epoch().number() != epoch {
+                    db_txn.insert_epoch_stats(epoch, &stats)?;
+                    epoch = header.epoch().number();
+                    stats = EpochStats::default();
+                }
+
+                // the bodies of the frozen blocks are not in the db, they have at least a cellbase
+                let txs_count = chain_db
+                    .get_block_txs_hashes(&block_hash)
+                    .len()
+                    .checked_sub(1);
+                let ext = chain_db.get_block_ext(&block_hash).unwrap_or_default();
+                let fees = ext
+                    .txs_fees
+                    .iter()
+                    .fold(0u64, |acc, fee| acc.saturating_add(fee.as_u64()));
+                let cycles = ext
+                    .cycles
+                    .unwrap_or_default()
+                    .into_iter()
+                    .fold(0u64, u64::saturating_add);
+
+                stats.block_count += 1;
+                stats.tx_count = stats
+                    .tx_count
+                    .zip(txs_count)
+                    .map(|(count, txs_count)| count + txs_count as u64);
+                stats.total_fees =
+                    Capacity::shannons(stats.total_fees.as_u64().saturating_add(fees));
+                stats.total_cycles = stats.total_cycles.saturating_add(cycles);
+
+                pbi.inc(1);
+                block_number += 1;
+            }
+            // the stats of the last epoch may be partial here, it's overwritten in the next round
+            db_txn.insert_epoch_stats(epoch, &stats)?;
+            db_txn.commit()?;
+
+            if block_number > tip_number {
+                break;
+            }
+        }
+        Ok(chain_db.into_inner())
+    }
+
+    fn version(&self) -> &str {
+        VERSION
+    }
+
+    fn description(&self) -> &str {
+        "Aggregate the statistics of the epochs"
+    }
+
+    fn expensive(&self) -> bool {
+        true
+    }
+
+    // the cycles are read from the block exts, which `BlockExt2019ToZero` rewrites
+    fn columns(&self) -> &[Col] {
+        &[COLUMN_EPOCH_STATS, COLUMN_BLOCK_EXT]
+    }
+}
@@ -1,6 +1,6 @@
 use ckb_db::{Direction, IteratorMode, Result, RocksDB};
 use ckb_db_migration::{Migration, ProgressBar, ProgressStyle};
-use ckb_db_schema::{COLUMN_CELL_DATA, COLUMN_CELL_DATA_HASH};
+use ckb_db_schema::{Col, COLUMN_CELL_DATA, COLUMN_CELL_DATA_HASH};
 use ckb_types::{packed, prelude::*};
 use std::sync::Arc;
 
@@ -64,4 +64,12 @@ impl Migration for AddExtraDataHash {
     fn version(&self) -> &str {
         VERSION
     }
+
+    fn columns(&self) -> &[Col] {
+        &[COLUMN_CELL_DATA_HASH]
+    }
+
+    fn description(&self) -> &str {
+        "Add the hashes of the cell data"
+    }
 }
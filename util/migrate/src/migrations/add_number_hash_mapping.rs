@@ -1,13 +1,21 @@
 use ckb_app_config::StoreConfig;
 use ckb_db::{Direction, IteratorMode, Result, RocksDB};
 use ckb_db_migration::{Migration, ProgressBar, ProgressStyle};
-use ckb_db_schema::{COLUMN_BLOCK_BODY, COLUMN_INDEX, COLUMN_NUMBER_HASH};
+use ckb_db_schema::{Col, COLUMN_BLOCK_BODY, COLUMN_INDEX, COLUMN_NUMBER_HASH};
 use ckb_migration_template::multi_thread_migration;
 use ckb_store::{ChainDB, ChainStore};
 use ckb_types::{molecule::io::Write, packed, prelude::*};
 use std::sync::Arc;
 
-pub struct AddNumberHashMapping;
+pub struct AddNumberHashMapping {
+    threads: Option<usize>,
+}
+
+impl AddNumberHashMapping {
+    pub fn new(threads: Option<usize>) -> Self {
+        AddNumberHashMapping { threads }
+    }
+}
 
 const VERSION: &str = "20200710181855";
 
@@ -56,4 +64,12 @@ impl Migration for AddNumberHashMapping {
     fn version(&self) -> &str {
         VERSION
     }
+
+    fn columns(&self) -> &[Col] {
+        &[COLUMN_NUMBER_HASH]
+    }
+
+    fn description(&self) -> &str {
+        "Add the mapping from the block numbers to the block hashes"
+    }
 }
@@ -15,7 +15,15 @@ use std::sync::Arc;
 const RESTORE_CELL_VERSION: &str = "20200707214700";
 const MAX_DELETE_BATCH_SIZE: usize = 32 * 1024;
 
-pub struct CellMigration;
+pub struct CellMigration {
+    threads: Option<usize>,
+}
+
+impl CellMigration {
+    pub fn new(threads: Option<usize>) -> Self {
+        CellMigration { threads }
+    }
+}
 
 impl Migration for CellMigration {
     fn migrate(
@@ -72,6 +80,10 @@ impl Migration for CellMigration {
     fn version(&self) -> &str {
         RESTORE_CELL_VERSION
     }
+
+    fn description(&self) -> &str {
+        "Rebuild the live cells"
+    }
 }
 
 // https://github.com/facebook/rocksdb/issues/1295
@@ -2,6 +2,7 @@ mod add_block_extension_cf;
 mod add_block_filter;
 mod add_block_filter_hash;
 mod add_chain_root_mmr;
+mod add_epoch_stats;
 mod add_extra_data_hash;
 mod add_number_hash_mapping;
 mod cell;
@@ -12,6 +13,7 @@ pub use add_block_extension_cf::AddBlockExtensionColumnFamily;
 pub use add_block_filter::AddBlockFilterColumnFamily;
 pub use add_block_filter_hash::AddBlockFilterHash;
 pub use add_chain_root_mmr::AddChainRootMMR;
+pub use add_epoch_stats::AddEpochStats;
 pub use add_extra_data_hash::AddExtraDataHash;
 pub use add_number_hash_mapping::AddNumberHashMapping;
 pub use cell::CellMigration;
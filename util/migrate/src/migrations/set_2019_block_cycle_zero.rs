@@ -1,6 +1,6 @@
 use ckb_app_config::StoreConfig;
-use ckb_db_migration::{Migration, ProgressBar, ProgressStyle};
-use ckb_db_schema::COLUMN_EPOCH;
+use ckb_db_migration::{migration_progress_key, Migration, ProgressBar, ProgressStyle};
+use ckb_db_schema::{COLUMN_EPOCH, COLUMN_META};
 use ckb_error::InternalErrorKind;
 use ckb_store::{ChainDB, ChainStore};
 use ckb_types::{
@@ -25,6 +25,10 @@ impl Migration for BlockExt2019ToZero {
         true
     }
 
+    fn can_resume(&self) -> bool {
+        true
+    }
+
     fn migrate(
         &self,
         db: ckb_db::RocksDB,
@@ -67,6 +71,15 @@ impl Migration for BlockExt2019ToZero {
         if let Some(mut header) = header {
             let pb = ::std::sync::Arc::clone(&pb);
             let pbi = pb(header.number() + 1);
+            let start_number = header.number();
+            // the blocks are processed from the top down, the progress is the hash of the next
+            // block to process
+            if let Some(progress) = self.load_progress(chain_db.db())? {
+                let hash = packed::Byte32Reader::from_slice_should_be_ok(&progress).to_entity();
+                header = chain_db
+                    .get_block_header(&hash)
+                    .expect("db must have header");
+            }
             pbi.set_style(
                         ProgressStyle::default_bar()
                             .template(
@@ -74,9 +87,10 @@ impl Migration for BlockExt2019ToZero {
                             )
                             .progress_chars("#>-"),
                     );
-            pbi.set_position(0);
+            pbi.set_position(start_number.saturating_sub(header.number()));
             pbi.enable_steady_tick(5000);
 
+            let progress_key = migration_progress_key(VERSION);
             loop {
                 let db_txn = chain_db.begin_transaction();
                 if self.stop_background() {
@@ -98,6 +112,7 @@ impl Migration for BlockExt2019ToZero {
 
                     pbi.inc(1);
                 }
+                db_txn.insert_raw(COLUMN_META, &progress_key, header.hash().as_slice())?;
                 db_txn.commit()?;
 
                 if header.is_genesis() {
@@ -111,4 +126,8 @@ impl Migration for BlockExt2019ToZero {
     fn version(&self) -> &str {
         VERSION
     }
+
+    fn description(&self) -> &str {
+        "Reset the cycles of the blocks before the 2021 hard fork to zero"
+    }
 }
@@ -182,4 +182,8 @@ impl Migration for ChangeMoleculeTableToStruct {
     fn version(&self) -> &str {
         VERSION
     }
+
+    fn description(&self) -> &str {
+        "Change the stored molecule tables of the headers and epochs to structs"
+    }
 }
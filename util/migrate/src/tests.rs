@@ -163,3 +163,13 @@ fn test_mock_migration() {
 
     assert_eq!(mg2.check(&rdb, true), std::cmp::Ordering::Equal)
 }
+
+#[test]
+fn test_migrate_offline_requires_database() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("test_migrate_offline_requires_database")
+        .tempdir()
+        .unwrap();
+    let mg = Migrate::new(tmp_dir.as_ref().join("db"), HardForks::new_mirana());
+    assert!(mg.migrate_offline().is_err());
+}
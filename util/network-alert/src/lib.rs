@@ -8,6 +8,7 @@
 //! Network Alert will be removed soon once the CKB network is considered mature.
 //!
 pub mod alert_relayer;
+pub mod local_rules;
 pub mod notifier;
 #[cfg(test)]
 mod tests;
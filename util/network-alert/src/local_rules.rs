@@ -0,0 +1,221 @@
+//! Operator-defined local alert rules.
+//!
+//! The local alerts share the notifier with the network alerts, so they show up in the alert RPC
+//! and subscription, but they are never relayed to the peers.
+use ckb_app_config::AlertRulesConfig;
+use ckb_types::{core::service::MigrationOutcome, packed, prelude::*};
+use std::collections::HashMap;
+
+/// The ids of the local alerts are reserved at the top of the id space, so they never collide with
+/// the network alerts.
+pub const LOCAL_ALERT_ID_START: u32 = 0xFFFF_FF00;
+// The priority of the local alerts, the network alerts usually have higher ones
+const LOCAL_ALERT_PRIORITY: u32 = 1;
+// A deep reorganization is an event, the alert stays active for an hour
+const DEEP_REORG_ALERT_MILLIS: u64 = 60 * 60 * 1000;
+/// The alert id of the background migrations outcome, after the ids of the rules.
+pub const MIGRATION_ALERT_ID: u32 = LOCAL_ALERT_ID_START + 0x80;
+/// How long the alert of the background migrations outcome stays active.
+pub const MIGRATION_ALERT_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// A local alert rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LocalRule {
+    /// The tip block is too old.
+    TipStalled,
+    /// Too few peers are connected.
+    LowPeers,
+    /// The tx-pool is almost full.
+    PoolFull,
+    /// A reorganization detached too many blocks.
+    DeepReorg,
+}
+
+impl LocalRule {
+    /// All the rules.
+    pub const ALL: [LocalRule; 4] = [
+        LocalRule::TipStalled,
+        LocalRule::LowPeers,
+        LocalRule::PoolFull,
+        LocalRule::DeepReorg,
+    ];
+
+    /// The alert id of the rule.
+    pub fn alert_id(self) -> u32 {
+        LOCAL_ALERT_ID_START + self as u32
+    }
+
+    /// The rule name, which is the same as the config option.
+    pub fn name(self) -> &'static str {
+        match self {
+            LocalRule::TipStalled => "tip_stalled_minutes",
+            LocalRule::LowPeers => "min_peers",
+            LocalRule::PoolFull => "pool_full_percent",
+            LocalRule::DeepReorg => "max_reorg_depth",
+        }
+    }
+}
+
+/// The node status sampled for the rules.
+#[derive(Clone, Debug, Default)]
+pub struct NodeStatus {
+    /// The timestamp of the tip block in milliseconds.
+    pub tip_timestamp: u64,
+    /// The number of the connected peers.
+    pub peers: usize,
+    /// The total size of the transactions in the tx-pool.
+    pub pool_size: u64,
+    /// The config option `tx_pool.max_tx_pool_size`.
+    pub max_pool_size: u64,
+    /// The number of the blocks detached by the reorganizations since the last evaluation.
+    pub reorg_depth: u64,
+}
+
+/// A change of the local alerts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocalAlertEvent {
+    /// The rule is violated.
+    Raised {
+        /// The violated rule.
+        rule: LocalRule,
+        /// The alert message.
+        message: String,
+    },
+    /// The rule is satisfied again.
+    Resolved {
+        /// The rule.
+        rule: LocalRule,
+    },
+}
+
+/// Evaluates the local alert rules.
+pub struct LocalAlertRules {
+    config: AlertRulesConfig,
+    // the active alerts and their messages
+    active: HashMap<LocalRule, String>,
+    // the time and the depth of the last deep reorganization
+    last_deep_reorg: Option<(u64, u64)>,
+}
+
+impl LocalAlertRules {
+    /// Init
+    pub fn new(config: AlertRulesConfig) -> Self {
+        LocalAlertRules {
+            config,
+            active: HashMap::new(),
+            last_deep_reorg: None,
+        }
+    }
+
+    /// Evaluates the rules with the sampled status, returns the alerts raised and resolved since
+    /// the last evaluation.
+    pub fn evaluate(&mut self, status: &NodeStatus, now: u64) -> Vec<LocalAlertEvent> {
+        if let Some(max_depth) = self.config.max_reorg_depth {
+            if status.reorg_depth > max_depth {
+                self.last_deep_reorg = Some((now, status.reorg_depth));
+            }
+        }
+
+        let mut events = Vec::new();
+        for rule in LocalRule::ALL {
+            match self.violation(rule, status, now) {
+                Some(message) => {
+                    // an active alert only updates its message
+                    if self.active.insert(rule, message.clone()).is_none() {
+                        events.push(LocalAlertEvent::Raised { rule, message });
+                    }
+                }
+                None => {
+                    if self.active.remove(&rule).is_some() {
+                        events.push(LocalAlertEvent::Resolved { rule });
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    /// The active alerts, built with the expiration time `notice_until`.
+    pub fn active_alerts(&self, notice_until: u64) -> Vec<packed::Alert> {
+        LocalRule::ALL
+            .iter()
+            .filter_map(|rule| {
+                self.active
+                    .get(rule)
+                    .map(|message| local_alert(*rule, message, notice_until))
+            })
+            .collect()
+    }
+
+    fn violation(&self, rule: LocalRule, status: &NodeStatus, now: u64) -> Option<String> {
+        match rule {
+            LocalRule::TipStalled => {
+                let minutes = self.config.tip_stalled_minutes?;
+                let stalled = now.saturating_sub(status.tip_timestamp) / 60_000;
+                (stalled >= minutes)
+                    .then(|| format!("The tip block has stalled for {stalled} minutes"))
+            }
+            LocalRule::LowPeers => {
+                let min_peers = self.config.min_peers?;
+                (status.peers < min_peers as usize).then(|| {
+                    format!(
+                        "Only {} peers are connected, fewer than {}",
+                        status.peers, min_peers
+                    )
+                })
+            }
+            LocalRule::PoolFull => {
+                let percent = u64::from(self.config.pool_full_percent?);
+                (status.max_pool_size > 0
+                    && status.pool_size.saturating_mul(100)
+                        >= status.max_pool_size.saturating_mul(percent))
+                .then(|| {
+                    format!(
+                        "The tx-pool size {} reaches {}% of the limit {}",
+                        status.pool_size, percent, status.max_pool_size
+                    )
+                })
+            }
+            LocalRule::DeepReorg => {
+                let max_depth = self.config.max_reorg_depth?;
+                let (at, depth) = self.last_deep_reorg?;
+                (now.saturating_sub(at) < DEEP_REORG_ALERT_MILLIS).then(|| {
+                    format!("A chain reorganization detached {depth} blocks, more than {max_depth}")
+                })
+            }
+        }
+    }
+}
+
+/// Builds the local alert of the rule, which is unsigned and must not be relayed.
+pub fn local_alert(rule: LocalRule, message: &str, notice_until: u64) -> packed::Alert {
+    build_local_alert(rule.alert_id(), message, notice_until)
+}
+
+/// Builds the local alert reporting the outcome of the background migrations.
+pub fn migration_alert(outcome: &MigrationOutcome, notice_until: u64) -> packed::Alert {
+    let message = match (&outcome.failed_version, &outcome.error) {
+        (Some(failed_version), error) => format!(
+            "Background migration {} failed after {} ms, the database version stays at {}: {}",
+            failed_version,
+            outcome.duration_ms,
+            outcome.version,
+            error.as_deref().unwrap_or("unknown error")
+        ),
+        (None, _) => format!(
+            "Background migrations finished in {} ms, the database version is {}",
+            outcome.duration_ms, outcome.version
+        ),
+    };
+    build_local_alert(MIGRATION_ALERT_ID, &message, notice_until)
+}
+
+fn build_local_alert(id: u32, message: &str, notice_until: u64) -> packed::Alert {
+    let raw = packed::RawAlert::new_builder()
+        .id(id.pack())
+        .priority(LOCAL_ALERT_PRIORITY.pack())
+        .notice_until(notice_until.pack())
+        .message(format!("[local] {message}").pack())
+        .build();
+    packed::Alert::new_builder().raw(raw).build()
+}
@@ -121,6 +121,39 @@ impl Notifier {
         });
     }
 
+    /// Add or refresh a local alert, returns whether it is newly noticed.
+    ///
+    /// The local alerts are raised by the local alert rules, they are only noticed but never
+    /// received, so they are not relayed to the peers.
+    pub fn raise_local(&mut self, alert: &Alert) -> bool {
+        let alert_id: u32 = alert.raw().id().unpack();
+        if let Some(noticed) = self
+            .noticed_alerts
+            .iter_mut()
+            .find(|a| Unpack::<u32>::unpack(&a.raw().id()) == alert_id)
+        {
+            // refresh the expiration time or the message silently
+            *noticed = alert.clone();
+            return false;
+        }
+        self.notify_controller.notify_network_alert(alert.clone());
+        self.noticed_alerts.push(alert.clone());
+        // sort by priority
+        self.noticed_alerts.sort_by_key(|a| {
+            let priority: u32 = a.raw().priority().unpack();
+            std::u32::MAX - priority
+        });
+        true
+    }
+
+    /// Remove a local alert
+    pub fn resolve_local(&mut self, alert_id: u32) {
+        self.noticed_alerts.retain(|a| {
+            let id: u32 = a.raw().id().unpack();
+            id != alert_id
+        });
+    }
+
     /// Cancel alert id
     pub fn cancel(&mut self, cancel_id: u32) {
         self.cancel_filter.put(cancel_id, ());
@@ -1,3 +1,4 @@
 mod generate_alert_signature;
+mod test_local_rules;
 mod test_notifier;
 mod test_verifier;
@@ -0,0 +1,191 @@
+use super::test_notifier::new_notifier;
+use crate::local_rules::{
+    local_alert, migration_alert, LocalAlertEvent, LocalAlertRules, LocalRule, NodeStatus,
+    LOCAL_ALERT_ID_START, MIGRATION_ALERT_ID,
+};
+use ckb_app_config::AlertRulesConfig;
+use ckb_types::{core::service::MigrationOutcome, prelude::*};
+
+const MINUTE: u64 = 60 * 1000;
+
+fn healthy_status(now: u64) -> NodeStatus {
+    NodeStatus {
+        tip_timestamp: now,
+        peers: 8,
+        pool_size: 0,
+        max_pool_size: 1_000,
+        reorg_depth: 0,
+    }
+}
+
+fn raised(events: &[LocalAlertEvent]) -> Vec<LocalRule> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            LocalAlertEvent::Raised { rule, .. } => Some(*rule),
+            LocalAlertEvent::Resolved { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_disabled_rules() {
+    let mut rules = LocalAlertRules::new(AlertRulesConfig::default());
+    let status = NodeStatus {
+        tip_timestamp: 0,
+        peers: 0,
+        pool_size: 1_000,
+        max_pool_size: 1_000,
+        reorg_depth: 100,
+    };
+    assert!(rules.evaluate(&status, 100 * MINUTE).is_empty());
+}
+
+#[test]
+fn test_raise_and_resolve() {
+    let config = AlertRulesConfig {
+        tip_stalled_minutes: Some(30),
+        min_peers: Some(4),
+        pool_full_percent: Some(90),
+        ..Default::default()
+    };
+    let mut rules = LocalAlertRules::new(config);
+    let now = 100 * MINUTE;
+    assert!(rules.evaluate(&healthy_status(now), now).is_empty());
+
+    let status = NodeStatus {
+        tip_timestamp: now - 30 * MINUTE,
+        peers: 3,
+        pool_size: 900,
+        ..healthy_status(now)
+    };
+    assert_eq!(
+        raised(&rules.evaluate(&status, now)),
+        vec![
+            LocalRule::TipStalled,
+            LocalRule::LowPeers,
+            LocalRule::PoolFull
+        ]
+    );
+    assert_eq!(rules.active_alerts(now).len(), 3);
+
+    // the active alerts are not raised again
+    let later = NodeStatus {
+        tip_timestamp: now - 30 * MINUTE,
+        ..status
+    };
+    assert!(rules.evaluate(&later, now + MINUTE).is_empty());
+
+    let events = rules.evaluate(&healthy_status(now), now + 2 * MINUTE);
+    assert_eq!(
+        events,
+        vec![
+            LocalAlertEvent::Resolved {
+                rule: LocalRule::TipStalled
+            },
+            LocalAlertEvent::Resolved {
+                rule: LocalRule::LowPeers
+            },
+            LocalAlertEvent::Resolved {
+                rule: LocalRule::PoolFull
+            },
+        ]
+    );
+    assert!(rules.active_alerts(now).is_empty());
+}
+
+#[test]
+fn test_deep_reorg_alert_lasts_an_hour() {
+    let config = AlertRulesConfig {
+        max_reorg_depth: Some(3),
+        ..Default::default()
+    };
+    let mut rules = LocalAlertRules::new(config);
+    let now = 100 * MINUTE;
+
+    let shallow = NodeStatus {
+        reorg_depth: 3,
+        ..healthy_status(now)
+    };
+    assert!(rules.evaluate(&shallow, now).is_empty());
+
+    let deep = NodeStatus {
+        reorg_depth: 4,
+        ..healthy_status(now)
+    };
+    assert_eq!(
+        raised(&rules.evaluate(&deep, now)),
+        vec![LocalRule::DeepReorg]
+    );
+    assert!(rules
+        .evaluate(&healthy_status(now), now + 59 * MINUTE)
+        .is_empty());
+    assert_eq!(
+        rules.evaluate(&healthy_status(now), now + 60 * MINUTE),
+        vec![LocalAlertEvent::Resolved {
+            rule: LocalRule::DeepReorg
+        }]
+    );
+}
+
+#[test]
+fn test_notifier_local_alerts() {
+    let mut notifier = new_notifier("0.1.0");
+    let alert = local_alert(LocalRule::LowPeers, "too few peers", 1_000);
+    assert_eq!(
+        Unpack::<u32>::unpack(&alert.raw().id()),
+        LOCAL_ALERT_ID_START + 1
+    );
+
+    assert!(notifier.raise_local(&alert));
+    // refreshing doesn't notify again
+    let refreshed = local_alert(LocalRule::LowPeers, "too few peers", 2_000);
+    assert!(!notifier.raise_local(&refreshed));
+    assert_eq!(notifier.noticed_alerts(), vec![refreshed]);
+    // the local alerts are not relayed
+    assert!(notifier.received_alerts().is_empty());
+
+    notifier.clear_expired_alerts(1_500);
+    assert_eq!(notifier.noticed_alerts().len(), 1);
+
+    notifier.resolve_local(LocalRule::LowPeers.alert_id());
+    assert!(notifier.noticed_alerts().is_empty());
+    // the alert can be raised again after resolved
+    assert!(notifier.raise_local(&alert));
+}
+
+#[test]
+fn test_migration_alert() {
+    let mut outcome = MigrationOutcome {
+        version: "20230101000000".to_string(),
+        duration_ms: 1_500,
+        failed_version: None,
+        error: None,
+    };
+    let alert = migration_alert(&outcome, 1_000);
+    assert_eq!(Unpack::<u32>::unpack(&alert.raw().id()), MIGRATION_ALERT_ID);
+    assert!(!LocalRule::ALL
+        .iter()
+        .any(|rule| rule.alert_id() == MIGRATION_ALERT_ID));
+    let message: String = alert
+        .raw()
+        .message()
+        .as_reader()
+        .as_utf8()
+        .unwrap()
+        .to_owned();
+    assert!(message.starts_with("[local] Background migrations finished"));
+
+    outcome.failed_version = Some("20240101000000".to_string());
+    outcome.error = Some("disk full".to_string());
+    let alert = migration_alert(&outcome, 1_000);
+    let message: String = alert
+        .raw()
+        .message()
+        .as_reader()
+        .as_utf8()
+        .unwrap()
+        .to_owned();
+    assert!(message.contains("20240101000000 failed"));
+    assert!(message.ends_with("disk full"));
+}
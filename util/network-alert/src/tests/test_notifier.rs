@@ -22,7 +22,7 @@ fn build_alert(
     packed::Alert::new_builder().raw(raw).build()
 }
 
-fn new_notifier(version: &str) -> Notifier {
+pub(crate) fn new_notifier(version: &str) -> Notifier {
     thread_local! {
         // NOTICE：we can't put the runtime directly into thread_local here,
         // on windows the runtime in thread_local will get stuck when dropping
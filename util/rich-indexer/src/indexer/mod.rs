@@ -132,7 +132,7 @@ impl AsyncRichIndexer {
             .transaction()
             .await
             .map_err(|err| Error::DB(err.to_string()))?;
-        if self.custom_filters.is_block_filter_match(block) {
+        if self.custom_filters.is_block_filter_match(block)? {
             let block_id = append_block(block, &mut tx).await?;
             self.insert_transactions(block_id, block, &mut tx).await?;
         } else {
@@ -190,7 +190,7 @@ impl AsyncRichIndexer {
         for (output_index, (cell, data)) in tx_view.outputs_with_data_iter().enumerate() {
             if self
                 .custom_filters
-                .is_cell_filter_match(&cell, &data.pack())
+                .is_cell_filter_match(&cell, &data.pack())?
             {
                 build_output_cell_rows(&cell, output_index, &data, &mut output_cell_rows);
                 build_script_set(&cell, &mut script_set).await;
@@ -207,7 +207,7 @@ impl AsyncRichIndexer {
                     {
                         if self
                             .custom_filters
-                            .is_cell_filter_match(&output, &output_data.pack())
+                            .is_cell_filter_match(&output, &output_data.pack())?
                         {
                             build_input_rows(output_id, &input, input_index, &mut input_rows);
                             is_tx_matched = true;
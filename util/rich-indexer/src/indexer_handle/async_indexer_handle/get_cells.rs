@@ -4,6 +4,7 @@ use crate::indexer::to_fixed_array;
 use crate::store::SQLXPool;
 
 use ckb_indexer_sync::Error;
+use ckb_jsonrpc_types::pagination::{check_limit, decode_id_cursor, encode_id_cursor};
 use ckb_jsonrpc_types::{
     IndexerCell, IndexerOrder, IndexerPagination, IndexerSearchKey, JsonBytes, Uint32,
 };
@@ -22,16 +23,7 @@ impl AsyncRichIndexerHandle {
         limit: Uint32,
         after: Option<JsonBytes>,
     ) -> Result<IndexerPagination<IndexerCell>, Error> {
-        let limit = limit.value();
-        if limit == 0 {
-            return Err(Error::invalid_params("limit should be greater than 0"));
-        }
-        if limit as usize > self.request_limit {
-            return Err(Error::invalid_params(format!(
-                "limit must be less than {}",
-                self.request_limit,
-            )));
-        }
+        let limit = check_limit(limit, self.request_limit).map_err(Error::invalid_params)? as u32;
 
         let mut param_index = 1;
 
@@ -137,7 +129,7 @@ impl AsyncRichIndexerHandle {
         }
 
         if let Some(after) = after {
-            let after = decode_i64(after.as_bytes())?;
+            let after = decode_id_cursor(&after).map_err(Error::Params)?;
             match order {
                 IndexerOrder::Asc => query_builder.and_where_gt("output.id", after),
                 IndexerOrder::Desc => query_builder.and_where_lt("output.id", after),
@@ -227,7 +219,7 @@ impl AsyncRichIndexerHandle {
         }
 
         // fetch
-        let mut last_cursor = Vec::new();
+        let mut last_cursor = JsonBytes::default();
         let cells = self
             .store
             .fetch_all(query)
@@ -235,14 +227,14 @@ impl AsyncRichIndexerHandle {
             .map_err(|err| Error::DB(err.to_string()))?
             .iter()
             .map(|row| {
-                last_cursor = row.get::<i64, _>("id").to_le_bytes().to_vec();
+                last_cursor = encode_id_cursor(row.get::<i64, _>("id"));
                 build_indexer_cell(row)
             })
             .collect::<Vec<_>>();
 
         Ok(IndexerPagination {
             objects: cells,
-            last_cursor: JsonBytes::from_vec(last_cursor),
+            last_cursor,
         })
     }
 }
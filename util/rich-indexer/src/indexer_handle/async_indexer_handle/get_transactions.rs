@@ -3,6 +3,7 @@ use crate::store::SQLXPool;
 
 use ckb_app_config::DBDriver;
 use ckb_indexer_sync::Error;
+use ckb_jsonrpc_types::pagination::{check_limit, decode_id_cursor, encode_id_cursor};
 use ckb_jsonrpc_types::{
     IndexerCellType, IndexerScriptType, IndexerSearchMode, IndexerTx, IndexerTxWithCell,
     IndexerTxWithCells,
@@ -20,16 +21,7 @@ impl AsyncRichIndexerHandle {
         limit: Uint32,
         after: Option<JsonBytes>,
     ) -> Result<IndexerPagination<IndexerTx>, Error> {
-        let limit = limit.value();
-        if limit == 0 {
-            return Err(Error::invalid_params("limit should be greater than 0"));
-        }
-        if limit as usize > self.request_limit {
-            return Err(Error::invalid_params(format!(
-                "limit must be less than {}",
-                self.request_limit,
-            )));
-        }
+        let limit = check_limit(limit, self.request_limit).map_err(Error::invalid_params)? as u32;
         search_key.filter = convert_max_values_in_search_filter(&search_key.filter);
 
         let mut tx = self
@@ -48,7 +40,8 @@ impl AsyncRichIndexerHandle {
                         ));
                     }
                     let (last, offset) = after.as_bytes().split_at(after.len() - 4);
-                    let last = decode_i64(last)?;
+                    let last = decode_id_cursor(&JsonBytes::from_vec(last.to_vec()))
+                        .map_err(Error::Params)?;
                     let offset = decode_i32(offset)?;
                     last_cursor = Some((last, offset));
                 };
@@ -88,9 +81,9 @@ impl AsyncRichIndexerHandle {
                     })
                     .collect::<Vec<_>>();
 
-                let mut last_cursor = last_id.to_le_bytes().to_vec();
-                let mut offset = count.to_le_bytes().to_vec();
-                last_cursor.append(&mut offset);
+                // the id cursor followed by the offset of the cell within the tx
+                let mut last_cursor = encode_id_cursor(last_id).as_bytes().to_vec();
+                last_cursor.extend_from_slice(&count.to_le_bytes());
 
                 Ok(IndexerPagination {
                     objects: txs,
@@ -135,7 +128,7 @@ impl AsyncRichIndexerHandle {
                     .collect::<Vec<_>>();
                 Ok(IndexerPagination {
                     objects: txs,
-                    last_cursor: JsonBytes::from_vec(last_cursor.to_le_bytes().to_vec()),
+                    last_cursor: encode_id_cursor(last_cursor),
                 })
             }
         }
@@ -318,7 +311,7 @@ pub async fn get_tx_with_cells(
     }
 
     if let Some(after) = after {
-        let after = decode_i64(after.as_bytes())?;
+        let after = decode_id_cursor(&after).map_err(Error::Params)?;
         match order {
             IndexerOrder::Asc => query_builder.and_where_gt("tx_id", after),
             IndexerOrder::Desc => query_builder.and_where_lt("tx_id", after),
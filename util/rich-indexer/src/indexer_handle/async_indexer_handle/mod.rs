@@ -58,6 +58,22 @@ impl AsyncRichIndexerHandle {
             })
             .map_err(|err| Error::DB(err.to_string()))
     }
+
+    /// Get the hash of the indexed block by number
+    pub async fn get_block_hash(&self, block_number: u64) -> Result<Option<H256>, Error> {
+        let query = SQLXPool::new_query(
+            r#"
+            SELECT block_hash FROM block
+            WHERE block_number = $1
+            "#,
+        )
+        .bind(block_number as i64);
+        self.store
+            .fetch_optional(query)
+            .await
+            .map(|res| res.map(|row| bytes_to_h256(row.get("block_hash"))))
+            .map_err(|err| Error::DB(err.to_string()))
+    }
 }
 
 fn add_filter_script_len_range_conditions(
@@ -261,16 +277,6 @@ fn bytes_to_h256(input: &[u8]) -> H256 {
     H256::from_slice(&input[0..32]).expect("bytes to h256")
 }
 
-fn decode_i64(data: &[u8]) -> Result<i64, Error> {
-    if data.len() != 8 {
-        return Err(Error::Params(
-            "unable to convert from bytes to i64 due to insufficient data in little-endian format"
-                .to_string(),
-        ));
-    }
-    Ok(i64::from_le_bytes(to_fixed_array(&data[0..8])))
-}
-
 fn decode_i32(data: &[u8]) -> Result<i32, Error> {
     if data.len() != 4 {
         return Err(Error::Params(
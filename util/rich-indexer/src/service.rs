@@ -18,8 +18,8 @@ pub(crate) const SUBSCRIBER_NAME: &str = "Rich-Indexer";
 pub struct RichIndexerService {
     store: SQLXPool,
     sync: IndexerSyncService,
-    block_filter: Option<String>,
-    cell_filter: Option<String>,
+    // compiled once, the indexers share the compiled filters
+    custom_filters: CustomFilters,
     async_handle: Handle,
     request_limit: usize,
 }
@@ -47,8 +47,10 @@ impl RichIndexerService {
         Self {
             store,
             sync,
-            block_filter: config.block_filter.clone(),
-            cell_filter: config.cell_filter.clone(),
+            custom_filters: CustomFilters::new(
+                config.block_filter.as_deref(),
+                config.cell_filter.as_deref(),
+            ),
             async_handle,
             request_limit: config.request_limit.unwrap_or(usize::MAX),
         }
@@ -58,7 +60,7 @@ impl RichIndexerService {
         RichIndexer::new(
             self.store.clone(),
             self.sync.pool(),
-            CustomFilters::new(self.block_filter.as_deref(), self.cell_filter.as_deref()),
+            self.custom_filters.clone(),
             self.async_handle.clone(),
             self.request_limit,
         )
@@ -193,6 +193,10 @@ impl ChainStore for Snapshot {
         self.store.get(col, key)
     }
 
+    fn multi_get<K: AsRef<[u8]>>(&self, col: Col, keys: &[K]) -> Vec<Option<DBPinnableSlice>> {
+        self.store.multi_get(col, keys)
+    }
+
     fn freezer(&self) -> Option<&Freezer> {
         self.store.freezer()
     }
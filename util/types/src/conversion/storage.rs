@@ -134,6 +134,34 @@ impl<'r> Unpack<core::EpochExt> for packed::EpochExtReader<'r> {
 }
 impl_conversion_for_entity_unpack!(core::EpochExt, EpochExt);
 
+impl Pack<packed::Uint64Vec> for core::EpochStats {
+    fn pack(&self) -> packed::Uint64Vec {
+        // the last value flags the unknown tx count, it's absent in the stats stored before
+        let values = [
+            self.block_count,
+            self.tx_count.unwrap_or_default(),
+            self.total_fees.as_u64(),
+            self.total_cycles,
+            u64::from(self.tx_count.is_none()),
+        ];
+        values[..].pack()
+    }
+}
+
+impl<'r> Unpack<core::EpochStats> for packed::Uint64VecReader<'r> {
+    fn unpack(&self) -> core::EpochStats {
+        let values: Vec<u64> = self.unpack();
+        let value = |index: usize| values.get(index).copied().unwrap_or_default();
+        core::EpochStats {
+            block_count: value(0),
+            tx_count: (value(4) == 0).then(|| value(1)),
+            total_fees: core::Capacity::shannons(value(2)),
+            total_cycles: value(3),
+        }
+    }
+}
+impl_conversion_for_entity_unpack!(core::EpochStats, Uint64Vec);
+
 impl Pack<packed::TransactionInfo> for core::TransactionInfo {
     fn pack(&self) -> packed::TransactionInfo {
         let key = packed::TransactionKey::new_builder()
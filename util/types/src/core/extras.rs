@@ -1,9 +1,11 @@
 use crate::{
+    bytes::Bytes,
     core::{BlockNumber, Capacity, CapacityResult, Cycle, EpochNumber},
     packed,
     prelude::*,
     U256,
 };
+use ckb_hash::new_blake2b;
 use ckb_rational::RationalU256;
 use std::cmp::Ordering;
 use std::fmt;
@@ -29,6 +31,86 @@ pub struct BlockExt {
     pub txs_sizes: Option<Vec<u64>>,
 }
 
+/// Aggregated statistics of the main chain blocks in an epoch.
+#[derive(Clone, PartialEq, Debug, Eq)]
+pub struct EpochStats {
+    /// The number of the main chain blocks in the epoch
+    pub block_count: u64,
+    /// The number of the committed transactions, cellbases excluded, `None` if it's unknown
+    /// because some blocks had been moved to the freezer when the stats were built
+    pub tx_count: Option<u64>,
+    /// The total fees of the committed transactions
+    pub total_fees: Capacity,
+    /// The total cycles consumed by the committed transactions
+    pub total_cycles: Cycle,
+}
+
+impl Default for EpochStats {
+    fn default() -> Self {
+        EpochStats {
+            block_count: 0,
+            tx_count: Some(0),
+            total_fees: Capacity::zero(),
+            total_cycles: 0,
+        }
+    }
+}
+
+/// A main chain header which light clients can start syncing from.
+#[derive(Clone, PartialEq, Debug, Eq)]
+pub struct HeaderCheckpoint {
+    /// The block number
+    pub number: BlockNumber,
+    /// The block hash
+    pub hash: packed::Byte32,
+    /// The total difficulty of the chain ending at the block
+    pub total_difficulty: U256,
+    /// The epoch of the block
+    pub epoch: EpochNumberWithFraction,
+}
+
+// Separates the signatures of the checkpoints from the other messages signed by the network key
+const HEADER_CHECKPOINTS_DOMAIN: &[u8] = b"ckb-header-checkpoints";
+
+/// The header checkpoints signed by the network key of a node.
+#[derive(Clone, PartialEq, Debug, Eq)]
+pub struct SignedHeaderCheckpoints {
+    /// The hash of the genesis block of the chain
+    pub genesis_hash: packed::Byte32,
+    /// The peer ID of the node, derived from `public_key`
+    pub node_id: String,
+    /// The compressed secp256k1 public key of the node
+    pub public_key: Bytes,
+    /// The checkpoints in ascending order of the block numbers
+    pub checkpoints: Vec<HeaderCheckpoint>,
+    /// The 65-byte recoverable secp256k1 signature of [`SignedHeaderCheckpoints::message`]
+    pub signature: Bytes,
+}
+
+impl SignedHeaderCheckpoints {
+    /// The message signed by the node.
+    ///
+    /// It is the blake2b hash of the genesis hash, the domain `ckb-header-checkpoints` and the
+    /// checkpoints, so the signature can't be replayed on another chain or as another message.
+    /// Each checkpoint is serialized as the block number in 8 bytes, the block hash in 32 bytes,
+    /// the total difficulty in 32 bytes and the full value of the epoch in 8 bytes, the integers
+    /// are little-endian.
+    pub fn message(genesis_hash: &packed::Byte32, checkpoints: &[HeaderCheckpoint]) -> [u8; 32] {
+        let mut hasher = new_blake2b();
+        hasher.update(genesis_hash.as_slice());
+        hasher.update(HEADER_CHECKPOINTS_DOMAIN);
+        for checkpoint in checkpoints {
+            hasher.update(&checkpoint.number.to_le_bytes());
+            hasher.update(checkpoint.hash.as_slice());
+            hasher.update(checkpoint.total_difficulty.pack().as_slice());
+            hasher.update(&checkpoint.epoch.full_value().to_le_bytes());
+        }
+        let mut message = [0u8; 32];
+        hasher.finalize(&mut message);
+        message
+    }
+}
+
 /// TODO(doc): @quake
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct TransactionInfo {
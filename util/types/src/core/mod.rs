@@ -30,7 +30,10 @@ mod views;
 
 pub use advanced_builders::{BlockBuilder, HeaderBuilder, TransactionBuilder};
 pub use blockchain::DepType;
-pub use extras::{BlockExt, EpochExt, EpochNumberWithFraction, TransactionInfo};
+pub use extras::{
+    BlockExt, EpochExt, EpochNumberWithFraction, EpochStats, HeaderCheckpoint,
+    SignedHeaderCheckpoints, TransactionInfo,
+};
 pub use fee_rate::FeeRate;
 pub use reward::{BlockEconomicState, BlockIssuance, BlockReward, MinerReward};
 pub use transaction_meta::{TransactionMeta, TransactionMetaBuilder};
@@ -3,6 +3,7 @@
 //! A CKB service acts as an actor, which processes requests from a channel and sends back the
 //! response via one shot channel.
 use crate::core::{Capacity, Cycle, TransactionView};
+use crate::packed::CellOutput;
 use ckb_channel::Sender;
 use std::sync::mpsc;
 /// Default channel size to send control signals.
@@ -35,6 +36,8 @@ impl<A, R> Request<A, R> {
 pub struct PoolTransactionEntry {
     /// Transaction view
     pub transaction: TransactionView,
+    /// The cells consumed by the inputs of the transaction
+    pub input_cells: Vec<CellOutput>,
     /// Transaction consumed cycles
     pub cycles: Cycle,
     /// Transaction serialized cycles
@@ -44,3 +47,25 @@ pub struct PoolTransactionEntry {
     /// The unix timestamp when entering the Txpool, unit: Millisecond
     pub timestamp: u64,
 }
+
+/// Notify the outcome of the background database migrations
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MigrationOutcome {
+    /// The database version after the migrations, the version of the last applied migration
+    pub version: String,
+    /// How long the background migrations took, unit: Millisecond
+    pub duration_ms: u64,
+    /// The version of the first failed migration, None if all the migrations succeeded
+    ///
+    /// A failed migration is not applied, and the later migrations still run.
+    pub failed_version: Option<String>,
+    /// Why the migration failed
+    pub error: Option<String>,
+}
+
+impl MigrationOutcome {
+    /// Whether all the background migrations succeeded
+    pub fn is_success(&self) -> bool {
+        self.failed_version.is_none()
+    }
+}
@@ -2,8 +2,9 @@ use ckb_error::{ErrorKind, InternalErrorKind, SilentError as DefaultError};
 
 use crate::core::{
     error::{OutPointError, TransactionError, TransactionErrorSource},
-    tx_pool::Reject,
+    tx_pool::{Reject, RejectCode, REJECT_CODES},
 };
+use std::collections::HashSet;
 
 #[test]
 fn test_if_is_malformed_tx() {
@@ -115,3 +116,27 @@ fn test_if_is_malformed_tx() {
         assert_eq!(reject.is_malformed_tx(), is_malformed);
     }
 }
+
+#[test]
+fn test_reject_code_table() {
+    let codes: HashSet<_> = REJECT_CODES.iter().map(|(code, _, _)| *code).collect();
+    let names: HashSet<_> = REJECT_CODES.iter().map(|(_, name, _)| *name).collect();
+    assert_eq!(codes.len(), REJECT_CODES.len());
+    assert_eq!(names.len(), REJECT_CODES.len());
+
+    for (code, name, rpc_error_code) in REJECT_CODES {
+        assert_eq!(code.as_str(), name);
+        assert_eq!(code.rpc_error_code(), rpc_error_code);
+        assert_eq!(RejectCode::from_name(name), Some(code));
+    }
+    assert_eq!(RejectCode::from_name("Unknown"), None);
+
+    assert_eq!(
+        Reject::RBFReplaced(Default::default()).code(),
+        RejectCode::RBFRejected
+    );
+    assert_eq!(
+        Reject::FullFeeRateFloor(Default::default(), Default::default()).code(),
+        RejectCode::Full
+    );
+}
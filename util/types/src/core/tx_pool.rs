@@ -32,6 +32,11 @@ pub enum Reject {
     #[error("Transaction is replaced because the pool is full, {0}")]
     Full(String),
 
+    /// Transaction are replaced because the pool is full and its fee rate is not higher than the
+    /// fee rate floor of the pool
+    #[error("Transaction is replaced because the pool is full, the fee_rate for this transaction is: {0}, expect it to > {1} to stay in the pool")]
+    FullFeeRateFloor(FeeRate, FeeRate),
+
     /// Transaction already exists in transaction_pool
     #[error("Transaction({0}) already exists in transaction_pool")]
     Duplicated(Byte32),
@@ -60,9 +65,29 @@ pub enum Reject {
     #[error("RBF rejected: {0}")]
     RBFRejected(String),
 
+    /// RBF rejected because the fee is lower than the min replace fee
+    #[error("RBF rejected: Tx's current fee is {0}, expect it to >= {1} to replace old txs")]
+    RBFRejectedByFee(Capacity, Capacity),
+
+    /// Replaced by the transaction with the hash via RBF
+    #[error("RBF rejected: replaced by tx {0}")]
+    RBFReplaced(Byte32),
+
     /// Invalidated by cell consuming Tx
     #[error("Invalidated: {0}")]
     Invalidated(String),
+
+    /// The fee of a local transaction exceeds the configured absurd fee threshold
+    #[error("The transaction fee {0} shannons is absurdly high, {1}")]
+    AbsurdFee(u64, String),
+
+    /// A script group consumes more cycles than the pool policy allows
+    #[error("Script group {0} exceeded the maximum cycles {1} of a script group")]
+    ExceededMaximumScriptGroupCycles(Byte32, Cycle),
+
+    /// A header dep is too close to the tip
+    #[error("Header dep {0} is only {1} blocks deep, requiring at least {2}")]
+    HeaderDepTooRecent(Byte32, BlockNumber, BlockNumber),
 }
 
 fn is_malformed_from_verification(error: &Error) -> bool {
@@ -110,6 +135,135 @@ impl Reject {
         matches!(self, Reject::DeclaredWrongCycles(..))
             || (!matches!(self, Reject::LowFeeRate(..)) && !self.is_malformed_tx())
     }
+
+    /// Returns the stable code of the reject reason.
+    pub fn code(&self) -> RejectCode {
+        match self {
+            Reject::LowFeeRate(..) => RejectCode::LowFeeRate,
+            Reject::ExceededMaximumAncestorsCount => RejectCode::ExceededMaximumAncestorsCount,
+            Reject::ExceededTransactionSizeLimit(..) => RejectCode::ExceededTransactionSizeLimit,
+            Reject::Full(..) | Reject::FullFeeRateFloor(..) => RejectCode::Full,
+            Reject::Duplicated(..) => RejectCode::Duplicated,
+            Reject::Malformed(..) => RejectCode::Malformed,
+            Reject::DeclaredWrongCycles(..) => RejectCode::DeclaredWrongCycles,
+            Reject::Resolve(..) => RejectCode::Resolve,
+            Reject::Verification(..) => RejectCode::Verification,
+            Reject::Expiry(..) => RejectCode::Expiry,
+            Reject::RBFRejected(..) | Reject::RBFRejectedByFee(..) | Reject::RBFReplaced(..) => {
+                RejectCode::RBFRejected
+            }
+            Reject::Invalidated(..) => RejectCode::Invalidated,
+            Reject::AbsurdFee(..) => RejectCode::AbsurdFee,
+            Reject::ExceededMaximumScriptGroupCycles(..) => {
+                RejectCode::ExceededMaximumScriptGroupCycles
+            }
+            Reject::HeaderDepTooRecent(..) => RejectCode::HeaderDepTooRecent,
+        }
+    }
+}
+
+/// The stable code of a reject reason, which clients can switch on across releases instead of
+/// parsing the messages.
+///
+/// The codes are listed in `REJECT_CODES`, shared by the pool and the RPC. A code is never
+/// renamed or renumbered once released, the new reject reasons get new codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RejectCode {
+    /// Transaction fee lower than config
+    LowFeeRate,
+    /// Transaction exceeded maximum ancestors count limit
+    ExceededMaximumAncestorsCount,
+    /// Transaction exceeded maximum size limit
+    ExceededTransactionSizeLimit,
+    /// Transaction are replaced because the pool is full
+    Full,
+    /// Transaction already exists in transaction_pool
+    Duplicated,
+    /// Malformed transaction
+    Malformed,
+    /// Declared wrong cycles
+    DeclaredWrongCycles,
+    /// Resolve failed
+    Resolve,
+    /// Verification failed
+    Verification,
+    /// Transaction expired
+    Expiry,
+    /// RBF rejected, or replaced by another transaction via RBF
+    RBFRejected,
+    /// Invalidated by another transaction consuming the same cells
+    Invalidated,
+    /// Transaction fee exceeds the absurd fee threshold
+    AbsurdFee,
+    /// A script group exceeds the cycles limit of a script group
+    ExceededMaximumScriptGroupCycles,
+    /// A header dep is too close to the tip
+    HeaderDepTooRecent,
+}
+
+/// The table of the reject codes: the code, its stable name and the RPC error code.
+///
+/// The names are the same as the `type` of the rejects returned by the RPC, and the RPC error
+/// codes are the same as the errors returned by `send_transaction`.
+pub const REJECT_CODES: [(RejectCode, &str, i64); 15] = [
+    (RejectCode::LowFeeRate, "LowFeeRate", -1104),
+    (
+        RejectCode::ExceededMaximumAncestorsCount,
+        "ExceededMaximumAncestorsCount",
+        -1105,
+    ),
+    (
+        RejectCode::ExceededTransactionSizeLimit,
+        "ExceededTransactionSizeLimit",
+        -1110,
+    ),
+    (RejectCode::Full, "Full", -1106),
+    (RejectCode::Duplicated, "Duplicated", -1107),
+    (RejectCode::Malformed, "Malformed", -1108),
+    (
+        RejectCode::DeclaredWrongCycles,
+        "DeclaredWrongCycles",
+        -1108,
+    ),
+    (RejectCode::Resolve, "Resolve", -301),
+    (RejectCode::Verification, "Verification", -302),
+    (RejectCode::Expiry, "Expiry", -1109),
+    (RejectCode::RBFRejected, "RBFRejected", -1111),
+    (RejectCode::Invalidated, "Invalidated", -1112),
+    (RejectCode::AbsurdFee, "AbsurdFee", -1113),
+    (
+        RejectCode::ExceededMaximumScriptGroupCycles,
+        "ExceededMaximumScriptGroupCycles",
+        -1114,
+    ),
+    (RejectCode::HeaderDepTooRecent, "HeaderDepTooRecent", -1115),
+];
+
+impl RejectCode {
+    fn entry(self) -> &'static (RejectCode, &'static str, i64) {
+        REJECT_CODES
+            .iter()
+            .find(|(code, _, _)| *code == self)
+            .expect("every reject code is in the table")
+    }
+
+    /// Returns the stable name of the code.
+    pub fn as_str(self) -> &'static str {
+        self.entry().1
+    }
+
+    /// Returns the RPC error code of the code.
+    pub fn rpc_error_code(self) -> i64 {
+        self.entry().2
+    }
+
+    /// Parses the code from its stable name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        REJECT_CODES
+            .iter()
+            .find(|(_, code_name, _)| *code_name == name)
+            .map(|(code, _, _)| *code)
+    }
 }
 
 impl_error_conversion_with_kind!(Reject, ErrorKind::SubmitTransaction, Error);
@@ -132,7 +286,7 @@ pub enum TxStatus {
 }
 
 /// Tx-pool entry info
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TxEntryInfo {
     /// Consumed cycles.
     pub cycles: Cycle,
@@ -155,7 +309,7 @@ pub struct TxEntryInfo {
 }
 
 /// Array of transaction ids
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TxPoolIds {
     /// Pending transaction ids
     pub pending: Vec<Byte32>,
@@ -164,7 +318,7 @@ pub struct TxPoolIds {
 }
 
 /// All in-pool transaction entry info
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TxPoolEntryInfo {
     /// Pending transaction entry info
     pub pending: HashMap<Byte32, TxEntryInfo>,
@@ -307,6 +461,49 @@ pub fn get_transaction_weight(tx_size: usize, cycles: u64) -> u64 {
 /// it may cause the transaction to fail to be packed
 pub const TRANSACTION_SIZE_LIMIT: u64 = 512 * 1_000;
 
+/// The effective relay and admission policy of the transaction pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolPolicy {
+    /// Increases by one every time the policy is reloaded with changes.
+    pub version: u64,
+    /// The pool rejects transactions which fee rate is below this threshold.
+    pub min_fee_rate: FeeRate,
+    /// The pool rejects RBF transactions which fee rate is below this threshold.
+    pub min_rbf_rate: FeeRate,
+    /// The max count of the in-pool ancestors of a transaction.
+    pub max_ancestors_count: usize,
+    /// Total limit on the size of transactions in the tx-pool.
+    pub max_tx_pool_size: u64,
+    /// The max serialized size of a transaction.
+    pub tx_size_limit: u64,
+    /// The max cycles of a transaction.
+    pub max_tx_verify_cycles: Cycle,
+    /// The max cycles of a script group in a transaction.
+    pub max_script_group_cycles: Option<Cycle>,
+    /// The sliding window in seconds to count the RBF replacements of an outpoint.
+    pub rbf_replacement_window_secs: u64,
+    /// The replacements of an outpoint within the window before the extra RBF fee escalates.
+    pub rbf_free_replacements: usize,
+    /// The max replacements of an outpoint within the window.
+    pub max_rbf_replacements: usize,
+    /// The max transactions replaced by a RBF transaction.
+    pub max_rbf_candidates: usize,
+    /// The max descendants of a transaction conflicting with a RBF transaction.
+    pub max_rbf_conflict_descendants: usize,
+    /// Whether a RBF transaction can replace the transactions in the gap.
+    pub rbf_replace_gap: bool,
+    /// Whether a RBF transaction can replace the proposed transactions.
+    pub rbf_replace_proposed: bool,
+    /// The soft cap of the fees in shannons replaced by RBF within the window.
+    pub rbf_replaced_fees_cap: Option<u64>,
+    /// The local transactions paying more fee than this in shannons are rejected.
+    pub max_absolute_fee: Option<u64>,
+    /// The local transactions paying higher fee rate than this in shannons/KB are rejected.
+    pub max_fee_rate: Option<u64>,
+    /// The min blocks a header dep of a transaction is below the tip.
+    pub min_header_dep_depth: Option<BlockNumber>,
+}
+
 /// Transaction pool information.
 #[derive(Clone, Debug)]
 pub struct TxPoolInfo {
@@ -359,6 +556,29 @@ pub struct TxPoolInfo {
 
     /// verify queue number
     pub verify_queue_size: usize,
+    /// Total size in bytes of the txs in the verify queue
+    pub verify_queue_tx_size: usize,
+    /// Total cycles declared by the relaying peers of the txs in the verify queue
+    pub verify_queue_cycles: Cycle,
+    /// The lock scripts whose txs take the most bytes in the pool, see `LockScriptStats`
+    pub top_lock_scripts: Vec<LockScriptStats>,
+    /// Total fees of the txs replaced by RBF within the replacement window
+    pub rbf_replaced_fees: Capacity,
+}
+
+/// The statistics of the txs in the pool unlocking the cells of a lock script.
+///
+/// A tx unlocking the cells of several lock scripts is counted in each of them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockScriptStats {
+    /// The lock script hash
+    pub lock_hash: Byte32,
+    /// Count of the txs
+    pub tx_count: usize,
+    /// Total size in bytes of the txs
+    pub tx_size: usize,
+    /// Total fees of the txs
+    pub fees: Capacity,
 }
 
 /// A struct as a sorted key in tx-pool
@@ -405,6 +625,341 @@ impl PoolTxDetailInfo {
     }
 }
 
+/// The relation of a node in a [`PoolTxGraph`] to the queried transaction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PoolTxGraphRelation {
+    /// The queried transaction itself
+    Root,
+    /// An in-pool ancestor of the queried transaction
+    Ancestor,
+    /// An in-pool descendant of the queried transaction
+    Descendant,
+}
+
+/// A transaction in a [`PoolTxGraph`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PoolTxGraphNode {
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The relation to the queried transaction
+    pub relation: PoolTxGraphRelation,
+    /// The distance in links to the queried transaction, 0 for the queried transaction itself
+    pub depth: usize,
+    /// The transaction fee
+    pub fee: Capacity,
+    /// The transaction size in block
+    pub size: usize,
+    /// The transaction cycles
+    pub cycles: Cycle,
+    /// The detailed status in tx-pool, `pending`, `gap`, `proposed`
+    pub status: String,
+    /// The time added into tx-pool
+    pub timestamp: u64,
+}
+
+/// An edge in a [`PoolTxGraph`], the child spends or depends on an output of the parent.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PoolTxGraphEdge {
+    /// The parent transaction hash
+    pub parent: Byte32,
+    /// The child transaction hash
+    pub child: Byte32,
+}
+
+/// The in-pool ancestors and descendants of a transaction, with the links between them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PoolTxGraph {
+    /// The queried transaction hash
+    pub tx_hash: Byte32,
+    /// The queried transaction, its ancestors and its descendants
+    pub nodes: Vec<PoolTxGraphNode>,
+    /// The links between the nodes
+    pub edges: Vec<PoolTxGraphEdge>,
+    /// Whether some ancestors or descendants are beyond the depth limit and not included
+    pub truncated: bool,
+}
+
+/// Why a transaction is not progressing, found by [`TxDiagnosis`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TxDiagnosisIssueKind {
+    /// The fee rate is below `min_fee_rate`, which may be raised after the tx is accepted
+    BelowFeeFloor,
+    /// The tx is pending but ranked beyond the proposals limit of a block
+    LowRank,
+    /// The in-pool ancestors reach `max_ancestors_count`
+    TooManyAncestors,
+    /// Other txs spend the same inputs
+    Conflicts,
+    /// The tx is waiting in the verify queue
+    Verifying,
+    /// The tx will be removed from the pool soon for expiry
+    ExpiresSoon,
+    /// The tx was rejected
+    Rejected,
+}
+
+/// An issue found by [`TxDiagnosis`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TxDiagnosisIssue {
+    /// What the issue is
+    pub kind: TxDiagnosisIssueKind,
+    /// The explanation with the numbers compared
+    pub message: String,
+    /// The related transactions, e.g., the conflicting ones
+    pub related_txs: Vec<Byte32>,
+}
+
+/// The explanation of why a transaction is not progressing.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TxDiagnosis {
+    /// The queried transaction hash
+    pub tx_hash: Byte32,
+    /// The status, `pending`, `gap`, `proposed`, `verifying`, `rejected` or `unknown`
+    pub status: String,
+    /// The fee rate, only for the txs in the pool
+    pub fee_rate: Option<FeeRate>,
+    /// The rank by score among the pending txs, starting from 0
+    pub rank_in_pending: Option<usize>,
+    /// The count of the in-pool ancestors
+    pub ancestors_count: Option<usize>,
+    /// When the tx expires in the pool
+    pub expires_at: Option<u64>,
+    /// The issues found, empty if the tx is expected to progress
+    pub issues: Vec<TxDiagnosisIssue>,
+}
+
+impl TxDiagnosis {
+    /// The diagnosis of a tx not in the pool
+    pub fn with_status(tx_hash: Byte32, status: &str) -> Self {
+        TxDiagnosis {
+            tx_hash,
+            status: status.to_owned(),
+            fee_rate: None,
+            rank_in_pending: None,
+            ancestors_count: None,
+            expires_at: None,
+            issues: Vec::new(),
+        }
+    }
+}
+
+/// The capacities of an input or output cell, found by [`TxFeeBreakdown`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CellCapacityBreakdown {
+    /// The capacity of the cell
+    pub capacity: Capacity,
+    /// The capacity occupied by the cell itself, the rest is free to spend
+    pub occupied_capacity: Capacity,
+}
+
+/// The capacities, the fee and the fee thresholds of a transaction.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TxFeeBreakdown {
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The capacities of the input cells
+    pub inputs: Vec<CellCapacityBreakdown>,
+    /// The capacities of the output cells
+    pub outputs: Vec<CellCapacityBreakdown>,
+    /// The total capacity provided by the inputs, including the compensation of the withdrawn
+    /// DAO cells
+    pub inputs_capacity: Capacity,
+    /// The total capacity of the outputs
+    pub outputs_capacity: Capacity,
+    /// The transaction fee
+    pub fee: Capacity,
+    /// The serialized size in block
+    pub size: usize,
+    /// The fee rate by the size, which is what the pool checks against `min_fee_rate`
+    pub fee_rate: FeeRate,
+    /// The `min_fee_rate` of the pool
+    pub min_fee_rate: FeeRate,
+    /// The minimal fee to be accepted by the pool
+    pub min_fee: Capacity,
+    /// The `min_rbf_rate` of the pool
+    pub min_rbf_rate: FeeRate,
+    /// The minimal fee to replace the conflicting txs in the pool, `None` if there are no
+    /// conflicts or RBF is disabled
+    pub min_replace_fee: Option<Capacity>,
+}
+
+/// An RBF rule checked by [`ReplacementSimulation`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RbfRule {
+    /// Rule #2, the replacement spends no unconfirmed inputs besides the ones of the replaced txs
+    NoNewUnconfirmedInputs,
+    /// Rule #5, the replaced txs are within the limits, replaceable in their status, and the
+    /// replacement depends on none of them
+    ReplacedTxs,
+    /// Rule #6, the inputs have not been replaced too many times recently
+    ReplacementChurn,
+    /// Rule #3 and #4, the fee is at least `min_replace_fee`
+    MinReplaceFee,
+}
+
+/// The result of an RBF rule checked by [`ReplacementSimulation`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RbfRuleCheck {
+    /// The checked rule
+    pub rule: RbfRule,
+    /// Whether the rule passes
+    pub passed: bool,
+    /// Why the rule fails, `None` if it passes
+    pub reason: Option<String>,
+}
+
+/// What would happen if a transaction were submitted to replace the conflicting ones in the
+/// pool.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReplacementSimulation {
+    /// The replacement transaction hash
+    pub tx_hash: Byte32,
+    /// The fee of the replacement
+    pub fee: Capacity,
+    /// The serialized size in block of the replacement
+    pub size: usize,
+    /// The pool txs spending the same inputs as the replacement
+    pub conflicts: Vec<Byte32>,
+    /// The pool txs to be removed, the conflicts and their descendants
+    pub evicted: Vec<Byte32>,
+    /// The combined fee of the evicted txs
+    pub evicted_fee: Capacity,
+    /// The minimal fee to replace the evicted txs, `None` if there are no conflicts
+    pub min_replace_fee: Option<Capacity>,
+    /// The results of the RBF rules in the order the pool checks them, empty if there are no
+    /// conflicts
+    pub rules: Vec<RbfRuleCheck>,
+}
+
+impl ReplacementSimulation {
+    /// Whether all the RBF rules pass
+    pub fn is_replaceable(&self) -> bool {
+        self.rules.iter().all(|check| check.passed)
+    }
+}
+
+/// When a transaction could be proposed and committed if it were submitted now, by its rank
+/// among the pending txs and the proposal window.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ProposalWindowSimulation {
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The fee rate of the transaction with its in-pool ancestors, by which it is ranked
+    pub fee_rate: FeeRate,
+    /// The tip block number when simulating
+    pub tip_number: BlockNumber,
+    /// The count of the pending txs ranked ahead of the transaction or its pending ancestors
+    pub rank: usize,
+    /// The max count of the txs proposed in a block
+    pub proposals_limit: u64,
+    /// The earliest block to propose the transaction
+    pub earliest_proposal_block: BlockNumber,
+    /// The earliest block to commit the transaction, the closest end of the proposal window
+    pub earliest_commit_block: BlockNumber,
+    /// The latest block to commit the transaction, the farthest end of the proposal window,
+    /// after which it must be proposed again
+    pub latest_commit_block: BlockNumber,
+}
+
+/// The totals of the txs left out of a block template.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TxsBacklog {
+    /// Count of the txs
+    pub tx_count: usize,
+    /// Total size in bytes of the txs
+    pub tx_size: usize,
+    /// Total cycles of the txs
+    pub cycles: Cycle,
+    /// Total weight of the txs, see `get_transaction_weight`
+    pub weight: u64,
+    /// Total fees of the txs
+    pub fees: Capacity,
+}
+
+/// The txs in the pool which did not fit in a generated block template.
+///
+/// The backlog beyond the block limits tells the congestion better than the pool size, e.g., a
+/// large pool which fits in the next block is not congested at all.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TemplateBacklog {
+    /// The number of the block template
+    pub number: BlockNumber,
+    /// The work id of the block template
+    pub work_id: u64,
+    /// The time in milliseconds when the template is generated
+    pub generated_at: u64,
+    /// The proposed txs which are committable but left out by the block size or cycles limits
+    pub proposed: TxsBacklog,
+    /// The pending and gap txs, which are not committable in the block yet
+    pub pending: TxsBacklog,
+}
+
+/// A stage in the lifecycle of a transaction traced by the tx-pool.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TxLifecycleStage {
+    /// Submitted via RPC or relayed by a peer, waiting for the verification
+    Submitted,
+    /// Passed the verification and entered the pending set
+    Verified,
+    /// Proposed in a block which is not in the proposal window yet
+    Gap,
+    /// Proposed and ready to be committed
+    Proposed,
+    /// Included in a block template
+    Packaged,
+    /// Committed in a block
+    Committed,
+    /// Rejected or removed from the pool
+    Rejected,
+}
+
+impl TxLifecycleStage {
+    /// Whether the lifecycle ends at this stage
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Committed | Self::Rejected)
+    }
+}
+
+/// A timestamped event in the lifecycle of a transaction.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TxLifecycleEvent {
+    /// The stage entered
+    pub stage: TxLifecycleStage,
+    /// The unix timestamp in milliseconds
+    pub timestamp: u64,
+    /// Extra information, such as the submitter or the reject reason
+    pub detail: Option<String>,
+}
+
+/// The lifecycle events of a transaction, from the submission to the commitment.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TxLifecycle {
+    /// The internal trace id assigned at the first event
+    pub trace_id: u64,
+    /// The transaction hash
+    pub tx_hash: Byte32,
+    /// The events in the order they happened
+    pub events: Vec<TxLifecycleEvent>,
+}
+
+impl TxLifecycle {
+    /// Whether the lifecycle has ended, by the commitment or the rejection
+    pub fn is_finished(&self) -> bool {
+        self.events
+            .last()
+            .map_or(false, |event| event.stage.is_final())
+    }
+}
+
+/// How the transactions in the pool relate to a cell.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CellPoolStatus {
+    /// The hash of the pool transaction which creates the cell
+    pub created_by: Option<Byte32>,
+    /// The hash of the pool transaction which spends the cell
+    pub spent_by: Option<Byte32>,
+}
+
 /// A Tx CacheEntry
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct EntryCompleted {
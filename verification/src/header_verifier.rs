@@ -9,6 +9,7 @@ use ckb_systemtime::unix_time_as_millis;
 use ckb_traits::HeaderFieldsProvider;
 use ckb_types::core::{BlockNumber, EpochNumberWithFraction, HeaderView};
 use ckb_verification_traits::Verifier;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator};
 
 /// Context-dependent verification checks for block header
 ///
@@ -16,6 +17,7 @@ use ckb_verification_traits::Verifier;
 pub struct HeaderVerifier<'a, DL> {
     data_loader: &'a DL,
     consensus: &'a Consensus,
+    pow_verified: bool,
 }
 
 impl<'a, DL: HeaderFieldsProvider> HeaderVerifier<'a, DL> {
@@ -24,8 +26,15 @@ impl<'a, DL: HeaderFieldsProvider> HeaderVerifier<'a, DL> {
         HeaderVerifier {
             consensus,
             data_loader,
+            pow_verified: false,
         }
     }
+
+    /// Skips the PoW check, for the headers already checked by [`BatchPowVerifier`].
+    pub fn pow_verified(mut self) -> Self {
+        self.pow_verified = true;
+        self
+    }
 }
 
 impl<'a, DL: HeaderFieldsProvider> Verifier for HeaderVerifier<'a, DL> {
@@ -33,7 +42,9 @@ impl<'a, DL: HeaderFieldsProvider> Verifier for HeaderVerifier<'a, DL> {
     fn verify(&self, header: &Self::Target) -> Result<(), Error> {
         VersionVerifier::new(header, self.consensus).verify()?;
         // POW check first
-        PowVerifier::new(header, self.consensus.pow_engine().as_ref()).verify()?;
+        if !self.pow_verified {
+            PowVerifier::new(header, self.consensus.pow_engine().as_ref()).verify()?;
+        }
         let parent_fields = self
             .data_loader
             .get_header_fields(&header.parent_hash())
@@ -178,6 +189,32 @@ impl<'a> EpochVerifier<'a> {
     }
 }
 
+/// Checks the PoW of a batch of headers in parallel.
+///
+/// The PoW of a header doesn't depend on the others, so the headers of a sync batch are checked
+/// by the workers of the current rayon pool at once, see `rayon::ThreadPool::install`, while the
+/// contextual checks still run in the chain order.
+pub struct BatchPowVerifier<'a> {
+    headers: &'a [HeaderView],
+    pow: &'a dyn PowEngine,
+}
+
+impl<'a> BatchPowVerifier<'a> {
+    /// Creates a verifier for the headers.
+    pub fn new(headers: &'a [HeaderView], pow: &'a dyn PowEngine) -> Self {
+        BatchPowVerifier { headers, pow }
+    }
+
+    /// Returns how many leading headers have valid PoW, the header after them, if any, is the
+    /// first one with invalid PoW.
+    pub fn verify(&self) -> usize {
+        self.headers
+            .par_iter()
+            .position_first(|header| !self.pow.verify(&header.data()))
+            .unwrap_or(self.headers.len())
+    }
+}
+
 pub struct PowVerifier<'a> {
     header: &'a HeaderView,
     pow: &'a dyn PowEngine,
@@ -19,7 +19,7 @@ pub use crate::error::{
     PowError, TimestampError, TransactionError, UnclesError, UnknownParentError,
 };
 pub use crate::genesis_verifier::GenesisVerifier;
-pub use crate::header_verifier::HeaderVerifier;
+pub use crate::header_verifier::{BatchPowVerifier, HeaderVerifier};
 pub use crate::transaction_verifier::{
     CapacityVerifier, ContextualTransactionVerifier, DaoScriptSizeVerifier,
     NonContextualTransactionVerifier, ScriptVerifier, Since, SinceMetric,
@@ -27,7 +27,7 @@ pub use crate::transaction_verifier::{
 };
 pub use ckb_script::{
     ScriptError, ScriptGroupType, TransactionSnapshot, TransactionState as ScriptVerifyState,
-    TxVerifyEnv, VerifyResult as ScriptVerifyResult,
+    TxVerifyEnv, VerifyResult as ScriptVerifyResult, VM_BACKEND,
 };
 
 /// Maximum amount of time that a block timestamp is allowed to exceed the
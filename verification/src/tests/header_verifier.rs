@@ -2,7 +2,8 @@ use crate::header_verifier::{
     EpochVerifier, NumberVerifier, PowVerifier, TimestampVerifier, VersionVerifier,
 };
 use crate::{
-    BlockVersionError, EpochError, NumberError, PowError, TimestampError, ALLOWED_FUTURE_BLOCKTIME,
+    BatchPowVerifier, BlockVersionError, EpochError, NumberError, PowError, TimestampError,
+    ALLOWED_FUTURE_BLOCKTIME,
 };
 use ckb_chain_spec::consensus::ConsensusBuilder;
 use ckb_error::assert_error_eq;
@@ -261,3 +262,26 @@ fn test_pow_verifier() {
 
     assert_error_eq!(verifier.verify().unwrap_err(), PowError::InvalidNonce);
 }
+
+// only the headers with the zero nonce are invalid
+struct NonZeroNoncePowEngine;
+
+impl PowEngine for NonZeroNoncePowEngine {
+    fn verify(&self, header: &Header) -> bool {
+        Unpack::<u128>::unpack(&header.nonce()) != 0
+    }
+}
+
+#[test]
+fn test_batch_pow_verifier() {
+    let pow_engine: &dyn PowEngine = &NonZeroNoncePowEngine;
+    let headers: Vec<_> = [1u128, 2, 0, 3, 0]
+        .iter()
+        .map(|nonce| HeaderBuilder::default().nonce(nonce.pack()).build())
+        .collect();
+
+    assert_eq!(BatchPowVerifier::new(&headers, pow_engine).verify(), 2);
+    assert_eq!(BatchPowVerifier::new(&headers[..2], pow_engine).verify(), 2);
+    assert_eq!(BatchPowVerifier::new(&headers[2..], pow_engine).verify(), 0);
+    assert_eq!(BatchPowVerifier::new(&[], pow_engine).verify(), 0);
+}
@@ -157,6 +157,12 @@ where
         }
     }
 
+    /// Limits the cycles of every script group, see
+    /// [`TransactionScriptsVerifier::set_max_group_cycles`]
+    pub fn set_max_group_cycles(&mut self, max_group_cycles: Option<Cycle>) {
+        self.script.set_max_group_cycles(max_group_cycles);
+    }
+
     /// Perform context-dependent verification, return a `Result` to `CacheEntry`
     ///
     /// skip script verify will result in the return value cycle always is zero